@@ -0,0 +1,38 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NetworkPolicyAmendment } from "./NetworkPolicyAmendment";
+
+/**
+ * The network proxy blocked a request of a command because its host is not in
+ * the allowlist. Approving it lets the command reach the host and reruns it.
+ */
+export type NetworkApprovalRequestEvent = { 
+/**
+ * Identifier for the exec call that made the request.
+ */
+call_id: string, 
+/**
+ * Turn ID that this command belongs to.
+ */
+turn_id: string, 
+/**
+ * The host the command tried to reach.
+ */
+host: string, port: number, 
+/**
+ * The HTTP method of the request, `CONNECT` for HTTPS tunnels.
+ */
+method?: string, 
+/**
+ * The command that made the request.
+ */
+command: Array<string>, 
+/**
+ * The command's working directory.
+ */
+cwd: string, 
+/**
+ * Proposed policy amendment that allows the host in future sessions.
+ */
+proposed_network_policy_amendment: NetworkPolicyAmendment, };
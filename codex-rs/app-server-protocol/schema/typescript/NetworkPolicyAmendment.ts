@@ -0,0 +1,11 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Proposed network proxy policy change to allow requests to this host.
+ *
+ * Applying it appends `host` to `[network_proxy.policy].allowed_domains` in
+ * the user's `config.toml`.
+ */
+export type NetworkPolicyAmendment = string;
@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MemoryListParams = { 
+/**
+ * List memories of threads started in this directory. Defaults to the
+ * server's working directory.
+ */
+cwd?: string | null, };
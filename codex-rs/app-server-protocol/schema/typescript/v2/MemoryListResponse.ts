@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ThreadMemory } from "./ThreadMemory";
+
+export type MemoryListResponse = { 
+/**
+ * Pinned memories first, then the most recently updated.
+ */
+data: Array<ThreadMemory>, };
@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MemoryUpdateParams = { threadId: string, 
+/**
+ * Replaces the memory summary; the trace summary is kept.
+ */
+memorySummary: string, };
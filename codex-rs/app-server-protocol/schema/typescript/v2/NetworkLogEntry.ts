@@ -0,0 +1,30 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NetworkLogDecision } from "./NetworkLogDecision";
+
+/**
+ * A request a session's network proxy allowed or blocked.
+ */
+export type NetworkLogEntry = { 
+/**
+ * Unix timestamp (in seconds) of the decision.
+ */
+timestamp: number, threadId: string | null, turnId: string | null, 
+/**
+ * Tool call that started the process making the request.
+ */
+callId: string | null, 
+/**
+ * `http`, `https` (intercepted), `http-connect`, `socks5`, `socks5-udp`
+ * or `unix-socket`.
+ */
+protocol: string, host: string, method: string | null, decision: NetworkLogDecision, reason: string | null, 
+/**
+ * Bytes sent upstream, when the proxy could tell.
+ */
+bytesSent: number | null, 
+/**
+ * Bytes received from upstream, when the proxy could tell.
+ */
+bytesReceived: number | null, };
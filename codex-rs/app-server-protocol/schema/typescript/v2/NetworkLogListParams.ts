@@ -0,0 +1,14 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NetworkLogDecision } from "./NetworkLogDecision";
+
+export type NetworkLogListParams = { threadId?: string | null, host?: string | null, decision?: NetworkLogDecision | null, 
+/**
+ * Only include requests from this unix timestamp (in seconds) on.
+ */
+since?: number | null, 
+/**
+ * Defaults to 100, at most 1000.
+ */
+limit?: number | null, };
@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NetworkLogEntry } from "./NetworkLogEntry";
+
+export type NetworkLogListResponse = { 
+/**
+ * Most recent requests first.
+ */
+data: Array<NetworkLogEntry>, };
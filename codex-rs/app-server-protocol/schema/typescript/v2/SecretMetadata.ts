@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SecretScope } from "./SecretScope";
+
+/**
+ * A stored secret. Values are never part of the metadata.
+ */
+export type SecretMetadata = { name: string, scope: SecretScope, 
+/**
+ * Set for environment-scoped secrets.
+ */
+environmentId: string | null, };
@@ -0,0 +1,15 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SecretScope } from "./SecretScope";
+
+export type SecretsGetParams = { name: string, 
+/**
+ * Defaults to the environment scope.
+ */
+scope?: SecretScope | null, environmentId?: string | null, cwd?: string | null, 
+/**
+ * Include the secret value in the response. Off by default so clients
+ * only see values they explicitly ask for.
+ */
+reveal: boolean, };
@@ -0,0 +1,14 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SecretMetadata } from "./SecretMetadata";
+
+export type SecretsGetResponse = { 
+/**
+ * Null when the secret is not set in the requested scope.
+ */
+secret: SecretMetadata | null, 
+/**
+ * Only populated when `reveal` was requested.
+ */
+value: string | null, };
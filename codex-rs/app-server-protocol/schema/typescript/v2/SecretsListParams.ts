@@ -0,0 +1,15 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SecretScope } from "./SecretScope";
+
+export type SecretsListParams = { 
+/**
+ * Restrict the listing to one scope. When omitted, global secrets and the
+ * secrets of the current environment are listed.
+ */
+scope?: SecretScope | null, environmentId?: string | null, cwd?: string | null, 
+/**
+ * List the secrets of every environment instead of only the current one.
+ */
+allEnvironments: boolean, };
@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SecretScope } from "./SecretScope";
+
+export type SecretsRemoveParams = { name: string, 
+/**
+ * Defaults to the environment scope.
+ */
+scope?: SecretScope | null, environmentId?: string | null, cwd?: string | null, };
@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RequestId } from "../RequestId";
+
+/**
+ * A request the server sent was answered elsewhere, for example from the menu
+ * bar, so clients should close the prompt they show for it.
+ */
+export type ServerRequestResolvedNotification = { threadId: string, requestId: RequestId, };
@@ -46,6 +46,18 @@ source: SessionSource,
  * Optional Git metadata captured when the thread was created.
  */
 gitInfo: GitInfo | null, 
+/**
+ * Tags attached to the thread.
+ */
+tags: Array<string>, 
+/**
+ * Whether the thread is pinned.
+ */
+pinned: boolean, 
+/**
+ * `/`-separated folder the thread is filed under, if any.
+ */
+folder: string | null, 
 /**
  * Only populated on `thread/resume`, `thread/rollback`, `thread/fork`, and `thread/read`
  * (when `includeTurns` is true) responses.
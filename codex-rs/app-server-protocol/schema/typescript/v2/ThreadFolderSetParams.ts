@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ThreadFolderSetParams = { threadId: string, 
+/**
+ * `/`-separated folder path such as `clients/acme`. Null or blank
+ * removes the thread from its folder.
+ */
+folder?: string | null, };
@@ -31,4 +31,19 @@ sourceKinds?: Array<ThreadSourceKind> | null,
  * Optional archived filter; when set to true, only archived threads are returned.
  * If false or null, only non-archived threads are returned.
  */
-archived?: boolean | null, };
+archived?: boolean | null, 
+/**
+ * Optional tag filter; when set, only threads carrying all of these tags
+ * are returned. Requires the state database.
+ */
+tags?: Array<string> | null, 
+/**
+ * Optional pin filter; when set, only pinned (true) or unpinned (false)
+ * threads are returned. Requires the state database.
+ */
+pinned?: boolean | null, 
+/**
+ * Optional folder filter; when set, only threads in this folder or one of
+ * its subfolders are returned. Requires the state database.
+ */
+folder?: string | null, };
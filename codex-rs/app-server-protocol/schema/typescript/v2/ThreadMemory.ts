@@ -0,0 +1,24 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Memory summaries recorded for a thread.
+ */
+export type ThreadMemory = { threadId: string, 
+/**
+ * Summary of what happened in the thread.
+ */
+traceSummary: string, 
+/**
+ * Summary recalled in later sessions started in the same directory.
+ */
+memorySummary: string, 
+/**
+ * Pinned memories are always recalled for their directory.
+ */
+pinned: boolean, 
+/**
+ * Unix timestamp (in seconds) when the memory was last updated.
+ */
+updatedAt: number, };
@@ -0,0 +1,20 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ByteRange } from "./ByteRange";
+import type { Thread } from "./Thread";
+import type { ThreadSearchMatchSource } from "./ThreadSearchMatchSource";
+
+export type ThreadSearchMatch = { thread: Thread, 
+/**
+ * Which part of the thread the best match came from.
+ */
+source: ThreadSearchMatchSource, 
+/**
+ * Short excerpt around the best match.
+ */
+snippet: string, 
+/**
+ * Byte ranges of the matched terms within `snippet`.
+ */
+highlights: Array<ByteRange>, };
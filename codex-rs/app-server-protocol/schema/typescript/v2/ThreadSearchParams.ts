@@ -0,0 +1,34 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ThreadSourceKind } from "./ThreadSourceKind";
+
+export type ThreadSearchParams = { 
+/**
+ * Free-text query matched against user messages, agent messages and
+ * commands. The last term also matches as a prefix.
+ */
+query: string, 
+/**
+ * Opaque pagination cursor returned by a previous call.
+ */
+cursor?: string | null, 
+/**
+ * Optional page size; defaults to a reasonable server-side value.
+ */
+limit?: number | null, 
+/**
+ * Optional provider filter; when set, only sessions recorded under these
+ * providers are returned. When present but empty, includes all providers.
+ */
+modelProviders?: Array<string> | null, 
+/**
+ * Optional source filter; when set, only sessions from these source kinds
+ * are returned. When omitted or empty, defaults to interactive sources.
+ */
+sourceKinds?: Array<ThreadSourceKind> | null, 
+/**
+ * Optional archived filter; when set to true, only archived threads are returned.
+ * If false or null, only non-archived threads are returned.
+ */
+archived?: boolean | null, };
@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ThreadTagsSetParams = { threadId: string, 
+/**
+ * The full set of tags for the thread; replaces any existing tags.
+ * Tags are trimmed, deduplicated and sorted; blank tags are dropped.
+ */
+tags: Array<string>, };
@@ -84,11 +84,25 @@ export type { McpToolCallError } from "./McpToolCallError";
 export type { McpToolCallProgressNotification } from "./McpToolCallProgressNotification";
 export type { McpToolCallResult } from "./McpToolCallResult";
 export type { McpToolCallStatus } from "./McpToolCallStatus";
+export type { MemoryDeleteParams } from "./MemoryDeleteParams";
+export type { MemoryDeleteResponse } from "./MemoryDeleteResponse";
+export type { MemoryListParams } from "./MemoryListParams";
+export type { MemoryListResponse } from "./MemoryListResponse";
+export type { MemoryPinSetParams } from "./MemoryPinSetParams";
+export type { MemoryPinSetResponse } from "./MemoryPinSetResponse";
+export type { MemoryReadParams } from "./MemoryReadParams";
+export type { MemoryReadResponse } from "./MemoryReadResponse";
+export type { MemoryUpdateParams } from "./MemoryUpdateParams";
+export type { MemoryUpdateResponse } from "./MemoryUpdateResponse";
 export type { MergeStrategy } from "./MergeStrategy";
 export type { Model } from "./Model";
 export type { ModelListParams } from "./ModelListParams";
 export type { ModelListResponse } from "./ModelListResponse";
 export type { NetworkAccess } from "./NetworkAccess";
+export type { NetworkLogDecision } from "./NetworkLogDecision";
+export type { NetworkLogEntry } from "./NetworkLogEntry";
+export type { NetworkLogListParams } from "./NetworkLogListParams";
+export type { NetworkLogListResponse } from "./NetworkLogListResponse";
 export type { OverriddenMetadata } from "./OverriddenMetadata";
 export type { PatchApplyStatus } from "./PatchApplyStatus";
 export type { PatchChangeKind } from "./PatchChangeKind";
@@ -112,6 +126,17 @@ export type { ReviewTarget } from "./ReviewTarget";
 export type { SandboxMode } from "./SandboxMode";
 export type { SandboxPolicy } from "./SandboxPolicy";
 export type { SandboxWorkspaceWrite } from "./SandboxWorkspaceWrite";
+export type { SecretMetadata } from "./SecretMetadata";
+export type { SecretScope } from "./SecretScope";
+export type { SecretsGetParams } from "./SecretsGetParams";
+export type { SecretsGetResponse } from "./SecretsGetResponse";
+export type { SecretsListParams } from "./SecretsListParams";
+export type { SecretsListResponse } from "./SecretsListResponse";
+export type { SecretsRemoveParams } from "./SecretsRemoveParams";
+export type { SecretsRemoveResponse } from "./SecretsRemoveResponse";
+export type { SecretsSetParams } from "./SecretsSetParams";
+export type { SecretsSetResponse } from "./SecretsSetResponse";
+export type { ServerRequestResolvedNotification } from "./ServerRequestResolvedNotification";
 export type { SessionSource } from "./SessionSource";
 export type { SkillDependencies } from "./SkillDependencies";
 export type { SkillErrorInfo } from "./SkillErrorInfo";
@@ -137,6 +162,8 @@ export type { ThreadArchiveParams } from "./ThreadArchiveParams";
 export type { ThreadArchiveResponse } from "./ThreadArchiveResponse";
 export type { ThreadCompactStartParams } from "./ThreadCompactStartParams";
 export type { ThreadCompactStartResponse } from "./ThreadCompactStartResponse";
+export type { ThreadFolderSetParams } from "./ThreadFolderSetParams";
+export type { ThreadFolderSetResponse } from "./ThreadFolderSetResponse";
 export type { ThreadForkParams } from "./ThreadForkParams";
 export type { ThreadForkResponse } from "./ThreadForkResponse";
 export type { ThreadItem } from "./ThreadItem";
@@ -144,13 +171,20 @@ export type { ThreadListParams } from "./ThreadListParams";
 export type { ThreadListResponse } from "./ThreadListResponse";
 export type { ThreadLoadedListParams } from "./ThreadLoadedListParams";
 export type { ThreadLoadedListResponse } from "./ThreadLoadedListResponse";
+export type { ThreadMemory } from "./ThreadMemory";
 export type { ThreadNameUpdatedNotification } from "./ThreadNameUpdatedNotification";
+export type { ThreadPinSetParams } from "./ThreadPinSetParams";
+export type { ThreadPinSetResponse } from "./ThreadPinSetResponse";
 export type { ThreadReadParams } from "./ThreadReadParams";
 export type { ThreadReadResponse } from "./ThreadReadResponse";
 export type { ThreadResumeParams } from "./ThreadResumeParams";
 export type { ThreadResumeResponse } from "./ThreadResumeResponse";
 export type { ThreadRollbackParams } from "./ThreadRollbackParams";
 export type { ThreadRollbackResponse } from "./ThreadRollbackResponse";
+export type { ThreadSearchMatch } from "./ThreadSearchMatch";
+export type { ThreadSearchMatchSource } from "./ThreadSearchMatchSource";
+export type { ThreadSearchParams } from "./ThreadSearchParams";
+export type { ThreadSearchResponse } from "./ThreadSearchResponse";
 export type { ThreadSetNameParams } from "./ThreadSetNameParams";
 export type { ThreadSetNameResponse } from "./ThreadSetNameResponse";
 export type { ThreadSortKey } from "./ThreadSortKey";
@@ -158,6 +192,8 @@ export type { ThreadSourceKind } from "./ThreadSourceKind";
 export type { ThreadStartParams } from "./ThreadStartParams";
 export type { ThreadStartResponse } from "./ThreadStartResponse";
 export type { ThreadStartedNotification } from "./ThreadStartedNotification";
+export type { ThreadTagsSetParams } from "./ThreadTagsSetParams";
+export type { ThreadTagsSetResponse } from "./ThreadTagsSetResponse";
 export type { ThreadTokenUsage } from "./ThreadTokenUsage";
 export type { ThreadTokenUsageUpdatedNotification } from "./ThreadTokenUsageUpdatedNotification";
 export type { ThreadUnarchiveParams } from "./ThreadUnarchiveParams";
@@ -204,6 +204,18 @@ client_request_definitions! {
         params: v2::ThreadSetNameParams,
         response: v2::ThreadSetNameResponse,
     },
+    ThreadTagsSet => "thread/tags/set" {
+        params: v2::ThreadTagsSetParams,
+        response: v2::ThreadTagsSetResponse,
+    },
+    ThreadPinSet => "thread/pin/set" {
+        params: v2::ThreadPinSetParams,
+        response: v2::ThreadPinSetResponse,
+    },
+    ThreadFolderSet => "thread/folder/set" {
+        params: v2::ThreadFolderSetParams,
+        response: v2::ThreadFolderSetResponse,
+    },
     ThreadUnarchive => "thread/unarchive" {
         params: v2::ThreadUnarchiveParams,
         response: v2::ThreadUnarchiveResponse,
@@ -220,6 +232,10 @@ client_request_definitions! {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
     },
+    ThreadSearch => "thread/search" {
+        params: v2::ThreadSearchParams,
+        response: v2::ThreadSearchResponse,
+    },
     ThreadLoadedList => "thread/loaded/list" {
         params: v2::ThreadLoadedListParams,
         response: v2::ThreadLoadedListResponse,
@@ -345,6 +361,49 @@ client_request_definitions! {
         response: v2::ConfigWriteResponse,
     },
 
+    SecretsList => "secrets/list" {
+        params: v2::SecretsListParams,
+        response: v2::SecretsListResponse,
+    },
+    SecretsGet => "secrets/get" {
+        params: v2::SecretsGetParams,
+        response: v2::SecretsGetResponse,
+    },
+    SecretsSet => "secrets/set" {
+        params: v2::SecretsSetParams,
+        response: v2::SecretsSetResponse,
+    },
+    SecretsRemove => "secrets/remove" {
+        params: v2::SecretsRemoveParams,
+        response: v2::SecretsRemoveResponse,
+    },
+
+    MemoryList => "memory/list" {
+        params: v2::MemoryListParams,
+        response: v2::MemoryListResponse,
+    },
+    MemoryRead => "memory/read" {
+        params: v2::MemoryReadParams,
+        response: v2::MemoryReadResponse,
+    },
+    MemoryUpdate => "memory/update" {
+        params: v2::MemoryUpdateParams,
+        response: v2::MemoryUpdateResponse,
+    },
+    MemoryDelete => "memory/delete" {
+        params: v2::MemoryDeleteParams,
+        response: v2::MemoryDeleteResponse,
+    },
+    MemoryPinSet => "memory/pin/set" {
+        params: v2::MemoryPinSetParams,
+        response: v2::MemoryPinSetResponse,
+    },
+
+    NetworkLogList => "network/log/list" {
+        params: v2::NetworkLogListParams,
+        response: v2::NetworkLogListResponse,
+    },
+
     ConfigRequirementsRead => "configRequirements/read" {
         params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
         response: v2::ConfigRequirementsReadResponse,
@@ -725,6 +784,7 @@ server_notification_definitions! {
     ContextCompacted => "thread/compacted" (v2::ContextCompactedNotification),
     DeprecationNotice => "deprecationNotice" (v2::DeprecationNoticeNotification),
     ConfigWarning => "configWarning" (v2::ConfigWarningNotification),
+    ServerRequestResolved => "serverRequest/resolved" (v2::ServerRequestResolvedNotification),
 
     /// Notifies the user of world-writable directories on Windows, which cannot be protected by the sandbox.
     WindowsWorldWritableWarning => "windows/worldWritableWarning" (v2::WindowsWorldWritableWarningNotification),
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::RequestId;
 use crate::protocol::common::AuthMode;
 use codex_experimental_api_macros::ExperimentalApi;
 use codex_protocol::account::PlanType;
@@ -555,6 +556,300 @@ pub struct ConfigEdit {
     pub merge_strategy: MergeStrategy,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum SecretScope {
+    /// Shared by every repository.
+    Global,
+    /// Specific to one repository. When `environmentId` is omitted, the
+    /// environment of `cwd` (or the server's working directory) is used.
+    Environment,
+}
+
+/// A stored secret. Values are never part of the metadata.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretMetadata {
+    pub name: String,
+    pub scope: SecretScope,
+    /// Set for environment-scoped secrets.
+    pub environment_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretsListParams {
+    /// Restrict the listing to one scope. When omitted, global secrets and the
+    /// secrets of the current environment are listed.
+    #[ts(optional = nullable)]
+    pub scope: Option<SecretScope>,
+    #[ts(optional = nullable)]
+    pub environment_id: Option<String>,
+    #[ts(optional = nullable)]
+    pub cwd: Option<String>,
+    /// List the secrets of every environment instead of only the current one.
+    #[serde(default)]
+    pub all_environments: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretsListResponse {
+    pub data: Vec<SecretMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretsGetParams {
+    pub name: String,
+    /// Defaults to the environment scope.
+    #[ts(optional = nullable)]
+    pub scope: Option<SecretScope>,
+    #[ts(optional = nullable)]
+    pub environment_id: Option<String>,
+    #[ts(optional = nullable)]
+    pub cwd: Option<String>,
+    /// Include the secret value in the response. Off by default so clients
+    /// only see values they explicitly ask for.
+    #[serde(default)]
+    pub reveal: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretsGetResponse {
+    /// Null when the secret is not set in the requested scope.
+    pub secret: Option<SecretMetadata>,
+    /// Only populated when `reveal` was requested.
+    pub value: Option<String>,
+}
+
+impl std::fmt::Debug for SecretsGetResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretsGetResponse")
+            .field("secret", &self.secret)
+            .field("value", &self.value.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretsSetParams {
+    pub name: String,
+    pub value: String,
+    /// Defaults to the environment scope.
+    #[ts(optional = nullable)]
+    pub scope: Option<SecretScope>,
+    #[ts(optional = nullable)]
+    pub environment_id: Option<String>,
+    #[ts(optional = nullable)]
+    pub cwd: Option<String>,
+}
+
+// Secret values are kept out of `Debug` output so request logging never
+// records them.
+impl std::fmt::Debug for SecretsSetParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretsSetParams")
+            .field("name", &self.name)
+            .field("value", &"<redacted>")
+            .field("scope", &self.scope)
+            .field("environment_id", &self.environment_id)
+            .field("cwd", &self.cwd)
+            .finish()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretsSetResponse {
+    pub secret: SecretMetadata,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretsRemoveParams {
+    pub name: String,
+    /// Defaults to the environment scope.
+    #[ts(optional = nullable)]
+    pub scope: Option<SecretScope>,
+    #[ts(optional = nullable)]
+    pub environment_id: Option<String>,
+    #[ts(optional = nullable)]
+    pub cwd: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SecretsRemoveResponse {
+    pub removed: bool,
+}
+
+/// Memory summaries recorded for a thread.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMemory {
+    pub thread_id: String,
+    /// Summary of what happened in the thread.
+    pub trace_summary: String,
+    /// Summary recalled in later sessions started in the same directory.
+    pub memory_summary: String,
+    /// Pinned memories are always recalled for their directory.
+    pub pinned: bool,
+    /// Unix timestamp (in seconds) when the memory was last updated.
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryListParams {
+    /// List memories of threads started in this directory. Defaults to the
+    /// server's working directory.
+    #[ts(optional = nullable)]
+    pub cwd: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryListResponse {
+    /// Pinned memories first, then the most recently updated.
+    pub data: Vec<ThreadMemory>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryReadParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryReadResponse {
+    pub memory: ThreadMemory,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryUpdateParams {
+    pub thread_id: String,
+    /// Replaces the memory summary; the trace summary is kept.
+    pub memory_summary: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryUpdateResponse {
+    pub memory: ThreadMemory,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryDeleteParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryDeleteResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryPinSetParams {
+    pub thread_id: String,
+    pub pinned: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct MemoryPinSetResponse {
+    pub memory: ThreadMemory,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export_to = "v2/")]
+pub enum NetworkLogDecision {
+    Allow,
+    Deny,
+}
+
+/// A request a session's network proxy allowed or blocked.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct NetworkLogEntry {
+    /// Unix timestamp (in seconds) of the decision.
+    #[ts(type = "number")]
+    pub timestamp: i64,
+    pub thread_id: Option<String>,
+    pub turn_id: Option<String>,
+    /// Tool call that started the process making the request.
+    pub call_id: Option<String>,
+    /// `http`, `https` (intercepted), `http-connect`, `socks5`, `socks5-udp`
+    /// or `unix-socket`.
+    pub protocol: String,
+    pub host: String,
+    pub method: Option<String>,
+    pub decision: NetworkLogDecision,
+    pub reason: Option<String>,
+    /// Bytes sent upstream, when the proxy could tell.
+    #[ts(type = "number | null")]
+    pub bytes_sent: Option<i64>,
+    /// Bytes received from upstream, when the proxy could tell.
+    #[ts(type = "number | null")]
+    pub bytes_received: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct NetworkLogListParams {
+    #[ts(optional = nullable)]
+    pub thread_id: Option<String>,
+    #[ts(optional = nullable)]
+    pub host: Option<String>,
+    #[ts(optional = nullable)]
+    pub decision: Option<NetworkLogDecision>,
+    /// Only include requests from this unix timestamp (in seconds) on.
+    #[ts(type = "number | null")]
+    #[ts(optional = nullable)]
+    pub since: Option<i64>,
+    /// Defaults to 100, at most 1000.
+    #[ts(optional = nullable)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct NetworkLogListResponse {
+    /// Most recent requests first.
+    pub data: Vec<NetworkLogEntry>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1431,6 +1726,50 @@ pub struct ThreadSetNameParams {
     pub name: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTagsSetParams {
+    pub thread_id: String,
+    /// The full set of tags for the thread; replaces any existing tags.
+    /// Tags are trimmed, deduplicated and sorted; blank tags are dropped.
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTagsSetResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinSetParams {
+    pub thread_id: String,
+    pub pinned: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinSetResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadFolderSetParams {
+    pub thread_id: String,
+    /// `/`-separated folder path such as `clients/acme`. Null or blank
+    /// removes the thread from its folder.
+    #[ts(optional = nullable)]
+    pub folder: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadFolderSetResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1511,6 +1850,18 @@ pub struct ThreadListParams {
     /// If false or null, only non-archived threads are returned.
     #[ts(optional = nullable)]
     pub archived: Option<bool>,
+    /// Optional tag filter; when set, only threads carrying all of these tags
+    /// are returned. Requires the state database.
+    #[ts(optional = nullable)]
+    pub tags: Option<Vec<String>>,
+    /// Optional pin filter; when set, only pinned (true) or unpinned (false)
+    /// threads are returned. Requires the state database.
+    #[ts(optional = nullable)]
+    pub pinned: Option<bool>,
+    /// Optional folder filter; when set, only threads in this folder or one of
+    /// its subfolders are returned. Requires the state database.
+    #[ts(optional = nullable)]
+    pub folder: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
@@ -1549,6 +1900,66 @@ pub struct ThreadListResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchParams {
+    /// Free-text query matched against user messages, agent messages and
+    /// commands. The last term also matches as a prefix.
+    pub query: String,
+    /// Opaque pagination cursor returned by a previous call.
+    #[ts(optional = nullable)]
+    pub cursor: Option<String>,
+    /// Optional page size; defaults to a reasonable server-side value.
+    #[ts(optional = nullable)]
+    pub limit: Option<u32>,
+    /// Optional provider filter; when set, only sessions recorded under these
+    /// providers are returned. When present but empty, includes all providers.
+    #[ts(optional = nullable)]
+    pub model_providers: Option<Vec<String>>,
+    /// Optional source filter; when set, only sessions from these source kinds
+    /// are returned. When omitted or empty, defaults to interactive sources.
+    #[ts(optional = nullable)]
+    pub source_kinds: Option<Vec<ThreadSourceKind>>,
+    /// Optional archived filter; when set to true, only archived threads are returned.
+    /// If false or null, only non-archived threads are returned.
+    #[ts(optional = nullable)]
+    pub archived: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ThreadSearchMatchSource {
+    UserMessage,
+    AgentMessage,
+    Command,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchMatch {
+    pub thread: Thread,
+    /// Which part of the thread the best match came from.
+    pub source: ThreadSearchMatchSource,
+    /// Short excerpt around the best match.
+    pub snippet: String,
+    /// Byte ranges of the matched terms within `snippet`.
+    pub highlights: Vec<ByteRange>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResponse {
+    /// Matching threads, most relevant first, with one match per thread.
+    pub data: Vec<ThreadSearchMatch>,
+    /// Opaque cursor to pass to the next call to continue after the last item.
+    /// if None, there are no more items to return.
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1878,6 +2289,12 @@ pub struct Thread {
     pub source: SessionSource,
     /// Optional Git metadata captured when the thread was created.
     pub git_info: Option<GitInfo>,
+    /// Tags attached to the thread.
+    pub tags: Vec<String>,
+    /// Whether the thread is pinned.
+    pub pinned: bool,
+    /// `/`-separated folder the thread is filed under, if any.
+    pub folder: Option<String>,
     /// Only populated on `thread/resume`, `thread/rollback`, `thread/fork`, and `thread/read`
     /// (when `includeTurns` is true) responses.
     /// For all other responses and notifications returning a Thread,
@@ -3108,6 +3525,16 @@ pub struct TextRange {
     pub end: TextPosition,
 }
 
+/// A request the server sent was answered elsewhere, for example from the menu
+/// bar, so clients should close the prompt they show for it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ServerRequestResolvedNotification {
+    pub thread_id: String,
+    pub request_id: RequestId,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
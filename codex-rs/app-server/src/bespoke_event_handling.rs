@@ -283,6 +283,47 @@ pub(crate) async fn apply_bespoke_event_handling(
                 });
             }
         },
+        EventMsg::NetworkApprovalRequest(request) => match api_version {
+            ApiVersion::V1 => {
+                error!(
+                    "network approvals are only supported on api v2 (call_id: {})",
+                    request.call_id
+                );
+                if let Err(err) = conversation
+                    .submit(Op::NetworkApproval {
+                        id: event_turn_id,
+                        decision: ReviewDecision::Denied,
+                    })
+                    .await
+                {
+                    error!("failed to submit NetworkApproval: {err}");
+                }
+            }
+            ApiVersion::V2 => {
+                let port = request.port;
+                let params = CommandExecutionRequestApprovalParams {
+                    thread_id: conversation_id.to_string(),
+                    turn_id: request.turn_id,
+                    item_id: request.call_id,
+                    reason: Some(format!(
+                        "Network access to {}:{port} was blocked by the allowlist",
+                        request.host
+                    )),
+                    command: Some(shlex_join(&request.command)),
+                    cwd: Some(request.cwd),
+                    command_actions: None,
+                    proposed_execpolicy_amendment: None,
+                };
+                let rx = outgoing
+                    .send_request(ServerRequestPayload::CommandExecutionRequestApproval(
+                        params,
+                    ))
+                    .await;
+                tokio::spawn(async move {
+                    on_network_approval_response(event_turn_id, rx, conversation).await;
+                });
+            }
+        },
         EventMsg::RequestUserInput(request) => {
             if matches!(api_version, ApiVersion::V2) {
                 let questions = request
@@ -1835,6 +1876,49 @@ async fn on_command_execution_request_approval_response(
     }
 }
 
+async fn on_network_approval_response(
+    event_turn_id: String,
+    receiver: oneshot::Receiver<JsonValue>,
+    conversation: Arc<CodexThread>,
+) {
+    let decision = match receiver.await {
+        Ok(value) => {
+            let response = serde_json::from_value::<CommandExecutionRequestApprovalResponse>(value)
+                .unwrap_or_else(|err| {
+                    error!("failed to deserialize CommandExecutionRequestApprovalResponse: {err}");
+                    CommandExecutionRequestApprovalResponse {
+                        decision: CommandExecutionApprovalDecision::Decline,
+                    }
+                });
+            match response.decision {
+                CommandExecutionApprovalDecision::Accept
+                | CommandExecutionApprovalDecision::AcceptWithExecpolicyAmendment { .. } => {
+                    ReviewDecision::Approved
+                }
+                CommandExecutionApprovalDecision::AcceptForSession => {
+                    ReviewDecision::ApprovedForSession
+                }
+                CommandExecutionApprovalDecision::Decline => ReviewDecision::Denied,
+                CommandExecutionApprovalDecision::Cancel => ReviewDecision::Abort,
+            }
+        }
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::NetworkApproval {
+            id: event_turn_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit NetworkApproval: {err}");
+    }
+}
+
 /// similar to handle_mcp_tool_call_begin in exec
 async fn construct_mcp_tool_call_notification(
     begin_event: McpToolCallBeginEvent,
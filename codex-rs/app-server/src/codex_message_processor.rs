@@ -23,6 +23,7 @@ use codex_app_server_protocol::ArchiveConversationResponse;
 use codex_app_server_protocol::AskForApproval;
 use codex_app_server_protocol::AuthMode;
 use codex_app_server_protocol::AuthStatusChangeNotification;
+use codex_app_server_protocol::ByteRange;
 use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginAccountResponse;
 use codex_app_server_protocol::CancelLoginAccountStatus;
@@ -75,10 +76,24 @@ use codex_app_server_protocol::McpServerOauthLoginParams;
 use codex_app_server_protocol::McpServerOauthLoginResponse;
 use codex_app_server_protocol::McpServerRefreshResponse;
 use codex_app_server_protocol::McpServerStatus;
+use codex_app_server_protocol::MemoryDeleteParams;
+use codex_app_server_protocol::MemoryDeleteResponse;
+use codex_app_server_protocol::MemoryListParams;
+use codex_app_server_protocol::MemoryListResponse;
+use codex_app_server_protocol::MemoryPinSetParams;
+use codex_app_server_protocol::MemoryPinSetResponse;
+use codex_app_server_protocol::MemoryReadParams;
+use codex_app_server_protocol::MemoryReadResponse;
+use codex_app_server_protocol::MemoryUpdateParams;
+use codex_app_server_protocol::MemoryUpdateResponse;
 use codex_app_server_protocol::MockExperimentalMethodParams;
 use codex_app_server_protocol::MockExperimentalMethodResponse;
 use codex_app_server_protocol::ModelListParams;
 use codex_app_server_protocol::ModelListResponse;
+use codex_app_server_protocol::NetworkLogDecision;
+use codex_app_server_protocol::NetworkLogEntry;
+use codex_app_server_protocol::NetworkLogListParams;
+use codex_app_server_protocol::NetworkLogListResponse;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::NewConversationResponse;
 use codex_app_server_protocol::RemoveConversationListenerParams;
@@ -111,6 +126,8 @@ use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
 use codex_app_server_protocol::ThreadCompactStartParams;
 use codex_app_server_protocol::ThreadCompactStartResponse;
+use codex_app_server_protocol::ThreadFolderSetParams;
+use codex_app_server_protocol::ThreadFolderSetResponse;
 use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadForkResponse;
 use codex_app_server_protocol::ThreadItem;
@@ -118,11 +135,18 @@ use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadLoadedListParams;
 use codex_app_server_protocol::ThreadLoadedListResponse;
+use codex_app_server_protocol::ThreadMemory as ApiThreadMemory;
+use codex_app_server_protocol::ThreadPinSetParams;
+use codex_app_server_protocol::ThreadPinSetResponse;
 use codex_app_server_protocol::ThreadReadParams;
 use codex_app_server_protocol::ThreadReadResponse;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadResumeResponse;
 use codex_app_server_protocol::ThreadRollbackParams;
+use codex_app_server_protocol::ThreadSearchMatch;
+use codex_app_server_protocol::ThreadSearchMatchSource;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
 use codex_app_server_protocol::ThreadSetNameParams;
 use codex_app_server_protocol::ThreadSetNameResponse;
 use codex_app_server_protocol::ThreadSortKey;
@@ -130,6 +154,8 @@ use codex_app_server_protocol::ThreadSourceKind;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
+use codex_app_server_protocol::ThreadTagsSetParams;
+use codex_app_server_protocol::ThreadTagsSetResponse;
 use codex_app_server_protocol::ThreadUnarchiveParams;
 use codex_app_server_protocol::ThreadUnarchiveResponse;
 use codex_app_server_protocol::Turn;
@@ -159,6 +185,7 @@ use codex_core::SessionMeta;
 use codex_core::ThreadConfigSnapshot;
 use codex_core::ThreadManager;
 use codex_core::ThreadSortKey as CoreThreadSortKey;
+use codex_core::ThreadsPage;
 use codex_core::auth::AuthMode as CoreAuthMode;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::login_with_api_key;
@@ -196,8 +223,20 @@ use codex_core::rollout_date_parts;
 use codex_core::sandboxing::SandboxPermissions;
 use codex_core::skills::remote::download_remote_skill;
 use codex_core::skills::remote::list_remote_skills;
+use codex_core::state_db::NetworkAuditDecision;
+use codex_core::state_db::NetworkAuditEntry;
+use codex_core::state_db::NetworkAuditQuery;
 use codex_core::state_db::StateDbHandle;
+use codex_core::state_db::ThreadMemory as StateThreadMemory;
+use codex_core::state_db::ThreadMetadata as StateThreadMetadata;
+use codex_core::state_db::ThreadOrganization;
+use codex_core::state_db::ThreadOrganizationFilter;
+use codex_core::state_db::ThreadSearchSource as StateThreadSearchSource;
+use codex_core::state_db::list_threads_db;
+use codex_core::state_db::normalize_thread_folder;
+use codex_core::state_db::normalize_thread_tags;
 use codex_core::state_db::open_if_present;
+use codex_core::state_db::search_threads_db;
 use codex_core::token_data::parse_id_token;
 use codex_core::windows_sandbox::WindowsSandboxLevelExt;
 use codex_feedback::CodexFeedback;
@@ -266,6 +305,8 @@ pub(crate) type TurnSummaryStore = Arc<Mutex<HashMap<ThreadId, TurnSummary>>>;
 
 const THREAD_LIST_DEFAULT_LIMIT: usize = 25;
 const THREAD_LIST_MAX_LIMIT: usize = 100;
+const NETWORK_LOG_DEFAULT_LIMIT: usize = 100;
+const NETWORK_LOG_MAX_LIMIT: usize = 1_000;
 
 // Duration before a ChatGPT login attempt is abandoned.
 const LOGIN_CHATGPT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
@@ -488,6 +529,42 @@ impl CodexMessageProcessor {
                 self.thread_set_name(to_connection_request_id(request_id), params)
                     .await;
             }
+            ClientRequest::ThreadTagsSet { request_id, params } => {
+                self.thread_tags_set(to_connection_request_id(request_id), params)
+                    .await;
+            }
+            ClientRequest::ThreadPinSet { request_id, params } => {
+                self.thread_pin_set(to_connection_request_id(request_id), params)
+                    .await;
+            }
+            ClientRequest::ThreadFolderSet { request_id, params } => {
+                self.thread_folder_set(to_connection_request_id(request_id), params)
+                    .await;
+            }
+            ClientRequest::MemoryList { request_id, params } => {
+                self.memory_list(to_connection_request_id(request_id), params)
+                    .await;
+            }
+            ClientRequest::MemoryRead { request_id, params } => {
+                self.memory_read(to_connection_request_id(request_id), params)
+                    .await;
+            }
+            ClientRequest::MemoryUpdate { request_id, params } => {
+                self.memory_update(to_connection_request_id(request_id), params)
+                    .await;
+            }
+            ClientRequest::MemoryDelete { request_id, params } => {
+                self.memory_delete(to_connection_request_id(request_id), params)
+                    .await;
+            }
+            ClientRequest::MemoryPinSet { request_id, params } => {
+                self.memory_pin_set(to_connection_request_id(request_id), params)
+                    .await;
+            }
+            ClientRequest::NetworkLogList { request_id, params } => {
+                self.network_log_list(to_connection_request_id(request_id), params)
+                    .await;
+            }
             ClientRequest::ThreadUnarchive { request_id, params } => {
                 self.thread_unarchive(to_connection_request_id(request_id), params)
                     .await;
@@ -504,6 +581,10 @@ impl CodexMessageProcessor {
                 self.thread_list(to_connection_request_id(request_id), params)
                     .await;
             }
+            ClientRequest::ThreadSearch { request_id, params } => {
+                self.thread_search(to_connection_request_id(request_id), params)
+                    .await;
+            }
             ClientRequest::ThreadLoadedList { request_id, params } => {
                 self.thread_loaded_list(to_connection_request_id(request_id), params)
                     .await;
@@ -727,6 +808,12 @@ impl CodexMessageProcessor {
             ClientRequest::ConfigRequirementsRead { .. } => {
                 warn!("ConfigRequirementsRead request reached CodexMessageProcessor unexpectedly");
             }
+            ClientRequest::SecretsList { .. }
+            | ClientRequest::SecretsGet { .. }
+            | ClientRequest::SecretsSet { .. }
+            | ClientRequest::SecretsRemove { .. } => {
+                warn!("Secrets request reached CodexMessageProcessor unexpectedly");
+            }
             ClientRequest::GetAccountRateLimits {
                 request_id,
                 params: _,
@@ -1643,6 +1730,7 @@ impl CodexMessageProcessor {
                 sandbox_cwd.as_path(),
                 &codex_linux_sandbox_exe,
                 use_linux_sandbox_bwrap,
+                &[],
                 None,
             )
             .await
@@ -2040,6 +2128,363 @@ impl CodexMessageProcessor {
             .await;
     }
 
+    async fn thread_tags_set(&self, request_id: ConnectionRequestId, params: ThreadTagsSetParams) {
+        let ThreadTagsSetParams { thread_id, tags } = params;
+        let result = self
+            .update_thread_organization(&thread_id, |state_db, thread_id| async move {
+                state_db.set_thread_tags(thread_id, &tags).await
+            })
+            .await;
+        match result {
+            Ok(()) => {
+                self.outgoing
+                    .send_response(request_id, ThreadTagsSetResponse {})
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn thread_pin_set(&self, request_id: ConnectionRequestId, params: ThreadPinSetParams) {
+        let ThreadPinSetParams { thread_id, pinned } = params;
+        let result = self
+            .update_thread_organization(&thread_id, |state_db, thread_id| async move {
+                state_db.set_thread_pinned(thread_id, pinned).await
+            })
+            .await;
+        match result {
+            Ok(()) => {
+                self.outgoing
+                    .send_response(request_id, ThreadPinSetResponse {})
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn thread_folder_set(
+        &self,
+        request_id: ConnectionRequestId,
+        params: ThreadFolderSetParams,
+    ) {
+        let ThreadFolderSetParams { thread_id, folder } = params;
+        let result = self
+            .update_thread_organization(&thread_id, |state_db, thread_id| async move {
+                state_db
+                    .set_thread_folder(thread_id, folder.as_deref())
+                    .await
+            })
+            .await;
+        match result {
+            Ok(()) => {
+                self.outgoing
+                    .send_response(request_id, ThreadFolderSetResponse {})
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn memory_list(&self, request_id: ConnectionRequestId, params: MemoryListParams) {
+        let cwd = params
+            .cwd
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.config.cwd.clone());
+        let result = match self.memory_state_db().await {
+            Ok(state_db) => state_db
+                .list_thread_memories_for_cwd(&cwd)
+                .await
+                .map_err(|err| JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to list memories: {err}"),
+                    data: None,
+                }),
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(memories) => {
+                let data = memories.into_iter().map(api_thread_memory).collect();
+                self.outgoing
+                    .send_response(request_id, MemoryListResponse { data })
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn memory_read(&self, request_id: ConnectionRequestId, params: MemoryReadParams) {
+        let result = self
+            .with_thread_memory(&params.thread_id, |state_db, thread_id| async move {
+                state_db.get_thread_memory(thread_id).await
+            })
+            .await;
+        match result {
+            Ok(memory) => {
+                self.outgoing
+                    .send_response(request_id, MemoryReadResponse { memory })
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn memory_update(&self, request_id: ConnectionRequestId, params: MemoryUpdateParams) {
+        let MemoryUpdateParams {
+            thread_id,
+            memory_summary,
+        } = params;
+        let result = self
+            .with_thread_memory(&thread_id, |state_db, thread_id| async move {
+                state_db
+                    .update_thread_memory_summary(thread_id, &memory_summary)
+                    .await
+            })
+            .await;
+        match result {
+            Ok(memory) => {
+                self.outgoing
+                    .send_response(request_id, MemoryUpdateResponse { memory })
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn memory_delete(&self, request_id: ConnectionRequestId, params: MemoryDeleteParams) {
+        let result = self
+            .with_thread_memory(&params.thread_id, |state_db, thread_id| async move {
+                let memory = state_db.get_thread_memory(thread_id).await?;
+                if memory.is_some() {
+                    state_db.delete_thread_memory(thread_id).await?;
+                }
+                Ok(memory)
+            })
+            .await;
+        match result {
+            Ok(_) => {
+                self.outgoing
+                    .send_response(request_id, MemoryDeleteResponse {})
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn memory_pin_set(&self, request_id: ConnectionRequestId, params: MemoryPinSetParams) {
+        let MemoryPinSetParams { thread_id, pinned } = params;
+        let result = self
+            .with_thread_memory(&thread_id, |state_db, thread_id| async move {
+                state_db.set_thread_memory_pinned(thread_id, pinned).await
+            })
+            .await;
+        match result {
+            Ok(memory) => {
+                self.outgoing
+                    .send_response(request_id, MemoryPinSetResponse { memory })
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    /// Read or change the memory of a stored thread. `update` returns `None`
+    /// when the thread has no memory.
+    async fn with_thread_memory<F, Fut>(
+        &self,
+        thread_id: &str,
+        update: F,
+    ) -> Result<ApiThreadMemory, JSONRPCErrorError>
+    where
+        F: FnOnce(StateDbHandle, ThreadId) -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<StateThreadMemory>>>,
+    {
+        let thread_uuid = ThreadId::from_string(thread_id).map_err(|err| JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: format!("invalid thread id: {err}"),
+            data: None,
+        })?;
+        let state_db = self.memory_state_db().await?;
+        match update(state_db, thread_uuid).await {
+            Ok(Some(memory)) => Ok(api_thread_memory(memory)),
+            Ok(None) => Err(JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("no memory for thread: {thread_uuid}"),
+                data: None,
+            }),
+            Err(err) => Err(JSONRPCErrorError {
+                code: INTERNAL_ERROR_CODE,
+                message: format!("failed to update memory of thread {thread_uuid}: {err}"),
+                data: None,
+            }),
+        }
+    }
+
+    async fn memory_state_db(&self) -> Result<StateDbHandle, JSONRPCErrorError> {
+        open_if_present(
+            &self.config.codex_home,
+            self.config.model_provider_id.as_str(),
+        )
+        .await
+        .ok_or_else(|| JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: "memories require the state database (enable the `sqlite` feature)"
+                .to_string(),
+            data: None,
+        })
+    }
+
+    async fn network_log_list(
+        &self,
+        request_id: ConnectionRequestId,
+        params: NetworkLogListParams,
+    ) {
+        match self.list_network_log(params).await {
+            Ok(data) => {
+                self.outgoing
+                    .send_response(request_id, NetworkLogListResponse { data })
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn list_network_log(
+        &self,
+        params: NetworkLogListParams,
+    ) -> Result<Vec<NetworkLogEntry>, JSONRPCErrorError> {
+        let thread_id = params
+            .thread_id
+            .as_deref()
+            .map(ThreadId::from_string)
+            .transpose()
+            .map_err(|err| JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("invalid thread id: {err}"),
+                data: None,
+            })?;
+        let query = NetworkAuditQuery {
+            thread_id,
+            host: params.host,
+            decision: params.decision.map(|decision| match decision {
+                NetworkLogDecision::Allow => NetworkAuditDecision::Allow,
+                NetworkLogDecision::Deny => NetworkAuditDecision::Deny,
+            }),
+            since: params
+                .since
+                .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0)),
+            limit: params
+                .limit
+                .map_or(NETWORK_LOG_DEFAULT_LIMIT, |limit| limit as usize)
+                .min(NETWORK_LOG_MAX_LIMIT),
+        };
+        let state_db = open_if_present(
+            &self.config.codex_home,
+            self.config.model_provider_id.as_str(),
+        )
+        .await
+        .ok_or_else(|| JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: "the network log requires the state database (enable the `sqlite` feature)"
+                .to_string(),
+            data: None,
+        })?;
+        let entries =
+            state_db
+                .list_network_audit(&query)
+                .await
+                .map_err(|err| JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to list network requests: {err}"),
+                    data: None,
+                })?;
+        Ok(entries.into_iter().map(api_network_log_entry).collect())
+    }
+
+    /// Apply an organization change (tags, pin or folder) to a stored thread.
+    async fn update_thread_organization<F, Fut>(
+        &self,
+        thread_id: &str,
+        update: F,
+    ) -> Result<(), JSONRPCErrorError>
+    where
+        F: FnOnce(StateDbHandle, ThreadId) -> Fut,
+        Fut: Future<Output = anyhow::Result<Option<ThreadOrganization>>>,
+    {
+        let thread_uuid = ThreadId::from_string(thread_id).map_err(|err| JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: format!("invalid thread id: {err}"),
+            data: None,
+        })?;
+        let Some(state_db_ctx) = open_if_present(
+            &self.config.codex_home,
+            self.config.model_provider_id.as_str(),
+        )
+        .await
+        else {
+            return Err(JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message:
+                    "thread organization requires the state database (enable the `sqlite` feature)"
+                        .to_string(),
+                data: None,
+            });
+        };
+        match update(state_db_ctx, thread_uuid).await {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("thread not found: {thread_uuid}"),
+                data: None,
+            }),
+            Err(err) => Err(JSONRPCErrorError {
+                code: INTERNAL_ERROR_CODE,
+                message: format!("failed to update thread {thread_uuid}: {err}"),
+                data: None,
+            }),
+        }
+    }
+
+    /// Fill in tags, pins and folders, which only live in the state database.
+    async fn attach_thread_organization<'a>(
+        &self,
+        threads: impl IntoIterator<Item = &'a mut Thread>,
+    ) {
+        let threads = threads.into_iter().collect::<Vec<_>>();
+        if threads.is_empty() {
+            return;
+        }
+        let Some(state_db_ctx) = open_if_present(
+            &self.config.codex_home,
+            self.config.model_provider_id.as_str(),
+        )
+        .await
+        else {
+            return;
+        };
+        let thread_ids = threads
+            .iter()
+            .filter_map(|thread| ThreadId::from_string(&thread.id).ok())
+            .collect::<Vec<_>>();
+        let organizations = match state_db_ctx.get_thread_organizations(&thread_ids).await {
+            Ok(organizations) => organizations,
+            Err(err) => {
+                warn!("failed to load thread organization: {err}");
+                return;
+            }
+        };
+        for thread in threads {
+            let Some(organization) = ThreadId::from_string(&thread.id)
+                .ok()
+                .and_then(|thread_id| organizations.get(&thread_id))
+            else {
+                continue;
+            };
+            thread.tags = organization.tags.clone();
+            thread.pinned = organization.pinned;
+            thread.folder = organization.folder.clone();
+        }
+    }
+
     async fn thread_unarchive(
         &mut self,
         request_id: ConnectionRequestId,
@@ -2211,7 +2656,9 @@ impl CodexMessageProcessor {
         .await;
 
         match result {
-            Ok(thread) => {
+            Ok(mut thread) => {
+                self.attach_thread_organization(std::iter::once(&mut thread))
+                    .await;
                 let response = ThreadUnarchiveResponse { thread };
                 self.outgoing.send_response(request_id, response).await;
             }
@@ -2308,12 +2755,20 @@ impl CodexMessageProcessor {
             model_providers,
             source_kinds,
             archived,
+            tags,
+            pinned,
+            folder,
         } = params;
 
         let requested_page_size = limit
             .map(|value| value as usize)
             .unwrap_or(THREAD_LIST_DEFAULT_LIMIT)
             .clamp(1, THREAD_LIST_MAX_LIMIT);
+        let organization = ThreadOrganizationFilter {
+            tags: normalize_thread_tags(tags.as_deref().unwrap_or_default()),
+            pinned,
+            folder: folder.as_deref().and_then(normalize_thread_folder),
+        };
         let core_sort_key = match sort_key.unwrap_or(ThreadSortKey::CreatedAt) {
             ThreadSortKey::CreatedAt => CoreThreadSortKey::CreatedAt,
             ThreadSortKey::UpdatedAt => CoreThreadSortKey::UpdatedAt,
@@ -2326,6 +2781,7 @@ impl CodexMessageProcessor {
                 source_kinds,
                 core_sort_key,
                 archived.unwrap_or(false),
+                &organization,
             )
             .await
         {
@@ -2336,11 +2792,140 @@ impl CodexMessageProcessor {
             }
         };
 
-        let data = summaries.into_iter().map(summary_to_thread).collect();
+        let mut data = summaries
+            .into_iter()
+            .map(summary_to_thread)
+            .collect::<Vec<_>>();
+        self.attach_thread_organization(data.iter_mut()).await;
         let response = ThreadListResponse { data, next_cursor };
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn thread_search(&self, request_id: ConnectionRequestId, params: ThreadSearchParams) {
+        let ThreadSearchParams {
+            query,
+            cursor,
+            limit,
+            model_providers,
+            source_kinds,
+            archived,
+        } = params;
+
+        let requested_page_size = limit
+            .map(|value| value as usize)
+            .unwrap_or(THREAD_LIST_DEFAULT_LIMIT)
+            .clamp(1, THREAD_LIST_MAX_LIMIT);
+        let mut offset = match cursor.as_deref().map(str::parse::<usize>) {
+            None => 0,
+            Some(Ok(offset)) => offset,
+            Some(Err(_)) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid cursor: {}", cursor.unwrap_or_default()),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        let Some(state_db_ctx) = open_if_present(
+            &self.config.codex_home,
+            self.config.model_provider_id.as_str(),
+        )
+        .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: "thread search requires the state database (enable the `sqlite` feature)"
+                    .to_string(),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        let model_provider_filter = match model_providers {
+            Some(providers) if providers.is_empty() => None,
+            Some(providers) => Some(providers),
+            None => Some(vec![self.config.model_provider_id.clone()]),
+        };
+        let (allowed_sources, source_kind_filter) = compute_source_filters(source_kinds);
+
+        // Source kinds that SQLite cannot filter on are applied per hit, so keep
+        // fetching pages until this one is full or the results run out.
+        let mut data = Vec::with_capacity(requested_page_size);
+        let mut next_cursor = None;
+        'pages: loop {
+            let page = match search_threads_db(
+                state_db_ctx.as_ref(),
+                query.as_str(),
+                requested_page_size,
+                offset,
+                allowed_sources.as_slice(),
+                model_provider_filter.as_deref(),
+                archived.unwrap_or(false),
+            )
+            .await
+            {
+                Ok(page) => page,
+                Err(err) => {
+                    self.send_internal_error(
+                        request_id,
+                        format!("failed to search threads: {err}"),
+                    )
+                    .await;
+                    return;
+                }
+            };
+            let page_len = page.items.len();
+            for (index, hit) in page.items.into_iter().enumerate() {
+                let summary = summary_from_thread_metadata(hit.thread);
+                if source_kind_filter
+                    .as_ref()
+                    .is_some_and(|filter| !source_kind_matches(&summary.source, filter))
+                {
+                    continue;
+                }
+                data.push(ThreadSearchMatch {
+                    thread: summary_to_thread(summary),
+                    source: match hit.source {
+                        StateThreadSearchSource::UserMessage => {
+                            ThreadSearchMatchSource::UserMessage
+                        }
+                        StateThreadSearchSource::AgentMessage => {
+                            ThreadSearchMatchSource::AgentMessage
+                        }
+                        StateThreadSearchSource::Command => ThreadSearchMatchSource::Command,
+                    },
+                    snippet: hit.snippet,
+                    highlights: hit
+                        .highlights
+                        .into_iter()
+                        .map(|range| ByteRange {
+                            start: range.start,
+                            end: range.end,
+                        })
+                        .collect(),
+                });
+                if data.len() == requested_page_size {
+                    if index + 1 < page_len || page.next_offset.is_some() {
+                        next_cursor = Some((offset + index + 1).to_string());
+                    }
+                    break 'pages;
+                }
+            }
+            match page.next_offset {
+                Some(next_offset) => offset = next_offset,
+                None => break,
+            }
+        }
+
+        self.attach_thread_organization(data.iter_mut().map(|hit| &mut hit.thread))
+            .await;
+        let response = ThreadSearchResponse { data, next_cursor };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
     async fn thread_loaded_list(
         &self,
         request_id: ConnectionRequestId,
@@ -2514,10 +3099,16 @@ impl CodexMessageProcessor {
             thread.model = Some(loaded_thread.config_snapshot().await.model);
         }
 
+        self.attach_thread_organization(std::iter::once(&mut thread))
+            .await;
         let response = ThreadReadResponse { thread };
         self.outgoing.send_response(request_id, response).await;
     }
 
+    pub(crate) fn thread_manager(&self) -> Arc<ThreadManager> {
+        Arc::clone(&self.thread_manager)
+    }
+
     pub(crate) fn thread_created_receiver(&self) -> broadcast::Receiver<ThreadId> {
         self.thread_manager.subscribe_thread_created()
     }
@@ -2735,6 +3326,8 @@ impl CodexMessageProcessor {
                     .as_deref()
                     .map_or_else(Vec::new, build_turns_from_event_msgs);
                 thread.model = Some(session_configured.model.clone());
+                self.attach_thread_organization(std::iter::once(&mut thread))
+                    .await;
 
                 let response = ThreadResumeResponse {
                     thread,
@@ -3051,6 +3644,7 @@ impl CodexMessageProcessor {
                 None,
                 CoreThreadSortKey::UpdatedAt,
                 false,
+                &ThreadOrganizationFilter::default(),
             )
             .await
         {
@@ -3064,6 +3658,7 @@ impl CodexMessageProcessor {
         };
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn list_threads_common(
         &self,
         requested_page_size: usize,
@@ -3072,6 +3667,7 @@ impl CodexMessageProcessor {
         source_kinds: Option<Vec<ThreadSourceKind>>,
         sort_key: CoreThreadSortKey,
         archived: bool,
+        organization: &ThreadOrganizationFilter,
     ) -> Result<(Vec<ConversationSummary>, Option<String>), JSONRPCErrorError> {
         let mut cursor_obj: Option<RolloutCursor> = match cursor.as_ref() {
             Some(cursor_str) => {
@@ -3106,10 +3702,38 @@ impl CodexMessageProcessor {
             self.config.model_provider_id.as_str(),
         )
         .await;
+        // Tags, pins and folders only live in the state database, so there is
+        // no rollout fallback when filtering on them.
+        if !organization.is_empty() && state_db_ctx.is_none() {
+            return Err(JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: "filtering threads by tags, pin or folder requires the state database (enable the `sqlite` feature)".to_string(),
+                data: None,
+            });
+        }
 
         while remaining > 0 {
             let page_size = remaining.min(THREAD_LIST_MAX_LIMIT);
-            let page = if archived {
+            let page = if !organization.is_empty() {
+                list_threads_db(
+                    state_db_ctx.as_deref(),
+                    &self.config.codex_home,
+                    page_size,
+                    cursor_obj.as_ref(),
+                    sort_key,
+                    allowed_sources,
+                    model_provider_filter.as_deref(),
+                    archived,
+                    organization,
+                )
+                .await
+                .map(ThreadsPage::from)
+                .ok_or_else(|| JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: "failed to list threads from the state database".to_string(),
+                    data: None,
+                })?
+            } else if archived {
                 RolloutRecorder::list_archived_threads(
                     &self.config.codex_home,
                     page_size,
@@ -5527,7 +6151,11 @@ async fn read_summary_from_state_db_context_by_thread_id(
         Ok(Some(metadata)) => metadata,
         Ok(None) | Err(_) => return None,
     };
-    Some(summary_from_state_db_metadata(
+    Some(summary_from_thread_metadata(metadata))
+}
+
+fn summary_from_thread_metadata(metadata: StateThreadMetadata) -> ConversationSummary {
+    summary_from_state_db_metadata(
         metadata.id,
         metadata.rollout_path,
         metadata.first_user_message,
@@ -5544,7 +6172,7 @@ async fn read_summary_from_state_db_context_by_thread_id(
         metadata.git_sha,
         metadata.git_branch,
         metadata.git_origin_url,
-    ))
+    )
 }
 
 async fn summary_from_thread_list_item(
@@ -5828,6 +6456,9 @@ fn build_ephemeral_thread(thread_id: ThreadId, config_snapshot: &ThreadConfigSna
         cli_version: env!("CARGO_PKG_VERSION").to_string(),
         source: config_snapshot.session_source.clone().into(),
         git_info: None,
+        tags: Vec::new(),
+        pinned: false,
+        folder: None,
         turns: Vec::new(),
     }
 }
@@ -5867,10 +6498,42 @@ pub(crate) fn summary_to_thread(summary: ConversationSummary) -> Thread {
         cli_version,
         source: source.into(),
         git_info,
+        tags: Vec::new(),
+        pinned: false,
+        folder: None,
         turns: Vec::new(),
     }
 }
 
+fn api_network_log_entry(entry: NetworkAuditEntry) -> NetworkLogEntry {
+    NetworkLogEntry {
+        timestamp: entry.ts.timestamp(),
+        thread_id: entry.thread_id.map(|thread_id| thread_id.to_string()),
+        turn_id: entry.turn_id,
+        call_id: entry.call_id,
+        protocol: entry.protocol,
+        host: entry.host,
+        method: entry.method,
+        decision: match entry.decision {
+            NetworkAuditDecision::Allow => NetworkLogDecision::Allow,
+            NetworkAuditDecision::Deny => NetworkLogDecision::Deny,
+        },
+        reason: entry.reason,
+        bytes_sent: entry.bytes_sent,
+        bytes_received: entry.bytes_received,
+    }
+}
+
+fn api_thread_memory(memory: StateThreadMemory) -> ApiThreadMemory {
+    ApiThreadMemory {
+        thread_id: memory.thread_id.to_string(),
+        trace_summary: memory.trace_summary,
+        memory_summary: memory.memory_summary,
+        pinned: memory.pinned,
+        updated_at: memory.updated_at.timestamp(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
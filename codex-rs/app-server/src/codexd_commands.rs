@@ -0,0 +1,133 @@
+//! Applies commands that hub consumers route to this app-server through codexd.
+
+use std::sync::Arc;
+
+use codex_app_server_protocol::CommandExecutionRequestApprovalResponse;
+use codex_app_server_protocol::FileChangeRequestApprovalResponse;
+use codex_codexd::producer::RuntimeCommandRequest;
+use codex_codexd::protocol::PendingApprovalKind;
+use codex_codexd::protocol::RuntimeCommand;
+use codex_core::CodexThread;
+use codex_core::ThreadManager;
+use codex_core::protocol::Op;
+use codex_protocol::ThreadId;
+use codex_protocol::user_input::UserInput;
+use tokio::sync::mpsc;
+
+use crate::outgoing_message::OutgoingMessageSender;
+
+pub(crate) async fn run_codexd_command_loop(
+    mut receiver: mpsc::Receiver<RuntimeCommandRequest>,
+    thread_manager: Arc<ThreadManager>,
+    outgoing: Arc<OutgoingMessageSender>,
+) {
+    while let Some(request) = receiver.recv().await {
+        let result = apply_command(&request.command, &thread_manager, &outgoing).await;
+        request.respond(result);
+    }
+}
+
+async fn apply_command(
+    command: &RuntimeCommand,
+    thread_manager: &ThreadManager,
+    outgoing: &OutgoingMessageSender,
+) -> Result<(), String> {
+    match command {
+        RuntimeCommand::TurnInterrupt { thread_id, .. } => {
+            let thread = load_thread(thread_manager, thread_id).await?;
+            submit(&thread, Op::Interrupt).await
+        }
+        RuntimeCommand::CommandExecutionApproval {
+            thread_id,
+            item_id,
+            decision,
+            ..
+        } => {
+            let response = CommandExecutionRequestApprovalResponse {
+                decision: decision.clone(),
+            };
+            resolve_approval(
+                outgoing,
+                PendingApprovalKind::CommandExecution,
+                thread_id,
+                item_id,
+                response,
+            )
+            .await
+        }
+        RuntimeCommand::FileChangeApproval {
+            thread_id,
+            item_id,
+            decision,
+            ..
+        } => {
+            let response = FileChangeRequestApprovalResponse {
+                decision: decision.clone(),
+            };
+            resolve_approval(
+                outgoing,
+                PendingApprovalKind::FileChange,
+                thread_id,
+                item_id,
+                response,
+            )
+            .await
+        }
+        RuntimeCommand::SendMessage { thread_id, text } => {
+            let thread = load_thread(thread_manager, thread_id).await?;
+            submit(
+                &thread,
+                Op::UserInput {
+                    items: vec![UserInput::Text {
+                        text: text.clone(),
+                        text_elements: Vec::new(),
+                    }],
+                    final_output_json_schema: None,
+                },
+            )
+            .await
+        }
+    }
+}
+
+/// Answers the pending approval server request exactly as if a connected
+/// client had responded, so the usual completion path runs.
+async fn resolve_approval<T: serde::Serialize>(
+    outgoing: &OutgoingMessageSender,
+    kind: PendingApprovalKind,
+    thread_id: &str,
+    item_id: &str,
+    response: T,
+) -> Result<(), String> {
+    let response = serde_json::to_value(response).map_err(|err| err.to_string())?;
+    if outgoing
+        .resolve_pending_approval(kind, thread_id, item_id, response)
+        .await
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "no pending approval for item `{item_id}` in thread `{thread_id}`"
+        ))
+    }
+}
+
+async fn load_thread(
+    thread_manager: &ThreadManager,
+    thread_id: &str,
+) -> Result<Arc<CodexThread>, String> {
+    let thread_id =
+        ThreadId::from_string(thread_id).map_err(|err| format!("invalid thread id: {err}"))?;
+    thread_manager
+        .get_thread(thread_id)
+        .await
+        .map_err(|_| format!("thread not found: {thread_id}"))
+}
+
+async fn submit(thread: &CodexThread, op: Op) -> Result<(), String> {
+    thread
+        .submit(op)
+        .await
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
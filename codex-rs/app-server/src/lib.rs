@@ -37,6 +37,7 @@ use codex_app_server_protocol::JSONRPCMessage;
 use codex_app_server_protocol::TextPosition as AppTextPosition;
 use codex_app_server_protocol::TextRange as AppTextRange;
 use codex_codexd::producer::CodexdProducerClient;
+use codex_codexd::producer::RuntimeCommandRequest;
 use codex_codexd::producer::RuntimeMetadata;
 use codex_core::ExecPolicyError;
 use codex_core::check_execpolicy_for_warnings;
@@ -57,6 +58,7 @@ use tracing_subscriber::util::SubscriberInitExt;
 
 mod bespoke_event_handling;
 mod codex_message_processor;
+mod codexd_commands;
 mod config_api;
 mod dynamic_tools;
 mod error_code;
@@ -65,6 +67,7 @@ mod fuzzy_file_search;
 mod message_processor;
 mod models;
 mod outgoing_message;
+mod secrets_api;
 mod transport;
 
 pub use crate::transport::AppServerTransport;
@@ -392,7 +395,8 @@ pub async fn run_main_with_transport(
     }
 
     let processor_handle = tokio::spawn({
-        let codexd_producer = create_codexd_producer(&config, "appServer");
+        let (codexd_producer, codexd_commands) =
+            create_codexd_producer(&config, "appServer").unzip();
         let outgoing_message_sender = Arc::new(OutgoingMessageSender::new_with_codexd(
             outgoing_tx,
             codexd_producer,
@@ -400,7 +404,7 @@ pub async fn run_main_with_transport(
         let cli_overrides: Vec<(String, TomlValue)> = cli_kv_overrides.clone();
         let loader_overrides = loader_overrides_for_config_api;
         let mut processor = MessageProcessor::new(MessageProcessorArgs {
-            outgoing: outgoing_message_sender,
+            outgoing: Arc::clone(&outgoing_message_sender),
             codex_linux_sandbox_exe,
             config: Arc::new(config),
             cli_overrides,
@@ -411,6 +415,7 @@ pub async fn run_main_with_transport(
             auth_manager: None,
             thread_manager: None,
         });
+        spawn_codexd_command_loop(codexd_commands, &processor, outgoing_message_sender);
         let mut thread_created_rx = processor.thread_created_receiver();
         let mut connections = HashMap::<ConnectionId, ConnectionState>::new();
         async move {
@@ -536,13 +541,14 @@ fn spawn_embedded_processor(
     let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
 
     let processor_handle = tokio::spawn({
-        let codexd_producer = create_codexd_producer(config.as_ref(), "appServer");
+        let (codexd_producer, codexd_commands) =
+            create_codexd_producer(config.as_ref(), "appServer").unzip();
         let outgoing_message_sender = Arc::new(OutgoingMessageSender::new_with_codexd(
             outgoing_tx,
             codexd_producer,
         ));
         let mut processor = MessageProcessor::new(MessageProcessorArgs {
-            outgoing: outgoing_message_sender,
+            outgoing: Arc::clone(&outgoing_message_sender),
             codex_linux_sandbox_exe,
             config,
             cli_overrides,
@@ -553,6 +559,7 @@ fn spawn_embedded_processor(
             auth_manager: Some(auth_manager),
             thread_manager: Some(thread_manager),
         });
+        spawn_codexd_command_loop(codexd_commands, &processor, outgoing_message_sender);
         let mut thread_created_rx = processor.thread_created_receiver();
         let mut connections = HashMap::<ConnectionId, ConnectionState>::new();
         async move {
@@ -630,7 +637,10 @@ fn spawn_embedded_processor(
     (shutdown_tx, processor_handle)
 }
 
-fn create_codexd_producer(config: &Config, session_source: &str) -> Option<CodexdProducerClient> {
+fn create_codexd_producer(
+    config: &Config,
+    session_source: &str,
+) -> Option<(CodexdProducerClient, mpsc::Receiver<RuntimeCommandRequest>)> {
     #[cfg(unix)]
     {
         let runtime_metadata = RuntimeMetadata {
@@ -640,7 +650,7 @@ fn create_codexd_producer(config: &Config, session_source: &str) -> Option<Codex
             cwd: Some(config.cwd.to_string_lossy().into_owned()),
             display_name: Some("codex-app-server".to_string()),
         };
-        Some(CodexdProducerClient::spawn(
+        Some(CodexdProducerClient::spawn_with_commands(
             config.codex_home.as_path(),
             runtime_metadata,
         ))
@@ -653,6 +663,20 @@ fn create_codexd_producer(config: &Config, session_source: &str) -> Option<Codex
     }
 }
 
+fn spawn_codexd_command_loop(
+    commands: Option<mpsc::Receiver<RuntimeCommandRequest>>,
+    processor: &MessageProcessor,
+    outgoing: Arc<OutgoingMessageSender>,
+) {
+    if let Some(commands) = commands {
+        tokio::spawn(codexd_commands::run_codexd_command_loop(
+            commands,
+            processor.thread_manager(),
+            outgoing,
+        ));
+    }
+}
+
 pub async fn start_embedded_websocket_server(
     codex_linux_sandbox_exe: Option<PathBuf>,
     config: Arc<Config>,
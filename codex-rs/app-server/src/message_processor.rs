@@ -8,6 +8,7 @@ use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::ConnectionId;
 use crate::outgoing_message::ConnectionRequestId;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::secrets_api::SecretsApi;
 use async_trait::async_trait;
 use codex_app_server_protocol::ChatgptAuthTokensRefreshParams;
 use codex_app_server_protocol::ChatgptAuthTokensRefreshReason;
@@ -108,6 +109,7 @@ pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     codex_message_processor: CodexMessageProcessor,
     config_api: ConfigApi,
+    secrets_api: SecretsApi,
     config: Arc<Config>,
     config_warnings: Arc<Vec<ConfigWarningNotification>>,
 }
@@ -181,11 +183,13 @@ impl MessageProcessor {
             loader_overrides,
             cloud_requirements,
         );
+        let secrets_api = SecretsApi::new(&config);
 
         Self {
             outgoing,
             codex_message_processor,
             config_api,
+            secrets_api,
             config,
             config_warnings: Arc::new(config_warnings),
         }
@@ -371,6 +375,38 @@ impl MessageProcessor {
                 })
                 .await;
             }
+            ClientRequest::SecretsList { request_id, params } => {
+                let request_id = ConnectionRequestId {
+                    connection_id,
+                    request_id,
+                };
+                let result = self.secrets_api.list(params).await;
+                self.send_result(request_id, result).await;
+            }
+            ClientRequest::SecretsGet { request_id, params } => {
+                let request_id = ConnectionRequestId {
+                    connection_id,
+                    request_id,
+                };
+                let result = self.secrets_api.get(params).await;
+                self.send_result(request_id, result).await;
+            }
+            ClientRequest::SecretsSet { request_id, params } => {
+                let request_id = ConnectionRequestId {
+                    connection_id,
+                    request_id,
+                };
+                let result = self.secrets_api.set(params).await;
+                self.send_result(request_id, result).await;
+            }
+            ClientRequest::SecretsRemove { request_id, params } => {
+                let request_id = ConnectionRequestId {
+                    connection_id,
+                    request_id,
+                };
+                let result = self.secrets_api.remove(params).await;
+                self.send_result(request_id, result).await;
+            }
             other => {
                 self.codex_message_processor
                     .process_request(connection_id, other)
@@ -389,6 +425,10 @@ impl MessageProcessor {
         self.codex_message_processor.thread_created_receiver()
     }
 
+    pub(crate) fn thread_manager(&self) -> Arc<ThreadManager> {
+        self.codex_message_processor.thread_manager()
+    }
+
     pub(crate) async fn try_attach_thread_listener(&mut self, thread_id: ThreadId) {
         self.codex_message_processor
             .try_attach_thread_listener(thread_id)
@@ -437,6 +477,17 @@ impl MessageProcessor {
         }
     }
 
+    async fn send_result<T: serde::Serialize>(
+        &self,
+        request_id: ConnectionRequestId,
+        result: Result<T, JSONRPCErrorError>,
+    ) {
+        match result {
+            Ok(response) => self.outgoing.send_response(request_id, response).await,
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
     async fn handle_config_requirements_read(&self, request_id: ConnectionRequestId) {
         match self.config_api.config_requirements_read().await {
             Ok(response) => self.outgoing.send_response(request_id, response).await,
@@ -8,7 +8,9 @@ use codex_app_server_protocol::Result;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::ServerRequestPayload;
+use codex_app_server_protocol::ServerRequestResolvedNotification;
 use codex_codexd::producer::CodexdProducerClient;
+use codex_codexd::protocol::PendingApprovalKind;
 use serde::Serialize;
 use tokio::sync::Mutex;
 use tokio::sync::mpsc;
@@ -47,6 +49,10 @@ pub(crate) struct OutgoingMessageSender {
     next_server_request_id: AtomicI64,
     sender: mpsc::Sender<OutgoingEnvelope>,
     request_id_to_callback: Mutex<HashMap<RequestId, oneshot::Sender<Result>>>,
+    /// Pending v2 approval and user-input requests keyed by
+    /// `(thread_id, item_id)` so codexd commands can answer them without
+    /// knowing the JSON-RPC request id.
+    pending_approvals: Mutex<HashMap<(String, String), (RequestId, PendingApprovalKind)>>,
     codexd_producer: Option<CodexdProducerClient>,
 }
 
@@ -64,6 +70,7 @@ impl OutgoingMessageSender {
             next_server_request_id: AtomicI64::new(0),
             sender,
             request_id_to_callback: Mutex::new(HashMap::new()),
+            pending_approvals: Mutex::new(HashMap::new()),
             codexd_producer,
         }
     }
@@ -83,6 +90,13 @@ impl OutgoingMessageSender {
         let id = RequestId::Integer(self.next_server_request_id.fetch_add(1, Ordering::Relaxed));
         let outgoing_message_id = id.clone();
         let (tx_approve, rx_approve) = oneshot::channel();
+        if let Some((key, kind)) = approval_key(&request) {
+            let mut pending_approvals = self.pending_approvals.lock().await;
+            pending_approvals.insert(key, (id.clone(), kind));
+        }
+        if let Some(codexd_producer) = &self.codexd_producer {
+            codexd_producer.publish_server_request(&request).await;
+        }
         {
             let mut request_id_to_callback = self.request_id_to_callback.lock().await;
             request_id_to_callback.insert(id, tx_approve);
@@ -105,6 +119,7 @@ impl OutgoingMessageSender {
     }
 
     pub(crate) async fn notify_client_response(&self, id: RequestId, result: Result) {
+        self.forget_pending_approval(&id).await;
         let entry = {
             let mut request_id_to_callback = self.request_id_to_callback.lock().await;
             request_id_to_callback.remove_entry(&id)
@@ -123,6 +138,7 @@ impl OutgoingMessageSender {
     }
 
     pub(crate) async fn notify_client_error(&self, id: RequestId, error: JSONRPCErrorError) {
+        self.forget_pending_approval(&id).await;
         let entry = {
             let mut request_id_to_callback = self.request_id_to_callback.lock().await;
             request_id_to_callback.remove_entry(&id)
@@ -139,6 +155,7 @@ impl OutgoingMessageSender {
     }
 
     pub(crate) async fn cancel_request(&self, id: &RequestId) -> bool {
+        self.forget_pending_approval(id).await;
         let entry = {
             let mut request_id_to_callback = self.request_id_to_callback.lock().await;
             request_id_to_callback.remove_entry(id)
@@ -146,6 +163,71 @@ impl OutgoingMessageSender {
         entry.is_some()
     }
 
+    /// Resolves the pending `kind` request for `item_id` with `result`, as if
+    /// the client had answered it, and tells clients to close its prompt with
+    /// `serverRequest/resolved`. Returns `false` when nothing is pending.
+    pub(crate) async fn resolve_pending_approval(
+        &self,
+        kind: PendingApprovalKind,
+        thread_id: &str,
+        item_id: &str,
+        result: Result,
+    ) -> bool {
+        let key = (thread_id.to_string(), item_id.to_string());
+        let request_id = {
+            let mut pending_approvals = self.pending_approvals.lock().await;
+            match pending_approvals.get(&key) {
+                Some((_, pending_kind)) if *pending_kind == kind => pending_approvals
+                    .remove(&key)
+                    .map(|(request_id, _)| request_id),
+                _ => None,
+            }
+        };
+        let Some(request_id) = request_id else {
+            return false;
+        };
+        self.send_server_notification(ServerNotification::ServerRequestResolved(
+            ServerRequestResolvedNotification {
+                thread_id: key.0.clone(),
+                request_id: request_id.clone(),
+            },
+        ))
+        .await;
+        self.publish_approval_resolved(key).await;
+
+        let callback = {
+            let mut request_id_to_callback = self.request_id_to_callback.lock().await;
+            request_id_to_callback.remove(&request_id)
+        };
+        callback.is_some_and(|callback| callback.send(result).is_ok())
+    }
+
+    async fn forget_pending_approval(&self, id: &RequestId) {
+        let resolved: Vec<(String, String)> = {
+            let mut pending_approvals = self.pending_approvals.lock().await;
+            let keys = pending_approvals
+                .iter()
+                .filter(|(_, (pending_id, _))| pending_id == id)
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>();
+            for key in &keys {
+                pending_approvals.remove(key);
+            }
+            keys
+        };
+        for key in resolved {
+            self.publish_approval_resolved(key).await;
+        }
+    }
+
+    async fn publish_approval_resolved(&self, (thread_id, item_id): (String, String)) {
+        if let Some(codexd_producer) = &self.codexd_producer {
+            codexd_producer
+                .publish_approval_resolved(thread_id, item_id)
+                .await;
+        }
+    }
+
     pub(crate) async fn send_response<T: Serialize>(
         &self,
         request_id: ConnectionRequestId,
@@ -239,6 +321,24 @@ impl OutgoingMessageSender {
     }
 }
 
+fn approval_key(request: &ServerRequestPayload) -> Option<((String, String), PendingApprovalKind)> {
+    match request {
+        ServerRequestPayload::CommandExecutionRequestApproval(params) => Some((
+            (params.thread_id.clone(), params.item_id.clone()),
+            PendingApprovalKind::CommandExecution,
+        )),
+        ServerRequestPayload::FileChangeRequestApproval(params) => Some((
+            (params.thread_id.clone(), params.item_id.clone()),
+            PendingApprovalKind::FileChange,
+        )),
+        ServerRequestPayload::ToolRequestUserInput(params) => Some((
+            (params.thread_id.clone(), params.item_id.clone()),
+            PendingApprovalKind::UserInput,
+        )),
+        _ => None,
+    }
+}
+
 fn should_publish_to_codexd(notification: &ServerNotification) -> bool {
     matches!(
         notification,
@@ -474,6 +574,95 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn resolve_pending_approval_answers_matching_request() {
+        let (tx, mut rx) = mpsc::channel::<OutgoingEnvelope>(4);
+        let outgoing = OutgoingMessageSender::new(tx);
+        let params = codex_app_server_protocol::CommandExecutionRequestApprovalParams {
+            thread_id: "thread-1".to_string(),
+            turn_id: "turn-1".to_string(),
+            item_id: "call-1".to_string(),
+            reason: None,
+            command: None,
+            cwd: None,
+            command_actions: None,
+            proposed_execpolicy_amendment: None,
+        };
+        let (request_id, receiver) = outgoing
+            .send_request_with_id(ServerRequestPayload::CommandExecutionRequestApproval(
+                params,
+            ))
+            .await;
+        assert!(matches!(
+            rx.recv().await,
+            Some(OutgoingEnvelope::Broadcast {
+                message: OutgoingMessage::Request(_)
+            })
+        ));
+
+        assert!(
+            !outgoing
+                .resolve_pending_approval(
+                    PendingApprovalKind::CommandExecution,
+                    "thread-1",
+                    "call-2",
+                    json!({})
+                )
+                .await
+        );
+        assert!(
+            !outgoing
+                .resolve_pending_approval(
+                    PendingApprovalKind::FileChange,
+                    "thread-1",
+                    "call-1",
+                    json!({})
+                )
+                .await
+        );
+        assert!(
+            outgoing
+                .resolve_pending_approval(
+                    PendingApprovalKind::CommandExecution,
+                    "thread-1",
+                    "call-1",
+                    json!({ "decision": "accept" })
+                )
+                .await
+        );
+        assert_eq!(
+            receiver.await.expect("callback should be answered"),
+            json!({ "decision": "accept" })
+        );
+        // The client that was asked is told to close its prompt.
+        match rx.recv().await {
+            Some(OutgoingEnvelope::Broadcast {
+                message:
+                    OutgoingMessage::AppServerNotification(ServerNotification::ServerRequestResolved(
+                        notification,
+                    )),
+            }) => assert_eq!(
+                notification,
+                ServerRequestResolvedNotification {
+                    thread_id: "thread-1".to_string(),
+                    request_id,
+                }
+            ),
+            other => panic!("expected serverRequest/resolved, got: {other:?}"),
+        }
+        assert!(rx.try_recv().is_err());
+        assert!(
+            !outgoing
+                .resolve_pending_approval(
+                    PendingApprovalKind::CommandExecution,
+                    "thread-1",
+                    "call-1",
+                    json!({})
+                )
+                .await
+        );
+    }
+
     #[tokio::test]
     async fn send_error_routes_to_target_connection() {
         let (tx, mut rx) = mpsc::channel::<OutgoingEnvelope>(4);
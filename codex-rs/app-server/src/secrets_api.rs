@@ -0,0 +1,394 @@
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::SecretMetadata;
+use codex_app_server_protocol::SecretScope as ApiSecretScope;
+use codex_app_server_protocol::SecretsGetParams;
+use codex_app_server_protocol::SecretsGetResponse;
+use codex_app_server_protocol::SecretsListParams;
+use codex_app_server_protocol::SecretsListResponse;
+use codex_app_server_protocol::SecretsRemoveParams;
+use codex_app_server_protocol::SecretsRemoveResponse;
+use codex_app_server_protocol::SecretsSetParams;
+use codex_app_server_protocol::SecretsSetResponse;
+use codex_core::config::Config;
+use codex_secrets::SecretListEntry;
+use codex_secrets::SecretName;
+use codex_secrets::SecretScope;
+use codex_secrets::SecretsManager;
+use std::path::PathBuf;
+
+/// Backs the `secrets/*` methods. Values are only ever returned by
+/// `secrets/get` with `reveal: true`.
+#[derive(Clone)]
+pub(crate) struct SecretsApi {
+    manager: SecretsManager,
+    default_cwd: PathBuf,
+}
+
+impl SecretsApi {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self::with_manager(
+            SecretsManager::new(config.codex_home.clone(), config.secrets.backend),
+            config.cwd.clone(),
+        )
+    }
+
+    pub(crate) fn with_manager(manager: SecretsManager, default_cwd: PathBuf) -> Self {
+        Self {
+            manager,
+            default_cwd,
+        }
+    }
+
+    pub(crate) async fn list(
+        &self,
+        params: SecretsListParams,
+    ) -> Result<SecretsListResponse, JSONRPCErrorError> {
+        let SecretsListParams {
+            scope,
+            environment_id,
+            cwd,
+            all_environments,
+        } = params;
+        let environment_scope = if all_environments {
+            None
+        } else {
+            Some(self.environment_scope(environment_id, cwd)?)
+        };
+        let filters = match scope {
+            Some(ApiSecretScope::Global) => vec![Some(SecretScope::Global)],
+            Some(ApiSecretScope::Environment) => vec![environment_scope],
+            None if all_environments => vec![None],
+            None => vec![Some(SecretScope::Global), environment_scope],
+        };
+
+        let manager = self.manager.clone();
+        let mut entries = run_blocking(move || {
+            let mut entries = Vec::new();
+            for filter in filters {
+                entries.extend(manager.list(filter.as_ref())?);
+            }
+            Ok(entries)
+        })
+        .await?;
+        entries.sort_by(|a, b| {
+            (a.scope.environment_id(), a.name.as_str())
+                .cmp(&(b.scope.environment_id(), b.name.as_str()))
+        });
+        entries.dedup();
+
+        Ok(SecretsListResponse {
+            data: entries.into_iter().map(metadata_from_entry).collect(),
+        })
+    }
+
+    pub(crate) async fn get(
+        &self,
+        params: SecretsGetParams,
+    ) -> Result<SecretsGetResponse, JSONRPCErrorError> {
+        let SecretsGetParams {
+            name,
+            scope,
+            environment_id,
+            cwd,
+            reveal,
+        } = params;
+        let name = parse_name(&name)?;
+        let scope = self.resolve_scope(scope, environment_id, cwd)?;
+
+        let manager = self.manager.clone();
+        let entry = SecretListEntry {
+            scope: scope.clone(),
+            name: name.clone(),
+        };
+        let value = run_blocking(move || manager.get(&scope, &name)).await?;
+        let Some(value) = value else {
+            return Ok(SecretsGetResponse {
+                secret: None,
+                value: None,
+            });
+        };
+        Ok(SecretsGetResponse {
+            secret: Some(metadata_from_entry(entry)),
+            value: reveal.then_some(value),
+        })
+    }
+
+    pub(crate) async fn set(
+        &self,
+        params: SecretsSetParams,
+    ) -> Result<SecretsSetResponse, JSONRPCErrorError> {
+        let SecretsSetParams {
+            name,
+            value,
+            scope,
+            environment_id,
+            cwd,
+        } = params;
+        let name = parse_name(&name)?;
+        if value.is_empty() {
+            return Err(invalid_request(
+                "secret value must not be empty".to_string(),
+            ));
+        }
+        let scope = self.resolve_scope(scope, environment_id, cwd)?;
+
+        let manager = self.manager.clone();
+        let entry = SecretListEntry {
+            scope: scope.clone(),
+            name: name.clone(),
+        };
+        run_blocking(move || manager.set(&scope, &name, &value)).await?;
+        Ok(SecretsSetResponse {
+            secret: metadata_from_entry(entry),
+        })
+    }
+
+    pub(crate) async fn remove(
+        &self,
+        params: SecretsRemoveParams,
+    ) -> Result<SecretsRemoveResponse, JSONRPCErrorError> {
+        let SecretsRemoveParams {
+            name,
+            scope,
+            environment_id,
+            cwd,
+        } = params;
+        let name = parse_name(&name)?;
+        let scope = self.resolve_scope(scope, environment_id, cwd)?;
+
+        let manager = self.manager.clone();
+        let removed = run_blocking(move || manager.delete(&scope, &name)).await?;
+        Ok(SecretsRemoveResponse { removed })
+    }
+
+    fn resolve_scope(
+        &self,
+        scope: Option<ApiSecretScope>,
+        environment_id: Option<String>,
+        cwd: Option<String>,
+    ) -> Result<SecretScope, JSONRPCErrorError> {
+        match scope {
+            Some(ApiSecretScope::Global) => Ok(SecretScope::Global),
+            Some(ApiSecretScope::Environment) | None => self.environment_scope(environment_id, cwd),
+        }
+    }
+
+    fn environment_scope(
+        &self,
+        environment_id: Option<String>,
+        cwd: Option<String>,
+    ) -> Result<SecretScope, JSONRPCErrorError> {
+        let scope = match environment_id {
+            Some(environment_id) => SecretScope::environment(environment_id),
+            None => {
+                let cwd = cwd
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| self.default_cwd.clone());
+                SecretScope::for_cwd(&cwd)
+            }
+        };
+        scope.map_err(|err| invalid_request(err.to_string()))
+    }
+}
+
+fn parse_name(name: &str) -> Result<SecretName, JSONRPCErrorError> {
+    SecretName::new(name).map_err(|err| invalid_request(err.to_string()))
+}
+
+fn metadata_from_entry(entry: SecretListEntry) -> SecretMetadata {
+    let SecretListEntry { scope, name } = entry;
+    let (scope, environment_id) = match scope {
+        SecretScope::Global => (ApiSecretScope::Global, None),
+        SecretScope::Environment(environment_id) => {
+            (ApiSecretScope::Environment, Some(environment_id))
+        }
+    };
+    SecretMetadata {
+        name: name.as_str().to_string(),
+        scope,
+        environment_id,
+    }
+}
+
+/// Secrets backends decrypt on every call, which is too slow for the async
+/// workers.
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> Result<T, JSONRPCErrorError> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(err)) => Err(internal_error(format!("secrets backend error: {err:#}"))),
+        Err(err) => Err(internal_error(format!("secrets task failed: {err}"))),
+    }
+}
+
+fn invalid_request(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INVALID_REQUEST_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+fn internal_error(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_keyring_store::tests::MockKeyringStore;
+    use codex_secrets::SecretsBackendKind;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn api(codex_home: &TempDir, cwd: &TempDir) -> SecretsApi {
+        SecretsApi::with_manager(
+            SecretsManager::new_with_keyring_store(
+                codex_home.path().to_path_buf(),
+                SecretsBackendKind::Local,
+                Arc::new(MockKeyringStore::default()),
+            ),
+            cwd.path().to_path_buf(),
+        )
+    }
+
+    #[tokio::test]
+    async fn get_only_returns_value_when_revealed() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cwd = TempDir::new()?;
+        let api = api(&codex_home, &cwd);
+        let environment_id = codex_secrets::environment_id_from_cwd(cwd.path());
+
+        let set = api
+            .set(SecretsSetParams {
+                name: "NPM_TOKEN".to_string(),
+                value: "npm-secret".to_string(),
+                scope: None,
+                environment_id: None,
+                cwd: None,
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err.message))?;
+        let expected = SecretMetadata {
+            name: "NPM_TOKEN".to_string(),
+            scope: ApiSecretScope::Environment,
+            environment_id: Some(environment_id),
+        };
+        assert_eq!(set.secret, expected);
+
+        let get_params = SecretsGetParams {
+            name: "NPM_TOKEN".to_string(),
+            scope: None,
+            environment_id: None,
+            cwd: None,
+            reveal: false,
+        };
+        let hidden = api
+            .get(get_params.clone())
+            .await
+            .map_err(|err| anyhow::anyhow!(err.message))?;
+        assert_eq!(hidden.secret, Some(expected.clone()));
+        assert_eq!(hidden.value, None);
+
+        let revealed = api
+            .get(SecretsGetParams {
+                reveal: true,
+                ..get_params
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err.message))?;
+        assert_eq!(revealed.value, Some("npm-secret".to_string()));
+        assert!(!format!("{revealed:?}").contains("npm-secret"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_defaults_to_global_and_current_environment() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cwd = TempDir::new()?;
+        let api = api(&codex_home, &cwd);
+        for (name, scope, environment_id) in [
+            ("GLOBAL_TOKEN", ApiSecretScope::Global, None),
+            ("REPO_TOKEN", ApiSecretScope::Environment, None),
+            (
+                "OTHER_TOKEN",
+                ApiSecretScope::Environment,
+                Some("other-repo".to_string()),
+            ),
+        ] {
+            api.set(SecretsSetParams {
+                name: name.to_string(),
+                value: "value".to_string(),
+                scope: Some(scope),
+                environment_id,
+                cwd: None,
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err.message))?;
+        }
+
+        let listed = api
+            .list(SecretsListParams {
+                scope: None,
+                environment_id: None,
+                cwd: None,
+                all_environments: false,
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err.message))?;
+        let names: Vec<&str> = listed.data.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["GLOBAL_TOKEN", "REPO_TOKEN"]);
+
+        let all = api
+            .list(SecretsListParams {
+                scope: None,
+                environment_id: None,
+                cwd: None,
+                all_environments: true,
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err.message))?;
+        assert_eq!(all.data.len(), 3);
+
+        let removed = api
+            .remove(SecretsRemoveParams {
+                name: "GLOBAL_TOKEN".to_string(),
+                scope: Some(ApiSecretScope::Global),
+                environment_id: None,
+                cwd: None,
+            })
+            .await
+            .map_err(|err| anyhow::anyhow!(err.message))?;
+        assert!(removed.removed);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invalid_names_are_rejected() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cwd = TempDir::new()?;
+        let api = api(&codex_home, &cwd);
+
+        let Err(err) = api
+            .remove(SecretsRemoveParams {
+                name: "not a name".to_string(),
+                scope: None,
+                environment_id: None,
+                cwd: None,
+            })
+            .await
+        else {
+            panic!("expected invalid name to be rejected");
+        };
+        assert_eq!(err.code, INVALID_REQUEST_ERROR_CODE);
+        Ok(())
+    }
+}
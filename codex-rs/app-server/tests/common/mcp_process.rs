@@ -39,6 +39,12 @@ use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::ListConversationsParams;
 use codex_app_server_protocol::LoginAccountParams;
 use codex_app_server_protocol::LoginApiKeyParams;
+use codex_app_server_protocol::MemoryDeleteParams;
+use codex_app_server_protocol::MemoryListParams;
+use codex_app_server_protocol::MemoryPinSetParams;
+use codex_app_server_protocol::MemoryReadParams;
+use codex_app_server_protocol::MemoryUpdateParams;
+use codex_app_server_protocol::NetworkLogListParams;
 use codex_app_server_protocol::MockExperimentalMethodParams;
 use codex_app_server_protocol::ModelListParams;
 use codex_app_server_protocol::NewConversationParams;
@@ -52,13 +58,17 @@ use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadCompactStartParams;
+use codex_app_server_protocol::ThreadFolderSetParams;
 use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadLoadedListParams;
+use codex_app_server_protocol::ThreadPinSetParams;
 use codex_app_server_protocol::ThreadReadParams;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadRollbackParams;
+use codex_app_server_protocol::ThreadSearchParams;
 use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadTagsSetParams;
 use codex_app_server_protocol::ThreadUnarchiveParams;
 use codex_app_server_protocol::TurnActiveParams;
 use codex_app_server_protocol::TurnInterruptParams;
@@ -448,6 +458,96 @@ impl McpProcess {
         self.send_request("thread/list", params).await
     }
 
+    /// Send a `thread/search` JSON-RPC request.
+    pub async fn send_thread_search_request(
+        &mut self,
+        params: ThreadSearchParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/search", params).await
+    }
+
+    /// Send a `thread/tags/set` JSON-RPC request.
+    pub async fn send_thread_tags_set_request(
+        &mut self,
+        params: ThreadTagsSetParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/tags/set", params).await
+    }
+
+    /// Send a `thread/pin/set` JSON-RPC request.
+    pub async fn send_thread_pin_set_request(
+        &mut self,
+        params: ThreadPinSetParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/pin/set", params).await
+    }
+
+    /// Send a `memory/list` JSON-RPC request.
+    pub async fn send_memory_list_request(
+        &mut self,
+        params: MemoryListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("memory/list", params).await
+    }
+
+    /// Send a `memory/read` JSON-RPC request.
+    pub async fn send_memory_read_request(
+        &mut self,
+        params: MemoryReadParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("memory/read", params).await
+    }
+
+    /// Send a `memory/update` JSON-RPC request.
+    pub async fn send_memory_update_request(
+        &mut self,
+        params: MemoryUpdateParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("memory/update", params).await
+    }
+
+    /// Send a `memory/delete` JSON-RPC request.
+    pub async fn send_memory_delete_request(
+        &mut self,
+        params: MemoryDeleteParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("memory/delete", params).await
+    }
+
+    /// Send a `memory/pin/set` JSON-RPC request.
+    pub async fn send_memory_pin_set_request(
+        &mut self,
+        params: MemoryPinSetParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("memory/pin/set", params).await
+    }
+
+    /// Send a `network/log/list` JSON-RPC request.
+    pub async fn send_network_log_list_request(
+        &mut self,
+        params: NetworkLogListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("network/log/list", params).await
+    }
+
+    /// Send a `thread/folder/set` JSON-RPC request.
+    pub async fn send_thread_folder_set_request(
+        &mut self,
+        params: ThreadFolderSetParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/folder/set", params).await
+    }
+
     /// Send a `thread/loaded/list` JSON-RPC request.
     pub async fn send_thread_loaded_list_request(
         &mut self,
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::MemoryDeleteParams;
+use codex_app_server_protocol::MemoryDeleteResponse;
+use codex_app_server_protocol::MemoryListParams;
+use codex_app_server_protocol::MemoryListResponse;
+use codex_app_server_protocol::MemoryPinSetParams;
+use codex_app_server_protocol::MemoryPinSetResponse;
+use codex_app_server_protocol::MemoryReadParams;
+use codex_app_server_protocol::MemoryUpdateParams;
+use codex_app_server_protocol::MemoryUpdateResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_core::state_db::open_if_present;
+use codex_protocol::ThreadId;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn memories_can_be_listed_edited_pinned_and_deleted() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "fix the deploy pipeline",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    // Starting a thread initializes the state database, which backfills the
+    // existing rollout.
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let _: ThreadStartResponse = read_response(&mut mcp, start_id).await?;
+    let state_db = open_if_present(codex_home.path(), "mock_provider")
+        .await
+        .expect("state database exists");
+    state_db
+        .upsert_thread_memory(
+            ThreadId::from_string(&thread_id)?,
+            "fixed the deploy pipeline",
+            "deploys run from `ci/deploy.sh`",
+        )
+        .await?;
+
+    let request_id = mcp
+        .send_memory_list_request(MemoryListParams {
+            cwd: Some("/".to_string()),
+        })
+        .await?;
+    let MemoryListResponse { data } = read_response(&mut mcp, request_id).await?;
+    assert_eq!(
+        data.iter()
+            .map(|memory| (memory.thread_id.as_str(), memory.memory_summary.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(thread_id.as_str(), "deploys run from `ci/deploy.sh`")]
+    );
+
+    let request_id = mcp
+        .send_memory_update_request(MemoryUpdateParams {
+            thread_id: thread_id.clone(),
+            memory_summary: "deploy with `just deploy`".to_string(),
+        })
+        .await?;
+    let MemoryUpdateResponse { memory } = read_response(&mut mcp, request_id).await?;
+    assert_eq!(memory.trace_summary, "fixed the deploy pipeline");
+    assert_eq!(memory.memory_summary, "deploy with `just deploy`");
+
+    let request_id = mcp
+        .send_memory_pin_set_request(MemoryPinSetParams {
+            thread_id: thread_id.clone(),
+            pinned: true,
+        })
+        .await?;
+    let MemoryPinSetResponse { memory } = read_response(&mut mcp, request_id).await?;
+    assert!(memory.pinned);
+    let recalled = state_db
+        .get_last_n_thread_memories_for_cwd(Path::new("/"), 1)
+        .await?;
+    assert_eq!(recalled.len(), 1);
+    assert!(recalled[0].pinned);
+
+    let request_id = mcp
+        .send_memory_delete_request(MemoryDeleteParams {
+            thread_id: thread_id.clone(),
+        })
+        .await?;
+    let _: MemoryDeleteResponse = read_response(&mut mcp, request_id).await?;
+
+    let request_id = mcp
+        .send_memory_read_request(MemoryReadParams {
+            thread_id: thread_id.clone(),
+        })
+        .await?;
+    let error = read_error(&mut mcp, request_id).await?;
+    assert_eq!(
+        error.error.message,
+        format!("no memory for thread: {thread_id}")
+    );
+
+    Ok(())
+}
+
+async fn read_response<T: serde::de::DeserializeOwned>(
+    mcp: &mut McpProcess,
+    request_id: i64,
+) -> Result<T> {
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<T>(resp)
+}
+
+async fn read_error(mcp: &mut McpProcess, request_id: i64) -> Result<JSONRPCError> {
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await?
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        r#"model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+[features]
+sqlite = true
+"#,
+    )
+}
@@ -9,7 +9,9 @@ mod dynamic_tools;
 mod experimental_api;
 mod experimental_feature_list;
 mod initialize;
+mod memory;
 mod model_list;
+mod network_log;
 mod output_schema;
 mod plan_item;
 mod rate_limits;
@@ -19,9 +21,11 @@ mod thread_archive;
 mod thread_fork;
 mod thread_list;
 mod thread_loaded_list;
+mod thread_organization;
 mod thread_read;
 mod thread_resume;
 mod thread_rollback;
+mod thread_search;
 mod thread_start;
 mod thread_unarchive;
 mod turn_active;
@@ -0,0 +1,140 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::NetworkLogDecision;
+use codex_app_server_protocol::NetworkLogEntry;
+use codex_app_server_protocol::NetworkLogListParams;
+use codex_app_server_protocol::NetworkLogListResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_core::state_db::NetworkAuditDecision;
+use codex_core::state_db::NetworkAuditEntry;
+use codex_core::state_db::open_if_present;
+use codex_protocol::ThreadId;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn network_log_lists_recorded_requests() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    // Starting a thread initializes the state database.
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let _: ThreadStartResponse = read_response(&mut mcp, start_id).await?;
+    let state_db = open_if_present(codex_home.path(), "mock_provider")
+        .await
+        .expect("state database exists");
+
+    let thread_id = ThreadId::new();
+    for (timestamp, host, decision) in [
+        (1_700_000_000, "pypi.org", NetworkAuditDecision::Allow),
+        (1_700_000_100, "evil.example", NetworkAuditDecision::Deny),
+    ] {
+        state_db
+            .append_network_audit(&NetworkAuditEntry {
+                ts: DateTime::<Utc>::from_timestamp(timestamp, 0).expect("timestamp"),
+                thread_id: Some(thread_id),
+                turn_id: Some("1".to_string()),
+                call_id: Some("call_1".to_string()),
+                protocol: "http".to_string(),
+                host: host.to_string(),
+                method: Some("GET".to_string()),
+                decision,
+                reason: (decision == NetworkAuditDecision::Deny).then(|| "not_allowed".to_string()),
+                bytes_sent: Some(0),
+                bytes_received: None,
+            })
+            .await?;
+    }
+
+    let request_id = mcp
+        .send_network_log_list_request(NetworkLogListParams {
+            thread_id: Some(thread_id.to_string()),
+            host: None,
+            decision: None,
+            since: None,
+            limit: None,
+        })
+        .await?;
+    let NetworkLogListResponse { data } = read_response(&mut mcp, request_id).await?;
+    assert_eq!(
+        data.iter()
+            .map(|entry| (entry.host.as_str(), entry.decision))
+            .collect::<Vec<_>>(),
+        vec![
+            ("evil.example", NetworkLogDecision::Deny),
+            ("pypi.org", NetworkLogDecision::Allow),
+        ]
+    );
+
+    let request_id = mcp
+        .send_network_log_list_request(NetworkLogListParams {
+            thread_id: None,
+            host: None,
+            decision: Some(NetworkLogDecision::Deny),
+            since: None,
+            limit: Some(10),
+        })
+        .await?;
+    let NetworkLogListResponse { data } = read_response(&mut mcp, request_id).await?;
+    assert_eq!(
+        data,
+        vec![NetworkLogEntry {
+            timestamp: 1_700_000_100,
+            thread_id: Some(thread_id.to_string()),
+            turn_id: Some("1".to_string()),
+            call_id: Some("call_1".to_string()),
+            protocol: "http".to_string(),
+            host: "evil.example".to_string(),
+            method: Some("GET".to_string()),
+            decision: NetworkLogDecision::Deny,
+            reason: Some("not_allowed".to_string()),
+            bytes_sent: Some(0),
+            bytes_received: None,
+        }]
+    );
+
+    Ok(())
+}
+
+async fn read_response<T: serde::de::DeserializeOwned>(
+    mcp: &mut McpProcess,
+    request_id: i64,
+) -> Result<T> {
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<T>(resp)
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        r#"model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+[features]
+sqlite = true
+"#,
+    )
+}
@@ -66,6 +66,9 @@ async fn list_threads_with_sort(
             model_providers: providers,
             source_kinds,
             archived,
+            tags: None,
+            pinned: None,
+            folder: None,
         })
         .await?;
     let resp: JSONRPCResponse = timeout(
@@ -1106,6 +1109,9 @@ async fn thread_list_invalid_cursor_returns_error() -> Result<()> {
             model_providers: Some(vec!["mock_provider".to_string()]),
             source_kinds: None,
             archived: None,
+            tags: None,
+            pinned: None,
+            folder: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
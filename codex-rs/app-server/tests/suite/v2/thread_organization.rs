@@ -0,0 +1,252 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadFolderSetParams;
+use codex_app_server_protocol::ThreadFolderSetResponse;
+use codex_app_server_protocol::ThreadListParams;
+use codex_app_server_protocol::ThreadListResponse;
+use codex_app_server_protocol::ThreadPinSetParams;
+use codex_app_server_protocol::ThreadPinSetResponse;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::ThreadTagsSetParams;
+use codex_app_server_protocol::ThreadTagsSetResponse;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+fn list_params() -> ThreadListParams {
+    ThreadListParams {
+        cursor: None,
+        limit: None,
+        sort_key: None,
+        model_providers: Some(Vec::new()),
+        source_kinds: None,
+        archived: None,
+        tags: None,
+        pinned: None,
+        folder: None,
+    }
+}
+
+#[tokio::test]
+async fn thread_organization_round_trips_through_thread_list() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), true)?;
+    let infra_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "fix the deploy pipeline",
+        Some("mock_provider"),
+        None,
+    )?;
+    let docs_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-01T12-00-00",
+        "2025-01-01T12:00:00Z",
+        "write the onboarding guide",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    // Starting a thread initializes the state database, which backfills the
+    // existing rollouts.
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let _: ThreadStartResponse = to_response(start_resp)?;
+
+    let request_id = mcp
+        .send_thread_tags_set_request(ThreadTagsSetParams {
+            thread_id: infra_id.clone(),
+            tags: vec!["infra".to_string(), " ci ".to_string()],
+        })
+        .await?;
+    let _: ThreadTagsSetResponse = read_response(&mut mcp, request_id).await?;
+    let request_id = mcp
+        .send_thread_pin_set_request(ThreadPinSetParams {
+            thread_id: infra_id.clone(),
+            pinned: true,
+        })
+        .await?;
+    let _: ThreadPinSetResponse = read_response(&mut mcp, request_id).await?;
+    let request_id = mcp
+        .send_thread_folder_set_request(ThreadFolderSetParams {
+            thread_id: docs_id.clone(),
+            folder: Some("clients/acme/".to_string()),
+        })
+        .await?;
+    let _: ThreadFolderSetResponse = read_response(&mut mcp, request_id).await?;
+
+    let ThreadListResponse { data, .. } = list(&mut mcp, list_params()).await?;
+    let infra = data
+        .iter()
+        .find(|thread| thread.id == infra_id)
+        .expect("infra thread listed");
+    assert_eq!(infra.tags, vec!["ci".to_string(), "infra".to_string()]);
+    assert!(infra.pinned);
+    assert_eq!(infra.folder, None);
+    let docs = data
+        .iter()
+        .find(|thread| thread.id == docs_id)
+        .expect("docs thread listed");
+    assert_eq!(docs.tags, Vec::<String>::new());
+    assert!(!docs.pinned);
+    assert_eq!(docs.folder.as_deref(), Some("clients/acme"));
+
+    let pinned = list(
+        &mut mcp,
+        ThreadListParams {
+            pinned: Some(true),
+            ..list_params()
+        },
+    )
+    .await?;
+    assert_eq!(
+        pinned
+            .data
+            .iter()
+            .map(|thread| thread.id.as_str())
+            .collect::<Vec<_>>(),
+        vec![infra_id.as_str()]
+    );
+    let tagged = list(
+        &mut mcp,
+        ThreadListParams {
+            tags: Some(vec!["infra".to_string(), "ci".to_string()]),
+            ..list_params()
+        },
+    )
+    .await?;
+    assert_eq!(
+        tagged
+            .data
+            .iter()
+            .map(|thread| thread.id.as_str())
+            .collect::<Vec<_>>(),
+        vec![infra_id.as_str()]
+    );
+    let in_folder = list(
+        &mut mcp,
+        ThreadListParams {
+            folder: Some("clients".to_string()),
+            ..list_params()
+        },
+    )
+    .await?;
+    assert_eq!(
+        in_folder
+            .data
+            .iter()
+            .map(|thread| thread.id.as_str())
+            .collect::<Vec<_>>(),
+        vec![docs_id.as_str()]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_organization_without_state_db_returns_error() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), false)?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "fix the deploy pipeline",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_thread_pin_set_request(ThreadPinSetParams {
+            thread_id,
+            pinned: true,
+        })
+        .await?;
+    let error = read_error(&mut mcp, request_id).await?;
+    assert!(
+        error.error.message.contains("state database"),
+        "unexpected error: {}",
+        error.error.message
+    );
+
+    let request_id = mcp
+        .send_thread_list_request(ThreadListParams {
+            pinned: Some(true),
+            ..list_params()
+        })
+        .await?;
+    let error = read_error(&mut mcp, request_id).await?;
+    assert!(
+        error.error.message.contains("state database"),
+        "unexpected error: {}",
+        error.error.message
+    );
+
+    Ok(())
+}
+
+async fn list(mcp: &mut McpProcess, params: ThreadListParams) -> Result<ThreadListResponse> {
+    let request_id = mcp.send_thread_list_request(params).await?;
+    read_response(mcp, request_id).await
+}
+
+async fn read_response<T: serde::de::DeserializeOwned>(
+    mcp: &mut McpProcess,
+    request_id: i64,
+) -> Result<T> {
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<T>(resp)
+}
+
+async fn read_error(mcp: &mut McpProcess, request_id: i64) -> Result<JSONRPCError> {
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await?
+}
+
+fn create_config_toml(codex_home: &Path, sqlite: bool) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+[features]
+sqlite = {sqlite}
+"#
+        ),
+    )
+}
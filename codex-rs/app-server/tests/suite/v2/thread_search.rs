@@ -0,0 +1,166 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::ByteRange;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadSearchMatchSource;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+fn search_params(query: &str, limit: Option<u32>, cursor: Option<String>) -> ThreadSearchParams {
+    ThreadSearchParams {
+        query: query.to_string(),
+        cursor,
+        limit,
+        model_providers: Some(Vec::new()),
+        source_kinds: None,
+        archived: None,
+    }
+}
+
+#[tokio::test]
+async fn thread_search_finds_backfilled_rollouts() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), true)?;
+    let websocket_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "debug the websocket reconnect loop",
+        Some("mock_provider"),
+        None,
+    )?;
+    create_fake_rollout(
+        codex_home.path(),
+        "2025-01-01T12-00-00",
+        "2025-01-01T12:00:00Z",
+        "rename the config module",
+        Some("mock_provider"),
+        None,
+    )?;
+    let second_websocket_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-03T12-00-00",
+        "2025-01-03T12:00:00Z",
+        "add websocket compression",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    // Starting a thread initializes the state database, which backfills and
+    // indexes the existing rollouts.
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let _: ThreadStartResponse = to_response(start_resp)?;
+
+    let ThreadSearchResponse { data, next_cursor } =
+        search(&mut mcp, search_params("reconnect", None, None)).await?;
+    assert_eq!(next_cursor, None);
+    assert_eq!(
+        data.iter()
+            .map(|hit| hit.thread.id.as_str())
+            .collect::<Vec<_>>(),
+        vec![websocket_id.as_str()]
+    );
+    let hit = &data[0];
+    assert_eq!(hit.source, ThreadSearchMatchSource::UserMessage);
+    assert_eq!(hit.thread.preview, "debug the websocket reconnect loop");
+    assert_eq!(hit.snippet, "debug the websocket reconnect loop");
+    assert_eq!(hit.highlights, vec![ByteRange { start: 20, end: 29 }]);
+
+    let first_page = search(&mut mcp, search_params("websock", Some(1), None)).await?;
+    assert_eq!(first_page.data.len(), 1);
+    let next_cursor = first_page.next_cursor.expect("expected a second page");
+    let second_page = search(
+        &mut mcp,
+        search_params("websock", Some(1), Some(next_cursor)),
+    )
+    .await?;
+    assert_eq!(second_page.next_cursor, None);
+    let mut found = first_page
+        .data
+        .iter()
+        .chain(second_page.data.iter())
+        .map(|hit| hit.thread.id.clone())
+        .collect::<Vec<_>>();
+    found.sort();
+    let mut expected = vec![websocket_id, second_websocket_id];
+    expected.sort();
+    assert_eq!(found, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_search_without_state_db_returns_error() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), false)?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_thread_search_request(search_params("anything", None, None))
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    assert!(
+        error.error.message.contains("state database"),
+        "unexpected error: {}",
+        error.error.message
+    );
+
+    Ok(())
+}
+
+async fn search(mcp: &mut McpProcess, params: ThreadSearchParams) -> Result<ThreadSearchResponse> {
+    let request_id = mcp.send_thread_search_request(params).await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<ThreadSearchResponse>(resp)
+}
+
+fn create_config_toml(codex_home: &Path, sqlite: bool) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+[features]
+sqlite = {sqlite}
+"#
+        ),
+    )
+}
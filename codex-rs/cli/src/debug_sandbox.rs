@@ -240,6 +240,7 @@ async fn run_command_under_sandbox(
                 config.sandbox_policy.get(),
                 sandbox_policy_cwd.as_path(),
                 use_bwrap_sandbox,
+                &[],
                 stdio_policy,
                 env,
             )
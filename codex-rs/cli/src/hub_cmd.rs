@@ -0,0 +1,379 @@
+use std::collections::VecDeque;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use clap::Parser;
+use codex_codexd::hub::HubClient;
+use codex_codexd::hub::HubUpdate;
+use codex_codexd::protocol::CodexdEventEnvelope;
+use codex_codexd::protocol::CodexdEventPayload;
+use codex_codexd::protocol::CodexdSnapshotResponse;
+use codex_codexd::protocol::PendingApprovalKind;
+use codex_codexd::protocol::RuntimeSnapshot;
+use codex_codexd::protocol::RuntimeStaleReason;
+use codex_codexd::unix_timestamp_now;
+use codex_core::config::find_codex_home;
+use owo_colors::OwoColorize;
+use supports_color::Stream;
+
+const MAX_RECENT_EVENTS: usize = 10;
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Show the Codex runtimes reported to codexd and follow their events.
+#[derive(Debug, Parser)]
+pub struct HubCommand {
+    /// Optional socket path override (defaults to $CODEX_HOME/runtime/codexd/codexd.sock).
+    #[arg(long = "socket-path", value_name = "PATH")]
+    pub socket_path: Option<PathBuf>,
+
+    /// Print the current snapshot once and exit instead of following events.
+    #[arg(long, default_value_t = false)]
+    pub once: bool,
+}
+
+pub async fn run_hub(cmd: HubCommand) -> Result<()> {
+    let socket_path = match cmd.socket_path {
+        Some(socket_path) => socket_path,
+        None => codex_codexd::default_socket_path(find_codex_home()?.as_path()),
+    };
+    let (mut client, snapshot) = HubClient::connect(&socket_path).await.map_err(|err| {
+        err.context("is codexd running? Start it with `codex app-server codexd run`")
+    })?;
+
+    let color = std::io::stdout().is_terminal() && supports_color::on(Stream::Stdout).is_some();
+    let mut view = HubView::new(snapshot);
+    if cmd.once {
+        for line in view.render(unix_timestamp_now(), color) {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+
+    let mut ticker = tokio::time::interval(REDRAW_INTERVAL);
+    // Events only say what changed; the daemon's snapshot is the source of
+    // truth, so refetch it after each burst of events.
+    let mut snapshot_dirty = false;
+    let mut snapshot_in_flight = false;
+    loop {
+        redraw(&view, color)?;
+
+        let update = tokio::select! {
+            update = client.next_update() => update?,
+            _ = ticker.tick() => continue,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        match update {
+            None => anyhow::bail!("codexd closed the connection"),
+            Some(HubUpdate::Snapshot(snapshot)) => {
+                view.snapshot = snapshot;
+                snapshot_in_flight = false;
+            }
+            Some(HubUpdate::Event(envelope)) => {
+                view.record_event(&envelope, unix_timestamp_now());
+                snapshot_dirty = true;
+            }
+            Some(HubUpdate::Gap(_)) => snapshot_dirty = true,
+        }
+
+        if snapshot_dirty && !snapshot_in_flight {
+            client.request_snapshot().await?;
+            snapshot_dirty = false;
+            snapshot_in_flight = true;
+        }
+    }
+}
+
+fn redraw(view: &HubView, color: bool) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    write!(stdout, "{CLEAR_SCREEN}")?;
+    for line in view.render(unix_timestamp_now(), color) {
+        writeln!(stdout, "{line}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+struct RecentEvent {
+    received_at: i64,
+    runtime: String,
+    description: String,
+}
+
+struct HubView {
+    snapshot: CodexdSnapshotResponse,
+    recent_events: VecDeque<RecentEvent>,
+}
+
+impl HubView {
+    fn new(snapshot: CodexdSnapshotResponse) -> Self {
+        Self {
+            snapshot,
+            recent_events: VecDeque::new(),
+        }
+    }
+
+    fn record_event(&mut self, envelope: &CodexdEventEnvelope, now: i64) {
+        let Some((runtime_id, description)) = describe_event(&envelope.event) else {
+            return;
+        };
+        let runtime = self
+            .snapshot
+            .runtimes
+            .iter()
+            .find(|runtime| runtime.runtime_id == runtime_id)
+            .map(runtime_label)
+            .unwrap_or(runtime_id);
+        self.recent_events.push_front(RecentEvent {
+            received_at: now,
+            runtime,
+            description,
+        });
+        self.recent_events.truncate(MAX_RECENT_EVENTS);
+    }
+
+    fn render(&self, now: i64, color: bool) -> Vec<String> {
+        let paint = |text: String, style: fn(&String) -> String| {
+            if color { style(&text) } else { text }
+        };
+
+        let runtimes = &self.snapshot.runtimes;
+        let mut lines = vec![paint(
+            format!(
+                "codex hub: {} runtime{}",
+                runtimes.len(),
+                if runtimes.len() == 1 { "" } else { "s" }
+            ),
+            |text| text.bold().to_string(),
+        )];
+        lines.push(String::new());
+        if runtimes.is_empty() {
+            lines.push(paint(
+                "No Codex runtimes are running.".to_string(),
+                |text| text.dimmed().to_string(),
+            ));
+        }
+
+        for runtime in runtimes {
+            let mut header = paint(runtime_label(runtime), |text| text.cyan().to_string());
+            if let Some(pid) = runtime.pid {
+                header.push_str(&format!("  pid {pid}"));
+            }
+            if let Some(source) = &runtime.session_source {
+                header.push_str(&format!("  {source}"));
+            }
+            lines.push(header);
+            if let Some(cwd) = &runtime.cwd {
+                lines.push(format!("  cwd       {cwd}"));
+            }
+            if runtime.active_turns.is_empty() {
+                lines.push(paint("  idle".to_string(), |text| {
+                    text.dimmed().to_string()
+                }));
+            }
+            for turn in &runtime.active_turns {
+                let mut line = format!("  turn      {}", turn.turn_id);
+                if let Some(model) = &turn.model {
+                    line.push_str(&format!("  {model}"));
+                }
+                if let Some(started_at) = turn.started_at {
+                    line.push_str(&format!("  running {}", format_elapsed(now - started_at)));
+                }
+                lines.push(paint(line, |text| text.green().to_string()));
+            }
+            for approval in &runtime.pending_approvals {
+                let kind = match approval.kind {
+                    PendingApprovalKind::CommandExecution => "command",
+                    PendingApprovalKind::FileChange => "file change",
+                    PendingApprovalKind::UserInput => "user input",
+                };
+                let mut line = format!("  approval  {kind}");
+                if let Some(detail) = approval.command.as_ref().or(approval.reason.as_ref()) {
+                    line.push_str(&format!("  {detail}"));
+                }
+                line.push_str(&format!(
+                    "  waiting {}",
+                    format_elapsed(now - approval.started_at)
+                ));
+                lines.push(paint(line, |text| text.yellow().to_string()));
+            }
+            lines.push(String::new());
+        }
+
+        if !self.recent_events.is_empty() {
+            lines.push(paint("Recent events".to_string(), |text| {
+                text.bold().to_string()
+            }));
+            for event in &self.recent_events {
+                lines.push(format!(
+                    "  {:>8} ago  {}  {}",
+                    format_elapsed(now - event.received_at),
+                    event.runtime,
+                    event.description
+                ));
+            }
+        }
+
+        lines
+    }
+}
+
+fn runtime_label(runtime: &RuntimeSnapshot) -> String {
+    runtime
+        .display_name
+        .clone()
+        .unwrap_or_else(|| runtime.runtime_id.clone())
+}
+
+/// Returns the runtime an event concerns and a one-line description, or
+/// `None` for events too chatty to list (such as token usage updates).
+fn describe_event(event: &CodexdEventPayload) -> Option<(String, String)> {
+    let (runtime_id, description) = match event {
+        CodexdEventPayload::RuntimeUpsert { runtime } => {
+            (runtime.runtime_id.clone(), "registered".to_string())
+        }
+        CodexdEventPayload::RuntimeRemoved { runtime_id } => {
+            (runtime_id.clone(), "exited".to_string())
+        }
+        CodexdEventPayload::RuntimeNotification {
+            runtime_id,
+            notification,
+        } => {
+            let description = match notification.method.as_str() {
+                "turn/started" => "turn started",
+                "turn/completed" => "turn completed",
+                "error" => "error",
+                _ => return None,
+            };
+            (runtime_id.clone(), description.to_string())
+        }
+        CodexdEventPayload::ApprovalRequested {
+            runtime_id,
+            approval,
+        } => {
+            let description = match &approval.command {
+                Some(command) => format!("approval requested: {command}"),
+                None => "approval requested".to_string(),
+            };
+            (runtime_id.clone(), description)
+        }
+        CodexdEventPayload::ApprovalResolved { runtime_id, .. } => {
+            (runtime_id.clone(), "approval resolved".to_string())
+        }
+        CodexdEventPayload::RuntimeStale { runtime_id, reason } => {
+            let description = match reason {
+                RuntimeStaleReason::ProcessExited => "stale: process exited",
+                RuntimeStaleReason::HeartbeatTimeout => "stale: heartbeat timed out",
+            };
+            (runtime_id.clone(), description.to_string())
+        }
+    };
+    Some((runtime_id, description))
+}
+
+fn format_elapsed(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else if seconds < 3600 {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_codexd::protocol::ActiveTurnSnapshot;
+    use codex_codexd::protocol::PendingApprovalSnapshot;
+    use pretty_assertions::assert_eq;
+
+    fn runtime() -> RuntimeSnapshot {
+        RuntimeSnapshot {
+            runtime_id: "pid:42".to_string(),
+            pid: Some(42),
+            session_source: Some("exec".to_string()),
+            cwd: Some("/work/project".to_string()),
+            display_name: Some("codex-exec".to_string()),
+            active_turns: vec![ActiveTurnSnapshot {
+                thread_id: "thread-1".to_string(),
+                turn_id: "turn-1".to_string(),
+                model: Some("gpt-5".to_string()),
+                started_at: Some(1_000),
+            }],
+            pending_approvals: vec![PendingApprovalSnapshot {
+                thread_id: "thread-1".to_string(),
+                turn_id: "turn-1".to_string(),
+                item_id: "call-1".to_string(),
+                kind: PendingApprovalKind::CommandExecution,
+                command: Some("cargo test".to_string()),
+                cwd: None,
+                reason: None,
+                started_at: 1_050,
+            }],
+        }
+    }
+
+    #[test]
+    fn renders_runtimes_turns_and_recent_events() {
+        let mut view = HubView::new(CodexdSnapshotResponse {
+            seq: 3,
+            runtimes: vec![runtime()],
+        });
+        view.record_event(
+            &CodexdEventEnvelope {
+                seq: 4,
+                event: CodexdEventPayload::ApprovalResolved {
+                    runtime_id: "pid:42".to_string(),
+                    thread_id: "thread-1".to_string(),
+                    item_id: "call-1".to_string(),
+                },
+            },
+            1_070,
+        );
+
+        assert_eq!(
+            view.render(1_075, false),
+            vec![
+                "codex hub: 1 runtime".to_string(),
+                String::new(),
+                "codex-exec  pid 42  exec".to_string(),
+                "  cwd       /work/project".to_string(),
+                "  turn      turn-1  gpt-5  running 1m 15s".to_string(),
+                "  approval  command  cargo test  waiting 25s".to_string(),
+                String::new(),
+                "Recent events".to_string(),
+                "        5s ago  codex-exec  approval resolved".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_placeholder_without_runtimes() {
+        let view = HubView::new(CodexdSnapshotResponse {
+            seq: 0,
+            runtimes: Vec::new(),
+        });
+
+        assert_eq!(
+            view.render(0, false),
+            vec![
+                "codex hub: 0 runtimes".to_string(),
+                String::new(),
+                "No Codex runtimes are running.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn format_elapsed_scales_units() {
+        assert_eq!(format_elapsed(-5), "0s");
+        assert_eq!(format_elapsed(59), "59s");
+        assert_eq!(format_elapsed(61), "1m 01s");
+        assert_eq!(format_elapsed(7_380), "2h 03m");
+    }
+}
@@ -0,0 +1,367 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use clap::Parser;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::state_db::LogField;
+use codex_core::state_db::LogFilter;
+use codex_core::state_db::LogFilterOp;
+use codex_core::state_db::LogQuery;
+use codex_core::state_db::LogRow;
+use codex_core::state_db::StateDbHandle;
+use codex_core::state_db::open_log_db;
+
+/// Rows fetched per query while exporting or following.
+const LOG_PAGE_SIZE: usize = 1_000;
+const FOLLOW_MIN_INTERVAL: Duration = Duration::from_millis(100);
+const FOLLOW_MAX_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Export or follow logs recorded in the state database.
+#[derive(Debug, Parser)]
+pub struct LogsCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Only include logs from this time on: RFC3339, unix seconds, or a
+    /// duration ago such as `30m`, `2h` or `7d`.
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    pub since: Option<i64>,
+
+    /// Only include logs up to this time (same formats as --since).
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    pub until: Option<i64>,
+
+    /// Only include logs of this thread. Repeat to include several threads.
+    #[arg(long = "thread", value_name = "THREAD_ID")]
+    pub threads: Vec<String>,
+
+    /// Only include logs of this turn. Repeat to include several turns.
+    #[arg(long = "turn", value_name = "TURN_ID")]
+    pub turns: Vec<String>,
+
+    /// Only include logs whose module path contains this text. Repeat to
+    /// include several modules.
+    #[arg(long = "module", value_name = "MODULE")]
+    pub modules: Vec<String>,
+
+    /// Minimum level to include (trace, debug, info, warn, error).
+    #[arg(long, value_name = "LEVEL")]
+    pub level: Option<String>,
+
+    /// Filter expression, e.g.
+    /// `level>=warn and (module~exec or message~"timed out") and not turn=none`.
+    #[arg(long, value_name = "EXPR", value_parser = parse_filter)]
+    pub filter: Option<LogFilter>,
+
+    /// Only export the most recent N matching logs.
+    #[arg(long, short = 'n', value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = LogsFormat::Jsonl)]
+    pub format: LogsFormat,
+
+    /// Write logs to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Keep running and print new logs as they are recorded.
+    #[arg(long, short = 'f', conflicts_with = "until")]
+    pub follow: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogsFormat {
+    /// One JSON object per line.
+    Jsonl,
+    /// Human-readable lines.
+    Text,
+}
+
+pub async fn run_logs(cmd: LogsCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides)
+        .await
+        .context("failed to load configuration")?;
+    let db = open_log_db(&config).await?;
+    let query = build_query(&cmd)?;
+
+    let mut out: Box<dyn Write + Send> = match cmd.output.as_ref() {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("failed to create {}", path.display()))?,
+        )),
+        None => Box::new(std::io::stdout()),
+    };
+
+    let mut last_id = export(&db, &query, cmd.limit, cmd.format, &mut out).await?;
+    out.flush()?;
+    if !cmd.follow {
+        return Ok(());
+    }
+    if last_id == 0 {
+        last_id = db.max_log_id(&LogQuery::default()).await?;
+    }
+    tokio::select! {
+        result = follow(&db, &query, last_id, cmd.format, &mut out) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+fn build_query(cmd: &LogsCommand) -> Result<LogQuery> {
+    if let (Some(since), Some(until)) = (cmd.since, cmd.until) {
+        anyhow::ensure!(since <= until, "--since must not be after --until");
+    }
+    let level = cmd
+        .level
+        .as_deref()
+        .map(|level| parse_filter(&format!("level >= {level}")))
+        .transpose()
+        .map_err(anyhow::Error::msg)?;
+    let turns = LogFilter::any(
+        cmd.turns
+            .iter()
+            .map(|turn| LogFilter::compare(LogField::Turn, LogFilterOp::Eq, turn)),
+    );
+    Ok(LogQuery {
+        from_ts: cmd.since,
+        to_ts: cmd.until,
+        module_like: cmd.modules.clone(),
+        thread_ids: cmd.threads.clone(),
+        filter: LogFilter::all([level, turns, cmd.filter.clone()].into_iter().flatten()),
+        ..Default::default()
+    })
+}
+
+/// Write the logs matching `query`, oldest first, and return the id of the
+/// last row written.
+async fn export(
+    db: &StateDbHandle,
+    query: &LogQuery,
+    limit: Option<usize>,
+    format: LogsFormat,
+    out: &mut (dyn Write + Send),
+) -> Result<i64> {
+    if let Some(limit) = limit {
+        let mut rows = db
+            .query_logs(&LogQuery {
+                limit: Some(limit),
+                descending: true,
+                ..query.clone()
+            })
+            .await?;
+        rows.reverse();
+        return write_rows(&rows, format, out);
+    }
+
+    let mut last_id = 0;
+    loop {
+        let rows = db
+            .query_logs(&LogQuery {
+                after_id: Some(last_id),
+                limit: Some(LOG_PAGE_SIZE),
+                ..query.clone()
+            })
+            .await?;
+        if rows.is_empty() {
+            return Ok(last_id);
+        }
+        last_id = write_rows(&rows, format, out)?;
+    }
+}
+
+/// Print logs recorded after `last_id`. The cheap `max_log_id` probe decides
+/// whether there is anything to fetch; the probe interval backs off while the
+/// database is idle and resets as soon as new rows arrive.
+async fn follow(
+    db: &StateDbHandle,
+    query: &LogQuery,
+    mut last_id: i64,
+    format: LogsFormat,
+    out: &mut (dyn Write + Send),
+) -> Result<()> {
+    let mut interval = FOLLOW_MIN_INTERVAL;
+    loop {
+        tokio::time::sleep(interval).await;
+        let max_id = db
+            .max_log_id(&LogQuery {
+                after_id: Some(last_id),
+                ..query.clone()
+            })
+            .await?;
+        if max_id <= last_id {
+            interval = (interval * 2).min(FOLLOW_MAX_INTERVAL);
+            continue;
+        }
+        interval = FOLLOW_MIN_INTERVAL;
+        while last_id < max_id {
+            let rows = db
+                .query_logs(&LogQuery {
+                    after_id: Some(last_id),
+                    limit: Some(LOG_PAGE_SIZE),
+                    ..query.clone()
+                })
+                .await?;
+            if rows.is_empty() {
+                break;
+            }
+            last_id = write_rows(&rows, format, out)?;
+        }
+        out.flush()?;
+    }
+}
+
+fn write_rows(rows: &[LogRow], format: LogsFormat, out: &mut (dyn Write + Send)) -> Result<i64> {
+    let mut last_id = 0;
+    for row in rows {
+        match format {
+            LogsFormat::Jsonl => writeln!(out, "{}", serde_json::to_string(row)?)?,
+            LogsFormat::Text => writeln!(out, "{}", format_text(row))?,
+        }
+        last_id = row.id;
+    }
+    Ok(last_id)
+}
+
+fn format_text(row: &LogRow) -> String {
+    let timestamp = u32::try_from(row.ts_nanos)
+        .ok()
+        .and_then(|nanos| DateTime::<Utc>::from_timestamp(row.ts, nanos))
+        .map(|ts| ts.to_rfc3339_opts(SecondsFormat::Millis, true))
+        .unwrap_or_else(|| row.ts.to_string());
+    let thread_id = row.thread_id.as_deref().unwrap_or("-");
+    let turn_id = row.turn_id.as_deref().unwrap_or("-");
+    let message = row.message.as_deref().unwrap_or("");
+    format!(
+        "{timestamp} {:<5} [{thread_id} {turn_id}] {} - {message}",
+        row.level, row.target
+    )
+}
+
+fn parse_filter(raw: &str) -> Result<LogFilter, String> {
+    raw.parse().map_err(|err: anyhow::Error| err.to_string())
+}
+
+pub(crate) fn parse_time(raw: &str) -> Result<i64, String> {
+    parse_time_at(raw, Utc::now())
+}
+
+fn parse_time_at(raw: &str, now: DateTime<Utc>) -> Result<i64, String> {
+    if let Ok(secs) = raw.parse::<i64>() {
+        return Ok(secs);
+    }
+    if let Ok(ts) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(ts.timestamp());
+    }
+    let unit_start = raw
+        .find(|ch: char| !ch.is_ascii_digit())
+        .filter(|idx| *idx > 0)
+        .ok_or_else(|| {
+            format!("expected RFC3339, unix seconds or a duration like 2h, got `{raw}`")
+        })?;
+    let (amount, unit) = raw.split_at(unit_start);
+    let amount = amount.parse::<i64>().map_err(|err| err.to_string())?;
+    let seconds_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "unknown duration unit `{unit}`; use s, m, h, d or w"
+            ));
+        }
+    };
+    Ok(now.timestamp() - amount.saturating_mul(seconds_per_unit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_time_accepts_absolute_and_relative_times() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .expect("timestamp")
+            .with_timezone(&Utc);
+        assert_eq!(parse_time_at("1700000000", now), Ok(1_700_000_000));
+        assert_eq!(
+            parse_time_at("2026-03-01T11:00:00+00:00", now),
+            Ok(now.timestamp() - 3_600)
+        );
+        assert_eq!(parse_time_at("90m", now), Ok(now.timestamp() - 5_400));
+        assert_eq!(parse_time_at("2d", now), Ok(now.timestamp() - 172_800));
+        assert!(parse_time_at("2 fortnights", now).is_err());
+        assert!(parse_time_at("yesterday", now).is_err());
+    }
+
+    #[test]
+    fn build_query_combines_level_turns_and_expression() -> Result<()> {
+        let cmd = LogsCommand::try_parse_from([
+            "logs",
+            "--level",
+            "warn",
+            "--turn",
+            "t1",
+            "--turn",
+            "t2",
+            "--filter",
+            "module~exec",
+            "--thread",
+            "thread-a",
+        ])?;
+        let query = build_query(&cmd)?;
+
+        assert_eq!(query.thread_ids, vec!["thread-a".to_string()]);
+        assert_eq!(
+            query.filter,
+            Some(
+                LogFilter::compare(LogField::Level, LogFilterOp::Ge, "WARN")
+                    .and(
+                        LogFilter::compare(LogField::Turn, LogFilterOp::Eq, "t1")
+                            .or(LogFilter::compare(LogField::Turn, LogFilterOp::Eq, "t2"))
+                    )
+                    .and(LogFilter::compare(
+                        LogField::Module,
+                        LogFilterOp::Contains,
+                        "exec"
+                    ))
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn format_text_includes_thread_and_turn() {
+        let row = LogRow {
+            id: 1,
+            ts: 1_700_000_000,
+            ts_nanos: 5_000_000,
+            level: "WARN".to_string(),
+            target: "codex_core::exec".to_string(),
+            message: Some("slow command".to_string()),
+            thread_id: Some("thread-a".to_string()),
+            turn_id: None,
+            module_path: None,
+            file: None,
+            line: None,
+        };
+        assert_eq!(
+            format_text(&row),
+            "2023-11-14T22:13:20.005Z WARN  [thread-a -] codex_core::exec - slow command"
+        );
+    }
+}
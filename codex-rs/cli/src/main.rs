@@ -16,6 +16,7 @@ use codex_cli::login::run_login_with_chatgpt;
 use codex_cli::login::run_login_with_device_code;
 use codex_cli::login::run_logout;
 use codex_cloud_tasks::Cli as CloudTasksCli;
+use codex_codexd::DaemonOptions as CodexdDaemonOptions;
 use codex_codexd::daemon::run_daemon as run_codexd_daemon;
 use codex_common::CliConfigOverrides;
 use codex_exec::Cli as ExecCli;
@@ -33,13 +34,21 @@ use std::io::IsTerminal;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command as ProcessCommand;
+use std::time::Duration;
 use supports_color::Stream;
 
 #[cfg(target_os = "macos")]
 mod app_cmd;
 #[cfg(target_os = "macos")]
 mod desktop_app;
+#[cfg(unix)]
+mod hub_cmd;
+mod logs_cmd;
 mod mcp_cmd;
+mod network_cmd;
+mod secrets_cmd;
+mod session_cmd;
+mod usage_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
@@ -110,6 +119,10 @@ enum Subcommand {
     #[cfg(target_os = "macos")]
     App(app_cmd::AppCommand),
 
+    /// Show running Codex sessions reported to codexd, with live turns and approvals.
+    #[cfg(unix)]
+    Hub(hub_cmd::HubCommand),
+
     /// Generate shell completion scripts.
     Completion(CompletionCommand),
 
@@ -133,6 +146,12 @@ enum Subcommand {
     /// Fork a previous interactive session (picker by default; use --last to fork the most recent).
     Fork(ForkCommand),
 
+    /// Export a session to a portable bundle or import one from another machine.
+    Session(session_cmd::SessionCommand),
+
+    /// Manage secrets injected into commands and MCP servers.
+    Secrets(secrets_cmd::SecretsCommand),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -147,6 +166,15 @@ enum Subcommand {
 
     /// Inspect feature flags.
     Features(FeaturesCli),
+
+    /// Report token usage recorded for past sessions (requires the `sqlite` feature).
+    Usage(usage_cmd::UsageCommand),
+
+    /// Export or follow logs recorded in the state database (requires the `sqlite` feature).
+    Logs(logs_cmd::LogsCommand),
+
+    /// Run the network proxy that sandboxed commands are routed through.
+    Network(network_cmd::NetworkCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -400,6 +428,16 @@ struct CodexdRunCommand {
     /// Optional socket path override (defaults to $CODEX_HOME/runtime/codexd/codexd.sock).
     #[arg(long = "socket-path", value_name = "PATH")]
     socket_path: Option<PathBuf>,
+
+    /// Seconds a runtime may go without a heartbeat before it is marked stale and removed.
+    /// Runtimes heartbeat every 15 seconds, so values below 30 are rejected.
+    #[arg(
+        long = "stale-timeout",
+        value_name = "SECONDS",
+        default_value_t = 60,
+        value_parser = clap::value_parser!(u64).range(30..)
+    )]
+    stale_timeout: u64,
 }
 
 #[derive(Debug, Parser)]
@@ -510,7 +548,10 @@ async fn run_codexd_command(cmd: CodexdCommand) -> anyhow::Result<()> {
     match cmd.action {
         CodexdSubcommand::Run(run) => {
             let codex_home = find_codex_home()?;
-            run_codexd_daemon(codex_home.as_path(), run.socket_path).await?;
+            let options = CodexdDaemonOptions {
+                stale_timeout: Duration::from_secs(run.stale_timeout),
+            };
+            run_codexd_daemon(codex_home.as_path(), run.socket_path, options).await?;
         }
         CodexdSubcommand::InstallLaunchAgent => {
             install_codexd_launch_agent()?;
@@ -838,6 +879,41 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Session(mut session_cli)) => {
+            prepend_config_flags(
+                &mut session_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            session_cmd::run_session(session_cli).await?;
+        }
+        Some(Subcommand::Secrets(mut secrets_cli)) => {
+            prepend_config_flags(
+                &mut secrets_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            secrets_cmd::run_secrets(secrets_cli).await?;
+        }
+        Some(Subcommand::Usage(mut usage_cli)) => {
+            prepend_config_flags(
+                &mut usage_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            usage_cmd::run_usage(usage_cli).await?;
+        }
+        Some(Subcommand::Logs(mut logs_cli)) => {
+            prepend_config_flags(
+                &mut logs_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            logs_cmd::run_logs(logs_cli).await?;
+        }
+        Some(Subcommand::Network(mut network_cli)) => {
+            prepend_config_flags(
+                &mut network_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            network_cmd::run_network(network_cli).await?;
+        }
         Some(Subcommand::AppServer(app_server_cli)) => match app_server_cli.subcommand {
             None => {
                 let transport = app_server_cli.listen;
@@ -875,6 +951,10 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
         Some(Subcommand::App(app_cli)) => {
             app_cmd::run_app(app_cli).await?;
         }
+        #[cfg(unix)]
+        Some(Subcommand::Hub(hub_cli)) => {
+            hub_cmd::run_hub(hub_cli).await?;
+        }
         Some(Subcommand::Resume(ResumeCommand {
             session_id,
             last,
@@ -1644,6 +1724,177 @@ mod tests {
         }
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn hub_parses_socket_path_and_once() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "hub",
+            "--socket-path",
+            "/tmp/codexd.sock",
+            "--once",
+        ])
+        .expect("parse should succeed");
+        let Some(Subcommand::Hub(hub)) = cli.subcommand else {
+            panic!("expected hub subcommand");
+        };
+        assert_eq!(hub.socket_path, Some(PathBuf::from("/tmp/codexd.sock")));
+        assert!(hub.once);
+    }
+
+    #[test]
+    fn usage_parses_grouping_dates_and_format() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "usage",
+            "--group-by",
+            "repo",
+            "--since",
+            "2026-01-01",
+            "--format",
+            "csv",
+        ])
+        .expect("parse should succeed");
+        let Some(Subcommand::Usage(usage)) = cli.subcommand else {
+            panic!("expected usage subcommand");
+        };
+        assert_eq!(usage.group_by, usage_cmd::UsageGroup::Repo);
+        assert_eq!(
+            usage.since,
+            Some(chrono::NaiveDate::from_ymd_opt(2026, 1, 1).expect("date"))
+        );
+        assert_eq!(usage.until, None);
+        assert_eq!(usage.format, usage_cmd::UsageFormat::Csv);
+    }
+
+    #[test]
+    fn logs_parses_range_filters_and_follow() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "logs",
+            "--since",
+            "1700000000",
+            "--thread",
+            "thread-a",
+            "--filter",
+            "level>=warn or module~exec",
+            "--format",
+            "text",
+            "--follow",
+        ])
+        .expect("parse should succeed");
+        let Some(Subcommand::Logs(logs)) = cli.subcommand else {
+            panic!("expected logs subcommand");
+        };
+        assert_eq!(logs.since, Some(1_700_000_000));
+        assert_eq!(logs.threads, vec!["thread-a".to_string()]);
+        assert!(logs.filter.is_some());
+        assert_eq!(logs.format, logs_cmd::LogsFormat::Text);
+        assert!(logs.follow);
+
+        let err = MultitoolCli::try_parse_from(["codex", "logs", "--filter", "level >= loud"])
+            .expect_err("invalid filter should fail");
+        assert!(err.to_string().contains("unknown log level"));
+    }
+
+    #[test]
+    fn network_proxy_parses() {
+        let cli = MultitoolCli::try_parse_from(["codex", "network", "proxy"])
+            .expect("parse should succeed");
+        let Some(Subcommand::Network(network)) = cli.subcommand else {
+            panic!("expected network subcommand");
+        };
+        assert!(matches!(
+            network.subcommand,
+            network_cmd::NetworkSubcommand::Proxy
+        ));
+    }
+
+    #[test]
+    fn network_log_parses_filters() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "network",
+            "log",
+            "--host",
+            "pypi.org",
+            "--blocked",
+            "-n",
+            "5",
+            "--json",
+        ])
+        .expect("parse should succeed");
+        let Some(Subcommand::Network(network)) = cli.subcommand else {
+            panic!("expected network subcommand");
+        };
+        let network_cmd::NetworkSubcommand::Log(args) = network.subcommand else {
+            panic!("expected log subcommand");
+        };
+        assert_eq!(args.host.as_deref(), Some("pypi.org"));
+        assert!(args.blocked);
+        assert_eq!(args.limit, 5);
+        assert!(args.json);
+        assert_eq!(args.thread, None);
+
+        MultitoolCli::try_parse_from(["codex", "network", "log", "--thread", "nope"])
+            .expect_err("invalid thread id should fail");
+    }
+
+    #[test]
+    fn session_import_parses_bundle_and_cwd() {
+        let cli = MultitoolCli::try_parse_from([
+            "codex",
+            "session",
+            "import",
+            "bundle.zip",
+            "--cwd",
+            "/home/me/repo",
+        ])
+        .expect("parse should succeed");
+        let Some(Subcommand::Session(session)) = cli.subcommand else {
+            panic!("expected session subcommand");
+        };
+        let session_cmd::SessionSubcommand::Import(import) = session.subcommand else {
+            panic!("expected session import");
+        };
+        assert_eq!(import.bundle, PathBuf::from("bundle.zip"));
+        assert_eq!(import.cwd, Some(PathBuf::from("/home/me/repo")));
+    }
+
+    #[test]
+    fn secrets_scope_flags_conflict() {
+        let cli =
+            MultitoolCli::try_parse_from(["codex", "secrets", "set", "NPM_TOKEN", "--global"])
+                .expect("parse should succeed");
+        let Some(Subcommand::Secrets(secrets)) = cli.subcommand else {
+            panic!("expected secrets subcommand");
+        };
+        let secrets_cmd::SecretsSubcommand::Set(set) = secrets.subcommand else {
+            panic!("expected secrets set");
+        };
+        assert_eq!(set.name, "NPM_TOKEN");
+        assert!(set.scope.global);
+
+        let conflicting =
+            MultitoolCli::try_parse_from(["codex", "secrets", "list", "--all", "--env", "my-repo"]);
+        assert!(conflicting.is_err());
+    }
+
+    #[test]
+    fn secrets_migrate_parses_target_backend() {
+        let cli =
+            MultitoolCli::try_parse_from(["codex", "secrets", "migrate", "--to", "passphrase"])
+                .expect("parse should succeed");
+        let Some(Subcommand::Secrets(secrets)) = cli.subcommand else {
+            panic!("expected secrets subcommand");
+        };
+        let secrets_cmd::SecretsSubcommand::Migrate(migrate) = secrets.subcommand else {
+            panic!("expected secrets migrate");
+        };
+        assert_eq!(migrate.to, secrets_cmd::BackendArg::Passphrase);
+        assert_eq!(migrate.from, None);
+    }
+
     #[test]
     fn features_enable_parses_feature_name() {
         let cli = MultitoolCli::try_parse_from(["codex", "features", "enable", "unified_exec"])
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
 use anyhow::Context;
@@ -19,6 +20,7 @@ use codex_core::mcp::auth::oauth_login_support;
 use codex_core::protocol::McpAuthStatus;
 use codex_rmcp_client::delete_oauth_tokens;
 use codex_rmcp_client::perform_oauth_login;
+use codex_secrets::SecretName;
 
 /// Subcommands:
 /// - `list`   — list configured servers (with `--json`)
@@ -249,6 +251,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         enabled_tools: None,
         disabled_tools: None,
         scopes: None,
+        secret_refs: Default::default(),
     };
 
     servers.insert(name.clone(), new_entry);
@@ -426,7 +429,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                         "type": "stdio",
                         "command": command,
                         "args": args,
-                        "env": env,
+                        "env": json_with_secret_refs(env.as_ref(), &cfg.secret_refs),
                         "env_vars": env_vars,
                         "cwd": cwd,
                     }),
@@ -440,7 +443,10 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                             "type": "streamable_http",
                             "url": url,
                             "bearer_token_env_var": bearer_token_env_var,
-                            "http_headers": http_headers,
+                            "http_headers": json_with_secret_refs(
+                                http_headers.as_ref(),
+                                &cfg.secret_refs,
+                            ),
                             "env_http_headers": env_http_headers,
                         })
                     }
@@ -665,7 +671,7 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
                 "type": "stdio",
                 "command": command,
                 "args": args,
-                "env": env,
+                "env": json_with_secret_refs(env.as_ref(), &server.secret_refs),
                 "env_vars": env_vars,
                 "cwd": cwd,
             }),
@@ -678,7 +684,7 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
                 "type": "streamable_http",
                 "url": url,
                 "bearer_token_env_var": bearer_token_env_var,
-                "http_headers": http_headers,
+                "http_headers": json_with_secret_refs(http_headers.as_ref(), &server.secret_refs),
                 "env_http_headers": env_http_headers,
             }),
         };
@@ -837,3 +843,27 @@ fn format_mcp_status(config: &McpServerConfig) -> String {
         "disabled".to_string()
     }
 }
+
+/// `env` or `http_headers` for JSON output. Values that come from a stored
+/// secret are shown as their `{ secret = "NAME" }` reference from config.toml
+/// instead of the resolved secret.
+fn json_with_secret_refs(
+    values: Option<&HashMap<String, String>>,
+    secret_refs: &BTreeMap<String, SecretName>,
+) -> serde_json::Value {
+    if values.is_none() && secret_refs.is_empty() {
+        return serde_json::Value::Null;
+    }
+    let mut map: serde_json::Map<String, serde_json::Value> = values
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| (key.clone(), serde_json::json!(value)))
+        .collect();
+    for (key, name) in secret_refs {
+        map.insert(
+            key.clone(),
+            serde_json::json!({ "secret": name.to_string() }),
+        );
+    }
+    serde_json::Value::Object(map)
+}
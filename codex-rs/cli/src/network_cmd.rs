@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use clap::Parser;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::network_proxy::CoreConfigLoader;
+use codex_core::network_proxy::NETWORK_PROXY_CA_DIR;
+use codex_core::state_db::NetworkAuditDecision;
+use codex_core::state_db::NetworkAuditEntry;
+use codex_core::state_db::NetworkAuditQuery;
+use codex_core::state_db::network_audit_log;
+use codex_network_proxy::NetworkProxy;
+use codex_network_proxy::NetworkProxyState;
+use codex_protocol::ThreadId;
+use serde_json::json;
+
+use crate::logs_cmd::parse_time;
+
+/// Inspect and run the network proxy configured under `[network_proxy]`.
+#[derive(Debug, Parser)]
+pub struct NetworkCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: NetworkSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum NetworkSubcommand {
+    /// Run the proxy standalone on the configured `proxy_url`, `socks_url`
+    /// and `admin_url`. Sessions start their own proxy; this is for using the
+    /// policy outside of Codex.
+    Proxy,
+
+    /// Show the requests session proxies allowed and blocked, oldest first
+    /// (requires the `sqlite` feature).
+    Log(NetworkLogArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct NetworkLogArgs {
+    /// Only include requests of this thread.
+    #[arg(long, value_name = "THREAD_ID", value_parser = parse_thread_id)]
+    pub thread: Option<ThreadId>,
+
+    /// Only include requests to this host.
+    #[arg(long, value_name = "HOST")]
+    pub host: Option<String>,
+
+    /// Only include blocked requests.
+    #[arg(long)]
+    pub blocked: bool,
+
+    /// Only include requests from this time on: RFC3339, unix seconds, or a
+    /// duration ago such as `30m`, `2h` or `7d`.
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    pub since: Option<i64>,
+
+    /// Show the most recent N matching requests.
+    #[arg(long, short = 'n', value_name = "N", default_value_t = 100)]
+    pub limit: usize,
+
+    /// Print one JSON object per line.
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub async fn run_network(cmd: NetworkCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides)
+        .await
+        .context("failed to load configuration")?;
+    match cmd.subcommand {
+        NetworkSubcommand::Proxy => run_proxy(&config).await,
+        NetworkSubcommand::Log(args) => run_log(&config, &args).await,
+    }
+}
+
+async fn run_log(config: &Config, args: &NetworkLogArgs) -> Result<()> {
+    let query = NetworkAuditQuery {
+        thread_id: args.thread,
+        host: args.host.clone(),
+        decision: args.blocked.then_some(NetworkAuditDecision::Deny),
+        since: args
+            .since
+            .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0)),
+        limit: args.limit,
+    };
+    let mut entries = network_audit_log(config, &query).await?;
+    entries.reverse();
+    if entries.is_empty() && !args.json {
+        println!("No network requests recorded.");
+    }
+    for entry in &entries {
+        if args.json {
+            println!("{}", entry_json(entry));
+        } else {
+            println!("{}", format_entry(entry));
+        }
+    }
+    Ok(())
+}
+
+fn parse_thread_id(raw: &str) -> Result<ThreadId, String> {
+    ThreadId::from_string(raw).map_err(|err| format!("invalid thread id `{raw}`: {err}"))
+}
+
+fn format_entry(entry: &NetworkAuditEntry) -> String {
+    let timestamp = entry.ts.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let method = entry.method.as_deref().unwrap_or("-");
+    let bytes = |bytes: Option<i64>| bytes.map_or_else(|| "?".to_string(), ToString::to_string);
+    let thread_id = entry
+        .thread_id
+        .map_or_else(|| "-".to_string(), |thread_id| thread_id.to_string());
+    let turn_id = entry.turn_id.as_deref().unwrap_or("-");
+    let call_id = entry.call_id.as_deref().unwrap_or("-");
+    let mut line = format!(
+        "{timestamp} {:<5} {:<12} {method:<7} {} sent={} received={} [{thread_id} {turn_id} {call_id}]",
+        entry.decision.as_str(),
+        entry.protocol,
+        entry.host,
+        bytes(entry.bytes_sent),
+        bytes(entry.bytes_received),
+    );
+    if let Some(reason) = &entry.reason {
+        line.push_str(" - ");
+        line.push_str(reason);
+    }
+    line
+}
+
+fn entry_json(entry: &NetworkAuditEntry) -> serde_json::Value {
+    json!({
+        "ts": entry.ts.to_rfc3339_opts(SecondsFormat::Secs, true),
+        "thread_id": entry.thread_id.map(|thread_id| thread_id.to_string()),
+        "turn_id": entry.turn_id,
+        "call_id": entry.call_id,
+        "protocol": entry.protocol,
+        "host": entry.host,
+        "method": entry.method,
+        "decision": entry.decision.as_str(),
+        "reason": entry.reason,
+        "bytes_sent": entry.bytes_sent,
+        "bytes_received": entry.bytes_received,
+    })
+}
+
+async fn run_proxy(config: &Config) -> Result<()> {
+    if !config.network_proxy.enabled {
+        anyhow::bail!("the network proxy is disabled; set `[network_proxy] enabled = true`");
+    }
+    let state = NetworkProxyState::new(Arc::new(CoreConfigLoader::for_config(config))).await?;
+    let handle = NetworkProxy::builder()
+        .state(Arc::new(state))
+        .ca_dir(config.codex_home.join(NETWORK_PROXY_CA_DIR))
+        .build()
+        .await?
+        .run()
+        .await?;
+    if let Some(addr) = handle.http_addr() {
+        eprintln!("HTTP proxy listening on {addr}");
+    }
+    if let Some(addr) = handle.socks_addr() {
+        eprintln!("SOCKS5 proxy listening on {addr}");
+    }
+    if let Some(addr) = handle.admin_addr() {
+        eprintln!("admin API listening on {addr}");
+    }
+    if let Some(path) = handle.ca_cert_path() {
+        eprintln!("intercepting HTTPS; clients must trust {}", path.display());
+    }
+    handle.wait().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn format_entry_shows_decision_and_origin() {
+        let thread_id =
+            ThreadId::from_string("67e55044-10b1-426f-9247-bb680e5fe0c8").expect("thread id");
+        let entry = NetworkAuditEntry {
+            ts: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).expect("timestamp"),
+            thread_id: Some(thread_id),
+            turn_id: Some("3".to_string()),
+            call_id: Some("call_1".to_string()),
+            protocol: "http-connect".to_string(),
+            host: "pypi.org".to_string(),
+            method: Some("CONNECT".to_string()),
+            decision: NetworkAuditDecision::Deny,
+            reason: Some("not_allowed".to_string()),
+            bytes_sent: None,
+            bytes_received: None,
+        };
+
+        assert_eq!(
+            format_entry(&entry),
+            "2023-11-14T22:13:20Z deny  http-connect CONNECT pypi.org sent=? received=? [67e55044-10b1-426f-9247-bb680e5fe0c8 3 call_1] - not_allowed"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::io::IsTerminal;
+use std::io::Read;
+
+use anyhow::Context;
+use anyhow::Result;
+use clap::Args;
+use clap::Parser;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_secrets::PASSPHRASE_ENV_VAR;
+use codex_secrets::PASSPHRASE_FD_ENV_VAR;
+use codex_secrets::SecretName;
+use codex_secrets::SecretScope;
+use codex_secrets::SecretsBackendKind;
+use codex_secrets::SecretsManager;
+
+/// Manage secrets injected via `[secrets].inject`.
+#[derive(Debug, Parser)]
+pub struct SecretsCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SecretsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SecretsSubcommand {
+    /// Store a secret. The value is read from stdin so it never lands in
+    /// shell history.
+    Set(SecretArgs),
+
+    /// Print a secret value.
+    Get(SecretArgs),
+
+    /// List secret names for the global scope and the current environment.
+    List(SecretsListArgs),
+
+    /// Remove a secret.
+    #[clap(visible_alias = "remove")]
+    Rm(SecretArgs),
+
+    /// Re-encrypt stored secrets under a new key. The `passphrase` backend
+    /// reads the new passphrase from stdin; the `local` backend generates a
+    /// new key in the OS keyring.
+    Rekey,
+
+    /// Copy every secret from one backend to another.
+    Migrate(SecretsMigrateArgs),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackendArg {
+    Local,
+    Passphrase,
+}
+
+impl From<BackendArg> for SecretsBackendKind {
+    fn from(value: BackendArg) -> Self {
+        match value {
+            BackendArg::Local => SecretsBackendKind::Local,
+            BackendArg::Passphrase => SecretsBackendKind::Passphrase,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct SecretsMigrateArgs {
+    /// Backend to copy secrets into.
+    #[arg(long, value_enum)]
+    pub to: BackendArg,
+
+    /// Backend to copy secrets from. Defaults to `[secrets].backend`.
+    #[arg(long, value_enum)]
+    pub from: Option<BackendArg>,
+}
+
+#[derive(Debug, Args)]
+pub struct ScopeArgs {
+    /// Use the global scope, shared by every repository.
+    #[arg(long, conflicts_with = "env")]
+    pub global: bool,
+
+    /// Use the given environment instead of the one derived from the current
+    /// directory.
+    #[arg(long, value_name = "ENVIRONMENT_ID")]
+    pub env: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct SecretArgs {
+    /// Secret name, e.g. `NPM_TOKEN`.
+    #[arg(value_name = "NAME")]
+    pub name: String,
+
+    #[clap(flatten)]
+    pub scope: ScopeArgs,
+}
+
+#[derive(Debug, Parser)]
+pub struct SecretsListArgs {
+    #[clap(flatten)]
+    pub scope: ScopeArgs,
+
+    /// List secrets of every environment.
+    #[arg(long, conflicts_with_all = ["global", "env"])]
+    pub all: bool,
+}
+
+pub async fn run_secrets(cmd: SecretsCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    // A referenced secret may not be stored yet; storing it is what this
+    // command is for.
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides {
+            skip_secret_refs: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+    let manager = SecretsManager::new(config.codex_home.clone(), config.secrets.backend);
+
+    match cmd.subcommand {
+        SecretsSubcommand::Set(args) => {
+            let name = SecretName::new(&args.name)?;
+            let scope = resolve_scope(&args.scope, &config)?;
+            let value = read_stdin_value(&format!("the value for {name}"))?;
+            manager.set(&scope, &name, &value)?;
+            println!("Stored {name} ({scope}).");
+        }
+        SecretsSubcommand::Get(args) => {
+            let name = SecretName::new(&args.name)?;
+            let scope = resolve_scope(&args.scope, &config)?;
+            match manager.get(&scope, &name)? {
+                Some(value) => println!("{value}"),
+                None => anyhow::bail!("secret {name} is not set ({scope})"),
+            }
+        }
+        SecretsSubcommand::List(args) => {
+            let filters = if args.all {
+                vec![None]
+            } else if args.scope.global || args.scope.env.is_some() {
+                vec![Some(resolve_scope(&args.scope, &config)?)]
+            } else {
+                vec![
+                    Some(SecretScope::Global),
+                    Some(SecretScope::for_cwd(&config.cwd)?),
+                ]
+            };
+            let mut entries = Vec::new();
+            for filter in &filters {
+                entries.extend(manager.list(filter.as_ref())?);
+            }
+            entries.sort_by(|a, b| {
+                (a.scope.environment_id(), a.name.as_str())
+                    .cmp(&(b.scope.environment_id(), b.name.as_str()))
+            });
+            if entries.is_empty() {
+                println!("No secrets stored.");
+            }
+            for entry in entries {
+                println!("{}\t{}", entry.scope, entry.name);
+            }
+        }
+        SecretsSubcommand::Rm(args) => {
+            let name = SecretName::new(&args.name)?;
+            let scope = resolve_scope(&args.scope, &config)?;
+            if manager.delete(&scope, &name)? {
+                println!("Removed {name} ({scope}).");
+            } else {
+                anyhow::bail!("secret {name} is not set ({scope})");
+            }
+        }
+        SecretsSubcommand::Rekey => match config.secrets.backend {
+            SecretsBackendKind::Passphrase => {
+                let new_passphrase = read_new_passphrase()?;
+                manager.rekey(Some(&new_passphrase))?;
+                println!(
+                    "Re-encrypted secrets with the new passphrase. Update {PASSPHRASE_ENV_VAR} (or {PASSPHRASE_FD_ENV_VAR}) before the next run."
+                );
+            }
+            SecretsBackendKind::Local => {
+                manager.rekey(None)?;
+                println!("Re-encrypted secrets with a new key stored in the OS keyring.");
+            }
+        },
+        SecretsSubcommand::Migrate(args) => {
+            let from = args
+                .from
+                .map_or(config.secrets.backend, SecretsBackendKind::from);
+            let to = SecretsBackendKind::from(args.to);
+            anyhow::ensure!(from != to, "source and target backend are both `{from}`");
+            let source = SecretsManager::new(config.codex_home.clone(), from);
+            let target = SecretsManager::new(config.codex_home.clone(), to);
+            let copied = source.migrate_to(&target)?;
+            println!("Copied {copied} secret(s) from the `{from}` backend to `{to}`.");
+            if config.secrets.backend != to {
+                println!(
+                    "Set `backend = \"{to}\"` under [secrets] in config.toml to start using it."
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `--global`, `--env <ID>`, or the environment of the current repository.
+fn resolve_scope(args: &ScopeArgs, config: &Config) -> Result<SecretScope> {
+    if args.global {
+        return Ok(SecretScope::Global);
+    }
+    match &args.env {
+        Some(environment_id) => SecretScope::environment(environment_id.clone()),
+        None => SecretScope::for_cwd(&config.cwd),
+    }
+}
+
+fn read_stdin_value(what: &str) -> Result<String> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        // Typed values are read without echo so they never show on screen.
+        let value = rpassword::prompt_password(format!("Enter {what}: "))
+            .with_context(|| format!("failed to read {what} from the terminal"))?;
+        anyhow::ensure!(!value.is_empty(), "{what} must not be empty");
+        return Ok(value);
+    }
+    let mut raw = String::new();
+    stdin
+        .read_to_string(&mut raw)
+        .with_context(|| format!("failed to read {what} from stdin"))?;
+    let value = trim_trailing_newline(&raw);
+    anyhow::ensure!(!value.is_empty(), "{what} must not be empty");
+    Ok(value.to_string())
+}
+
+/// The new passphrase for `rekey`. Typed passphrases are asked for twice, so a
+/// typo cannot lock the store under a passphrase nobody knows.
+fn read_new_passphrase() -> Result<String> {
+    const WHAT: &str = "the new secrets passphrase";
+    let passphrase = read_stdin_value(WHAT)?;
+    if std::io::stdin().is_terminal() {
+        let confirmation = rpassword::prompt_password("Confirm the new secrets passphrase: ")
+            .with_context(|| format!("failed to read {WHAT} from the terminal"))?;
+        anyhow::ensure!(
+            confirmation == passphrase,
+            "passphrases do not match; the secrets were not re-encrypted"
+        );
+    }
+    Ok(passphrase)
+}
+
+/// Drop the single trailing newline added by `echo` or a terminal, keeping any
+/// other whitespace that may be part of the secret.
+fn trim_trailing_newline(raw: &str) -> &str {
+    raw.strip_suffix("\r\n")
+        .or_else(|| raw.strip_suffix('\n'))
+        .unwrap_or(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn trims_only_one_trailing_newline() {
+        assert_eq!(trim_trailing_newline("token\n"), "token");
+        assert_eq!(trim_trailing_newline("token\r\n"), "token");
+        assert_eq!(trim_trailing_newline("token \n\n"), "token \n");
+        assert_eq!(trim_trailing_newline("token"), "token");
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use clap::Parser;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::session_bundle::SessionImportOptions;
+use codex_core::session_bundle::export_session;
+use codex_core::session_bundle::import_session;
+
+/// Move sessions between machines as portable bundles.
+#[derive(Debug, Parser)]
+pub struct SessionCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionSubcommand {
+    /// Write a session, its metadata and memory to a single archive.
+    Export(SessionExportArgs),
+
+    /// Register a session archive so it can be resumed with `codex resume`.
+    Import(SessionImportArgs),
+}
+
+#[derive(Debug, Parser)]
+pub struct SessionExportArgs {
+    /// Session id (UUID) or thread name to export.
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Where to write the bundle. Defaults to `codex-session-<id>.zip` in the
+    /// current directory.
+    #[arg(long, short = 'o', value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub struct SessionImportArgs {
+    /// Path to a bundle written by `codex session export`.
+    #[arg(value_name = "BUNDLE")]
+    pub bundle: PathBuf,
+
+    /// Working directory to use for the imported session, when the repository
+    /// lives at a different path on this machine.
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+}
+
+pub async fn run_session(cmd: SessionCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides)
+        .await
+        .context("failed to load configuration")?;
+
+    match cmd.subcommand {
+        SessionSubcommand::Export(args) => {
+            let output = match args.output {
+                Some(output) => output,
+                None => PathBuf::from(default_bundle_name(&args.session_id)),
+            };
+            let exported =
+                export_session(config.codex_home.as_path(), &args.session_id, &output).await?;
+            println!(
+                "Exported session {} to {}",
+                exported.thread_id,
+                output.display()
+            );
+        }
+        SessionSubcommand::Import(args) => {
+            let cwd = args.cwd.map(|cwd| std::path::absolute(&cwd).unwrap_or(cwd));
+            let imported = import_session(
+                config.codex_home.as_path(),
+                &args.bundle,
+                &config.model_provider_id,
+                &SessionImportOptions { cwd },
+            )
+            .await?;
+            println!(
+                "Imported session {} (cwd {})",
+                imported.thread_id,
+                imported.cwd.display()
+            );
+            if !imported.state_db_updated {
+                println!("No state database found; memory, tags and folders were not restored.");
+            }
+            println!("Resume it with `codex resume {}`.", imported.thread_id);
+        }
+    }
+    Ok(())
+}
+
+/// Bundle file name for a session id or thread name, keeping it filesystem
+/// friendly.
+fn default_bundle_name(session_id: &str) -> String {
+    let sanitized: String = session_id
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("codex-session-{sanitized}.zip")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn default_bundle_name_sanitizes_thread_names() {
+        assert_eq!(
+            default_bundle_name("019a5d1c-aaaa-7bbb-8ccc-0123456789ab"),
+            "codex-session-019a5d1c-aaaa-7bbb-8ccc-0123456789ab.zip"
+        );
+        assert_eq!(
+            default_bundle_name(" fix/the build "),
+            "codex-session-fix-the-build.zip"
+        );
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use anyhow::Result;
+use chrono::Days;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use clap::Parser;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::types::ModelPrice;
+use codex_core::state_db::UsageGroupBy;
+use codex_core::state_db::UsageQuery;
+use codex_core::state_db::UsageReportRow;
+use codex_core::state_db::usage_report;
+use serde_json::json;
+
+const CSV_HEADER: &str = "group,turns,input_tokens,cached_input_tokens,output_tokens,reasoning_output_tokens,total_tokens,estimated_cost_usd";
+
+/// Report token usage recorded for past turns.
+#[derive(Debug, Parser)]
+pub struct UsageCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// How to group turns.
+    #[arg(long = "group-by", value_enum, default_value_t = UsageGroup::Day)]
+    pub group_by: UsageGroup,
+
+    /// Only include turns from this day on (YYYY-MM-DD, UTC).
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<NaiveDate>,
+
+    /// Only include turns up to and including this day (YYYY-MM-DD, UTC).
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<NaiveDate>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = UsageFormat::Text)]
+    pub format: UsageFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UsageGroup {
+    /// Calendar day (UTC).
+    Day,
+    /// Model that served the turn.
+    Model,
+    /// Git origin of the session, or its working directory outside git.
+    Repo,
+    /// Session id.
+    Thread,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum UsageFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+/// Usage totals for one report line.
+#[derive(Debug, Clone, PartialEq)]
+struct UsageSummary {
+    group: String,
+    turns: i64,
+    input_tokens: i64,
+    cached_input_tokens: i64,
+    output_tokens: i64,
+    reasoning_output_tokens: i64,
+    /// Estimated cost in USD, or `None` when a model in the group has no price.
+    estimated_cost: Option<f64>,
+}
+
+impl UsageSummary {
+    fn new(group: String) -> Self {
+        Self {
+            group,
+            turns: 0,
+            input_tokens: 0,
+            cached_input_tokens: 0,
+            output_tokens: 0,
+            reasoning_output_tokens: 0,
+            estimated_cost: Some(0.0),
+        }
+    }
+
+    fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    fn add(&mut self, other: &UsageSummary) {
+        self.turns += other.turns;
+        self.input_tokens += other.input_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_output_tokens += other.reasoning_output_tokens;
+        self.estimated_cost = self
+            .estimated_cost
+            .zip(other.estimated_cost)
+            .map(|(cost, other)| cost + other);
+    }
+}
+
+pub async fn run_usage(cmd: UsageCommand) -> Result<()> {
+    let overrides = cmd
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides)
+        .await
+        .context("failed to load configuration")?;
+
+    let query = UsageQuery {
+        group_by: match cmd.group_by {
+            UsageGroup::Day => UsageGroupBy::Day,
+            UsageGroup::Model => UsageGroupBy::Model,
+            UsageGroup::Repo => UsageGroupBy::Repo,
+            UsageGroup::Thread => UsageGroupBy::Thread,
+        },
+        since: cmd.since.map(|day| day.and_time(NaiveTime::MIN).and_utc()),
+        until: cmd
+            .until
+            .and_then(|day| day.checked_add_days(Days::new(1)))
+            .map(|day| day.and_time(NaiveTime::MIN).and_utc()),
+    };
+    let rows = usage_report(&config, &query).await?;
+    let summaries = summarize(&rows, cmd.group_by, &config.usage_prices);
+    let show_cost = !config.usage_prices.is_empty();
+    let output = match cmd.format {
+        UsageFormat::Text => render_text(&summaries, show_cost).join("\n"),
+        UsageFormat::Json => serde_json::to_string_pretty(&render_json(&summaries))?,
+        UsageFormat::Csv => render_csv(&summaries).join("\n"),
+    };
+    println!("{output}");
+    Ok(())
+}
+
+/// Fold per-model report rows into one summary per group, pricing each row
+/// with its model's rates.
+fn summarize(
+    rows: &[UsageReportRow],
+    group_by: UsageGroup,
+    prices: &HashMap<String, ModelPrice>,
+) -> Vec<UsageSummary> {
+    let mut summaries: Vec<UsageSummary> = Vec::new();
+    for row in rows {
+        let line = UsageSummary {
+            group: row.group.clone(),
+            turns: row.turns,
+            input_tokens: row.input_tokens,
+            cached_input_tokens: row.cached_input_tokens,
+            output_tokens: row.output_tokens,
+            reasoning_output_tokens: row.reasoning_output_tokens,
+            estimated_cost: prices
+                .get(&row.model)
+                .map(|price| estimate_cost(price, row)),
+        };
+        match summaries
+            .iter_mut()
+            .find(|summary| summary.group == row.group)
+        {
+            Some(summary) => summary.add(&line),
+            None => summaries.push(line),
+        }
+    }
+    // Days read best in order; everything else leads with the heaviest users.
+    if group_by != UsageGroup::Day {
+        summaries.sort_by(|a, b| {
+            b.total_tokens()
+                .cmp(&a.total_tokens())
+                .then_with(|| a.group.cmp(&b.group))
+        });
+    }
+    summaries
+}
+
+fn estimate_cost(price: &ModelPrice, row: &UsageReportRow) -> f64 {
+    let uncached_input = (row.input_tokens - row.cached_input_tokens).max(0) as f64;
+    let cached_input = row.cached_input_tokens as f64;
+    let output = row.output_tokens as f64;
+    (uncached_input * price.input
+        + cached_input * price.cached_input.unwrap_or(price.input)
+        + output * price.output)
+        / 1_000_000.0
+}
+
+fn render_text(summaries: &[UsageSummary], show_cost: bool) -> Vec<String> {
+    if summaries.is_empty() {
+        return vec!["No usage recorded.".to_string()];
+    }
+    let mut total = UsageSummary::new("TOTAL".to_string());
+    for summary in summaries {
+        total.add(summary);
+    }
+
+    let mut header = vec![
+        "GROUP".to_string(),
+        "TURNS".to_string(),
+        "INPUT".to_string(),
+        "CACHED".to_string(),
+        "OUTPUT".to_string(),
+        "REASONING".to_string(),
+        "TOTAL".to_string(),
+    ];
+    if show_cost {
+        header.push("COST (USD)".to_string());
+    }
+    let mut table = vec![header];
+    for summary in summaries.iter().chain(std::iter::once(&total)) {
+        let mut cells = vec![
+            summary.group.clone(),
+            summary.turns.to_string(),
+            summary.input_tokens.to_string(),
+            summary.cached_input_tokens.to_string(),
+            summary.output_tokens.to_string(),
+            summary.reasoning_output_tokens.to_string(),
+            summary.total_tokens().to_string(),
+        ];
+        if show_cost {
+            cells.push(
+                summary
+                    .estimated_cost
+                    .map_or_else(|| "-".to_string(), |cost| format!("{cost:.2}")),
+            );
+        }
+        table.push(cells);
+    }
+
+    let column_count = table[0].len();
+    let widths = (0..column_count)
+        .map(|column| {
+            table
+                .iter()
+                .map(|cells| cells[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    table
+        .iter()
+        .map(|cells| {
+            cells
+                .iter()
+                .enumerate()
+                .map(|(column, cell)| {
+                    let width = widths[column];
+                    if column == 0 {
+                        format!("{cell:<width$}")
+                    } else {
+                        format!("{cell:>width$}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+fn render_json(summaries: &[UsageSummary]) -> serde_json::Value {
+    summaries
+        .iter()
+        .map(|summary| {
+            json!({
+                "group": summary.group,
+                "turns": summary.turns,
+                "input_tokens": summary.input_tokens,
+                "cached_input_tokens": summary.cached_input_tokens,
+                "output_tokens": summary.output_tokens,
+                "reasoning_output_tokens": summary.reasoning_output_tokens,
+                "total_tokens": summary.total_tokens(),
+                "estimated_cost_usd": summary.estimated_cost,
+            })
+        })
+        .collect()
+}
+
+fn render_csv(summaries: &[UsageSummary]) -> Vec<String> {
+    std::iter::once(CSV_HEADER.to_string())
+        .chain(summaries.iter().map(|summary| {
+            format!(
+                "{},{},{},{},{},{},{},{}",
+                csv_field(&summary.group),
+                summary.turns,
+                summary.input_tokens,
+                summary.cached_input_tokens,
+                summary.output_tokens,
+                summary.reasoning_output_tokens,
+                summary.total_tokens(),
+                summary
+                    .estimated_cost
+                    .map(|cost| format!("{cost:.6}"))
+                    .unwrap_or_default(),
+            )
+        }))
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn row(group: &str, model: &str, input_tokens: i64, output_tokens: i64) -> UsageReportRow {
+        UsageReportRow {
+            group: group.to_string(),
+            model: model.to_string(),
+            turns: 1,
+            input_tokens,
+            cached_input_tokens: input_tokens / 2,
+            output_tokens,
+            reasoning_output_tokens: 0,
+        }
+    }
+
+    fn prices() -> HashMap<String, ModelPrice> {
+        HashMap::from([(
+            "gpt-5".to_string(),
+            ModelPrice {
+                input: 2.0,
+                cached_input: Some(0.5),
+                output: 10.0,
+            },
+        )])
+    }
+
+    #[test]
+    fn summarize_prices_each_model_and_merges_groups() {
+        let rows = vec![
+            row("2026-01-01", "gpt-5", 1_000_000, 100_000),
+            row("2026-01-01", "gpt-5", 2_000_000, 0),
+            row("2026-01-02", "local-model", 10, 5),
+        ];
+
+        let summaries = summarize(&rows, UsageGroup::Day, &prices());
+
+        assert_eq!(
+            summaries,
+            vec![
+                UsageSummary {
+                    group: "2026-01-01".to_string(),
+                    turns: 2,
+                    input_tokens: 3_000_000,
+                    cached_input_tokens: 1_500_000,
+                    output_tokens: 100_000,
+                    reasoning_output_tokens: 0,
+                    // 1.5M uncached * $2 + 1.5M cached * $0.5 + 0.1M output * $10.
+                    estimated_cost: Some(4.75),
+                },
+                UsageSummary {
+                    group: "2026-01-02".to_string(),
+                    turns: 1,
+                    input_tokens: 10,
+                    cached_input_tokens: 5,
+                    output_tokens: 5,
+                    reasoning_output_tokens: 0,
+                    estimated_cost: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn summarize_orders_non_day_groups_by_total_tokens() {
+        let rows = vec![
+            row("https://github.com/a/small.git", "gpt-5", 10, 0),
+            row("https://github.com/a/large.git", "gpt-5", 1_000, 0),
+        ];
+
+        let groups = summarize(&rows, UsageGroup::Repo, &HashMap::new())
+            .into_iter()
+            .map(|summary| summary.group)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            groups,
+            vec![
+                "https://github.com/a/large.git".to_string(),
+                "https://github.com/a/small.git".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_text_aligns_columns_and_adds_total() {
+        let summaries = summarize(
+            &[row("gpt-5", "gpt-5", 1_000_000, 100_000)],
+            UsageGroup::Model,
+            &prices(),
+        );
+
+        assert_eq!(
+            render_text(&summaries, true),
+            vec![
+                "GROUP  TURNS    INPUT  CACHED  OUTPUT  REASONING    TOTAL  COST (USD)".to_string(),
+                "gpt-5      1  1000000  500000  100000          0  1100000        2.25".to_string(),
+                "TOTAL      1  1000000  500000  100000          0  1100000        2.25".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_csv_quotes_fields_with_commas() {
+        let summaries = summarize(
+            &[row("/tmp/a,b", "gpt-5", 10, 2)],
+            UsageGroup::Repo,
+            &HashMap::new(),
+        );
+
+        assert_eq!(
+            render_csv(&summaries),
+            vec![
+                CSV_HEADER.to_string(),
+                "\"/tmp/a,b\",1,10,5,2,0,12,".to_string(),
+            ]
+        );
+    }
+}
@@ -164,3 +164,50 @@ async fn get_disabled_server_shows_single_line() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn json_output_shows_secret_references_instead_of_values() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let config_path = codex_home.path().join("config.toml");
+    std::fs::write(&config_path, "[secrets]\nbackend = \"passphrase\"\n")?;
+
+    let mut set = codex_command(codex_home.path())?;
+    set.env("CODEX_SECRETS_PASSPHRASE", "test-passphrase")
+        .args(["secrets", "set", "DOCS_TOKEN", "--global"])
+        .write_stdin("docs-token-value")
+        .assert()
+        .success();
+
+    let mut config = std::fs::read_to_string(&config_path)?;
+    config.push_str(
+        r#"
+[mcp_servers.docs]
+command = "docs-server"
+env = { TOKEN = { secret = "DOCS_TOKEN" }, LOG_LEVEL = "debug" }
+"#,
+    );
+    std::fs::write(&config_path, config)?;
+
+    let expected_env = json!({
+        "TOKEN": { "secret": "DOCS_TOKEN" },
+        "LOG_LEVEL": "debug",
+    });
+    for args in [
+        ["mcp", "list", "--json"].as_slice(),
+        ["mcp", "get", "docs", "--json"].as_slice(),
+    ] {
+        let mut cmd = codex_command(codex_home.path())?;
+        let output = cmd
+            .env("CODEX_SECRETS_PASSPHRASE", "test-passphrase")
+            .args(args)
+            .output()?;
+        assert!(output.status.success(), "{args:?} failed: {output:?}");
+        let stdout = String::from_utf8(output.stdout)?;
+        assert!(!stdout.contains("docs-token-value"), "{stdout}");
+        let parsed: JsonValue = serde_json::from_str(&stdout)?;
+        let server = parsed.get(0).unwrap_or(&parsed);
+        assert_eq!(server["transport"]["env"], expected_env);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use anyhow::Result;
+use predicates::str::contains;
+use tempfile::TempDir;
+
+const PASSPHRASE: &str = "test-passphrase";
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::new(codex_utils_cargo_bin::cargo_bin("codex")?);
+    cmd.env("CODEX_HOME", codex_home)
+        .env("CODEX_SECRETS_PASSPHRASE", PASSPHRASE);
+    Ok(cmd)
+}
+
+#[test]
+fn set_stores_a_secret_the_config_already_references() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        r#"
+[secrets]
+backend = "passphrase"
+
+[mcp_servers.docs]
+command = "docs-server"
+env = { TOKEN = { secret = "DOCS_TOKEN" } }
+"#,
+    )?;
+
+    codex_command(codex_home.path())?
+        .args(["mcp", "get", "docs"])
+        .assert()
+        .success()
+        .stdout(contains("docs (disabled: secret `DOCS_TOKEN` is not set"));
+
+    codex_command(codex_home.path())?
+        .args(["secrets", "set", "DOCS_TOKEN", "--global"])
+        .write_stdin("docs-token-value")
+        .assert()
+        .success()
+        .stdout(contains("Stored DOCS_TOKEN"));
+
+    codex_command(codex_home.path())?
+        .args(["mcp", "get", "docs"])
+        .assert()
+        .success()
+        .stdout(contains("env: TOKEN=*****"));
+
+    Ok(())
+}
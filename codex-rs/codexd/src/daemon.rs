@@ -1,16 +1,37 @@
+use crate::DaemonOptions;
+use crate::journal::DEFAULT_JOURNAL_MAX_EVENTS;
+use crate::journal::EventJournal;
+use crate::journal::JournalRecovery;
 use crate::protocol::ActiveTurnSnapshot;
+use crate::protocol::CodexdCommandParams;
 use crate::protocol::CodexdEventEnvelope;
 use crate::protocol::CodexdEventPayload;
+use crate::protocol::CodexdGapParams;
 use crate::protocol::CodexdSnapshotResponse;
 use crate::protocol::CodexdSubscribeParams;
 use crate::protocol::CodexdSubscribeResponse;
+use crate::protocol::CodexdUsageResponse;
 use crate::protocol::HubNotification;
+use crate::protocol::PendingApprovalSnapshot;
+use crate::protocol::RateLimitsUsageSnapshot;
+use crate::protocol::RuntimeApprovalRequestedParams;
+use crate::protocol::RuntimeApprovalResolvedParams;
+use crate::protocol::RuntimeCommand;
+use crate::protocol::RuntimeCommandParams;
 use crate::protocol::RuntimeEventParams;
+use crate::protocol::RuntimeHeartbeatParams;
 use crate::protocol::RuntimeRegisterParams;
 use crate::protocol::RuntimeSnapshot;
+use crate::protocol::RuntimeStaleReason;
 use crate::protocol::RuntimeUnregisterParams;
 use crate::protocol::RuntimeUpdateMetadataParams;
+use crate::protocol::RuntimeUsageSnapshot;
+use crate::protocol::ThreadUsageSnapshot;
 use anyhow::Context;
+use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
+use codex_app_server_protocol::RateLimitSnapshot;
+use codex_app_server_protocol::ThreadTokenUsage;
+use codex_app_server_protocol::TokenUsageBreakdown;
 use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
 use std::collections::BTreeMap;
@@ -19,6 +40,8 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
@@ -31,20 +54,41 @@ use tracing::warn;
 
 const CODEXD_SUBSCRIBE_METHOD: &str = "codexd/subscribe";
 const CODEXD_SNAPSHOT_METHOD: &str = "codexd/snapshot";
+const CODEXD_USAGE_METHOD: &str = "codexd/usage";
+const CODEXD_COMMAND_METHOD: &str = "codexd/command";
 const CODEXD_EVENT_METHOD: &str = "codexd/event";
+const CODEXD_GAP_METHOD: &str = "codexd/gap";
 const RUNTIME_EVENT_METHOD: &str = "codexd/runtime/event";
 const RUNTIME_REGISTER_METHOD: &str = "codexd/runtime/register";
 const RUNTIME_UNREGISTER_METHOD: &str = "codexd/runtime/unregister";
 const RUNTIME_UPDATE_METADATA_METHOD: &str = "codexd/runtime/updateMetadata";
+const RUNTIME_COMMAND_METHOD: &str = "codexd/runtime/command";
+const RUNTIME_APPROVAL_REQUESTED_METHOD: &str = "codexd/runtime/approvalRequested";
+const RUNTIME_APPROVAL_RESOLVED_METHOD: &str = "codexd/runtime/approvalResolved";
+const RUNTIME_HEARTBEAT_METHOD: &str = "codexd/runtime/heartbeat";
 const LAUNCHD_SOCKET_NAME: &str = "codexd";
-
-#[derive(Debug, Clone)]
+/// How long runtimes restored from the journal may go without reconnecting
+/// before the daemon treats them as gone.
+const RESTORED_RUNTIME_GRACE: Duration = Duration::from_secs(30);
+/// How long a routed command may wait for the owning runtime to answer.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const COMMAND_ERROR_CODE: i64 = -32000;
+/// How often the daemon checks for exited or silent runtimes.
+const STALE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default)]
 struct RuntimeState {
     pid: Option<u32>,
     session_source: Option<String>,
     cwd: Option<String>,
     display_name: Option<String>,
-    active_turns: BTreeMap<String, String>,
+    /// Keyed by turn id.
+    active_turns: BTreeMap<String, ActiveTurnSnapshot>,
+    /// Keyed by `(thread_id, item_id)`.
+    pending_approvals: BTreeMap<(String, String), PendingApprovalSnapshot>,
+    /// Keyed by thread id. Not checkpointed: token usage reports carry
+    /// cumulative totals, so the next report restores anything compacted away.
+    thread_usage: BTreeMap<String, ThreadUsageSnapshot>,
 }
 
 impl RuntimeState {
@@ -55,6 +99,28 @@ impl RuntimeState {
             cwd: params.cwd.clone(),
             display_name: params.display_name.clone(),
             active_turns: BTreeMap::new(),
+            pending_approvals: BTreeMap::new(),
+            thread_usage: BTreeMap::new(),
+        }
+    }
+
+    fn from_snapshot(snapshot: &RuntimeSnapshot) -> Self {
+        Self {
+            pid: snapshot.pid,
+            session_source: snapshot.session_source.clone(),
+            cwd: snapshot.cwd.clone(),
+            display_name: snapshot.display_name.clone(),
+            active_turns: snapshot
+                .active_turns
+                .iter()
+                .map(|turn| (turn.turn_id.clone(), turn.clone()))
+                .collect(),
+            pending_approvals: snapshot
+                .pending_approvals
+                .iter()
+                .map(|approval| (approval_key(approval), approval.clone()))
+                .collect(),
+            thread_usage: BTreeMap::new(),
         }
     }
 
@@ -71,15 +137,88 @@ impl RuntimeState {
             .or_else(|| self.display_name.clone());
     }
 
+    /// Applies turn lifecycle changes and returns approvals that were still
+    /// pending for a turn that just finished.
+    fn apply_turn_notification(
+        &mut self,
+        notification: &HubNotification,
+    ) -> Vec<PendingApprovalSnapshot> {
+        if let Some(turn) = parse_active_turn_started(notification) {
+            self.active_turns.insert(turn.turn_id.clone(), turn);
+        }
+
+        let Some(turn_id) = parse_active_turn_completed(notification) else {
+            return Vec::new();
+        };
+        self.active_turns.remove(&turn_id);
+
+        let (abandoned, remaining): (BTreeMap<_, _>, BTreeMap<_, _>) =
+            std::mem::take(&mut self.pending_approvals)
+                .into_iter()
+                .partition(|(_, approval)| approval.turn_id == turn_id);
+        self.pending_approvals = remaining;
+        abandoned.into_values().collect()
+    }
+
+    fn apply_usage_notification(&mut self, notification: &HubNotification, now: i64) {
+        let Some((thread_id, token_usage)) = parse_thread_token_usage(notification) else {
+            return;
+        };
+        self.thread_usage.insert(
+            thread_id.clone(),
+            ThreadUsageSnapshot {
+                thread_id,
+                total: token_usage.total,
+                last: token_usage.last,
+                model_context_window: token_usage.model_context_window,
+                updated_at: now,
+            },
+        );
+    }
+
+    fn as_usage_snapshot(&self, runtime_id: String) -> Option<RuntimeUsageSnapshot> {
+        if self.thread_usage.is_empty() {
+            return None;
+        }
+
+        let mut total = empty_token_usage();
+        for thread in self.thread_usage.values() {
+            add_token_usage(&mut total, &thread.total);
+        }
+        let mut threads: Vec<ThreadUsageSnapshot> = self.thread_usage.values().cloned().collect();
+        threads.sort_by_key(|thread| std::cmp::Reverse(thread.total.total_tokens));
+
+        Some(RuntimeUsageSnapshot {
+            runtime_id,
+            display_name: self.display_name.clone(),
+            cwd: self.cwd.clone(),
+            total,
+            threads,
+        })
+    }
+
+    /// Returns `false` when the same approval is already recorded, which
+    /// happens when a producer re-announces its inbox after reconnecting.
+    fn insert_pending_approval(&mut self, approval: PendingApprovalSnapshot) -> bool {
+        let key = approval_key(&approval);
+        if self.pending_approvals.get(&key) == Some(&approval) {
+            return false;
+        }
+        self.pending_approvals.insert(key, approval);
+        true
+    }
+
+    fn remove_pending_approval(&mut self, thread_id: &str, item_id: &str) -> bool {
+        self.pending_approvals
+            .remove(&(thread_id.to_string(), item_id.to_string()))
+            .is_some()
+    }
+
     fn as_snapshot(&self, runtime_id: String) -> RuntimeSnapshot {
-        let active_turns = self
-            .active_turns
-            .iter()
-            .map(|(turn_id, thread_id)| ActiveTurnSnapshot {
-                thread_id: thread_id.clone(),
-                turn_id: turn_id.clone(),
-            })
-            .collect();
+        let active_turns = self.active_turns.values().cloned().collect();
+        let mut pending_approvals: Vec<PendingApprovalSnapshot> =
+            self.pending_approvals.values().cloned().collect();
+        pending_approvals.sort_by_key(|approval| approval.started_at);
 
         RuntimeSnapshot {
             runtime_id,
@@ -88,19 +227,245 @@ impl RuntimeState {
             cwd: self.cwd.clone(),
             display_name: self.display_name.clone(),
             active_turns,
+            pending_approvals,
         }
     }
 }
 
-#[derive(Default)]
+fn approval_key(approval: &PendingApprovalSnapshot) -> (String, String) {
+    (approval.thread_id.clone(), approval.item_id.clone())
+}
+
+fn empty_token_usage() -> TokenUsageBreakdown {
+    TokenUsageBreakdown {
+        total_tokens: 0,
+        input_tokens: 0,
+        cached_input_tokens: 0,
+        output_tokens: 0,
+        reasoning_output_tokens: 0,
+    }
+}
+
+fn add_token_usage(total: &mut TokenUsageBreakdown, usage: &TokenUsageBreakdown) {
+    total.total_tokens += usage.total_tokens;
+    total.input_tokens += usage.input_tokens;
+    total.cached_input_tokens += usage.cached_input_tokens;
+    total.output_tokens += usage.output_tokens;
+    total.reasoning_output_tokens += usage.reasoning_output_tokens;
+}
+
+/// Producer connection that commands for a runtime are delivered to.
+#[derive(Debug, Clone)]
+struct RuntimeRoute {
+    connection_id: u64,
+    writer_tx: mpsc::UnboundedSender<String>,
+}
+
+/// A `codexd/command` request waiting for the owning runtime to answer.
+struct PendingCommand {
+    producer_connection_id: u64,
+    consumer_tx: mpsc::UnboundedSender<String>,
+    request_id: Option<JsonValue>,
+    /// `(runtime_id, thread_id, item_id)` of the approval this command answers.
+    approval: Option<(String, String, String)>,
+}
+
 struct DaemonState {
     next_connection_id: u64,
+    next_command_id: u64,
     seq: u64,
     runtimes: BTreeMap<String, RuntimeState>,
     subscribers: HashMap<u64, mpsc::UnboundedSender<String>>,
+    runtime_routes: HashMap<String, RuntimeRoute>,
+    pending_commands: HashMap<u64, PendingCommand>,
+    journal: EventJournal,
+    /// Runtimes rebuilt from the journal that no producer has reclaimed yet.
+    restored_runtime_ids: HashSet<String>,
+    /// When each runtime's producer last sent anything, heartbeats included.
+    last_seen: HashMap<String, Instant>,
+    /// Metadata of runtimes reaped for missing heartbeats, with the producer
+    /// connection that owned them, so a late heartbeat can bring them back.
+    silenced_runtimes: HashMap<String, (u64, RuntimeRegisterParams)>,
+    rate_limits: Option<RateLimitsUsageSnapshot>,
 }
 
 impl DaemonState {
+    fn new(journal: EventJournal) -> Self {
+        Self {
+            next_connection_id: 0,
+            next_command_id: 0,
+            seq: 0,
+            runtimes: BTreeMap::new(),
+            subscribers: HashMap::new(),
+            runtime_routes: HashMap::new(),
+            pending_commands: HashMap::new(),
+            journal,
+            restored_runtime_ids: HashSet::new(),
+            last_seen: HashMap::new(),
+            silenced_runtimes: HashMap::new(),
+            rate_limits: None,
+        }
+    }
+
+    /// Rebuilds the runtime table and sequence counter from a journal that
+    /// survived a daemon restart.
+    fn restore(&mut self, recovery: JournalRecovery) {
+        self.seq = recovery.seq;
+        self.runtimes = recovery
+            .checkpoint_runtimes
+            .iter()
+            .map(|runtime| {
+                (
+                    runtime.runtime_id.clone(),
+                    RuntimeState::from_snapshot(runtime),
+                )
+            })
+            .collect();
+
+        for envelope in recovery.events_after_checkpoint {
+            match envelope.event {
+                CodexdEventPayload::RuntimeUpsert { runtime } => {
+                    self.runtimes.insert(
+                        runtime.runtime_id.clone(),
+                        RuntimeState::from_snapshot(&runtime),
+                    );
+                }
+                CodexdEventPayload::RuntimeRemoved { runtime_id } => {
+                    self.runtimes.remove(&runtime_id);
+                }
+                CodexdEventPayload::RuntimeNotification {
+                    runtime_id,
+                    notification,
+                } => {
+                    self.apply_notification_to_state(&runtime_id, &notification);
+                }
+                CodexdEventPayload::ApprovalRequested {
+                    runtime_id,
+                    approval,
+                } => {
+                    self.runtimes
+                        .entry(runtime_id)
+                        .or_default()
+                        .insert_pending_approval(approval);
+                }
+                CodexdEventPayload::ApprovalResolved {
+                    runtime_id,
+                    thread_id,
+                    item_id,
+                } => {
+                    if let Some(runtime) = self.runtimes.get_mut(&runtime_id) {
+                        runtime.remove_pending_approval(&thread_id, &item_id);
+                    }
+                }
+                // Always followed by the `RuntimeRemoved` that does the work.
+                CodexdEventPayload::RuntimeStale { .. } => {}
+            }
+        }
+
+        self.restored_runtime_ids = self.runtimes.keys().cloned().collect();
+        let now = Instant::now();
+        self.last_seen = self
+            .runtimes
+            .keys()
+            .map(|runtime_id| (runtime_id.clone(), now))
+            .collect();
+    }
+
+    /// Marks a runtime restored from the journal as owned by a live producer.
+    fn reclaim_runtime(&mut self, runtime_id: &str) {
+        self.restored_runtime_ids.remove(runtime_id);
+    }
+
+    /// Records that the runtime's producer is alive. Every producer message,
+    /// including heartbeats, counts.
+    fn mark_runtime_seen(&mut self, runtime_id: &str) {
+        self.reclaim_runtime(runtime_id);
+        self.last_seen
+            .insert(runtime_id.to_string(), Instant::now());
+    }
+
+    /// Whether `connection_id` registered the runtime and still routes its
+    /// commands. Producer messages for a runtime are only accepted from it.
+    fn runtime_owned_by(&self, runtime_id: &str, connection_id: u64) -> bool {
+        self.runtime_routes
+            .get(runtime_id)
+            .is_some_and(|owner| owner.connection_id == connection_id)
+    }
+
+    /// Keeps a runtime alive. Like command routing, only the connection that
+    /// registered the runtime speaks for it; heartbeats from any other
+    /// connection are ignored.
+    fn heartbeat(&mut self, runtime_id: &str, route: &RuntimeRoute) {
+        if self.runtimes.contains_key(runtime_id) {
+            if self.runtime_owned_by(runtime_id, route.connection_id) {
+                self.mark_runtime_seen(runtime_id);
+            }
+            return;
+        }
+        // A runtime reaped while its producer stayed connected (for example
+        // after a stalled event loop) is re-created from its last metadata.
+        // Any other unknown runtime registers again when it reconnects.
+        // Heartbeats from other connections leave the entry in place.
+        if self
+            .silenced_runtimes
+            .get(runtime_id)
+            .is_some_and(|(owner, _)| *owner == route.connection_id)
+            && let Some((_, params)) = self.silenced_runtimes.remove(runtime_id)
+        {
+            self.bind_runtime_route(runtime_id, route);
+            self.upsert_runtime_from_register(params);
+        }
+    }
+
+    /// Removes runtimes whose process has exited or whose producer has been
+    /// silent for longer than `stale_timeout`, announcing each with a
+    /// `runtimeStale` event before the usual `runtimeRemoved`.
+    fn reap_stale_runtimes(&mut self, now: Instant, stale_timeout: Duration) {
+        let stale: Vec<(String, RuntimeStaleReason)> = self
+            .runtimes
+            .iter()
+            .filter_map(|(runtime_id, runtime)| {
+                if runtime.pid.is_some_and(|pid| !process_is_alive(pid)) {
+                    return Some((runtime_id.clone(), RuntimeStaleReason::ProcessExited));
+                }
+                let last_seen = self.last_seen.get(runtime_id)?;
+                (now.saturating_duration_since(*last_seen) > stale_timeout)
+                    .then(|| (runtime_id.clone(), RuntimeStaleReason::HeartbeatTimeout))
+            })
+            .collect();
+
+        for (runtime_id, reason) in stale {
+            debug!("codexd reaping stale runtime {runtime_id}: {reason:?}");
+            if reason == RuntimeStaleReason::HeartbeatTimeout
+                && let Some(route) = self.runtime_routes.get(&runtime_id)
+                && let Some(runtime) = self.runtimes.get(&runtime_id)
+            {
+                let params = RuntimeRegisterParams {
+                    runtime_id: runtime_id.clone(),
+                    pid: runtime.pid,
+                    session_source: runtime.session_source.clone(),
+                    cwd: runtime.cwd.clone(),
+                    display_name: runtime.display_name.clone(),
+                };
+                self.silenced_runtimes
+                    .insert(runtime_id.clone(), (route.connection_id, params));
+            }
+            self.broadcast_event(CodexdEventPayload::RuntimeStale {
+                runtime_id: runtime_id.clone(),
+                reason,
+            });
+            self.unregister_runtime(&runtime_id);
+        }
+    }
+
+    fn expire_restored_runtimes(&mut self) {
+        let runtime_ids = std::mem::take(&mut self.restored_runtime_ids);
+        for runtime_id in runtime_ids {
+            debug!("codexd dropping restored runtime {runtime_id} that never reconnected");
+            self.unregister_runtime(&runtime_id);
+        }
+    }
+
     fn alloc_connection_id(&mut self) -> u64 {
         self.next_connection_id = self.next_connection_id.saturating_add(1);
         self.next_connection_id
@@ -119,6 +484,27 @@ impl DaemonState {
         }
     }
 
+    fn usage(&self) -> CodexdUsageResponse {
+        let mut runtimes: Vec<RuntimeUsageSnapshot> = self
+            .runtimes
+            .iter()
+            .filter_map(|(runtime_id, runtime)| runtime.as_usage_snapshot(runtime_id.clone()))
+            .collect();
+        runtimes.sort_by_key(|runtime| std::cmp::Reverse(runtime.total.total_tokens));
+
+        let mut total = empty_token_usage();
+        for runtime in &runtimes {
+            add_token_usage(&mut total, &runtime.total);
+        }
+
+        CodexdUsageResponse {
+            seq: self.seq,
+            total,
+            runtimes,
+            rate_limits: self.rate_limits.clone(),
+        }
+    }
+
     fn add_subscriber(
         &mut self,
         connection_id: u64,
@@ -134,16 +520,194 @@ impl DaemonState {
             ));
         }
 
+        // Replay is queued before the subscriber is registered so it cannot
+        // interleave with live events, which are all newer than `self.seq`.
+        if let Some(after_seq) = after_seq {
+            self.replay_events(&sender, after_seq);
+        }
+
         self.subscribers.insert(connection_id, sender);
         Ok(CodexdSubscribeResponse { seq: self.seq })
     }
 
+    fn replay_events(&self, sender: &mpsc::UnboundedSender<String>, after_seq: u64) {
+        if after_seq == self.seq {
+            return;
+        }
+
+        let oldest_retained_seq = self.journal.oldest_retained_seq();
+        let missing_events = match oldest_retained_seq {
+            Some(oldest) => oldest > after_seq.saturating_add(1),
+            None => true,
+        };
+
+        if missing_events {
+            let gap = CodexdGapParams {
+                requested_after_seq: after_seq,
+                oldest_retained_seq,
+                seq: self.seq,
+            };
+            if let Some(line) = encode_notification(CODEXD_GAP_METHOD, serde_json::json!(gap)) {
+                let _ = sender.send(line);
+            }
+        }
+
+        for envelope in self.journal.events_after(after_seq) {
+            if let Some(line) =
+                encode_notification(CODEXD_EVENT_METHOD, serde_json::json!(envelope))
+            {
+                let _ = sender.send(line);
+            }
+        }
+    }
+
     fn remove_subscriber(&mut self, connection_id: u64) {
         self.subscribers.remove(&connection_id);
     }
 
+    /// Routes commands for the runtime to `route`. Only registration (and the
+    /// owner's heartbeat restoring a reaped runtime) binds, so other producer
+    /// connections cannot take over a runtime's commands.
+    fn bind_runtime_route(&mut self, runtime_id: &str, route: &RuntimeRoute) {
+        self.runtime_routes
+            .insert(runtime_id.to_string(), route.clone());
+    }
+
+    /// Forwards a consumer command to the producer connection that owns the
+    /// runtime. The consumer is answered once the runtime replies, the runtime
+    /// disconnects, or the command times out.
+    fn route_command(
+        &mut self,
+        params: CodexdCommandParams,
+        consumer_tx: mpsc::UnboundedSender<String>,
+        request_id: Option<JsonValue>,
+    ) -> Result<u64, String> {
+        let Some(route) = self.runtime_routes.get(&params.runtime_id) else {
+            return Err(format!(
+                "runtime `{}` is not connected to codexd",
+                params.runtime_id
+            ));
+        };
+
+        self.next_command_id = self.next_command_id.saturating_add(1);
+        let command_id = self.next_command_id;
+        let approval = match &params.command {
+            RuntimeCommand::CommandExecutionApproval {
+                thread_id, item_id, ..
+            }
+            | RuntimeCommand::FileChangeApproval {
+                thread_id, item_id, ..
+            } => Some((
+                params.runtime_id.clone(),
+                thread_id.clone(),
+                item_id.clone(),
+            )),
+            RuntimeCommand::TurnInterrupt { .. } | RuntimeCommand::SendMessage { .. } => None,
+        };
+        let command_params = RuntimeCommandParams {
+            runtime_id: params.runtime_id.clone(),
+            command: params.command,
+        };
+        let line = serde_json::to_value(command_params)
+            .ok()
+            .and_then(|params| {
+                serde_json::to_string(&serde_json::json!({
+                    "id": command_id,
+                    "method": RUNTIME_COMMAND_METHOD,
+                    "params": params,
+                }))
+                .ok()
+            })
+            .ok_or_else(|| "failed to encode runtime command".to_string())?;
+
+        if route.writer_tx.send(line).is_err() {
+            return Err(format!(
+                "runtime `{}` disconnected before the command was delivered",
+                params.runtime_id
+            ));
+        }
+
+        self.pending_commands.insert(
+            command_id,
+            PendingCommand {
+                producer_connection_id: route.connection_id,
+                consumer_tx,
+                request_id,
+                approval,
+            },
+        );
+        Ok(command_id)
+    }
+
+    /// Apply a producer's answer to a routed command. Only the connection the command was
+    /// delivered to may answer it; command ids are sequential, so anyone else could guess them.
+    fn answer_command(
+        &mut self,
+        connection_id: u64,
+        command_id: u64,
+        outcome: Result<JsonValue, String>,
+    ) {
+        match self.pending_commands.get(&command_id) {
+            Some(pending) if pending.producer_connection_id == connection_id => {
+                self.complete_command(command_id, outcome);
+            }
+            Some(_) => {
+                warn!(
+                    "codexd ignored an answer to command {command_id} from connection {connection_id}"
+                );
+            }
+            None => {}
+        }
+    }
+
+    fn complete_command(&mut self, command_id: u64, outcome: Result<JsonValue, String>) {
+        let Some(pending) = self.pending_commands.remove(&command_id) else {
+            return;
+        };
+        // The runtime accepted the answer, so the approval is no longer
+        // waiting even if the producer never reports it as resolved.
+        if outcome.is_ok()
+            && let Some((runtime_id, thread_id, item_id)) = &pending.approval
+        {
+            self.resolve_approval(runtime_id, thread_id, item_id);
+        }
+        let Some(request_id) = pending.request_id else {
+            return;
+        };
+
+        let line = match outcome {
+            Ok(result) => encode_response(request_id, result),
+            Err(message) => encode_error(request_id, COMMAND_ERROR_CODE, message),
+        };
+        if let Some(line) = line {
+            let _ = pending.consumer_tx.send(line);
+        }
+    }
+
+    fn drop_producer_connection(&mut self, connection_id: u64) {
+        self.runtime_routes
+            .retain(|_, route| route.connection_id != connection_id);
+        self.silenced_runtimes
+            .retain(|_, (owner, _)| *owner != connection_id);
+
+        let orphaned: Vec<u64> = self
+            .pending_commands
+            .iter()
+            .filter(|(_, pending)| pending.producer_connection_id == connection_id)
+            .map(|(&command_id, _)| command_id)
+            .collect();
+        for command_id in orphaned {
+            self.complete_command(
+                command_id,
+                Err("runtime disconnected before answering the command".to_string()),
+            );
+        }
+    }
+
     fn upsert_runtime_from_register(&mut self, params: RuntimeRegisterParams) {
         let runtime_id = params.runtime_id.clone();
+        self.silenced_runtimes.remove(&runtime_id);
+        self.mark_runtime_seen(&runtime_id);
         let snapshot = {
             let runtime = self
                 .runtimes
@@ -167,6 +731,7 @@ impl DaemonState {
 
     fn update_runtime_metadata(&mut self, params: RuntimeUpdateMetadataParams) {
         let runtime_id = params.runtime_id.clone();
+        self.mark_runtime_seen(&runtime_id);
         let snapshot = {
             let runtime = self
                 .runtimes
@@ -177,6 +742,8 @@ impl DaemonState {
                     cwd: params.cwd.clone(),
                     display_name: params.display_name.clone(),
                     active_turns: BTreeMap::new(),
+                    pending_approvals: BTreeMap::new(),
+                    thread_usage: BTreeMap::new(),
                 });
             runtime.apply_metadata_update(&params);
             runtime.as_snapshot(runtime_id)
@@ -185,33 +752,82 @@ impl DaemonState {
         self.broadcast_event(CodexdEventPayload::RuntimeUpsert { runtime: snapshot });
     }
 
-    fn apply_runtime_notification(&mut self, params: RuntimeEventParams) {
-        let runtime = self
-            .runtimes
-            .entry(params.runtime_id.clone())
-            .or_insert_with(|| RuntimeState {
-                pid: None,
-                session_source: None,
-                cwd: None,
-                display_name: None,
-                active_turns: BTreeMap::new(),
+    /// Folds a runtime notification into turn, usage and rate-limit state and
+    /// returns approvals abandoned by a completed turn. Shared by live events
+    /// and journal replay.
+    fn apply_notification_to_state(
+        &mut self,
+        runtime_id: &str,
+        notification: &HubNotification,
+    ) -> Vec<PendingApprovalSnapshot> {
+        let now = crate::unix_timestamp_now();
+        if let Some(rate_limits) = parse_rate_limits_updated(notification) {
+            self.rate_limits = Some(RateLimitsUsageSnapshot {
+                runtime_id: runtime_id.to_string(),
+                rate_limits,
+                updated_at: now,
             });
-
-        if let Some((thread_id, turn_id)) = parse_active_turn_started(&params.notification) {
-            runtime.active_turns.insert(turn_id, thread_id);
         }
 
-        if let Some(turn_id) = parse_active_turn_completed(&params.notification) {
-            runtime.active_turns.remove(&turn_id);
-        }
+        let runtime = self.runtimes.entry(runtime_id.to_string()).or_default();
+        runtime.apply_usage_notification(notification, now);
+        runtime.apply_turn_notification(notification)
+    }
 
+    fn apply_runtime_notification(&mut self, params: RuntimeEventParams) {
+        self.mark_runtime_seen(&params.runtime_id);
+        let abandoned = self.apply_notification_to_state(&params.runtime_id, &params.notification);
+
+        for approval in abandoned {
+            self.broadcast_event(CodexdEventPayload::ApprovalResolved {
+                runtime_id: params.runtime_id.clone(),
+                thread_id: approval.thread_id,
+                item_id: approval.item_id,
+            });
+        }
         self.broadcast_event(CodexdEventPayload::RuntimeNotification {
             runtime_id: params.runtime_id,
             notification: params.notification,
         });
     }
 
+    fn request_approval(&mut self, params: RuntimeApprovalRequestedParams) {
+        self.mark_runtime_seen(&params.runtime_id);
+        let inserted = self
+            .runtimes
+            .entry(params.runtime_id.clone())
+            .or_default()
+            .insert_pending_approval(params.approval.clone());
+        if !inserted {
+            return;
+        }
+
+        self.broadcast_event(CodexdEventPayload::ApprovalRequested {
+            runtime_id: params.runtime_id,
+            approval: params.approval,
+        });
+    }
+
+    fn resolve_approval(&mut self, runtime_id: &str, thread_id: &str, item_id: &str) {
+        let removed = self
+            .runtimes
+            .get_mut(runtime_id)
+            .is_some_and(|runtime| runtime.remove_pending_approval(thread_id, item_id));
+        if !removed {
+            return;
+        }
+
+        self.broadcast_event(CodexdEventPayload::ApprovalResolved {
+            runtime_id: runtime_id.to_string(),
+            thread_id: thread_id.to_string(),
+            item_id: item_id.to_string(),
+        });
+    }
+
     fn unregister_runtime(&mut self, runtime_id: &str) {
+        self.reclaim_runtime(runtime_id);
+        self.last_seen.remove(runtime_id);
+        self.runtime_routes.remove(runtime_id);
         if self.runtimes.remove(runtime_id).is_none() {
             return;
         }
@@ -228,6 +844,14 @@ impl DaemonState {
             event: payload,
         };
 
+        let runtimes = &self.runtimes;
+        self.journal.append(envelope.clone(), || {
+            runtimes
+                .iter()
+                .map(|(runtime_id, runtime)| runtime.as_snapshot(runtime_id.clone()))
+                .collect()
+        });
+
         let Some(line) = encode_notification(CODEXD_EVENT_METHOD, serde_json::json!(envelope))
         else {
             return;
@@ -246,7 +870,11 @@ impl DaemonState {
     }
 }
 
-pub async fn run_daemon(codex_home: &Path, socket_path: Option<PathBuf>) -> anyhow::Result<()> {
+pub async fn run_daemon(
+    codex_home: &Path,
+    socket_path: Option<PathBuf>,
+    options: DaemonOptions,
+) -> anyhow::Result<()> {
     let fallback_socket_path = super::default_socket_path(codex_home);
     let socket_path = socket_path.unwrap_or(fallback_socket_path);
 
@@ -261,7 +889,44 @@ pub async fn run_daemon(codex_home: &Path, socket_path: Option<PathBuf>) -> anyh
         }
     };
 
-    let state = Arc::new(Mutex::new(DaemonState::default()));
+    let journal_path = EventJournal::journal_path(codex_home);
+    let state = match EventJournal::open(journal_path, DEFAULT_JOURNAL_MAX_EVENTS) {
+        Ok((journal, recovery)) => {
+            let mut state = DaemonState::new(journal);
+            state.restore(recovery);
+            state
+        }
+        Err(err) => {
+            warn!("codexd event journal unavailable; events will not survive restart: {err:#}");
+            DaemonState::new(EventJournal::in_memory(DEFAULT_JOURNAL_MAX_EVENTS))
+        }
+    };
+    let has_restored_runtimes = !state.restored_runtime_ids.is_empty();
+    let state = Arc::new(Mutex::new(state));
+
+    if has_restored_runtimes {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            tokio::time::sleep(RESTORED_RUNTIME_GRACE).await;
+            state.lock().await.expire_restored_runtimes();
+        });
+    }
+
+    {
+        let state = Arc::clone(&state);
+        let stale_timeout = options.stale_timeout;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(STALE_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                state
+                    .lock()
+                    .await
+                    .reap_stale_runtimes(Instant::now(), stale_timeout);
+            }
+        });
+    }
 
     loop {
         tokio::select! {
@@ -321,6 +986,10 @@ async fn handle_connection(
         }
     });
 
+    let route = RuntimeRoute {
+        connection_id,
+        writer_tx: writer_tx.clone(),
+    };
     let mut owned_runtime_ids = HashSet::<String>::new();
     let mut subscribed = false;
 
@@ -342,14 +1011,49 @@ async fn handle_connection(
             .and_then(JsonValue::as_str)
             .map(ToString::to_string)
         else {
+            // Producers answer routed commands with plain JSON-RPC responses.
+            if let Some(command_id) = value.get("id").and_then(JsonValue::as_u64) {
+                let outcome = match value.get("error") {
+                    Some(error) => Err(error
+                        .get("message")
+                        .and_then(JsonValue::as_str)
+                        .unwrap_or("runtime command failed")
+                        .to_string()),
+                    None => Ok(value
+                        .get("result")
+                        .cloned()
+                        .unwrap_or_else(|| serde_json::json!({}))),
+                };
+                state
+                    .lock()
+                    .await
+                    .answer_command(connection_id, command_id, outcome);
+            }
             continue;
         };
 
         let params = value.get("params").cloned();
         let request_id = value.get("id").cloned();
 
+        if method == CODEXD_COMMAND_METHOD {
+            let routed = handle_command_method(
+                Arc::clone(&state),
+                writer_tx.clone(),
+                params,
+                request_id.clone(),
+            )
+            .await;
+            if let (Err(message), Some(request_id)) = (routed, request_id)
+                && let Some(line) = encode_error(request_id, COMMAND_ERROR_CODE, message)
+            {
+                let _ = writer_tx.send(line);
+            }
+            continue;
+        }
+
         let result = match method.as_str() {
             CODEXD_SNAPSHOT_METHOD => handle_snapshot_method(Arc::clone(&state)).await,
+            CODEXD_USAGE_METHOD => handle_usage_method(Arc::clone(&state)).await,
             CODEXD_SUBSCRIBE_METHOD => {
                 handle_subscribe_method(
                     Arc::clone(&state),
@@ -361,24 +1065,37 @@ async fn handle_connection(
                 .await
             }
             RUNTIME_REGISTER_METHOD => {
-                handle_runtime_register_method(Arc::clone(&state), params, &mut owned_runtime_ids)
-                    .await
-            }
-            RUNTIME_UPDATE_METADATA_METHOD => {
-                handle_runtime_update_metadata_method(
+                handle_runtime_register_method(
                     Arc::clone(&state),
                     params,
+                    &route,
                     &mut owned_runtime_ids,
                 )
                 .await
             }
+            RUNTIME_UPDATE_METADATA_METHOD => {
+                handle_runtime_update_metadata_method(Arc::clone(&state), params, &route).await
+            }
             RUNTIME_EVENT_METHOD => {
-                handle_runtime_event_method(Arc::clone(&state), params, &mut owned_runtime_ids)
-                    .await
+                handle_runtime_event_method(Arc::clone(&state), params, &route).await
+            }
+            RUNTIME_APPROVAL_REQUESTED_METHOD => {
+                handle_runtime_approval_requested_method(Arc::clone(&state), params, &route).await
+            }
+            RUNTIME_APPROVAL_RESOLVED_METHOD => {
+                handle_runtime_approval_resolved_method(Arc::clone(&state), params, &route).await
+            }
+            RUNTIME_HEARTBEAT_METHOD => {
+                handle_runtime_heartbeat_method(Arc::clone(&state), params, &route).await
             }
             RUNTIME_UNREGISTER_METHOD => {
-                handle_runtime_unregister_method(Arc::clone(&state), params, &mut owned_runtime_ids)
-                    .await
+                handle_runtime_unregister_method(
+                    Arc::clone(&state),
+                    params,
+                    &route,
+                    &mut owned_runtime_ids,
+                )
+                .await
             }
             _ => Err(format!("unknown method `{method}`")),
         };
@@ -386,7 +1103,7 @@ async fn handle_connection(
         if let Some(request_id) = request_id {
             let line = match result {
                 Ok(result_value) => encode_response(request_id, result_value),
-                Err(message) => encode_error(request_id, -32000, message),
+                Err(message) => encode_error(request_id, COMMAND_ERROR_CODE, message),
             };
 
             if let Some(line) = line {
@@ -397,6 +1114,7 @@ async fn handle_connection(
 
     cleanup_connection(state, connection_id, owned_runtime_ids, subscribed).await;
 
+    drop(route);
     drop(writer_tx);
     let _ = writer_handle.await;
 
@@ -415,6 +1133,13 @@ async fn cleanup_connection(
         state.remove_subscriber(connection_id);
     }
 
+    // Runtimes another connection has since registered stay with it.
+    let owned_runtime_ids: Vec<String> = owned_runtime_ids
+        .into_iter()
+        .filter(|runtime_id| state.runtime_owned_by(runtime_id, connection_id))
+        .collect();
+    state.drop_producer_connection(connection_id);
+
     for runtime_id in owned_runtime_ids {
         state.unregister_runtime(&runtime_id);
     }
@@ -425,6 +1150,11 @@ async fn handle_snapshot_method(state: Arc<Mutex<DaemonState>>) -> Result<JsonVa
     serde_json::to_value(state.snapshot()).map_err(|err| err.to_string())
 }
 
+async fn handle_usage_method(state: Arc<Mutex<DaemonState>>) -> Result<JsonValue, String> {
+    let state = state.lock().await;
+    serde_json::to_value(state.usage()).map_err(|err| err.to_string())
+}
+
 async fn handle_subscribe_method(
     state: Arc<Mutex<DaemonState>>,
     connection_id: u64,
@@ -441,9 +1171,34 @@ async fn handle_subscribe_method(
     serde_json::to_value(response).map_err(|err| err.to_string())
 }
 
+async fn handle_command_method(
+    state: Arc<Mutex<DaemonState>>,
+    consumer_tx: mpsc::UnboundedSender<String>,
+    params: Option<JsonValue>,
+    request_id: Option<JsonValue>,
+) -> Result<(), String> {
+    let params: CodexdCommandParams = deserialize_params(params)?;
+
+    let command_id = state
+        .lock()
+        .await
+        .route_command(params, consumer_tx, request_id)?;
+
+    tokio::spawn(async move {
+        tokio::time::sleep(COMMAND_TIMEOUT).await;
+        state.lock().await.complete_command(
+            command_id,
+            Err("runtime did not answer the command in time".to_string()),
+        );
+    });
+
+    Ok(())
+}
+
 async fn handle_runtime_register_method(
     state: Arc<Mutex<DaemonState>>,
     params: Option<JsonValue>,
+    route: &RuntimeRoute,
     owned_runtime_ids: &mut HashSet<String>,
 ) -> Result<JsonValue, String> {
     let params: RuntimeRegisterParams = deserialize_params(params)?;
@@ -451,6 +1206,7 @@ async fn handle_runtime_register_method(
     owned_runtime_ids.insert(params.runtime_id.clone());
 
     let mut state = state.lock().await;
+    state.bind_runtime_route(&params.runtime_id, route);
     state.upsert_runtime_from_register(params);
     Ok(serde_json::json!({}))
 }
@@ -458,34 +1214,75 @@ async fn handle_runtime_register_method(
 async fn handle_runtime_update_metadata_method(
     state: Arc<Mutex<DaemonState>>,
     params: Option<JsonValue>,
-    owned_runtime_ids: &mut HashSet<String>,
+    route: &RuntimeRoute,
 ) -> Result<JsonValue, String> {
     let params: RuntimeUpdateMetadataParams = deserialize_params(params)?;
 
-    owned_runtime_ids.insert(params.runtime_id.clone());
-
     let mut state = state.lock().await;
-    state.update_runtime_metadata(params);
+    if state.runtime_owned_by(&params.runtime_id, route.connection_id) {
+        state.update_runtime_metadata(params);
+    }
     Ok(serde_json::json!({}))
 }
 
 async fn handle_runtime_event_method(
     state: Arc<Mutex<DaemonState>>,
     params: Option<JsonValue>,
-    owned_runtime_ids: &mut HashSet<String>,
+    route: &RuntimeRoute,
 ) -> Result<JsonValue, String> {
     let params: RuntimeEventParams = deserialize_params(params)?;
 
-    owned_runtime_ids.insert(params.runtime_id.clone());
+    let mut state = state.lock().await;
+    if state.runtime_owned_by(&params.runtime_id, route.connection_id) {
+        state.apply_runtime_notification(params);
+    }
+    Ok(serde_json::json!({}))
+}
+
+async fn handle_runtime_approval_requested_method(
+    state: Arc<Mutex<DaemonState>>,
+    params: Option<JsonValue>,
+    route: &RuntimeRoute,
+) -> Result<JsonValue, String> {
+    let params: RuntimeApprovalRequestedParams = deserialize_params(params)?;
+
+    let mut state = state.lock().await;
+    if state.runtime_owned_by(&params.runtime_id, route.connection_id) {
+        state.request_approval(params);
+    }
+    Ok(serde_json::json!({}))
+}
+
+async fn handle_runtime_approval_resolved_method(
+    state: Arc<Mutex<DaemonState>>,
+    params: Option<JsonValue>,
+    route: &RuntimeRoute,
+) -> Result<JsonValue, String> {
+    let params: RuntimeApprovalResolvedParams = deserialize_params(params)?;
 
     let mut state = state.lock().await;
-    state.apply_runtime_notification(params);
+    if state.runtime_owned_by(&params.runtime_id, route.connection_id) {
+        state.resolve_approval(&params.runtime_id, &params.thread_id, &params.item_id);
+    }
+    Ok(serde_json::json!({}))
+}
+
+async fn handle_runtime_heartbeat_method(
+    state: Arc<Mutex<DaemonState>>,
+    params: Option<JsonValue>,
+    route: &RuntimeRoute,
+) -> Result<JsonValue, String> {
+    let params: RuntimeHeartbeatParams = deserialize_params(params)?;
+
+    let mut state = state.lock().await;
+    state.heartbeat(&params.runtime_id, route);
     Ok(serde_json::json!({}))
 }
 
 async fn handle_runtime_unregister_method(
     state: Arc<Mutex<DaemonState>>,
     params: Option<JsonValue>,
+    route: &RuntimeRoute,
     owned_runtime_ids: &mut HashSet<String>,
 ) -> Result<JsonValue, String> {
     let params: RuntimeUnregisterParams = deserialize_params(params)?;
@@ -493,11 +1290,13 @@ async fn handle_runtime_unregister_method(
     owned_runtime_ids.remove(&params.runtime_id);
 
     let mut state = state.lock().await;
-    state.unregister_runtime(&params.runtime_id);
+    if state.runtime_owned_by(&params.runtime_id, route.connection_id) {
+        state.unregister_runtime(&params.runtime_id);
+    }
     Ok(serde_json::json!({}))
 }
 
-fn parse_active_turn_started(notification: &HubNotification) -> Option<(String, String)> {
+fn parse_active_turn_started(notification: &HubNotification) -> Option<ActiveTurnSnapshot> {
     if notification.method != "turn/started" {
         return None;
     }
@@ -506,8 +1305,23 @@ fn parse_active_turn_started(notification: &HubNotification) -> Option<(String,
     let thread_id = params.get("threadId")?.as_str()?.to_string();
     let turn = params.get("turn")?.as_object()?;
     let turn_id = turn.get("id")?.as_str()?.to_string();
-
-    Some((thread_id, turn_id))
+    let model = turn
+        .get("model")
+        .and_then(JsonValue::as_str)
+        .map(ToString::to_string);
+    // Prefer the producer's timestamp so replaying the journal after a
+    // restart does not reset elapsed times.
+    let started_at = turn
+        .get("startedAt")
+        .and_then(JsonValue::as_i64)
+        .unwrap_or_else(crate::unix_timestamp_now);
+
+    Some(ActiveTurnSnapshot {
+        thread_id,
+        turn_id,
+        model,
+        started_at: Some(started_at),
+    })
 }
 
 fn parse_active_turn_completed(notification: &HubNotification) -> Option<String> {
@@ -522,6 +1336,47 @@ fn parse_active_turn_completed(notification: &HubNotification) -> Option<String>
     Some(turn_id)
 }
 
+fn parse_thread_token_usage(notification: &HubNotification) -> Option<(String, ThreadTokenUsage)> {
+    if notification.method != "thread/tokenUsage/updated" {
+        return None;
+    }
+
+    // The TUI bridge may omit `turnId`, so only the fields the rollup needs
+    // are required.
+    let params = notification.params.as_ref()?.as_object()?;
+    let thread_id = params.get("threadId")?.as_str()?.to_string();
+    let token_usage = serde_json::from_value(params.get("tokenUsage")?.clone()).ok()?;
+
+    Some((thread_id, token_usage))
+}
+
+fn parse_rate_limits_updated(notification: &HubNotification) -> Option<RateLimitSnapshot> {
+    if notification.method != "account/rateLimits/updated" {
+        return None;
+    }
+
+    let params = notification.params.clone()?;
+    let notification: AccountRateLimitsUpdatedNotification = serde_json::from_value(params).ok()?;
+    Some(notification.rate_limits)
+}
+
+/// Signal 0 performs the permission and existence checks without delivering
+/// anything. `EPERM` means the process exists but belongs to another user.
+fn process_is_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return true;
+    };
+    if pid <= 0 {
+        return true;
+    }
+
+    // SAFETY: kill with signal 0 has no side effects on the target process.
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
 fn deserialize_params<T: DeserializeOwned>(params: Option<JsonValue>) -> Result<T, String> {
     let value = params.unwrap_or(JsonValue::Null);
     serde_json::from_value(value).map_err(|err| err.to_string())
@@ -639,3 +1494,635 @@ async fn launchd_listener() -> anyhow::Result<Option<UnixListener>> {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_app_server_protocol::CommandExecutionApprovalDecision;
+    use pretty_assertions::assert_eq;
+
+    fn register(state: &mut DaemonState, runtime_id: &str) {
+        state.upsert_runtime_from_register(RuntimeRegisterParams {
+            runtime_id: runtime_id.to_string(),
+            pid: None,
+            session_source: None,
+            cwd: None,
+            display_name: None,
+        });
+    }
+
+    fn drain(receiver: &mut mpsc::UnboundedReceiver<String>) -> Vec<JsonValue> {
+        let mut lines = Vec::new();
+        while let Ok(line) = receiver.try_recv() {
+            lines.push(serde_json::from_str(&line).expect("valid json line"));
+        }
+        lines
+    }
+
+    #[test]
+    fn subscribe_replays_retained_events_and_reports_gap() {
+        let mut state = DaemonState::new(EventJournal::in_memory(2));
+        for runtime_id in ["a", "b", "c"] {
+            register(&mut state, runtime_id);
+        }
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let response = state.add_subscriber(1, sender, Some(0)).expect("subscribe");
+        assert_eq!(response.seq, 3);
+
+        let lines = drain(&mut receiver);
+        let methods: Vec<&str> = lines
+            .iter()
+            .filter_map(|line| line.get("method").and_then(JsonValue::as_str))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![CODEXD_GAP_METHOD, CODEXD_EVENT_METHOD, CODEXD_EVENT_METHOD]
+        );
+        assert_eq!(
+            lines[0]["params"],
+            serde_json::json!({"requestedAfterSeq": 0, "oldestRetainedSeq": 2, "seq": 3})
+        );
+        assert_eq!(lines[1]["params"]["seq"], serde_json::json!(2));
+        assert_eq!(lines[2]["params"]["seq"], serde_json::json!(3));
+    }
+
+    #[test]
+    fn commands_are_routed_to_owning_producer_and_answered() {
+        let mut state = DaemonState::new(EventJournal::in_memory(16));
+        let (producer_tx, mut producer_rx) = mpsc::unbounded_channel();
+        let (consumer_tx, mut consumer_rx) = mpsc::unbounded_channel();
+        state.bind_runtime_route(
+            "rt",
+            &RuntimeRoute {
+                connection_id: 7,
+                writer_tx: producer_tx,
+            },
+        );
+
+        let command = crate::protocol::RuntimeCommand::TurnInterrupt {
+            thread_id: "th".to_string(),
+            turn_id: None,
+        };
+        let command_id = state
+            .route_command(
+                CodexdCommandParams {
+                    runtime_id: "rt".to_string(),
+                    command: command.clone(),
+                },
+                consumer_tx.clone(),
+                Some(serde_json::json!(42)),
+            )
+            .expect("route command");
+
+        let routed = drain(&mut producer_rx);
+        assert_eq!(
+            routed,
+            vec![serde_json::json!({
+                "id": command_id,
+                "method": RUNTIME_COMMAND_METHOD,
+                "params": {"runtimeId": "rt", "command": command},
+            })]
+        );
+
+        state.answer_command(7, command_id, Ok(serde_json::json!({})));
+        assert_eq!(
+            drain(&mut consumer_rx),
+            vec![serde_json::json!({"id": 42, "result": {}})]
+        );
+
+        let command_id = state
+            .route_command(
+                CodexdCommandParams {
+                    runtime_id: "rt".to_string(),
+                    command,
+                },
+                consumer_tx,
+                Some(serde_json::json!(43)),
+            )
+            .expect("route command");
+        assert_eq!(command_id, 2);
+        state.drop_producer_connection(7);
+        let answered = drain(&mut consumer_rx);
+        assert_eq!(answered[0]["id"], serde_json::json!(43));
+        assert!(answered[0].get("error").is_some());
+        assert!(state.runtime_routes.is_empty());
+    }
+
+    fn approval(item_id: &str, started_at: i64) -> PendingApprovalSnapshot {
+        PendingApprovalSnapshot {
+            thread_id: "th".to_string(),
+            turn_id: "tu".to_string(),
+            item_id: item_id.to_string(),
+            kind: crate::protocol::PendingApprovalKind::CommandExecution,
+            command: Some("cargo test".to_string()),
+            cwd: Some("/repo".to_string()),
+            reason: None,
+            started_at,
+        }
+    }
+
+    #[test]
+    fn pending_approvals_appear_in_snapshot_until_resolved() {
+        let mut state = DaemonState::new(EventJournal::in_memory(16));
+        register(&mut state, "rt");
+        for approval in [approval("second", 20), approval("first", 10)] {
+            state.request_approval(RuntimeApprovalRequestedParams {
+                runtime_id: "rt".to_string(),
+                approval,
+            });
+        }
+        let seq = state.seq;
+        state.request_approval(RuntimeApprovalRequestedParams {
+            runtime_id: "rt".to_string(),
+            approval: approval("first", 10),
+        });
+        assert_eq!(state.seq, seq, "re-announced approval should be ignored");
+        assert_eq!(
+            state.snapshot().runtimes[0].pending_approvals,
+            vec![approval("first", 10), approval("second", 20)]
+        );
+
+        state.resolve_approval("rt", "th", "first");
+        assert_eq!(
+            state.journal.events_after(seq).last().map(|e| &e.event),
+            Some(&CodexdEventPayload::ApprovalResolved {
+                runtime_id: "rt".to_string(),
+                thread_id: "th".to_string(),
+                item_id: "first".to_string(),
+            })
+        );
+
+        state.apply_runtime_notification(RuntimeEventParams {
+            runtime_id: "rt".to_string(),
+            notification: HubNotification {
+                method: "turn/completed".to_string(),
+                params: Some(serde_json::json!({"threadId": "th", "turn": {"id": "tu"}})),
+            },
+        });
+        assert_eq!(state.snapshot().runtimes[0].pending_approvals, Vec::new());
+    }
+
+    #[test]
+    fn answers_from_other_connections_are_ignored() {
+        let mut state = DaemonState::new(EventJournal::in_memory(16));
+        register(&mut state, "rt");
+        let (producer_tx, _producer_rx) = mpsc::unbounded_channel();
+        let (consumer_tx, mut consumer_rx) = mpsc::unbounded_channel();
+        state.bind_runtime_route(
+            "rt",
+            &RuntimeRoute {
+                connection_id: 7,
+                writer_tx: producer_tx,
+            },
+        );
+        state.request_approval(RuntimeApprovalRequestedParams {
+            runtime_id: "rt".to_string(),
+            approval: approval("item", 10),
+        });
+        let command_id = state
+            .route_command(
+                CodexdCommandParams {
+                    runtime_id: "rt".to_string(),
+                    command: crate::protocol::RuntimeCommand::CommandExecutionApproval {
+                        thread_id: "th".to_string(),
+                        turn_id: "tu".to_string(),
+                        item_id: "item".to_string(),
+                        decision: CommandExecutionApprovalDecision::Accept,
+                    },
+                },
+                consumer_tx,
+                Some(serde_json::json!(42)),
+            )
+            .expect("route command");
+
+        // A consumer guessing the command id cannot answer for the runtime.
+        state.answer_command(9, command_id, Ok(serde_json::json!({})));
+        assert_eq!(drain(&mut consumer_rx), Vec::<JsonValue>::new());
+        assert_eq!(
+            state.snapshot().runtimes[0].pending_approvals,
+            vec![approval("item", 10)]
+        );
+
+        state.answer_command(7, command_id, Ok(serde_json::json!({})));
+        assert_eq!(
+            drain(&mut consumer_rx),
+            vec![serde_json::json!({"id": 42, "result": {}})]
+        );
+        assert_eq!(state.snapshot().runtimes[0].pending_approvals, Vec::new());
+    }
+
+    #[test]
+    fn heartbeat_after_reaping_restores_runtime_for_its_connection() {
+        let mut state = DaemonState::new(EventJournal::in_memory(16));
+        let (producer_tx, _producer_rx) = mpsc::unbounded_channel();
+        let route = RuntimeRoute {
+            connection_id: 7,
+            writer_tx: producer_tx,
+        };
+        state.bind_runtime_route("rt", &route);
+        state.upsert_runtime_from_register(RuntimeRegisterParams {
+            runtime_id: "rt".to_string(),
+            pid: None,
+            session_source: None,
+            cwd: Some("/repo".to_string()),
+            display_name: Some("stalled".to_string()),
+        });
+
+        let stale_timeout = Duration::from_secs(60);
+        let later = Instant::now() + stale_timeout + Duration::from_secs(1);
+        state.reap_stale_runtimes(later, stale_timeout);
+        assert!(state.snapshot().runtimes.is_empty());
+
+        // A heartbeat from another connection neither revives the runtime
+        // nor discards what its own producer needs to restore it.
+        let (other_tx, _other_rx) = mpsc::unbounded_channel();
+        let other = RuntimeRoute {
+            connection_id: 9,
+            writer_tx: other_tx,
+        };
+        state.heartbeat("rt", &other);
+        assert!(state.snapshot().runtimes.is_empty());
+        assert!(state.silenced_runtimes.contains_key("rt"));
+
+        state.heartbeat("rt", &route);
+
+        let runtimes = state.snapshot().runtimes;
+        assert_eq!(runtimes.len(), 1);
+        assert_eq!(runtimes[0].runtime_id, "rt");
+        assert_eq!(runtimes[0].cwd.as_deref(), Some("/repo"));
+        assert_eq!(runtimes[0].display_name.as_deref(), Some("stalled"));
+        assert!(state.silenced_runtimes.is_empty());
+    }
+
+    #[test]
+    fn heartbeats_from_other_connections_keep_the_route() {
+        let mut state = DaemonState::new(EventJournal::in_memory(16));
+        let (owner_tx, _owner_rx) = mpsc::unbounded_channel();
+        let owner = RuntimeRoute {
+            connection_id: 7,
+            writer_tx: owner_tx,
+        };
+        state.bind_runtime_route("rt", &owner);
+        register(&mut state, "rt");
+
+        let (other_tx, _other_rx) = mpsc::unbounded_channel();
+        let other = RuntimeRoute {
+            connection_id: 9,
+            writer_tx: other_tx,
+        };
+        state.heartbeat("rt", &other);
+
+        assert_eq!(
+            state
+                .runtime_routes
+                .get("rt")
+                .map(|route| route.connection_id),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn heartbeats_from_other_connections_do_not_keep_the_runtime_alive() {
+        let mut state = DaemonState::new(EventJournal::in_memory(16));
+        let (owner_tx, _owner_rx) = mpsc::unbounded_channel();
+        let owner = RuntimeRoute {
+            connection_id: 7,
+            writer_tx: owner_tx,
+        };
+        state.bind_runtime_route("rt", &owner);
+        register(&mut state, "rt");
+        let registered_at = Instant::now();
+        state.last_seen.insert("rt".to_string(), registered_at);
+
+        let (other_tx, _other_rx) = mpsc::unbounded_channel();
+        let other = RuntimeRoute {
+            connection_id: 9,
+            writer_tx: other_tx,
+        };
+        state.heartbeat("rt", &other);
+        assert_eq!(state.last_seen.get("rt"), Some(&registered_at));
+
+        let stale_timeout = Duration::from_secs(60);
+        state.reap_stale_runtimes(
+            registered_at + stale_timeout + Duration::from_secs(1),
+            stale_timeout,
+        );
+        assert!(state.snapshot().runtimes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn producer_messages_from_other_connections_are_ignored() {
+        let state = Arc::new(Mutex::new(DaemonState::new(EventJournal::in_memory(16))));
+        let (owner_tx, _owner_rx) = mpsc::unbounded_channel();
+        let owner = RuntimeRoute {
+            connection_id: 7,
+            writer_tx: owner_tx,
+        };
+        let (other_tx, _other_rx) = mpsc::unbounded_channel();
+        let other = RuntimeRoute {
+            connection_id: 9,
+            writer_tx: other_tx,
+        };
+        let mut owned_runtime_ids = HashSet::new();
+        handle_runtime_register_method(
+            Arc::clone(&state),
+            Some(serde_json::json!({ "runtimeId": "rt", "displayName": "owner" })),
+            &owner,
+            &mut owned_runtime_ids,
+        )
+        .await
+        .expect("register");
+        handle_runtime_approval_requested_method(
+            Arc::clone(&state),
+            Some(serde_json::json!(RuntimeApprovalRequestedParams {
+                runtime_id: "rt".to_string(),
+                approval: approval("owned", 10),
+            })),
+            &owner,
+        )
+        .await
+        .expect("request approval");
+        let registered_at = Instant::now();
+        state
+            .lock()
+            .await
+            .last_seen
+            .insert("rt".to_string(), registered_at);
+        let seq = state.lock().await.seq;
+
+        handle_runtime_update_metadata_method(
+            Arc::clone(&state),
+            Some(serde_json::json!({ "runtimeId": "rt", "displayName": "forged" })),
+            &other,
+        )
+        .await
+        .expect("update metadata");
+        handle_runtime_approval_requested_method(
+            Arc::clone(&state),
+            Some(serde_json::json!(RuntimeApprovalRequestedParams {
+                runtime_id: "rt".to_string(),
+                approval: approval("injected", 20),
+            })),
+            &other,
+        )
+        .await
+        .expect("request approval");
+        handle_runtime_approval_resolved_method(
+            Arc::clone(&state),
+            Some(serde_json::json!({ "runtimeId": "rt", "threadId": "th", "itemId": "owned" })),
+            &other,
+        )
+        .await
+        .expect("resolve approval");
+        handle_runtime_unregister_method(
+            Arc::clone(&state),
+            Some(serde_json::json!({ "runtimeId": "rt" })),
+            &other,
+            &mut HashSet::new(),
+        )
+        .await
+        .expect("unregister");
+        // Runtimes the connection never registered are not created either.
+        handle_runtime_approval_requested_method(
+            Arc::clone(&state),
+            Some(serde_json::json!(RuntimeApprovalRequestedParams {
+                runtime_id: "unknown".to_string(),
+                approval: approval("injected", 20),
+            })),
+            &other,
+        )
+        .await
+        .expect("request approval");
+
+        let state = state.lock().await;
+        assert_eq!(state.seq, seq);
+        assert_eq!(state.last_seen.get("rt"), Some(&registered_at));
+        let runtimes = state.snapshot().runtimes;
+        assert_eq!(runtimes.len(), 1);
+        assert_eq!(runtimes[0].runtime_id, "rt");
+        assert_eq!(runtimes[0].display_name.as_deref(), Some("owner"));
+        assert_eq!(runtimes[0].pending_approvals, vec![approval("owned", 10)]);
+    }
+
+    #[tokio::test]
+    async fn disconnecting_keeps_runtimes_registered_by_another_connection() {
+        let state = Arc::new(Mutex::new(DaemonState::new(EventJournal::in_memory(16))));
+        let (first_tx, _first_rx) = mpsc::unbounded_channel();
+        let first = RuntimeRoute {
+            connection_id: 7,
+            writer_tx: first_tx,
+        };
+        let (second_tx, _second_rx) = mpsc::unbounded_channel();
+        let second = RuntimeRoute {
+            connection_id: 9,
+            writer_tx: second_tx,
+        };
+        let mut first_owned = HashSet::new();
+        for (route, owned) in [(&first, &mut first_owned), (&second, &mut HashSet::new())] {
+            handle_runtime_register_method(
+                Arc::clone(&state),
+                Some(serde_json::json!({ "runtimeId": "rt" })),
+                route,
+                owned,
+            )
+            .await
+            .expect("register");
+        }
+
+        cleanup_connection(Arc::clone(&state), first.connection_id, first_owned, false).await;
+
+        let state = state.lock().await;
+        assert_eq!(state.snapshot().runtimes.len(), 1);
+        assert!(state.runtime_owned_by("rt", second.connection_id));
+    }
+
+    #[test]
+    fn exited_and_silent_runtimes_are_reaped() {
+        let mut child = std::process::Command::new("true")
+            .spawn()
+            .expect("spawn true");
+        let exited_pid = child.id();
+        child.wait().expect("wait for true");
+
+        let mut state = DaemonState::new(EventJournal::in_memory(16));
+        state.upsert_runtime_from_register(RuntimeRegisterParams {
+            runtime_id: "exited".to_string(),
+            pid: Some(exited_pid),
+            session_source: None,
+            cwd: None,
+            display_name: None,
+        });
+        register(&mut state, "silent");
+        register(&mut state, "live");
+        let seq = state.seq;
+
+        let stale_timeout = Duration::from_secs(60);
+        let later = Instant::now() + stale_timeout + Duration::from_secs(1);
+        state.last_seen.insert("live".to_string(), later);
+        state.reap_stale_runtimes(later, stale_timeout);
+
+        let runtime_ids: Vec<String> = state
+            .snapshot()
+            .runtimes
+            .into_iter()
+            .map(|runtime| runtime.runtime_id)
+            .collect();
+        assert_eq!(runtime_ids, vec!["live".to_string()]);
+
+        let stale_events: Vec<CodexdEventPayload> = state
+            .journal
+            .events_after(seq)
+            .filter(|envelope| matches!(envelope.event, CodexdEventPayload::RuntimeStale { .. }))
+            .map(|envelope| envelope.event.clone())
+            .collect();
+        assert_eq!(
+            stale_events,
+            vec![
+                CodexdEventPayload::RuntimeStale {
+                    runtime_id: "exited".to_string(),
+                    reason: RuntimeStaleReason::ProcessExited,
+                },
+                CodexdEventPayload::RuntimeStale {
+                    runtime_id: "silent".to_string(),
+                    reason: RuntimeStaleReason::HeartbeatTimeout,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn restore_rebuilds_runtimes_with_active_turns() {
+        let mut original = DaemonState::new(EventJournal::in_memory(16));
+        register(&mut original, "rt");
+        original.apply_runtime_notification(RuntimeEventParams {
+            runtime_id: "rt".to_string(),
+            notification: HubNotification {
+                method: "turn/started".to_string(),
+                params: Some(serde_json::json!({
+                    "threadId": "th",
+                    "turn": {"id": "tu", "startedAt": 100},
+                })),
+            },
+        });
+        original.request_approval(RuntimeApprovalRequestedParams {
+            runtime_id: "rt".to_string(),
+            approval: approval("call", 10),
+        });
+
+        let recovery = JournalRecovery {
+            seq: original.seq,
+            checkpoint_runtimes: Vec::new(),
+            events_after_checkpoint: original.journal.events_after(0).cloned().collect(),
+        };
+        let mut restored = DaemonState::new(EventJournal::in_memory(16));
+        restored.restore(recovery);
+
+        assert_eq!(restored.snapshot(), original.snapshot());
+        assert_eq!(
+            restored.restored_runtime_ids,
+            HashSet::from(["rt".to_string()])
+        );
+
+        restored.expire_restored_runtimes();
+        assert_eq!(restored.snapshot().runtimes, Vec::new());
+        assert_eq!(restored.seq, original.seq + 1);
+    }
+
+    fn token_usage(runtime_id: &str, thread_id: &str, total_tokens: i64) -> RuntimeEventParams {
+        RuntimeEventParams {
+            runtime_id: runtime_id.to_string(),
+            notification: HubNotification {
+                method: "thread/tokenUsage/updated".to_string(),
+                params: Some(serde_json::json!({
+                    "threadId": thread_id,
+                    "turnId": "tu",
+                    "tokenUsage": {
+                        "total": {
+                            "totalTokens": total_tokens,
+                            "inputTokens": total_tokens - 10,
+                            "cachedInputTokens": 0,
+                            "outputTokens": 10,
+                            "reasoningOutputTokens": 0,
+                        },
+                        "last": {
+                            "totalTokens": 10,
+                            "inputTokens": 0,
+                            "cachedInputTokens": 0,
+                            "outputTokens": 10,
+                            "reasoningOutputTokens": 0,
+                        },
+                        "modelContextWindow": 1000,
+                    },
+                })),
+            },
+        }
+    }
+
+    fn rate_limits(runtime_id: &str, used_percent: i32) -> RuntimeEventParams {
+        RuntimeEventParams {
+            runtime_id: runtime_id.to_string(),
+            notification: HubNotification {
+                method: "account/rateLimits/updated".to_string(),
+                params: Some(serde_json::json!({
+                    "rateLimits": {
+                        "primary": {
+                            "usedPercent": used_percent,
+                            "windowDurationMins": 300,
+                            "resetsAt": 5000,
+                        },
+                        "secondary": null,
+                        "credits": null,
+                        "planType": null,
+                    },
+                })),
+            },
+        }
+    }
+
+    #[test]
+    fn usage_rolls_up_threads_and_keeps_latest_rate_limits() {
+        let mut state = DaemonState::new(EventJournal::in_memory(16));
+        register(&mut state, "light");
+        register(&mut state, "heavy");
+        register(&mut state, "idle");
+        state.apply_runtime_notification(token_usage("light", "th-1", 100));
+        state.apply_runtime_notification(token_usage("heavy", "th-2", 300));
+        state.apply_runtime_notification(token_usage("heavy", "th-3", 500));
+        // Later reports replace a thread's cumulative totals.
+        state.apply_runtime_notification(token_usage("light", "th-1", 150));
+        state.apply_runtime_notification(rate_limits("light", 20));
+        state.apply_runtime_notification(rate_limits("heavy", 35));
+
+        let usage = state.usage();
+        assert_eq!(usage.total.total_tokens, 950);
+        assert_eq!(
+            usage
+                .runtimes
+                .iter()
+                .map(|runtime| (runtime.runtime_id.as_str(), runtime.total.total_tokens))
+                .collect::<Vec<_>>(),
+            vec![("heavy", 800), ("light", 150)]
+        );
+        assert_eq!(
+            usage.runtimes[0]
+                .threads
+                .iter()
+                .map(|thread| thread.thread_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["th-3", "th-2"]
+        );
+        let rate_limits = usage.rate_limits.expect("rate limits recorded");
+        assert_eq!(rate_limits.runtime_id, "heavy");
+        assert_eq!(
+            rate_limits
+                .rate_limits
+                .primary
+                .map(|window| window.used_percent),
+            Some(35)
+        );
+
+        state.unregister_runtime("heavy");
+        assert_eq!(state.usage().total.total_tokens, 150);
+    }
+}
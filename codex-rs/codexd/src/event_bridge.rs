@@ -0,0 +1,455 @@
+//! Translates core `EventMsg`s into hub notifications for runtimes that drive
+//! a thread directly (the TUI and `codex exec`) rather than through the app
+//! server, which already speaks the v2 notification shapes.
+
+use crate::producer::CodexdProducerClient;
+use crate::protocol::HubNotification;
+use crate::protocol::PendingApprovalKind;
+use crate::protocol::PendingApprovalSnapshot;
+use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::RateLimitSnapshot;
+use codex_protocol::protocol::TokenUsageInfo;
+use serde_json::json;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+enum BridgeMessage {
+    Notification(HubNotification),
+    ApprovalRequested(PendingApprovalSnapshot),
+    ApprovalResolved { thread_id: String, item_id: String },
+}
+
+pub struct EventBridge {
+    producer: CodexdProducerClient,
+    /// Messages are forwarded by a single task so they reach the producer in
+    /// the order the events were observed.
+    outbox: mpsc::UnboundedSender<BridgeMessage>,
+    forwarder: JoinHandle<()>,
+    active_turns: HashMap<String, String>,
+    turn_key_by_turn_id: HashMap<String, String>,
+    turn_start_order: Vec<String>,
+    known_turn_keys: HashSet<String>,
+    /// Approvals published to codexd, keyed by the kind and the turn id the
+    /// answering `Op` carries, mapped to `(thread_id, call_id)`.
+    pending_approvals: HashMap<(PendingApprovalKind, String), (String, String)>,
+    current_model: Option<String>,
+    current_model_provider: Option<String>,
+    current_thinking_level: Option<String>,
+}
+
+impl EventBridge {
+    pub fn new(producer: CodexdProducerClient) -> Self {
+        let (outbox, mut receiver) = mpsc::unbounded_channel::<BridgeMessage>();
+        let forward_producer = producer.clone();
+        let forwarder = tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    BridgeMessage::Notification(notification) => {
+                        forward_producer
+                            .publish_hub_notification(notification)
+                            .await;
+                    }
+                    BridgeMessage::ApprovalRequested(approval) => {
+                        forward_producer.publish_approval_requested(approval).await;
+                    }
+                    BridgeMessage::ApprovalResolved { thread_id, item_id } => {
+                        forward_producer
+                            .publish_approval_resolved(thread_id, item_id)
+                            .await;
+                    }
+                }
+            }
+        });
+
+        Self {
+            producer,
+            outbox,
+            forwarder,
+            active_turns: HashMap::new(),
+            turn_key_by_turn_id: HashMap::new(),
+            turn_start_order: Vec::new(),
+            known_turn_keys: HashSet::new(),
+            pending_approvals: HashMap::new(),
+            current_model: None,
+            current_model_provider: None,
+            current_thinking_level: None,
+        }
+    }
+
+    pub fn publish_event(
+        &mut self,
+        event: &EventMsg,
+        event_turn_id: &str,
+        active_thread_id: Option<String>,
+    ) {
+        let mut notifications = Vec::new();
+        match event {
+            EventMsg::TurnStarted(_) => {
+                if let Some(turn_id) = normalize_turn_id(event_turn_id)
+                    && let Some(thread_id) = active_thread_id
+                {
+                    notifications.extend(self.ensure_turn_started(thread_id, turn_id));
+                }
+            }
+            EventMsg::SessionConfigured(event) => {
+                self.current_model = Some(event.model.clone());
+                self.current_model_provider = Some(event.model_provider_id.clone());
+                self.current_thinking_level =
+                    event.reasoning_effort.as_ref().map(ToString::to_string);
+            }
+            EventMsg::ItemStarted(item) => {
+                notifications.extend(
+                    self.ensure_turn_started(item.thread_id.to_string(), item.turn_id.clone()),
+                );
+                notifications.push(HubNotification {
+                    method: "item/started".to_string(),
+                    params: Some(json!({
+                        "threadId": item.thread_id,
+                        "turnId": item.turn_id,
+                        "item": item.item,
+                    })),
+                });
+            }
+            EventMsg::ItemCompleted(item) => {
+                notifications.extend(
+                    self.ensure_turn_started(item.thread_id.to_string(), item.turn_id.clone()),
+                );
+                notifications.push(HubNotification {
+                    method: "item/completed".to_string(),
+                    params: Some(json!({
+                        "threadId": item.thread_id,
+                        "turnId": item.turn_id,
+                        "item": item.item,
+                    })),
+                });
+            }
+            EventMsg::ProgressTrace(trace) => {
+                notifications.extend(
+                    self.ensure_turn_started(trace.thread_id.to_string(), trace.turn_id.clone()),
+                );
+                notifications.push(HubNotification {
+                    method: "turn/progressTrace".to_string(),
+                    params: Some(json!({
+                        "threadId": trace.thread_id,
+                        "turnId": trace.turn_id,
+                        "category": trace.category,
+                        "state": trace.state,
+                        "label": trace.label,
+                    })),
+                });
+            }
+            EventMsg::AgentMessageContentDelta(event) => {
+                notifications.extend(
+                    self.ensure_turn_started(event.thread_id.clone(), event.turn_id.clone()),
+                );
+            }
+            EventMsg::PlanDelta(event) => {
+                notifications.extend(
+                    self.ensure_turn_started(event.thread_id.clone(), event.turn_id.clone()),
+                );
+            }
+            EventMsg::ReasoningContentDelta(event) => {
+                notifications.extend(
+                    self.ensure_turn_started(event.thread_id.clone(), event.turn_id.clone()),
+                );
+            }
+            EventMsg::ReasoningRawContentDelta(event) => {
+                notifications.extend(
+                    self.ensure_turn_started(event.thread_id.clone(), event.turn_id.clone()),
+                );
+            }
+            EventMsg::TokenCount(event) => {
+                if let Some(info) = &event.info {
+                    let turn_id = normalize_turn_id(event_turn_id);
+                    let thread_id = turn_id
+                        .as_deref()
+                        .and_then(|id| self.resolve_thread_id_for_turn(id))
+                        .or(active_thread_id.clone());
+                    notifications.push(token_usage_notification(info, thread_id, turn_id));
+                }
+                if let Some(rate_limits) = &event.rate_limits {
+                    notifications.push(rate_limits_notification(rate_limits));
+                }
+            }
+            EventMsg::ExecApprovalRequest(event) => {
+                if let Some(thread_id) = active_thread_id {
+                    self.publish_approval_requested(PendingApprovalSnapshot {
+                        thread_id,
+                        turn_id: event_turn_id.to_string(),
+                        item_id: event.call_id.clone(),
+                        kind: PendingApprovalKind::CommandExecution,
+                        command: Some(display_command(&event.command)),
+                        cwd: Some(event.cwd.to_string_lossy().into_owned()),
+                        reason: event.reason.clone(),
+                        started_at: crate::unix_timestamp_now(),
+                    });
+                }
+            }
+            EventMsg::NetworkApprovalRequest(event) => {
+                if let Some(thread_id) = active_thread_id {
+                    self.publish_approval_requested(PendingApprovalSnapshot {
+                        thread_id,
+                        turn_id: event_turn_id.to_string(),
+                        item_id: event.call_id.clone(),
+                        kind: PendingApprovalKind::CommandExecution,
+                        command: Some(display_command(&event.command)),
+                        cwd: Some(event.cwd.to_string_lossy().into_owned()),
+                        reason: Some(format!(
+                            "Network access to {}:{} was blocked",
+                            event.host, event.port
+                        )),
+                        started_at: crate::unix_timestamp_now(),
+                    });
+                }
+            }
+            EventMsg::ApplyPatchApprovalRequest(event) => {
+                if let Some(thread_id) = active_thread_id {
+                    self.publish_approval_requested(PendingApprovalSnapshot {
+                        thread_id,
+                        turn_id: event_turn_id.to_string(),
+                        item_id: event.call_id.clone(),
+                        kind: PendingApprovalKind::FileChange,
+                        command: None,
+                        cwd: event
+                            .grant_root
+                            .as_ref()
+                            .map(|root| root.to_string_lossy().into_owned()),
+                        reason: event.reason.clone(),
+                        started_at: crate::unix_timestamp_now(),
+                    });
+                }
+            }
+            EventMsg::RequestUserInput(event) => {
+                if let Some(thread_id) = active_thread_id {
+                    let questions: Vec<&str> = event
+                        .questions
+                        .iter()
+                        .map(|question| question.question.as_str())
+                        .collect();
+                    self.publish_approval_requested(PendingApprovalSnapshot {
+                        thread_id,
+                        turn_id: event_turn_id.to_string(),
+                        item_id: event.call_id.clone(),
+                        kind: PendingApprovalKind::UserInput,
+                        command: None,
+                        cwd: None,
+                        reason: Some(questions.join("\n")),
+                        started_at: crate::unix_timestamp_now(),
+                    });
+                }
+            }
+            EventMsg::TurnComplete(_) | EventMsg::TurnAborted(_) => {
+                // codexd drops approvals for finished turns on its own.
+                self.pending_approvals
+                    .retain(|(_, turn_id), _| turn_id != event_turn_id);
+                notifications.extend(self.complete_turn(normalize_turn_id(event_turn_id)));
+            }
+            EventMsg::Error(error) => {
+                notifications.push(HubNotification {
+                    method: "error".to_string(),
+                    params: Some(json!({
+                        "error": {
+                            "message": error.message,
+                        },
+                        "willRetry": false,
+                    })),
+                });
+            }
+            _ => {}
+        }
+
+        for notification in notifications {
+            let _ = self.outbox.send(BridgeMessage::Notification(notification));
+        }
+    }
+
+    /// Marks the approval answered by `op` as resolved in codexd.
+    pub fn observe_op(&mut self, op: &Op) {
+        let key = match op {
+            Op::ExecApproval { id, .. } | Op::NetworkApproval { id, .. } => {
+                (PendingApprovalKind::CommandExecution, id.clone())
+            }
+            Op::PatchApproval { id, .. } => (PendingApprovalKind::FileChange, id.clone()),
+            Op::UserInputAnswer { id, .. } => (PendingApprovalKind::UserInput, id.clone()),
+            _ => return,
+        };
+        let Some((thread_id, item_id)) = self.pending_approvals.remove(&key) else {
+            return;
+        };
+
+        let _ = self
+            .outbox
+            .send(BridgeMessage::ApprovalResolved { thread_id, item_id });
+    }
+
+    /// Delivers everything published so far, then stops the producer.
+    pub async fn shutdown(self) {
+        let Self {
+            producer,
+            outbox,
+            forwarder,
+            ..
+        } = self;
+        drop(outbox);
+        let _ = forwarder.await;
+        producer.shutdown().await;
+    }
+
+    fn publish_approval_requested(&mut self, approval: PendingApprovalSnapshot) {
+        self.pending_approvals.insert(
+            (approval.kind, approval.turn_id.clone()),
+            (approval.thread_id.clone(), approval.item_id.clone()),
+        );
+        let _ = self.outbox.send(BridgeMessage::ApprovalRequested(approval));
+    }
+
+    fn ensure_turn_started(&mut self, thread_id: String, turn_id: String) -> Vec<HubNotification> {
+        let key = format!("{thread_id}:{turn_id}");
+        if self.known_turn_keys.contains(&key) {
+            return Vec::new();
+        }
+
+        if let Some(existing_key) = self.turn_key_by_turn_id.get(&turn_id)
+            && existing_key != &key
+        {
+            return Vec::new();
+        }
+
+        self.known_turn_keys.insert(key.clone());
+        self.active_turns.insert(key.clone(), thread_id.clone());
+        self.turn_key_by_turn_id
+            .insert(turn_id.clone(), key.clone());
+        self.turn_start_order.push(key);
+
+        vec![HubNotification {
+            method: "turn/started".to_string(),
+            params: Some(json!({
+                "threadId": thread_id,
+                "turn": {
+                    "id": turn_id,
+                    "status": "inProgress",
+                    "model": self.current_model.clone(),
+                    "modelProvider": self.current_model_provider.clone(),
+                    "thinkingLevel": self.current_thinking_level.clone(),
+                    "startedAt": crate::unix_timestamp_now(),
+                }
+            })),
+        }]
+    }
+
+    fn resolve_thread_id_for_turn(&self, turn_id: &str) -> Option<String> {
+        let key = self.turn_key_by_turn_id.get(turn_id)?;
+        self.active_turns.get(key).cloned()
+    }
+
+    fn complete_turn(&mut self, turn_id: Option<String>) -> Vec<HubNotification> {
+        if let Some(turn_id) = turn_id
+            && let Some(key) = self.turn_key_by_turn_id.remove(&turn_id)
+            && let Some(thread_id) = self.active_turns.remove(&key)
+        {
+            self.turn_start_order.retain(|existing| existing != &key);
+            return vec![turn_completed_notification(thread_id, &turn_id)];
+        }
+
+        while let Some(key) = self.turn_start_order.pop() {
+            let Some(thread_id) = self.active_turns.remove(&key) else {
+                continue;
+            };
+            let Some((_, turn_id)) = key.split_once(':') else {
+                continue;
+            };
+            self.turn_key_by_turn_id.remove(turn_id);
+            return vec![turn_completed_notification(thread_id, turn_id)];
+        }
+
+        Vec::new()
+    }
+}
+
+fn turn_completed_notification(thread_id: String, turn_id: &str) -> HubNotification {
+    HubNotification {
+        method: "turn/completed".to_string(),
+        params: Some(json!({
+            "threadId": thread_id,
+            "turn": {
+                "id": turn_id,
+                "status": "completed",
+            }
+        })),
+    }
+}
+
+fn token_usage_notification(
+    info: &TokenUsageInfo,
+    thread_id: Option<String>,
+    turn_id: Option<String>,
+) -> HubNotification {
+    let mut params = serde_json::Map::new();
+    if let Some(thread_id) = thread_id {
+        params.insert("threadId".to_string(), json!(thread_id));
+    }
+    if let Some(turn_id) = turn_id {
+        params.insert("turnId".to_string(), json!(turn_id));
+    }
+    params.insert(
+        "tokenUsage".to_string(),
+        json!({
+            "total": {
+                "totalTokens": info.total_token_usage.total_tokens,
+                "inputTokens": info.total_token_usage.input_tokens,
+                "cachedInputTokens": info.total_token_usage.cached_input_tokens,
+                "outputTokens": info.total_token_usage.output_tokens,
+                "reasoningOutputTokens": info.total_token_usage.reasoning_output_tokens,
+            },
+            "last": {
+                "totalTokens": info.last_token_usage.total_tokens,
+                "inputTokens": info.last_token_usage.input_tokens,
+                "cachedInputTokens": info.last_token_usage.cached_input_tokens,
+                "outputTokens": info.last_token_usage.output_tokens,
+                "reasoningOutputTokens": info.last_token_usage.reasoning_output_tokens,
+            },
+            "modelContextWindow": info.model_context_window,
+        }),
+    );
+    HubNotification {
+        method: "thread/tokenUsage/updated".to_string(),
+        params: Some(serde_json::Value::Object(params)),
+    }
+}
+
+fn rate_limits_notification(rate_limits: &RateLimitSnapshot) -> HubNotification {
+    let notification = AccountRateLimitsUpdatedNotification {
+        rate_limits: rate_limits.clone().into(),
+    };
+    HubNotification {
+        method: "account/rateLimits/updated".to_string(),
+        params: serde_json::to_value(notification).ok(),
+    }
+}
+
+fn normalize_turn_id(turn_id: &str) -> Option<String> {
+    let trimmed = turn_id.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some(trimmed.to_string())
+}
+
+/// Shows the script for `bash -lc <script>` style commands and a shell-quoted
+/// argv otherwise.
+fn display_command(command: &[String]) -> String {
+    if let [shell, flag, script] = command
+        && matches!(flag.as_str(), "-lc" | "-c")
+        && std::path::Path::new(shell)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| matches!(name, "bash" | "zsh" | "sh"))
+    {
+        return script.clone();
+    }
+    shlex::try_join(command.iter().map(String::as_str)).unwrap_or_else(|_| command.join(" "))
+}
@@ -0,0 +1,326 @@
+//! Consumer side of the codexd socket, used by terminal clients such as
+//! `codex hub` that render the live runtime table.
+
+use crate::protocol::CodexdEventEnvelope;
+use crate::protocol::CodexdGapParams;
+use crate::protocol::CodexdSnapshotResponse;
+use crate::protocol::CodexdSubscribeParams;
+use crate::protocol::CodexdSubscribeResponse;
+use anyhow::Context;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::path::Path;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::io::Lines;
+use tokio::net::UnixStream;
+use tokio::net::unix::OwnedReadHalf;
+use tokio::net::unix::OwnedWriteHalf;
+use tracing::debug;
+
+const CODEXD_SNAPSHOT_METHOD: &str = "codexd/snapshot";
+const CODEXD_SUBSCRIBE_METHOD: &str = "codexd/subscribe";
+const CODEXD_EVENT_METHOD: &str = "codexd/event";
+const CODEXD_GAP_METHOD: &str = "codexd/gap";
+
+/// Something the daemon pushed to a hub consumer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HubUpdate {
+    /// Response to [`HubClient::request_snapshot`].
+    Snapshot(CodexdSnapshotResponse),
+    Event(CodexdEventEnvelope),
+    /// Events were lost; the consumer should request a fresh snapshot.
+    Gap(CodexdGapParams),
+}
+
+/// A subscribed consumer connection to codexd.
+pub struct HubClient {
+    writer: OwnedWriteHalf,
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    next_request_id: i64,
+    snapshot_request_ids: HashSet<i64>,
+    /// Messages that arrived while [`HubClient::call`] waited for a response,
+    /// delivered by [`HubClient::next_update`] before anything newer.
+    queued: VecDeque<JsonValue>,
+}
+
+impl HubClient {
+    /// Connects to the daemon, fetches the current snapshot and subscribes to
+    /// every event after it.
+    pub async fn connect(socket_path: &Path) -> anyhow::Result<(Self, CodexdSnapshotResponse)> {
+        let stream = UnixStream::connect(socket_path)
+            .await
+            .with_context(|| format!("failed to connect to codexd at {}", socket_path.display()))?;
+        let (reader, writer) = stream.into_split();
+        let mut client = Self {
+            writer,
+            lines: BufReader::new(reader).lines(),
+            next_request_id: 0,
+            snapshot_request_ids: HashSet::new(),
+            queued: VecDeque::new(),
+        };
+
+        let snapshot: CodexdSnapshotResponse =
+            client.call(CODEXD_SNAPSHOT_METHOD, json!({})).await?;
+        let _: CodexdSubscribeResponse = client
+            .call(
+                CODEXD_SUBSCRIBE_METHOD,
+                json!(CodexdSubscribeParams {
+                    after_seq: Some(snapshot.seq),
+                }),
+            )
+            .await?;
+
+        Ok((client, snapshot))
+    }
+
+    /// Asks for a fresh snapshot; it arrives as [`HubUpdate::Snapshot`].
+    pub async fn request_snapshot(&mut self) -> anyhow::Result<()> {
+        let id = self.send_request(CODEXD_SNAPSHOT_METHOD, json!({})).await?;
+        self.snapshot_request_ids.insert(id);
+        Ok(())
+    }
+
+    /// Waits for the next update. Returns `None` once the daemon closes the
+    /// connection.
+    pub async fn next_update(&mut self) -> anyhow::Result<Option<HubUpdate>> {
+        while let Some(value) = self.queued.pop_front() {
+            if let Some(update) = self.parse_update(value)? {
+                return Ok(Some(update));
+            }
+        }
+        while let Some(value) = self.next_message().await? {
+            if let Some(update) = self.parse_update(value)? {
+                return Ok(Some(update));
+            }
+        }
+        Ok(None)
+    }
+
+    fn parse_update(&mut self, value: JsonValue) -> anyhow::Result<Option<HubUpdate>> {
+        if let Some(id) = value.get("id").and_then(JsonValue::as_i64) {
+            if !self.snapshot_request_ids.remove(&id) {
+                return Ok(None);
+            }
+            let result = response_result(value)?;
+            return Ok(Some(HubUpdate::Snapshot(serde_json::from_value(result)?)));
+        }
+
+        let params = value.get("params").cloned().unwrap_or(JsonValue::Null);
+        let update = match value.get("method").and_then(JsonValue::as_str) {
+            Some(CODEXD_EVENT_METHOD) => HubUpdate::Event(serde_json::from_value(params)?),
+            Some(CODEXD_GAP_METHOD) => HubUpdate::Gap(serde_json::from_value(params)?),
+            other => {
+                debug!("codex hub ignored codexd message {other:?}");
+                return Ok(None);
+            }
+        };
+        Ok(Some(update))
+    }
+
+    /// Sends a request and waits for its response. Anything else that arrives
+    /// first, such as events replayed by `codexd/subscribe`, is queued for
+    /// [`HubClient::next_update`].
+    async fn call<T: serde::de::DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: JsonValue,
+    ) -> anyhow::Result<T> {
+        let id = self.send_request(method, params).await?;
+        loop {
+            let value = self
+                .next_message()
+                .await?
+                .with_context(|| format!("codexd closed the connection during `{method}`"))?;
+            if value.get("id").and_then(JsonValue::as_i64) == Some(id) {
+                return Ok(serde_json::from_value(response_result(value)?)?);
+            }
+            self.queued.push_back(value);
+        }
+    }
+
+    async fn send_request(&mut self, method: &str, params: JsonValue) -> anyhow::Result<i64> {
+        self.next_request_id += 1;
+        let id = self.next_request_id;
+        let mut line = serde_json::to_string(&json!({
+            "id": id,
+            "method": method,
+            "params": params,
+        }))?;
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .await
+            .context("failed to write to codexd")?;
+        Ok(id)
+    }
+
+    async fn next_message(&mut self) -> anyhow::Result<Option<JsonValue>> {
+        while let Some(line) = self
+            .lines
+            .next_line()
+            .await
+            .context("failed to read from codexd")?
+        {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(value) => return Ok(Some(value)),
+                Err(err) => debug!("codex hub ignored invalid codexd line: {err}"),
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn response_result(value: JsonValue) -> anyhow::Result<JsonValue> {
+    if let Some(error) = value.get("error") {
+        let message = error
+            .get("message")
+            .and_then(JsonValue::as_str)
+            .unwrap_or("codexd request failed");
+        anyhow::bail!("{message}");
+    }
+    Ok(value.get("result").cloned().unwrap_or(JsonValue::Null))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DaemonOptions;
+    use crate::daemon::run_daemon;
+    use crate::producer::CodexdProducerClient;
+    use crate::producer::RuntimeMetadata;
+    use crate::protocol::CodexdEventPayload;
+    use crate::protocol::RuntimeSnapshot;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use tokio::net::UnixListener;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn hub_client_sees_snapshot_and_live_events() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let socket_path = codex_home.path().join("codexd.sock");
+        let daemon = tokio::spawn({
+            let codex_home = codex_home.path().to_path_buf();
+            let socket_path = socket_path.clone();
+            async move { run_daemon(&codex_home, Some(socket_path), DaemonOptions::default()).await }
+        });
+
+        let (mut client, snapshot) = loop {
+            match HubClient::connect(&socket_path).await {
+                Ok(connected) => break connected,
+                Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+            }
+        };
+        assert_eq!(snapshot.runtimes, Vec::new());
+
+        let metadata = RuntimeMetadata {
+            runtime_id: "rt-hub".to_string(),
+            pid: None,
+            session_source: Some("exec".to_string()),
+            cwd: Some("/tmp/project".to_string()),
+            display_name: None,
+        };
+        let producer =
+            CodexdProducerClient::spawn_with_socket_path(socket_path.clone(), metadata.clone());
+        producer.update_metadata(metadata).await;
+
+        let update = tokio::time::timeout(Duration::from_secs(5), client.next_update())
+            .await
+            .expect("event before timeout")
+            .expect("read update")
+            .expect("connection open");
+        let HubUpdate::Event(envelope) = update else {
+            panic!("expected event, got {update:?}");
+        };
+        let CodexdEventPayload::RuntimeUpsert { runtime } = envelope.event else {
+            panic!("expected runtime upsert, got {:?}", envelope.event);
+        };
+        assert_eq!(runtime.runtime_id, "rt-hub");
+
+        client.request_snapshot().await.expect("request snapshot");
+        let update = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match client.next_update().await.expect("read update") {
+                    Some(HubUpdate::Snapshot(snapshot)) => break snapshot,
+                    Some(_) => continue,
+                    None => panic!("connection closed"),
+                }
+            }
+        })
+        .await
+        .expect("snapshot before timeout");
+        assert_eq!(
+            update
+                .runtimes
+                .iter()
+                .map(|runtime| runtime.cwd.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("/tmp/project")]
+        );
+
+        producer.shutdown().await;
+        daemon.abort();
+    }
+
+    #[tokio::test]
+    async fn events_sent_before_the_subscribe_response_are_delivered() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let socket_path = dir.path().join("codexd.sock");
+        let listener = UnixListener::bind(&socket_path).expect("bind");
+        let runtime = RuntimeSnapshot {
+            runtime_id: "rt-early".to_string(),
+            pid: None,
+            session_source: None,
+            cwd: None,
+            display_name: None,
+            active_turns: Vec::new(),
+            pending_approvals: Vec::new(),
+        };
+        let event = CodexdEventEnvelope {
+            seq: 1,
+            event: CodexdEventPayload::RuntimeUpsert {
+                runtime: runtime.clone(),
+            },
+        };
+
+        let server = tokio::spawn({
+            let event = event.clone();
+            async move {
+                let (stream, _) = listener.accept().await.expect("accept");
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                let mut send = async |value: JsonValue| {
+                    let line = format!("{value}\n");
+                    writer.write_all(line.as_bytes()).await.expect("write");
+                };
+
+                let snapshot: JsonValue =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                send(json!({"id": snapshot["id"], "result": {"seq": 0, "runtimes": []}})).await;
+                let subscribe: JsonValue =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                send(json!({"method": CODEXD_EVENT_METHOD, "params": event})).await;
+                send(json!({"id": subscribe["id"], "result": {"seq": 1}})).await;
+                // Keep the connection open until the client hangs up.
+                while let Ok(Some(_)) = lines.next_line().await {}
+            }
+        });
+
+        let (mut client, snapshot) = HubClient::connect(&socket_path).await.expect("connect");
+        assert_eq!(snapshot.runtimes, Vec::new());
+        let update = tokio::time::timeout(Duration::from_secs(5), client.next_update())
+            .await
+            .expect("update before timeout")
+            .expect("read update");
+        assert_eq!(update, Some(HubUpdate::Event(event)));
+
+        drop(client);
+        server.await.expect("server");
+    }
+}
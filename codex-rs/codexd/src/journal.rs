@@ -0,0 +1,369 @@
+use crate::protocol::CodexdEventEnvelope;
+use crate::protocol::RuntimeSnapshot;
+use anyhow::Context;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::warn;
+
+pub const DEFAULT_JOURNAL_MAX_EVENTS: usize = 10_000;
+const JOURNAL_FILE_NAME: &str = "events.jsonl";
+
+/// A single line in the on-disk journal.
+///
+/// Checkpoints capture the full runtime table so the journal can be compacted
+/// without losing the state needed to rebuild `codexd/snapshot` after a restart.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "record", rename_all = "camelCase")]
+enum JournalRecord {
+    #[serde(rename_all = "camelCase")]
+    Checkpoint {
+        seq: u64,
+        runtimes: Vec<RuntimeSnapshot>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Event { envelope: CodexdEventEnvelope },
+}
+
+/// State recovered from an existing journal when the daemon starts.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct JournalRecovery {
+    /// Highest sequence number observed in the journal.
+    pub seq: u64,
+    /// Runtime table captured by the most recent checkpoint, if any.
+    pub checkpoint_runtimes: Vec<RuntimeSnapshot>,
+    /// Events recorded after the most recent checkpoint, in order.
+    pub events_after_checkpoint: Vec<CodexdEventEnvelope>,
+}
+
+/// Bounded, append-only event journal stored under `codex_home/runtime/codexd/`.
+///
+/// The most recent `max_events` events are kept in memory for subscriber
+/// replay. The file is rewritten from a checkpoint once it grows past twice
+/// that size, which keeps disk usage bounded without compacting on every
+/// append.
+pub struct EventJournal {
+    path: Option<PathBuf>,
+    file: Option<File>,
+    max_events: usize,
+    retained: VecDeque<CodexdEventEnvelope>,
+    records_on_disk: usize,
+}
+
+impl EventJournal {
+    pub fn journal_path(codex_home: &Path) -> PathBuf {
+        codex_home
+            .join("runtime")
+            .join("codexd")
+            .join(JOURNAL_FILE_NAME)
+    }
+
+    /// Journal that only keeps events in memory. Used when the on-disk journal
+    /// cannot be opened so the daemon can still serve replay for its lifetime.
+    pub fn in_memory(max_events: usize) -> Self {
+        Self {
+            path: None,
+            file: None,
+            max_events: max_events.max(1),
+            retained: VecDeque::new(),
+            records_on_disk: 0,
+        }
+    }
+
+    /// Opens (or creates) the journal at `path` and returns the state needed to
+    /// rebuild the daemon's runtime table.
+    pub fn open(path: PathBuf, max_events: usize) -> anyhow::Result<(Self, JournalRecovery)> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "failed to create codexd journal directory {}",
+                    parent.display()
+                )
+            })?;
+        }
+
+        let mut journal = Self::in_memory(max_events);
+        let recovery = journal.load_existing(&path)?;
+
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options
+            .open(&path)
+            .with_context(|| format!("failed to open codexd journal {}", path.display()))?;
+
+        journal.path = Some(path);
+        journal.file = Some(file);
+        Ok((journal, recovery))
+    }
+
+    fn load_existing(&mut self, path: &Path) -> anyhow::Result<JournalRecovery> {
+        let mut recovery = JournalRecovery::default();
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(recovery),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to read codexd journal {}", path.display()));
+            }
+        };
+
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line =
+                line.with_context(|| format!("failed to read codexd journal {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            // A crash mid-write can leave a truncated final line; skip anything
+            // unparsable rather than refusing to start.
+            let record: JournalRecord = match serde_json::from_str(&line) {
+                Ok(record) => record,
+                Err(err) => {
+                    warn!(
+                        "codexd ignored invalid journal line {} in {}: {err}",
+                        index + 1,
+                        path.display()
+                    );
+                    continue;
+                }
+            };
+
+            self.records_on_disk += 1;
+            match record {
+                JournalRecord::Checkpoint { seq, runtimes } => {
+                    recovery.seq = recovery.seq.max(seq);
+                    recovery.checkpoint_runtimes = runtimes;
+                    recovery.events_after_checkpoint.clear();
+                }
+                JournalRecord::Event { envelope } => {
+                    recovery.seq = recovery.seq.max(envelope.seq);
+                    recovery.events_after_checkpoint.push(envelope.clone());
+                    self.push_retained(envelope);
+                }
+            }
+        }
+
+        Ok(recovery)
+    }
+
+    pub fn oldest_retained_seq(&self) -> Option<u64> {
+        self.retained.front().map(|envelope| envelope.seq)
+    }
+
+    /// Retained events with a sequence number strictly greater than `after_seq`.
+    pub fn events_after(&self, after_seq: u64) -> impl Iterator<Item = &CodexdEventEnvelope> {
+        self.retained
+            .iter()
+            .skip_while(move |envelope| envelope.seq <= after_seq)
+    }
+
+    /// Records `envelope` and, when the file has grown past its bound, compacts
+    /// it around a checkpoint of the runtime table returned by `checkpoint`.
+    pub fn append(
+        &mut self,
+        envelope: CodexdEventEnvelope,
+        checkpoint: impl FnOnce() -> Vec<RuntimeSnapshot>,
+    ) {
+        let seq = envelope.seq;
+        self.write_record(&JournalRecord::Event {
+            envelope: envelope.clone(),
+        });
+        self.push_retained(envelope);
+
+        if self.records_on_disk > self.max_events.saturating_mul(2) {
+            self.compact(seq, &checkpoint());
+        }
+    }
+
+    fn push_retained(&mut self, envelope: CodexdEventEnvelope) {
+        self.retained.push_back(envelope);
+        while self.retained.len() > self.max_events {
+            self.retained.pop_front();
+        }
+    }
+
+    fn write_record(&mut self, record: &JournalRecord) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+
+        let result = serde_json::to_string(record)
+            .map_err(std::io::Error::other)
+            .and_then(|line| writeln!(file, "{line}"));
+        match result {
+            Ok(()) => self.records_on_disk += 1,
+            Err(err) => {
+                warn!("codexd failed to append to event journal; disabling persistence: {err}");
+                self.file = None;
+            }
+        }
+    }
+
+    /// Rewrites the journal as a checkpoint of the current runtime table
+    /// followed by the retained events, swapping it in atomically.
+    fn compact(&mut self, seq: u64, runtimes: &[RuntimeSnapshot]) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+
+        match self.write_compacted(&path, seq, runtimes) {
+            Ok((file, records)) => {
+                self.file = Some(file);
+                self.records_on_disk = records;
+            }
+            Err(err) => {
+                warn!(
+                    "codexd failed to compact event journal {}: {err}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    fn write_compacted(
+        &self,
+        path: &Path,
+        seq: u64,
+        runtimes: &[RuntimeSnapshot],
+    ) -> anyhow::Result<(File, usize)> {
+        let tmp_path = path.with_extension("jsonl.tmp");
+        // A leftover from an interrupted compaction keeps its old mode, so start afresh.
+        let _ = std::fs::remove_file(&tmp_path);
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut tmp = options
+            .open(&tmp_path)
+            .with_context(|| format!("failed to create {}", tmp_path.display()))?;
+
+        // Retained events come first so the trailing checkpoint, which
+        // reflects the state after all of them, wins on reload.
+        let mut records = 0;
+        for envelope in &self.retained {
+            let record = JournalRecord::Event {
+                envelope: envelope.clone(),
+            };
+            writeln!(tmp, "{}", serde_json::to_string(&record)?)?;
+            records += 1;
+        }
+        let checkpoint = JournalRecord::Checkpoint {
+            seq,
+            runtimes: runtimes.to_vec(),
+        };
+        writeln!(tmp, "{}", serde_json::to_string(&checkpoint)?)?;
+        records += 1;
+        tmp.sync_all()?;
+        drop(tmp);
+
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("failed to replace {}", path.display()))?;
+
+        let file = OpenOptions::new()
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to reopen {}", path.display()))?;
+        Ok((file, records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::CodexdEventPayload;
+    use pretty_assertions::assert_eq;
+
+    fn removed(seq: u64) -> CodexdEventEnvelope {
+        CodexdEventEnvelope {
+            seq,
+            event: CodexdEventPayload::RuntimeRemoved {
+                runtime_id: format!("rt-{seq}"),
+            },
+        }
+    }
+
+    fn runtime(runtime_id: &str) -> RuntimeSnapshot {
+        RuntimeSnapshot {
+            runtime_id: runtime_id.to_string(),
+            pid: None,
+            session_source: None,
+            cwd: None,
+            display_name: None,
+            active_turns: Vec::new(),
+            pending_approvals: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reopen_recovers_sequence_and_events() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+
+        let (mut journal, recovery) = EventJournal::open(path.clone(), 8).expect("open");
+        assert_eq!(recovery, JournalRecovery::default());
+        journal.append(removed(1), Vec::new);
+        journal.append(removed(2), Vec::new);
+        drop(journal);
+
+        let (journal, recovery) = EventJournal::open(path, 8).expect("reopen");
+        assert_eq!(recovery.seq, 2);
+        assert_eq!(
+            recovery.events_after_checkpoint,
+            vec![removed(1), removed(2)]
+        );
+        assert_eq!(
+            journal.events_after(1).cloned().collect::<Vec<_>>(),
+            vec![removed(2)]
+        );
+    }
+
+    #[test]
+    fn compaction_bounds_file_and_keeps_checkpoint() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+
+        let (mut journal, _) = EventJournal::open(path.clone(), 2).expect("open");
+        for seq in 1..=5 {
+            journal.append(removed(seq), || vec![runtime("rt-live")]);
+        }
+        assert_eq!(journal.oldest_retained_seq(), Some(4));
+        drop(journal);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path)
+                .expect("metadata")
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let contents = std::fs::read_to_string(&path).expect("read journal");
+        assert!(
+            contents.lines().count() <= 4,
+            "journal not compacted: {contents}"
+        );
+
+        let (journal, recovery) = EventJournal::open(path, 2).expect("reopen");
+        assert_eq!(recovery.seq, 5);
+        assert_eq!(recovery.checkpoint_runtimes, vec![runtime("rt-live")]);
+        assert_eq!(recovery.events_after_checkpoint, Vec::new());
+        assert_eq!(journal.oldest_retained_seq(), Some(4));
+    }
+}
@@ -2,12 +2,17 @@
 
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
+pub mod event_bridge;
+pub mod journal;
 pub mod protocol;
 
 #[cfg(unix)]
 pub mod daemon;
 #[cfg(unix)]
+pub mod hub;
+#[cfg(unix)]
 pub mod producer;
 
 #[cfg(not(unix))]
@@ -16,7 +21,11 @@ pub mod daemon {
     use std::path::Path;
     use std::path::PathBuf;
 
-    pub async fn run_daemon(_codex_home: &Path, _socket_path: Option<PathBuf>) -> Result<()> {
+    pub async fn run_daemon(
+        _codex_home: &Path,
+        _socket_path: Option<PathBuf>,
+        _options: crate::DaemonOptions,
+    ) -> Result<()> {
         Ok(())
     }
 }
@@ -24,9 +33,13 @@ pub mod daemon {
 #[cfg(not(unix))]
 pub mod producer {
     use crate::protocol::HubNotification;
+    use crate::protocol::PendingApprovalSnapshot;
+    use crate::protocol::RuntimeCommand;
     use codex_app_server_protocol::ServerNotification;
+    use codex_app_server_protocol::ServerRequestPayload;
     use std::path::Path;
     use std::path::PathBuf;
+    use tokio::sync::mpsc;
 
     #[derive(Debug, Clone)]
     pub struct RuntimeMetadata {
@@ -37,6 +50,15 @@ pub mod producer {
         pub display_name: Option<String>,
     }
 
+    #[derive(Debug)]
+    pub struct RuntimeCommandRequest {
+        pub command: RuntimeCommand,
+    }
+
+    impl RuntimeCommandRequest {
+        pub fn respond(self, _result: Result<(), String>) {}
+    }
+
     #[derive(Clone)]
     pub struct CodexdProducerClient;
 
@@ -45,6 +67,14 @@ pub mod producer {
             Self
         }
 
+        pub fn spawn_with_commands(
+            _codex_home: &Path,
+            _metadata: RuntimeMetadata,
+        ) -> (Self, mpsc::Receiver<RuntimeCommandRequest>) {
+            let (_sender, receiver) = mpsc::channel(1);
+            (Self, receiver)
+        }
+
         pub fn spawn_with_socket_path(_socket_path: PathBuf, _metadata: RuntimeMetadata) -> Self {
             Self
         }
@@ -55,13 +85,44 @@ pub mod producer {
 
         pub async fn publish_server_notification(&self, _notification: &ServerNotification) {}
 
+        pub async fn publish_approval_requested(&self, _approval: PendingApprovalSnapshot) {}
+
+        pub async fn publish_approval_resolved(&self, _thread_id: String, _item_id: String) {}
+
+        pub async fn publish_server_request(&self, _request: &ServerRequestPayload) {}
+
         pub async fn shutdown(&self) {}
     }
 }
 
+/// How long a runtime may go without any message, heartbeats included, before
+/// the daemon treats it as stale. Producers heartbeat every 15 seconds.
+pub const DEFAULT_STALE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub struct DaemonOptions {
+    pub stale_timeout: Duration,
+}
+
+impl Default for DaemonOptions {
+    fn default() -> Self {
+        Self {
+            stale_timeout: DEFAULT_STALE_TIMEOUT,
+        }
+    }
+}
+
 pub fn default_socket_path(codex_home: &Path) -> PathBuf {
     codex_home
         .join("runtime")
         .join("codexd")
         .join("codexd.sock")
 }
+
+/// Seconds since the Unix epoch, used to stamp pending approvals.
+pub fn unix_timestamp_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX))
+        .unwrap_or_default()
+}
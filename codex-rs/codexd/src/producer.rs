@@ -1,21 +1,43 @@
 use crate::protocol::HubNotification;
+use crate::protocol::PendingApprovalKind;
+use crate::protocol::PendingApprovalSnapshot;
+use crate::protocol::RuntimeApprovalRequestedParams;
+use crate::protocol::RuntimeApprovalResolvedParams;
+use crate::protocol::RuntimeCommand;
+use crate::protocol::RuntimeCommandParams;
 use crate::protocol::RuntimeRegisterParams;
 use crate::protocol::RuntimeUpdateMetadataParams;
 use codex_app_server_protocol::ServerNotification;
+use codex_app_server_protocol::ServerRequestPayload;
 use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
 use std::collections::VecDeque;
 use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
+use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
 use tokio::net::UnixStream;
+use tokio::net::unix::OwnedReadHalf;
+use tokio::net::unix::OwnedWriteHalf;
 use tokio::sync::mpsc;
+use tokio::sync::oneshot;
 use tracing::debug;
 use tracing::warn;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 2048;
 const MAX_PENDING_LINES: usize = 4096;
+const DEFAULT_COMMAND_CAPACITY: usize = 64;
+const RUNTIME_COMMAND_METHOD: &str = "codexd/runtime/command";
+const RUNTIME_APPROVAL_REQUESTED_METHOD: &str = "codexd/runtime/approvalRequested";
+const RUNTIME_APPROVAL_RESOLVED_METHOD: &str = "codexd/runtime/approvalResolved";
+const RUNTIME_HEARTBEAT_METHOD: &str = "codexd/runtime/heartbeat";
+/// Must stay well below the daemon's stale timeout.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+const COMMAND_ERROR_CODE: i64 = -32000;
 
 #[derive(Debug, Clone)]
 pub struct RuntimeMetadata {
@@ -48,11 +70,44 @@ impl RuntimeMetadata {
     }
 }
 
+/// A command routed from a hub consumer through codexd to this runtime.
+///
+/// The runtime must call [`RuntimeCommandRequest::respond`]; dropping the
+/// request reports a failure back to the consumer.
+#[derive(Debug)]
+pub struct RuntimeCommandRequest {
+    pub command: RuntimeCommand,
+    responder: oneshot::Sender<Result<(), String>>,
+}
+
+impl RuntimeCommandRequest {
+    pub fn respond(self, result: Result<(), String>) {
+        let _ = self.responder.send(result);
+    }
+}
+
 #[derive(Debug)]
 enum ProducerCommand {
     UpdateMetadata(RuntimeMetadata),
     PublishNotification(HubNotification),
-    Shutdown,
+    ApprovalRequested(PendingApprovalSnapshot),
+    ApprovalResolved {
+        thread_id: String,
+        item_id: String,
+    },
+    IncomingCommand {
+        id: JsonValue,
+        params: Option<JsonValue>,
+    },
+    CommandResult {
+        id: JsonValue,
+        result: Result<(), String>,
+    },
+    ConnectionClosed {
+        generation: u64,
+    },
+    /// Flushes queued lines, acknowledges, and stops the task.
+    Shutdown(oneshot::Sender<()>),
 }
 
 #[derive(Clone)]
@@ -67,10 +122,32 @@ impl CodexdProducerClient {
     }
 
     pub fn spawn_with_socket_path(socket_path: PathBuf, metadata: RuntimeMetadata) -> Self {
+        Self::spawn_inner(socket_path, metadata, None)
+    }
+
+    /// Like [`CodexdProducerClient::spawn`], but also accepts commands routed
+    /// from hub consumers. Runtimes spawned without a command receiver reject
+    /// every command.
+    pub fn spawn_with_commands(
+        codex_home: &Path,
+        metadata: RuntimeMetadata,
+    ) -> (Self, mpsc::Receiver<RuntimeCommandRequest>) {
+        let socket_path = super::default_socket_path(codex_home);
+        let (command_tx, command_rx) = mpsc::channel(DEFAULT_COMMAND_CAPACITY);
+        let client = Self::spawn_inner(socket_path, metadata, Some(command_tx));
+        (client, command_rx)
+    }
+
+    fn spawn_inner(
+        socket_path: PathBuf,
+        metadata: RuntimeMetadata,
+        command_tx: Option<mpsc::Sender<RuntimeCommandRequest>>,
+    ) -> Self {
         let (sender, receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+        let task_sender = sender.downgrade();
 
         tokio::spawn(async move {
-            run_producer_task(socket_path, metadata, receiver).await;
+            run_producer_task(socket_path, metadata, receiver, task_sender, command_tx).await;
         });
 
         Self { sender }
@@ -104,11 +181,107 @@ impl CodexdProducerClient {
         }
     }
 
+    /// Adds an approval to this runtime's inbox in codexd. The approval is
+    /// re-announced whenever the producer reconnects until it is resolved.
+    pub async fn publish_approval_requested(&self, approval: PendingApprovalSnapshot) {
+        if self
+            .sender
+            .send(ProducerCommand::ApprovalRequested(approval))
+            .await
+            .is_err()
+        {
+            debug!("codexd producer task stopped before approval could be queued");
+        }
+    }
+
+    pub async fn publish_approval_resolved(&self, thread_id: String, item_id: String) {
+        if self
+            .sender
+            .send(ProducerCommand::ApprovalResolved { thread_id, item_id })
+            .await
+            .is_err()
+        {
+            debug!("codexd producer task stopped before approval resolution could be queued");
+        }
+    }
+
+    /// Publishes approval and user-input server requests as pending approvals.
+    /// Other requests are ignored.
+    pub async fn publish_server_request(&self, request: &ServerRequestPayload) {
+        if let Some(approval) = pending_approval_from_server_request(request) {
+            self.publish_approval_requested(approval).await;
+        }
+    }
+
+    /// Stops the producer after a best-effort flush of queued messages, so a
+    /// short-lived runtime such as `codex exec` still reports its final turn.
     pub async fn shutdown(&self) {
-        let _ = self.sender.send(ProducerCommand::Shutdown).await;
+        let (done_tx, done_rx) = oneshot::channel();
+        if self
+            .sender
+            .send(ProducerCommand::Shutdown(done_tx))
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let _ = tokio::time::timeout(SHUTDOWN_FLUSH_TIMEOUT, done_rx).await;
     }
 }
 
+fn pending_approval_from_server_request(
+    request: &ServerRequestPayload,
+) -> Option<PendingApprovalSnapshot> {
+    let started_at = super::unix_timestamp_now();
+    let approval = match request {
+        ServerRequestPayload::CommandExecutionRequestApproval(params) => PendingApprovalSnapshot {
+            thread_id: params.thread_id.clone(),
+            turn_id: params.turn_id.clone(),
+            item_id: params.item_id.clone(),
+            kind: PendingApprovalKind::CommandExecution,
+            command: params.command.clone(),
+            cwd: params
+                .cwd
+                .as_ref()
+                .map(|cwd| cwd.to_string_lossy().into_owned()),
+            reason: params.reason.clone(),
+            started_at,
+        },
+        ServerRequestPayload::FileChangeRequestApproval(params) => PendingApprovalSnapshot {
+            thread_id: params.thread_id.clone(),
+            turn_id: params.turn_id.clone(),
+            item_id: params.item_id.clone(),
+            kind: PendingApprovalKind::FileChange,
+            command: None,
+            cwd: params
+                .grant_root
+                .as_ref()
+                .map(|root| root.to_string_lossy().into_owned()),
+            reason: params.reason.clone(),
+            started_at,
+        },
+        ServerRequestPayload::ToolRequestUserInput(params) => PendingApprovalSnapshot {
+            thread_id: params.thread_id.clone(),
+            turn_id: params.turn_id.clone(),
+            item_id: params.item_id.clone(),
+            kind: PendingApprovalKind::UserInput,
+            command: None,
+            cwd: None,
+            reason: Some(
+                params
+                    .questions
+                    .iter()
+                    .map(|question| question.question.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            started_at,
+        },
+        _ => return None,
+    };
+    Some(approval)
+}
+
 fn hub_notification_from_server_notification(
     notification: &ServerNotification,
 ) -> Option<HubNotification> {
@@ -123,6 +296,8 @@ async fn run_producer_task(
     socket_path: PathBuf,
     mut metadata: RuntimeMetadata,
     mut receiver: mpsc::Receiver<ProducerCommand>,
+    task_sender: mpsc::WeakSender<ProducerCommand>,
+    command_tx: Option<mpsc::Sender<RuntimeCommandRequest>>,
 ) {
     if socket_path_too_long(socket_path.as_path()) {
         warn!(
@@ -131,7 +306,8 @@ async fn run_producer_task(
         );
 
         while let Some(command) = receiver.recv().await {
-            if matches!(command, ProducerCommand::Shutdown) {
+            if let ProducerCommand::Shutdown(done) = command {
+                let _ = done.send(());
                 break;
             }
         }
@@ -139,10 +315,18 @@ async fn run_producer_task(
         return;
     }
 
-    let mut connection: Option<UnixStream> = None;
+    let mut connection = ProducerConnection {
+        writer: None,
+        generation: 0,
+        task_sender,
+    };
     let mut pending_lines = VecDeque::<String>::new();
+    // Outstanding approvals, re-announced after every register so a restarted
+    // daemon still knows this runtime is waiting on a human.
+    let mut pending_approvals = BTreeMap::<(String, String), PendingApprovalSnapshot>::new();
     let mut needs_register = true;
     let mut flush_interval = tokio::time::interval(Duration::from_millis(500));
+    let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_INTERVAL);
 
     loop {
         tokio::select! {
@@ -167,12 +351,79 @@ async fn run_producer_task(
                             push_pending_line(&mut pending_lines, line);
                         }
                     }
-                    ProducerCommand::Shutdown => {
+                    ProducerCommand::ApprovalRequested(approval) => {
+                        let key = (approval.thread_id.clone(), approval.item_id.clone());
+                        if let Some(line) =
+                            encode_approval_requested(&metadata.runtime_id, &approval)
+                        {
+                            push_pending_line(&mut pending_lines, line);
+                        }
+                        pending_approvals.insert(key, approval);
+                    }
+                    ProducerCommand::ApprovalResolved { thread_id, item_id } => {
+                        pending_approvals.remove(&(thread_id.clone(), item_id.clone()));
+                        let params = RuntimeApprovalResolvedParams {
+                            runtime_id: metadata.runtime_id.clone(),
+                            thread_id,
+                            item_id,
+                        };
+                        if let Some(line) = serde_json::to_value(params)
+                            .ok()
+                            .and_then(|params| {
+                                encode_notification(RUNTIME_APPROVAL_RESOLVED_METHOD, params)
+                            })
+                        {
+                            push_pending_line(&mut pending_lines, line);
+                        }
+                    }
+                    ProducerCommand::IncomingCommand { id, params } => {
+                        dispatch_runtime_command(
+                            id,
+                            params,
+                            command_tx.as_ref(),
+                            &connection.task_sender,
+                            &mut pending_lines,
+                        );
+                    }
+                    ProducerCommand::CommandResult { id, result } => {
+                        if let Some(line) = encode_command_result(id, result) {
+                            push_pending_line(&mut pending_lines, line);
+                        }
+                    }
+                    ProducerCommand::ConnectionClosed { generation } => {
+                        if generation == connection.generation {
+                            connection.writer = None;
+                            needs_register = true;
+                        }
+                    }
+                    ProducerCommand::Shutdown(done) => {
+                        flush_pending_lines(
+                            socket_path.as_path(),
+                            &metadata,
+                            &mut connection,
+                            &mut pending_lines,
+                            &pending_approvals,
+                            &mut needs_register,
+                        )
+                        .await;
+                        let _ = done.send(());
                         break;
                     }
                 }
             }
             _ = flush_interval.tick() => {}
+            _ = heartbeat_interval.tick() => {
+                // Only heartbeat over a live connection; a reconnect registers
+                // again, which is proof of life on its own.
+                if connection.writer.is_some()
+                    && let Some(line) = encode_notification(
+                        RUNTIME_HEARTBEAT_METHOD,
+                        serde_json::json!({ "runtimeId": metadata.runtime_id }),
+                    )
+                {
+                    push_pending_line(&mut pending_lines, line);
+                }
+            }
         }
 
         flush_pending_lines(
@@ -180,6 +431,7 @@ async fn run_producer_task(
             &metadata,
             &mut connection,
             &mut pending_lines,
+            &pending_approvals,
             &mut needs_register,
         )
         .await;
@@ -201,22 +453,152 @@ fn push_pending_line(pending_lines: &mut VecDeque<String>, line: String) {
     warn!("codexd producer queue overflowed; dropped {overflow} pending messages");
 }
 
+/// Write half of the daemon connection plus the bookkeeping needed to read
+/// routed commands from the other half.
+struct ProducerConnection {
+    writer: Option<OwnedWriteHalf>,
+    /// Incremented per connection so a stale reader cannot tear down a newer
+    /// connection when it observes EOF.
+    generation: u64,
+    task_sender: mpsc::WeakSender<ProducerCommand>,
+}
+
+fn dispatch_runtime_command(
+    id: JsonValue,
+    params: Option<JsonValue>,
+    command_tx: Option<&mpsc::Sender<RuntimeCommandRequest>>,
+    task_sender: &mpsc::WeakSender<ProducerCommand>,
+    pending_lines: &mut VecDeque<String>,
+) {
+    let params =
+        match serde_json::from_value::<RuntimeCommandParams>(params.unwrap_or(JsonValue::Null)) {
+            Ok(params) => params,
+            Err(err) => {
+                if let Some(line) =
+                    encode_command_result(id, Err(format!("invalid command: {err}")))
+                {
+                    push_pending_line(pending_lines, line);
+                }
+                return;
+            }
+        };
+
+    let Some(command_tx) = command_tx else {
+        if let Some(line) =
+            encode_command_result(id, Err("runtime does not accept commands".to_string()))
+        {
+            push_pending_line(pending_lines, line);
+        }
+        return;
+    };
+
+    let (responder, response_rx) = oneshot::channel();
+    let request = RuntimeCommandRequest {
+        command: params.command,
+        responder,
+    };
+    if command_tx.try_send(request).is_err() {
+        if let Some(line) =
+            encode_command_result(id, Err("runtime is not accepting commands".to_string()))
+        {
+            push_pending_line(pending_lines, line);
+        }
+        return;
+    }
+
+    let task_sender = task_sender.clone();
+    tokio::spawn(async move {
+        let result = response_rx
+            .await
+            .unwrap_or_else(|_| Err("runtime dropped the command".to_string()));
+        if let Some(sender) = task_sender.upgrade() {
+            let _ = sender
+                .send(ProducerCommand::CommandResult { id, result })
+                .await;
+        }
+    });
+}
+
+fn encode_command_result(id: JsonValue, result: Result<(), String>) -> Option<String> {
+    let message = match result {
+        Ok(()) => serde_json::json!({
+            "id": id,
+            "result": {},
+        }),
+        Err(message) => serde_json::json!({
+            "id": id,
+            "error": {
+                "code": COMMAND_ERROR_CODE,
+                "message": message,
+            },
+        }),
+    };
+
+    serde_json::to_string(&message).ok()
+}
+
+/// Reads routed commands from the daemon until the connection closes.
+async fn read_daemon_lines(
+    reader: OwnedReadHalf,
+    generation: u64,
+    task_sender: mpsc::WeakSender<ProducerCommand>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let Ok(value) = serde_json::from_str::<JsonValue>(&line) else {
+            continue;
+        };
+        if value.get("method").and_then(JsonValue::as_str) != Some(RUNTIME_COMMAND_METHOD) {
+            continue;
+        }
+        let Some(id) = value.get("id").cloned() else {
+            continue;
+        };
+        let params = value.get("params").cloned();
+
+        let Some(sender) = task_sender.upgrade() else {
+            return;
+        };
+        if sender
+            .send(ProducerCommand::IncomingCommand { id, params })
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+
+    if let Some(sender) = task_sender.upgrade() {
+        let _ = sender
+            .send(ProducerCommand::ConnectionClosed { generation })
+            .await;
+    }
+}
+
 async fn flush_pending_lines(
     socket_path: &Path,
     metadata: &RuntimeMetadata,
-    connection: &mut Option<UnixStream>,
+    connection: &mut ProducerConnection,
     pending_lines: &mut VecDeque<String>,
+    pending_approvals: &BTreeMap<(String, String), PendingApprovalSnapshot>,
     needs_register: &mut bool,
 ) {
     if pending_lines.is_empty() && !*needs_register {
         return;
     }
 
-    if connection.is_none() {
+    if connection.writer.is_none() {
         match UnixStream::connect(socket_path).await {
             Ok(stream) => {
                 debug!("connected codexd producer socket {}", socket_path.display());
-                *connection = Some(stream);
+                let (reader, writer) = stream.into_split();
+                connection.generation = connection.generation.wrapping_add(1);
+                tokio::spawn(read_daemon_lines(
+                    reader,
+                    connection.generation,
+                    connection.task_sender.clone(),
+                ));
+                connection.writer = Some(writer);
                 *needs_register = true;
             }
             Err(err) => {
@@ -229,7 +611,7 @@ async fn flush_pending_lines(
         }
     }
 
-    let Some(stream) = connection.as_mut() else {
+    let Some(stream) = connection.writer.as_mut() else {
         return;
     };
 
@@ -242,11 +624,21 @@ async fn flush_pending_lines(
         if let Some(register_line) = register_line
             && write_line(stream, &register_line).await.is_err()
         {
-            *connection = None;
+            connection.writer = None;
             return;
         }
 
         *needs_register = false;
+
+        for approval in pending_approvals.values() {
+            if let Some(line) = encode_approval_requested(&metadata.runtime_id, approval)
+                && write_line(stream, &line).await.is_err()
+            {
+                connection.writer = None;
+                *needs_register = true;
+                return;
+            }
+        }
     }
 
     while let Some(line) = pending_lines.front() {
@@ -255,11 +647,25 @@ async fn flush_pending_lines(
             continue;
         }
 
-        *connection = None;
+        connection.writer = None;
         return;
     }
 }
 
+fn encode_approval_requested(
+    runtime_id: &str,
+    approval: &PendingApprovalSnapshot,
+) -> Option<String> {
+    let params = RuntimeApprovalRequestedParams {
+        runtime_id: runtime_id.to_string(),
+        approval: approval.clone(),
+    };
+    encode_notification(
+        RUNTIME_APPROVAL_REQUESTED_METHOD,
+        serde_json::to_value(params).ok()?,
+    )
+}
+
 fn encode_notification(method: &str, params: JsonValue) -> Option<String> {
     let message = serde_json::json!({
         "method": method,
@@ -269,7 +675,7 @@ fn encode_notification(method: &str, params: JsonValue) -> Option<String> {
     serde_json::to_string(&message).ok()
 }
 
-async fn write_line(stream: &mut UnixStream, line: &str) -> std::io::Result<()> {
+async fn write_line(stream: &mut OwnedWriteHalf, line: &str) -> std::io::Result<()> {
     stream.write_all(line.as_bytes()).await?;
     stream.write_all(b"\n").await?;
     stream.flush().await
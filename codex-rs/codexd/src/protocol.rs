@@ -1,3 +1,7 @@
+use codex_app_server_protocol::CommandExecutionApprovalDecision;
+use codex_app_server_protocol::FileChangeApprovalDecision;
+use codex_app_server_protocol::RateLimitSnapshot;
+use codex_app_server_protocol::TokenUsageBreakdown;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
@@ -15,6 +19,42 @@ pub struct HubNotification {
 pub struct ActiveTurnSnapshot {
     pub thread_id: String,
     pub turn_id: String,
+    /// Model reported by the producer's `turn/started`, when it includes one.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Unix timestamp (seconds) at which the turn started: the producer's
+    /// `startedAt` when `turn/started` carries one, otherwise the time the
+    /// daemon saw the notification.
+    #[serde(default)]
+    pub started_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum PendingApprovalKind {
+    CommandExecution,
+    FileChange,
+    UserInput,
+}
+
+/// A request that is blocked until a human answers it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApprovalSnapshot {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub item_id: String,
+    pub kind: PendingApprovalKind,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Model-provided reason for an approval, or the questions asked for user
+    /// input.
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Unix timestamp (seconds) at which the runtime started waiting.
+    pub started_at: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -31,6 +71,8 @@ pub struct RuntimeSnapshot {
     pub display_name: Option<String>,
     #[serde(default)]
     pub active_turns: Vec<ActiveTurnSnapshot>,
+    #[serde(default)]
+    pub pending_approvals: Vec<PendingApprovalSnapshot>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -40,6 +82,55 @@ pub struct CodexdSnapshotResponse {
     pub runtimes: Vec<RuntimeSnapshot>,
 }
 
+/// Latest `thread/tokenUsage/updated` totals for one thread.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadUsageSnapshot {
+    pub thread_id: String,
+    pub total: TokenUsageBreakdown,
+    pub last: TokenUsageBreakdown,
+    #[serde(default)]
+    pub model_context_window: Option<i64>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeUsageSnapshot {
+    pub runtime_id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Sum of `total` across the runtime's threads.
+    pub total: TokenUsageBreakdown,
+    /// Heaviest thread first.
+    pub threads: Vec<ThreadUsageSnapshot>,
+}
+
+/// Most recent `account/rateLimits/updated` seen from any runtime. Limits are
+/// per account, so the newest report wins regardless of which runtime sent it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitsUsageSnapshot {
+    pub runtime_id: String,
+    pub rate_limits: RateLimitSnapshot,
+    pub updated_at: i64,
+}
+
+/// Response to `codexd/usage`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexdUsageResponse {
+    pub seq: u64,
+    /// Sum of `total` across every runtime.
+    pub total: TokenUsageBreakdown,
+    /// Heaviest runtime first.
+    pub runtimes: Vec<RuntimeUsageSnapshot>,
+    #[serde(default)]
+    pub rate_limits: Option<RateLimitsUsageSnapshot>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CodexdSubscribeParams {
@@ -53,6 +144,18 @@ pub struct CodexdSubscribeResponse {
     pub seq: u64,
 }
 
+/// Sent to a subscriber before replay when some events after its `afterSeq`
+/// have already been evicted from the journal. Consumers should re-fetch
+/// `codexd/snapshot` to resynchronize.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexdGapParams {
+    pub requested_after_seq: u64,
+    #[serde(default)]
+    pub oldest_retained_seq: Option<u64>,
+    pub seq: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RuntimeRegisterParams {
@@ -88,6 +191,38 @@ pub struct RuntimeEventParams {
     pub notification: HubNotification,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeApprovalRequestedParams {
+    pub runtime_id: String,
+    pub approval: PendingApprovalSnapshot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeApprovalResolvedParams {
+    pub runtime_id: String,
+    pub thread_id: String,
+    pub item_id: String,
+}
+
+/// Sent periodically by producers so the daemon can tell a live but idle
+/// runtime from one whose process hung or vanished.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeHeartbeatParams {
+    pub runtime_id: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RuntimeStaleReason {
+    /// The runtime's `pid` no longer refers to a running process.
+    ProcessExited,
+    /// No message, including heartbeats, arrived within the stale timeout.
+    HeartbeatTimeout,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RuntimeUnregisterParams {
@@ -107,6 +242,21 @@ pub enum CodexdEventPayload {
         runtime_id: String,
         notification: HubNotification,
     },
+    ApprovalRequested {
+        runtime_id: String,
+        approval: PendingApprovalSnapshot,
+    },
+    ApprovalResolved {
+        runtime_id: String,
+        thread_id: String,
+        item_id: String,
+    },
+    /// Emitted just before a stale runtime is removed along with its turns and
+    /// approvals.
+    RuntimeStale {
+        runtime_id: String,
+        reason: RuntimeStaleReason,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -115,3 +265,57 @@ pub struct CodexdEventEnvelope {
     pub seq: u64,
     pub event: CodexdEventPayload,
 }
+
+/// Action a hub consumer asks a specific runtime to perform.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuntimeCommand {
+    /// Equivalent of `turn/interrupt` for the thread's active turn.
+    #[serde(rename_all = "camelCase")]
+    TurnInterrupt {
+        thread_id: String,
+        #[serde(default)]
+        turn_id: Option<String>,
+    },
+    /// Answers a pending `item/commandExecution/requestApproval`.
+    #[serde(rename_all = "camelCase")]
+    CommandExecutionApproval {
+        thread_id: String,
+        turn_id: String,
+        item_id: String,
+        decision: CommandExecutionApprovalDecision,
+    },
+    /// Answers a pending `item/fileChange/requestApproval`.
+    #[serde(rename_all = "camelCase")]
+    FileChangeApproval {
+        thread_id: String,
+        turn_id: String,
+        item_id: String,
+        decision: FileChangeApprovalDecision,
+    },
+    /// Queues a user message on the thread. If a turn is running the message
+    /// is delivered to it, otherwise it starts a new turn.
+    #[serde(rename_all = "camelCase")]
+    SendMessage { thread_id: String, text: String },
+}
+
+/// Params for `codexd/command`, sent by a hub consumer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexdCommandParams {
+    pub runtime_id: String,
+    pub command: RuntimeCommand,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexdCommandResponse {}
+
+/// Params for `codexd/runtime/command`, routed by the daemon to the producer
+/// connection that owns the runtime.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeCommandParams {
+    pub runtime_id: String,
+    pub command: RuntimeCommand,
+}
@@ -9,6 +9,7 @@ use http::HeaderMap;
 use serde::Deserialize;
 
 use crate::auth::CodexAuth;
+use crate::config::types::StringOrSecret;
 use crate::error::CodexErr;
 use crate::error::ModelCapError;
 use crate::error::RetryLimitReachedError;
@@ -182,9 +183,13 @@ pub(crate) fn auth_provider_from_auth(
         });
     }
 
-    if let Some(token) = provider.experimental_bearer_token.clone() {
+    if let Some(token) = provider
+        .experimental_bearer_token
+        .as_ref()
+        .and_then(StringOrSecret::as_str)
+    {
         return Ok(CoreAuthProvider {
-            token: Some(token),
+            token: Some(token.to_string()),
             account_id: None,
         });
     }
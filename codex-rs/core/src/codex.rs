@@ -43,6 +43,7 @@ use async_channel::Receiver;
 use async_channel::Sender;
 use codex_protocol::ThreadId;
 use codex_protocol::approvals::ExecPolicyAmendment;
+use codex_protocol::approvals::NetworkPolicyAmendment;
 use codex_protocol::config_types::ModeKind;
 use codex_protocol::config_types::Settings;
 use codex_protocol::config_types::WebSearchMode;
@@ -74,6 +75,7 @@ use codex_protocol::request_user_input::RequestUserInputArgs;
 use codex_protocol::request_user_input::RequestUserInputResponse;
 use codex_rmcp_client::ElicitationResponse;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_secrets::redact_secrets_in_json;
 use futures::future::BoxFuture;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
@@ -100,6 +102,7 @@ use tracing::instrument;
 use tracing::trace;
 use tracing::trace_span;
 use tracing::warn;
+use uuid::Uuid;
 
 use crate::ModelProviderInfo;
 use crate::client::ModelClient;
@@ -112,6 +115,7 @@ use crate::config::Config;
 use crate::config::Constrained;
 use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
+use crate::config::edit::ConfigEditsBuilder;
 use crate::config::resolve_web_search_mode_for_turn;
 use crate::config::types::McpServerConfig;
 use crate::config::types::ShellEnvironmentPolicy;
@@ -137,6 +141,7 @@ use crate::mentions::build_connector_slug_counts;
 use crate::mentions::build_skill_name_counts;
 use crate::mentions::collect_explicit_app_paths;
 use crate::mentions::collect_tool_mentions_from_messages;
+use crate::network_proxy::SessionNetworkProxy;
 use crate::project_doc::get_user_instructions;
 use crate::proposed_plan_parser::ProposedPlanParser;
 use crate::proposed_plan_parser::ProposedPlanSegment;
@@ -152,6 +157,7 @@ use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::McpServerRefreshConfig;
+use crate::protocol::NetworkApprovalRequestEvent;
 use crate::protocol::Op;
 use crate::protocol::PlanDeltaEvent;
 use crate::protocol::RateLimitSnapshot;
@@ -177,6 +183,7 @@ use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
 use crate::rollout::metadata;
+use crate::secrets::SecretsInjector;
 use crate::shell;
 use crate::shell_snapshot::ShellSnapshot;
 use crate::skills::SkillError;
@@ -501,6 +508,10 @@ pub(crate) struct Session {
 #[derive(Debug)]
 pub(crate) struct TurnContext {
     pub(crate) sub_id: String,
+    /// Globally unique id minted when the turn starts. `sub_id` comes from a
+    /// per-process counter that restarts on resume, so rollouts and the usage
+    /// ledger identify turns by this instead.
+    pub(crate) turn_uuid: String,
     pub(crate) config: Arc<Config>,
     pub(crate) auth_manager: Option<Arc<AuthManager>>,
     pub(crate) model_info: ModelInfo,
@@ -796,6 +807,7 @@ impl Session {
         let cwd = session_configuration.cwd.clone();
         TurnContext {
             sub_id,
+            turn_uuid: Uuid::new_v4().to_string(),
             config: per_turn_config.clone(),
             auth_manager: auth_manager_for_context,
             model_info: model_info.clone(),
@@ -905,7 +917,6 @@ impl Session {
             }
         };
 
-        let history_meta_fut = crate::message_history::history_metadata(&config);
         let auth_manager_clone = Arc::clone(&auth_manager);
         let config_for_mcp = Arc::clone(&config);
         let auth_and_mcp_fut = async move {
@@ -920,16 +931,16 @@ impl Session {
         };
 
         // Join all independent futures.
-        let (
-            rollout_recorder_and_state_db,
-            (history_log_id, history_entry_count),
-            (auth, mcp_servers, auth_statuses),
-        ) = tokio::join!(rollout_fut, history_meta_fut, auth_and_mcp_fut);
+        let (rollout_recorder_and_state_db, (auth, mcp_servers, auth_statuses)) =
+            tokio::join!(rollout_fut, auth_and_mcp_fut);
 
         let (rollout_recorder, state_db_ctx) = rollout_recorder_and_state_db.map_err(|e| {
             error!("failed to initialize rollout recorder: {e:#}");
             e
         })?;
+        // The message history lives in the state DB when there is one.
+        let (history_log_id, history_entry_count) =
+            crate::message_history::history_metadata(&config, state_db_ctx.as_deref()).await;
         let rollout_path = rollout_recorder
             .as_ref()
             .map(|rec| rec.rollout_path.clone());
@@ -960,6 +971,30 @@ impl Session {
         }
         maybe_push_unstable_features_warning(&config, &mut post_session_configured_events);
 
+        // Without the proxy the sandbox keeps blocking the network, so a failed
+        // start only degrades to the previous behavior.
+        let network_proxy = match SessionNetworkProxy::start(
+            &config,
+            conversation_id,
+            state_db_ctx.clone(),
+        )
+        .await
+        {
+            Ok(network_proxy) => network_proxy,
+            Err(err) => {
+                warn!("failed to start network proxy: {err:#}");
+                post_session_configured_events.push(Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg: EventMsg::Warning(WarningEvent {
+                        message: format!(
+                            "Failed to start the network proxy, sandboxed commands have no network access: {err:#}"
+                        ),
+                    }),
+                });
+                None
+            }
+        };
+
         let auth = auth.as_ref();
         let auth_mode = auth.map(CodexAuth::auth_mode).map(TelemetryAuthMode::from);
         let otel_manager = OtelManager::new(
@@ -1082,6 +1117,8 @@ impl Session {
             file_watcher,
             agent_control,
             state_db: state_db_ctx.clone(),
+            secrets: SecretsInjector::new(config.codex_home.clone(), &config.secrets),
+            network_proxy,
             model_client: ModelClient::new(
                 Some(Arc::clone(&auth_manager)),
                 conversation_id,
@@ -1143,8 +1180,18 @@ impl Session {
             codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
             sandbox_cwd: session_configuration.cwd.clone(),
             use_linux_sandbox_bwrap: config.features.enabled(Feature::UseLinuxSandboxBwrap),
+            network_proxy_ports: sess.network_proxy_ports(),
         };
         let cancel_token = sess.mcp_startup_cancellation_token().await;
+        let mut mcp_servers = mcp_servers;
+        sess.services
+            .secrets
+            .env_for_cwd(&session_configuration.cwd)
+            .await
+            .apply_to_mcp_servers(&mut mcp_servers);
+        if let Some(network_proxy) = &sess.services.network_proxy {
+            network_proxy.apply_to_mcp_servers(&mut mcp_servers);
+        }
 
         sess.services
             .mcp_connection_manager
@@ -1174,6 +1221,15 @@ impl Session {
         self.services.state_db.clone()
     }
 
+    /// Loopback ports of the session's network proxy, empty without one.
+    pub(crate) fn network_proxy_ports(&self) -> Vec<u16> {
+        self.services
+            .network_proxy
+            .as_ref()
+            .map(SessionNetworkProxy::ports)
+            .unwrap_or_default()
+    }
+
     /// Ensure all rollout writes are durably flushed.
     pub(crate) async fn flush_rollout(&self) {
         let recorder = {
@@ -1444,6 +1500,7 @@ impl Session {
                 use_linux_sandbox_bwrap: per_turn_config
                     .features
                     .enabled(Feature::UseLinuxSandboxBwrap),
+                network_proxy_ports: self.network_proxy_ports(),
             };
             if let Err(e) = self
                 .services
@@ -1789,6 +1846,25 @@ impl Session {
         Ok(())
     }
 
+    /// Adds a host to `[network_proxy.policy].allowed_domains` in the user config. The proxy
+    /// picks up the change on its next config reload.
+    pub(crate) async fn persist_network_policy_amendment(
+        &self,
+        amendment: &NetworkPolicyAmendment,
+    ) -> anyhow::Result<()> {
+        let codex_home = self
+            .state
+            .lock()
+            .await
+            .session_configuration
+            .codex_home()
+            .clone();
+        ConfigEditsBuilder::new(&codex_home)
+            .add_network_proxy_allowed_domain(amendment.host())
+            .apply()
+            .await
+    }
+
     async fn turn_context_for_sub_id(&self, sub_id: &str) -> Option<Arc<TurnContext>> {
         let active = self.active_turn.lock().await;
         active
@@ -1885,6 +1961,53 @@ impl Session {
         rx_approve.await.unwrap_or_default()
     }
 
+    /// Emit a network approval request event for a host the network proxy blocked and
+    /// await the user's decision.
+    ///
+    /// Keyed by `sub_id` like [`Session::request_command_approval`]. If the task is aborted,
+    /// this returns the default `ReviewDecision` (`Denied`).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn request_network_approval(
+        &self,
+        turn_context: &TurnContext,
+        call_id: String,
+        command: Vec<String>,
+        cwd: PathBuf,
+        host: String,
+        port: u16,
+        method: Option<String>,
+    ) -> ReviewDecision {
+        let sub_id = turn_context.sub_id.clone();
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let event_id = sub_id.clone();
+        let prev_entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.insert_pending_approval(sub_id, tx_approve)
+                }
+                None => None,
+            }
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending approval for sub_id: {event_id}");
+        }
+
+        let event = EventMsg::NetworkApprovalRequest(NetworkApprovalRequestEvent {
+            call_id,
+            turn_id: turn_context.sub_id.clone(),
+            proposed_network_policy_amendment: NetworkPolicyAmendment::new(host.clone()),
+            host,
+            port,
+            method,
+            command,
+            cwd,
+        });
+        self.send_event(turn_context, event).await;
+        rx_approve.await.unwrap_or_default()
+    }
+
     pub async fn request_patch_approval(
         &self,
         turn_context: &TurnContext,
@@ -2274,6 +2397,15 @@ impl Session {
         self.send_token_count_event(turn_context).await;
     }
 
+    /// Running token totals for the session so far.
+    pub(crate) async fn token_usage_totals(&self) -> TokenUsage {
+        let state = self.state.lock().await;
+        state
+            .token_info()
+            .map(|info| info.total_token_usage)
+            .unwrap_or_default()
+    }
+
     pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
         let history = self.clone_history().await;
         let base_instructions = self.get_base_instructions().await;
@@ -2551,12 +2683,20 @@ impl Session {
         tool: &str,
         arguments: Option<serde_json::Value>,
     ) -> anyhow::Result<CallToolResult> {
-        self.services
+        let mut result = self
+            .services
             .mcp_connection_manager
             .read()
             .await
             .call_tool(server, tool, arguments)
-            .await
+            .await?;
+        for item in &mut result.content {
+            redact_secrets_in_json(item);
+        }
+        if let Some(structured_content) = result.structured_content.as_mut() {
+            redact_secrets_in_json(structured_content);
+        }
+        Ok(result)
     }
 
     pub(crate) async fn parse_mcp_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
@@ -2594,18 +2734,27 @@ impl Session {
     ) {
         let auth = self.services.auth_manager.auth().await;
         let config = self.get_config().await;
-        let mcp_servers = with_codex_apps_mcp(
+        let mut mcp_servers = with_codex_apps_mcp(
             mcp_servers,
             self.features.enabled(Feature::Apps),
             auth.as_ref(),
             config.as_ref(),
         );
+        self.services
+            .secrets
+            .env_for_cwd(&turn_context.cwd)
+            .await
+            .apply_to_mcp_servers(&mut mcp_servers);
+        if let Some(network_proxy) = &self.services.network_proxy {
+            network_proxy.apply_to_mcp_servers(&mut mcp_servers);
+        }
         let auth_statuses = compute_auth_statuses(mcp_servers.iter(), store_mode).await;
         let sandbox_state = SandboxState {
             sandbox_policy: turn_context.sandbox_policy.clone(),
             codex_linux_sandbox_exe: turn_context.codex_linux_sandbox_exe.clone(),
             sandbox_cwd: turn_context.cwd.clone(),
             use_linux_sandbox_bwrap: turn_context.features.enabled(Feature::UseLinuxSandboxBwrap),
+            network_proxy_ports: self.network_proxy_ports(),
         };
         let cancel_token = self.reset_mcp_startup_cancellation_token().await;
 
@@ -2752,6 +2901,9 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ExecApproval { id, decision } => {
                 handlers::exec_approval(&sess, id, decision).await;
             }
+            Op::NetworkApproval { id, decision } => {
+                handlers::network_approval(&sess, id, decision).await;
+            }
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
@@ -3103,6 +3255,33 @@ mod handlers {
         }
     }
 
+    /// Propagate a user's network approval decision to the session.
+    /// Also optionally adds the host to the network proxy allowlist.
+    pub async fn network_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
+        if let ReviewDecision::ApprovedNetworkPolicyAmendment {
+            proposed_network_policy_amendment,
+        } = &decision
+            && let Err(err) = sess
+                .persist_network_policy_amendment(proposed_network_policy_amendment)
+                .await
+        {
+            let message = format!("Failed to add the host to the network allowlist: {err:#}");
+            tracing::warn!("{message}");
+            let warning = EventMsg::Warning(WarningEvent { message });
+            sess.send_event_raw(Event {
+                id: id.clone(),
+                msg: warning,
+            })
+            .await;
+        }
+        match decision {
+            ReviewDecision::Abort => {
+                sess.interrupt_task().await;
+            }
+            other => sess.notify_approval(&id, other).await,
+        }
+    }
+
     pub async fn patch_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
         match decision {
             ReviewDecision::Abort => {
@@ -3131,8 +3310,13 @@ mod handlers {
     pub async fn add_to_history(sess: &Arc<Session>, config: &Arc<Config>, text: String) {
         let id = sess.conversation_id;
         let config = Arc::clone(config);
+        let state_db = sess.state_db();
+        let cwd = sess.state.lock().await.session_configuration.cwd.clone();
         tokio::spawn(async move {
-            if let Err(e) = crate::message_history::append_entry(&text, &id, &config).await {
+            if let Err(e) =
+                crate::message_history::append_entry(&text, &id, &cwd, &config, state_db.as_deref())
+                    .await
+            {
                 warn!("failed to append to message history: {e}");
             }
         });
@@ -3147,14 +3331,20 @@ mod handlers {
     ) {
         let config = Arc::clone(config);
         let sess_clone = Arc::clone(sess);
+        let state_db = sess.state_db();
 
         tokio::spawn(async move {
-            // Run lookup in blocking thread because it does file IO + locking.
-            let entry_opt = tokio::task::spawn_blocking(move || {
-                crate::message_history::lookup(log_id, offset, &config)
-            })
-            .await
-            .unwrap_or(None);
+            let entry_opt = match state_db {
+                Some(state_db) => {
+                    crate::message_history::lookup_in_state_db(&state_db, log_id, offset).await
+                }
+                // Run lookup in blocking thread because it does file IO + locking.
+                None => tokio::task::spawn_blocking(move || {
+                    crate::message_history::lookup(log_id, offset, &config)
+                })
+                .await
+                .unwrap_or(None),
+            };
 
             let event = Event {
                 id: sub_id,
@@ -3615,6 +3805,7 @@ async fn spawn_review_thread(
 
     let review_turn_context = TurnContext {
         sub_id: sub_id.to_string(),
+        turn_uuid: Uuid::new_v4().to_string(),
         config: per_turn_config,
         auth_manager: auth_manager_for_context,
         model_info: model_info.clone(),
@@ -4790,6 +4981,7 @@ async fn try_run_sampling_request(
 ) -> CodexResult<SamplingRequestResult> {
     let collaboration_mode = sess.current_collaboration_mode().await;
     let rollout_item = RolloutItem::TurnContext(TurnContextItem {
+        turn_id: Some(turn_context.turn_uuid.clone()),
         cwd: turn_context.cwd.clone(),
         approval_policy: turn_context.approval_policy,
         sandbox_policy: turn_context.sandbox_policy.clone(),
@@ -5322,6 +5514,17 @@ mod tests {
         assert_eq!(selected, Vec::new());
     }
 
+    #[tokio::test]
+    async fn turns_with_the_same_sub_id_get_distinct_turn_uuids() {
+        let (session, _turn_context) = make_session_and_context().await;
+
+        let first = session.new_default_turn_with_sub_id("1".to_string()).await;
+        let second = session.new_default_turn_with_sub_id("1".to_string()).await;
+
+        assert_eq!(first.sub_id, second.sub_id);
+        assert_ne!(first.turn_uuid, second.turn_uuid);
+    }
+
     #[tokio::test]
     async fn reconstruct_history_matches_live_compactions() {
         let (session, turn_context) = make_session_and_context().await;
@@ -6075,6 +6278,8 @@ mod tests {
             file_watcher,
             agent_control,
             state_db: None,
+            secrets: SecretsInjector::new(config.codex_home.clone(), &config.secrets),
+            network_proxy: None,
             model_client: ModelClient::new(
                 Some(auth_manager.clone()),
                 conversation_id,
@@ -6205,6 +6410,8 @@ mod tests {
             file_watcher,
             agent_control,
             state_db: None,
+            secrets: SecretsInjector::new(config.codex_home.clone(), &config.secrets),
+            network_proxy: None,
             model_client: ModelClient::new(
                 Some(Arc::clone(&auth_manager)),
                 conversation_id,
@@ -9,6 +9,7 @@ use codex_protocol::protocol::ApplyPatchApprovalRequestEvent;
 use codex_protocol::protocol::Event;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ExecApprovalRequestEvent;
+use codex_protocol::protocol::NetworkApprovalRequestEvent;
 use codex_protocol::protocol::Op;
 use codex_protocol::protocol::RequestUserInputEvent;
 use codex_protocol::protocol::SessionSource;
@@ -228,6 +229,20 @@ async fn forward_events(
                         )
                         .await;
                     }
+                    Event {
+                        id,
+                        msg: EventMsg::NetworkApprovalRequest(event),
+                    } => {
+                        handle_network_approval(
+                            &codex,
+                            id,
+                            &parent_session,
+                            &parent_ctx,
+                            event,
+                            &cancel_token,
+                        )
+                        .await;
+                    }
                     Event {
                         id,
                         msg: EventMsg::ApplyPatchApprovalRequest(event),
@@ -341,6 +356,37 @@ async fn handle_exec_approval(
     let _ = codex.submit(Op::ExecApproval { id, decision }).await;
 }
 
+/// Handle a NetworkApprovalRequest by consulting the parent session and replying.
+async fn handle_network_approval(
+    codex: &Codex,
+    id: String,
+    parent_session: &Session,
+    parent_ctx: &TurnContext,
+    event: NetworkApprovalRequestEvent,
+    cancel_token: &CancellationToken,
+) {
+    let NetworkApprovalRequestEvent {
+        call_id,
+        host,
+        port,
+        method,
+        command,
+        cwd,
+        ..
+    } = event;
+    let approval_fut = parent_session
+        .request_network_approval(parent_ctx, call_id, command, cwd, host, port, method);
+    let decision = await_approval_with_cancel(
+        approval_fut,
+        parent_session,
+        &parent_ctx.sub_id,
+        cancel_token,
+    )
+    .await;
+
+    let _ = codex.submit(Op::NetworkApproval { id, decision }).await;
+}
+
 /// Handle an ApplyPatchApprovalRequest by consulting the parent session and replying.
 async fn handle_patch_approval(
     codex: &Codex,
@@ -47,6 +47,9 @@ pub enum ConfigEdit {
     /// Set trust_level under `[projects."<path>"]`,
     /// migrating inline tables to explicit tables.
     SetProjectTrustLevel { path: PathBuf, level: TrustLevel },
+    /// Append a host to `[network_proxy.policy].allowed_domains` unless it is
+    /// already listed.
+    AddNetworkProxyAllowedDomain(String),
     /// Set the value stored at the exact dotted path.
     SetPath {
         segments: Vec<String>,
@@ -79,10 +82,14 @@ pub fn progress_legend_mode_edit(mode: ProgressLegendMode) -> ConfigEdit {
 mod document_helpers {
     use crate::config::types::McpServerConfig;
     use crate::config::types::McpServerTransportConfig;
+    use codex_secrets::SecretName;
+    use std::collections::BTreeMap;
+    use std::collections::HashMap;
     use toml_edit::Array as TomlArray;
     use toml_edit::InlineTable;
     use toml_edit::Item as TomlItem;
     use toml_edit::Table as TomlTable;
+    use toml_edit::Value as TomlValue;
     use toml_edit::value;
 
     pub(super) fn ensure_table_for_write(item: &mut TomlItem) -> Option<&mut TomlTable> {
@@ -133,10 +140,8 @@ mod document_helpers {
                 if !args.is_empty() {
                     entry["args"] = array_from_iter(args.iter().cloned());
                 }
-                if let Some(env) = env
-                    && !env.is_empty()
-                {
-                    entry["env"] = table_from_pairs(env.iter());
+                if let Some(env) = table_with_secret_refs(env.as_ref(), &config.secret_refs) {
+                    entry["env"] = env;
                 }
                 if !env_vars.is_empty() {
                     entry["env_vars"] = array_from_iter(env_vars.iter().cloned());
@@ -155,10 +160,10 @@ mod document_helpers {
                 if let Some(env_var) = bearer_token_env_var {
                     entry["bearer_token_env_var"] = value(env_var.clone());
                 }
-                if let Some(headers) = http_headers
-                    && !headers.is_empty()
+                if let Some(headers) =
+                    table_with_secret_refs(http_headers.as_ref(), &config.secret_refs)
                 {
-                    entry["http_headers"] = table_from_pairs(headers.iter());
+                    entry["http_headers"] = headers;
                 }
                 if let Some(headers) = env_http_headers
                     && !headers.is_empty()
@@ -246,6 +251,33 @@ mod document_helpers {
         TomlItem::Value(array.into())
     }
 
+    /// Like [`table_from_pairs`], but entries that came from a
+    /// `{ secret = "NAME" }` reference are written back as the reference,
+    /// never as the resolved value.
+    fn table_with_secret_refs(
+        values: Option<&HashMap<String, String>>,
+        secret_refs: &BTreeMap<String, SecretName>,
+    ) -> Option<TomlItem> {
+        let mut entries: BTreeMap<&str, TomlValue> = BTreeMap::new();
+        for (key, val) in values.into_iter().flatten() {
+            entries.insert(key, TomlValue::from(val.as_str()));
+        }
+        for (key, name) in secret_refs {
+            let mut reference = InlineTable::new();
+            reference.insert("secret", TomlValue::from(name.as_str()));
+            entries.insert(key, TomlValue::InlineTable(reference));
+        }
+        if entries.is_empty() {
+            return None;
+        }
+        let mut table = TomlTable::new();
+        table.set_implicit(false);
+        for (key, val) in entries {
+            table.insert(key, TomlItem::Value(val));
+        }
+        Some(TomlItem::Table(table))
+    }
+
     fn table_from_pairs<'a, I>(pairs: I) -> TomlItem
     where
         I: IntoIterator<Item = (&'a String, &'a String)>,
@@ -337,6 +369,9 @@ impl ConfigDocument {
             ConfigEdit::SetSkillConfig { path, enabled } => {
                 Ok(self.set_skill_config(path.as_path(), *enabled))
             }
+            ConfigEdit::AddNetworkProxyAllowedDomain(domain) => {
+                Ok(self.add_network_proxy_allowed_domain(domain))
+            }
             ConfigEdit::SetPath { segments, value } => Ok(self.insert(segments, value.clone())),
             ConfigEdit::ClearPath { segments } => Ok(self.clear_owned(segments)),
             ConfigEdit::SetProjectTrustLevel { path, level } => {
@@ -533,6 +568,31 @@ impl ConfigDocument {
         mutated
     }
 
+    fn add_network_proxy_allowed_domain(&mut self, domain: &str) -> bool {
+        let segments = ["network_proxy".to_string(), "policy".to_string()];
+        let Some(policy) = self.descend(&segments, TraversalMode::Create) else {
+            return false;
+        };
+        let Some(item) = policy.get_mut("allowed_domains") else {
+            let mut domains = toml_edit::Array::new();
+            domains.push(domain);
+            policy["allowed_domains"] = TomlItem::Value(domains.into());
+            return true;
+        };
+        let Some(domains) = item.as_array_mut() else {
+            return false;
+        };
+        if domains
+            .iter()
+            .filter_map(toml_edit::Value::as_str)
+            .any(|existing| existing.eq_ignore_ascii_case(domain))
+        {
+            return false;
+        }
+        domains.push(domain);
+        true
+    }
+
     fn scoped_segments(&self, scope: Scope, segments: &[&str]) -> Vec<String> {
         let resolved: Vec<String> = segments
             .iter()
@@ -818,6 +878,12 @@ impl ConfigEditsBuilder {
         self
     }
 
+    pub fn add_network_proxy_allowed_domain(mut self, domain: &str) -> Self {
+        self.edits
+            .push(ConfigEdit::AddNetworkProxyAllowedDomain(domain.to_string()));
+        self
+    }
+
     pub fn with_edits<I>(mut self, edits: I) -> Self
     where
         I: IntoIterator<Item = ConfigEdit>,
@@ -911,6 +977,50 @@ status_line = []
         assert_eq!(contents, "enabled = true\n");
     }
 
+    #[test]
+    fn add_network_proxy_allowed_domain_appends_once() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+        std::fs::write(
+            codex_home.join(CONFIG_TOML_FILE),
+            r#"[network_proxy.policy]
+allowed_domains = ["pypi.org"] # package index
+"#,
+        )
+        .expect("seed config");
+
+        ConfigEditsBuilder::new(codex_home)
+            .add_network_proxy_allowed_domain("github.com")
+            .add_network_proxy_allowed_domain("PyPI.org")
+            .apply_blocking()
+            .expect("persist");
+
+        let contents =
+            std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        let expected = r#"[network_proxy.policy]
+allowed_domains = ["pypi.org", "github.com"] # package index
+"#;
+        assert_eq!(contents, expected);
+    }
+
+    #[test]
+    fn add_network_proxy_allowed_domain_creates_policy_table() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+
+        ConfigEditsBuilder::new(codex_home)
+            .add_network_proxy_allowed_domain("github.com")
+            .apply_blocking()
+            .expect("persist");
+
+        let contents =
+            std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        let expected = r#"[network_proxy.policy]
+allowed_domains = ["github.com"]
+"#;
+        assert_eq!(contents, expected);
+    }
+
     #[test]
     fn set_skill_config_writes_disabled_entry() {
         let tmp = tempdir().expect("tmpdir");
@@ -1417,6 +1527,7 @@ gpt-5 = "gpt-5.1"
                 enabled_tools: Some(vec!["one".to_string(), "two".to_string()]),
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         );
 
@@ -1440,6 +1551,7 @@ gpt-5 = "gpt-5.1"
                 enabled_tools: None,
                 disabled_tools: Some(vec!["forbidden".to_string()]),
                 scopes: None,
+                secret_refs: Default::default(),
             },
         );
 
@@ -1475,6 +1587,38 @@ B = \"2\"
         assert_eq!(raw, expected);
     }
 
+    #[test]
+    fn blocking_replace_mcp_servers_writes_secret_references() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+
+        let mut server: McpServerConfig = toml::from_str(
+            r#"
+command = "github-mcp"
+env = { GITHUB_TOKEN = { secret = "GH_TOKEN" }, LOG_LEVEL = "debug" }
+"#,
+        )
+        .expect("parse server");
+        // Simulate a config whose references were already resolved.
+        if let McpServerTransportConfig::Stdio { env: Some(env), .. } = &mut server.transport {
+            env.insert("GITHUB_TOKEN".to_string(), "resolved-value".to_string());
+        }
+        let servers = BTreeMap::from([("github".to_string(), server)]);
+
+        apply_blocking(codex_home, None, &[ConfigEdit::ReplaceMcpServers(servers)])
+            .expect("persist");
+
+        let raw = std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        let expected = r#"[mcp_servers.github]
+command = "github-mcp"
+
+[mcp_servers.github.env]
+GITHUB_TOKEN = { secret = "GH_TOKEN" }
+LOG_LEVEL = "debug"
+"#;
+        assert_eq!(raw, expected);
+    }
+
     #[test]
     fn blocking_replace_mcp_servers_preserves_inline_comments() {
         let tmp = tempdir().expect("tmpdir");
@@ -1506,6 +1650,7 @@ foo = { command = "cmd" }
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         );
 
@@ -1551,6 +1696,7 @@ foo = { command = "cmd" } # keep me
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         );
 
@@ -1595,6 +1741,7 @@ foo = { command = "cmd", args = ["--flag"] } # keep me
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         );
 
@@ -1640,6 +1787,7 @@ foo = { command = "cmd" }
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         );
 
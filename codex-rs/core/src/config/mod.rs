@@ -62,6 +62,8 @@ use codex_protocol::config_types::WebSearchMode;
 use codex_protocol::config_types::WindowsSandboxLevel;
 use codex_protocol::openai_models::ReasoningEffort;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_secrets::SecretsManager;
+use codex_state::log_db::LogRetention;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use codex_utils_absolute_path::AbsolutePathBufGuard;
 use schemars::JsonSchema;
@@ -84,6 +86,7 @@ mod constraint;
 pub mod edit;
 pub mod profile;
 pub mod schema;
+mod secret_refs;
 pub mod service;
 pub mod types;
 pub use constraint::Constrained;
@@ -306,7 +309,8 @@ pub struct Config {
     /// Directory where Codex writes log files (defaults to `$CODEX_HOME/log`).
     pub log_dir: PathBuf,
 
-    /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
+    /// Settings that govern if and what will be written to the message history
+    /// (the state database, or `~/.codex/history.jsonl` without it).
     pub history: History,
 
     /// When true, session is not persisted on disk. Default to `false`
@@ -400,6 +404,19 @@ pub struct Config {
 
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config::types::OtelConfig,
+
+    /// Model prices used to estimate the cost of recorded usage, keyed by model slug.
+    pub usage_prices: HashMap<String, crate::config::types::ModelPrice>,
+
+    /// Secrets backend and the secrets injected into command environments.
+    pub secrets: crate::config::types::SecretsConfig,
+
+    /// Retention limits applied to the log database.
+    pub log_retention: LogRetention,
+
+    /// `[network_proxy]` settings. When enabled, each session starts the proxy and
+    /// routes sandboxed commands through it.
+    pub network_proxy: codex_network_proxy::NetworkProxySettings,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -605,7 +622,14 @@ fn filter_mcp_servers_by_requirements(
             .get(name)
             .is_some_and(|requirement| mcp_server_matches_requirement(requirement, server));
         if allowed {
-            server.disabled_reason = None;
+            // Keep reasons that do not come from requirements, such as an
+            // unresolved secret.
+            if matches!(
+                server.disabled_reason,
+                Some(McpServerDisabledReason::Requirements { .. })
+            ) {
+                server.disabled_reason = None;
+            }
         } else {
             server.enabled = false;
             server.disabled_reason = Some(McpServerDisabledReason::Requirements {
@@ -927,7 +951,8 @@ pub struct ConfigToml {
     #[serde(default)]
     pub profiles: HashMap<String, ConfigProfile>,
 
-    /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
+    /// Settings that govern if and what will be written to the message history
+    /// (the state database, or `~/.codex/history.jsonl` without it).
     #[serde(default)]
     pub history: Option<History>,
 
@@ -1021,6 +1046,18 @@ pub struct ConfigToml {
     /// OTEL configuration.
     pub otel: Option<crate::config::types::OtelConfigToml>,
 
+    /// Usage reporting settings (model prices for `codex usage`).
+    pub usage: Option<crate::config::types::UsageConfigToml>,
+
+    /// Secrets settings (backend and secrets injected into commands).
+    pub secrets: Option<crate::config::types::SecretsConfigToml>,
+
+    /// Retention limits for the log database (`codex logs`).
+    pub logs: Option<crate::config::types::LogsConfigToml>,
+
+    /// Network proxy that sandboxed commands are routed through.
+    pub network_proxy: Option<codex_network_proxy::NetworkProxySettings>,
+
     /// Tracks whether the Windows onboarding screen has been acknowledged.
     pub windows_wsl_setup_acknowledged: Option<bool>,
 
@@ -1271,6 +1308,9 @@ pub struct ConfigOverrides {
     pub ephemeral: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    /// Leave `{ secret = "NAME" }` references unresolved, so `codex secrets`
+    /// can store a secret that the config already references.
+    pub skip_secret_refs: bool,
 }
 
 /// Resolves the OSS provider from CLI override, profile config, or global config.
@@ -1377,6 +1417,7 @@ impl Config {
             tools_web_search_request: override_tools_web_search_request,
             ephemeral,
             additional_writable_roots,
+            skip_secret_refs,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -1486,10 +1527,34 @@ impl Config {
             model_providers.entry(key).or_insert(provider);
         }
 
+        let secrets =
+            crate::config::types::SecretsConfig::try_from(cfg.secrets.clone().unwrap_or_default())
+                .map_err(|e| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("invalid [secrets] config: {e}"),
+                    )
+                })?;
         let model_provider_id = model_provider
             .or(config_profile.model_provider)
             .or(cfg.model_provider)
             .unwrap_or_else(|| "openai".to_string());
+        let mut mcp_servers = cfg.mcp_servers.clone();
+        if !skip_secret_refs
+            && secret_refs::has_secret_refs(&mcp_servers, model_providers.get(&model_provider_id))
+        {
+            let manager = SecretsManager::new(codex_home.clone(), secrets.backend);
+            secret_refs::resolve_secret_refs(
+                &manager,
+                &resolved_cwd,
+                &config_layer_stack,
+                &mut mcp_servers,
+                model_providers
+                    .get_mut(&model_provider_id)
+                    .map(|provider| (model_provider_id.as_str(), provider)),
+            )?;
+        }
+
         let model_provider = model_providers
             .get(&model_provider_id)
             .ok_or_else(|| {
@@ -1618,7 +1683,7 @@ impl Config {
         let ConfigRequirements {
             approval_policy: mut constrained_approval_policy,
             sandbox_policy: mut constrained_sandbox_policy,
-            mcp_servers,
+            mcp_servers: mcp_server_requirements,
             exec_policy: _,
             enforce_residency,
         } = requirements;
@@ -1630,7 +1695,7 @@ impl Config {
             .set(sandbox_policy)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
 
-        let mcp_servers = constrain_mcp_servers(cfg.mcp_servers.clone(), mcp_servers.as_ref())
+        let mcp_servers = constrain_mcp_servers(mcp_servers, mcp_server_requirements.as_ref())
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
 
         let config = Self {
@@ -1795,6 +1860,10 @@ impl Config {
                     metrics_exporter: OtelExporterKind::Statsig,
                 }
             },
+            usage_prices: cfg.usage.map(|usage| usage.prices).unwrap_or_default(),
+            secrets,
+            log_retention: cfg.logs.map(Into::into).unwrap_or_default(),
+            network_proxy: cfg.network_proxy.unwrap_or_default(),
         };
         Ok(config)
     }
@@ -1942,6 +2011,7 @@ mod tests {
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
+            secret_refs: Default::default(),
         }
     }
 
@@ -1960,6 +2030,7 @@ mod tests {
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
+            secret_refs: Default::default(),
         }
     }
 
@@ -2439,6 +2510,123 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn usage_prices_load_from_config_toml() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[usage.prices.gpt-5]
+input = 1.25
+cached_input = 0.125
+output = 10.0
+"#,
+        )
+        .expect("parse usage prices");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.usage_prices,
+            HashMap::from([(
+                "gpt-5".to_string(),
+                crate::config::types::ModelPrice {
+                    input: 1.25,
+                    cached_input: Some(0.125),
+                    output: 10.0,
+                },
+            )])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn logs_config_sets_retention_limits() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let load = |contents: &str| {
+            let cfg: ConfigToml = toml::from_str(contents).expect("parse logs config");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+            .map(|config| config.log_retention)
+        };
+
+        assert_eq!(load("")?, LogRetention::default());
+        assert_eq!(
+            load(
+                r#"
+[logs]
+retention_days = 14
+max_size_mb = 256
+"#
+            )?,
+            LogRetention {
+                max_age_days: Some(14),
+                max_bytes: Some(256 * 1024 * 1024),
+            }
+        );
+        assert_eq!(
+            load(
+                r#"
+[logs]
+retention_days = 0
+"#
+            )?,
+            LogRetention {
+                max_age_days: None,
+                max_bytes: None,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn secrets_inject_names_are_validated() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[secrets]
+inject = ["GITHUB_TOKEN"]
+"#,
+        )
+        .expect("parse secrets config");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.secrets.inject,
+            vec![codex_secrets::SecretName::new("GITHUB_TOKEN").expect("valid name")]
+        );
+
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[secrets]
+inject = ["github-token"]
+"#,
+        )
+        .expect("parse secrets config");
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("lowercase secret names are rejected");
+        assert!(
+            err.to_string().contains("invalid [secrets] config"),
+            "{err}"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn web_search_mode_defaults_to_none_if_unset() {
         let cfg = ConfigToml::default();
@@ -2840,6 +3028,7 @@ profile = "project"
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         );
 
@@ -2996,6 +3185,7 @@ bearer_token = "secret"
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -3066,6 +3256,7 @@ ZIG_VAR = "3"
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -3116,6 +3307,7 @@ ZIG_VAR = "3"
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -3164,6 +3356,7 @@ ZIG_VAR = "3"
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -3228,6 +3421,7 @@ startup_timeout_sec = 2.0
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
         apply_blocking(
@@ -3304,6 +3498,7 @@ X-Auth = "DOCS_AUTH"
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -3333,6 +3528,7 @@ X-Auth = "DOCS_AUTH"
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         );
         apply_blocking(
@@ -3400,6 +3596,7 @@ url = "https://example.com/mcp"
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
+                    secret_refs: Default::default(),
                 },
             ),
             (
@@ -3419,6 +3616,7 @@ url = "https://example.com/mcp"
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
+                    secret_refs: Default::default(),
                 },
             ),
         ]);
@@ -3501,6 +3699,7 @@ url = "https://example.com/mcp"
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -3545,6 +3744,7 @@ url = "https://example.com/mcp"
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -3980,6 +4180,10 @@ model_verbosity = "high"
                 tui_syntax_highlight_theme: "base16-ocean.dark".to_string(),
                 diff_view: DiffView::Pretty,
                 otel: OtelConfig::default(),
+                usage_prices: HashMap::new(),
+                secrets: crate::config::types::SecretsConfig::default(),
+                log_retention: LogRetention::default(),
+                network_proxy: codex_network_proxy::NetworkProxySettings::default(),
             },
             o3_profile_config
         );
@@ -4074,6 +4278,10 @@ model_verbosity = "high"
             tui_syntax_highlight_theme: "base16-ocean.dark".to_string(),
             diff_view: DiffView::Pretty,
             otel: OtelConfig::default(),
+            usage_prices: HashMap::new(),
+            secrets: crate::config::types::SecretsConfig::default(),
+            log_retention: LogRetention::default(),
+            network_proxy: codex_network_proxy::NetworkProxySettings::default(),
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -4183,6 +4391,10 @@ model_verbosity = "high"
             tui_syntax_highlight_theme: "base16-ocean.dark".to_string(),
             diff_view: DiffView::Pretty,
             otel: OtelConfig::default(),
+            usage_prices: HashMap::new(),
+            secrets: crate::config::types::SecretsConfig::default(),
+            log_retention: LogRetention::default(),
+            network_proxy: codex_network_proxy::NetworkProxySettings::default(),
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -4278,6 +4490,10 @@ model_verbosity = "high"
             tui_syntax_highlight_theme: "base16-ocean.dark".to_string(),
             diff_view: DiffView::Pretty,
             otel: OtelConfig::default(),
+            usage_prices: HashMap::new(),
+            secrets: crate::config::types::SecretsConfig::default(),
+            log_retention: LogRetention::default(),
+            network_proxy: codex_network_proxy::NetworkProxySettings::default(),
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
@@ -4767,6 +4983,46 @@ mcp_oauth_callback_port = 5678
         Ok(())
     }
 
+    #[tokio::test]
+    async fn skip_secret_refs_loads_config_with_unstored_secrets() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            r#"
+[secrets]
+backend = "passphrase"
+
+[mcp_servers.docs]
+command = "docs-server"
+env = { TOKEN = { secret = "DOCS_TOKEN" } }
+"#,
+        )?;
+        let load = |skip_secret_refs| {
+            ConfigBuilder::default()
+                .codex_home(codex_home.path().to_path_buf())
+                .fallback_cwd(Some(codex_home.path().to_path_buf()))
+                .harness_overrides(ConfigOverrides {
+                    skip_secret_refs,
+                    ..Default::default()
+                })
+                .build()
+        };
+
+        load(false)
+            .await
+            .expect_err("an unstored secret should fail the load");
+        let config = load(true).await?;
+        assert_eq!(
+            config.mcp_servers.get()["docs"].secret_refs,
+            [(
+                "TOKEN".to_string(),
+                codex_secrets::SecretName::new("DOCS_TOKEN")?
+            )]
+            .into()
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn requirements_disallowing_default_sandbox_falls_back_to_required_default()
     -> std::io::Result<()> {
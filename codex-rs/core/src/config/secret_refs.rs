@@ -0,0 +1,471 @@
+//! Resolution of `{ secret = "NAME" }` references in MCP server and model
+//! provider configs.
+//!
+//! References are looked up in the secrets environment of the session cwd
+//! first, then in the global scope, so a checked-in project config can name
+//! a credential that every contributor stores locally. Only the active model
+//! provider is resolved, and its references must resolve: the error points
+//! at the config file that declared them. An enabled MCP server whose
+//! reference cannot be resolved is disabled instead, so one missing
+//! credential does not block startup.
+
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use codex_secrets::SecretName;
+use codex_secrets::SecretScope;
+use codex_secrets::SecretsManager;
+use codex_secrets::register_secret_for_redaction;
+use tracing::warn;
+
+use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerDisabledReason;
+use crate::config::types::McpServerTransportConfig;
+use crate::config::types::SecretRef;
+use crate::config::types::StringOrSecret;
+use crate::config_loader::ConfigLayerStack;
+use crate::config_loader::config_error_for_key_path;
+use crate::config_loader::io_error_from_config_error;
+use crate::model_provider_info::ModelProviderInfo;
+
+/// Whether any enabled MCP server or the active model provider references a
+/// secret.
+pub(crate) fn has_secret_refs(
+    mcp_servers: &HashMap<String, McpServerConfig>,
+    model_provider: Option<&ModelProviderInfo>,
+) -> bool {
+    let is_ref = |value: &StringOrSecret| matches!(value, StringOrSecret::Secret(_));
+    mcp_servers
+        .values()
+        .any(|server| server.enabled && !server.secret_refs.is_empty())
+        || model_provider.is_some_and(|provider| {
+            provider
+                .experimental_bearer_token
+                .as_ref()
+                .is_some_and(is_ref)
+                || provider
+                    .http_headers
+                    .iter()
+                    .flat_map(HashMap::values)
+                    .any(is_ref)
+        })
+}
+
+/// Replace the secret references of the enabled MCP servers and of the
+/// active model provider with their values.
+///
+/// Disabled servers and other providers keep their references. An enabled
+/// server with a reference that cannot be resolved is disabled with a
+/// [`McpServerDisabledReason::Secret`].
+pub(crate) fn resolve_secret_refs(
+    manager: &SecretsManager,
+    cwd: &Path,
+    layers: &ConfigLayerStack,
+    mcp_servers: &mut HashMap<String, McpServerConfig>,
+    model_provider: Option<(&str, &mut ModelProviderInfo)>,
+) -> io::Result<()> {
+    let resolver = Resolver {
+        manager,
+        environment_scope: SecretScope::for_cwd(cwd).ok(),
+        layers,
+        stored: OnceCell::new(),
+    };
+
+    if let Some((provider_id, provider)) = model_provider {
+        if let Some(token) = provider.experimental_bearer_token.as_mut() {
+            resolver.resolve_in_place(
+                token,
+                &["model_providers", provider_id, "experimental_bearer_token"],
+            )?;
+        }
+        for (header, value) in provider.http_headers.iter_mut().flatten() {
+            resolver.resolve_in_place(
+                value,
+                &["model_providers", provider_id, "http_headers", header],
+            )?;
+        }
+    }
+
+    for (server_name, server) in mcp_servers.iter_mut() {
+        if !server.enabled || server.secret_refs.is_empty() {
+            continue;
+        }
+        let resolved: Result<Vec<(String, String)>, String> = server
+            .secret_refs
+            .iter()
+            .map(|(key, name)| resolver.lookup(name).map(|value| (key.clone(), value)))
+            .collect();
+        let resolved = match resolved {
+            Ok(resolved) => resolved,
+            Err(message) => {
+                warn!("disabling MCP server `{server_name}`: {message}");
+                server.enabled = false;
+                server.disabled_reason = Some(McpServerDisabledReason::Secret { message });
+                continue;
+            }
+        };
+        let values = match &mut server.transport {
+            McpServerTransportConfig::Stdio { env, .. } => env,
+            McpServerTransportConfig::StreamableHttp { http_headers, .. } => http_headers,
+        };
+        values.get_or_insert_with(HashMap::new).extend(resolved);
+    }
+    Ok(())
+}
+
+struct Resolver<'a> {
+    manager: &'a SecretsManager,
+    environment_scope: Option<SecretScope>,
+    layers: &'a ConfigLayerStack,
+    /// Stored secrets by canonical key. Reading the backend decrypts the
+    /// secrets file, which is deliberately slow, so it happens once per load.
+    stored: OnceCell<Result<HashMap<String, String>, String>>,
+}
+
+impl Resolver<'_> {
+    /// Look `name` up in the environment scope, then the global scope.
+    fn lookup(&self, name: &SecretName) -> Result<String, String> {
+        let stored = self
+            .stored
+            .get_or_init(|| {
+                self.manager
+                    .entries()
+                    .map(|entries| {
+                        entries
+                            .into_iter()
+                            .map(|(entry, value)| (entry.scope.canonical_key(&entry.name), value))
+                            .collect()
+                    })
+                    .map_err(|err| format!("{err:#}"))
+            })
+            .as_ref()
+            .map_err(|err| format!("failed to read secret `{name}`: {err}"))?;
+        let value = self
+            .environment_scope
+            .iter()
+            .chain(std::iter::once(&SecretScope::Global))
+            .find_map(|scope| stored.get(&scope.canonical_key(name)))
+            .ok_or_else(|| {
+                format!("secret `{name}` is not set; store it with `codex secrets set {name}`")
+            })?;
+        register_secret_for_redaction(name, value);
+        Ok(value.clone())
+    }
+
+    fn resolve_in_place(&self, value: &mut StringOrSecret, key_path: &[&str]) -> io::Result<()> {
+        let StringOrSecret::Secret(SecretRef { secret }) = value else {
+            return Ok(());
+        };
+        let name = SecretName::new(secret)
+            .map_err(|err| self.error(key_path, format!("invalid secret reference: {err}")))?;
+        let resolved = self
+            .lookup(&name)
+            .map_err(|message| self.error(key_path, message))?;
+        *value = StringOrSecret::String(resolved);
+        Ok(())
+    }
+
+    fn error(&self, key_path: &[&str], message: String) -> io::Error {
+        match config_error_for_key_path(self.layers, key_path, message.clone()) {
+            Some(config_error) => {
+                io_error_from_config_error(io::ErrorKind::InvalidData, config_error, None)
+            }
+            None => io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {message}", key_path.join(".")),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_loader::ConfigLayerEntry;
+    use crate::config_loader::ConfigLoadError;
+    use codex_app_server_protocol::ConfigLayerSource;
+    use codex_keyring_store::tests::MockKeyringStore;
+    use codex_secrets::SecretListEntry;
+    use codex_secrets::SecretsBackend;
+    use codex_secrets::SecretsBackendKind;
+    use codex_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use tempfile::TempDir;
+
+    const CONFIG: &str = r#"
+[mcp_servers.github]
+command = "github-mcp"
+env = { GITHUB_TOKEN = { secret = "GITHUB_TOKEN" }, LOG_LEVEL = "debug" }
+
+[mcp_servers.disabled]
+url = "https://example.com/mcp"
+enabled = false
+http_headers = { Authorization = { secret = "NOT_STORED" } }
+
+[model_providers.proxy]
+name = "Proxy"
+base_url = "https://proxy.example.com/v1"
+experimental_bearer_token = { secret = "PROXY_TOKEN" }
+"#;
+
+    struct Fixture {
+        _codex_home: TempDir,
+        cwd: TempDir,
+        manager: SecretsManager,
+        layers: ConfigLayerStack,
+        mcp_servers: HashMap<String, McpServerConfig>,
+        model_providers: HashMap<String, ModelProviderInfo>,
+    }
+
+    fn fixture(contents: &str) -> anyhow::Result<Fixture> {
+        let codex_home = TempDir::new()?;
+        let cwd = TempDir::new()?;
+        let config_path = codex_home.path().join("config.toml");
+        std::fs::write(&config_path, contents)?;
+        let value: toml::Value = toml::from_str(contents)?;
+        let layers = ConfigLayerStack::new(
+            vec![ConfigLayerEntry::new(
+                ConfigLayerSource::User {
+                    file: AbsolutePathBuf::try_from(config_path)?,
+                },
+                value.clone(),
+            )],
+            Default::default(),
+            Default::default(),
+        )?;
+        let table = |key: &str| {
+            value
+                .get(key)
+                .cloned()
+                .unwrap_or(toml::Value::Table(Default::default()))
+        };
+        Ok(Fixture {
+            manager: SecretsManager::new_with_keyring_store(
+                codex_home.path().to_path_buf(),
+                SecretsBackendKind::Local,
+                Arc::new(MockKeyringStore::default()),
+            ),
+            mcp_servers: table("mcp_servers").try_into()?,
+            model_providers: table("model_providers").try_into()?,
+            _codex_home: codex_home,
+            cwd,
+            layers,
+        })
+    }
+
+    fn name(raw: &str) -> SecretName {
+        SecretName::new(raw).expect("valid secret name")
+    }
+
+    /// Resolve the references of `fixture`, with `proxy` as the active
+    /// provider when `with_provider` is set.
+    fn resolve(fixture: &mut Fixture, with_provider: bool) -> io::Result<()> {
+        let model_provider = with_provider
+            .then(|| fixture.model_providers.get_mut("proxy"))
+            .flatten()
+            .map(|provider| ("proxy", provider));
+        resolve_secret_refs(
+            &fixture.manager,
+            fixture.cwd.path(),
+            &fixture.layers,
+            &mut fixture.mcp_servers,
+            model_provider,
+        )
+    }
+
+    #[test]
+    fn resolves_references_from_environment_then_global_scope() -> anyhow::Result<()> {
+        let mut fixture = fixture(CONFIG)?;
+        let environment_scope = SecretScope::for_cwd(fixture.cwd.path())?;
+        fixture
+            .manager
+            .set(&environment_scope, &name("GITHUB_TOKEN"), "repo-gh-token")?;
+        fixture.manager.set(
+            &SecretScope::Global,
+            &name("GITHUB_TOKEN"),
+            "global-gh-token",
+        )?;
+        fixture
+            .manager
+            .set(&SecretScope::Global, &name("PROXY_TOKEN"), "proxy-token")?;
+        assert!(has_secret_refs(
+            &fixture.mcp_servers,
+            fixture.model_providers.get("proxy")
+        ));
+
+        resolve(&mut fixture, true)?;
+
+        let McpServerTransportConfig::Stdio { env, .. } = &fixture.mcp_servers["github"].transport
+        else {
+            panic!("github server should use stdio");
+        };
+        assert_eq!(
+            env,
+            &Some(HashMap::from([
+                ("GITHUB_TOKEN".to_string(), "repo-gh-token".to_string()),
+                ("LOG_LEVEL".to_string(), "debug".to_string()),
+            ]))
+        );
+        assert_eq!(
+            fixture.mcp_servers["github"].secret_refs,
+            [("GITHUB_TOKEN".to_string(), name("GITHUB_TOKEN"))].into()
+        );
+        assert_eq!(
+            fixture.model_providers["proxy"].experimental_bearer_token,
+            Some(StringOrSecret::String("proxy-token".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_provider_secret_points_at_the_reference() -> anyhow::Result<()> {
+        let mut fixture = fixture(CONFIG)?;
+        fixture
+            .manager
+            .set(&SecretScope::Global, &name("GITHUB_TOKEN"), "gh-token")?;
+
+        let err = resolve(&mut fixture, true).expect_err("missing secret must fail");
+
+        let config_error = err
+            .get_ref()
+            .and_then(|err| err.downcast_ref::<ConfigLoadError>())
+            .map(ConfigLoadError::config_error)
+            .expect("error should carry a config location");
+        assert_eq!(
+            (
+                config_error.range.start.line,
+                config_error.range.start.column
+            ),
+            (14, 29)
+        );
+        assert_eq!(
+            config_error.message,
+            "secret `PROXY_TOKEN` is not set; store it with `codex secrets set PROXY_TOKEN`"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_mcp_secret_disables_only_that_server() -> anyhow::Result<()> {
+        let mut fixture = fixture(CONFIG)?;
+
+        // `proxy` is not the active provider, so its missing token is fine.
+        resolve(&mut fixture, false)?;
+
+        let github = &fixture.mcp_servers["github"];
+        assert!(!github.enabled);
+        assert_eq!(
+            github.disabled_reason,
+            Some(McpServerDisabledReason::Secret {
+                message: "secret `GITHUB_TOKEN` is not set; store it with `codex secrets set GITHUB_TOKEN`"
+                    .to_string(),
+            })
+        );
+        assert_eq!(
+            fixture.model_providers["proxy"].experimental_bearer_token,
+            Some(StringOrSecret::Secret(SecretRef {
+                secret: "PROXY_TOKEN".to_string(),
+            }))
+        );
+        Ok(())
+    }
+
+    /// Serves fixed secrets and counts how often the store is read.
+    #[derive(Default)]
+    struct CountingBackend {
+        entries: Vec<(SecretListEntry, String)>,
+        reads: AtomicUsize,
+    }
+
+    impl SecretsBackend for CountingBackend {
+        fn set(
+            &self,
+            _scope: &SecretScope,
+            _name: &SecretName,
+            _value: &str,
+        ) -> anyhow::Result<()> {
+            anyhow::bail!("read-only backend")
+        }
+
+        fn get(&self, scope: &SecretScope, name: &SecretName) -> anyhow::Result<Option<String>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self
+                .entries
+                .iter()
+                .find(|(entry, _)| &entry.scope == scope && &entry.name == name)
+                .map(|(_, value)| value.clone()))
+        }
+
+        fn delete(&self, _scope: &SecretScope, _name: &SecretName) -> anyhow::Result<bool> {
+            anyhow::bail!("read-only backend")
+        }
+
+        fn list(
+            &self,
+            _scope_filter: Option<&SecretScope>,
+        ) -> anyhow::Result<Vec<SecretListEntry>> {
+            Ok(self
+                .entries
+                .iter()
+                .map(|(entry, _)| entry.clone())
+                .collect())
+        }
+
+        fn entries(&self) -> anyhow::Result<Vec<(SecretListEntry, String)>> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.entries.clone())
+        }
+
+        fn rekey(&self, _new_passphrase: Option<&str>) -> anyhow::Result<()> {
+            anyhow::bail!("read-only backend")
+        }
+    }
+
+    #[test]
+    fn reads_the_secrets_store_once_per_load() -> anyhow::Result<()> {
+        let mut fixture = fixture(CONFIG)?;
+        let backend = Arc::new(CountingBackend {
+            entries: ["GITHUB_TOKEN", "PROXY_TOKEN"]
+                .into_iter()
+                .map(|raw| {
+                    let entry = SecretListEntry {
+                        scope: SecretScope::Global,
+                        name: name(raw),
+                    };
+                    (entry, format!("{raw}-value"))
+                })
+                .collect(),
+            ..Default::default()
+        });
+        fixture.manager = SecretsManager::with_backend(backend.clone());
+
+        resolve(&mut fixture, true)?;
+
+        assert_eq!(backend.reads.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            fixture.model_providers["proxy"].experimental_bearer_token,
+            Some(StringOrSecret::String("PROXY_TOKEN-value".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_secret_names_are_rejected_when_parsing() {
+        let err = toml::from_str::<McpServerConfig>(
+            r#"
+command = "github-mcp"
+env = { GITHUB_TOKEN = { secret = "github token" } }
+"#,
+        )
+        .expect_err("invalid name must fail");
+        assert!(
+            err.to_string().contains("invalid secret reference"),
+            "unexpected error: {err}"
+        );
+    }
+}
@@ -8,6 +8,9 @@ pub use codex_protocol::config_types::AltScreenMode;
 pub use codex_protocol::config_types::ModeKind;
 pub use codex_protocol::config_types::Personality;
 pub use codex_protocol::config_types::WebSearchMode;
+use codex_secrets::SecretName;
+use codex_secrets::SecretsBackendKind;
+use codex_state::log_db::LogRetention;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -27,7 +30,13 @@ pub const DEFAULT_OTEL_ENVIRONMENT: &str = "dev";
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum McpServerDisabledReason {
     Unknown,
-    Requirements { source: RequirementSource },
+    Requirements {
+        source: RequirementSource,
+    },
+    /// A secret referenced by the server's config could not be resolved.
+    Secret {
+        message: String,
+    },
 }
 
 impl fmt::Display for McpServerDisabledReason {
@@ -37,6 +46,7 @@ impl fmt::Display for McpServerDisabledReason {
             McpServerDisabledReason::Requirements { source } => {
                 write!(f, "requirements ({source})")
             }
+            McpServerDisabledReason::Secret { message } => f.write_str(message),
         }
     }
 }
@@ -50,7 +60,8 @@ pub struct McpServerConfig {
     #[serde(default = "default_enabled")]
     pub enabled: bool,
 
-    /// Reason this server was disabled after applying requirements.
+    /// Reason this server was disabled after applying requirements or
+    /// resolving its secret references.
     #[serde(skip)]
     pub disabled_reason: Option<McpServerDisabledReason>,
 
@@ -77,6 +88,72 @@ pub struct McpServerConfig {
     /// Optional OAuth scopes to request during MCP login.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub scopes: Option<Vec<String>>,
+
+    /// `{ secret = "NAME" }` entries of `env` (stdio) or `http_headers`
+    /// (streamable HTTP), keyed by variable or header name. The config loader
+    /// resolves them into the transport maps; edits write the reference back
+    /// instead of the resolved value.
+    #[serde(skip)]
+    pub secret_refs: BTreeMap<String, SecretName>,
+}
+
+/// `{ secret = "NAME" }`: a reference to a value stored with
+/// `codex secrets set NAME`, resolved when the config is loaded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SecretRef {
+    pub secret: String,
+}
+
+/// A config string that may be given inline or as a [`SecretRef`], so
+/// checked-in configs can name credentials without containing them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum StringOrSecret {
+    String(String),
+    Secret(SecretRef),
+}
+
+impl StringOrSecret {
+    /// The inline or resolved value; `None` while a reference is unresolved.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            Self::Secret(_) => None,
+        }
+    }
+}
+
+impl From<String> for StringOrSecret {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+/// Split a map whose values may be secret references into its inline values
+/// and its (validated) references.
+fn split_secret_refs<E: SerdeError>(
+    map: Option<HashMap<String, StringOrSecret>>,
+    secret_refs: &mut BTreeMap<String, SecretName>,
+) -> Result<Option<HashMap<String, String>>, E> {
+    let Some(map) = map else {
+        return Ok(None);
+    };
+    let mut values = HashMap::with_capacity(map.len());
+    for (key, value) in map {
+        match value {
+            StringOrSecret::String(value) => {
+                values.insert(key, value);
+            }
+            StringOrSecret::Secret(SecretRef { secret }) => {
+                let name = SecretName::new(&secret).map_err(|err| {
+                    E::custom(format!("invalid secret reference for {key}: {err}"))
+                })?;
+                secret_refs.insert(key, name);
+            }
+        }
+    }
+    Ok(Some(values))
 }
 
 // Raw MCP config shape used for deserialization and JSON Schema generation.
@@ -89,12 +166,12 @@ pub(crate) struct RawMcpServerConfig {
     #[serde(default)]
     pub args: Option<Vec<String>>,
     #[serde(default)]
-    pub env: Option<HashMap<String, String>>,
+    pub env: Option<HashMap<String, StringOrSecret>>,
     #[serde(default)]
     pub env_vars: Option<Vec<String>>,
     #[serde(default)]
     pub cwd: Option<PathBuf>,
-    pub http_headers: Option<HashMap<String, String>>,
+    pub http_headers: Option<HashMap<String, StringOrSecret>>,
     #[serde(default)]
     pub env_http_headers: Option<HashMap<String, String>>,
 
@@ -141,6 +218,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
         let scopes = raw.scopes.clone();
+        let mut secret_refs = BTreeMap::new();
 
         fn throw_if_set<E, T>(transport: &str, field: &str, value: Option<&T>) -> Result<(), E>
         where
@@ -167,7 +245,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             McpServerTransportConfig::Stdio {
                 command,
                 args: raw.args.clone().unwrap_or_default(),
-                env: raw.env.clone(),
+                env: split_secret_refs(raw.env.take(), &mut secret_refs)?,
                 env_vars: raw.env_vars.clone().unwrap_or_default(),
                 cwd: raw.cwd.take(),
             }
@@ -180,7 +258,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             McpServerTransportConfig::StreamableHttp {
                 url,
                 bearer_token_env_var: raw.bearer_token_env_var.clone(),
-                http_headers: raw.http_headers.clone(),
+                http_headers: split_secret_refs(raw.http_headers.take(), &mut secret_refs)?,
                 env_http_headers: raw.env_http_headers.take(),
             }
         } else {
@@ -196,6 +274,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled_tools,
             disabled_tools,
             scopes,
+            secret_refs,
         })
     }
 }
@@ -293,15 +372,16 @@ impl UriBasedFileOpener {
     }
 }
 
-/// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
+/// Settings that govern if and what will be written to the message history
+/// (the state database, or `~/.codex/history.jsonl` without it).
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct History {
     /// If true, history entries will not be written to disk.
     pub persistence: HistoryPersistence,
 
-    /// If set, the maximum size of the history file in bytes. The oldest entries
-    /// are dropped once the file exceeds this limit.
+    /// If set, the maximum size of the history in bytes. The oldest entries
+    /// are dropped once the history exceeds this limit.
     pub max_bytes: Option<usize>,
 }
 
@@ -332,6 +412,98 @@ pub struct FeedbackConfigToml {
     pub enabled: Option<bool>,
 }
 
+// ===== Secrets configuration =====
+
+/// Secrets settings loaded from config.toml.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SecretsConfigToml {
+    /// Where secrets are stored. Defaults to `local`.
+    pub backend: Option<SecretsBackendKind>,
+    /// Names of secrets exported into the environment of shell commands and
+    /// MCP stdio servers. Each name is looked up in the current environment's
+    /// scope first, then globally.
+    #[serde(default)]
+    pub inject: Vec<String>,
+}
+
+/// Resolved secrets settings.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SecretsConfig {
+    pub backend: SecretsBackendKind,
+    pub inject: Vec<SecretName>,
+}
+
+impl TryFrom<SecretsConfigToml> for SecretsConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(toml: SecretsConfigToml) -> anyhow::Result<Self> {
+        let inject = toml
+            .inject
+            .iter()
+            .map(|name| SecretName::new(name))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            backend: toml.backend.unwrap_or_default(),
+            inject,
+        })
+    }
+}
+
+// ===== Logs configuration =====
+
+/// Retention limits for the log database, loaded from config.toml.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct LogsConfigToml {
+    /// Delete logs older than this many days. Defaults to 90; `0` keeps logs
+    /// regardless of age.
+    pub retention_days: Option<u32>,
+    /// Delete the oldest logs once the log database grows past this many
+    /// megabytes. Unlimited by default.
+    pub max_size_mb: Option<u64>,
+}
+
+impl From<LogsConfigToml> for LogRetention {
+    fn from(toml: LogsConfigToml) -> Self {
+        let default = LogRetention::default();
+        Self {
+            max_age_days: match toml.retention_days {
+                Some(0) => None,
+                Some(days) => Some(days),
+                None => default.max_age_days,
+            },
+            max_bytes: toml
+                .max_size_mb
+                .map(|mb| mb.saturating_mul(1024 * 1024))
+                .or(default.max_bytes),
+        }
+    }
+}
+
+// ===== Usage configuration =====
+
+/// Usage reporting settings loaded from config.toml.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct UsageConfigToml {
+    /// Prices used by `codex usage` to estimate cost, keyed by model slug.
+    #[serde(default)]
+    pub prices: HashMap<String, ModelPrice>,
+}
+
+/// Price of a model in USD per million tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ModelPrice {
+    /// Price per million uncached input tokens.
+    pub input: f64,
+    /// Price per million cached input tokens. Defaults to `input`.
+    pub cached_input: Option<f64>,
+    /// Price per million output tokens, including reasoning tokens.
+    pub output: f64,
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -182,6 +182,35 @@ where
     None
 }
 
+/// Point at the value for `key_path` in the highest-precedence config file
+/// that sets it. Used for errors found only after the layers were merged and
+/// deserialized, such as a `{ secret = "NAME" }` reference that cannot be
+/// resolved. Returns `None` when that layer has no backing file.
+pub(crate) fn config_error_for_key_path(
+    layers: &ConfigLayerStack,
+    key_path: &[&str],
+    message: impl Into<String>,
+) -> Option<ConfigError> {
+    let layer = layers
+        .get_layers(ConfigLayerStackOrdering::HighestPrecedenceFirst, false)
+        .into_iter()
+        .find(|layer| {
+            key_path
+                .iter()
+                .try_fold(&layer.config, |value, key| value.get(*key))
+                .is_some()
+        })?;
+    let path = config_path_for_layer(layer)?;
+    let range = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|contents| {
+            let span = span_for_key_path(&contents, key_path)?;
+            Some(text_range_from_span(&contents, span))
+        })
+        .unwrap_or_else(default_range);
+    Some(ConfigError::new(path, range, message))
+}
+
 fn config_path_for_layer(layer: &ConfigLayerEntry) -> Option<PathBuf> {
     match &layer.name {
         ConfigLayerSource::System { file } => Some(file.to_path_buf()),
@@ -298,6 +327,19 @@ enum TomlNode<'a> {
 fn span_for_path(contents: &str, path: &SerdePath) -> Option<std::ops::Range<usize>> {
     let doc = contents.parse::<Document<String>>().ok()?;
     let node = node_for_path(doc.as_item(), path)?;
+    span_for_node(&node)
+}
+
+fn span_for_key_path(contents: &str, key_path: &[&str]) -> Option<std::ops::Range<usize>> {
+    let doc = contents.parse::<Document<String>>().ok()?;
+    let mut node = TomlNode::Item(doc.as_item());
+    for key in key_path {
+        node = map_child(&node, key)?;
+    }
+    span_for_node(&node)
+}
+
+fn span_for_node(node: &TomlNode<'_>) -> Option<std::ops::Range<usize>> {
     match node {
         TomlNode::Item(item) => item.span(),
         TomlNode::Table(table) => table.span(),
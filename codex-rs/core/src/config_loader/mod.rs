@@ -45,6 +45,7 @@ pub use diagnostics::ConfigError;
 pub use diagnostics::ConfigLoadError;
 pub use diagnostics::TextPosition;
 pub use diagnostics::TextRange;
+pub(crate) use diagnostics::config_error_for_key_path;
 pub(crate) use diagnostics::config_error_from_toml;
 pub(crate) use diagnostics::first_layer_config_error;
 pub(crate) use diagnostics::first_layer_config_error_from_entries;
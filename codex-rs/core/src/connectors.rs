@@ -44,6 +44,7 @@ pub async fn list_accessible_connectors_from_mcp_tools(
         codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
         sandbox_cwd: env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
         use_linux_sandbox_bwrap: config.features.enabled(Feature::UseLinuxSandboxBwrap),
+        network_proxy_ports: Vec::new(),
     };
 
     mcp_connection_manager
@@ -10,6 +10,8 @@ use std::time::Duration;
 use std::time::Instant;
 
 use async_channel::Sender;
+use codex_secrets::StreamRedactor;
+use codex_secrets::redact_secrets;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
 use tokio::io::BufReader;
@@ -152,6 +154,7 @@ pub async fn process_exec_tool_call(
     sandbox_cwd: &Path,
     codex_linux_sandbox_exe: &Option<PathBuf>,
     use_linux_sandbox_bwrap: bool,
+    network_proxy_ports: &[u16],
     stdout_stream: Option<StdoutStream>,
 ) -> Result<ExecToolCallOutput> {
     let windows_sandbox_level = params.windows_sandbox_level;
@@ -203,6 +206,7 @@ pub async fn process_exec_tool_call(
             sandbox_policy_cwd: sandbox_cwd,
             codex_linux_sandbox_exe: codex_linux_sandbox_exe.as_ref(),
             use_linux_sandbox_bwrap,
+            network_proxy_ports,
             windows_sandbox_level,
         })
         .map_err(CodexErr::from)?;
@@ -586,7 +590,7 @@ impl StreamOutput<String> {
 impl StreamOutput<Vec<u8>> {
     pub fn from_utf8_lossy(&self) -> StreamOutput<String> {
         StreamOutput {
-            text: bytes_to_string_smart(&self.text),
+            text: redact_secrets(&bytes_to_string_smart(&self.text)).into_owned(),
             truncated_after_lines: self.truncated_after_lines,
         }
     }
@@ -730,11 +734,13 @@ async fn consume_truncated_output(
         BufReader::new(stdout_reader),
         stdout_stream.clone(),
         false,
+        StreamRedactor::new(),
     ));
     let stderr_handle = tokio::spawn(read_capped(
         BufReader::new(stderr_reader),
         stdout_stream.clone(),
         true,
+        StreamRedactor::new(),
     ));
 
     let (exit_status, timed_out) = tokio::select! {
@@ -815,6 +821,9 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     mut reader: R,
     stream: Option<StdoutStream>,
     is_stderr: bool,
+    // Deltas are redacted as a stream so a secret split across reads is still
+    // caught; the aggregated output is redacted again once the command finishes.
+    mut redactor: StreamRedactor,
 ) -> io::Result<StreamOutput<Vec<u8>>> {
     let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY.min(EXEC_OUTPUT_MAX_BYTES));
     let mut tmp = [0u8; READ_CHUNK_SIZE];
@@ -829,35 +838,50 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
         if let Some(stream) = &stream
             && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
         {
-            let chunk = tmp[..n].to_vec();
-            let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
-                call_id: stream.call_id.clone(),
-                stream: if is_stderr {
-                    ExecOutputStream::Stderr
-                } else {
-                    ExecOutputStream::Stdout
-                },
-                chunk,
-            });
-            let event = Event {
-                id: stream.sub_id.clone(),
-                msg,
-            };
-            #[allow(clippy::let_unit_value)]
-            let _ = stream.tx_event.send(event).await;
-            emitted_deltas += 1;
+            let chunk = redactor.push(&tmp[..n]);
+            if !chunk.is_empty() {
+                send_output_delta(stream, is_stderr, chunk).await;
+                emitted_deltas += 1;
+            }
         }
 
         append_capped(&mut buf, &tmp[..n], EXEC_OUTPUT_MAX_BYTES);
         // Continue reading to EOF to avoid back-pressure
     }
 
+    if let Some(stream) = &stream
+        && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
+    {
+        let chunk = redactor.finish();
+        if !chunk.is_empty() {
+            send_output_delta(stream, is_stderr, chunk).await;
+        }
+    }
+
     Ok(StreamOutput {
         text: buf,
         truncated_after_lines: None,
     })
 }
 
+async fn send_output_delta(stream: &StdoutStream, is_stderr: bool, chunk: Vec<u8>) {
+    let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+        call_id: stream.call_id.clone(),
+        stream: if is_stderr {
+            ExecOutputStream::Stderr
+        } else {
+            ExecOutputStream::Stdout
+        },
+        chunk,
+    });
+    let event = Event {
+        id: stream.sub_id.clone(),
+        msg,
+    };
+    #[allow(clippy::let_unit_value)]
+    let _ = stream.tx_event.send(event).await;
+}
+
 #[cfg(unix)]
 fn synthetic_exit_status(code: i32) -> ExitStatus {
     use std::os::unix::process::ExitStatusExt;
@@ -876,6 +900,8 @@ fn synthetic_exit_status(code: i32) -> ExitStatus {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use std::collections::VecDeque;
+    use std::sync::Arc;
     use std::time::Duration;
     use tokio::io::AsyncWriteExt;
 
@@ -947,10 +973,99 @@ mod tests {
             writer.write_all(&bytes).await.expect("write");
         });
 
-        let out = read_capped(reader, None, false).await.expect("read");
+        let out = read_capped(reader, None, false, StreamRedactor::new())
+            .await
+            .expect("read");
         assert_eq!(out.text.len(), EXEC_OUTPUT_MAX_BYTES);
     }
 
+    /// Returns one queued chunk per read.
+    struct ChunkedReader(VecDeque<&'static [u8]>);
+
+    impl AsyncRead for ChunkedReader {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            if let Some(chunk) = self.0.pop_front() {
+                buf.put_slice(chunk);
+            }
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn read_capped_redacts_secret_split_across_reads() {
+        let name = codex_secrets::SecretName::new("EXEC_SPLIT_TOKEN").expect("secret name");
+        let mut redactor = codex_secrets::SecretRedactor::default();
+        redactor.add(&name, "split-secret-value");
+        let reader = ChunkedReader(VecDeque::from([
+            b"token=split-sec".as_slice(),
+            b"ret-value\n".as_slice(),
+        ]));
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let stream = StdoutStream {
+            sub_id: "sub".to_string(),
+            call_id: "call".to_string(),
+            tx_event,
+        };
+
+        let out = read_capped(
+            reader,
+            Some(stream),
+            false,
+            StreamRedactor::with_redactor(Arc::new(redactor)),
+        )
+        .await
+        .expect("read");
+        assert_eq!(out.text, b"token=split-secret-value\n");
+
+        let mut streamed = Vec::new();
+        while let Ok(event) = rx_event.try_recv() {
+            let EventMsg::ExecCommandOutputDelta(delta) = event.msg else {
+                panic!("unexpected event {:?}", event.msg);
+            };
+            streamed.extend(delta.chunk);
+        }
+        assert_eq!(
+            String::from_utf8(streamed).expect("utf-8"),
+            "token=[secret:EXEC_SPLIT_TOKEN]\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn read_capped_does_not_flush_past_the_delta_cap() {
+        let name = codex_secrets::SecretName::new("EXEC_CAP_TOKEN").expect("secret name");
+        let mut redactor = codex_secrets::SecretRedactor::default();
+        redactor.add(&name, "never-printed");
+        // Every read leaves the last bytes pending for the final flush.
+        let reader = ChunkedReader(
+            std::iter::repeat_n(
+                b"output line\n".as_slice(),
+                MAX_EXEC_OUTPUT_DELTAS_PER_CALL + 1,
+            )
+            .collect(),
+        );
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let stream = StdoutStream {
+            sub_id: "sub".to_string(),
+            call_id: "call".to_string(),
+            tx_event,
+        };
+
+        read_capped(
+            reader,
+            Some(stream),
+            false,
+            StreamRedactor::with_redactor(Arc::new(redactor)),
+        )
+        .await
+        .expect("read");
+
+        assert_eq!(rx_event.len(), MAX_EXEC_OUTPUT_DELTAS_PER_CALL);
+    }
+
     #[test]
     fn aggregate_output_prefers_stderr_on_contention() {
         let stdout = StreamOutput {
@@ -1122,6 +1237,7 @@ mod tests {
             cwd.as_path(),
             &None,
             false,
+            &[],
             None,
         )
         .await;
@@ -2,6 +2,8 @@ use crate::config::types::EnvironmentVariablePattern;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyInherit;
 use codex_protocol::ThreadId;
+use codex_secrets::PASSPHRASE_ENV_VAR;
+use codex_secrets::PASSPHRASE_FD_ENV_VAR;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
@@ -16,7 +18,8 @@ pub const CODEX_THREAD_ID_ENV_VAR: &str = "CODEX_THREAD_ID";
 /// for [`ShellEnvironmentPolicy`].
 ///
 /// `CODEX_THREAD_ID` is injected when a thread id is provided, even when
-/// `include_only` is set.
+/// `include_only` is set. The secrets passphrase variables are always removed,
+/// whatever the policy says, so commands cannot decrypt the secrets file.
 pub fn create_env(
     policy: &ShellEnvironmentPolicy,
     thread_id: Option<ThreadId>,
@@ -85,7 +88,11 @@ where
         env_map.retain(|k, _| matches_any(k, &policy.include_only));
     }
 
-    // Step 6 – Populate the thread ID environment variable when provided.
+    // Step 6 – Never hand the secrets passphrase to a child process.
+    env_map.remove(PASSPHRASE_ENV_VAR);
+    env_map.remove(PASSPHRASE_FD_ENV_VAR);
+
+    // Step 7 – Populate the thread ID environment variable when provided.
     if let Some(thread_id) = thread_id {
         env_map.insert(CODEX_THREAD_ID_ENV_VAR.to_string(), thread_id.to_string());
     }
@@ -310,4 +317,33 @@ mod tests {
         expected.insert(CODEX_THREAD_ID_ENV_VAR.to_string(), thread_id.to_string());
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn populate_env_always_strips_secrets_passphrase() {
+        let vars = make_vars(&[
+            ("PATH", "/usr/bin"),
+            (PASSPHRASE_ENV_VAR, "correct horse"),
+            (PASSPHRASE_FD_ENV_VAR, "3"),
+        ]);
+
+        let mut policy = ShellEnvironmentPolicy {
+            inherit: ShellEnvironmentPolicyInherit::All,
+            ignore_default_excludes: true,
+            include_only: vec![
+                EnvironmentVariablePattern::new_case_insensitive("PATH"),
+                EnvironmentVariablePattern::new_case_insensitive("CODEX_SECRETS_*"),
+            ],
+            ..Default::default()
+        };
+        policy
+            .r#set
+            .insert(PASSPHRASE_ENV_VAR.to_string(), "battery staple".to_string());
+
+        let result = populate_env(vars, &policy, None);
+
+        let expected: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+        };
+        assert_eq!(result, expected);
+    }
 }
@@ -14,6 +14,9 @@ use tokio::process::Child;
 /// helper accepts a list of `--sandbox-permission`/`-s` flags mirroring the
 /// public CLI. We convert the internal [`SandboxPolicy`] representation into
 /// the equivalent CLI options.
+///
+/// `network_proxy_ports` are the loopback ports of the session's network
+/// proxy, which commands without network access can still reach.
 #[allow(clippy::too_many_arguments)]
 pub async fn spawn_command_under_linux_sandbox<P>(
     codex_linux_sandbox_exe: P,
@@ -22,6 +25,7 @@ pub async fn spawn_command_under_linux_sandbox<P>(
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
     use_bwrap_sandbox: bool,
+    network_proxy_ports: &[u16],
     stdio_policy: StdioPolicy,
     env: HashMap<String, String>,
 ) -> std::io::Result<Child>
@@ -33,6 +37,7 @@ where
         sandbox_policy,
         sandbox_policy_cwd,
         use_bwrap_sandbox,
+        network_proxy_ports,
     );
     let arg0 = Some("codex-linux-sandbox");
     spawn_child_async(
@@ -56,6 +61,7 @@ pub(crate) fn create_linux_sandbox_command_args(
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
     use_bwrap_sandbox: bool,
+    network_proxy_ports: &[u16],
 ) -> Vec<String> {
     #[expect(clippy::expect_used)]
     let sandbox_policy_cwd = sandbox_policy_cwd
@@ -76,6 +82,10 @@ pub(crate) fn create_linux_sandbox_command_args(
     if use_bwrap_sandbox {
         linux_cmd.push("--use-bwrap-sandbox".to_string());
     }
+    for port in network_proxy_ports {
+        linux_cmd.push("--network-proxy-port".to_string());
+        linux_cmd.push(port.to_string());
+    }
 
     // Separator so that command arguments starting with `-` are not parsed as
     // options of the helper itself.
@@ -98,16 +108,39 @@ mod tests {
         let cwd = Path::new("/tmp");
         let policy = SandboxPolicy::ReadOnly;
 
-        let with_bwrap = create_linux_sandbox_command_args(command.clone(), &policy, cwd, true);
+        let with_bwrap =
+            create_linux_sandbox_command_args(command.clone(), &policy, cwd, true, &[]);
         assert_eq!(
             with_bwrap.contains(&"--use-bwrap-sandbox".to_string()),
             true
         );
 
-        let without_bwrap = create_linux_sandbox_command_args(command, &policy, cwd, false);
+        let without_bwrap = create_linux_sandbox_command_args(command, &policy, cwd, false, &[]);
         assert_eq!(
             without_bwrap.contains(&"--use-bwrap-sandbox".to_string()),
             false
         );
     }
+
+    #[test]
+    fn network_proxy_ports_are_forwarded() {
+        let command = vec!["/bin/true".to_string()];
+        let args = create_linux_sandbox_command_args(
+            command,
+            &SandboxPolicy::ReadOnly,
+            Path::new("/tmp"),
+            false,
+            &[3128, 8081],
+        );
+        let separator = args.iter().position(|arg| arg == "--").expect("separator");
+        assert_eq!(
+            args[separator - 4..separator].to_vec(),
+            vec![
+                "--network-proxy-port".to_string(),
+                "3128".to_string(),
+                "--network-proxy-port".to_string(),
+                "8081".to_string(),
+            ]
+        );
+    }
 }
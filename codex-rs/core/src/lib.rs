@@ -49,12 +49,14 @@ mod mcp_tool_call;
 mod mentions;
 mod message_history;
 mod model_provider_info;
+pub mod network_proxy;
 pub mod parse_command;
 pub mod path_utils;
 pub mod personality_migration;
 pub mod powershell;
 mod proposed_plan_parser;
 pub mod sandboxing;
+mod secrets;
 mod session_prefix;
 mod stream_events_utils;
 mod tagged_block_parser;
@@ -91,9 +93,11 @@ pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
 pub mod project_doc;
+pub mod project_knowledge;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
+pub mod session_bundle;
 pub mod shell;
 pub mod shell_snapshot;
 pub mod skills;
@@ -385,6 +385,7 @@ fn mcp_dependency_to_server_config(
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
+            secret_refs: Default::default(),
         });
     }
 
@@ -408,6 +409,7 @@ fn mcp_dependency_to_server_config(
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
+            secret_refs: Default::default(),
         });
     }
 
@@ -461,6 +463,7 @@ mod tests {
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -508,6 +511,7 @@ mod tests {
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
         )]);
 
@@ -329,6 +329,10 @@ pub struct SandboxState {
     pub sandbox_cwd: PathBuf,
     #[serde(default)]
     pub use_linux_sandbox_bwrap: bool,
+    /// Loopback ports of the session's network proxy, reachable from
+    /// sandboxed commands that have no network access.
+    #[serde(default)]
+    pub network_proxy_ports: Vec<u16>,
 }
 
 /// A thin wrapper around a set of running [`RmcpClient`] instances.
@@ -1331,6 +1335,7 @@ mod tests {
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -1377,6 +1382,7 @@ mod tests {
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
+                secret_refs: Default::default(),
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -15,6 +15,10 @@
 //! that writes up to `PIPE_BUF` bytes are atomic in that case.
 //! Note: `conversation_id` stores the thread id; the field name is preserved for
 //! backwards compatibility with existing history files.
+//!
+//! When the state database is available (the `sqlite` feature) the history is
+//! kept there instead, together with the cwd of each message, and an existing
+//! `history.jsonl` is imported on the next session start.
 
 use std::fs::File;
 use std::fs::OpenOptions;
@@ -38,7 +42,11 @@ use tokio::io::AsyncReadExt;
 use crate::config::Config;
 use crate::config::types::HistoryPersistence;
 
+use chrono::DateTime;
+use chrono::Utc;
 use codex_protocol::ThreadId;
+use codex_state::MessageHistoryEntry;
+use codex_state::StateRuntime;
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
 #[cfg(unix)]
@@ -47,6 +55,9 @@ use std::os::unix::fs::PermissionsExt;
 /// Filename that stores the message history inside `~/.codex`.
 const HISTORY_FILENAME: &str = "history.jsonl";
 
+/// Name `history.jsonl` is renamed to once imported into the state database.
+const MIGRATED_HISTORY_FILENAME: &str = "history.jsonl.migrated";
+
 /// When history exceeds the hard cap, trim it down to this fraction of `max_bytes`.
 const HISTORY_SOFT_CAP_RATIO: f64 = 0.8;
 
@@ -66,13 +77,16 @@ fn history_filepath(config: &Config) -> PathBuf {
     path
 }
 
-/// Append a `text` entry associated with `conversation_id` to the history file. Uses
-/// advisory file locking to ensure that concurrent writes do not interleave,
-/// which entails a small amount of blocking I/O internally.
+/// Append a `text` entry associated with `conversation_id` to the history,
+/// stored in `state_db` when available and in the history file otherwise.
+/// The file uses advisory locking to ensure that concurrent writes do not
+/// interleave, which entails a small amount of blocking I/O internally.
 pub(crate) async fn append_entry(
     text: &str,
     conversation_id: &ThreadId,
+    cwd: &Path,
     config: &Config,
+    state_db: Option<&StateRuntime>,
 ) -> Result<()> {
     match config.history.persistence {
         HistoryPersistence::SaveAll => {
@@ -86,6 +100,19 @@ pub(crate) async fn append_entry(
 
     // TODO: check `text` for sensitive patterns
 
+    if let Some(state_db) = state_db {
+        let entry = MessageHistoryEntry {
+            thread_id: Some(*conversation_id),
+            cwd: Some(cwd.to_path_buf()),
+            text: text.to_string(),
+            ts: Utc::now(),
+        };
+        return state_db
+            .append_message_history(&entry, config.history.max_bytes)
+            .await
+            .map_err(|e| std::io::Error::other(format!("failed to store history entry: {e}")));
+    }
+
     // Resolve `~/.codex/history.jsonl` and ensure the parent directory exists.
     let path = history_filepath(config);
     if let Some(parent) = path.parent() {
@@ -243,11 +270,28 @@ fn trim_target_bytes(max_bytes: u64, newest_entry_len: u64) -> u64 {
     soft_cap_bytes.max(newest_entry_len)
 }
 
-/// Asynchronously fetch the history file's *identifier* (inode on Unix) and
-/// the current number of entries by counting newline characters.
-pub(crate) async fn history_metadata(config: &Config) -> (u64, usize) {
-    let path = history_filepath(config);
-    history_metadata_for_file(&path).await
+/// Asynchronously fetch the history's *identifier* and the current number of
+/// entries.
+///
+/// For the history file the identifier is its inode on Unix and entries are
+/// counted by newline characters. In the state database it is the id of the
+/// newest entry; a `history.jsonl` left from before is imported first.
+pub(crate) async fn history_metadata(
+    config: &Config,
+    state_db: Option<&StateRuntime>,
+) -> (u64, usize) {
+    let Some(state_db) = state_db else {
+        let path = history_filepath(config);
+        return history_metadata_for_file(&path).await;
+    };
+    migrate_history_file(&config.codex_home, state_db).await;
+    match state_db.message_history_metadata().await {
+        Ok((newest_id, count)) => (u64::try_from(newest_id).unwrap_or(0), count),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to read message history metadata");
+            (0, 0)
+        }
+    }
 }
 
 /// Given a `log_id` (on Unix this is the file's inode number,
@@ -263,6 +307,76 @@ pub(crate) fn lookup(log_id: u64, offset: usize, config: &Config) -> Option<Hist
     lookup_history_entry(&path, log_id, offset)
 }
 
+/// Like [`lookup`], for a history kept in the state database.
+pub(crate) async fn lookup_in_state_db(
+    state_db: &StateRuntime,
+    log_id: u64,
+    offset: usize,
+) -> Option<HistoryEntry> {
+    let newest_id = i64::try_from(log_id).ok()?;
+    match state_db.message_history_entry(newest_id, offset).await {
+        Ok(entry) => entry.map(|entry| HistoryEntry {
+            session_id: entry
+                .thread_id
+                .map(|thread_id| thread_id.to_string())
+                .unwrap_or_default(),
+            ts: u64::try_from(entry.ts.timestamp()).unwrap_or(0),
+            text: entry.text,
+        }),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to read message history entry");
+            None
+        }
+    }
+}
+
+/// Import the `history.jsonl` of `codex_home`, if any, into `state_db`.
+///
+/// The file is renamed to [`MIGRATED_HISTORY_FILENAME`] before it is read so
+/// concurrent sessions import it once, and renamed back if the import fails.
+/// Lines that cannot be parsed are skipped.
+async fn migrate_history_file(codex_home: &Path, state_db: &StateRuntime) {
+    let path = codex_home.join(HISTORY_FILENAME);
+    let migrated_path = codex_home.join(MIGRATED_HISTORY_FILENAME);
+    match fs::rename(&path, &migrated_path).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to claim history file for migration");
+            return;
+        }
+    }
+
+    let result = match fs::read_to_string(&migrated_path).await {
+        Ok(contents) => {
+            let entries = contents
+                .lines()
+                .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+                .map(|entry| MessageHistoryEntry {
+                    thread_id: ThreadId::from_string(&entry.session_id).ok(),
+                    cwd: None,
+                    text: entry.text,
+                    ts: i64::try_from(entry.ts)
+                        .ok()
+                        .and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0))
+                        .unwrap_or_default(),
+                })
+                .collect::<Vec<_>>();
+            state_db.import_message_history(&entries).await
+        }
+        Err(e) => Err(e.into()),
+    };
+    match result {
+        Ok(count) => tracing::info!("imported {count} entries from {HISTORY_FILENAME}"),
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to import history file");
+            if let Err(e) = fs::rename(&migrated_path, &path).await {
+                tracing::warn!(error = %e, "failed to restore history file");
+            }
+        }
+    }
+}
+
 /// On Unix systems, ensure the file permissions are `0o600` (rw-------). If the
 /// permissions cannot be changed the error is propagated to the caller.
 #[cfg(unix)]
@@ -506,9 +620,15 @@ mod tests {
 
         let history_path = codex_home.path().join("history.jsonl");
 
-        append_entry(&entry_one, &conversation_id, &config)
-            .await
-            .expect("write first entry");
+        append_entry(
+            &entry_one,
+            &conversation_id,
+            codex_home.path(),
+            &config,
+            None,
+        )
+        .await
+        .expect("write first entry");
 
         let first_len = std::fs::metadata(&history_path).expect("metadata").len();
         let limit_bytes = first_len + 10;
@@ -516,9 +636,15 @@ mod tests {
         config.history.max_bytes =
             Some(usize::try_from(limit_bytes).expect("limit should fit into usize"));
 
-        append_entry(&entry_two, &conversation_id, &config)
-            .await
-            .expect("write second entry");
+        append_entry(
+            &entry_two,
+            &conversation_id,
+            codex_home.path(),
+            &config,
+            None,
+        )
+        .await
+        .expect("write second entry");
 
         let contents = std::fs::read_to_string(&history_path).expect("read history");
 
@@ -553,15 +679,27 @@ mod tests {
 
         let history_path = codex_home.path().join("history.jsonl");
 
-        append_entry(&short_entry, &conversation_id, &config)
-            .await
-            .expect("write first entry");
+        append_entry(
+            &short_entry,
+            &conversation_id,
+            codex_home.path(),
+            &config,
+            None,
+        )
+        .await
+        .expect("write first entry");
 
         let short_entry_len = std::fs::metadata(&history_path).expect("metadata").len();
 
-        append_entry(&long_entry, &conversation_id, &config)
-            .await
-            .expect("write second entry");
+        append_entry(
+            &long_entry,
+            &conversation_id,
+            codex_home.path(),
+            &config,
+            None,
+        )
+        .await
+        .expect("write second entry");
 
         let two_entry_len = std::fs::metadata(&history_path).expect("metadata").len();
 
@@ -574,9 +712,15 @@ mod tests {
                 .expect("max bytes should fit into usize"),
         );
 
-        append_entry(&long_entry, &conversation_id, &config)
-            .await
-            .expect("write third entry");
+        append_entry(
+            &long_entry,
+            &conversation_id,
+            codex_home.path(),
+            &config,
+            None,
+        )
+        .await
+        .expect("write third entry");
 
         let contents = std::fs::read_to_string(&history_path).expect("read history");
 
@@ -613,4 +757,74 @@ mod tests {
         assert_eq!(pruned_len, long_entry_len);
         assert!(pruned_len <= soft_cap_bytes.max(long_entry_len));
     }
+
+    #[tokio::test]
+    async fn history_file_is_imported_into_state_db() {
+        let codex_home = TempDir::new().expect("create temp dir");
+        let config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .build()
+            .await
+            .expect("load config");
+        let state_db = StateRuntime::init(
+            codex_home.path().to_path_buf(),
+            "test-provider".to_string(),
+            None,
+        )
+        .await
+        .expect("initialize state db");
+
+        let thread_id = ThreadId::new();
+        let history_path = codex_home.path().join(HISTORY_FILENAME);
+        let mut file = File::create(&history_path).expect("create history file");
+        for text in ["first", "second", "second"] {
+            let entry = HistoryEntry {
+                session_id: thread_id.to_string(),
+                ts: 1,
+                text: text.to_string(),
+            };
+            writeln!(
+                file,
+                "{}",
+                serde_json::to_string(&entry).expect("serialize history entry")
+            )
+            .expect("write history entry");
+        }
+        writeln!(file, "not json").expect("write invalid line");
+
+        let (log_id, count) = history_metadata(&config, Some(&state_db)).await;
+        assert_eq!(count, 2);
+        assert!(!history_path.exists());
+        assert!(codex_home.path().join(MIGRATED_HISTORY_FILENAME).exists());
+        assert_eq!(
+            lookup_in_state_db(&state_db, log_id, 1).await,
+            Some(HistoryEntry {
+                session_id: thread_id.to_string(),
+                ts: 1,
+                text: "second".to_string(),
+            })
+        );
+
+        append_entry(
+            "third",
+            &thread_id,
+            codex_home.path(),
+            &config,
+            Some(&state_db),
+        )
+        .await
+        .expect("append entry");
+        assert!(
+            !history_path.exists(),
+            "entries are no longer written to the file"
+        );
+        let (log_id, count) = history_metadata(&config, Some(&state_db)).await;
+        assert_eq!(count, 3);
+        assert_eq!(
+            lookup_in_state_db(&state_db, log_id, 2)
+                .await
+                .map(|entry| entry.text),
+            Some("third".to_string())
+        );
+    }
 }
@@ -6,6 +6,7 @@
 //!      key. These override or extend the defaults at runtime.
 
 use crate::auth::AuthMode;
+use crate::config::types::StringOrSecret;
 use crate::error::EnvVarError;
 use codex_api::Provider as ApiProvider;
 use codex_api::is_azure_responses_wire_base_url;
@@ -73,8 +74,9 @@ pub struct ModelProviderInfo {
 
     /// Value to use with `Authorization: Bearer <token>` header. Use of this
     /// config is discouraged in favor of `env_key` for security reasons, but
-    /// this may be necessary when using this programmatically.
-    pub experimental_bearer_token: Option<String>,
+    /// this may be necessary when using this programmatically. May be a
+    /// `{ secret = "NAME" }` reference instead of the token itself.
+    pub experimental_bearer_token: Option<StringOrSecret>,
 
     /// Which wire protocol this provider expects.
     #[serde(default)]
@@ -84,8 +86,9 @@ pub struct ModelProviderInfo {
     pub query_params: Option<HashMap<String, String>>,
 
     /// Additional HTTP headers to include in requests to this provider where
-    /// the (key, value) pairs are the header name and value.
-    pub http_headers: Option<HashMap<String, String>>,
+    /// the (key, value) pairs are the header name and value. Values may be
+    /// `{ secret = "NAME" }` references.
+    pub http_headers: Option<HashMap<String, StringOrSecret>>,
 
     /// Optional HTTP headers to include in requests to this provider where the
     /// (key, value) pairs are the header name and _environment variable_ whose
@@ -120,6 +123,9 @@ impl ModelProviderInfo {
         let mut headers = HeaderMap::new();
         if let Some(extra) = &self.http_headers {
             for (k, v) in extra {
+                let Some(v) = v.as_str() else {
+                    continue;
+                };
                 if let (Ok(name), Ok(value)) = (HeaderName::try_from(k), HeaderValue::try_from(v)) {
                     headers.insert(name, value);
                 }
@@ -241,9 +247,12 @@ impl ModelProviderInfo {
             wire_api: WireApi::Responses,
             query_params: None,
             http_headers: Some(
-                [("version".to_string(), env!("CARGO_PKG_VERSION").to_string())]
-                    .into_iter()
-                    .collect(),
+                [(
+                    "version".to_string(),
+                    env!("CARGO_PKG_VERSION").to_string().into(),
+                )]
+                .into_iter()
+                .collect(),
             ),
             env_http_headers: Some(
                 [
@@ -420,7 +429,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             wire_api: WireApi::Responses,
             query_params: None,
             http_headers: Some(maplit::hashmap! {
-                "X-Example-Header".to_string() => "example-value".to_string(),
+                "X-Example-Header".to_string() => "example-value".to_string().into(),
             }),
             env_http_headers: Some(maplit::hashmap! {
                 "X-Example-Env-Header".to_string() => "EXAMPLE_ENV_VAR".to_string(),
@@ -0,0 +1,824 @@
+//! In-process network proxy for sandboxed commands.
+//!
+//! When `[network_proxy] enabled = true`, each session starts a
+//! [`codex_network_proxy::NetworkProxy`] on loopback ports chosen by the OS and
+//! points shell commands, unified exec processes and MCP stdio servers at it
+//! through the standard proxy environment variables. With the Linux sandbox,
+//! commands without network access then run in their own network namespace
+//! where only those ports lead anywhere (to the proxy), so the domain policy
+//! of the proxy is the only way out.
+//!
+//! With `mitm = true` the proxy decrypts HTTPS using a CA kept under
+//! `codex_home`, and commands get the usual CA bundle variables pointing at it.
+//!
+//! Each command presents a random token issued for its call as the proxy user
+//! name; [`ClientTokens`] maps it back to the call. When the allowlist blocks
+//! a host, [`NetworkApprovals`] records it against that command so the
+//! orchestrator can ask the user and rerun the command.
+//!
+//! With the state database available, [`NetworkAuditRecorder`] logs every
+//! request the proxy decides on, tagged with the thread, turn and call that
+//! started the process.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_network_proxy::ConfigLayer;
+use codex_network_proxy::ConfigLayers;
+use codex_network_proxy::ConfigLoader;
+use codex_network_proxy::NetworkAuditEvent;
+use codex_network_proxy::NetworkAuditSink;
+use codex_network_proxy::NetworkDecision;
+use codex_network_proxy::NetworkPolicyDecider;
+use codex_network_proxy::NetworkPolicyRequest;
+use codex_network_proxy::NetworkProxy;
+use codex_network_proxy::NetworkProxyHandle;
+use codex_network_proxy::NetworkProxyState;
+use codex_protocol::ThreadId;
+use codex_state::NetworkAuditDecision;
+use codex_state::NetworkAuditEntry;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use toml::Value as TomlValue;
+use tracing::info;
+use tracing::warn;
+use url::Url;
+use uuid::Uuid;
+
+use crate::config::CONFIG_TOML_FILE;
+use crate::config::Config;
+use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerTransportConfig;
+use crate::config_loader::CloudRequirementsLoader;
+use crate::config_loader::ConfigLayerStack;
+use crate::config_loader::ConfigLayerStackOrdering;
+use crate::config_loader::LoaderOverrides;
+use crate::config_loader::load_config_layers_state;
+use crate::state_db::StateDbHandle;
+
+/// Client tokens remembered at once; requests with older ones are treated as
+/// anonymous.
+const MAX_CLIENT_TOKENS: usize = 1024;
+
+/// Directory under `codex_home` holding the CA used to intercept HTTPS.
+pub const NETWORK_PROXY_CA_DIR: &str = "network-proxy";
+
+/// Variables telling common clients (OpenSSL, curl, Python requests, pip,
+/// Node, npm, cargo and git) which CA bundle to trust.
+const CA_BUNDLE_VARS: &[&str] = &[
+    "SSL_CERT_FILE",
+    "CURL_CA_BUNDLE",
+    "REQUESTS_CA_BUNDLE",
+    "PIP_CERT",
+    "NODE_EXTRA_CA_CERTS",
+    "npm_config_cafile",
+    "CARGO_HTTP_CAINFO",
+    "GIT_SSL_CAINFO",
+];
+
+/// Loads the proxy policy from the same config layers as the rest of Codex.
+pub struct CoreConfigLoader {
+    codex_home: PathBuf,
+    cwd: Option<AbsolutePathBuf>,
+    cli_overrides: Vec<(String, TomlValue)>,
+}
+
+impl CoreConfigLoader {
+    pub fn new(
+        codex_home: PathBuf,
+        cwd: Option<AbsolutePathBuf>,
+        cli_overrides: Vec<(String, TomlValue)>,
+    ) -> Self {
+        Self {
+            codex_home,
+            cwd,
+            cli_overrides,
+        }
+    }
+
+    /// A loader that reproduces the layers `config` was built from, including
+    /// its `-c` overrides.
+    pub fn for_config(config: &Config) -> Self {
+        let cwd = AbsolutePathBuf::from_absolute_path(&config.cwd).ok();
+        Self::new(
+            config.codex_home.clone(),
+            cwd,
+            session_flag_overrides(&config.config_layer_stack),
+        )
+    }
+}
+
+#[async_trait]
+impl ConfigLoader for CoreConfigLoader {
+    async fn load(&self) -> anyhow::Result<ConfigLayers> {
+        let stack = load_config_layers_state(
+            &self.codex_home,
+            self.cwd.clone(),
+            &self.cli_overrides,
+            LoaderOverrides::default(),
+            CloudRequirementsLoader::default(),
+        )
+        .await?;
+        let layers = stack
+            .get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst, false)
+            .into_iter()
+            .map(|layer| ConfigLayer {
+                config: layer.config.clone(),
+                path: layer_path(&layer.name),
+                user_controlled: is_user_controlled_layer(&layer.name),
+            })
+            .collect();
+        Ok(ConfigLayers {
+            effective_config: stack.effective_config(),
+            layers,
+            cfg_path: self.codex_home.join(CONFIG_TOML_FILE),
+        })
+    }
+}
+
+/// The `-c` overrides of a loaded config, as top-level key/value pairs.
+fn session_flag_overrides(stack: &ConfigLayerStack) -> Vec<(String, TomlValue)> {
+    stack
+        .get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst, false)
+        .into_iter()
+        .filter(|layer| matches!(layer.name, ConfigLayerSource::SessionFlags))
+        .filter_map(|layer| layer.config.as_table())
+        .flat_map(|table| {
+            table
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+        })
+        .collect()
+}
+
+fn layer_path(source: &ConfigLayerSource) -> Option<PathBuf> {
+    match source {
+        ConfigLayerSource::System { file }
+        | ConfigLayerSource::User { file }
+        | ConfigLayerSource::LegacyManagedConfigTomlFromFile { file } => {
+            Some(file.as_path().to_path_buf())
+        }
+        ConfigLayerSource::Project { dot_codex_folder } => dot_codex_folder
+            .join(CONFIG_TOML_FILE)
+            .ok()
+            .map(|path| path.as_path().to_path_buf()),
+        ConfigLayerSource::Mdm { .. }
+        | ConfigLayerSource::SessionFlags
+        | ConfigLayerSource::LegacyManagedConfigTomlFromMdm => None,
+    }
+}
+
+/// Only managed layers may constrain the proxy policy; these may only narrow it.
+fn is_user_controlled_layer(source: &ConfigLayerSource) -> bool {
+    matches!(
+        source,
+        ConfigLayerSource::User { .. }
+            | ConfigLayerSource::Project { .. }
+            | ConfigLayerSource::SessionFlags
+    )
+}
+
+/// Deny reason the proxy reports for hosts missing from the allowlist.
+const REASON_NOT_ALLOWED: &str = "not_allowed";
+
+/// A request the allowlist blocked while a tracked command was running.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BlockedHost {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) method: Option<String>,
+}
+
+/// The command a client token was issued to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TokenOwner {
+    turn_id: String,
+    call_id: String,
+}
+
+#[derive(Default)]
+struct ClientTokensState {
+    order: VecDeque<String>,
+    owners: HashMap<String, TokenOwner>,
+}
+
+/// Unguessable tokens handed to commands as proxy user names, and the
+/// commands they were issued to.
+///
+/// The sandboxed process controls the user name it presents, so presenting
+/// its call id would let it claim another command's approvals. Tokens that
+/// were never issued resolve to nothing.
+#[derive(Default)]
+pub(crate) struct ClientTokens {
+    state: Mutex<ClientTokensState>,
+}
+
+impl ClientTokens {
+    fn lock(&self) -> std::sync::MutexGuard<'_, ClientTokensState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Issue a fresh token for the command `call_id` of turn `turn_id`.
+    fn issue(&self, turn_id: &str, call_id: &str) -> String {
+        let token = Uuid::new_v4().simple().to_string();
+        let mut state = self.lock();
+        state.owners.insert(
+            token.clone(),
+            TokenOwner {
+                turn_id: turn_id.to_string(),
+                call_id: call_id.to_string(),
+            },
+        );
+        state.order.push_back(token.clone());
+        while state.order.len() > MAX_CLIENT_TOKENS {
+            if let Some(oldest) = state.order.pop_front() {
+                state.owners.remove(&oldest);
+            }
+        }
+        token
+    }
+
+    /// The command `token` was issued to, if it was.
+    fn resolve(&self, token: Option<&str>) -> Option<TokenOwner> {
+        token.and_then(|token| self.lock().owners.get(token).cloned())
+    }
+}
+
+#[derive(Default)]
+struct TrackedCommand {
+    allowed_hosts: HashSet<String>,
+    blocked: Vec<BlockedHost>,
+}
+
+#[derive(Default)]
+struct NetworkApprovalsState {
+    session_hosts: HashSet<String>,
+    commands: HashMap<String, TrackedCommand>,
+}
+
+/// Hosts the user allowed beyond the configured allowlist, and the blocked
+/// requests of commands that can be retried once a host is allowed.
+///
+/// The proxy consults it for every request the allowlist would block; denied
+/// and local hosts never reach it.
+#[derive(Default)]
+pub(crate) struct NetworkApprovals {
+    tokens: Arc<ClientTokens>,
+    state: Mutex<NetworkApprovalsState>,
+}
+
+impl NetworkApprovals {
+    pub(crate) fn new(tokens: Arc<ClientTokens>) -> Self {
+        Self {
+            tokens,
+            state: Mutex::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, NetworkApprovalsState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Start recording the blocked requests of `call_id` until the guard drops.
+    pub(crate) fn track_command(&self, call_id: &str) -> TrackedCommandGuard<'_> {
+        self.lock()
+            .commands
+            .insert(call_id.to_string(), TrackedCommand::default());
+        TrackedCommandGuard {
+            approvals: self,
+            call_id: call_id.to_string(),
+        }
+    }
+
+    /// Blocked hosts of `call_id` since the last call, one entry per host.
+    pub(crate) fn take_blocked(&self, call_id: &str) -> Vec<BlockedHost> {
+        self.lock()
+            .commands
+            .get_mut(call_id)
+            .map(|command| std::mem::take(&mut command.blocked))
+            .unwrap_or_default()
+    }
+
+    /// Allow `host` for the remaining attempts of `call_id`.
+    pub(crate) fn allow_for_command(&self, call_id: &str, host: &str) {
+        if let Some(command) = self.lock().commands.get_mut(call_id) {
+            command.allowed_hosts.insert(host.to_ascii_lowercase());
+        }
+    }
+
+    /// Allow `host` for every command of the session.
+    pub(crate) fn allow_for_session(&self, host: &str) {
+        self.lock().session_hosts.insert(host.to_ascii_lowercase());
+    }
+
+    fn decide_blocked(&self, req: &NetworkPolicyRequest) -> NetworkDecision {
+        let host = req.host.to_ascii_lowercase();
+        let owner = self.tokens.resolve(req.client_token.as_deref());
+        let mut state = self.lock();
+        if state.session_hosts.contains(&host) {
+            return NetworkDecision::Allow;
+        }
+        let command = owner.and_then(|owner| state.commands.get_mut(&owner.call_id));
+        if let Some(command) = command {
+            if command.allowed_hosts.contains(&host) {
+                return NetworkDecision::Allow;
+            }
+            if !command.blocked.iter().any(|blocked| blocked.host == host) {
+                command.blocked.push(BlockedHost {
+                    host,
+                    port: req.port,
+                    method: req.method.clone(),
+                });
+            }
+        }
+        NetworkDecision::deny(REASON_NOT_ALLOWED)
+    }
+}
+
+#[async_trait]
+impl NetworkPolicyDecider for NetworkApprovals {
+    async fn decide(&self, req: NetworkPolicyRequest) -> NetworkDecision {
+        self.decide_blocked(&req)
+    }
+}
+
+/// Stops recording the blocked requests of a command when dropped.
+pub(crate) struct TrackedCommandGuard<'a> {
+    approvals: &'a NetworkApprovals,
+    call_id: String,
+}
+
+impl Drop for TrackedCommandGuard<'_> {
+    fn drop(&mut self) {
+        self.approvals.lock().commands.remove(&self.call_id);
+    }
+}
+
+/// Writes the requests the proxy decides on to the network audit log of the
+/// state database.
+pub(crate) struct NetworkAuditRecorder {
+    state_db: StateDbHandle,
+    thread_id: ThreadId,
+    tokens: Arc<ClientTokens>,
+}
+
+impl NetworkAuditRecorder {
+    pub(crate) fn new(
+        state_db: StateDbHandle,
+        thread_id: ThreadId,
+        tokens: Arc<ClientTokens>,
+    ) -> Self {
+        Self {
+            state_db,
+            thread_id,
+            tokens,
+        }
+    }
+
+    fn entry(&self, event: NetworkAuditEvent) -> NetworkAuditEntry {
+        let (turn_id, call_id) = match self.tokens.resolve(event.client_token.as_deref()) {
+            Some(owner) => (Some(owner.turn_id), Some(owner.call_id)),
+            None => (None, None),
+        };
+        let (decision, reason) = match event.decision {
+            NetworkDecision::Allow => (NetworkAuditDecision::Allow, None),
+            NetworkDecision::Deny { reason } => (NetworkAuditDecision::Deny, Some(reason)),
+        };
+        NetworkAuditEntry {
+            ts: DateTime::<Utc>::from_timestamp(event.timestamp, 0).unwrap_or_else(Utc::now),
+            thread_id: Some(self.thread_id),
+            turn_id,
+            call_id,
+            protocol: event.protocol,
+            host: event.host,
+            method: event.method,
+            decision,
+            reason,
+            bytes_sent: event.bytes_sent.and_then(|bytes| i64::try_from(bytes).ok()),
+            bytes_received: event
+                .bytes_received
+                .and_then(|bytes| i64::try_from(bytes).ok()),
+        }
+    }
+}
+
+impl NetworkAuditSink for NetworkAuditRecorder {
+    fn record(&self, event: NetworkAuditEvent) {
+        let entry = self.entry(event);
+        let state_db = Arc::clone(&self.state_db);
+        tokio::spawn(async move {
+            if let Err(err) = state_db.append_network_audit(&entry).await {
+                warn!("failed to record network request to {}: {err}", entry.host);
+            }
+        });
+    }
+}
+
+/// The network proxy of a session and the environment pointing commands at it.
+pub(crate) struct SessionNetworkProxy {
+    http_addr: SocketAddr,
+    socks_addr: Option<SocketAddr>,
+    ca_cert: Option<PathBuf>,
+    tokens: Arc<ClientTokens>,
+    approvals: Arc<NetworkApprovals>,
+    _handle: NetworkProxyHandle,
+}
+
+impl SessionNetworkProxy {
+    /// Start the proxy when `[network_proxy] enabled = true`. Listeners bind to
+    /// loopback ports picked by the OS so concurrent sessions do not collide.
+    /// With `state_db`, requests are logged to its network audit log.
+    pub(crate) async fn start(
+        config: &Config,
+        thread_id: ThreadId,
+        state_db: Option<StateDbHandle>,
+    ) -> anyhow::Result<Option<Self>> {
+        if !config.network_proxy.enabled {
+            return Ok(None);
+        }
+        let loader = CoreConfigLoader::for_config(config);
+        let mut state = NetworkProxyState::new(Arc::new(loader)).await?;
+        let tokens = Arc::new(ClientTokens::default());
+        if let Some(state_db) = state_db {
+            let audit = NetworkAuditRecorder::new(state_db, thread_id, Arc::clone(&tokens));
+            state = state.with_audit_sink(Arc::new(audit) as Arc<dyn NetworkAuditSink>);
+        }
+        let approvals = Arc::new(NetworkApprovals::new(Arc::clone(&tokens)));
+        let loopback = SocketAddr::from(([127, 0, 0, 1], 0));
+        let handle = NetworkProxy::builder()
+            .state(Arc::new(state))
+            .http_addr(loopback)
+            .socks_addr(loopback)
+            .admin_addr(loopback)
+            .policy_decider(Arc::clone(&approvals))
+            .ca_dir(config.codex_home.join(NETWORK_PROXY_CA_DIR))
+            .build()
+            .await?
+            .run()
+            .await?;
+        // `run` skips the listeners when a trusted layer disables the proxy.
+        let http_addr = handle
+            .http_addr()
+            .context("network proxy is disabled by managed config")?;
+        info!("network proxy listening on {http_addr}");
+        Ok(Some(Self {
+            http_addr,
+            socks_addr: handle.socks_addr(),
+            ca_cert: handle.ca_cert_path().map(Path::to_path_buf),
+            tokens,
+            approvals,
+            _handle: handle,
+        }))
+    }
+
+    pub(crate) fn approvals(&self) -> &NetworkApprovals {
+        &self.approvals
+    }
+
+    /// Proxy variables for a client. With a `client_token` the HTTP and SOCKS5
+    /// proxy URLs carry it as user name so requests are attributed to its
+    /// command.
+    fn env_vars(&self, client_token: Option<&str>) -> Vec<(&'static str, String)> {
+        let http_url = proxy_url("http", self.http_addr, client_token);
+        let all_url = match self.socks_addr {
+            Some(addr) => proxy_url("socks5h", addr, client_token),
+            None => http_url.clone(),
+        };
+        let mut vars = Vec::new();
+        for (upper, lower, value) in [
+            ("HTTP_PROXY", "http_proxy", &http_url),
+            ("HTTPS_PROXY", "https_proxy", &http_url),
+            ("ALL_PROXY", "all_proxy", &all_url),
+        ] {
+            vars.push((upper, value.clone()));
+            vars.push((lower, value.clone()));
+        }
+        if let Some(ca_cert) = &self.ca_cert {
+            let ca_cert = ca_cert.to_string_lossy().into_owned();
+            vars.extend(CA_BUNDLE_VARS.iter().map(|name| (*name, ca_cert.clone())));
+        }
+        vars
+    }
+
+    /// Point the proxy variables of the command `call_id` of turn `turn_id` at
+    /// the proxy, with a client token issued for that command.
+    pub(crate) fn apply(&self, env: &mut HashMap<String, String>, turn_id: &str, call_id: &str) {
+        let token = self.tokens.issue(turn_id, call_id);
+        for (name, value) in self.env_vars(Some(&token)) {
+            env.insert(name.to_string(), value);
+        }
+    }
+
+    /// Point the proxy variables of every stdio MCP server at the proxy.
+    pub(crate) fn apply_to_mcp_servers(&self, servers: &mut HashMap<String, McpServerConfig>) {
+        for server in servers.values_mut() {
+            if let McpServerTransportConfig::Stdio { env, .. } = &mut server.transport {
+                let env = env.get_or_insert_with(HashMap::new);
+                for (name, value) in self.env_vars(None) {
+                    env.insert(name.to_string(), value);
+                }
+            }
+        }
+    }
+
+    /// Loopback ports the sandbox lets commands connect to.
+    pub(crate) fn ports(&self) -> Vec<u16> {
+        std::iter::once(self.http_addr)
+            .chain(self.socks_addr)
+            .map(|addr| addr.port())
+            .collect()
+    }
+}
+
+fn proxy_url(scheme: &str, addr: SocketAddr, client_token: Option<&str>) -> String {
+    let plain = format!("{scheme}://{addr}");
+    let Some(token) = client_token else {
+        return plain;
+    };
+    let Ok(mut url) = Url::parse(&plain) else {
+        return plain;
+    };
+    if url.set_username(token).is_err() {
+        return plain;
+    }
+    url.as_str().trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_network_proxy::NetworkPolicyRequestArgs;
+    use codex_network_proxy::NetworkProtocol;
+    use pretty_assertions::assert_eq;
+
+    fn proxy(socks_port: Option<u16>) -> SessionNetworkProxy {
+        let tokens = Arc::new(ClientTokens::default());
+        SessionNetworkProxy {
+            http_addr: SocketAddr::from(([127, 0, 0, 1], 4100)),
+            socks_addr: socks_port.map(|port| SocketAddr::from(([127, 0, 0, 1], port))),
+            ca_cert: None,
+            approvals: Arc::new(NetworkApprovals::new(Arc::clone(&tokens))),
+            tokens,
+            _handle: NetworkProxyHandle::noop(),
+        }
+    }
+
+    fn request(host: &str, client_token: Option<&str>) -> NetworkPolicyRequest {
+        NetworkPolicyRequest::new(NetworkPolicyRequestArgs {
+            protocol: NetworkProtocol::HttpsConnect,
+            host: host.to_string(),
+            port: 443,
+            client_addr: None,
+            client_token: client_token.map(str::to_string),
+            method: Some("CONNECT".to_string()),
+            command: None,
+            exec_policy_hint: None,
+        })
+    }
+
+    fn owner(turn_id: &str, call_id: &str) -> TokenOwner {
+        TokenOwner {
+            turn_id: turn_id.to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_points_proxy_variables_at_the_proxy() {
+        let proxy = proxy(Some(4101));
+        let mut env = HashMap::from([
+            ("PATH".to_string(), "/bin".to_string()),
+            ("HTTPS_PROXY".to_string(), "http://corp:8080".to_string()),
+        ]);
+        proxy.apply(&mut env, "turn_1", "call_1");
+
+        let http = env["HTTP_PROXY"].clone();
+        let token = http
+            .strip_prefix("http://")
+            .and_then(|rest| rest.strip_suffix("@127.0.0.1:4100"))
+            .expect("proxy URL carries a client token");
+        assert_eq!(
+            proxy.tokens.resolve(Some(token)),
+            Some(owner("turn_1", "call_1"))
+        );
+        let socks = format!("socks5h://{token}@127.0.0.1:4101");
+        assert_eq!(
+            env,
+            HashMap::from([
+                ("PATH".to_string(), "/bin".to_string()),
+                ("HTTP_PROXY".to_string(), http.clone()),
+                ("http_proxy".to_string(), http.clone()),
+                ("HTTPS_PROXY".to_string(), http.clone()),
+                ("https_proxy".to_string(), http),
+                ("ALL_PROXY".to_string(), socks.clone()),
+                ("all_proxy".to_string(), socks),
+            ])
+        );
+        assert_eq!(proxy.ports(), vec![4100, 4101]);
+    }
+
+    #[tokio::test]
+    async fn apply_points_ca_bundle_variables_at_the_proxy_ca() {
+        let mut proxy = proxy(None);
+        proxy.ca_cert = Some(PathBuf::from("/codex/network-proxy/ca.pem"));
+        let mut env = HashMap::new();
+        proxy.apply(&mut env, "turn_1", "call_1");
+
+        for name in CA_BUNDLE_VARS {
+            assert_eq!(
+                env.get(*name).map(String::as_str),
+                Some("/codex/network-proxy/ca.pem"),
+                "{name}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn all_proxy_uses_http_without_socks() {
+        let mut env = HashMap::new();
+        proxy(None).apply(&mut env, "turn_1", "call 1");
+        assert_eq!(env.get("ALL_PROXY"), env.get("HTTP_PROXY"));
+        assert_eq!(proxy(None).ports(), vec![4100]);
+    }
+
+    #[test]
+    fn proxy_url_escapes_the_client_token() {
+        let addr = SocketAddr::from(([127, 0, 0, 1], 4100));
+        assert_eq!(proxy_url("http", addr, None), "http://127.0.0.1:4100");
+        assert_eq!(
+            proxy_url("http", addr, Some("a b")),
+            "http://a%20b@127.0.0.1:4100"
+        );
+        assert_eq!(
+            proxy_url("socks5h", addr, Some("a b")),
+            "socks5h://a%20b@127.0.0.1:4100"
+        );
+    }
+
+    #[test]
+    fn client_tokens_are_unique_per_issue() {
+        let tokens = ClientTokens::default();
+        let first = tokens.issue("turn_1", "call_1");
+        let second = tokens.issue("turn_1", "call_1");
+
+        assert_ne!(first, second);
+        assert_eq!(
+            tokens.resolve(Some(&second)),
+            Some(owner("turn_1", "call_1"))
+        );
+        assert_eq!(tokens.resolve(Some("call_1")), None);
+        assert_eq!(tokens.resolve(None), None);
+    }
+
+    #[tokio::test]
+    async fn audit_entries_carry_the_thread_turn_and_call() {
+        let codex_home = tempfile::TempDir::new().expect("create temp dir");
+        let state_db = codex_state::StateRuntime::init(
+            codex_home.path().to_path_buf(),
+            "test-provider".to_string(),
+            None,
+        )
+        .await
+        .expect("initialize state db");
+        let thread_id = ThreadId::new();
+        let tokens = Arc::new(ClientTokens::default());
+        let recorder = NetworkAuditRecorder::new(state_db, thread_id, Arc::clone(&tokens));
+        let token = tokens.issue("turn_1", "call_1");
+
+        let event = |client_token: Option<&str>, decision| NetworkAuditEvent {
+            timestamp: 1_700_000_000,
+            protocol: "http".to_string(),
+            host: "pypi.org".to_string(),
+            method: Some("GET".to_string()),
+            client_token: client_token.map(str::to_string),
+            decision,
+            bytes_sent: Some(0),
+            bytes_received: Some(2048),
+        };
+        assert_eq!(
+            recorder.entry(event(Some(&token), NetworkDecision::Allow)),
+            NetworkAuditEntry {
+                ts: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).expect("timestamp"),
+                thread_id: Some(thread_id),
+                turn_id: Some("turn_1".to_string()),
+                call_id: Some("call_1".to_string()),
+                protocol: "http".to_string(),
+                host: "pypi.org".to_string(),
+                method: Some("GET".to_string()),
+                decision: NetworkAuditDecision::Allow,
+                reason: None,
+                bytes_sent: Some(0),
+                bytes_received: Some(2048),
+            }
+        );
+
+        // A user name that was never issued is not attributed to any command.
+        for client_token in [None, Some("call_1")] {
+            let denied = recorder.entry(event(
+                client_token,
+                NetworkDecision::deny(REASON_NOT_ALLOWED),
+            ));
+            assert_eq!(
+                (
+                    denied.turn_id,
+                    denied.call_id,
+                    denied.decision,
+                    denied.reason
+                ),
+                (
+                    None,
+                    None,
+                    NetworkAuditDecision::Deny,
+                    Some(REASON_NOT_ALLOWED.to_string())
+                )
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn approvals_record_blocked_hosts_of_tracked_commands() {
+        let tokens = Arc::new(ClientTokens::default());
+        let approvals = NetworkApprovals::new(Arc::clone(&tokens));
+        let call_1 = tokens.issue("turn_1", "call_1");
+        let call_2 = tokens.issue("turn_1", "call_2");
+        let denied = NetworkDecision::deny(REASON_NOT_ALLOWED);
+        {
+            let _tracked = approvals.track_command("call_1");
+            assert_eq!(
+                approvals.decide(request("PyPI.org", Some(&call_1))).await,
+                denied
+            );
+            assert_eq!(
+                approvals.decide(request("pypi.org", Some(&call_1))).await,
+                denied
+            );
+            assert_eq!(
+                approvals.decide(request("github.com", Some(&call_2))).await,
+                denied
+            );
+            assert_eq!(
+                approvals.take_blocked("call_1"),
+                vec![BlockedHost {
+                    host: "pypi.org".to_string(),
+                    port: 443,
+                    method: Some("CONNECT".to_string()),
+                }]
+            );
+            assert_eq!(approvals.take_blocked("call_1"), Vec::new());
+
+            approvals.allow_for_command("call_1", "pypi.org");
+            assert_eq!(
+                approvals.decide(request("pypi.org", Some(&call_1))).await,
+                NetworkDecision::Allow
+            );
+            assert_eq!(approvals.decide(request("pypi.org", None)).await, denied);
+        }
+
+        // Hosts allowed for a command end with it.
+        let _tracked = approvals.track_command("call_1");
+        assert_eq!(
+            approvals.decide(request("pypi.org", Some(&call_1))).await,
+            denied
+        );
+
+        approvals.allow_for_session("pypi.org");
+        assert_eq!(
+            approvals.decide(request("pypi.org", None)).await,
+            NetworkDecision::Allow
+        );
+    }
+
+    #[tokio::test]
+    async fn approvals_deny_a_forged_client_token() {
+        let tokens = Arc::new(ClientTokens::default());
+        let approvals = NetworkApprovals::new(Arc::clone(&tokens));
+        let _token = tokens.issue("turn_1", "call_1");
+        let _tracked = approvals.track_command("call_1");
+        approvals.allow_for_command("call_1", "pypi.org");
+
+        // Another process presents the call id, or a made-up token, as its
+        // user name to reuse the approval of `call_1`.
+        for forged in ["call_1", "00000000000000000000000000000000"] {
+            assert_eq!(
+                approvals.decide(request("pypi.org", Some(forged))).await,
+                NetworkDecision::deny(REASON_NOT_ALLOWED)
+            );
+            assert_eq!(
+                approvals.decide(request("github.com", Some(forged))).await,
+                NetworkDecision::deny(REASON_NOT_ALLOWED)
+            );
+        }
+        assert_eq!(approvals.take_blocked("call_1"), Vec::new());
+    }
+}
@@ -15,6 +15,7 @@
 
 use crate::config::Config;
 use crate::features::Feature;
+use crate::project_knowledge::read_project_knowledge;
 use crate::skills::SkillMetadata;
 use crate::skills::render_skills_section;
 use dunce::canonicalize as normalize_path;
@@ -34,8 +35,8 @@ pub const LOCAL_PROJECT_DOC_FILENAME: &str = "AGENTS.override.md";
 /// be concatenated with the following separator.
 const PROJECT_DOC_SEPARATOR: &str = "\n\n--- project-doc ---\n\n";
 
-/// Combines `Config::instructions` and `AGENTS.md` (if present) into a single
-/// string of instructions.
+/// Combines `Config::instructions`, `AGENTS.md` and approved project knowledge
+/// (if present) into a single string of instructions.
 pub(crate) async fn get_user_instructions(
     config: &Config,
     skills: Option<&[SkillMetadata]>,
@@ -61,6 +62,13 @@ pub(crate) async fn get_user_instructions(
         }
     };
 
+    if let Some(knowledge) = read_project_knowledge(config).await {
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+        output.push_str(&knowledge);
+    }
+
     let skills_section = skills.and_then(render_skills_section);
     if let Some(skills_section) = skills_section {
         if !output.is_empty() {
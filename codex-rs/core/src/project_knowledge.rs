@@ -0,0 +1,90 @@
+//! Durable facts about a repository recorded with the `remember` tool.
+//!
+//! Facts are keyed by the root of the main repository, so every worktree and
+//! subdirectory of a repository shares them. The model can only propose
+//! facts; they are injected into new sessions next to `AGENTS.md` once the
+//! user approves them.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::features::Feature;
+use crate::git_info::resolve_root_git_project_for_trust;
+use crate::state_db;
+use crate::state_db::KnowledgeStatus;
+use crate::state_db::ProjectKnowledge;
+
+/// The key under which knowledge about `cwd` is stored: the root of its main
+/// repository, or `cwd` itself outside a repository.
+pub fn knowledge_repo_root(cwd: &Path) -> PathBuf {
+    resolve_root_git_project_for_trust(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// Render the approved knowledge for the repository of `config.cwd`, if any.
+pub(crate) async fn read_project_knowledge(config: &Config) -> Option<String> {
+    if !config.features.enabled(Feature::Sqlite) {
+        return None;
+    }
+    let ctx = state_db::open_if_present(&config.codex_home, &config.model_provider_id).await?;
+    let repo_root = knowledge_repo_root(&config.cwd);
+    match ctx
+        .list_project_knowledge(&repo_root, Some(KnowledgeStatus::Approved))
+        .await
+    {
+        Ok(knowledge) => render_project_knowledge_section(&knowledge),
+        Err(err) => {
+            tracing::warn!("failed to load project knowledge: {err}");
+            None
+        }
+    }
+}
+
+fn render_project_knowledge_section(knowledge: &[ProjectKnowledge]) -> Option<String> {
+    if knowledge.is_empty() {
+        return None;
+    }
+    let mut section = String::from(
+        "## Project knowledge\nFacts about this repository recorded in earlier sessions and approved by the user:\n",
+    );
+    for entry in knowledge {
+        section.push_str("- ");
+        section.push_str(&entry.content);
+        section.push('\n');
+    }
+    Some(section.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use pretty_assertions::assert_eq;
+
+    fn entry(id: i64, content: &str) -> ProjectKnowledge {
+        ProjectKnowledge {
+            id,
+            repo_root: PathBuf::from("/repo"),
+            content: content.to_string(),
+            status: KnowledgeStatus::Approved,
+            source_thread_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn renders_one_bullet_per_fact() {
+        assert_eq!(render_project_knowledge_section(&[]), None);
+        assert_eq!(
+            render_project_knowledge_section(&[
+                entry(1, "tests need `--features sqlite`"),
+                entry(2, "never touch vendored/"),
+            ]),
+            Some(
+                "## Project knowledge\nFacts about this repository recorded in earlier sessions and approved by the user:\n- tests need `--features sqlite`\n- never touch vendored/"
+                    .to_string()
+            )
+        );
+    }
+}
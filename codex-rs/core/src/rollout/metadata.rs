@@ -10,6 +10,7 @@ use codex_otel::OtelManager;
 use codex_protocol::ThreadId;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
 use codex_protocol::protocol::SandboxPolicy;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
@@ -20,8 +21,11 @@ use codex_state::DB_ERROR_METRIC;
 use codex_state::DB_METRIC_BACKFILL;
 use codex_state::DB_METRIC_BACKFILL_DURATION_MS;
 use codex_state::ExtractionOutcome;
+use codex_state::ThreadMetadata;
 use codex_state::ThreadMetadataBuilder;
 use codex_state::apply_rollout_item;
+use codex_state::thread_search_entries;
+use codex_state::turn_usage_from_rollout;
 use std::path::Path;
 use std::path::PathBuf;
 use tracing::info;
@@ -124,6 +128,7 @@ pub(crate) async fn extract_metadata_from_rollout(
     Ok(ExtractionOutcome {
         metadata,
         parse_errors,
+        search_entries: thread_search_entries(&items),
     })
 }
 
@@ -231,6 +236,23 @@ pub(crate) async fn backfill_sessions(
                         warn!("failed to upsert rollout {}: {err}", rollout.path.display());
                     } else {
                         stats.upserted = stats.upserted.saturating_add(1);
+                        if let Err(err) = runtime
+                            .insert_thread_search_entries(metadata.id, &outcome.search_entries)
+                            .await
+                        {
+                            if let Some(otel) = otel {
+                                otel.counter(
+                                    DB_ERROR_METRIC,
+                                    1,
+                                    &[("stage", "backfill_thread_search")],
+                                );
+                            }
+                            warn!(
+                                "failed to backfill search index {}: {err}",
+                                rollout.path.display()
+                            );
+                        }
+                        backfill_thread_usage(runtime, &metadata, &rollout.path, otel).await;
                         if let Ok(meta_line) =
                             rollout::list::read_session_meta_line(&rollout.path).await
                         {
@@ -337,6 +359,37 @@ pub(crate) async fn backfill_sessions(
     }
 }
 
+/// Record per-turn usage reconstructed from the token counts in a rollout.
+async fn backfill_thread_usage(
+    runtime: &codex_state::StateRuntime,
+    metadata: &ThreadMetadata,
+    rollout_path: &Path,
+    otel: Option<&OtelManager>,
+) {
+    let text = match tokio::fs::read_to_string(rollout_path).await {
+        Ok(text) => text,
+        Err(err) => {
+            warn!(
+                "failed to read rollout for usage {}: {err}",
+                rollout_path.display()
+            );
+            return;
+        }
+    };
+    // Unparseable lines are already counted while extracting metadata.
+    let lines = text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .collect::<Vec<_>>();
+    let usage = turn_usage_from_rollout(metadata, &lines);
+    if let Err(err) = runtime.backfill_thread_usage(metadata.id, &usage).await {
+        if let Some(otel) = otel {
+            otel.counter(DB_ERROR_METRIC, 1, &[("stage", "backfill_usage")]);
+        }
+        warn!("failed to backfill usage {}: {err}", rollout_path.display());
+    }
+}
+
 #[derive(Debug, Clone)]
 struct BackfillRolloutPath {
     watermark: String,
@@ -429,14 +482,26 @@ mod tests {
     use chrono::Timelike;
     use chrono::Utc;
     use codex_protocol::ThreadId;
+    use codex_protocol::config_types::ReasoningSummary;
+    use codex_protocol::protocol::AskForApproval;
     use codex_protocol::protocol::CompactedItem;
+    use codex_protocol::protocol::EventMsg;
     use codex_protocol::protocol::RolloutItem;
     use codex_protocol::protocol::RolloutLine;
+    use codex_protocol::protocol::SandboxPolicy;
     use codex_protocol::protocol::SessionMeta;
     use codex_protocol::protocol::SessionMetaLine;
     use codex_protocol::protocol::SessionSource;
+    use codex_protocol::protocol::TokenCountEvent;
+    use codex_protocol::protocol::TokenUsage;
+    use codex_protocol::protocol::TokenUsageInfo;
+    use codex_protocol::protocol::TurnContextItem;
+    use codex_protocol::protocol::UserMessageEvent;
     use codex_state::BackfillStatus;
     use codex_state::ThreadMetadataBuilder;
+    use codex_state::UsageGroupBy;
+    use codex_state::UsageQuery;
+    use codex_state::UsageReportRow;
     use pretty_assertions::assert_eq;
     use std::fs::File;
     use std::io::Write;
@@ -588,6 +653,273 @@ mod tests {
         assert!(state.last_success_at.is_some());
     }
 
+    #[tokio::test]
+    async fn backfill_sessions_indexes_thread_search() {
+        let dir = tempdir().expect("tempdir");
+        let codex_home = dir.path().to_path_buf();
+        let thread_uuid = Uuid::new_v4();
+        let path = write_rollout_in_sessions(
+            codex_home.as_path(),
+            "2026-01-27T12-34-56",
+            "2026-01-27T12:34:56Z",
+            thread_uuid,
+        );
+        let user_message = RolloutLine {
+            timestamp: "2026-01-27T12:34:57Z".to_string(),
+            item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: "investigate the websocket reconnect loop".to_string(),
+                images: None,
+                local_images: Vec::new(),
+                text_elements: Vec::new(),
+            })),
+        };
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open rollout");
+        writeln!(
+            file,
+            "{}",
+            serde_json::to_string(&user_message).expect("serialize user message")
+        )
+        .expect("write user message");
+
+        let runtime =
+            codex_state::StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+                .await
+                .expect("initialize runtime");
+        let mut config = crate::config::test_config();
+        config.codex_home = codex_home.clone();
+        config.model_provider_id = "test-provider".to_string();
+        backfill_sessions(runtime.as_ref(), &config, None).await;
+
+        let page = runtime
+            .search_threads("websocket", 10, 0, &[], None, false)
+            .await
+            .expect("search threads");
+        assert_eq!(
+            page.items
+                .iter()
+                .map(|hit| hit.thread.id.to_string())
+                .collect::<Vec<_>>(),
+            vec![thread_uuid.to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_sessions_records_turn_usage() {
+        let dir = tempdir().expect("tempdir");
+        let codex_home = dir.path().to_path_buf();
+        let thread_uuid = Uuid::new_v4();
+        let path = write_rollout_in_sessions(
+            codex_home.as_path(),
+            "2026-01-27T12-34-56",
+            "2026-01-27T12:34:56Z",
+            thread_uuid,
+        );
+        let lines = [
+            RolloutLine {
+                timestamp: "2026-01-27T12:34:57Z".to_string(),
+                item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                    message: "summarize the changelog".to_string(),
+                    images: None,
+                    local_images: Vec::new(),
+                    text_elements: Vec::new(),
+                })),
+            },
+            RolloutLine {
+                timestamp: "2026-01-27T12:35:10Z".to_string(),
+                item: RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                    info: Some(TokenUsageInfo {
+                        total_token_usage: TokenUsage {
+                            input_tokens: 1200,
+                            cached_input_tokens: 200,
+                            output_tokens: 300,
+                            reasoning_output_tokens: 100,
+                            total_tokens: 1500,
+                        },
+                        last_token_usage: TokenUsage::default(),
+                        model_context_window: None,
+                    }),
+                    rate_limits: None,
+                })),
+            },
+        ];
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open rollout");
+        for line in lines {
+            writeln!(
+                file,
+                "{}",
+                serde_json::to_string(&line).expect("serialize rollout line")
+            )
+            .expect("write rollout line");
+        }
+
+        let runtime =
+            codex_state::StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+                .await
+                .expect("initialize runtime");
+        let mut config = crate::config::test_config();
+        config.codex_home = codex_home.clone();
+        config.model_provider_id = "test-provider".to_string();
+        backfill_sessions(runtime.as_ref(), &config, None).await;
+
+        let report = runtime
+            .usage_report(&UsageQuery {
+                group_by: UsageGroupBy::Day,
+                since: None,
+                until: None,
+            })
+            .await
+            .expect("usage report");
+        assert_eq!(
+            report,
+            vec![UsageReportRow {
+                group: "2026-01-27".to_string(),
+                model: "unknown".to_string(),
+                turns: 1,
+                input_tokens: 1200,
+                cached_input_tokens: 200,
+                output_tokens: 300,
+                reasoning_output_tokens: 100,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_sessions_counts_resumed_turns_with_repeated_sub_ids() {
+        let dir = tempdir().expect("tempdir");
+        let codex_home = dir.path().to_path_buf();
+        let thread_uuid = Uuid::new_v4();
+        let thread_id = ThreadId::from_string(&thread_uuid.to_string()).expect("thread id");
+        let path = write_rollout_in_sessions(
+            codex_home.as_path(),
+            "2026-01-27T12-34-56",
+            "2026-01-27T12:34:56Z",
+            thread_uuid,
+        );
+        // Both turns ran as sub_id "1": the second one after the thread was
+        // resumed in a new process. Only their minted ids tell them apart.
+        let first_turn = Uuid::new_v4().to_string();
+        let second_turn = Uuid::new_v4().to_string();
+        let user_message = |message: &str| {
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: message.to_string(),
+                images: None,
+                local_images: Vec::new(),
+                text_elements: Vec::new(),
+            }))
+        };
+        let turn_context = |turn_id: &str| {
+            RolloutItem::TurnContext(TurnContextItem {
+                turn_id: Some(turn_id.to_string()),
+                cwd: codex_home.clone(),
+                approval_policy: AskForApproval::Never,
+                sandbox_policy: SandboxPolicy::DangerFullAccess,
+                model: "gpt-5".to_string(),
+                personality: None,
+                collaboration_mode: None,
+                effort: None,
+                summary: ReasoningSummary::Auto,
+                user_instructions: None,
+                developer_instructions: None,
+                final_output_json_schema: None,
+                truncation_policy: None,
+            })
+        };
+        let token_count = |input_tokens: i64| {
+            RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                info: Some(TokenUsageInfo {
+                    total_token_usage: TokenUsage {
+                        input_tokens,
+                        cached_input_tokens: 0,
+                        output_tokens: 0,
+                        reasoning_output_tokens: 0,
+                        total_tokens: input_tokens,
+                    },
+                    last_token_usage: TokenUsage::default(),
+                    model_context_window: None,
+                }),
+                rate_limits: None,
+            }))
+        };
+        let lines = [
+            ("2026-01-27T12:34:57Z", user_message("first")),
+            ("2026-01-27T12:34:58Z", turn_context(&first_turn)),
+            ("2026-01-27T12:35:00Z", token_count(100)),
+            ("2026-01-28T09:00:00Z", user_message("after resume")),
+            ("2026-01-28T09:00:01Z", turn_context(&second_turn)),
+            ("2026-01-28T09:00:05Z", token_count(250)),
+        ];
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open rollout");
+        for (timestamp, item) in lines {
+            let line = RolloutLine {
+                timestamp: timestamp.to_string(),
+                item,
+            };
+            writeln!(
+                file,
+                "{}",
+                serde_json::to_string(&line).expect("serialize rollout line")
+            )
+            .expect("write rollout line");
+        }
+
+        let runtime =
+            codex_state::StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+                .await
+                .expect("initialize runtime");
+        // The first turn was recorded live when it finished.
+        runtime
+            .record_turn_usage(&codex_state::TurnUsage {
+                thread_id,
+                turn_id: first_turn,
+                model: "gpt-5".to_string(),
+                model_provider: "test-provider".to_string(),
+                cwd: codex_home.clone(),
+                input_tokens: 100,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+                reasoning_output_tokens: 0,
+                recorded_at: DateTime::parse_from_rfc3339("2026-01-27T12:35:01Z")
+                    .expect("timestamp")
+                    .with_timezone(&Utc),
+            })
+            .await
+            .expect("record live turn");
+        let mut config = crate::config::test_config();
+        config.codex_home = codex_home.clone();
+        config.model_provider_id = "test-provider".to_string();
+        backfill_sessions(runtime.as_ref(), &config, None).await;
+
+        let report = runtime
+            .usage_report(&UsageQuery {
+                group_by: UsageGroupBy::Thread,
+                since: None,
+                until: None,
+            })
+            .await
+            .expect("usage report");
+        assert_eq!(
+            report,
+            vec![UsageReportRow {
+                group: thread_id.to_string(),
+                model: "gpt-5".to_string(),
+                turns: 2,
+                input_tokens: 250,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+                reasoning_output_tokens: 0,
+            }]
+        );
+    }
+
     fn write_rollout_in_sessions(
         codex_home: &Path,
         filename_ts: &str,
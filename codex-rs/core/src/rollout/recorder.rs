@@ -40,6 +40,7 @@ use crate::git_info::collect_git_info;
 use crate::path_utils;
 use crate::state_db;
 use crate::state_db::StateDbHandle;
+use crate::state_db::ThreadOrganizationFilter;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::ResumedHistory;
 use codex_protocol::protocol::RolloutItem;
@@ -47,6 +48,7 @@ use codex_protocol::protocol::RolloutLine;
 use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
+use codex_secrets::redact_secrets;
 use codex_state::ThreadMetadataBuilder;
 
 /// Records all [`ResponseItem`]s for a session and flushes them to disk after
@@ -180,6 +182,7 @@ impl RolloutRecorder {
             allowed_sources,
             model_providers,
             archived,
+            &ThreadOrganizationFilter::default(),
         )
         .await
         {
@@ -242,6 +245,7 @@ impl RolloutRecorder {
                     allowed_sources,
                     model_providers,
                     false,
+                    &ThreadOrganizationFilter::default(),
                 )
                 .await
                 else {
@@ -669,7 +673,8 @@ impl JsonlWriter {
         self.write_line(&line).await
     }
     async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
-        let mut json = serde_json::to_string(item)?;
+        let json = serde_json::to_string(item)?;
+        let mut json = redact_secrets(&json).into_owned();
         json.push('\n');
         self.file.write_all(json.as_bytes()).await?;
         self.file.flush().await?;
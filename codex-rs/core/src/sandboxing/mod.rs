@@ -61,6 +61,9 @@ pub(crate) struct SandboxTransformRequest<'a> {
     pub sandbox_policy_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a PathBuf>,
     pub use_linux_sandbox_bwrap: bool,
+    /// Loopback ports of the session's network proxy. When the policy blocks
+    /// the network, the Linux sandbox still lets commands connect to these.
+    pub network_proxy_ports: &'a [u16],
     pub windows_sandbox_level: WindowsSandboxLevel,
 }
 
@@ -126,6 +129,7 @@ impl SandboxManager {
             sandbox_policy_cwd,
             codex_linux_sandbox_exe,
             use_linux_sandbox_bwrap,
+            network_proxy_ports,
             windows_sandbox_level,
         } = request;
         let mut env = spec.env;
@@ -163,6 +167,7 @@ impl SandboxManager {
                     policy,
                     sandbox_policy_cwd,
                     use_linux_sandbox_bwrap,
+                    network_proxy_ports,
                 );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(exe.to_string_lossy().to_string());
@@ -0,0 +1,232 @@
+//! Injection of configured secrets into spawned processes.
+//!
+//! `[secrets].inject` names secrets that shell commands, unified exec
+//! processes and MCP stdio servers receive as environment variables. Values
+//! are resolved per environment (see [`environment_id_from_cwd`]) so each repo
+//! can carry its own credentials, falling back to the global scope. Every
+//! resolved value is registered with the process-wide redactor so it is
+//! replaced by `[secret:NAME]` wherever output leaves the process.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_secrets::SecretName;
+use codex_secrets::SecretScope;
+use codex_secrets::SecretsManager;
+use codex_secrets::environment_id_from_cwd;
+use codex_secrets::register_secret_for_redaction;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerTransportConfig;
+use crate::config::types::SecretsConfig;
+
+/// Secret environment variables resolved for one environment scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SecretEnv {
+    vars: Vec<(String, String)>,
+}
+
+impl SecretEnv {
+    /// Add the secrets to an environment built by `create_env`. Secrets win
+    /// over inherited variables and are added after the default `*TOKEN*` /
+    /// `*SECRET*` excludes so they are not filtered out again.
+    pub(crate) fn apply(&self, env: &mut HashMap<String, String>) {
+        for (name, value) in &self.vars {
+            env.insert(name.clone(), value.clone());
+        }
+    }
+
+    /// Add the secrets to the environment of every stdio MCP server.
+    pub(crate) fn apply_to_mcp_servers(&self, servers: &mut HashMap<String, McpServerConfig>) {
+        if self.vars.is_empty() {
+            return;
+        }
+        for server in servers.values_mut() {
+            if let McpServerTransportConfig::Stdio { env, .. } = &mut server.transport {
+                let env = env.get_or_insert_with(HashMap::new);
+                for (name, value) in &self.vars {
+                    env.entry(name.clone()).or_insert_with(|| value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Resolves and caches [`SecretEnv`]s for a session.
+pub(crate) struct SecretsInjector {
+    manager: Option<SecretsManager>,
+    names: Vec<SecretName>,
+    resolved: Mutex<HashMap<String, Arc<SecretEnv>>>,
+}
+
+impl SecretsInjector {
+    pub(crate) fn new(codex_home: PathBuf, config: &SecretsConfig) -> Self {
+        let manager =
+            (!config.inject.is_empty()).then(|| SecretsManager::new(codex_home, config.backend));
+        Self::with_manager(manager, config.inject.clone())
+    }
+
+    pub(crate) fn with_manager(manager: Option<SecretsManager>, names: Vec<SecretName>) -> Self {
+        Self {
+            manager,
+            names,
+            resolved: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The secrets to inject for commands running in `cwd`.
+    pub(crate) async fn env_for_cwd(&self, cwd: &Path) -> Arc<SecretEnv> {
+        let Some(manager) = self.manager.as_ref() else {
+            return Arc::new(SecretEnv::default());
+        };
+        let environment_id = environment_id_from_cwd(cwd);
+        let mut resolved = self.resolved.lock().await;
+        if let Some(env) = resolved.get(&environment_id) {
+            return Arc::clone(env);
+        }
+
+        let manager = manager.clone();
+        let names = self.names.clone();
+        let scope_id = environment_id.clone();
+        // Decrypting the secrets file is deliberately slow, keep it off the
+        // async workers.
+        let env =
+            tokio::task::spawn_blocking(move || resolve_secret_env(&manager, &names, scope_id))
+                .await
+                .unwrap_or_else(|err| {
+                    warn!("failed to resolve injected secrets: {err}");
+                    SecretEnv::default()
+                });
+        let env = Arc::new(env);
+        resolved.insert(environment_id, Arc::clone(&env));
+        env
+    }
+}
+
+fn resolve_secret_env(
+    manager: &SecretsManager,
+    names: &[SecretName],
+    environment_id: String,
+) -> SecretEnv {
+    let environment_scope = match SecretScope::environment(environment_id) {
+        Ok(scope) => Some(scope),
+        Err(err) => {
+            warn!("invalid secrets environment scope: {err}");
+            None
+        }
+    };
+    let mut vars = Vec::with_capacity(names.len());
+    for name in names {
+        match lookup_secret(manager, environment_scope.as_ref(), name) {
+            Ok(Some(value)) => vars.push((name.as_str().to_string(), value)),
+            Ok(None) => warn!("secret {name} is configured for injection but is not set"),
+            Err(err) => warn!("failed to read secret {name}: {err:#}"),
+        }
+    }
+    SecretEnv { vars }
+}
+
+/// Look `name` up in `environment_scope`, falling back to the global scope.
+/// A found value is registered for redaction before it is returned.
+fn lookup_secret(
+    manager: &SecretsManager,
+    environment_scope: Option<&SecretScope>,
+    name: &SecretName,
+) -> anyhow::Result<Option<String>> {
+    for scope in environment_scope
+        .into_iter()
+        .chain(std::iter::once(&SecretScope::Global))
+    {
+        if let Some(value) = manager.get(scope, name)? {
+            register_secret_for_redaction(name, &value);
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_keyring_store::tests::MockKeyringStore;
+    use codex_secrets::SecretsBackendKind;
+    use codex_secrets::redact_secrets;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn name(raw: &str) -> SecretName {
+        SecretName::new(raw).expect("valid secret name")
+    }
+
+    #[tokio::test]
+    async fn env_prefers_environment_scope_and_registers_redaction() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let repo = TempDir::new()?;
+        let manager = SecretsManager::new_with_keyring_store(
+            codex_home.path().to_path_buf(),
+            SecretsBackendKind::Local,
+            Arc::new(MockKeyringStore::default()),
+        );
+        let environment_scope = SecretScope::environment(environment_id_from_cwd(repo.path()))?;
+        manager.set(&SecretScope::Global, &name("API_TOKEN"), "global-token-1")?;
+        manager.set(&environment_scope, &name("API_TOKEN"), "repo-token-2")?;
+        manager.set(&SecretScope::Global, &name("NPM_TOKEN"), "npm-token-3")?;
+
+        let injector = SecretsInjector::with_manager(
+            Some(manager),
+            vec![name("API_TOKEN"), name("NPM_TOKEN"), name("MISSING")],
+        );
+        let secret_env = injector.env_for_cwd(repo.path()).await;
+
+        let mut env = HashMap::from([("PATH".to_string(), "/bin".to_string())]);
+        secret_env.apply(&mut env);
+        assert_eq!(
+            env,
+            HashMap::from([
+                ("PATH".to_string(), "/bin".to_string()),
+                ("API_TOKEN".to_string(), "repo-token-2".to_string()),
+                ("NPM_TOKEN".to_string(), "npm-token-3".to_string()),
+            ])
+        );
+        assert_eq!(
+            redact_secrets("echo repo-token-2 npm-token-3"),
+            "echo [secret:API_TOKEN] [secret:NPM_TOKEN]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mcp_servers_keep_explicit_env_values() {
+        let secret_env = SecretEnv {
+            vars: vec![
+                ("API_TOKEN".to_string(), "secret".to_string()),
+                ("OTHER".to_string(), "value".to_string()),
+            ],
+        };
+        let mut servers: HashMap<String, McpServerConfig> = toml::from_str(
+            r#"
+[docs]
+command = "docs-server"
+env = { OTHER = "explicit" }
+"#,
+        )
+        .expect("parse mcp servers");
+
+        secret_env.apply_to_mcp_servers(&mut servers);
+
+        let McpServerTransportConfig::Stdio { env, .. } = &servers["docs"].transport else {
+            panic!("expected stdio transport");
+        };
+        assert_eq!(
+            env.clone(),
+            Some(HashMap::from([
+                ("API_TOKEN".to_string(), "secret".to_string()),
+                ("OTHER".to_string(), "explicit".to_string()),
+            ]))
+        );
+    }
+}
@@ -0,0 +1,858 @@
+//! Portable session bundles.
+//!
+//! A bundle is a zip archive holding everything needed to resume a thread on
+//! another machine: the rollout, its session index name and state DB metadata
+//! (memory, dynamic tools, tags, pin and folder). A `manifest.json` lists every
+//! file with its SHA-256 so imports can reject truncated or edited bundles.
+//!
+//! Shell snapshots are intentionally left out: they describe the exporting
+//! machine's shell and are recreated when the thread is resumed.
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use chrono::Utc;
+use codex_protocol::ThreadId;
+use codex_protocol::dynamic_tools::DynamicToolSpec;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use zip::ZipArchive;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+use crate::rollout::ARCHIVED_SESSIONS_SUBDIR;
+use crate::rollout::SESSIONS_SUBDIR;
+use crate::rollout::find_archived_thread_path_by_id_str;
+use crate::rollout::find_thread_path_by_id_str;
+use crate::rollout::list::read_session_meta_line;
+use crate::rollout::rollout_date_parts;
+use crate::rollout::session_index::SessionIndexEntry;
+use crate::rollout::session_index::append_session_index_entry;
+use crate::rollout::session_index::find_thread_id_by_name;
+use crate::rollout::session_index::find_thread_name_by_id;
+use crate::state_db;
+
+/// Version of the bundle layout written by [`export_session`].
+pub const SESSION_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const ROLLOUT_FILE: &str = "rollout.jsonl";
+const METADATA_FILE: &str = "metadata.json";
+const MEMORY_FILE: &str = "memory.json";
+const DYNAMIC_TOOLS_FILE: &str = "dynamic_tools.json";
+/// Largest manifest accepted on import; real ones are a few hundred bytes.
+const MAX_MANIFEST_SIZE: u64 = 1024 * 1024;
+
+/// Describes the contents of a session bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionBundleManifest {
+    pub format_version: u32,
+    pub thread_id: ThreadId,
+    /// RFC 3339 timestamp of the export.
+    pub exported_at: String,
+    /// Version of the CLI that wrote the bundle.
+    pub cli_version: String,
+    /// Working directory recorded in the session metadata.
+    pub cwd: PathBuf,
+    /// File name of the rollout on the exporting machine.
+    pub rollout_file_name: String,
+    pub files: Vec<SessionBundleFile>,
+}
+
+/// A file inside a session bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionBundleFile {
+    pub name: String,
+    /// Hex encoded SHA-256 of the file contents.
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Thread metadata that lives outside the rollout file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct SessionBundleMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thread_name: Option<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    folder: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SessionBundleMemory {
+    trace_summary: String,
+    memory_summary: String,
+    #[serde(default)]
+    pinned: bool,
+}
+
+/// Result of [`export_session`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedSession {
+    pub thread_id: ThreadId,
+    pub manifest: SessionBundleManifest,
+}
+
+/// Options for [`import_session`].
+#[derive(Debug, Clone, Default)]
+pub struct SessionImportOptions {
+    /// Replace the exported working directory with this path. Paths recorded
+    /// below the exported directory are moved along with it.
+    pub cwd: Option<PathBuf>,
+}
+
+/// Result of [`import_session`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedSession {
+    pub thread_id: ThreadId,
+    pub rollout_path: PathBuf,
+    pub thread_name: Option<String>,
+    /// Working directory of the imported thread after remapping.
+    pub cwd: PathBuf,
+    /// Whether the state DB was updated. When it is absent, memory and
+    /// organization are dropped and the rollout is picked up by the next
+    /// backfill instead.
+    pub state_db_updated: bool,
+}
+
+/// Write a bundle for the thread identified by `id_or_name` to `output`.
+///
+/// `id_or_name` is either a thread id or a thread name from the session
+/// index, matching what `codex resume` accepts.
+pub async fn export_session(
+    codex_home: &Path,
+    id_or_name: &str,
+    output: &Path,
+) -> Result<ExportedSession> {
+    let thread_id = resolve_thread_id(codex_home, id_or_name).await?;
+    let id_str = thread_id.to_string();
+    let (rollout_path, archived) =
+        if let Some(path) = find_thread_path_by_id_str(codex_home, &id_str).await? {
+            (path, false)
+        } else if let Some(path) = find_archived_thread_path_by_id_str(codex_home, &id_str).await? {
+            (path, true)
+        } else {
+            bail!("no rollout found for session {thread_id}");
+        };
+    let rollout_file_name = rollout_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .with_context(|| format!("invalid rollout path {}", rollout_path.display()))?;
+    let rollout = tokio::fs::read(&rollout_path)
+        .await
+        .with_context(|| format!("failed to read rollout {}", rollout_path.display()))?;
+    let session_meta = read_session_meta_line(&rollout_path).await?;
+
+    let state_db_ctx = state_db::open_if_present(codex_home, "").await;
+    let ctx = state_db_ctx.as_deref();
+    let organization = match ctx {
+        Some(ctx) => ctx
+            .get_thread_organization(thread_id)
+            .await?
+            .unwrap_or_default(),
+        None => state_db::ThreadOrganization::default(),
+    };
+    let metadata = SessionBundleMetadata {
+        thread_name: find_thread_name_by_id(codex_home, &thread_id).await?,
+        archived,
+        tags: organization.tags,
+        pinned: organization.pinned,
+        folder: organization.folder,
+    };
+    let memory = state_db::get_thread_memory(ctx, thread_id, "session_export")
+        .await
+        .map(|memory| SessionBundleMemory {
+            trace_summary: memory.trace_summary,
+            memory_summary: memory.memory_summary,
+            pinned: memory.pinned,
+        });
+    let dynamic_tools = state_db::get_dynamic_tools(ctx, thread_id, "session_export")
+        .await
+        .or(session_meta.meta.dynamic_tools.clone());
+
+    let mut files = BTreeMap::new();
+    files.insert(ROLLOUT_FILE, rollout);
+    files.insert(METADATA_FILE, serde_json::to_vec_pretty(&metadata)?);
+    if let Some(memory) = memory {
+        files.insert(MEMORY_FILE, serde_json::to_vec_pretty(&memory)?);
+    }
+    if let Some(dynamic_tools) = dynamic_tools {
+        files.insert(
+            DYNAMIC_TOOLS_FILE,
+            serde_json::to_vec_pretty(&dynamic_tools)?,
+        );
+    }
+
+    let manifest = SessionBundleManifest {
+        format_version: SESSION_BUNDLE_FORMAT_VERSION,
+        thread_id,
+        exported_at: Utc::now().to_rfc3339(),
+        cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        cwd: session_meta.meta.cwd,
+        rollout_file_name,
+        files: files
+            .iter()
+            .map(|(name, contents)| SessionBundleFile {
+                name: (*name).to_string(),
+                sha256: sha256_hex(contents),
+                size: contents.len() as u64,
+            })
+            .collect(),
+    };
+
+    let archive = write_bundle(&manifest, &files)?;
+    if let Some(parent) = output.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(output, archive)
+        .await
+        .with_context(|| format!("failed to write {}", output.display()))?;
+
+    Ok(ExportedSession {
+        thread_id,
+        manifest,
+    })
+}
+
+/// Register the thread stored in the bundle at `bundle` under `codex_home`.
+///
+/// The rollout is written where it would have been recorded locally, the
+/// thread name is appended to the session index and, when a state DB exists,
+/// the thread's metadata, memory, dynamic tools and organization are restored.
+pub async fn import_session(
+    codex_home: &Path,
+    bundle: &Path,
+    default_provider: &str,
+    options: &SessionImportOptions,
+) -> Result<ImportedSession> {
+    let bytes = tokio::fs::read(bundle)
+        .await
+        .with_context(|| format!("failed to read {}", bundle.display()))?;
+    let (manifest, mut files) = read_bundle(bytes)?;
+    let thread_id = manifest.thread_id;
+    let id_str = thread_id.to_string();
+
+    if let Some(path) = find_thread_path_by_id_str(codex_home, &id_str).await? {
+        bail!("session {thread_id} already exists at {}", path.display());
+    }
+    if let Some(path) = find_archived_thread_path_by_id_str(codex_home, &id_str).await? {
+        bail!("session {thread_id} already exists at {}", path.display());
+    }
+
+    let metadata = match files.remove(METADATA_FILE) {
+        Some(contents) => serde_json::from_slice::<SessionBundleMetadata>(&contents)
+            .context("invalid metadata.json")?,
+        None => SessionBundleMetadata::default(),
+    };
+    let memory = files
+        .remove(MEMORY_FILE)
+        .map(|contents| serde_json::from_slice::<SessionBundleMemory>(&contents))
+        .transpose()
+        .context("invalid memory.json")?;
+    let dynamic_tools = files
+        .remove(DYNAMIC_TOOLS_FILE)
+        .map(|contents| serde_json::from_slice::<Vec<DynamicToolSpec>>(&contents))
+        .transpose()
+        .context("invalid dynamic_tools.json")?;
+    let rollout = files
+        .remove(ROLLOUT_FILE)
+        .context("bundle is missing rollout.jsonl")?;
+    let rollout = String::from_utf8(rollout).context("rollout.jsonl is not valid UTF-8")?;
+    let rollout_thread_id =
+        rollout_thread_id(&rollout).context("rollout.jsonl has no session metadata")?;
+    if rollout_thread_id != thread_id {
+        bail!("bundle manifest is for session {thread_id} but its rollout is {rollout_thread_id}");
+    }
+    let (rollout, cwd) = match options.cwd.as_deref() {
+        Some(cwd) => (remap_rollout_cwd(&rollout, &manifest.cwd, cwd), cwd),
+        None => (rollout, manifest.cwd.as_path()),
+    };
+
+    let rollout_path = bundle_rollout_destination(codex_home, &manifest, metadata.archived)?;
+    if let Some(parent) = rollout_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&rollout_path)
+        .await
+        .with_context(|| format!("failed to create {}", rollout_path.display()))?;
+    tokio::io::AsyncWriteExt::write_all(&mut file, rollout.as_bytes()).await?;
+    tokio::io::AsyncWriteExt::flush(&mut file).await?;
+
+    if let Some(thread_name) = metadata.thread_name.as_deref() {
+        append_session_index_entry(
+            codex_home,
+            &SessionIndexEntry {
+                id: thread_id,
+                thread_name: thread_name.to_string(),
+                updated_at: Utc::now().to_rfc3339(),
+            },
+        )
+        .await?;
+    }
+
+    let state_db_ctx = state_db::open_if_present(codex_home, default_provider).await;
+    if let Some(ctx) = state_db_ctx.as_deref() {
+        state_db::reconcile_rollout(
+            Some(ctx),
+            rollout_path.as_path(),
+            default_provider,
+            None,
+            &[],
+            Some(metadata.archived),
+        )
+        .await;
+        state_db::persist_dynamic_tools(
+            Some(ctx),
+            thread_id,
+            dynamic_tools.as_deref(),
+            "session_import",
+        )
+        .await;
+        if let Some(memory) = memory.as_ref() {
+            ctx.upsert_thread_memory(thread_id, &memory.trace_summary, &memory.memory_summary)
+                .await?;
+            if memory.pinned {
+                ctx.set_thread_memory_pinned(thread_id, true).await?;
+            }
+        }
+        if !metadata.tags.is_empty() {
+            ctx.set_thread_tags(thread_id, &metadata.tags).await?;
+        }
+        if metadata.pinned {
+            ctx.set_thread_pinned(thread_id, true).await?;
+        }
+        if metadata.folder.is_some() {
+            ctx.set_thread_folder(thread_id, metadata.folder.as_deref())
+                .await?;
+        }
+    }
+
+    Ok(ImportedSession {
+        thread_id,
+        rollout_path,
+        thread_name: metadata.thread_name,
+        cwd: cwd.to_path_buf(),
+        state_db_updated: state_db_ctx.is_some(),
+    })
+}
+
+async fn resolve_thread_id(codex_home: &Path, id_or_name: &str) -> Result<ThreadId> {
+    if let Ok(thread_id) = ThreadId::from_string(id_or_name) {
+        return Ok(thread_id);
+    }
+    find_thread_id_by_name(codex_home, id_or_name)
+        .await?
+        .with_context(|| format!("no session found with id or name `{id_or_name}`"))
+}
+
+fn bundle_rollout_destination(
+    codex_home: &Path,
+    manifest: &SessionBundleManifest,
+    archived: bool,
+) -> Result<PathBuf> {
+    let file_name = manifest.rollout_file_name.as_str();
+    let valid_name = file_name.starts_with("rollout-")
+        && file_name.ends_with(".jsonl")
+        && !file_name.contains(['/', '\\'])
+        && file_name.contains(&manifest.thread_id.to_string());
+    if !valid_name {
+        bail!("bundle has an invalid rollout file name `{file_name}`");
+    }
+    if archived {
+        return Ok(codex_home.join(ARCHIVED_SESSIONS_SUBDIR).join(file_name));
+    }
+    let (year, month, day) = rollout_date_parts(file_name.as_ref())
+        .with_context(|| format!("bundle has an invalid rollout file name `{file_name}`"))?;
+    Ok(codex_home
+        .join(SESSIONS_SUBDIR)
+        .join(year)
+        .join(month)
+        .join(day)
+        .join(file_name))
+}
+
+fn write_bundle(
+    manifest: &SessionBundleManifest,
+    files: &BTreeMap<&str, Vec<u8>>,
+) -> Result<Vec<u8>> {
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    writer.start_file(MANIFEST_FILE, options)?;
+    writer.write_all(&serde_json::to_vec_pretty(manifest)?)?;
+    for (name, contents) in files {
+        writer.start_file(*name, options)?;
+        writer.write_all(contents)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Read and verify a bundle, returning its manifest and files keyed by name.
+fn read_bundle(bytes: Vec<u8>) -> Result<(SessionBundleManifest, BTreeMap<String, Vec<u8>>)> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).context("not a valid session bundle")?;
+    let manifest = read_bundle_entry(&mut archive, MANIFEST_FILE, MAX_MANIFEST_SIZE)?;
+    let manifest: SessionBundleManifest =
+        serde_json::from_slice(&manifest).context("invalid manifest.json")?;
+    if manifest.format_version != SESSION_BUNDLE_FORMAT_VERSION {
+        bail!(
+            "unsupported session bundle format version {} (expected {SESSION_BUNDLE_FORMAT_VERSION})",
+            manifest.format_version
+        );
+    }
+
+    let mut files = BTreeMap::new();
+    for file in &manifest.files {
+        let contents = read_bundle_entry(&mut archive, &file.name, file.size)?;
+        let sha256 = sha256_hex(&contents);
+        if contents.len() as u64 != file.size || sha256 != file.sha256 {
+            bail!("checksum mismatch for {} in session bundle", file.name);
+        }
+        files.insert(file.name.clone(), contents);
+    }
+    Ok((manifest, files))
+}
+
+/// Read an entry, failing as soon as it decompresses to more than
+/// `max_size` bytes.
+fn read_bundle_entry(
+    archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+    name: &str,
+    max_size: u64,
+) -> Result<Vec<u8>> {
+    let entry = archive
+        .by_name(name)
+        .with_context(|| format!("session bundle is missing {name}"))?;
+    let mut contents = Vec::new();
+    entry
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut contents)
+        .with_context(|| format!("failed to read {name} from session bundle"))?;
+    if contents.len() as u64 > max_size {
+        bail!("{name} in session bundle is larger than {max_size} bytes");
+    }
+    Ok(contents)
+}
+
+fn sha256_hex(contents: &[u8]) -> String {
+    let digest = Sha256::digest(contents);
+    format!("{digest:x}")
+}
+
+/// Id recorded in the first session metadata line of a rollout.
+fn rollout_thread_id(rollout: &str) -> Option<ThreadId> {
+    rollout.lines().find_map(
+        |line| match serde_json::from_str::<RolloutLine>(line).ok()?.item {
+            RolloutItem::SessionMeta(meta_line) => Some(meta_line.meta.id),
+            _ => None,
+        },
+    )
+}
+
+/// Rewrite working directories recorded in a rollout from `from` to `to`.
+///
+/// Only session metadata and turn context lines carry a cwd; every other line
+/// is kept byte for byte.
+fn remap_rollout_cwd(rollout: &str, from: &Path, to: &Path) -> String {
+    let remap = |cwd: &mut PathBuf| -> bool {
+        match cwd.strip_prefix(from) {
+            Ok(rest) => {
+                *cwd = if rest.as_os_str().is_empty() {
+                    to.to_path_buf()
+                } else {
+                    to.join(rest)
+                };
+                true
+            }
+            Err(_) => false,
+        }
+    };
+
+    let mut remapped = String::with_capacity(rollout.len());
+    for line in rollout.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let rewritten = serde_json::from_str::<RolloutLine>(content)
+            .ok()
+            .and_then(|mut parsed| {
+                let changed = match &mut parsed.item {
+                    RolloutItem::SessionMeta(meta_line) => remap(&mut meta_line.meta.cwd),
+                    RolloutItem::TurnContext(context) => remap(&mut context.cwd),
+                    _ => false,
+                };
+                if changed {
+                    serde_json::to_string(&parsed).ok()
+                } else {
+                    None
+                }
+            });
+        match rewritten {
+            Some(rewritten) => {
+                remapped.push_str(&rewritten);
+                remapped.push_str(&line[content.len()..]);
+            }
+            None => remapped.push_str(line),
+        }
+    }
+    remapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::SessionSource;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const TEST_PROVIDER: &str = "test-provider";
+
+    async fn init_state_db(codex_home: &Path) -> state_db::StateDbHandle {
+        let runtime = codex_state::StateRuntime::init(
+            codex_home.to_path_buf(),
+            TEST_PROVIDER.to_string(),
+            None,
+        )
+        .await
+        .expect("state db should initialize");
+        runtime
+            .mark_backfill_complete(None)
+            .await
+            .expect("backfill should complete");
+        runtime
+    }
+
+    /// Write a rollout with a session meta line and one turn context, and
+    /// register it in the state DB of `codex_home`.
+    async fn seed_session(codex_home: &Path, thread_id: ThreadId, cwd: &Path) -> PathBuf {
+        let ts = "2025-01-03T12-00-00";
+        let dir = codex_home.join("sessions/2025/01/03");
+        std::fs::create_dir_all(&dir).expect("create sessions dir");
+        let rollout_path = dir.join(format!("rollout-{ts}-{thread_id}.jsonl"));
+        let meta = RolloutLine {
+            timestamp: ts.to_string(),
+            item: RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id: thread_id,
+                    timestamp: ts.to_string(),
+                    cwd: cwd.to_path_buf(),
+                    originator: "test".to_string(),
+                    cli_version: "0.0.0".to_string(),
+                    source: SessionSource::Cli,
+                    model_provider: Some(TEST_PROVIDER.to_string()),
+                    ..Default::default()
+                },
+                git: None,
+            }),
+        };
+        let turn_context = json!({
+            "timestamp": ts,
+            "type": "turn_context",
+            "payload": {
+                "cwd": cwd.join("crates/app"),
+                "approval_policy": "on-request",
+                "sandbox_policy": { "type": "read-only" },
+                "model": "gpt-5",
+                "summary": "auto",
+            },
+        });
+        let user_message = json!({
+            "timestamp": ts,
+            "type": "event_msg",
+            "payload": { "type": "user_message", "message": "fix the build", "kind": "plain" },
+        });
+        let contents = format!(
+            "{}\n{turn_context}\n{user_message}\n",
+            serde_json::to_string(&meta).expect("serialize meta")
+        );
+        std::fs::write(&rollout_path, contents).expect("write rollout");
+
+        let ctx = init_state_db(codex_home).await;
+        state_db::reconcile_rollout(
+            Some(ctx.as_ref()),
+            &rollout_path,
+            TEST_PROVIDER,
+            None,
+            &[],
+            Some(false),
+        )
+        .await;
+        rollout_path
+    }
+
+    #[tokio::test]
+    async fn export_and_import_round_trips_thread_state() {
+        let source = TempDir::new().expect("source home");
+        let target = TempDir::new().expect("target home");
+        let thread_id = ThreadId::new();
+        let source_cwd = PathBuf::from("/work/source/repo");
+        let source_rollout = seed_session(source.path(), thread_id, &source_cwd).await;
+
+        let source_db = init_state_db(source.path()).await;
+        source_db
+            .upsert_thread_memory(thread_id, "trace", "memory")
+            .await
+            .expect("memory");
+        source_db
+            .set_thread_memory_pinned(thread_id, true)
+            .await
+            .expect("pin memory");
+        source_db
+            .set_thread_tags(thread_id, &["bugfix", "build"])
+            .await
+            .expect("tags");
+        source_db
+            .set_thread_pinned(thread_id, true)
+            .await
+            .expect("pin");
+        source_db
+            .set_thread_folder(thread_id, Some("work/infra"))
+            .await
+            .expect("folder");
+        append_session_index_entry(
+            source.path(),
+            &SessionIndexEntry {
+                id: thread_id,
+                thread_name: "build fix".to_string(),
+                updated_at: Utc::now().to_rfc3339(),
+            },
+        )
+        .await
+        .expect("session index");
+
+        let bundle = source.path().join("export/build-fix.zip");
+        let exported = export_session(source.path(), "build fix", &bundle)
+            .await
+            .expect("export");
+        assert_eq!(exported.thread_id, thread_id);
+        assert_eq!(exported.manifest.cwd, source_cwd);
+        let names: Vec<_> = exported
+            .manifest
+            .files
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect();
+        assert_eq!(names, vec![MEMORY_FILE, METADATA_FILE, ROLLOUT_FILE]);
+
+        let target_db = init_state_db(target.path()).await;
+        let target_cwd = PathBuf::from("/home/me/repo");
+        let imported = import_session(
+            target.path(),
+            &bundle,
+            TEST_PROVIDER,
+            &SessionImportOptions {
+                cwd: Some(target_cwd.clone()),
+            },
+        )
+        .await
+        .expect("import");
+
+        let expected_path = target.path().join(
+            source_rollout
+                .strip_prefix(source.path())
+                .expect("rollout under source home"),
+        );
+        assert_eq!(
+            imported,
+            ImportedSession {
+                thread_id,
+                rollout_path: expected_path.clone(),
+                thread_name: Some("build fix".to_string()),
+                cwd: target_cwd.clone(),
+                state_db_updated: true,
+            }
+        );
+
+        let meta = read_session_meta_line(&expected_path)
+            .await
+            .expect("session meta");
+        assert_eq!(meta.meta.cwd, target_cwd);
+        let rollout = std::fs::read_to_string(&expected_path).expect("read rollout");
+        assert!(rollout.contains("/home/me/repo/crates/app"));
+        assert!(!rollout.contains("/work/source/repo"));
+
+        let thread = target_db
+            .get_thread(thread_id)
+            .await
+            .expect("get thread")
+            .expect("imported thread");
+        assert_eq!(thread.rollout_path, expected_path);
+        assert_eq!(thread.cwd, target_cwd.join("crates/app"));
+        let memory = target_db
+            .get_thread_memory(thread_id)
+            .await
+            .expect("get memory")
+            .expect("imported memory");
+        assert_eq!(memory.memory_summary, "memory");
+        assert!(memory.pinned);
+        assert_eq!(
+            target_db
+                .get_thread_organization(thread_id)
+                .await
+                .expect("get organization"),
+            Some(state_db::ThreadOrganization {
+                tags: vec!["bugfix".to_string(), "build".to_string()],
+                pinned: true,
+                folder: Some("work/infra".to_string()),
+            })
+        );
+        assert_eq!(
+            find_thread_name_by_id(target.path(), &thread_id)
+                .await
+                .expect("thread name"),
+            Some("build fix".to_string())
+        );
+
+        let err = import_session(
+            target.path(),
+            &bundle,
+            TEST_PROVIDER,
+            &SessionImportOptions::default(),
+        )
+        .await
+        .expect_err("second import should fail");
+        assert!(err.to_string().contains("already exists"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn import_rejects_tampered_bundle() {
+        let source = TempDir::new().expect("source home");
+        let target = TempDir::new().expect("target home");
+        let thread_id = ThreadId::new();
+        seed_session(source.path(), thread_id, Path::new("/work/repo")).await;
+        let bundle = source.path().join("bundle.zip");
+        let exported = export_session(source.path(), &thread_id.to_string(), &bundle)
+            .await
+            .expect("export");
+
+        let mut files = BTreeMap::new();
+        files.insert(ROLLOUT_FILE, b"{}\n".to_vec());
+        files.insert(METADATA_FILE, b"{}".to_vec());
+        let tampered = write_bundle(&exported.manifest, &files).expect("write bundle");
+        std::fs::write(&bundle, tampered).expect("overwrite bundle");
+
+        let err = import_session(
+            target.path(),
+            &bundle,
+            TEST_PROVIDER,
+            &SessionImportOptions::default(),
+        )
+        .await
+        .expect_err("tampered bundle should be rejected");
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn import_rejects_entries_larger_than_the_manifest_says() {
+        let source = TempDir::new().expect("source home");
+        let target = TempDir::new().expect("target home");
+        let thread_id = ThreadId::new();
+        seed_session(source.path(), thread_id, Path::new("/work/repo")).await;
+        let bundle = source.path().join("bundle.zip");
+        export_session(source.path(), &thread_id.to_string(), &bundle)
+            .await
+            .expect("export");
+
+        let (mut manifest, files) =
+            read_bundle(std::fs::read(&bundle).expect("read bundle")).expect("valid bundle");
+        for file in &mut manifest.files {
+            if file.name == ROLLOUT_FILE {
+                file.size = 4;
+            }
+        }
+        let mut files: BTreeMap<&str, Vec<u8>> = files
+            .iter()
+            .map(|(name, contents)| (name.as_str(), contents.clone()))
+            .collect();
+        files.insert(ROLLOUT_FILE, vec![b' '; 64 * 1024]);
+        let oversized = write_bundle(&manifest, &files).expect("write bundle");
+        std::fs::write(&bundle, oversized).expect("overwrite bundle");
+
+        let err = import_session(
+            target.path(),
+            &bundle,
+            TEST_PROVIDER,
+            &SessionImportOptions::default(),
+        )
+        .await
+        .expect_err("oversized entry should be rejected");
+        assert_eq!(
+            err.to_string(),
+            format!("{ROLLOUT_FILE} in session bundle is larger than 4 bytes")
+        );
+    }
+
+    #[tokio::test]
+    async fn import_rejects_bundle_whose_rollout_is_another_session() {
+        let source = TempDir::new().expect("source home");
+        let target = TempDir::new().expect("target home");
+        let thread_id = ThreadId::new();
+        seed_session(source.path(), thread_id, Path::new("/work/repo")).await;
+        let bundle = source.path().join("bundle.zip");
+        export_session(source.path(), &thread_id.to_string(), &bundle)
+            .await
+            .expect("export");
+
+        let (mut manifest, files) =
+            read_bundle(std::fs::read(&bundle).expect("read bundle")).expect("valid bundle");
+        let other_id = ThreadId::new();
+        manifest.thread_id = other_id;
+        let files = files
+            .iter()
+            .map(|(name, contents)| (name.as_str(), contents.clone()))
+            .collect();
+        let relabeled = write_bundle(&manifest, &files).expect("write bundle");
+        std::fs::write(&bundle, relabeled).expect("overwrite bundle");
+
+        let err = import_session(
+            target.path(),
+            &bundle,
+            TEST_PROVIDER,
+            &SessionImportOptions::default(),
+        )
+        .await
+        .expect_err("relabeled bundle should be rejected");
+        assert_eq!(
+            err.to_string(),
+            format!("bundle manifest is for session {other_id} but its rollout is {thread_id}")
+        );
+        assert!(
+            !target.path().join(SESSIONS_SUBDIR).exists(),
+            "nothing should be written for a rejected bundle"
+        );
+    }
+
+    #[test]
+    fn remap_rollout_cwd_only_rewrites_paths_below_the_exported_cwd() {
+        let rollout = concat!(
+            r#"{"timestamp":"t","type":"turn_context","payload":{"cwd":"/other","approval_policy":"never","sandbox_policy":{"type":"read-only"},"model":"m","summary":"auto"}}"#,
+            "\n",
+            "not json\n",
+        );
+        assert_eq!(
+            remap_rollout_cwd(rollout, Path::new("/work/repo"), Path::new("/home/repo")),
+            rollout
+        );
+    }
+}
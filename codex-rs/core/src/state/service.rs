@@ -10,6 +10,8 @@ use crate::file_watcher::FileWatcher;
 use crate::hooks::Hooks;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
+use crate::network_proxy::SessionNetworkProxy;
+use crate::secrets::SecretsInjector;
 use crate::skills::SkillsManager;
 use crate::state_db::StateDbHandle;
 use crate::tools::sandboxing::ApprovalStore;
@@ -37,6 +39,10 @@ pub(crate) struct SessionServices {
     pub(crate) file_watcher: Arc<FileWatcher>,
     pub(crate) agent_control: AgentControl,
     pub(crate) state_db: Option<StateDbHandle>,
+    /// Secrets injected into spawned commands and MCP stdio servers.
+    pub(crate) secrets: SecretsInjector,
+    /// Proxy that sandboxed commands are routed through, when enabled.
+    pub(crate) network_proxy: Option<SessionNetworkProxy>,
     /// Session-scoped model client shared across turns.
     pub(crate) model_client: ModelClient,
 }
@@ -13,9 +13,33 @@ use codex_protocol::dynamic_tools::DynamicToolSpec;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
 use codex_state::DB_METRIC_COMPARE_ERROR;
+pub use codex_state::KnowledgeStatus;
 pub use codex_state::LogEntry;
+pub use codex_state::LogField;
+pub use codex_state::LogFilter;
+pub use codex_state::LogFilterOp;
+pub use codex_state::LogQuery;
+pub use codex_state::LogRow;
+pub use codex_state::MessageHistoryEntry;
+pub use codex_state::NetworkAuditDecision;
+pub use codex_state::NetworkAuditEntry;
+pub use codex_state::NetworkAuditQuery;
+pub use codex_state::ProjectKnowledge;
 use codex_state::STATE_DB_VERSION;
+pub use codex_state::ThreadMemory;
+pub use codex_state::ThreadMetadata;
 use codex_state::ThreadMetadataBuilder;
+pub use codex_state::ThreadOrganization;
+pub use codex_state::ThreadOrganizationFilter;
+pub use codex_state::ThreadSearchHit;
+pub use codex_state::ThreadSearchPage;
+pub use codex_state::ThreadSearchSource;
+pub use codex_state::TurnUsage;
+pub use codex_state::UsageGroupBy;
+pub use codex_state::UsageQuery;
+pub use codex_state::UsageReportRow;
+pub use codex_state::normalize_thread_folder;
+pub use codex_state::normalize_thread_tags;
 use serde_json::Value;
 use std::path::Path;
 use std::path::PathBuf;
@@ -106,6 +130,24 @@ pub async fn open_if_present(codex_home: &Path, default_provider: &str) -> Optio
     require_backfill_complete(runtime, codex_home).await
 }
 
+/// Open the state DB to read recorded logs. Unlike [`open_if_present`] this
+/// does not require the rollout backfill to be complete.
+pub async fn open_log_db(config: &Config) -> anyhow::Result<StateDbHandle> {
+    let db_path = codex_state::state_db_path(config.codex_home.as_path());
+    if !tokio::fs::try_exists(&db_path).await.unwrap_or(false) {
+        anyhow::bail!(
+            "no log database at {}; logs are recorded when the `sqlite` feature is enabled",
+            db_path.display()
+        );
+    }
+    codex_state::StateRuntime::init(
+        config.codex_home.clone(),
+        config.model_provider_id.clone(),
+        None,
+    )
+    .await
+}
+
 async fn require_backfill_complete(
     runtime: StateDbHandle,
     codex_home: &Path,
@@ -173,14 +215,7 @@ pub async fn list_thread_ids_db(
     }
 
     let anchor = cursor_to_anchor(cursor);
-    let allowed_sources: Vec<String> = allowed_sources
-        .iter()
-        .map(|value| match serde_json::to_value(value) {
-            Ok(Value::String(s)) => s,
-            Ok(other) => other.to_string(),
-            Err(_) => String::new(),
-        })
-        .collect();
+    let allowed_sources = session_sources_to_strings(allowed_sources);
     let model_providers = model_providers.map(<[String]>::to_vec);
     match ctx
         .list_thread_ids(
@@ -215,6 +250,7 @@ pub async fn list_threads_db(
     allowed_sources: &[SessionSource],
     model_providers: Option<&[String]>,
     archived: bool,
+    organization: &ThreadOrganizationFilter,
 ) -> Option<codex_state::ThreadsPage> {
     let ctx = context?;
     if ctx.codex_home() != codex_home {
@@ -226,14 +262,7 @@ pub async fn list_threads_db(
     }
 
     let anchor = cursor_to_anchor(cursor);
-    let allowed_sources: Vec<String> = allowed_sources
-        .iter()
-        .map(|value| match serde_json::to_value(value) {
-            Ok(Value::String(s)) => s,
-            Ok(other) => other.to_string(),
-            Err(_) => String::new(),
-        })
-        .collect();
+    let allowed_sources = session_sources_to_strings(allowed_sources);
     let model_providers = model_providers.map(<[String]>::to_vec);
     match ctx
         .list_threads(
@@ -246,6 +275,7 @@ pub async fn list_threads_db(
             allowed_sources.as_slice(),
             model_providers.as_deref(),
             archived,
+            organization,
         )
         .await
     {
@@ -257,6 +287,40 @@ pub async fn list_threads_db(
     }
 }
 
+/// Full-text search over thread contents in SQLite.
+pub async fn search_threads_db(
+    context: &codex_state::StateRuntime,
+    query: &str,
+    page_size: usize,
+    offset: usize,
+    allowed_sources: &[SessionSource],
+    model_providers: Option<&[String]>,
+    archived: bool,
+) -> anyhow::Result<ThreadSearchPage> {
+    let allowed_sources = session_sources_to_strings(allowed_sources);
+    context
+        .search_threads(
+            query,
+            page_size,
+            offset,
+            allowed_sources.as_slice(),
+            model_providers,
+            archived,
+        )
+        .await
+}
+
+fn session_sources_to_strings(sources: &[SessionSource]) -> Vec<String> {
+    sources
+        .iter()
+        .map(|value| match serde_json::to_value(value) {
+            Ok(Value::String(s)) => s,
+            Ok(other) => other.to_string(),
+            Err(_) => String::new(),
+        })
+        .collect()
+}
+
 /// Look up the rollout path for a thread id using SQLite.
 pub async fn find_rollout_path_by_id(
     context: Option<&codex_state::StateRuntime>,
@@ -304,6 +368,53 @@ pub async fn persist_dynamic_tools(
     }
 }
 
+/// Record the token usage of a finished turn using SQLite.
+pub async fn record_turn_usage(
+    context: Option<&codex_state::StateRuntime>,
+    usage: &TurnUsage,
+    stage: &str,
+) {
+    let Some(ctx) = context else {
+        return;
+    };
+    if let Err(err) = ctx.record_turn_usage(usage).await {
+        warn!("state db record_turn_usage failed during {stage}: {err}");
+    }
+}
+
+/// Summarize recorded token usage, backfilling existing rollouts first if
+/// needed.
+pub async fn usage_report(
+    config: &Config,
+    query: &UsageQuery,
+) -> anyhow::Result<Vec<UsageReportRow>> {
+    let Some(ctx) = init_if_enabled(config, None).await else {
+        anyhow::bail!("usage history requires the state database (enable the `sqlite` feature)");
+    };
+    ctx.usage_report(query).await
+}
+
+/// List the requests recorded by session network proxies, newest first.
+pub async fn network_audit_log(
+    config: &Config,
+    query: &NetworkAuditQuery,
+) -> anyhow::Result<Vec<NetworkAuditEntry>> {
+    let db_path = codex_state::state_db_path(config.codex_home.as_path());
+    if !tokio::fs::try_exists(&db_path).await.unwrap_or(false) {
+        anyhow::bail!(
+            "no state database at {}; network requests are recorded when the `sqlite` feature is enabled",
+            db_path.display()
+        );
+    }
+    let runtime = codex_state::StateRuntime::init(
+        config.codex_home.clone(),
+        config.model_provider_id.clone(),
+        None,
+    )
+    .await?;
+    runtime.list_network_audit(query).await
+}
+
 /// Get memory summaries for a thread id using SQLite.
 pub async fn get_thread_memory(
     context: Option<&codex_state::StateRuntime>,
@@ -424,6 +535,15 @@ pub async fn reconcile_rollout(
         );
         return;
     }
+    if let Err(err) = ctx
+        .insert_thread_search_entries(metadata.id, &outcome.search_entries)
+        .await
+    {
+        warn!(
+            "state db reconcile_rollout search index failed {}: {err}",
+            rollout_path.display()
+        );
+    }
     if let Ok(meta_line) = crate::rollout::list::read_session_meta_line(rollout_path).await {
         persist_dynamic_tools(
             Some(ctx),
@@ -9,12 +9,14 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use chrono::Utc;
 use tokio::select;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::AbortOnDropHandle;
 use tracing::Instrument;
 use tracing::Span;
+use tracing::info_span;
 use tracing::trace;
 use tracing::warn;
 
@@ -27,6 +29,7 @@ use crate::protocol::ErrorEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::SessionSource;
 use crate::protocol::ThreadNameUpdatedEvent;
+use crate::protocol::TokenUsage;
 use crate::protocol::TurnAbortReason;
 use crate::protocol::TurnAbortedEvent;
 use crate::protocol::TurnCompleteEvent;
@@ -34,6 +37,7 @@ use crate::session_prefix::TURN_ABORTED_OPEN_TAG;
 use crate::state::ActiveTurn;
 use crate::state::RunningTask;
 use crate::state::TaskKind;
+use crate::state_db;
 use codex_protocol::config_types::ModeKind;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
@@ -127,6 +131,7 @@ impl Session {
         self.abort_all_tasks(TurnAbortReason::Replaced).await;
         self.seed_initial_context_if_needed(turn_context.as_ref())
             .await;
+        let token_usage_at_start = self.token_usage_totals().await;
 
         let task: Arc<dyn SessionTask> = Arc::new(task);
         let task_kind = task.kind();
@@ -141,6 +146,12 @@ impl Session {
             let task_for_run = Arc::clone(&task);
             let task_cancellation_token = cancellation_token.child_token();
             let session_span = Span::current();
+            // Carries the turn id so log rows can be filtered per turn.
+            let turn_span = info_span!(
+                parent: &session_span,
+                "turn",
+                turn_id = %turn_context.sub_id
+            );
             tokio::spawn(
                 async move {
                     let ctx_for_finish = Arc::clone(&ctx);
@@ -161,7 +172,7 @@ impl Session {
                     }
                     done_clone.notify_waiters();
                 }
-                .instrument(session_span),
+                .instrument(turn_span),
             )
         };
 
@@ -177,6 +188,7 @@ impl Session {
             task,
             cancellation_token,
             turn_context: Arc::clone(&turn_context),
+            token_usage_at_start,
             _timer: timer,
         };
         self.register_new_active_task(running_task).await;
@@ -198,12 +210,17 @@ impl Session {
         let mut active = self.active_turn.lock().await;
         let mut pending_input = Vec::<ResponseInputItem>::new();
         let mut should_close_processes = false;
-        if let Some(at) = active.as_mut()
-            && at.remove_task(&turn_context.sub_id)
-        {
-            let mut ts = at.turn_state.lock().await;
-            pending_input = ts.take_pending_input();
-            should_close_processes = true;
+        let mut token_usage_at_start = None;
+        if let Some(at) = active.as_mut() {
+            token_usage_at_start = at
+                .tasks
+                .get(&turn_context.sub_id)
+                .map(|task| task.token_usage_at_start.clone());
+            if at.remove_task(&turn_context.sub_id) {
+                let mut ts = at.turn_state.lock().await;
+                pending_input = ts.take_pending_input();
+                should_close_processes = true;
+            }
         }
         if should_close_processes {
             *active = None;
@@ -220,6 +237,10 @@ impl Session {
         if should_close_processes {
             self.close_unified_exec_processes().await;
         }
+        if let Some(token_usage_at_start) = token_usage_at_start.as_ref() {
+            self.record_turn_usage(turn_context.as_ref(), token_usage_at_start)
+                .await;
+        }
         let event = EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: last_agent_message.clone(),
         });
@@ -318,6 +339,34 @@ impl Session {
         });
     }
 
+    /// Add the tokens spent since `token_usage_at_start` to the usage ledger.
+    async fn record_turn_usage(
+        &self,
+        turn_context: &TurnContext,
+        token_usage_at_start: &TokenUsage,
+    ) {
+        let Some(state_db_ctx) = self.state_db() else {
+            return;
+        };
+        let token_usage = self.token_usage_totals().await;
+        let Some(delta) = codex_state::token_usage_delta(token_usage_at_start, &token_usage) else {
+            return;
+        };
+        let usage = state_db::TurnUsage {
+            thread_id: self.conversation_id,
+            turn_id: turn_context.turn_uuid.clone(),
+            model: turn_context.model_info.slug.clone(),
+            model_provider: turn_context.config.model_provider_id.clone(),
+            cwd: turn_context.cwd.clone(),
+            input_tokens: delta.input_tokens,
+            cached_input_tokens: delta.cached_input_tokens,
+            output_tokens: delta.output_tokens,
+            reasoning_output_tokens: delta.reasoning_output_tokens,
+            recorded_at: Utc::now(),
+        };
+        state_db::record_turn_usage(Some(state_db_ctx.as_ref()), &usage, "turn_finished").await;
+    }
+
     async fn register_new_active_task(&self, task: RunningTask) {
         let mut active = self.active_turn.lock().await;
         let mut turn = ActiveTurn::default();
@@ -390,6 +439,9 @@ impl Session {
             self.flush_rollout().await;
         }
 
+        self.record_turn_usage(task.turn_context.as_ref(), &task.token_usage_at_start)
+            .await;
+
         let event = EventMsg::TurnAborted(TurnAbortedEvent { reason });
         self.send_event(task.turn_context.as_ref(), event).await;
     }
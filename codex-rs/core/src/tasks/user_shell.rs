@@ -136,13 +136,20 @@ pub(crate) async fn execute_user_shell_command(
         )
         .await;
 
+    let mut env = create_env(
+        &turn_context.shell_environment_policy,
+        Some(session.conversation_id),
+    );
+    session
+        .services
+        .secrets
+        .env_for_cwd(&cwd)
+        .await
+        .apply(&mut env);
     let exec_env = ExecEnv {
         command: exec_command.clone(),
         cwd: cwd.clone(),
-        env: create_env(
-            &turn_context.shell_environment_policy,
-            Some(session.conversation_id),
-        ),
+        env,
         // TODO(zhao-oai): Now that we have ExecExpiration::Cancellation, we
         // should use that instead of an "arbitrarily large" timeout here.
         expiration: USER_SHELL_TIMEOUT_MS.into(),
@@ -1,4 +1,5 @@
 use crate::function_tool::FunctionCallError;
+use crate::project_knowledge::knowledge_repo_root;
 use crate::state_db;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
@@ -11,12 +12,19 @@ use codex_protocol::ThreadId;
 use codex_protocol::models::FunctionCallOutputBody;
 use serde::Deserialize;
 use serde_json::json;
+use std::path::Path;
 
 pub struct GetMemoryHandler;
 
+/// Memories returned for a `query`.
+const MEMORY_SEARCH_LIMIT: usize = 5;
+
 #[derive(Deserialize)]
 struct GetMemoryArgs {
-    memory_id: String,
+    #[serde(default)]
+    memory_id: Option<String>,
+    #[serde(default)]
+    query: Option<String>,
 }
 
 #[async_trait]
@@ -27,7 +35,10 @@ impl ToolHandler for GetMemoryHandler {
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
-            session, payload, ..
+            session,
+            turn,
+            payload,
+            ..
         } = invocation;
 
         let arguments = match payload {
@@ -40,33 +51,81 @@ impl ToolHandler for GetMemoryHandler {
         };
 
         let args: GetMemoryArgs = parse_arguments(&arguments)?;
-        let thread_id = ThreadId::from_string(args.memory_id.as_str()).map_err(|err| {
+        let state_db_ctx = session.state_db();
+        let memory_id = match (args.memory_id, args.query) {
+            (Some(memory_id), None) => memory_id,
+            (None, Some(query)) => {
+                return search_memories(state_db_ctx.as_deref(), &turn.cwd, &query).await;
+            }
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "provide exactly one of memory_id or query".to_string(),
+                ));
+            }
+        };
+        let thread_id = ThreadId::from_string(memory_id.as_str()).map_err(|err| {
             FunctionCallError::RespondToModel(format!("memory_id must be a valid thread id: {err}"))
         })?;
 
-        let state_db_ctx = session.state_db();
         let memory =
             state_db::get_thread_memory(state_db_ctx.as_deref(), thread_id, "get_memory_tool")
                 .await
                 .ok_or_else(|| {
                     FunctionCallError::RespondToModel(format!(
-                        "memory not found for memory_id={}",
-                        args.memory_id
+                        "memory not found for memory_id={memory_id}"
                     ))
                 })?;
 
-        let content = serde_json::to_string_pretty(&json!({
-            "memory_id": args.memory_id,
+        memory_output(&json!({
+            "memory_id": memory_id,
             "trace_summary": memory.trace_summary,
             "memory_summary": memory.memory_summary,
         }))
+    }
+}
+
+/// Rank the memories recorded anywhere in the repository of `cwd` against
+/// `query`.
+async fn search_memories(
+    state_db_ctx: Option<&codex_state::StateRuntime>,
+    cwd: &Path,
+    query: &str,
+) -> Result<ToolOutput, FunctionCallError> {
+    let Some(ctx) = state_db_ctx else {
+        return Err(FunctionCallError::RespondToModel(
+            "get_memory is unavailable because the state database is disabled".to_string(),
+        ));
+    };
+    let hits = ctx
+        .search_thread_memories(&knowledge_repo_root(cwd), query, MEMORY_SEARCH_LIMIT)
+        .await
         .map_err(|err| {
-            FunctionCallError::Fatal(format!("failed to serialize memory payload: {err}"))
+            FunctionCallError::RespondToModel(format!("failed to search memories: {err}"))
         })?;
-
-        Ok(ToolOutput::Function {
-            body: FunctionCallOutputBody::Text(content),
-            success: Some(true),
+    let memories = hits
+        .into_iter()
+        .map(|hit| {
+            json!({
+                "memory_id": hit.memory.thread_id.to_string(),
+                "memory_summary": hit.memory.memory_summary,
+                "cwd": hit.cwd,
+                "updated_at": hit.memory.updated_at.to_rfc3339(),
+            })
         })
-    }
+        .collect::<Vec<_>>();
+    memory_output(&json!({
+        "query": query,
+        "memories": memories,
+    }))
+}
+
+fn memory_output(payload: &serde_json::Value) -> Result<ToolOutput, FunctionCallError> {
+    let content = serde_json::to_string_pretty(payload).map_err(|err| {
+        FunctionCallError::Fatal(format!("failed to serialize memory payload: {err}"))
+    })?;
+
+    Ok(ToolOutput::Function {
+        body: FunctionCallOutputBody::Text(content),
+        success: Some(true),
+    })
 }
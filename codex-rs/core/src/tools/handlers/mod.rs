@@ -8,6 +8,7 @@ mod mcp;
 mod mcp_resource;
 mod plan;
 mod read_file;
+mod remember;
 mod request_user_input;
 mod shell;
 mod test_sync;
@@ -28,6 +29,7 @@ pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
+pub use remember::RememberHandler;
 pub use request_user_input::RequestUserInputHandler;
 pub(crate) use request_user_input::request_user_input_tool_description;
 pub use shell::ShellCommandHandler;
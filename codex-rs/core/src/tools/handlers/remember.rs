@@ -0,0 +1,73 @@
+use crate::function_tool::FunctionCallError;
+use crate::project_knowledge::knowledge_repo_root;
+use crate::state_db::KnowledgeStatus;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use serde::Deserialize;
+
+pub struct RememberHandler;
+
+#[derive(Deserialize)]
+struct RememberArgs {
+    fact: String,
+}
+
+#[async_trait]
+impl ToolHandler for RememberHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "remember handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: RememberArgs = parse_arguments(&arguments)?;
+        let Some(state_db_ctx) = session.state_db() else {
+            return Err(FunctionCallError::RespondToModel(
+                "remember is unavailable because the state database is disabled".to_string(),
+            ));
+        };
+        let repo_root = knowledge_repo_root(&turn.cwd);
+        let entry = state_db_ctx
+            .record_project_knowledge(&repo_root, &args.fact, Some(session.conversation_id))
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to remember: {err}"))
+            })?;
+
+        let message = match entry.status {
+            KnowledgeStatus::Pending => {
+                "Recorded for review. Once the user approves it, the fact is included in the instructions of future sessions in this repository."
+            }
+            KnowledgeStatus::Approved => "This fact is already known for this repository.",
+            KnowledgeStatus::Rejected => {
+                "The user rejected this fact before; do not propose it again."
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(message.to_string()),
+            success: Some(entry.status != KnowledgeStatus::Rejected),
+        })
+    }
+}
@@ -238,6 +238,16 @@ impl ShellHandler {
             call_id,
             freeform,
         } = args;
+        let mut exec_params = exec_params;
+        session
+            .services
+            .secrets
+            .env_for_cwd(&exec_params.cwd)
+            .await
+            .apply(&mut exec_params.env);
+        if let Some(network_proxy) = &session.services.network_proxy {
+            network_proxy.apply(&mut exec_params.env, &turn.sub_id, &call_id);
+        }
 
         let features = session.features();
         let request_rule_enabled = features.enabled(crate::features::Feature::RequestRule);
@@ -4,11 +4,18 @@ Module: orchestrator
 Central place for approvals + sandbox selection + retry semantics. Drives a
 simple sequence for any ToolRuntime: approval → select sandbox → attempt →
 retry without sandbox on denial (no re‑approval thanks to caching).
+
+Attempts whose requests the network proxy blocked are rerun once the user
+allows the blocked hosts.
 */
+use std::collections::HashSet;
+use std::path::Path;
+
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
 use crate::features::Feature;
+use crate::network_proxy::NetworkApprovals;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ExecApprovalRequirement;
@@ -80,6 +87,7 @@ impl ToolOrchestrator {
                     }
                     ReviewDecision::Approved
                     | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                    | ReviewDecision::ApprovedNetworkPolicyAmendment { .. }
                     | ReviewDecision::ApprovedForSession => {}
                 }
                 already_approved = true;
@@ -99,6 +107,7 @@ impl ToolOrchestrator {
         // Platform-specific flag gating is handled by SandboxManager::select_initial
         // via crate::safety::get_platform_sandbox(..).
         let use_linux_sandbox_bwrap = turn_ctx.features.enabled(Feature::UseLinuxSandboxBwrap);
+        let network_proxy_ports = tool_ctx.session.network_proxy_ports();
         let initial_attempt = SandboxAttempt {
             sandbox: initial_sandbox,
             policy: &turn_ctx.sandbox_policy,
@@ -106,10 +115,14 @@ impl ToolOrchestrator {
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
             use_linux_sandbox_bwrap,
+            network_proxy_ports: &network_proxy_ports,
             windows_sandbox_level: turn_ctx.windows_sandbox_level,
         };
 
-        match tool.run(req, &initial_attempt, tool_ctx).await {
+        match self
+            .run_attempt(tool, req, &initial_attempt, tool_ctx, approval_policy)
+            .await
+        {
             Ok(out) => {
                 // We have a successful initial result
                 Ok(out)
@@ -147,6 +160,7 @@ impl ToolOrchestrator {
                         }
                         ReviewDecision::Approved
                         | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                        | ReviewDecision::ApprovedNetworkPolicyAmendment { .. }
                         | ReviewDecision::ApprovedForSession => {}
                     }
                 }
@@ -158,15 +172,112 @@ impl ToolOrchestrator {
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
                     use_linux_sandbox_bwrap,
+                    network_proxy_ports: &[],
                     windows_sandbox_level: turn_ctx.windows_sandbox_level,
                 };
 
                 // Second attempt.
-                (*tool).run(req, &escalated_attempt, tool_ctx).await
+                self.run_attempt(tool, req, &escalated_attempt, tool_ctx, approval_policy)
+                    .await
             }
             other => other,
         }
     }
+
+    /// Run one attempt. If the network proxy blocked hosts the command tried to
+    /// reach, ask the user about each and rerun the attempt when any is allowed.
+    async fn run_attempt<Rq, Out, T>(
+        &self,
+        tool: &mut T,
+        req: &Rq,
+        attempt: &SandboxAttempt<'_>,
+        tool_ctx: &ToolCtx<'_>,
+        approval_policy: AskForApproval,
+    ) -> Result<Out, ToolError>
+    where
+        T: ToolRuntime<Rq, Out>,
+    {
+        let network_proxy = tool_ctx
+            .session
+            .services
+            .network_proxy
+            .as_ref()
+            .filter(|_| !matches!(approval_policy, AskForApproval::Never));
+        let (Some(network_proxy), Some((command, cwd))) =
+            (network_proxy, tool.network_approval_command(req))
+        else {
+            return tool.run(req, attempt, tool_ctx).await;
+        };
+
+        let approvals = network_proxy.approvals();
+        let _tracked = approvals.track_command(&tool_ctx.call_id);
+        let mut asked = HashSet::new();
+        loop {
+            let result = tool.run(req, attempt, tool_ctx).await;
+            let retry =
+                request_blocked_host_approvals(tool_ctx, approvals, &command, &cwd, &mut asked)
+                    .await?;
+            if !retry {
+                return result;
+            }
+        }
+    }
+}
+
+/// Ask the user about each host the network proxy blocked for the tracked
+/// command `tool_ctx.call_id` that is not in `asked` yet, and allow the ones
+/// they approve. Returns whether any host was allowed, i.e. whether rerunning
+/// the command can get further.
+pub(crate) async fn request_blocked_host_approvals(
+    tool_ctx: &ToolCtx<'_>,
+    approvals: &NetworkApprovals,
+    command: &[String],
+    cwd: &Path,
+    asked: &mut HashSet<String>,
+) -> Result<bool, ToolError> {
+    let call_id = &tool_ctx.call_id;
+    let blocked: Vec<_> = approvals
+        .take_blocked(call_id)
+        .into_iter()
+        .filter(|blocked| asked.insert(blocked.host.clone()))
+        .collect();
+    let mut allowed = false;
+    for blocked in blocked {
+        let decision = tool_ctx
+            .session
+            .request_network_approval(
+                tool_ctx.turn,
+                call_id.clone(),
+                command.to_vec(),
+                cwd.to_path_buf(),
+                blocked.host.clone(),
+                blocked.port,
+                blocked.method,
+            )
+            .await;
+        tool_ctx.turn.otel_manager.tool_decision(
+            &tool_ctx.tool_name,
+            call_id,
+            &decision,
+            ToolDecisionSource::User,
+        );
+        match decision {
+            ReviewDecision::Approved | ReviewDecision::ApprovedExecpolicyAmendment { .. } => {
+                approvals.allow_for_command(call_id, &blocked.host);
+                allowed = true;
+            }
+            ReviewDecision::ApprovedForSession
+            | ReviewDecision::ApprovedNetworkPolicyAmendment { .. } => {
+                approvals.allow_for_session(&blocked.host);
+                allowed = true;
+            }
+            ReviewDecision::Denied => {}
+            ReviewDecision::Abort => {
+                return Err(ToolError::Rejected("rejected by user".to_string()));
+            }
+        }
+    }
+    Ok(allowed)
 }
 
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
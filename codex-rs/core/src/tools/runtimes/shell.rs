@@ -135,6 +135,10 @@ impl Approvable<ShellRequest> for ShellRuntime {
             SandboxOverride::NoOverride
         }
     }
+
+    fn network_approval_command(&self, req: &ShellRequest) -> Option<(Vec<String>, PathBuf)> {
+        Some((req.command.clone(), req.cwd.clone()))
+    }
 }
 
 impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
@@ -19,6 +19,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::path::Path;
+use std::path::PathBuf;
 
 use futures::Future;
 use futures::future::BoxFuture;
@@ -228,6 +229,15 @@ pub(crate) trait Approvable<Req> {
         req: &'a Req,
         ctx: ApprovalCtx<'a>,
     ) -> BoxFuture<'a, ReviewDecision>;
+
+    /// The command and cwd to show when the network proxy blocks a host, for
+    /// runtimes whose attempts run the command to completion and can therefore
+    /// be retried once the user allows it. `None` skips network approvals.
+    /// Unified exec processes outlive their attempt, so the unified exec
+    /// process manager asks around the whole command instead.
+    fn network_approval_command(&self, _req: &Req) -> Option<(Vec<String>, PathBuf)> {
+        None
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -275,6 +285,7 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
     pub use_linux_sandbox_bwrap: bool,
+    pub network_proxy_ports: &'a [u16],
     pub windows_sandbox_level: codex_protocol::config_types::WindowsSandboxLevel,
 }
 
@@ -291,6 +302,7 @@ impl<'a> SandboxAttempt<'a> {
                 sandbox_policy_cwd: self.sandbox_cwd,
                 codex_linux_sandbox_exe: self.codex_linux_sandbox_exe,
                 use_linux_sandbox_bwrap: self.use_linux_sandbox_bwrap,
+                network_proxy_ports: self.network_proxy_ports,
                 windows_sandbox_level: self.windows_sandbox_level,
             })
     }
@@ -638,22 +638,56 @@ fn create_request_user_input_tool() -> ToolSpec {
 }
 
 fn create_get_memory_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "memory_id".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Memory ID to fetch. Uses the thread ID as the memory identifier.".to_string(),
+                ),
+            },
+        ),
+        (
+            "query".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Keywords to search the memories of earlier sessions in this repository for."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "get_memory".to_string(),
+        description: "Searches the memories of earlier sessions in the current repository when given a query, returning the most relevant and recent ones with their memory_id. Given a memory_id, loads the full stored memory payload. Provide exactly one of query or memory_id.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_remember_tool() -> ToolSpec {
     let properties = BTreeMap::from([(
-        "memory_id".to_string(),
+        "fact".to_string(),
         JsonSchema::String {
             description: Some(
-                "Memory ID to fetch. Uses the thread ID as the memory identifier.".to_string(),
+                "One short, self-contained fact, e.g. \"tests need `--features sqlite`\"."
+                    .to_string(),
             ),
         },
     )]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "get_memory".to_string(),
-        description: "Loads the full stored memory payload for a memory_id.".to_string(),
+        name: "remember".to_string(),
+        description: "Records a durable fact about the current repository, such as a build requirement or a convention to follow, so future sessions in this repository know it. Facts take effect after the user approves them. Only record facts that stay true beyond the current task.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["memory_id".to_string()]),
+            required: Some(vec!["fact".to_string()]),
             additional_properties: Some(false.into()),
         },
     })
@@ -1260,6 +1294,7 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::RememberHandler;
     use crate::tools::handlers::RequestUserInputHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
@@ -1276,6 +1311,7 @@ pub(crate) fn build_specs(
     let apply_patch_handler = Arc::new(ApplyPatchHandler);
     let dynamic_tool_handler = Arc::new(DynamicToolHandler);
     let get_memory_handler = Arc::new(GetMemoryHandler);
+    let remember_handler = Arc::new(RememberHandler);
     let view_image_handler = Arc::new(ViewImageHandler);
     let mcp_handler = Arc::new(McpHandler);
     let mcp_resource_handler = Arc::new(McpResourceHandler);
@@ -1338,6 +1374,8 @@ pub(crate) fn build_specs(
     if config.memory_tools {
         builder.push_spec(create_get_memory_tool());
         builder.register_handler("get_memory", get_memory_handler);
+        builder.push_spec(create_remember_tool());
+        builder.register_handler("remember", remember_handler);
     }
 
     if let Some(apply_patch_tool_type) = &config.apply_patch_tool_type {
@@ -1714,8 +1752,10 @@ mod tests {
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
         assert!(
-            !tools.iter().any(|t| t.spec.name() == "get_memory"),
-            "get_memory should be disabled when memory_tool feature is off"
+            !tools
+                .iter()
+                .any(|t| matches!(t.spec.name(), "get_memory" | "remember")),
+            "memory tools should be disabled when memory_tool feature is off"
         );
 
         features.enable(Feature::MemoryTool);
@@ -1725,7 +1765,7 @@ mod tests {
             web_search_mode: Some(WebSearchMode::Cached),
         });
         let (tools, _) = build_specs(&tools_config, None, &[]).build();
-        assert_contains_tool_names(&tools, &["get_memory"]);
+        assert_contains_tool_names(&tools, &["get_memory", "remember"]);
     }
 
     fn assert_model_tools(
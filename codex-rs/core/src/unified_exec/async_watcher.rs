@@ -2,6 +2,8 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use codex_secrets::StreamRedactor;
+use codex_secrets::redact_secrets;
 use tokio::sync::Mutex;
 use tokio::time::Duration;
 use tokio::time::Instant;
@@ -53,6 +55,7 @@ pub(crate) fn start_streaming_output(
         use tokio::sync::broadcast::error::RecvError;
 
         let mut pending = Vec::<u8>::new();
+        let mut redactor = StreamRedactor::new();
         let mut emitted_deltas: usize = 0;
 
         let mut grace_sleep: Option<Pin<Box<Sleep>>> = None;
@@ -69,7 +72,6 @@ pub(crate) fn start_streaming_output(
                         sleep.as_mut().await;
                     }
                 }, if grace_sleep.is_some() => {
-                    output_drained.notify_one();
                     break;
                 }
 
@@ -80,13 +82,13 @@ pub(crate) fn start_streaming_output(
                             continue;
                         },
                         Err(RecvError::Closed) => {
-                            output_drained.notify_one();
                             break;
                         }
                     };
 
                     process_chunk(
                         &mut pending,
+                        &mut redactor,
                         &transcript,
                         &call_id,
                         &session_ref,
@@ -97,6 +99,13 @@ pub(crate) fn start_streaming_output(
                 }
             }
         }
+
+        // Release output held back in case it was the start of a secret.
+        let tail = redactor.finish();
+        if !tail.is_empty() && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL {
+            send_output_delta(&session_ref, &turn_ref, &call_id, tail).await;
+        }
+        output_drained.notify_one();
     });
 }
 
@@ -139,8 +148,10 @@ pub(crate) fn spawn_exit_watcher(
     });
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn process_chunk(
     pending: &mut Vec<u8>,
+    redactor: &mut StreamRedactor,
     transcript: &Arc<Mutex<HeadTailBuffer>>,
     call_id: &str,
     session_ref: &Arc<Session>,
@@ -159,18 +170,31 @@ async fn process_chunk(
             continue;
         }
 
-        let event = ExecCommandOutputDeltaEvent {
-            call_id: call_id.to_string(),
-            stream: ExecOutputStream::Stdout,
-            chunk: prefix,
-        };
-        session_ref
-            .send_event(turn_ref.as_ref(), EventMsg::ExecCommandOutputDelta(event))
-            .await;
+        let chunk = redactor.push(&prefix);
+        if chunk.is_empty() {
+            continue;
+        }
+        send_output_delta(session_ref, turn_ref, call_id, chunk).await;
         *emitted_deltas += 1;
     }
 }
 
+async fn send_output_delta(
+    session_ref: &Arc<Session>,
+    turn_ref: &Arc<TurnContext>,
+    call_id: &str,
+    chunk: Vec<u8>,
+) {
+    let event = ExecCommandOutputDeltaEvent {
+        call_id: call_id.to_string(),
+        stream: ExecOutputStream::Stdout,
+        chunk,
+    };
+    session_ref
+        .send_event(turn_ref.as_ref(), EventMsg::ExecCommandOutputDelta(event))
+        .await;
+}
+
 /// Emit an ExecCommandEnd event for a unified exec session, using the transcript
 /// as the primary source of aggregated_output and falling back to the provided
 /// text when the transcript is empty.
@@ -247,7 +271,7 @@ async fn resolve_aggregated_output(
         return fallback;
     }
 
-    String::from_utf8_lossy(&guard.to_bytes()).to_string()
+    redact_secrets(&String::from_utf8_lossy(&guard.to_bytes())).into_owned()
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use codex_secrets::redact_secrets;
 use rand::Rng;
 use std::cmp::Reverse;
 use std::collections::HashMap;
@@ -12,12 +13,14 @@ use tokio_util::sync::CancellationToken;
 
 use crate::exec_env::create_env;
 use crate::exec_policy::ExecApprovalRequest;
+use crate::protocol::AskForApproval;
 use crate::protocol::ExecCommandSource;
 use crate::sandboxing::ExecEnv;
 use crate::tools::events::ToolEmitter;
 use crate::tools::events::ToolEventCtx;
 use crate::tools::events::ToolEventStage;
 use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::orchestrator::request_blocked_host_approvals;
 use crate::tools::runtimes::unified_exec::UnifiedExecRequest as UnifiedExecToolRequest;
 use crate::tools::runtimes::unified_exec::UnifiedExecRuntime;
 use crate::tools::sandboxing::ToolCtx;
@@ -122,19 +125,18 @@ impl UnifiedExecProcessManager {
             .clone()
             .unwrap_or_else(|| context.turn.cwd.clone());
 
-        let process = self
-            .open_session_with_sandbox(&request, cwd.clone(), context)
-            .await;
-
-        let process = match process {
-            Ok(process) => Arc::new(process),
-            Err(err) => {
-                self.release_process_id(&request.process_id).await;
-                return Err(err);
-            }
-        };
+        // Like shell commands, a command that exits within its yield time is
+        // rerun once the user allows hosts the network proxy blocked for it.
+        let network_approvals = context
+            .session
+            .services
+            .network_proxy
+            .as_ref()
+            .filter(|_| !matches!(context.turn.approval_policy, AskForApproval::Never))
+            .map(|network_proxy| network_proxy.approvals());
+        let _tracked = network_approvals.map(|approvals| approvals.track_command(&context.call_id));
+        let mut asked_hosts = HashSet::new();
 
-        let transcript = Arc::new(tokio::sync::Mutex::new(HeadTailBuffer::default()));
         let event_ctx = ToolEventCtx::new(
             context.session.as_ref(),
             context.turn.as_ref(),
@@ -147,33 +149,79 @@ impl UnifiedExecProcessManager {
             ExecCommandSource::UnifiedExecStartup,
             Some(request.process_id.clone()),
         );
-        emitter.emit(event_ctx, ToolEventStage::Begin).await;
-
-        start_streaming_output(&process, context, Arc::clone(&transcript));
-
         let max_tokens = resolve_max_tokens(request.max_output_tokens);
         let yield_time_ms = clamp_yield_time(request.yield_time_ms);
 
         let start = Instant::now();
-        // For the initial exec_command call, we both stream output to events
-        // (via start_streaming_output above) and collect a snapshot here for
-        // the tool response body.
-        let OutputHandles {
-            output_buffer,
-            output_notify,
-            cancellation_token,
-        } = process.output_handles();
-        let deadline = start + Duration::from_millis(yield_time_ms);
-        let collected = Self::collect_output_until_deadline(
-            &output_buffer,
-            &output_notify,
-            &cancellation_token,
-            deadline,
-        )
-        .await;
+        let mut began = false;
+        let (process, transcript, collected) = loop {
+            let process = self
+                .open_session_with_sandbox(&request, cwd.clone(), context)
+                .await;
+
+            let process = match process {
+                Ok(process) => Arc::new(process),
+                Err(err) => {
+                    self.release_process_id(&request.process_id).await;
+                    return Err(err);
+                }
+            };
+
+            if !began {
+                emitter.emit(event_ctx, ToolEventStage::Begin).await;
+                began = true;
+            }
+
+            let transcript = Arc::new(tokio::sync::Mutex::new(HeadTailBuffer::default()));
+            start_streaming_output(&process, context, Arc::clone(&transcript));
+
+            // For the initial exec_command call, we both stream output to events
+            // (via start_streaming_output above) and collect a snapshot here for
+            // the tool response body.
+            let OutputHandles {
+                output_buffer,
+                output_notify,
+                cancellation_token,
+            } = process.output_handles();
+            let deadline = Instant::now() + Duration::from_millis(yield_time_ms);
+            let collected = Self::collect_output_until_deadline(
+                &output_buffer,
+                &output_notify,
+                &cancellation_token,
+                deadline,
+            )
+            .await;
+
+            let has_exited = process.has_exited() || process.exit_code().is_some();
+            let Some(approvals) = network_approvals.filter(|_| has_exited) else {
+                break (process, transcript, collected);
+            };
+            let tool_ctx = ToolCtx {
+                session: context.session.as_ref(),
+                turn: context.turn.as_ref(),
+                call_id: context.call_id.clone(),
+                tool_name: "exec_command".to_string(),
+            };
+            let retry = request_blocked_host_approvals(
+                &tool_ctx,
+                approvals,
+                &request.command,
+                &cwd,
+                &mut asked_hosts,
+            )
+            .await;
+            match retry {
+                Ok(true) => {}
+                Ok(false) => break (process, transcript, collected),
+                Err(err) => {
+                    self.release_process_id(&request.process_id).await;
+                    return Err(UnifiedExecError::create_process(format!("{err:?}")));
+                }
+            }
+        };
         let wall_time = Instant::now().saturating_duration_since(start);
 
-        let text = String::from_utf8_lossy(&collected).to_string();
+        let text = redact_secrets(&String::from_utf8_lossy(&collected)).into_owned();
         let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
         let exit_code = process.exit_code();
         let has_exited = process.has_exited() || exit_code.is_some();
@@ -285,7 +333,7 @@ impl UnifiedExecProcessManager {
         .await;
         let wall_time = Instant::now().saturating_duration_since(start);
 
-        let text = String::from_utf8_lossy(&collected).to_string();
+        let text = redact_secrets(&String::from_utf8_lossy(&collected)).into_owned();
         let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
         let original_token_count = approx_token_count(&text);
         let chunk_id = generate_chunk_id();
@@ -483,10 +531,21 @@ impl UnifiedExecProcessManager {
         cwd: PathBuf,
         context: &UnifiedExecContext,
     ) -> Result<UnifiedExecProcess, UnifiedExecError> {
-        let env = apply_unified_exec_env(create_env(
+        let mut env = create_env(
             &context.turn.shell_environment_policy,
             Some(context.session.conversation_id),
-        ));
+        );
+        context
+            .session
+            .services
+            .secrets
+            .env_for_cwd(&cwd)
+            .await
+            .apply(&mut env);
+        if let Some(network_proxy) = &context.session.services.network_proxy {
+            network_proxy.apply(&mut env, &context.turn.sub_id, &context.call_id);
+        }
+        let env = apply_unified_exec_env(env);
         let features = context.session.features();
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = UnifiedExecRuntime::new(self);
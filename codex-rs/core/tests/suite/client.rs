@@ -1755,7 +1755,7 @@ async fn azure_overrides_assign_properties_used_for_responses_url() {
         wire_api: WireApi::Responses,
         http_headers: Some(std::collections::HashMap::from([(
             "Custom-Header".to_string(),
-            "Value".to_string(),
+            "Value".to_string().into(),
         )])),
         env_http_headers: None,
         request_max_retries: None,
@@ -1839,7 +1839,7 @@ async fn env_var_overrides_loaded_auth() {
         wire_api: WireApi::Responses,
         http_headers: Some(std::collections::HashMap::from([(
             "Custom-Header".to_string(),
-            "Value".to_string(),
+            "Value".to_string().into(),
         )])),
         env_http_headers: None,
         request_max_retries: None,
@@ -2,13 +2,21 @@
 
 use anyhow::Result;
 use codex_core::features::Feature;
+use codex_core::project_knowledge::knowledge_repo_root;
+use codex_core::state_db::KnowledgeStatus;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
 use core_test_support::responses::mount_function_call_agent_response;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
 use core_test_support::responses::start_mock_server;
 use core_test_support::skip_if_no_network;
 use core_test_support::test_codex::test_codex;
 use pretty_assertions::assert_eq;
 use serde_json::Value;
 use serde_json::json;
+use std::sync::Arc;
 use tokio::time::Duration;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -82,3 +90,125 @@ async fn get_memory_tool_returns_persisted_thread_memory() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_memory_tool_searches_memories_by_query() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_config(|config| {
+        config.features.enable(Feature::Sqlite);
+        config.features.enable(Feature::MemoryTool);
+    });
+    let test = builder.build(&server).await?;
+
+    let db = test.codex.state_db().expect("state db enabled");
+    let thread_id = test.session_configured.session_id;
+    for _ in 0..100 {
+        if db.get_thread(thread_id).await?.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+    let memory_summary = "The sqlite migration test is flaky; rerun it once before debugging.";
+    db.upsert_thread_memory(thread_id, "trace summary", memory_summary)
+        .await?;
+
+    let call_id = "memory-search-1";
+    let arguments = json!({ "query": "flaky migration" }).to_string();
+    let mocks =
+        mount_function_call_agent_response(&server, call_id, &arguments, "get_memory").await;
+    test.submit_turn("have we seen this flaky test before?")
+        .await?;
+
+    let (content, _) = mocks
+        .completion
+        .single_request()
+        .function_call_output_content_and_success(call_id)
+        .expect("function_call_output should be present");
+    let payload: Value = serde_json::from_str(&content.expect("content"))?;
+    assert_eq!(payload["query"], "flaky migration");
+    let memories = payload["memories"].as_array().expect("memories array");
+    assert_eq!(memories.len(), 1);
+    assert_eq!(memories[0]["memory_id"], thread_id.to_string());
+    assert_eq!(memories[0]["memory_summary"], memory_summary);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn remembered_facts_are_injected_once_approved() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex().with_config(|config| {
+        config.features.enable(Feature::Sqlite);
+        config.features.enable(Feature::MemoryTool);
+    });
+    let test = builder.build(&server).await?;
+    let db = test.codex.state_db().expect("state db enabled");
+
+    let fact = "tests need `--features sqlite`";
+    let call_id = "remember-call-1";
+    let arguments = json!({ "fact": fact }).to_string();
+    let mocks = mount_function_call_agent_response(&server, call_id, &arguments, "remember").await;
+    test.submit_turn("remember how to run the tests").await?;
+
+    let (content, success) = mocks
+        .completion
+        .single_request()
+        .function_call_output_content_and_success(call_id)
+        .expect("function_call_output should be present");
+    assert!(
+        success.unwrap_or(true),
+        "expected successful remember output"
+    );
+    assert!(
+        content
+            .as_deref()
+            .is_some_and(|content| content.starts_with("Recorded for review.")),
+        "unexpected remember output: {content:?}"
+    );
+
+    let repo_root = knowledge_repo_root(test.cwd_path());
+    let knowledge = db.list_project_knowledge(&repo_root, None).await?;
+    assert_eq!(knowledge.len(), 1);
+    assert_eq!(knowledge[0].content, fact);
+    assert_eq!(knowledge[0].status, KnowledgeStatus::Pending);
+    assert_eq!(
+        knowledge[0].source_thread_id,
+        Some(test.session_configured.session_id)
+    );
+    db.set_project_knowledge_status(knowledge[0].id, KnowledgeStatus::Approved)
+        .await?;
+
+    // A new session in the same repository starts with the approved fact.
+    let cwd = test.cwd_path().to_path_buf();
+    let mut builder = test_codex()
+        .with_home(Arc::clone(&test.home))
+        .with_config(move |config| {
+            config.features.enable(Feature::Sqlite);
+            config.features.enable(Feature::MemoryTool);
+            config.cwd = cwd;
+        });
+    let next = builder.build(&server).await?;
+    let response = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-3"),
+            ev_assistant_message("msg-3", "ok"),
+            ev_completed("resp-3"),
+        ]),
+    )
+    .await;
+    next.submit_turn("hello").await?;
+
+    let request = response.single_request().body_json().to_string();
+    assert!(
+        request.contains("## Project knowledge")
+            && request.contains("tests need `--features sqlite`"),
+        "approved fact should be part of the instructions"
+    );
+
+    Ok(())
+}
@@ -93,6 +93,7 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
+                    secret_refs: Default::default(),
                 },
             );
             config
@@ -234,6 +235,7 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
+                    secret_refs: Default::default(),
                 },
             );
             config
@@ -382,6 +384,7 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
+                    secret_refs: Default::default(),
                 },
             );
             config
@@ -540,6 +543,7 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
+                    secret_refs: Default::default(),
                 },
             );
             config
@@ -730,6 +734,7 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
+                    secret_refs: Default::default(),
                 },
             );
             config
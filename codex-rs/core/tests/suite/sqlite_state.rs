@@ -9,8 +9,11 @@ use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::UserMessageEvent;
+use codex_state::UsageGroupBy;
+use codex_state::UsageQuery;
 use core_test_support::responses;
 use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_completed_with_tokens;
 use core_test_support::responses::ev_function_call;
 use core_test_support::responses::ev_response_created;
 use core_test_support::responses::mount_sse_sequence;
@@ -235,6 +238,52 @@ async fn user_messages_persist_in_state_db() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn finished_turns_record_usage_in_state_db() -> Result<()> {
+    let server = start_mock_server().await;
+    mount_sse_sequence(
+        &server,
+        vec![
+            responses::sse(vec![
+                ev_response_created("resp-1"),
+                ev_completed_with_tokens("resp-1", 100),
+            ]),
+            responses::sse(vec![
+                ev_response_created("resp-2"),
+                ev_completed_with_tokens("resp-2", 250),
+            ]),
+        ],
+    )
+    .await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.features.enable(Feature::Sqlite);
+    });
+    let test = builder.build(&server).await?;
+
+    test.submit_turn("first turn").await?;
+    test.submit_turn("second turn").await?;
+
+    let db = test.codex.state_db().expect("state db enabled");
+    let report = db
+        .usage_report(&UsageQuery {
+            group_by: UsageGroupBy::Thread,
+            since: None,
+            until: None,
+        })
+        .await?;
+    assert_eq!(report.len(), 1);
+    assert_eq!(
+        report[0].group,
+        test.session_configured.session_id.to_string()
+    );
+    assert_eq!(report[0].model, test.session_configured.model);
+    assert_eq!(report[0].turns, 2);
+    assert_eq!(report[0].input_tokens, 350);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn tool_call_logs_include_thread_id() -> Result<()> {
     let server = start_mock_server().await;
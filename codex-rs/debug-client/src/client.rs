@@ -179,6 +179,9 @@ impl AppServerClient {
                 model_providers: None,
                 source_kinds: None,
                 archived: None,
+                tags: None,
+                pinned: None,
+                folder: None,
             },
         };
         self.send(&request)?;
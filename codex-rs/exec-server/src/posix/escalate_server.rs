@@ -96,6 +96,7 @@ impl EscalateServer {
             &sandbox_state.sandbox_cwd,
             &sandbox_state.codex_linux_sandbox_exe,
             sandbox_state.use_linux_sandbox_bwrap,
+            &sandbox_state.network_proxy_ports,
             None,
         )
         .await?;
@@ -14,6 +14,11 @@ pub use cli::Cli;
 pub use cli::Command;
 pub use cli::ReviewArgs;
 use codex_cloud_requirements::cloud_requirements_loader;
+use codex_codexd::event_bridge::EventBridge;
+#[cfg(unix)]
+use codex_codexd::producer::CodexdProducerClient;
+#[cfg(unix)]
+use codex_codexd::producer::RuntimeMetadata;
 use codex_common::oss::ensure_oss_provider_ready;
 use codex_common::oss::get_default_model_for_oss_provider;
 use codex_core::AuthManager;
@@ -257,6 +262,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         tools_web_search_request: None,
         ephemeral: ephemeral.then_some(true),
         additional_writable_roots: add_dir,
+        skip_secret_refs: false,
     };
 
     let config = ConfigBuilder::default()
@@ -437,6 +443,15 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
 
     info!("Codex initialized with event: {session_configured:?}");
 
+    let mut hub_bridge = start_hub_bridge(&config);
+    if let Some(bridge) = hub_bridge.as_mut() {
+        bridge.publish_event(
+            &EventMsg::SessionConfigured(session_configured.clone()),
+            "",
+            Some(primary_thread_id.to_string()),
+        );
+    }
+
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<ThreadEventEnvelope>();
     let attached_threads = Arc::new(Mutex::new(HashSet::from([primary_thread_id])));
     spawn_thread_listener(primary_thread_id, thread.clone(), tx.clone());
@@ -535,6 +550,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if let Some(bridge) = hub_bridge.as_mut() {
+            bridge.publish_event(&event.msg, &event.id, Some(thread_id.to_string()));
+        }
         if thread_id != primary_thread_id && matches!(&event.msg, EventMsg::TurnComplete(_)) {
             continue;
         }
@@ -551,6 +569,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             CodexStatus::Shutdown => continue,
         }
     }
+    if let Some(bridge) = hub_bridge {
+        bridge.shutdown().await;
+    }
     event_processor.print_final_output();
     if error_seen {
         std::process::exit(1);
@@ -559,6 +580,28 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     Ok(())
 }
 
+/// Publishes this run to codexd so hub consumers can follow it. `codex exec`
+/// does not accept routed commands; they are rejected by the producer.
+#[cfg(unix)]
+fn start_hub_bridge(config: &Config) -> Option<EventBridge> {
+    let producer = CodexdProducerClient::spawn(
+        config.codex_home.as_path(),
+        RuntimeMetadata {
+            runtime_id: format!("pid:{}", std::process::id()),
+            pid: Some(std::process::id()),
+            session_source: Some("exec".to_string()),
+            cwd: Some(config.cwd.to_string_lossy().into_owned()),
+            display_name: Some("codex-exec".to_string()),
+        },
+    );
+    Some(EventBridge::new(producer))
+}
+
+#[cfg(not(unix))]
+fn start_hub_bridge(_config: &Config) -> Option<EventBridge> {
+    None
+}
+
 fn spawn_thread_listener(
     thread_id: codex_protocol::ThreadId,
     thread: Arc<codex_core::CodexThread>,
@@ -51,6 +51,7 @@ async fn spawn_command_under_sandbox(
         sandbox_policy,
         sandbox_cwd,
         false,
+        &[],
         stdio_policy,
         env,
     )
@@ -26,11 +26,17 @@ pub(crate) struct BwrapOptions {
     /// This is the secure default, but some restrictive container environments
     /// deny `--proc /proc` even when PID namespaces are available.
     pub mount_proc: bool,
+    /// Whether to give the command its own network namespace, which only has
+    /// a loopback interface.
+    pub unshare_net: bool,
 }
 
 impl Default for BwrapOptions {
     fn default() -> Self {
-        Self { mount_proc: true }
+        Self {
+            mount_proc: true,
+            unshare_net: false,
+        }
     }
 }
 
@@ -65,6 +71,9 @@ fn create_bwrap_flags(
     args.extend(create_filesystem_args(sandbox_policy, cwd)?);
     // Isolate the PID namespace.
     args.push("--unshare-pid".to_string());
+    if options.unshare_net {
+        args.push("--unshare-net".to_string());
+    }
     // Mount a fresh /proc unless the caller explicitly disables it.
     if options.mount_proc {
         args.push("--proc".to_string());
@@ -37,11 +37,17 @@ use seccompiler::apply_filter;
 /// - enabling `PR_SET_NO_NEW_PRIVS` when restrictions apply, and
 /// - installing the network seccomp filter when network access is disabled.
 ///
+/// With `network_proxy_bridged`, the command runs in a network namespace whose
+/// loopback only leads to the session's network proxy (see `proxy_bridge`),
+/// so TCP client sockets are allowed. The bridges must be running before this
+/// is called: Unix sockets are denied from here on.
+///
 /// Filesystem restrictions are intentionally handled by bubblewrap.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
     apply_landlock_fs: bool,
+    network_proxy_bridged: bool,
 ) -> Result<()> {
     // `PR_SET_NO_NEW_PRIVS` is required for seccomp, but it also prevents
     // setuid privilege elevation. Many `bwrap` deployments rely on setuid, so
@@ -54,7 +60,7 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
     }
 
     if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+        install_network_seccomp_filter_on_current_thread(network_proxy_bridged)?;
     }
 
     if apply_landlock_fs && !sandbox_policy.has_full_disk_write_access() {
@@ -121,9 +127,17 @@ fn install_filesystem_landlock_rules_on_current_thread(
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
 ///
+/// With `proxy_only`, the network namespace already limits TCP connections to
+/// the network proxy. The filter then also allows TCP client sockets
+/// (`connect` and friends), and still denies UDP, raw sockets and listening.
+/// A network namespace does not isolate filesystem Unix sockets, so `socket`
+/// is denied for AF_UNIX instead; `socketpair` stays available.
+///
 /// The filter is applied to the current thread so only the sandboxed child
 /// inherits it.
-fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+fn install_network_seccomp_filter_on_current_thread(
+    proxy_only: bool,
+) -> std::result::Result<(), SandboxErr> {
     // Build rule map.
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
@@ -132,22 +146,24 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         rules.insert(nr, vec![]); // empty rule vec = unconditional match
     };
 
-    deny_syscall(libc::SYS_connect);
+    if !proxy_only {
+        deny_syscall(libc::SYS_connect);
+        deny_syscall(libc::SYS_getpeername);
+        deny_syscall(libc::SYS_getsockname);
+        deny_syscall(libc::SYS_shutdown);
+        deny_syscall(libc::SYS_sendto);
+        deny_syscall(libc::SYS_getsockopt);
+        deny_syscall(libc::SYS_setsockopt);
+    }
     deny_syscall(libc::SYS_accept);
     deny_syscall(libc::SYS_accept4);
     deny_syscall(libc::SYS_bind);
     deny_syscall(libc::SYS_listen);
-    deny_syscall(libc::SYS_getpeername);
-    deny_syscall(libc::SYS_getsockname);
-    deny_syscall(libc::SYS_shutdown);
-    deny_syscall(libc::SYS_sendto);
     deny_syscall(libc::SYS_sendmmsg);
     // NOTE: allowing recvfrom allows some tools like: `cargo clippy` to run
     // with their socketpair + child processes for sub-proc management
     // deny_syscall(libc::SYS_recvfrom);
     deny_syscall(libc::SYS_recvmmsg);
-    deny_syscall(libc::SYS_getsockopt);
-    deny_syscall(libc::SYS_setsockopt);
     deny_syscall(libc::SYS_ptrace);
 
     // For `socket` we allow AF_UNIX (arg0 == AF_UNIX) and deny everything else.
@@ -158,7 +174,12 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         libc::AF_UNIX as u64,
     )?])?;
 
-    rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
+    let socket_rules = if proxy_only {
+        proxy_only_socket_rules()?
+    } else {
+        vec![unix_only_rule.clone()]
+    };
+    rules.insert(libc::SYS_socket, socket_rules);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
     let filter = SeccompFilter::new(
@@ -180,3 +201,40 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
 
     Ok(())
 }
+
+/// Deny rules for `socket` that only let TCP sockets through.
+fn proxy_only_socket_rules() -> std::result::Result<Vec<SeccompRule>, SandboxErr> {
+    let domain =
+        |op, family: i32| SeccompCondition::new(0, SeccompCmpArgLen::Dword, op, family as u64);
+    let mut rules = vec![SeccompRule::new(vec![
+        domain(SeccompCmpOp::Ne, libc::AF_INET)?,
+        domain(SeccompCmpOp::Ne, libc::AF_INET6)?,
+    ])?];
+    // The low bits of the type argument hold the socket type; the rest are
+    // flags such as SOCK_CLOEXEC.
+    const SOCK_TYPE_MASK: u64 = 0xf;
+    // Obsolete packet interface; deprecated in `libc` but still accepted by
+    // the kernel for AF_INET.
+    const SOCK_PACKET: i32 = 10;
+    for family in [libc::AF_INET, libc::AF_INET6] {
+        for sock_type in [
+            libc::SOCK_DGRAM,
+            libc::SOCK_RAW,
+            libc::SOCK_RDM,
+            libc::SOCK_SEQPACKET,
+            libc::SOCK_DCCP,
+            SOCK_PACKET,
+        ] {
+            rules.push(SeccompRule::new(vec![
+                domain(SeccompCmpOp::Eq, family)?,
+                SeccompCondition::new(
+                    1, // second argument (type)
+                    SeccompCmpArgLen::Dword,
+                    SeccompCmpOp::MaskedEq(SOCK_TYPE_MASK),
+                    sock_type as u64,
+                )?,
+            ])?);
+        }
+    }
+    Ok(rules)
+}
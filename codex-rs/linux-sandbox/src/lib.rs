@@ -10,6 +10,8 @@ mod landlock;
 #[cfg(target_os = "linux")]
 mod linux_run_main;
 #[cfg(target_os = "linux")]
+mod proxy_bridge;
+#[cfg(target_os = "linux")]
 mod vendored_bwrap;
 
 #[cfg(target_os = "linux")]
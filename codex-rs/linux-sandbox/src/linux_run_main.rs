@@ -9,6 +9,9 @@ use std::path::PathBuf;
 use crate::bwrap::BwrapOptions;
 use crate::bwrap::create_bwrap_command_args;
 use crate::landlock::apply_sandbox_policy_to_current_thread;
+use crate::proxy_bridge::enter_proxy_network_namespace;
+use crate::proxy_bridge::spawn_host_bridge;
+use crate::proxy_bridge::spawn_sandbox_bridge;
 use crate::vendored_bwrap::exec_vendored_bwrap;
 use crate::vendored_bwrap::run_vendored_bwrap_main;
 
@@ -46,6 +49,20 @@ pub struct LandlockCommand {
     #[arg(long = "no-proc", default_value_t = false)]
     pub no_proc: bool,
 
+    /// Loopback port of the network proxy. When the policy disables network
+    /// access, commands run in their own network namespace where these ports
+    /// lead to the proxy and nothing else is reachable. Repeatable.
+    ///
+    /// Without bubblewrap the namespace needs unprivileged user namespaces;
+    /// where those are unavailable the network stays fully blocked.
+    #[arg(long = "network-proxy-port")]
+    pub network_proxy_ports: Vec<u16>,
+
+    /// Internal: directory of the Unix sockets bridging the proxy ports into
+    /// the sandbox, set by the outer stage for the inner one.
+    #[arg(long = "network-proxy-socket-dir", hide = true)]
+    pub network_proxy_socket_dir: Option<PathBuf>,
+
     /// Full command args to run under the Linux sandbox helper.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -65,6 +82,8 @@ pub fn run_main() -> ! {
         use_bwrap_sandbox,
         apply_seccomp_then_exec,
         no_proc,
+        network_proxy_ports,
+        network_proxy_socket_dir,
         command,
     } = LandlockCommand::parse();
 
@@ -75,18 +94,31 @@ pub fn run_main() -> ! {
     // Inner stage: apply seccomp/no_new_privs after bubblewrap has already
     // established the filesystem view.
     if apply_seccomp_then_exec {
-        if let Err(e) =
-            apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd, false)
-        {
+        let network_proxy_bridged = match &network_proxy_socket_dir {
+            Some(socket_dir) if !sandbox_policy.has_full_network_access() => {
+                spawn_sandbox_bridge(&network_proxy_ports, socket_dir);
+                true
+            }
+            _ => false,
+        };
+        if let Err(e) = apply_sandbox_policy_to_current_thread(
+            &sandbox_policy,
+            &sandbox_policy_cwd,
+            false,
+            network_proxy_bridged,
+        ) {
             panic!("error applying Linux sandbox restrictions: {e:?}");
         }
         exec_or_panic(command);
     }
 
     if sandbox_policy.has_full_disk_write_access() {
-        if let Err(e) =
-            apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd, false)
-        {
+        if let Err(e) = apply_sandbox_policy_to_current_thread(
+            &sandbox_policy,
+            &sandbox_policy_cwd,
+            false,
+            false,
+        ) {
             panic!("error applying Linux sandbox restrictions: {e:?}");
         }
         exec_or_panic(command);
@@ -96,19 +128,44 @@ pub fn run_main() -> ! {
         // Outer stage: bubblewrap first, then re-enter this binary in the
         // sandboxed environment to apply seccomp. This path never falls back
         // to legacy Landlock on failure.
+        let network_proxy_socket_dir = (!network_proxy_ports.is_empty()
+            && !sandbox_policy.has_full_network_access())
+        .then(|| spawn_host_bridge(&network_proxy_ports));
         let inner = build_inner_seccomp_command(
             &sandbox_policy_cwd,
             &sandbox_policy,
             use_bwrap_sandbox,
+            &network_proxy_ports,
+            network_proxy_socket_dir.as_deref(),
             command,
         );
-        run_bwrap_with_proc_fallback(&sandbox_policy_cwd, &sandbox_policy, inner, !no_proc);
+        run_bwrap_with_proc_fallback(
+            &sandbox_policy_cwd,
+            &sandbox_policy,
+            inner,
+            !no_proc,
+            network_proxy_socket_dir.is_some(),
+        );
     }
 
     // Legacy path: Landlock enforcement only, when bwrap sandboxing is not enabled.
-    if let Err(e) =
-        apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd, true)
-    {
+    let network_proxy_bridged = !network_proxy_ports.is_empty()
+        && !sandbox_policy.has_full_network_access()
+        && match enter_proxy_network_namespace(&network_proxy_ports) {
+            Ok(()) => true,
+            Err(err) => {
+                eprintln!(
+                    "codex-linux-sandbox: could not bridge the network proxy into the sandbox, network access stays blocked: {err}"
+                );
+                false
+            }
+        };
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        true,
+        network_proxy_bridged,
+    ) {
         panic!("error applying legacy Linux sandbox restrictions: {e:?}");
     }
     exec_or_panic(command);
@@ -119,6 +176,7 @@ fn run_bwrap_with_proc_fallback(
     sandbox_policy: &codex_core::protocol::SandboxPolicy,
     inner: Vec<String>,
     mount_proc: bool,
+    unshare_net: bool,
 ) -> ! {
     let mut mount_proc = mount_proc;
 
@@ -127,7 +185,10 @@ fn run_bwrap_with_proc_fallback(
         mount_proc = false;
     }
 
-    let options = BwrapOptions { mount_proc };
+    let options = BwrapOptions {
+        mount_proc,
+        unshare_net,
+    };
     let argv = build_bwrap_argv(inner, sandbox_policy, sandbox_policy_cwd, options);
     exec_vendored_bwrap(argv);
 }
@@ -164,7 +225,7 @@ fn preflight_proc_mount_support(
         preflight_command,
         sandbox_policy,
         sandbox_policy_cwd,
-        BwrapOptions { mount_proc: true },
+        BwrapOptions::default(),
     );
     let stderr = run_bwrap_in_child_capture_stderr(preflight_argv);
     !is_proc_mount_failure(stderr.as_str())
@@ -268,6 +329,8 @@ fn build_inner_seccomp_command(
     sandbox_policy_cwd: &Path,
     sandbox_policy: &codex_core::protocol::SandboxPolicy,
     use_bwrap_sandbox: bool,
+    network_proxy_ports: &[u16],
+    network_proxy_socket_dir: Option<&Path>,
     command: Vec<String>,
 ) -> Vec<String> {
     let current_exe = match std::env::current_exe() {
@@ -290,6 +353,14 @@ fn build_inner_seccomp_command(
         inner.push("--use-bwrap-sandbox".to_string());
         inner.push("--apply-seccomp-then-exec".to_string());
     }
+    for port in network_proxy_ports {
+        inner.push("--network-proxy-port".to_string());
+        inner.push(port.to_string());
+    }
+    if let Some(socket_dir) = network_proxy_socket_dir {
+        inner.push("--network-proxy-socket-dir".to_string());
+        inner.push(socket_dir.to_string_lossy().to_string());
+    }
     inner.push("--".to_string());
     inner.extend(command);
     inner
@@ -342,7 +413,7 @@ mod tests {
             vec!["/bin/true".to_string()],
             &SandboxPolicy::ReadOnly,
             Path::new("/"),
-            BwrapOptions { mount_proc: true },
+            BwrapOptions::default(),
         );
         assert_eq!(
             argv,
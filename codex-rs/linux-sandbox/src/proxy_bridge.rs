@@ -0,0 +1,459 @@
+//! Bridges the session's network proxy into a sandbox without network access.
+//!
+//! The sandboxed command runs in its own network namespace, which only has a
+//! loopback interface, so the proxy is the only thing it can reach. With
+//! bubblewrap (`bwrap --unshare-net`):
+//! - outside the namespace, [`spawn_host_bridge`] accepts connections on one
+//!   Unix socket per proxy port and forwards them to the proxy on the host
+//!   loopback;
+//! - inside, [`spawn_sandbox_bridge`] listens on the same loopback ports and
+//!   forwards to those sockets, so the proxy URLs handed to commands work
+//!   unchanged.
+//!
+//! The legacy Landlock pipeline has no bubblewrap to create the namespace, so
+//! [`enter_proxy_network_namespace`] unshares one itself and hands listeners
+//! bound inside it to a bridge process that stayed outside.
+use std::ffi::CString;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::Shutdown;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::ffi::OsStringExt;
+use std::os::unix::net::UnixListener;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Path of the Unix socket that leads to the proxy on `port`.
+fn socket_path(socket_dir: &Path, port: u16) -> PathBuf {
+    socket_dir.join(format!("{port}.sock"))
+}
+
+/// Start the bridge from per-port Unix sockets to the proxy on the host
+/// loopback and return the directory holding the sockets.
+///
+/// The bridge runs in a forked child (see [`run_bridge_until_parent_exits`]).
+/// It gets `SIGTERM` when the caller exits (after it has `exec`ed into
+/// bubblewrap, that is when the sandbox is gone) and then removes the socket
+/// directory.
+pub(crate) fn spawn_host_bridge(ports: &[u16]) -> PathBuf {
+    let socket_dir = create_socket_dir()
+        .unwrap_or_else(|err| panic!("failed to create network proxy socket directory: {err}"));
+    let listeners: Vec<(u16, UnixListener)> = ports
+        .iter()
+        .map(|port| {
+            let path = socket_path(&socket_dir, *port);
+            let listener = UnixListener::bind(&path).unwrap_or_else(|err| {
+                panic!(
+                    "failed to bind network proxy socket {}: {err}",
+                    path.display()
+                )
+            });
+            (*port, listener)
+        })
+        .collect();
+
+    let parent_pid = unsafe { libc::getpid() };
+    let pid = unsafe { libc::fork() };
+    if pid < 0 {
+        let err = io::Error::last_os_error();
+        panic!("failed to fork network proxy bridge: {err}");
+    }
+    if pid > 0 {
+        return socket_dir;
+    }
+
+    run_bridge_until_parent_exits(parent_pid, || {
+        for (port, listener) in listeners {
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    relay_to_proxy(stream, port);
+                }
+            });
+        }
+        true
+    });
+    let _ = std::fs::remove_dir_all(&socket_dir);
+    unsafe { libc::_exit(0) }
+}
+
+/// Move the current process into a new user and network namespace whose
+/// loopback proxy ports lead to the proxy on the host loopback.
+///
+/// This is the legacy pipeline's counterpart of the two bubblewrap bridges: a
+/// bridge child forked first stays in the host namespace, and the proxy ports
+/// are bound inside the new namespace and handed to it over a socket pair. It
+/// accepts on them and connects to the proxy, and like [`spawn_host_bridge`]
+/// gets `SIGTERM` when the command exits.
+///
+/// Must run while the process is single threaded. Fails when unprivileged
+/// user namespaces are unavailable; the bridge then exits on its own.
+pub(crate) fn enter_proxy_network_namespace(ports: &[u16]) -> io::Result<()> {
+    let (parent_end, bridge_end) = UnixStream::pair()?;
+    let parent_pid = unsafe { libc::getpid() };
+    let pid = unsafe { libc::fork() };
+    if pid < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if pid == 0 {
+        drop(parent_end);
+        run_bridge_until_parent_exits(parent_pid, || {
+            let Ok(fds) = receive_fds(&bridge_end, ports.len()) else {
+                return false;
+            };
+            for (port, fd) in ports.iter().copied().zip(fds) {
+                let listener = TcpListener::from(fd);
+                std::thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        relay_to_proxy(stream, port);
+                    }
+                });
+            }
+            true
+        });
+        unsafe { libc::_exit(0) }
+    }
+    drop(bridge_end);
+
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    std::fs::write("/proc/self/setgroups", "deny")?;
+    std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+    bring_up_loopback()?;
+
+    let listeners = ports
+        .iter()
+        .map(|port| TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], *port))))
+        .collect::<io::Result<Vec<_>>>()?;
+    let fds: Vec<RawFd> = listeners.iter().map(AsRawFd::as_raw_fd).collect();
+    send_fds(&parent_end, &fds)
+}
+
+/// Relay `stream` to the proxy on `port` of the host loopback, from a
+/// background thread.
+fn relay_to_proxy<S: Duplex>(stream: S, port: u16) {
+    std::thread::spawn(move || {
+        if let Ok(proxy) = TcpStream::connect(SocketAddr::from(([127, 0, 0, 1], port))) {
+            relay(stream, proxy);
+        }
+    });
+}
+
+/// Turn a freshly forked child into a bridge process and run `serve` in it
+/// until the parent exits.
+///
+/// The child leaves the caller's process group, so a group kill on timeout
+/// does not skip cleanup, and detaches its stdio. `serve` starts the bridge
+/// threads and returns whether there is anything to wait for.
+fn run_bridge_until_parent_exits(parent_pid: libc::pid_t, serve: impl FnOnce() -> bool) {
+    // Block SIGTERM before starting threads so only `sigwait` sees it.
+    let mut term = unsafe { std::mem::zeroed::<libc::sigset_t>() };
+    unsafe {
+        libc::sigemptyset(&mut term);
+        libc::sigaddset(&mut term, libc::SIGTERM);
+        libc::pthread_sigmask(libc::SIG_BLOCK, &term, std::ptr::null_mut());
+        libc::setpgid(0, 0);
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM);
+    }
+    detach_stdio();
+    // The parent may have exited before `PR_SET_PDEATHSIG` took effect.
+    if unsafe { libc::getppid() } == parent_pid && serve() {
+        let mut signal = 0;
+        unsafe { libc::sigwait(&term, &mut signal) };
+    }
+}
+
+/// Set the `IFF_UP` flag of the loopback interface of the current network
+/// namespace, which starts out down.
+fn bring_up_loopback() -> io::Result<()> {
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `socket` was just created and is owned by nothing else.
+    let socket = unsafe { OwnedFd::from_raw_fd(socket) };
+    let mut request = unsafe { std::mem::zeroed::<libc::ifreq>() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCGIFFLAGS as _, &mut request) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe { request.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short };
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS as _, &request) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Send `fds` over `stream` as a single `SCM_RIGHTS` message.
+fn send_fds(stream: &UnixStream, fds: &[RawFd]) -> io::Result<()> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let data_len = std::mem::size_of_val(fds) as libc::c_uint;
+    let space = unsafe { libc::CMSG_SPACE(data_len) } as usize;
+    // `u64` keeps the buffer aligned for `cmsghdr`.
+    let mut control = vec![0u64; space.div_ceil(std::mem::size_of::<u64>())];
+    let mut msg = unsafe { std::mem::zeroed::<libc::msghdr>() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = space as _;
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(data_len) as _;
+        std::ptr::copy_nonoverlapping(
+            fds.as_ptr(),
+            libc::CMSG_DATA(cmsg).cast::<RawFd>(),
+            fds.len(),
+        );
+    }
+    if unsafe { libc::sendmsg(stream.as_raw_fd(), &msg, 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Receive the `count` descriptors sent by [`send_fds`].
+fn receive_fds(stream: &UnixStream, count: usize) -> io::Result<Vec<OwnedFd>> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let data_len = (count * std::mem::size_of::<RawFd>()) as libc::c_uint;
+    let space = unsafe { libc::CMSG_SPACE(data_len) } as usize;
+    let mut control = vec![0u64; space.div_ceil(std::mem::size_of::<u64>())];
+    let mut msg = unsafe { std::mem::zeroed::<libc::msghdr>() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = space as _;
+    let received = unsafe { libc::recvmsg(stream.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) };
+    if received < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if received == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let mut fds = Vec::with_capacity(count);
+    let cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+    if !cmsg.is_null()
+        && unsafe { (*cmsg).cmsg_level } == libc::SOL_SOCKET
+        && unsafe { (*cmsg).cmsg_type } == libc::SCM_RIGHTS
+    {
+        let header_len = unsafe { libc::CMSG_LEN(0) } as usize;
+        let received_len = (unsafe { (*cmsg).cmsg_len } as usize).saturating_sub(header_len);
+        let data = unsafe { libc::CMSG_DATA(cmsg) }.cast::<RawFd>();
+        for index in 0..received_len / std::mem::size_of::<RawFd>() {
+            // SAFETY: the kernel installed these descriptors for this process.
+            let fd = unsafe { std::ptr::read_unaligned(data.add(index)) };
+            fds.push(unsafe { OwnedFd::from_raw_fd(fd) });
+        }
+    }
+    if fds.len() != count {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("expected {count} proxy listeners, got {}", fds.len()),
+        ));
+    }
+    Ok(fds)
+}
+
+/// Listen on the proxy ports of the sandbox's loopback and forward each
+/// connection to the matching socket in `socket_dir`.
+///
+/// Must run inside the sandbox's network namespace, before seccomp denies
+/// `bind` and `listen`. The bridge runs in a detached grandchild, which the
+/// pid namespace of the sandbox tears down together with the command.
+pub(crate) fn spawn_sandbox_bridge(ports: &[u16], socket_dir: &Path) {
+    let listeners: Vec<(PathBuf, TcpListener)> = ports
+        .iter()
+        .map(|port| {
+            let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], *port)))
+                .unwrap_or_else(|err| panic!("failed to listen on proxy port {port}: {err}"));
+            (socket_path(socket_dir, *port), listener)
+        })
+        .collect();
+
+    let pid = unsafe { libc::fork() };
+    if pid < 0 {
+        let err = io::Error::last_os_error();
+        panic!("failed to fork network proxy bridge: {err}");
+    }
+    if pid > 0 {
+        // Reap the intermediate child so the command does not inherit it.
+        let mut status: libc::c_int = 0;
+        unsafe { libc::waitpid(pid, &mut status, 0) };
+        return;
+    }
+
+    // Intermediate child: fork the bridge and exit so it is reparented to
+    // the sandbox's init process instead of becoming a child of the command.
+    if unsafe { libc::fork() } != 0 {
+        unsafe { libc::_exit(0) }
+    }
+    detach_stdio();
+    let mut handles = Vec::new();
+    for (path, listener) in listeners {
+        handles.push(std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    if let Ok(proxy) = UnixStream::connect(&path) {
+                        relay(stream, proxy);
+                    }
+                });
+            }
+        }));
+    }
+    for handle in handles {
+        let _ = handle.join();
+    }
+    unsafe { libc::_exit(0) }
+}
+
+/// Point stdio of a bridge process at `/dev/null`, so it does not keep the
+/// command's output pipes open.
+fn detach_stdio() {
+    let Ok(dev_null) = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/null")
+    else {
+        return;
+    };
+    for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+        unsafe { libc::dup2(dev_null.as_raw_fd(), fd) };
+    }
+}
+
+/// Create a private directory for the bridge sockets under the temp dir.
+fn create_socket_dir() -> io::Result<PathBuf> {
+    let template = std::env::temp_dir().join("codex-proxy-XXXXXX");
+    let template = CString::new(template.as_os_str().as_bytes())?;
+    let mut template = template.into_bytes_with_nul();
+    let dir = unsafe { libc::mkdtemp(template.as_mut_ptr().cast()) };
+    if dir.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    template.pop();
+    Ok(PathBuf::from(std::ffi::OsString::from_vec(template)))
+}
+
+/// A connected stream that can be split into a reader and a writer.
+trait Duplex: Read + Write + Send + Sized + 'static {
+    fn try_clone(&self) -> io::Result<Self>;
+    fn shutdown_write(&self);
+}
+
+impl Duplex for TcpStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        TcpStream::try_clone(self)
+    }
+
+    fn shutdown_write(&self) {
+        let _ = self.shutdown(Shutdown::Write);
+    }
+}
+
+impl Duplex for UnixStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        UnixStream::try_clone(self)
+    }
+
+    fn shutdown_write(&self) {
+        let _ = self.shutdown(Shutdown::Write);
+    }
+}
+
+/// Copy bytes both ways until each side has closed its write half.
+fn relay<A: Duplex, B: Duplex>(a: A, b: B) {
+    let (Ok(mut a_read), Ok(mut b_write)) = (a.try_clone(), b.try_clone()) else {
+        return;
+    };
+    let upload = std::thread::spawn(move || {
+        let _ = io::copy(&mut a_read, &mut b_write);
+        b_write.shutdown_write();
+    });
+    let (mut b_read, mut a_write) = (b, a);
+    let _ = io::copy(&mut b_read, &mut a_write);
+    a_write.shutdown_write();
+    let _ = upload.join();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn relay_copies_both_directions() {
+        let upstream = TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        let echo = std::thread::spawn(move || {
+            let (mut conn, _) = upstream.accept().unwrap();
+            let mut request = String::new();
+            conn.read_to_string(&mut request).unwrap();
+            conn.write_all(format!("echo: {request}").as_bytes())
+                .unwrap();
+        });
+
+        let (client, bridge_end) = UnixStream::pair().unwrap();
+        let bridge = std::thread::spawn(move || {
+            relay(bridge_end, TcpStream::connect(upstream_addr).unwrap());
+        });
+
+        let mut client = client;
+        client.write_all(b"hello").unwrap();
+        client.shutdown(Shutdown::Write).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert_eq!(response, "echo: hello");
+        echo.join().unwrap();
+        bridge.join().unwrap();
+    }
+
+    #[test]
+    fn passes_listeners_over_a_socket_pair() {
+        let (sender, receiver) = UnixStream::pair().unwrap();
+        let listeners = [
+            TcpListener::bind("127.0.0.1:0").unwrap(),
+            TcpListener::bind("127.0.0.1:0").unwrap(),
+        ];
+        let fds: Vec<RawFd> = listeners.iter().map(AsRawFd::as_raw_fd).collect();
+        send_fds(&sender, &fds).unwrap();
+
+        let received: Vec<TcpListener> = receive_fds(&receiver, listeners.len())
+            .unwrap()
+            .into_iter()
+            .map(TcpListener::from)
+            .collect();
+        assert_eq!(
+            received
+                .iter()
+                .map(|listener| listener.local_addr().unwrap())
+                .collect::<Vec<_>>(),
+            listeners
+                .iter()
+                .map(|listener| listener.local_addr().unwrap())
+                .collect::<Vec<_>>()
+        );
+    }
+}
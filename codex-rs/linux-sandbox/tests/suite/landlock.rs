@@ -102,6 +102,7 @@ async fn run_cmd_result_with_writable_roots(
         sandbox_cwd.as_path(),
         &codex_linux_sandbox_exe,
         use_bwrap_sandbox,
+        &[],
         None,
     )
     .await
@@ -246,6 +247,7 @@ async fn assert_network_blocked(cmd: &[&str]) {
         sandbox_cwd.as_path(),
         &codex_linux_sandbox_exe,
         false,
+        &[],
         None,
     )
     .await;
@@ -407,3 +409,129 @@ async fn sandbox_blocks_dev_tcp_redirection() {
     // all images ship bash, so we guard against 127 as well.
     assert_network_blocked(&["bash", "-c", "echo hi > /dev/tcp/127.0.0.1/80"]).await;
 }
+
+/// Run `bash -c script` in the sandbox with network disabled but `proxy_port`
+/// bridged in. Returns `None` when the pipeline cannot bridge the proxy here:
+/// bwrap is unavailable, or, without bwrap, unprivileged user namespaces are.
+#[expect(clippy::expect_used)]
+async fn run_with_network_proxy_port(
+    script: &str,
+    proxy_port: u16,
+    use_bwrap_sandbox: bool,
+) -> Option<std::process::Output> {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let policy_json = serde_json::to_string(&SandboxPolicy::new_read_only_policy())
+        .expect("serialize sandbox policy");
+    let mut command = tokio::process::Command::new(env!("CARGO_BIN_EXE_codex-linux-sandbox"));
+    command
+        .arg("--sandbox-policy-cwd")
+        .arg(&cwd)
+        .arg("--sandbox-policy")
+        .arg(policy_json);
+    if use_bwrap_sandbox {
+        command.arg("--use-bwrap-sandbox");
+    }
+    let output = command
+        .arg("--network-proxy-port")
+        .arg(proxy_port.to_string())
+        .arg("--")
+        .args(["bash", "-c", script])
+        .current_dir(&cwd)
+        .output()
+        .await
+        .expect("spawn codex-linux-sandbox");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    (!stderr.contains(BWRAP_UNAVAILABLE_ERR)
+        && !stderr.contains("could not bridge the network proxy"))
+    .then_some(output)
+}
+
+/// Check that a sandboxed command reaches the proxy port and nothing else.
+async fn assert_only_reaches_the_network_proxy(use_bwrap_sandbox: bool) {
+    let proxy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let other = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let proxy_port = proxy.local_addr().unwrap().port();
+    let other_port = other.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        use std::io::Write;
+        for mut conn in proxy.incoming().flatten() {
+            let _ = conn.write_all(b"pong");
+        }
+    });
+
+    // The proxy port leads to the proxy on the host loopback.
+    let Some(output) = run_with_network_proxy_port(
+        &format!("exec 3<>/dev/tcp/127.0.0.1/{proxy_port}; cat <&3"),
+        proxy_port,
+        use_bwrap_sandbox,
+    )
+    .await
+    else {
+        eprintln!("skipping network proxy test: the proxy cannot be bridged in this environment");
+        return;
+    };
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "pong");
+
+    // Other host loopback ports are in another network namespace.
+    let output = run_with_network_proxy_port(
+        &format!("echo hi > /dev/tcp/127.0.0.1/{other_port}"),
+        proxy_port,
+        use_bwrap_sandbox,
+    )
+    .await
+    .unwrap();
+    assert!(!output.status.success());
+
+    // UDP stays blocked.
+    let output = run_with_network_proxy_port(
+        &format!("echo hi > /dev/udp/127.0.0.1/{proxy_port}"),
+        proxy_port,
+        use_bwrap_sandbox,
+    )
+    .await
+    .unwrap();
+    assert!(!output.status.success());
+}
+
+/// Check that a sandboxed command cannot reach a Unix socket on the host while
+/// the network proxy is bridged in.
+async fn assert_host_unix_sockets_are_unreachable(use_bwrap_sandbox: bool) {
+    let socket_dir = tempfile::tempdir().unwrap();
+    let socket_path = socket_dir.path().join("host.sock");
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let proxy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let proxy_port = proxy.local_addr().unwrap().port();
+
+    let script = format!(
+        "python3 -c 'import socket; socket.socket(socket.AF_UNIX).connect(\"{}\")' && echo connected",
+        socket_path.display()
+    );
+    let Some(output) = run_with_network_proxy_port(&script, proxy_port, use_bwrap_sandbox).await
+    else {
+        eprintln!("skipping network proxy test: the proxy cannot be bridged in this environment");
+        return;
+    };
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    assert!(listener.accept().is_err());
+}
+
+#[tokio::test]
+async fn sandbox_cannot_reach_host_unix_sockets_through_the_network_proxy() {
+    assert_host_unix_sockets_are_unreachable(true).await;
+}
+
+#[tokio::test]
+async fn legacy_sandbox_cannot_reach_host_unix_sockets_through_the_network_proxy() {
+    assert_host_unix_sockets_are_unreachable(false).await;
+}
+
+#[tokio::test]
+async fn sandbox_only_reaches_the_network_proxy() {
+    assert_only_reaches_the_network_proxy(true).await;
+}
+
+#[tokio::test]
+async fn legacy_sandbox_only_reaches_the_network_proxy() {
+    assert_only_reaches_the_network_proxy(false).await;
+}
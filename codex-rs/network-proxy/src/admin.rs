@@ -2,7 +2,6 @@ use crate::config::NetworkMode;
 use crate::responses::json_response;
 use crate::responses::text_response;
 use crate::state::NetworkProxyState;
-use anyhow::Context;
 use anyhow::Result;
 use rama_core::rt::Executor;
 use rama_core::service::service_fn;
@@ -20,16 +19,13 @@ use std::sync::Arc;
 use tracing::error;
 use tracing::info;
 
-pub async fn run_admin_api(state: Arc<NetworkProxyState>, addr: SocketAddr) -> Result<()> {
+pub async fn run_admin_api(
+    state: Arc<NetworkProxyState>,
+    listener: TcpListener,
+    addr: SocketAddr,
+) -> Result<()> {
     // Debug-only admin API (health/config/patterns/blocked + mode/reload). Policy is config-driven
     // and constraint-enforced; this endpoint should not become a second policy/approval plane.
-    let listener = TcpListener::build()
-        .bind(addr)
-        .await
-        // See `http_proxy.rs` for details on why we wrap `BoxError` before converting to anyhow.
-        .map_err(rama_core::error::OpaqueError::from)
-        .map_err(anyhow::Error::from)
-        .with_context(|| format!("bind admin API: {addr}"))?;
 
     let server_state = state.clone();
     let server = HttpServer::auto(Executor::new()).service(service_fn(move |req| {
@@ -0,0 +1,44 @@
+use crate::network_policy::NetworkDecision;
+use std::sync::Arc;
+
+/// A request the proxy decided on, reported to the [`NetworkAuditSink`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkAuditEvent {
+    /// Unix timestamp (seconds) of the decision.
+    pub timestamp: i64,
+    /// `http`, `https` (intercepted), `http-connect`, `socks5`, `socks5-udp` or `unix-socket`.
+    pub protocol: String,
+    /// Host, or socket path for `unix-socket`.
+    pub host: String,
+    pub method: Option<String>,
+    /// See [`crate::NetworkPolicyRequest::client_token`].
+    pub client_token: Option<String>,
+    pub decision: NetworkDecision,
+    /// Bytes sent upstream, when the proxy can tell.
+    pub bytes_sent: Option<u64>,
+    /// Bytes received from upstream, when the proxy can tell.
+    pub bytes_received: Option<u64>,
+}
+
+/// Receives every request the proxy allows or blocks.
+///
+/// `record` is called on the request path, so implementations should hand slow work (such as
+/// database writes) off to a task.
+pub trait NetworkAuditSink: Send + Sync + 'static {
+    fn record(&self, event: NetworkAuditEvent);
+}
+
+impl<S: NetworkAuditSink + ?Sized> NetworkAuditSink for Arc<S> {
+    fn record(&self, event: NetworkAuditEvent) {
+        (**self).record(event);
+    }
+}
+
+impl<F> NetworkAuditSink for F
+where
+    F: Fn(NetworkAuditEvent) + Send + Sync + 'static,
+{
+    fn record(&self, event: NetworkAuditEvent) {
+        (self)(event);
+    }
+}
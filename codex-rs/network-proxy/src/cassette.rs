@@ -0,0 +1,236 @@
+use crate::config::NetworkCassette;
+use crate::config::NetworkCassetteMode;
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::bail;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+/// A directory of recorded responses, each stored under a hash of the request it answers, as
+/// `<dir>/<first two hex digits>/<hash>.json`.
+#[derive(Debug)]
+pub(crate) struct Cassette {
+    dir: PathBuf,
+    mode: NetworkCassetteMode,
+    /// Distinguishes the temporary files of concurrent writes.
+    next_tmp: AtomicU64,
+}
+
+/// The parts of a request a recording is keyed on. Headers are left out so tokens and user
+/// agents that change between runs don't turn every request into a miss.
+pub(crate) struct CassetteRequest<'a> {
+    pub(crate) method: &'a str,
+    /// Absolute URI, e.g. `https://pypi.org:443/simple/requests/`.
+    pub(crate) uri: &'a str,
+    pub(crate) body: &'a [u8],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CassetteResponse {
+    pub(crate) status: u16,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+
+/// On-disk form of a recording. The method and URI are kept so the files can be inspected.
+#[derive(Serialize, Deserialize)]
+struct CassetteEntry {
+    method: String,
+    uri: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// Base64 of the response body.
+    body: String,
+}
+
+impl Cassette {
+    /// Recording creates the directory; replaying requires it to exist.
+    pub(crate) fn open(config: &NetworkCassette) -> Result<Self> {
+        let dir = &config.dir;
+        if !dir.is_absolute() {
+            bail!(
+                "network_proxy.cassette.dir must be an absolute path: {}",
+                dir.display()
+            );
+        }
+        match config.mode {
+            NetworkCassetteMode::Record => std::fs::create_dir_all(dir)
+                .with_context(|| format!("create cassette directory {}", dir.display()))?,
+            NetworkCassetteMode::Replay => {
+                if !dir.is_dir() {
+                    bail!("cassette directory {} does not exist", dir.display());
+                }
+            }
+        }
+        Ok(Self {
+            dir: dir.clone(),
+            mode: config.mode,
+            next_tmp: AtomicU64::new(0),
+        })
+    }
+
+    pub(crate) fn replaying(&self) -> bool {
+        self.mode == NetworkCassetteMode::Replay
+    }
+
+    pub(crate) fn key(request: &CassetteRequest<'_>) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(request.method.to_ascii_uppercase().as_bytes());
+        hasher.update([0]);
+        hasher.update(request.uri.as_bytes());
+        hasher.update([0]);
+        hasher.update(request.body);
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub(crate) async fn load(
+        &self,
+        request: &CassetteRequest<'_>,
+    ) -> Result<Option<CassetteResponse>> {
+        let path = self.entry_path(&Self::key(request));
+        let contents = match tokio::fs::read(&path).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("read {}", path.display()));
+            }
+        };
+        let entry: CassetteEntry = serde_json::from_slice(&contents)
+            .with_context(|| format!("parse {}", path.display()))?;
+        let body = BASE64_STANDARD
+            .decode(entry.body)
+            .with_context(|| format!("decode body of {}", path.display()))?;
+        Ok(Some(CassetteResponse {
+            status: entry.status,
+            headers: entry.headers,
+            body,
+        }))
+    }
+
+    /// Save `response` as the answer to `request`, replacing an earlier recording of it.
+    pub(crate) async fn store(
+        &self,
+        request: &CassetteRequest<'_>,
+        response: &CassetteResponse,
+    ) -> Result<()> {
+        let key = Self::key(request);
+        let path = self.entry_path(&key);
+        let entry = CassetteEntry {
+            method: request.method.to_string(),
+            uri: request.uri.to_string(),
+            status: response.status,
+            headers: response.headers.clone(),
+            body: BASE64_STANDARD.encode(&response.body),
+        };
+        let contents = serde_json::to_vec_pretty(&entry)?;
+        let Some(parent) = path.parent() else {
+            bail!("invalid cassette path {}", path.display());
+        };
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("create {}", parent.display()))?;
+        // Write to a temporary file first so a concurrent replay never reads half an entry.
+        let tmp = parent.join(format!(
+            "{key}.{}.{}.tmp",
+            std::process::id(),
+            self.next_tmp.fetch_add(1, Ordering::Relaxed)
+        ));
+        tokio::fs::write(&tmp, contents)
+            .await
+            .with_context(|| format!("write {}", tmp.display()))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .with_context(|| format!("write {}", path.display()))
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let prefix = key.get(..2).unwrap_or(key);
+        self.dir.join(prefix).join(format!("{key}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn request<'a>(method: &'a str, uri: &'a str, body: &'a [u8]) -> CassetteRequest<'a> {
+        CassetteRequest { method, uri, body }
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_replay_by_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = |mode| NetworkCassette {
+            mode,
+            dir: dir.path().join("cassette"),
+        };
+        let recorder = Cassette::open(&config(NetworkCassetteMode::Record)).unwrap();
+        let simple = request("GET", "https://pypi.org:443/simple/requests/", b"");
+        let response = CassetteResponse {
+            status: 200,
+            headers: vec![("content-type".to_string(), "text/html".to_string())],
+            body: b"<html>requests</html>".to_vec(),
+        };
+        recorder.store(&simple, &response).await.unwrap();
+
+        let key = Cassette::key(&simple);
+        let entry = dir
+            .path()
+            .join("cassette")
+            .join(&key[..2])
+            .join(format!("{key}.json"));
+        assert!(entry.is_file());
+
+        let replayer = Cassette::open(&config(NetworkCassetteMode::Replay)).unwrap();
+        assert!(replayer.replaying());
+        assert_eq!(replayer.load(&simple).await.unwrap(), Some(response));
+        assert_eq!(
+            replayer
+                .load(&request(
+                    "POST",
+                    "https://pypi.org:443/simple/requests/",
+                    b""
+                ))
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            replayer
+                .load(&request(
+                    "GET",
+                    "https://pypi.org:443/simple/requests/",
+                    b"x"
+                ))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn open_rejects_relative_and_missing_replay_dirs() {
+        let relative = NetworkCassette {
+            mode: NetworkCassetteMode::Record,
+            dir: PathBuf::from("cassette"),
+        };
+        assert!(Cassette::open(&relative).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let missing = NetworkCassette {
+            mode: NetworkCassetteMode::Replay,
+            dir: dir.path().join("missing"),
+        };
+        assert!(Cassette::open(&missing).is_err());
+    }
+}
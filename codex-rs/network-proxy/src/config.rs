@@ -1,10 +1,12 @@
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::bail;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use std::net::IpAddr;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use tracing::warn;
 use url::Url;
 
@@ -14,28 +16,46 @@ pub struct NetworkProxyConfig {
     pub network_proxy: NetworkProxySettings,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The `[network_proxy]` section of `config.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct NetworkProxySettings {
+    /// Start the proxy and route sandboxed commands through it.
     #[serde(default)]
     pub enabled: bool,
+    /// Listen address of the HTTP proxy when run standalone.
     #[serde(default = "default_proxy_url")]
     pub proxy_url: String,
+    /// Listen address of the admin API when run standalone.
     #[serde(default = "default_admin_url")]
     pub admin_url: String,
+    /// Also run a SOCKS5 proxy.
     #[serde(default)]
     pub enable_socks5: bool,
+    /// Listen address of the SOCKS5 proxy when run standalone.
     #[serde(default = "default_socks_url")]
     pub socks_url: String,
+    /// Allow UDP associate requests on the SOCKS5 proxy.
     #[serde(default)]
     pub enable_socks5_udp: bool,
+    /// Forward requests through the proxy from the environment (`HTTP_PROXY`, etc.).
     #[serde(default)]
     pub allow_upstream_proxy: bool,
     #[serde(default)]
     pub dangerously_allow_non_loopback_proxy: bool,
     #[serde(default)]
     pub dangerously_allow_non_loopback_admin: bool,
+    /// `full` allows every method; `limited` only allows GET/HEAD/OPTIONS.
     #[serde(default)]
     pub mode: NetworkMode,
+    /// Decrypt HTTPS tunnels with a local CA so `limited` mode can check the methods of HTTPS
+    /// requests. Clients have to trust the CA. Read when the proxy starts.
+    #[serde(default)]
+    pub mitm: bool,
+    /// Record responses into a cassette directory, or answer requests only from one. Read when
+    /// the proxy starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cassette: Option<NetworkCassette>,
     #[serde(default)]
     pub policy: NetworkPolicy,
 }
@@ -53,28 +73,89 @@ impl Default for NetworkProxySettings {
             dangerously_allow_non_loopback_proxy: false,
             dangerously_allow_non_loopback_admin: false,
             mode: NetworkMode::default(),
+            mitm: false,
+            cassette: None,
             policy: NetworkPolicy::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// The `[network_proxy.cassette]` section.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct NetworkCassette {
+    pub mode: NetworkCassetteMode,
+    /// Absolute path of the directory holding the recorded responses.
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkCassetteMode {
+    /// Forward allowed requests and save each response.
+    Record,
+    /// Answer allowed requests from the saved responses and never contact upstream. Requests
+    /// without a saved response are blocked.
+    Replay,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct NetworkPolicy {
+    /// Domains requests may go to. Supports `*.example.com` and `**.example.com`.
     #[serde(default)]
     pub allowed_domains: Vec<String>,
+    /// Domains that are always blocked, even when allowed.
     #[serde(default)]
     pub denied_domains: Vec<String>,
+    /// Unix sockets reachable through the `x-unix-socket` header (macOS only).
     #[serde(default)]
     pub allow_unix_sockets: Vec<String>,
+    /// Allow requests to loopback and private addresses.
     #[serde(default)]
     pub allow_local_binding: bool,
+    /// Per-request rules on host, path and method. The first matching rule decides; requests no
+    /// rule matches fall back to the domain lists and `mode`.
+    #[serde(default)]
+    pub rules: Vec<NetworkRule>,
+}
+
+/// A `[[network_proxy.policy.rules]]` entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct NetworkRule {
+    /// Hosts the rule applies to, in the same syntax as `allowed_domains`.
+    pub host: String,
+    /// Path glob such as `/v1/*`, where `*` also matches `/`. Matches every path when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// HTTP methods the rule applies to. Matches every method when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+    pub decision: NetworkRuleDecision,
+    /// Reported in the `x-proxy-error` header of requests the rule blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Requests the rule must match, as `[METHOD ]host[/path]`. Checked when the config loads.
+    #[serde(default, rename = "match", skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<String>,
+    /// Requests the rule must not match. Checked when the config loads.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_match: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkRuleDecision {
+    Allow,
+    Deny,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum NetworkMode {
     /// Limited (read-only) access: only GET/HEAD/OPTIONS are allowed for HTTP. HTTPS CONNECT is
-    /// blocked unless MITM is enabled so the proxy can enforce method policy on inner requests.
+    /// blocked unless `mitm` is enabled so the proxy can enforce method policy on inner requests.
     Limited,
     /// Full network access: all HTTP methods are allowed, and HTTPS CONNECTs are tunneled without
     /// MITM interception.
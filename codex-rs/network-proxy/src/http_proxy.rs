@@ -1,4 +1,6 @@
+use crate::cassette::Cassette;
 use crate::config::NetworkMode;
+use crate::mitm::MitmState;
 use crate::network_policy::NetworkDecision;
 use crate::network_policy::NetworkPolicyDecider;
 use crate::network_policy::NetworkPolicyRequest;
@@ -6,19 +8,26 @@ use crate::network_policy::NetworkPolicyRequestArgs;
 use crate::network_policy::NetworkProtocol;
 use crate::network_policy::evaluate_host_policy;
 use crate::policy::normalize_host;
+use crate::reasons::REASON_HOST_MISMATCH;
 use crate::reasons::REASON_METHOD_NOT_ALLOWED;
 use crate::reasons::REASON_NOT_ALLOWED;
 use crate::reasons::REASON_PROXY_DISABLED;
+use crate::reasons::REASON_REPLAY_MISS;
 use crate::responses::blocked_header_value;
 use crate::responses::json_response;
+use crate::rules::RuleTarget;
+use crate::runtime::AllowedRequestArgs;
 use crate::runtime::unix_socket_permissions_supported;
 use crate::state::BlockedRequest;
 use crate::state::BlockedRequestArgs;
 use crate::state::NetworkProxyState;
 use crate::upstream::UpstreamClient;
 use crate::upstream::proxy_for_connect;
+use crate::upstream::replay;
 use anyhow::Context as _;
 use anyhow::Result;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use rama_core::Layer;
 use rama_core::Service;
 use rama_core::error::BoxError;
@@ -30,10 +39,12 @@ use rama_core::layer::AddInputExtensionLayer;
 use rama_core::rt::Executor;
 use rama_core::service::service_fn;
 use rama_http::Body;
+use rama_http::HeaderMap;
 use rama_http::HeaderValue;
 use rama_http::Request;
 use rama_http::Response;
 use rama_http::StatusCode;
+use rama_http::header::CONTENT_LENGTH;
 use rama_http::layer::remove_header::RemoveRequestHeaderLayer;
 use rama_http::layer::remove_header::RemoveResponseHeaderLayer;
 use rama_http::matcher::MethodMatcher;
@@ -42,50 +53,52 @@ use rama_http_backend::server::HttpServer;
 use rama_http_backend::server::layer::upgrade::UpgradeLayer;
 use rama_http_backend::server::layer::upgrade::Upgraded;
 use rama_net::Protocol;
+use rama_net::address::HostWithPort;
 use rama_net::address::ProxyAddress;
 use rama_net::client::ConnectorService;
 use rama_net::client::EstablishedClientConnection;
 use rama_net::http::RequestContext;
-use rama_net::proxy::ProxyRequest;
 use rama_net::proxy::ProxyTarget;
-use rama_net::proxy::StreamForwardService;
 use rama_net::stream::SocketInfo;
 use rama_tcp::client::Request as TcpRequest;
 use rama_tcp::client::service::TcpConnector;
 use rama_tcp::server::TcpListener;
 use rama_tls_boring::client::TlsConnectorDataBuilder;
 use rama_tls_boring::client::TlsConnectorLayer;
+use rama_tls_boring::server::TlsAcceptorLayer;
 use serde::Serialize;
 use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::pin::pin;
 use std::sync::Arc;
+use tokio::io::copy_bidirectional;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
 
 pub async fn run_http_proxy(
     state: Arc<NetworkProxyState>,
+    listener: TcpListener,
     addr: SocketAddr,
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    mitm: Option<Arc<MitmState>>,
+    cassette: Option<Arc<Cassette>>,
 ) -> Result<()> {
-    let listener = TcpListener::build()
-        .bind(addr)
-        .await
-        // Rama's `BoxError` is a `Box<dyn Error + Send + Sync>` without an explicit `'static`
-        // lifetime bound, which means it doesn't satisfy `anyhow::Context`'s `StdError` constraint.
-        // Wrap it in Rama's `OpaqueError` so we can preserve the original error as a source and
-        // still use `anyhow` for chaining.
-        .map_err(rama_core::error::OpaqueError::from)
-        .map_err(anyhow::Error::from)
-        .with_context(|| format!("bind HTTP proxy: {addr}"))?;
-
-    let http_service = HttpServer::auto(Executor::new()).service(
+    let proxy_service = Arc::new(
         (
             UpgradeLayer::new(
                 MethodMatcher::CONNECT,
                 service_fn({
                     let policy_decider = policy_decider.clone();
-                    move |req| http_connect_accept(policy_decider.clone(), req)
+                    let cassette = cassette.clone();
+                    move |req| {
+                        http_connect_accept(
+                            policy_decider.clone(),
+                            mitm.clone(),
+                            cassette.clone(),
+                            req,
+                        )
+                    }
                 }),
                 service_fn(http_connect_proxy),
             ),
@@ -94,9 +107,21 @@ pub async fn run_http_proxy(
         )
             .into_layer(service_fn({
                 let policy_decider = policy_decider.clone();
-                move |req| http_plain_proxy(policy_decider.clone(), req)
+                move |req| http_plain_proxy(policy_decider.clone(), cassette.clone(), req)
             })),
     );
+    // `Proxy-Authorization` is a hop-by-hop header, so the client token has to be read
+    // before the layers above strip it.
+    let http_service =
+        HttpServer::auto(Executor::new()).service(service_fn(move |mut req: Request| {
+            let proxy_service = Arc::clone(&proxy_service);
+            async move {
+                if let Some(token) = proxy_client_token(&req) {
+                    req.extensions_mut().insert(ClientToken(token));
+                }
+                proxy_service.serve(req).await
+            }
+        }));
 
     info!("HTTP proxy listening on {addr}");
 
@@ -108,6 +133,8 @@ pub async fn run_http_proxy(
 
 async fn http_connect_accept(
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    mitm: Option<Arc<MitmState>>,
+    cassette: Option<Arc<Cassette>>,
     mut req: Request,
 ) -> Result<(Response, Request), Response> {
     let app_state = req
@@ -130,6 +157,7 @@ async fn http_connect_accept(
     }
 
     let client = client_addr(&req);
+    let client_token = client_token(&req);
 
     let enabled = app_state
         .enabled()
@@ -142,6 +170,7 @@ async fn http_connect_accept(
             &app_state,
             host,
             client_addr(&req),
+            client_token,
             Some("CONNECT".to_string()),
             "http-connect",
         )
@@ -153,18 +182,23 @@ async fn http_connect_accept(
         host: host.clone(),
         port: authority.port,
         client_addr: client.clone(),
+        client_token,
         method: Some("CONNECT".to_string()),
         command: None,
         exec_policy_hint: None,
     });
 
-    match evaluate_host_policy(&app_state, policy_decider.as_ref(), &request).await {
+    let target = RuleTarget::Tunnel {
+        inspected: mitm.is_some(),
+    };
+    match evaluate_host_policy(&app_state, policy_decider.as_ref(), &request, target).await {
         Ok(NetworkDecision::Deny { reason }) => {
             let _ = app_state
                 .record_blocked(BlockedRequest::new(BlockedRequestArgs {
                     host: host.clone(),
                     reason: reason.clone(),
                     client: client.clone(),
+                    client_token: request.client_token.clone(),
                     method: Some("CONNECT".to_string()),
                     mode: None,
                     protocol: "http-connect".to_string(),
@@ -189,12 +223,14 @@ async fn http_connect_accept(
         .await
         .map_err(|err| internal_error("failed to read network mode", err))?;
 
-    if mode == NetworkMode::Limited {
+    // Without interception the methods inside the tunnel cannot be checked.
+    if mode == NetworkMode::Limited && mitm.is_none() {
         let _ = app_state
             .record_blocked(BlockedRequest::new(BlockedRequestArgs {
                 host: host.clone(),
                 reason: REASON_METHOD_NOT_ALLOWED.to_string(),
                 client: client.clone(),
+                client_token: request.client_token.clone(),
                 method: Some("CONNECT".to_string()),
                 mode: Some(NetworkMode::Limited),
                 protocol: "http-connect".to_string(),
@@ -205,8 +241,32 @@ async fn http_connect_accept(
         return Err(blocked_text(REASON_METHOD_NOT_ALLOWED));
     }
 
+    // A replay cassette can only answer requests it can see.
+    if mitm.is_none() && cassette.as_deref().is_some_and(Cassette::replaying) {
+        let _ = app_state
+            .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                host: host.clone(),
+                reason: REASON_REPLAY_MISS.to_string(),
+                client: client.clone(),
+                client_token: request.client_token.clone(),
+                method: Some("CONNECT".to_string()),
+                mode: None,
+                protocol: "http-connect".to_string(),
+            }))
+            .await;
+        let client = client.as_deref().unwrap_or_default();
+        warn!("CONNECT blocked; replay needs mitm to see requests (client={client}, host={host})");
+        return Err(blocked_text(REASON_REPLAY_MISS));
+    }
+
     req.extensions_mut().insert(ProxyTarget(authority));
     req.extensions_mut().insert(mode);
+    if let Some(mitm) = mitm {
+        req.extensions_mut().insert(mitm);
+    }
+    if let Some(cassette) = cassette {
+        req.extensions_mut().insert(cassette);
+    }
 
     Ok((
         Response::builder()
@@ -223,11 +283,18 @@ async fn http_connect_proxy(upgraded: Upgraded) -> Result<(), Infallible> {
         return Ok(());
     }
 
-    let allow_upstream_proxy = match upgraded
+    if let Some(mitm) = upgraded.extensions().get::<Arc<MitmState>>().cloned() {
+        if let Err(err) = mitm_tunnel(upgraded, mitm).await {
+            warn!("intercepted tunnel error: {err}");
+        }
+        return Ok(());
+    }
+
+    let app_state = upgraded
         .extensions()
         .get::<Arc<NetworkProxyState>>()
-        .cloned()
-    {
+        .cloned();
+    let allow_upstream_proxy = match &app_state {
         Some(state) => match state.allow_upstream_proxy().await {
             Ok(allowed) => allowed,
             Err(err) => {
@@ -247,16 +314,39 @@ async fn http_connect_proxy(upgraded: Upgraded) -> Result<(), Infallible> {
         None
     };
 
-    if let Err(err) = forward_connect_tunnel(upgraded, proxy).await {
-        warn!("tunnel error: {err}");
+    let host = upgraded
+        .extensions()
+        .get::<ProxyTarget>()
+        .map(|target| normalize_host(&target.0.host.to_string()))
+        .unwrap_or_default();
+    let client_token = client_token(&upgraded);
+    let (bytes_sent, bytes_received) = match forward_connect_tunnel(upgraded, proxy).await {
+        Ok((sent, received)) => (Some(sent), Some(received)),
+        Err(err) => {
+            warn!("tunnel error: {err}");
+            (None, None)
+        }
+    };
+    // The tunnel is recorded once it closes so the entry can carry its byte counts.
+    if let Some(app_state) = app_state {
+        app_state.record_allowed(AllowedRequestArgs {
+            host,
+            client_token,
+            method: Some("CONNECT".to_string()),
+            protocol: "http-connect".to_string(),
+            bytes_sent,
+            bytes_received,
+        });
     }
     Ok(())
 }
 
+/// Relay a CONNECT tunnel until either side closes it, returning the bytes sent upstream and the
+/// bytes received from it.
 async fn forward_connect_tunnel(
     upgraded: Upgraded,
     proxy: Option<ProxyAddress>,
-) -> Result<(), BoxError> {
+) -> Result<(u64, u64), BoxError> {
     let authority = upgraded
         .extensions()
         .get::<ProxyTarget>()
@@ -282,12 +372,9 @@ async fn forward_connect_tunnel(
                 .into_boxed()
         })?;
 
-    let proxy_req = ProxyRequest {
-        source: upgraded,
-        target,
-    };
-    StreamForwardService::default()
-        .serve(proxy_req)
+    let mut source = pin!(upgraded);
+    let mut target = pin!(target);
+    copy_bidirectional(&mut source, &mut target)
         .await
         .map_err(|err| {
             OpaqueError::from_boxed(err.into())
@@ -296,8 +383,192 @@ async fn forward_connect_tunnel(
         })
 }
 
+/// Terminate the TLS of a CONNECT tunnel and serve the requests inside it, so the method policy
+/// applies to them like to plain HTTP requests.
+async fn mitm_tunnel(upgraded: Upgraded, mitm: Arc<MitmState>) -> Result<(), BoxError> {
+    let authority = upgraded
+        .extensions()
+        .get::<ProxyTarget>()
+        .map(|target| target.0.clone())
+        .ok_or_else(|| OpaqueError::from_display("missing forward authority").into_boxed())?;
+    let app_state = upgraded
+        .extensions()
+        .get::<Arc<NetworkProxyState>>()
+        .cloned()
+        .ok_or_else(|| OpaqueError::from_display("missing app state").into_boxed())?;
+    let host = normalize_host(&authority.host.to_string());
+    let acceptor = mitm.acceptor_for_host(&host).map_err(|err| {
+        OpaqueError::from_display(format!("{err:#}"))
+            .with_context(|| format!("mint certificate for {host}"))
+            .into_boxed()
+    })?;
+    let tunnel = Arc::new(MitmTunnel {
+        authority: authority.clone(),
+        host,
+        client: client_addr(&upgraded),
+        client_token: client_token(&upgraded),
+        app_state,
+        cassette: upgraded.extensions().get::<Arc<Cassette>>().cloned(),
+    });
+
+    let inner_service = (
+        RemoveResponseHeaderLayer::hop_by_hop(),
+        RemoveRequestHeaderLayer::hop_by_hop(),
+    )
+        .into_layer(service_fn(move |req| {
+            mitm_request(Arc::clone(&tunnel), req)
+        }));
+    let http_service = HttpServer::auto(Executor::new()).service(inner_service);
+    TlsAcceptorLayer::new(acceptor)
+        .into_layer(http_service)
+        .serve(upgraded)
+        .await
+        .map_err(|err| {
+            OpaqueError::from_boxed(err.into())
+                .with_context(|| format!("serve intercepted tunnel to {authority}"))
+                .into_boxed()
+        })
+}
+
+/// The CONNECT request an intercepted tunnel was opened with.
+struct MitmTunnel {
+    authority: HostWithPort,
+    host: String,
+    client: Option<String>,
+    client_token: Option<String>,
+    app_state: Arc<NetworkProxyState>,
+    cassette: Option<Arc<Cassette>>,
+}
+
+async fn mitm_request(tunnel: Arc<MitmTunnel>, req: Request) -> Result<Response, Infallible> {
+    let MitmTunnel {
+        authority,
+        host,
+        client,
+        client_token,
+        app_state,
+        cassette,
+    } = tunnel.as_ref();
+    let method = req.method().as_str().to_string();
+
+    // The policy only looked at the CONNECT host. A request naming another host would be routed
+    // by the upstream (a CDN, say) to a host the policy never saw.
+    if let Some(requested) = misdirected_host(&req, host) {
+        let _ = app_state
+            .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                host: host.clone(),
+                reason: REASON_HOST_MISMATCH.to_string(),
+                client: client.clone(),
+                client_token: client_token.clone(),
+                method: Some(method.clone()),
+                mode: None,
+                protocol: "https".to_string(),
+            }))
+            .await;
+        let client = client.as_deref().unwrap_or_default();
+        warn!(
+            "request blocked; host differs from the tunnel (client={client}, host={host}, requested={requested})"
+        );
+        return Ok(json_blocked(host, REASON_HOST_MISMATCH));
+    }
+
+    // The tunnel was admitted on its host; now that the request is visible, the rules get to
+    // look at its method and path.
+    let target = RuleTarget::Request {
+        method: &method,
+        path: req.uri().path(),
+    };
+    let method_allowed = match app_state.rule_decision(host, target).await {
+        Ok(Some(NetworkDecision::Deny { reason })) => {
+            let _ = app_state
+                .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                    host: host.clone(),
+                    reason: reason.clone(),
+                    client: client.clone(),
+                    client_token: client_token.clone(),
+                    method: Some(method.clone()),
+                    mode: None,
+                    protocol: "https".to_string(),
+                }))
+                .await;
+            let client = client.as_deref().unwrap_or_default();
+            warn!("request blocked (client={client}, host={host}, reason={reason})");
+            return Ok(json_blocked(host, &reason));
+        }
+        Ok(Some(NetworkDecision::Allow)) => true,
+        Ok(None) => match app_state.method_allowed(&method).await {
+            Ok(allowed) => allowed,
+            Err(err) => return Ok(internal_error("failed to evaluate method policy", err)),
+        },
+        Err(err) => return Ok(internal_error("failed to evaluate network rules", err)),
+    };
+    if !method_allowed {
+        let _ = app_state
+            .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                host: host.clone(),
+                reason: REASON_METHOD_NOT_ALLOWED.to_string(),
+                client: client.clone(),
+                client_token: client_token.clone(),
+                method: Some(method.clone()),
+                mode: Some(NetworkMode::Limited),
+                protocol: "https".to_string(),
+            }))
+            .await;
+        let client = client.as_deref().unwrap_or_default();
+        warn!(
+            "request blocked by method policy (client={client}, host={host}, method={method}, mode=limited, allowed_methods=GET, HEAD, OPTIONS)"
+        );
+        return Ok(json_blocked(host, REASON_METHOD_NOT_ALLOWED));
+    }
+
+    // Requests inside the tunnel carry origin-form targets; send them to the CONNECT authority.
+    let (mut parts, body) = req.into_parts();
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(rama_http::uri::PathAndQuery::as_str)
+        .unwrap_or("/");
+    parts.uri = match format!("https://{authority}{path}").parse() {
+        Ok(uri) => uri,
+        Err(err) => {
+            warn!("invalid intercepted request target {path}: {err}");
+            return Ok(text_response(
+                StatusCode::BAD_REQUEST,
+                "invalid request target",
+            ));
+        }
+    };
+    let req = Request::from_parts(parts, body);
+
+    let client_addr = client.as_deref().unwrap_or_default();
+    info!("request allowed (client={client_addr}, host={host}, method={method}, intercepted)");
+    let forward = AllowedForward {
+        host: host.clone(),
+        client: client.clone(),
+        client_token: client_token.clone(),
+        method,
+        protocol: "https",
+    };
+    Ok(forward_allowed(app_state, cassette.as_ref(), forward, req).await)
+}
+
+/// The host an intercepted request names in its `Host` header or target when that is not the
+/// tunnel's `host`.
+fn misdirected_host(req: &Request, host: &str) -> Option<String> {
+    let header = req
+        .headers()
+        .get(rama_http::header::HOST)
+        .map(|value| value.to_str().unwrap_or_default());
+    header
+        .into_iter()
+        .chain(req.uri().host())
+        .map(normalize_host)
+        .find(|requested| requested != host)
+}
+
 async fn http_plain_proxy(
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    cassette: Option<Arc<Cassette>>,
     req: Request,
 ) -> Result<Response, Infallible> {
     let app_state = match req.extensions().get::<Arc<NetworkProxyState>>().cloned() {
@@ -347,12 +618,24 @@ async fn http_plain_proxy(
                 &app_state,
                 socket_path,
                 client_addr(&req),
+                client_token(&req),
                 Some(req.method().as_str().to_string()),
                 "unix-socket",
             )
             .await);
         }
         if !method_allowed {
+            let _ = app_state
+                .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                    host: socket_path.clone(),
+                    reason: REASON_METHOD_NOT_ALLOWED.to_string(),
+                    client: client.clone(),
+                    client_token: client_token(&req),
+                    method: Some(req.method().as_str().to_string()),
+                    mode: Some(NetworkMode::Limited),
+                    protocol: "unix-socket".to_string(),
+                }))
+                .await;
             let client = client.as_deref().unwrap_or_default();
             let method = req.method();
             warn!(
@@ -370,10 +653,43 @@ async fn http_plain_proxy(
         }
 
         return match app_state.is_unix_socket_allowed(&socket_path).await {
+            Ok(true) if cassette.as_deref().is_some_and(Cassette::replaying) => {
+                let _ = app_state
+                    .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                        host: socket_path.clone(),
+                        reason: REASON_REPLAY_MISS.to_string(),
+                        client: client.clone(),
+                        client_token: client_token(&req),
+                        method: Some(req.method().as_str().to_string()),
+                        mode: None,
+                        protocol: "unix-socket".to_string(),
+                    }))
+                    .await;
+                let client = client.as_deref().unwrap_or_default();
+                warn!(
+                    "unix socket blocked; replay does not cover sockets (client={client}, path={socket_path})"
+                );
+                Ok(json_blocked("unix-socket", REASON_REPLAY_MISS))
+            }
             Ok(true) => {
                 let client = client.as_deref().unwrap_or_default();
                 info!("unix socket allowed (client={client}, path={socket_path})");
-                match proxy_via_unix_socket(req, &socket_path).await {
+                let client_token = client_token(&req);
+                let method = req.method().as_str().to_string();
+                let bytes_sent = content_length(req.headers());
+                let result = proxy_via_unix_socket(req, &socket_path).await;
+                app_state.record_allowed(AllowedRequestArgs {
+                    host: socket_path,
+                    client_token,
+                    method: Some(method),
+                    protocol: "unix-socket".to_string(),
+                    bytes_sent,
+                    bytes_received: result
+                        .as_ref()
+                        .ok()
+                        .and_then(|resp| content_length(resp.headers())),
+                });
+                match result {
                     Ok(resp) => Ok(resp),
                     Err(err) => {
                         warn!("unix socket proxy failed: {err}");
@@ -385,6 +701,17 @@ async fn http_plain_proxy(
                 }
             }
             Ok(false) => {
+                let _ = app_state
+                    .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                        host: socket_path.clone(),
+                        reason: REASON_NOT_ALLOWED.to_string(),
+                        client: client.clone(),
+                        client_token: client_token(&req),
+                        method: Some(req.method().as_str().to_string()),
+                        mode: None,
+                        protocol: "unix-socket".to_string(),
+                    }))
+                    .await;
                 let client = client.as_deref().unwrap_or_default();
                 warn!("unix socket blocked (client={client}, path={socket_path})");
                 Ok(json_blocked("unix-socket", REASON_NOT_ALLOWED))
@@ -421,6 +748,7 @@ async fn http_plain_proxy(
             &app_state,
             host,
             client_addr(&req),
+            client_token(&req),
             Some(req.method().as_str().to_string()),
             "http",
         )
@@ -432,18 +760,24 @@ async fn http_plain_proxy(
         host: host.clone(),
         port,
         client_addr: client.clone(),
+        client_token: client_token(&req),
         method: Some(req.method().as_str().to_string()),
         command: None,
         exec_policy_hint: None,
     });
 
-    match evaluate_host_policy(&app_state, policy_decider.as_ref(), &request).await {
+    let target = RuleTarget::Request {
+        method: req.method().as_str(),
+        path: req.uri().path(),
+    };
+    match evaluate_host_policy(&app_state, policy_decider.as_ref(), &request, target).await {
         Ok(NetworkDecision::Deny { reason }) => {
             let _ = app_state
                 .record_blocked(BlockedRequest::new(BlockedRequestArgs {
                     host: host.clone(),
                     reason: reason.clone(),
                     client: client.clone(),
+                    client_token: request.client_token.clone(),
                     method: Some(req.method().as_str().to_string()),
                     mode: None,
                     protocol: "http".to_string(),
@@ -460,12 +794,21 @@ async fn http_plain_proxy(
         }
     }
 
+    let method_allowed = match app_state
+        .request_method_allowed(&host, req.method().as_str(), req.uri().path())
+        .await
+        .map_err(|err| internal_error("failed to evaluate method policy", err))
+    {
+        Ok(allowed) => allowed,
+        Err(resp) => return Ok(resp),
+    };
     if !method_allowed {
         let _ = app_state
             .record_blocked(BlockedRequest::new(BlockedRequestArgs {
                 host: host.clone(),
                 reason: REASON_METHOD_NOT_ALLOWED.to_string(),
                 client: client.clone(),
+                client_token: request.client_token.clone(),
                 method: Some(req.method().as_str().to_string()),
                 mode: Some(NetworkMode::Limited),
                 protocol: "http".to_string(),
@@ -479,29 +822,109 @@ async fn http_plain_proxy(
         return Ok(json_blocked(&host, REASON_METHOD_NOT_ALLOWED));
     }
 
-    let client = client.as_deref().unwrap_or_default();
-    let method = req.method();
-    info!("request allowed (client={client}, host={host}, method={method})");
+    let method = req.method().as_str().to_string();
+    let client_addr = client.as_deref().unwrap_or_default();
+    info!("request allowed (client={client_addr}, host={host}, method={method})");
+    let forward = AllowedForward {
+        host,
+        client,
+        client_token: request.client_token,
+        method,
+        protocol: "http",
+    };
+    Ok(forward_allowed(&app_state, cassette.as_ref(), forward, req).await)
+}
 
-    let allow_upstream_proxy = match app_state
-        .allow_upstream_proxy()
-        .await
-        .map_err(|err| internal_error("failed to read upstream proxy config", err))
-    {
+/// An allowed request on its way upstream, as the audit log and block list report it.
+struct AllowedForward {
+    host: String,
+    client: Option<String>,
+    client_token: Option<String>,
+    method: String,
+    protocol: &'static str,
+}
+
+/// Send an allowed request upstream, saving the response when a cassette records. When a
+/// cassette replays, answer from it alone and block requests it has no response for.
+async fn forward_allowed(
+    app_state: &NetworkProxyState,
+    cassette: Option<&Arc<Cassette>>,
+    forward: AllowedForward,
+    req: Request,
+) -> Response {
+    let AllowedForward {
+        host,
+        client,
+        client_token,
+        method,
+        protocol,
+    } = forward;
+    let bytes_sent = content_length(req.headers());
+
+    if let Some(cassette) = cassette.filter(|cassette| cassette.replaying()) {
+        let resp = match replay(cassette, req).await {
+            Ok(Some(resp)) => resp,
+            Ok(None) => {
+                let _ = app_state
+                    .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                        host: host.clone(),
+                        reason: REASON_REPLAY_MISS.to_string(),
+                        client: client.clone(),
+                        client_token,
+                        method: Some(method.clone()),
+                        mode: None,
+                        protocol: protocol.to_string(),
+                    }))
+                    .await;
+                let client = client.as_deref().unwrap_or_default();
+                warn!(
+                    "request blocked; no recorded response (client={client}, host={host}, method={method})"
+                );
+                return json_blocked(&host, REASON_REPLAY_MISS);
+            }
+            Err(err) => return internal_error("failed to replay request", err),
+        };
+        app_state.record_allowed(AllowedRequestArgs {
+            host,
+            client_token,
+            method: Some(method),
+            protocol: protocol.to_string(),
+            bytes_sent,
+            bytes_received: content_length(resp.headers()),
+        });
+        return resp;
+    }
+
+    let allow_upstream_proxy = match app_state.allow_upstream_proxy().await {
         Ok(allow) => allow,
-        Err(resp) => return Ok(resp),
+        Err(err) => return internal_error("failed to read upstream proxy config", err),
     };
-    let client = if allow_upstream_proxy {
+    let mut upstream = if allow_upstream_proxy {
         UpstreamClient::from_env_proxy()
     } else {
         UpstreamClient::direct()
     };
+    if let Some(cassette) = cassette {
+        upstream = upstream.recording_into(Arc::clone(cassette));
+    }
 
-    match client.serve(req).await {
-        Ok(resp) => Ok(resp),
+    let result = upstream.serve(req).await;
+    app_state.record_allowed(AllowedRequestArgs {
+        host,
+        client_token,
+        method: Some(method),
+        protocol: protocol.to_string(),
+        bytes_sent,
+        bytes_received: result
+            .as_ref()
+            .ok()
+            .and_then(|resp| content_length(resp.headers())),
+    });
+    match result {
+        Ok(resp) => resp,
         Err(err) => {
             warn!("upstream request failed: {err}");
-            Ok(text_response(StatusCode::BAD_GATEWAY, "upstream failure"))
+            text_response(StatusCode::BAD_GATEWAY, "upstream failure")
         }
     }
 }
@@ -533,6 +956,17 @@ async fn proxy_via_unix_socket(req: Request, socket_path: &str) -> Result<Respon
     }
 }
 
+/// The `Content-Length` of a request or response; streamed bodies have none.
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
 fn client_addr<T: ExtensionsRef>(input: &T) -> Option<String> {
     input
         .extensions()
@@ -540,6 +974,33 @@ fn client_addr<T: ExtensionsRef>(input: &T) -> Option<String> {
         .map(|info| info.peer_addr().to_string())
 }
 
+/// The token a client identifies itself with, see [`NetworkPolicyRequest::client_token`].
+#[derive(Clone, Debug)]
+pub(crate) struct ClientToken(pub(crate) String);
+
+pub(crate) fn client_token<T: ExtensionsRef>(input: &T) -> Option<String> {
+    input
+        .extensions()
+        .get::<ClientToken>()
+        .map(|token| token.0.clone())
+}
+
+/// The user name of a `Proxy-Authorization: Basic` header. Clients send one when the
+/// proxy URL carries credentials, e.g. `http://token@127.0.0.1:3128`.
+fn proxy_client_token(req: &Request) -> Option<String> {
+    let value = req.headers().get("proxy-authorization")?.to_str().ok()?;
+    let (scheme, credentials) = value.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("basic") {
+        return None;
+    }
+    let decoded = BASE64_STANDARD.decode(credentials.trim()).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    let user = decoded
+        .split_once(':')
+        .map_or(decoded.as_str(), |(user, _)| user);
+    (!user.is_empty()).then(|| user.to_string())
+}
+
 fn json_blocked(host: &str, reason: &str) -> Response {
     let response = BlockedResponse {
         status: "blocked",
@@ -548,10 +1009,9 @@ fn json_blocked(host: &str, reason: &str) -> Response {
     };
     let mut resp = json_response(&response);
     *resp.status_mut() = StatusCode::FORBIDDEN;
-    resp.headers_mut().insert(
-        "x-proxy-error",
-        HeaderValue::from_static(blocked_header_value(reason)),
-    );
+    let header = HeaderValue::from_str(&blocked_header_value(reason))
+        .unwrap_or_else(|_| HeaderValue::from_static("blocked-by-policy"));
+    resp.headers_mut().insert("x-proxy-error", header);
     resp
 }
 
@@ -563,6 +1023,7 @@ async fn proxy_disabled_response(
     app_state: &NetworkProxyState,
     host: String,
     client: Option<String>,
+    client_token: Option<String>,
     method: Option<String>,
     protocol: &str,
 ) -> Response {
@@ -571,6 +1032,7 @@ async fn proxy_disabled_response(
             host,
             reason: REASON_PROXY_DISABLED.to_string(),
             client,
+            client_token,
             method,
             mode: None,
             protocol: protocol.to_string(),
@@ -603,6 +1065,9 @@ struct BlockedResponse<'a> {
 mod tests {
     use super::*;
 
+    use crate::audit::NetworkAuditEvent;
+    use crate::config::NetworkCassette;
+    use crate::config::NetworkCassetteMode;
     use crate::config::NetworkMode;
     use crate::config::NetworkPolicy;
     use crate::runtime::network_proxy_state_for_policy;
@@ -610,6 +1075,7 @@ mod tests {
     use rama_http::Method;
     use rama_http::Request;
     use std::sync::Arc;
+    use std::sync::Mutex;
 
     #[tokio::test]
     async fn http_connect_accept_blocks_in_limited_mode() {
@@ -628,11 +1094,179 @@ mod tests {
             .unwrap();
         req.extensions_mut().insert(state);
 
-        let response = http_connect_accept(None, req).await.unwrap_err();
+        let response = http_connect_accept(None, None, None, req)
+            .await
+            .unwrap_err();
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
         assert_eq!(
             response.headers().get("x-proxy-error").unwrap(),
             "blocked-by-method-policy"
         );
     }
+
+    #[tokio::test]
+    async fn http_connect_accept_audits_blocked_tunnel_with_client_token() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = {
+            let events = Arc::clone(&events);
+            move |event: NetworkAuditEvent| events.lock().unwrap().push(event)
+        };
+        let state = network_proxy_state_for_policy(NetworkPolicy {
+            denied_domains: vec!["example.com".to_string()],
+            ..Default::default()
+        })
+        .with_audit_sink(Arc::new(sink));
+
+        let mut req = Request::builder()
+            .method(Method::CONNECT)
+            .uri("https://example.com:443")
+            .header("host", "example.com:443")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(Arc::new(state));
+        req.extensions_mut()
+            .insert(ClientToken("call_1".to_string()));
+
+        http_connect_accept(None, None, None, req)
+            .await
+            .unwrap_err();
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].host, "example.com");
+        assert_eq!(events[0].protocol, "http-connect");
+        assert_eq!(events[0].client_token.as_deref(), Some("call_1"));
+        assert_eq!(events[0].decision, NetworkDecision::deny("denied"));
+    }
+
+    #[tokio::test]
+    async fn http_connect_accept_intercepts_in_limited_mode_with_mitm() {
+        let policy = NetworkPolicy {
+            allowed_domains: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+        let state = Arc::new(network_proxy_state_for_policy(policy));
+        state.set_network_mode(NetworkMode::Limited).await.unwrap();
+        let ca_dir = tempfile::tempdir().unwrap();
+        let mitm = Arc::new(MitmState::load_or_create(ca_dir.path()).unwrap());
+
+        let mut req = Request::builder()
+            .method(Method::CONNECT)
+            .uri("https://example.com:443")
+            .header("host", "example.com:443")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(state);
+
+        let (response, req) = http_connect_accept(None, Some(mitm), None, req)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(req.extensions().get::<Arc<MitmState>>().is_some());
+    }
+
+    #[tokio::test]
+    async fn http_connect_accept_blocks_tunnels_it_cannot_replay() {
+        let policy = NetworkPolicy {
+            allowed_domains: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+        let state = Arc::new(network_proxy_state_for_policy(policy));
+        let cassette_dir = tempfile::tempdir().unwrap();
+        let cassette = Arc::new(
+            Cassette::open(&NetworkCassette {
+                mode: NetworkCassetteMode::Replay,
+                dir: cassette_dir.path().to_path_buf(),
+            })
+            .unwrap(),
+        );
+
+        let mut req = Request::builder()
+            .method(Method::CONNECT)
+            .uri("https://example.com:443")
+            .header("host", "example.com:443")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(state);
+
+        let response = http_connect_accept(None, None, Some(cassette), req)
+            .await
+            .unwrap_err();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers().get("x-proxy-error").unwrap(),
+            "blocked-by-replay-cassette"
+        );
+    }
+
+    #[test]
+    fn misdirected_host_rejects_requests_for_other_hosts() {
+        let request = |uri: &str, host: Option<&str>| {
+            let mut builder = Request::builder().uri(uri);
+            if let Some(host) = host {
+                builder = builder.header("host", host);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        assert_eq!(
+            misdirected_host(
+                &request("/", Some("Allowed-CDN.example:443")),
+                "allowed-cdn.example"
+            ),
+            None
+        );
+        assert_eq!(
+            misdirected_host(
+                &request("https://allowed-cdn.example/", None),
+                "allowed-cdn.example"
+            ),
+            None
+        );
+        assert_eq!(
+            misdirected_host(
+                &request("/", Some("blocked.example")),
+                "allowed-cdn.example"
+            ),
+            Some("blocked.example".to_string())
+        );
+        assert_eq!(
+            misdirected_host(
+                &request("https://blocked.example/", Some("allowed-cdn.example")),
+                "allowed-cdn.example"
+            ),
+            Some("blocked.example".to_string())
+        );
+    }
+
+    #[test]
+    fn proxy_client_token_reads_basic_auth_user() {
+        let request = |value: &str| {
+            Request::builder()
+                .uri("http://example.com/")
+                .header("proxy-authorization", value)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let basic = |credentials: &str| format!("Basic {}", BASE64_STANDARD.encode(credentials));
+
+        assert_eq!(
+            proxy_client_token(&request(&basic("call_1:"))),
+            Some("call_1".to_string())
+        );
+        assert_eq!(
+            proxy_client_token(&request(&basic("call_1:secret"))),
+            Some("call_1".to_string())
+        );
+        assert_eq!(proxy_client_token(&request(&basic(":secret"))), None);
+        assert_eq!(proxy_client_token(&request("Bearer call_1")), None);
+        assert_eq!(
+            proxy_client_token(
+                &Request::builder()
+                    .uri("http://example.com/")
+                    .body(Body::empty())
+                    .unwrap()
+            ),
+            None
+        );
+    }
 }
@@ -1,31 +1,41 @@
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
 mod admin;
+mod audit;
+mod cassette;
 mod config;
 mod http_proxy;
+mod mitm;
 mod network_policy;
 mod policy;
 mod proxy;
 mod reasons;
 mod responses;
+mod rules;
 mod runtime;
 mod socks5;
 mod state;
 mod upstream;
 
-use anyhow::Result;
+pub use audit::NetworkAuditEvent;
+pub use audit::NetworkAuditSink;
+pub use config::NetworkCassette;
+pub use config::NetworkCassetteMode;
+pub use config::NetworkMode;
+pub use config::NetworkPolicy;
+pub use config::NetworkProxySettings;
+pub use config::NetworkRule;
+pub use config::NetworkRuleDecision;
+pub use mitm::CA_CERT_FILE;
 pub use network_policy::NetworkDecision;
 pub use network_policy::NetworkPolicyDecider;
 pub use network_policy::NetworkPolicyRequest;
 pub use network_policy::NetworkPolicyRequestArgs;
 pub use network_policy::NetworkProtocol;
-pub use proxy::Args;
 pub use proxy::NetworkProxy;
 pub use proxy::NetworkProxyBuilder;
 pub use proxy::NetworkProxyHandle;
-
-pub async fn run_main(args: Args) -> Result<()> {
-    let _ = args;
-    let proxy = NetworkProxy::builder().build().await?;
-    proxy.run().await?.wait().await
-}
+pub use state::ConfigLayer;
+pub use state::ConfigLayers;
+pub use state::ConfigLoader;
+pub use state::NetworkProxyState;
@@ -1,14 +1,71 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
 use anyhow::Result;
+use async_trait::async_trait;
 use clap::Parser;
-use codex_network_proxy::Args;
+use codex_network_proxy::ConfigLayer;
+use codex_network_proxy::ConfigLayers;
+use codex_network_proxy::ConfigLoader;
 use codex_network_proxy::NetworkProxy;
+use codex_network_proxy::NetworkProxyState;
+
+/// Directory under `CODEX_HOME` holding the CA used when `mitm` is enabled.
+const CA_DIR: &str = "network-proxy";
+
+#[derive(Debug, Clone, Parser)]
+#[command(name = "codex-network-proxy", about = "Codex network sandbox proxy")]
+struct Args {}
+
+/// Reads the policy from `$CODEX_HOME/config.toml` alone.
+///
+/// Managed and project layers are only applied by `codex network proxy`, which
+/// loads config through `codex-core`.
+struct UserConfigLoader {
+    cfg_path: PathBuf,
+}
+
+#[async_trait]
+impl ConfigLoader for UserConfigLoader {
+    async fn load(&self) -> Result<ConfigLayers> {
+        let config = match tokio::fs::read_to_string(&self.cfg_path).await {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", self.cfg_path.display()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                toml::Value::Table(Default::default())
+            }
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to read {}", self.cfg_path.display()));
+            }
+        };
+        Ok(ConfigLayers {
+            effective_config: config.clone(),
+            layers: vec![ConfigLayer {
+                config,
+                path: Some(self.cfg_path.clone()),
+                user_controlled: true,
+            }],
+            cfg_path: self.cfg_path.clone(),
+        })
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
-    let args = Args::parse();
-    let _ = args;
-    let proxy = NetworkProxy::builder().build().await?;
+    let _args = Args::parse();
+    let codex_home = codex_utils_home_dir::find_codex_home()?;
+    let loader = UserConfigLoader {
+        cfg_path: codex_home.join("config.toml"),
+    };
+    let state = NetworkProxyState::new(Arc::new(loader)).await?;
+    let proxy = NetworkProxy::builder()
+        .state(Arc::new(state))
+        .ca_dir(codex_home.join(CA_DIR))
+        .build()
+        .await?;
     proxy.run().await?.wait().await
 }
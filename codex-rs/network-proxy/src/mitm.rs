@@ -0,0 +1,268 @@
+//! TLS interception for HTTPS tunnels.
+//!
+//! With `mitm = true` the proxy terminates the TLS of CONNECT tunnels itself so it can apply the
+//! method policy of `limited` mode to the requests inside them. It presents leaf certificates
+//! minted per host and signed by a CA that is generated once and kept in the CA directory given
+//! to [`crate::NetworkProxyBuilder::ca_dir`]. Clients only accept them when they trust that CA.
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use rama_net::tls::ApplicationProtocol;
+use rama_net::tls::DataEncoding;
+use rama_net::tls::server::ServerAuth;
+use rama_net::tls::server::ServerAuthData;
+use rama_net::tls::server::ServerConfig;
+use rama_tls_boring::core::asn1::Asn1Time;
+use rama_tls_boring::core::bn::BigNum;
+use rama_tls_boring::core::bn::MsbOption;
+use rama_tls_boring::core::ec::EcGroup;
+use rama_tls_boring::core::ec::EcKey;
+use rama_tls_boring::core::hash::MessageDigest;
+use rama_tls_boring::core::nid::Nid;
+use rama_tls_boring::core::pkey::PKey;
+use rama_tls_boring::core::pkey::Private;
+use rama_tls_boring::core::x509::X509;
+use rama_tls_boring::core::x509::X509Name;
+use rama_tls_boring::core::x509::X509NameBuilder;
+use rama_tls_boring::core::x509::extension::AuthorityKeyIdentifier;
+use rama_tls_boring::core::x509::extension::BasicConstraints;
+use rama_tls_boring::core::x509::extension::ExtendedKeyUsage;
+use rama_tls_boring::core::x509::extension::KeyUsage;
+use rama_tls_boring::core::x509::extension::SubjectAlternativeName;
+use rama_tls_boring::core::x509::extension::SubjectKeyIdentifier;
+use rama_tls_boring::server::TlsAcceptorData;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use tracing::info;
+
+/// File name of the CA certificate (PEM) inside the CA directory.
+pub const CA_CERT_FILE: &str = "ca.pem";
+const CA_KEY_FILE: &str = "ca.key";
+const CA_COMMON_NAME: &str = "Codex network proxy CA";
+const CA_VALIDITY_DAYS: u32 = 3650;
+// Short enough for clients that cap the lifetime of server certificates.
+const LEAF_VALIDITY_DAYS: u32 = 30;
+
+pub(crate) struct MitmState {
+    ca_cert: X509,
+    ca_key: PKey<Private>,
+    ca_cert_path: PathBuf,
+    acceptors: Mutex<HashMap<String, TlsAcceptorData>>,
+}
+
+impl MitmState {
+    /// Load the CA from `dir`, generating it on first use.
+    pub(crate) fn load_or_create(dir: &Path) -> Result<Self> {
+        let ca_cert_path = dir.join(CA_CERT_FILE);
+        let ca_key_path = dir.join(CA_KEY_FILE);
+        let (ca_cert, ca_key) = if ca_cert_path.exists() && ca_key_path.exists() {
+            load_ca(&ca_cert_path, &ca_key_path)?
+        } else {
+            let (ca_cert, ca_key) = generate_ca()?;
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("create CA directory {}", dir.display()))?;
+            write_private_file(&ca_key_path, &ca_key.private_key_to_pem_pkcs8()?)?;
+            std::fs::write(&ca_cert_path, ca_cert.to_pem()?)
+                .with_context(|| format!("write {}", ca_cert_path.display()))?;
+            info!("generated network proxy CA at {}", ca_cert_path.display());
+            (ca_cert, ca_key)
+        };
+        Ok(Self {
+            ca_cert,
+            ca_key,
+            ca_cert_path,
+            acceptors: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub(crate) fn ca_cert_path(&self) -> &Path {
+        &self.ca_cert_path
+    }
+
+    /// TLS server settings presenting a certificate for `host`. Certificates are minted on first
+    /// use and reused for the lifetime of the proxy.
+    pub(crate) fn acceptor_for_host(&self, host: &str) -> Result<TlsAcceptorData> {
+        let mut acceptors = self
+            .acceptors
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(acceptor) = acceptors.get(host) {
+            return Ok(acceptor.clone());
+        }
+        let (cert, key) = self.issue(host)?;
+        let mut chain = cert.to_pem()?;
+        chain.extend(self.ca_cert.to_pem()?);
+        let config = ServerConfig {
+            // Only HTTP/1.1 is offered, so inner requests can be forwarded as they are.
+            application_layer_protocol_negotiation: Some(vec![ApplicationProtocol::HTTP_11]),
+            ..ServerConfig::new(ServerAuth::Single(ServerAuthData {
+                private_key: DataEncoding::Pem(pem_string(key.private_key_to_pem_pkcs8()?)?),
+                cert_chain: DataEncoding::Pem(pem_string(chain)?),
+                ocsp: None,
+            }))
+        };
+        let acceptor = TlsAcceptorData::try_from(config)
+            .map_err(|err| anyhow!("build TLS acceptor for {host}: {err}"))?;
+        acceptors.insert(host.to_string(), acceptor.clone());
+        Ok(acceptor)
+    }
+
+    /// Mint a server certificate for `host` signed by the CA.
+    fn issue(&self, host: &str) -> Result<(X509, PKey<Private>)> {
+        let key = generate_key()?;
+        let mut builder = X509::builder()?;
+        builder.set_version(2)?;
+        builder.set_serial_number(random_serial()?.as_ref())?;
+        builder.set_subject_name(&common_name(host)?)?;
+        builder.set_issuer_name(self.ca_cert.subject_name())?;
+        builder.set_pubkey(&key)?;
+        builder.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+        builder.set_not_after(Asn1Time::days_from_now(LEAF_VALIDITY_DAYS)?.as_ref())?;
+        builder.append_extension(BasicConstraints::new().build()?)?;
+        builder.append_extension(
+            KeyUsage::new()
+                .critical()
+                .digital_signature()
+                .key_encipherment()
+                .build()?,
+        )?;
+        builder.append_extension(ExtendedKeyUsage::new().server_auth().build()?)?;
+        let mut san = SubjectAlternativeName::new();
+        if host.parse::<IpAddr>().is_ok() {
+            san.ip(host);
+        } else {
+            san.dns(host);
+        }
+        let san = san.build(&builder.x509v3_context(Some(&self.ca_cert), None))?;
+        builder.append_extension(san)?;
+        let authority_key_id = AuthorityKeyIdentifier::new()
+            .keyid(false)
+            .build(&builder.x509v3_context(Some(&self.ca_cert), None))?;
+        builder.append_extension(authority_key_id)?;
+        builder.sign(&self.ca_key, MessageDigest::sha256())?;
+        Ok((builder.build(), key))
+    }
+}
+
+fn load_ca(cert_path: &Path, key_path: &Path) -> Result<(X509, PKey<Private>)> {
+    let cert = std::fs::read(cert_path).with_context(|| format!("read {}", cert_path.display()))?;
+    let key = std::fs::read(key_path).with_context(|| format!("read {}", key_path.display()))?;
+    let cert = X509::from_pem(&cert)
+        .with_context(|| format!("parse CA certificate {}", cert_path.display()))?;
+    let key = PKey::private_key_from_pem(&key)
+        .with_context(|| format!("parse CA key {}", key_path.display()))?;
+    Ok((cert, key))
+}
+
+fn generate_ca() -> Result<(X509, PKey<Private>)> {
+    let key = generate_key()?;
+    let name = common_name(CA_COMMON_NAME)?;
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    builder.set_serial_number(random_serial()?.as_ref())?;
+    builder.set_subject_name(&name)?;
+    builder.set_issuer_name(&name)?;
+    builder.set_pubkey(&key)?;
+    builder.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+    builder.set_not_after(Asn1Time::days_from_now(CA_VALIDITY_DAYS)?.as_ref())?;
+    builder.append_extension(BasicConstraints::new().critical().ca().pathlen(0).build()?)?;
+    builder.append_extension(
+        KeyUsage::new()
+            .critical()
+            .key_cert_sign()
+            .crl_sign()
+            .build()?,
+    )?;
+    let subject_key_id = SubjectKeyIdentifier::new().build(&builder.x509v3_context(None, None))?;
+    builder.append_extension(subject_key_id)?;
+    builder.sign(&key, MessageDigest::sha256())?;
+    Ok((builder.build(), key))
+}
+
+fn generate_key() -> Result<PKey<Private>> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+    Ok(PKey::from_ec_key(EcKey::generate(&group)?)?)
+}
+
+fn random_serial() -> Result<rama_tls_boring::core::asn1::Asn1Integer> {
+    let mut serial = BigNum::new()?;
+    serial.rand(127, MsbOption::MAYBE_ZERO, false)?;
+    Ok(serial.to_asn1_integer()?)
+}
+
+fn common_name(value: &str) -> Result<X509Name> {
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_nid(Nid::COMMONNAME, value)?;
+    Ok(name.build())
+}
+
+fn pem_string<T>(pem: Vec<u8>) -> Result<T>
+where
+    T: TryFrom<String>,
+{
+    let pem = String::from_utf8(pem).context("PEM data is not UTF-8")?;
+    T::try_from(pem).map_err(|_| anyhow!("empty PEM data"))
+}
+
+#[cfg(unix)]
+fn write_private_file(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write as _;
+    use std::os::unix::fs::OpenOptionsExt as _;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("create {}", path.display()))?;
+    file.write_all(contents)
+        .with_context(|| format!("write {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn write_private_file(path: &Path, contents: &[u8]) -> Result<()> {
+    std::fs::write(path, contents).with_context(|| format!("write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn load_or_create_reuses_the_stored_ca() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = MitmState::load_or_create(dir.path()).unwrap();
+        let second = MitmState::load_or_create(dir.path()).unwrap();
+
+        assert_eq!(first.ca_cert_path(), dir.path().join(CA_CERT_FILE));
+        assert_eq!(
+            first.ca_cert.to_der().unwrap(),
+            second.ca_cert.to_der().unwrap()
+        );
+    }
+
+    #[test]
+    fn issued_certificates_are_signed_by_the_ca() {
+        let dir = tempfile::tempdir().unwrap();
+        let mitm = MitmState::load_or_create(dir.path()).unwrap();
+        let ca_key = mitm.ca_cert.public_key().unwrap();
+
+        for host in ["example.com", "127.0.0.1"] {
+            let (cert, _key) = mitm.issue(host).unwrap();
+            assert!(cert.verify(&ca_key).unwrap(), "{host}");
+            let names = cert.subject_alt_names().unwrap();
+            let name = names.iter().next().unwrap();
+            match host.parse::<IpAddr>() {
+                Ok(IpAddr::V4(ip)) => assert_eq!(name.ipaddress(), Some(&ip.octets()[..])),
+                _ => assert_eq!(name.dnsname(), Some(host)),
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::reasons::REASON_POLICY_DENIED;
+use crate::rules::RuleTarget;
 use crate::runtime::HostBlockDecision;
 use crate::runtime::HostBlockReason;
 use crate::state::NetworkProxyState;
@@ -21,6 +22,10 @@ pub struct NetworkPolicyRequest {
     pub host: String,
     pub port: u16,
     pub client_addr: Option<String>,
+    /// User name the client sent in `Proxy-Authorization` or in the SOCKS5
+    /// username/password handshake. Codex gives each command its own, so a
+    /// decision can be tied to the command that asked.
+    pub client_token: Option<String>,
     pub method: Option<String>,
     pub command: Option<String>,
     pub exec_policy_hint: Option<String>,
@@ -31,6 +36,7 @@ pub struct NetworkPolicyRequestArgs {
     pub host: String,
     pub port: u16,
     pub client_addr: Option<String>,
+    pub client_token: Option<String>,
     pub method: Option<String>,
     pub command: Option<String>,
     pub exec_policy_hint: Option<String>,
@@ -43,6 +49,7 @@ impl NetworkPolicyRequest {
             host,
             port,
             client_addr,
+            client_token,
             method,
             command,
             exec_policy_hint,
@@ -52,6 +59,7 @@ impl NetworkPolicyRequest {
             host,
             port,
             client_addr,
+            client_token,
             method,
             command,
             exec_policy_hint,
@@ -105,12 +113,26 @@ where
     }
 }
 
+/// Decide a request from the domain lists, the rules and the decider, in that order of
+/// precedence: a denied or local host is blocked outright, the first matching rule decides next,
+/// and only hosts missing from the allowlist reach the decider.
 pub(crate) async fn evaluate_host_policy(
     state: &NetworkProxyState,
     decider: Option<&Arc<dyn NetworkPolicyDecider>>,
     request: &NetworkPolicyRequest,
+    target: RuleTarget<'_>,
 ) -> Result<NetworkDecision> {
-    match state.host_blocked(&request.host, request.port).await? {
+    let host_decision = state.host_blocked(&request.host, request.port).await?;
+    if let HostBlockDecision::Blocked(
+        reason @ (HostBlockReason::Denied | HostBlockReason::NotAllowedLocal),
+    ) = host_decision
+    {
+        return Ok(NetworkDecision::deny(reason.as_str()));
+    }
+    if let Some(decision) = state.rule_decision(&request.host, target).await? {
+        return Ok(decision);
+    }
+    match host_decision {
         HostBlockDecision::Allowed => Ok(NetworkDecision::Allow),
         HostBlockDecision::Blocked(HostBlockReason::NotAllowed) => {
             if let Some(decider) = decider {
@@ -128,6 +150,7 @@ mod tests {
     use super::*;
 
     use crate::config::NetworkPolicy;
+    use crate::config::NetworkRuleDecision;
     use crate::reasons::REASON_DENIED;
     use crate::reasons::REASON_NOT_ALLOWED_LOCAL;
     use crate::state::network_proxy_state_for_policy;
@@ -136,6 +159,11 @@ mod tests {
     use std::sync::atomic::AtomicUsize;
     use std::sync::atomic::Ordering;
 
+    const GET_ROOT: RuleTarget<'static> = RuleTarget::Request {
+        method: "GET",
+        path: "/",
+    };
+
     #[tokio::test]
     async fn evaluate_host_policy_invokes_decider_for_not_allowed() {
         let state = network_proxy_state_for_policy(NetworkPolicy::default());
@@ -155,12 +183,13 @@ mod tests {
             host: "example.com".to_string(),
             port: 80,
             client_addr: None,
+            client_token: None,
             method: Some("GET".to_string()),
             command: None,
             exec_policy_hint: None,
         });
 
-        let decision = evaluate_host_policy(&state, Some(&decider), &request)
+        let decision = evaluate_host_policy(&state, Some(&decider), &request, GET_ROOT)
             .await
             .unwrap();
         assert_eq!(decision, NetworkDecision::Allow);
@@ -188,12 +217,13 @@ mod tests {
             host: "blocked.com".to_string(),
             port: 80,
             client_addr: None,
+            client_token: None,
             method: Some("GET".to_string()),
             command: None,
             exec_policy_hint: None,
         });
 
-        let decision = evaluate_host_policy(&state, Some(&decider), &request)
+        let decision = evaluate_host_policy(&state, Some(&decider), &request, GET_ROOT)
             .await
             .unwrap();
         assert_eq!(
@@ -226,12 +256,13 @@ mod tests {
             host: "127.0.0.1".to_string(),
             port: 80,
             client_addr: None,
+            client_token: None,
             method: Some("GET".to_string()),
             command: None,
             exec_policy_hint: None,
         });
 
-        let decision = evaluate_host_policy(&state, Some(&decider), &request)
+        let decision = evaluate_host_policy(&state, Some(&decider), &request, GET_ROOT)
             .await
             .unwrap();
         assert_eq!(
@@ -242,4 +273,80 @@ mod tests {
         );
         assert_eq!(calls.load(Ordering::SeqCst), 0);
     }
+
+    #[tokio::test]
+    async fn evaluate_host_policy_applies_rules_between_denylist_and_allowlist() {
+        let rule = |host: &str, path: Option<&str>, decision| crate::config::NetworkRule {
+            host: host.to_string(),
+            path: path.map(str::to_string),
+            methods: Vec::new(),
+            decision,
+            reason: None,
+            matches: Vec::new(),
+            not_match: Vec::new(),
+        };
+        let state = network_proxy_state_for_policy(NetworkPolicy {
+            allowed_domains: vec!["github.com".to_string()],
+            denied_domains: vec!["blocked.com".to_string()],
+            rules: vec![
+                rule("blocked.com", None, NetworkRuleDecision::Allow),
+                rule(
+                    "github.com",
+                    Some("/*/archive/*"),
+                    NetworkRuleDecision::Deny,
+                ),
+                rule("npmjs.org", None, NetworkRuleDecision::Allow),
+            ],
+            ..NetworkPolicy::default()
+        });
+        let calls = Arc::new(AtomicUsize::new(0));
+        let decider: Arc<dyn NetworkPolicyDecider> = Arc::new({
+            let calls = calls.clone();
+            move |_req| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { NetworkDecision::deny("declined") }
+            }
+        });
+        let evaluate = |host: &str, target| {
+            let request = NetworkPolicyRequest::new(NetworkPolicyRequestArgs {
+                protocol: NetworkProtocol::Http,
+                host: host.to_string(),
+                port: 80,
+                client_addr: None,
+                client_token: None,
+                method: Some("GET".to_string()),
+                command: None,
+                exec_policy_hint: None,
+            });
+            let state = &state;
+            let decider = &decider;
+            async move {
+                evaluate_host_policy(state, Some(decider), &request, target)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        assert_eq!(
+            evaluate("blocked.com", GET_ROOT).await,
+            NetworkDecision::deny(REASON_DENIED)
+        );
+        let archive = RuleTarget::Request {
+            method: "GET",
+            path: "/openai/codex/archive/main.zip",
+        };
+        assert_eq!(
+            evaluate("github.com", archive).await,
+            NetworkDecision::deny("rule_denied: rules[1]")
+        );
+        assert_eq!(
+            evaluate("github.com", GET_ROOT).await,
+            NetworkDecision::Allow
+        );
+        assert_eq!(
+            evaluate("npmjs.org", GET_ROOT).await,
+            NetworkDecision::Allow
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
 }
@@ -1,28 +1,30 @@
 use crate::admin;
+use crate::cassette::Cassette;
 use crate::config;
 use crate::http_proxy;
+use crate::mitm::MitmState;
 use crate::network_policy::NetworkPolicyDecider;
 use crate::runtime::unix_socket_permissions_supported;
 use crate::socks5;
 use crate::state::NetworkProxyState;
 use anyhow::Context;
 use anyhow::Result;
-use clap::Parser;
+use rama_tcp::server::TcpListener;
 use std::net::SocketAddr;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::task::JoinHandle;
 use tracing::warn;
 
-#[derive(Debug, Clone, Parser)]
-#[command(name = "codex-network-proxy", about = "Codex network sandbox proxy")]
-pub struct Args {}
-
 #[derive(Clone, Default)]
 pub struct NetworkProxyBuilder {
     state: Option<Arc<NetworkProxyState>>,
     http_addr: Option<SocketAddr>,
+    socks_addr: Option<SocketAddr>,
     admin_addr: Option<SocketAddr>,
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    ca_dir: Option<PathBuf>,
 }
 
 impl NetworkProxyBuilder {
@@ -36,6 +38,11 @@ impl NetworkProxyBuilder {
         self
     }
 
+    pub fn socks_addr(mut self, addr: SocketAddr) -> Self {
+        self.socks_addr = Some(addr);
+        self
+    }
+
     pub fn admin_addr(mut self, addr: SocketAddr) -> Self {
         self.admin_addr = Some(addr);
         self
@@ -54,20 +61,41 @@ impl NetworkProxyBuilder {
         self
     }
 
+    /// Directory holding the CA used to intercept HTTPS when `mitm` is enabled. The CA is
+    /// generated there on first use.
+    pub fn ca_dir(mut self, dir: PathBuf) -> Self {
+        self.ca_dir = Some(dir);
+        self
+    }
+
     pub async fn build(self) -> Result<NetworkProxy> {
-        let state = match self.state {
-            Some(state) => state,
-            None => Arc::new(NetworkProxyState::new().await?),
-        };
+        let state = self
+            .state
+            .context("network proxy state is required (see `NetworkProxyState::new`)")?;
         let current_cfg = state.current_cfg().await?;
         let runtime = config::resolve_runtime(&current_cfg)?;
         // Reapply bind clamping for caller overrides so unix-socket proxying stays loopback-only.
         let (http_addr, socks_addr, admin_addr) = config::clamp_bind_addrs(
             self.http_addr.unwrap_or(runtime.http_addr),
-            runtime.socks_addr,
+            self.socks_addr.unwrap_or(runtime.socks_addr),
             self.admin_addr.unwrap_or(runtime.admin_addr),
             &current_cfg.network_proxy,
         );
+        let mitm = if current_cfg.network_proxy.mitm {
+            let ca_dir = self
+                .ca_dir
+                .context("`network_proxy.mitm` needs a CA directory (see `ca_dir`)")?;
+            Some(Arc::new(MitmState::load_or_create(&ca_dir)?))
+        } else {
+            None
+        };
+        let cassette = current_cfg
+            .network_proxy
+            .cassette
+            .as_ref()
+            .map(Cassette::open)
+            .transpose()?
+            .map(Arc::new);
 
         Ok(NetworkProxy {
             state,
@@ -75,6 +103,8 @@ impl NetworkProxyBuilder {
             socks_addr,
             admin_addr,
             policy_decider: self.policy_decider,
+            mitm,
+            cassette,
         })
     }
 }
@@ -86,6 +116,8 @@ pub struct NetworkProxy {
     socks_addr: SocketAddr,
     admin_addr: SocketAddr,
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
+    mitm: Option<Arc<MitmState>>,
+    cassette: Option<Arc<Cassette>>,
 }
 
 impl NetworkProxy {
@@ -104,24 +136,53 @@ impl NetworkProxy {
             warn!("allowUnixSockets is macOS-only; requests will be rejected on this platform");
         }
 
+        // Bind every listener before spawning the servers so callers learn about port conflicts
+        // right away and can read back the ports picked for `:0` addresses.
+        let (http_listener, http_addr) = bind(self.http_addr, "HTTP proxy").await?;
+        // SOCKS5 only sees tunnels, which a replay cassette cannot answer.
+        let replaying = self.cassette.as_deref().is_some_and(Cassette::replaying);
+        if replaying && current_cfg.network_proxy.enable_socks5 {
+            warn!("not starting the SOCKS5 proxy; a replay cassette cannot answer SOCKS5 traffic");
+        }
+        let socks_listener = if current_cfg.network_proxy.enable_socks5 && !replaying {
+            Some(bind(self.socks_addr, "SOCKS5 proxy").await?)
+        } else {
+            None
+        };
+        let (admin_listener, admin_addr) = bind(self.admin_addr, "admin API").await?;
+
         let http_task = tokio::spawn(http_proxy::run_http_proxy(
             self.state.clone(),
-            self.http_addr,
+            http_listener,
+            http_addr,
             self.policy_decider.clone(),
+            self.mitm.clone(),
+            self.cassette.clone(),
         ));
-        let socks_task = if current_cfg.network_proxy.enable_socks5 {
-            Some(tokio::spawn(socks5::run_socks5(
+        let socks_addr = socks_listener.as_ref().map(|(_, addr)| *addr);
+        let socks_task = socks_listener.map(|(listener, addr)| {
+            tokio::spawn(socks5::run_socks5(
                 self.state.clone(),
-                self.socks_addr,
+                listener,
+                addr,
                 self.policy_decider.clone(),
                 current_cfg.network_proxy.enable_socks5_udp,
-            )))
-        } else {
-            None
-        };
-        let admin_task = tokio::spawn(admin::run_admin_api(self.state.clone(), self.admin_addr));
+            ))
+        });
+        let admin_task = tokio::spawn(admin::run_admin_api(
+            self.state.clone(),
+            admin_listener,
+            admin_addr,
+        ));
 
         Ok(NetworkProxyHandle {
+            http_addr: Some(http_addr),
+            socks_addr,
+            admin_addr: Some(admin_addr),
+            ca_cert_path: self
+                .mitm
+                .as_ref()
+                .map(|mitm| mitm.ca_cert_path().to_path_buf()),
             http_task: Some(http_task),
             socks_task,
             admin_task: Some(admin_task),
@@ -130,7 +191,28 @@ impl NetworkProxy {
     }
 }
 
+async fn bind(addr: SocketAddr, name: &str) -> Result<(TcpListener, SocketAddr)> {
+    let listener = TcpListener::build()
+        .bind(addr)
+        .await
+        // Rama's `BoxError` is a `Box<dyn Error + Send + Sync>` without an explicit `'static`
+        // lifetime bound, which means it doesn't satisfy `anyhow::Context`'s `StdError` constraint.
+        // Wrap it in Rama's `OpaqueError` so we can preserve the original error as a source and
+        // still use `anyhow` for chaining.
+        .map_err(rama_core::error::OpaqueError::from)
+        .map_err(anyhow::Error::from)
+        .with_context(|| format!("bind {name}: {addr}"))?;
+    let local_addr = listener
+        .local_addr()
+        .with_context(|| format!("read {name} address"))?;
+    Ok((listener, local_addr))
+}
+
 pub struct NetworkProxyHandle {
+    http_addr: Option<SocketAddr>,
+    socks_addr: Option<SocketAddr>,
+    admin_addr: Option<SocketAddr>,
+    ca_cert_path: Option<PathBuf>,
     http_task: Option<JoinHandle<Result<()>>>,
     socks_task: Option<JoinHandle<Result<()>>>,
     admin_task: Option<JoinHandle<Result<()>>>,
@@ -138,8 +220,13 @@ pub struct NetworkProxyHandle {
 }
 
 impl NetworkProxyHandle {
-    fn noop() -> Self {
+    /// A handle without listeners, as returned by `run` when the proxy is disabled.
+    pub fn noop() -> Self {
         Self {
+            http_addr: None,
+            socks_addr: None,
+            admin_addr: None,
+            ca_cert_path: None,
             http_task: Some(tokio::spawn(async { Ok(()) })),
             socks_task: None,
             admin_task: Some(tokio::spawn(async { Ok(()) })),
@@ -147,6 +234,26 @@ impl NetworkProxyHandle {
         }
     }
 
+    /// Address the HTTP proxy listens on, or `None` when the proxy is disabled.
+    pub fn http_addr(&self) -> Option<SocketAddr> {
+        self.http_addr
+    }
+
+    /// Address the SOCKS5 proxy listens on, or `None` when it is disabled.
+    pub fn socks_addr(&self) -> Option<SocketAddr> {
+        self.socks_addr
+    }
+
+    /// Address the admin API listens on, or `None` when the proxy is disabled.
+    pub fn admin_addr(&self) -> Option<SocketAddr> {
+        self.admin_addr
+    }
+
+    /// The CA certificate clients must trust, or `None` when HTTPS is not intercepted.
+    pub fn ca_cert_path(&self) -> Option<&Path> {
+        self.ca_cert_path.as_deref()
+    }
+
     pub async fn wait(mut self) -> Result<()> {
         let http_task = self.http_task.take().context("missing http proxy task")?;
         let admin_task = self.admin_task.take().context("missing admin proxy task")?;
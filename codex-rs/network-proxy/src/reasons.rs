@@ -1,6 +1,15 @@
 pub(crate) const REASON_DENIED: &str = "denied";
+/// Intercepted requests whose `Host` differs from the host of their CONNECT tunnel.
+pub(crate) const REASON_HOST_MISMATCH: &str = "host_mismatch";
+/// Request paths with malformed percent-escapes, when a path rule applies to the host.
+pub(crate) const REASON_INVALID_PATH: &str = "invalid_path";
 pub(crate) const REASON_METHOD_NOT_ALLOWED: &str = "method_not_allowed";
 pub(crate) const REASON_NOT_ALLOWED: &str = "not_allowed";
 pub(crate) const REASON_NOT_ALLOWED_LOCAL: &str = "not_allowed_local";
 pub(crate) const REASON_POLICY_DENIED: &str = "policy_denied";
 pub(crate) const REASON_PROXY_DISABLED: &str = "proxy_disabled";
+/// Requests a replay cassette has no response for, including tunnels it cannot look into.
+pub(crate) const REASON_REPLAY_MISS: &str = "replay_miss";
+/// Prefix of the reason for requests a `network_proxy.policy.rules` entry denies, followed by
+/// `: ` and the rule's own reason.
+pub(crate) const REASON_RULE_DENIED: &str = "rule_denied";
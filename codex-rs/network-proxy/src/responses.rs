@@ -2,6 +2,8 @@ use crate::reasons::REASON_DENIED;
 use crate::reasons::REASON_METHOD_NOT_ALLOWED;
 use crate::reasons::REASON_NOT_ALLOWED;
 use crate::reasons::REASON_NOT_ALLOWED_LOCAL;
+use crate::reasons::REASON_REPLAY_MISS;
+use crate::reasons::REASON_RULE_DENIED;
 use rama_http::Body;
 use rama_http::Response;
 use rama_http::StatusCode;
@@ -34,16 +36,24 @@ pub fn json_response<T: Serialize>(value: &T) -> Response {
         })
 }
 
-pub fn blocked_header_value(reason: &str) -> &'static str {
+pub fn blocked_header_value(reason: &str) -> String {
+    if let Some(detail) = rule_denied_detail(reason) {
+        return format!("blocked-by-rule: {detail}");
+    }
     match reason {
         REASON_NOT_ALLOWED | REASON_NOT_ALLOWED_LOCAL => "blocked-by-allowlist",
         REASON_DENIED => "blocked-by-denylist",
         REASON_METHOD_NOT_ALLOWED => "blocked-by-method-policy",
+        REASON_REPLAY_MISS => "blocked-by-replay-cassette",
         _ => "blocked-by-policy",
     }
+    .to_string()
 }
 
-pub fn blocked_message(reason: &str) -> &'static str {
+pub fn blocked_message(reason: &str) -> String {
+    if let Some(detail) = rule_denied_detail(reason) {
+        return format!("Codex blocked this request: denied by network rule ({detail}).");
+    }
     match reason {
         REASON_NOT_ALLOWED => "Codex blocked this request: domain not in allowlist.",
         REASON_NOT_ALLOWED_LOCAL => {
@@ -53,8 +63,15 @@ pub fn blocked_message(reason: &str) -> &'static str {
         REASON_METHOD_NOT_ALLOWED => {
             "Codex blocked this request: method not allowed in limited mode."
         }
+        REASON_REPLAY_MISS => "Codex blocked this request: no recorded response to replay.",
         _ => "Codex blocked this request by network policy.",
     }
+    .to_string()
+}
+
+/// The rule's reason from a `rule_denied: <reason>` block reason.
+fn rule_denied_detail(reason: &str) -> Option<&str> {
+    reason.strip_prefix(REASON_RULE_DENIED)?.strip_prefix(": ")
 }
 
 pub fn blocked_text_response(reason: &str) -> Response {
@@ -0,0 +1,511 @@
+//! Host, path and method rules from `network_proxy.policy.rules`.
+//!
+//! Rules are checked in the order they are written and the first one that matches decides. They
+//! run after `denied_domains` and the local-address checks and before `allowed_domains`, so a rule
+//! can narrow or widen the domain lists but never reopen a denied or local host.
+
+use crate::config::NetworkRule;
+use crate::config::NetworkRuleDecision;
+use crate::network_policy::NetworkDecision;
+use crate::policy::Host;
+use crate::policy::compile_globset;
+use crate::reasons::REASON_INVALID_PATH;
+use crate::reasons::REASON_RULE_DENIED;
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use anyhow::ensure;
+use globset::GlobBuilder;
+use globset::GlobMatcher;
+use globset::GlobSet;
+
+/// What the proxy can see of the traffic a rule is checked against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RuleTarget<'a> {
+    /// A plain HTTP request, or a request inside an intercepted HTTPS tunnel.
+    Request { method: &'a str, path: &'a str },
+    /// A CONNECT tunnel or SOCKS5 connection. Only the host is known. When `inspected`, the
+    /// requests inside it are checked against the rules again once decrypted.
+    Tunnel { inspected: bool },
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct NetworkRules {
+    rules: Vec<CompiledRule>,
+}
+
+#[derive(Clone, Debug)]
+struct CompiledRule {
+    hosts: GlobSet,
+    path: Option<GlobMatcher>,
+    methods: Vec<String>,
+    decision: NetworkRuleDecision,
+    /// Label reported when the rule blocks: its `reason`, or its position in the list.
+    label: String,
+}
+
+impl NetworkRules {
+    /// Compile the rules and check their `match` / `not_match` examples.
+    pub(crate) fn compile(rules: &[NetworkRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                CompiledRule::compile(index, rule)
+                    .with_context(|| format!("invalid network_proxy.policy.rules[{index}]"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// The decision of the first rule that applies to `host`, or `None` when no rule does.
+    pub(crate) fn evaluate(&self, host: &str, target: RuleTarget<'_>) -> Option<NetworkDecision> {
+        let host = Host::parse(host).ok()?;
+        let normalized_path = match target {
+            RuleTarget::Request { path, .. } => normalize_path(path),
+            RuleTarget::Tunnel { .. } => None,
+        };
+        self.rules.iter().find_map(|rule| {
+            if !rule.hosts.is_match(host.as_str()) {
+                return None;
+            }
+            match target {
+                RuleTarget::Request { method, .. } => {
+                    let Some(path) = normalized_path.as_deref() else {
+                        // A path that cannot be normalized could slip past the glob either way.
+                        return rule
+                            .path
+                            .is_some()
+                            .then(|| NetworkDecision::deny(REASON_INVALID_PATH));
+                    };
+                    rule.matches_request(method, path).then(|| rule.decision())
+                }
+                RuleTarget::Tunnel { inspected } => {
+                    if !rule.is_constrained() {
+                        return Some(rule.decision());
+                    }
+                    // A tunnel hides the methods and paths a constrained rule needs. Allow rules
+                    // cannot vouch for it, and deny rules close it unless the requests inside will
+                    // be checked once decrypted.
+                    match rule.decision {
+                        NetworkRuleDecision::Deny if !inspected => Some(rule.decision()),
+                        NetworkRuleDecision::Deny | NetworkRuleDecision::Allow => None,
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl CompiledRule {
+    fn compile(index: usize, rule: &NetworkRule) -> Result<Self> {
+        ensure!(!rule.host.trim().is_empty(), "`host` is empty");
+        let hosts = compile_globset(std::slice::from_ref(&rule.host))?;
+        let path = rule
+            .path
+            .as_deref()
+            .map(|path| {
+                ensure!(path.starts_with('/'), "`path` must start with `/`: {path}");
+                Ok(GlobBuilder::new(path)
+                    .literal_separator(false)
+                    .build()
+                    .with_context(|| format!("invalid `path` glob: {path}"))?
+                    .compile_matcher())
+            })
+            .transpose()?;
+        let methods = rule
+            .methods
+            .iter()
+            .map(|method| {
+                ensure!(
+                    !method.is_empty() && method.bytes().all(|b| b.is_ascii_alphabetic()),
+                    "invalid method: {method:?}"
+                );
+                Ok(method.to_ascii_uppercase())
+            })
+            .collect::<Result<_>>()?;
+        let label = match &rule.reason {
+            Some(reason) => {
+                // The reason ends up in a response header.
+                ensure!(
+                    !reason.trim().is_empty()
+                        && reason.chars().all(|c| c == ' ' || c.is_ascii_graphic()),
+                    "`reason` must be non-empty printable ASCII: {reason:?}"
+                );
+                reason.trim().to_string()
+            }
+            None => format!("rules[{index}]"),
+        };
+        let compiled = Self {
+            hosts,
+            path,
+            methods,
+            decision: rule.decision,
+            label,
+        };
+        compiled.validate_examples(rule)?;
+        Ok(compiled)
+    }
+
+    fn validate_examples(&self, rule: &NetworkRule) -> Result<()> {
+        for example in &rule.matches {
+            if !self.matches_example(example)? {
+                bail!("`match` example `{example}` does not match the rule");
+            }
+        }
+        for example in &rule.not_match {
+            if self.matches_example(example)? {
+                bail!("`not_match` example `{example}` matches the rule");
+            }
+        }
+        Ok(())
+    }
+
+    fn matches_example(&self, example: &str) -> Result<bool> {
+        let RequestExample { method, host, path } = RequestExample::parse(example)?;
+        let host = Host::parse(&host).with_context(|| format!("invalid example: {example}"))?;
+        let path =
+            normalize_path(&path).with_context(|| format!("invalid example path: {example}"))?;
+        Ok(self.hosts.is_match(host.as_str()) && self.matches_request(&method, &path))
+    }
+
+    /// Whether the rule covers a request; `path` must come from [`normalize_path`].
+    fn matches_request(&self, method: &str, path: &str) -> bool {
+        let method_matches = self.methods.is_empty()
+            || self
+                .methods
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(method));
+        let path_matches = self.path.as_ref().is_none_or(|glob| glob.is_match(path));
+        method_matches && path_matches
+    }
+
+    fn is_constrained(&self) -> bool {
+        self.path.is_some() || !self.methods.is_empty()
+    }
+
+    fn decision(&self) -> NetworkDecision {
+        match self.decision {
+            NetworkRuleDecision::Allow => NetworkDecision::Allow,
+            NetworkRuleDecision::Deny => {
+                NetworkDecision::deny(format!("{REASON_RULE_DENIED}: {}", self.label))
+            }
+        }
+    }
+}
+
+/// A `match` / `not_match` example: `[METHOD ]host[/path]`, where the host may carry an
+/// `http://` or `https://` prefix. The method defaults to GET and the path to `/`.
+struct RequestExample {
+    method: String,
+    host: String,
+    path: String,
+}
+
+impl RequestExample {
+    fn parse(example: &str) -> Result<Self> {
+        let example = example.trim();
+        let (method, target) = match example.split_once(char::is_whitespace) {
+            Some((method, target)) => (method.to_ascii_uppercase(), target.trim()),
+            None => ("GET".to_string(), example),
+        };
+        let target = target
+            .strip_prefix("https://")
+            .or_else(|| target.strip_prefix("http://"))
+            .unwrap_or(target);
+        let (host, path) = match target.find('/') {
+            Some(index) => (&target[..index], &target[index..]),
+            None => (target, "/"),
+        };
+        if host.is_empty() {
+            bail!("invalid example: {example}");
+        }
+        Ok(Self {
+            method,
+            host: host.to_string(),
+            path: path.to_string(),
+        })
+    }
+}
+
+fn path_without_query(path: &str) -> &str {
+    path.split_once('?').map_or(path, |(path, _)| path)
+}
+
+/// The path of a request as rules see it: without the query, with percent-escapes of unreserved
+/// characters decoded and `.`/`..` segments resolved (RFC 3986, sections 6.2.2 and 5.2.4), so
+/// `/%61rchive` and `/x/../archive` both read `/archive`. Other escapes stay encoded, in upper
+/// case. `None` when an escape is malformed.
+fn normalize_path(path: &str) -> Option<String> {
+    let raw = path_without_query(path).as_bytes();
+    let mut decoded = Vec::with_capacity(raw.len());
+    let mut index = 0;
+    while index < raw.len() {
+        if raw[index] != b'%' {
+            decoded.push(raw[index]);
+            index += 1;
+            continue;
+        }
+        let hex = raw.get(index + 1..index + 3)?;
+        if !hex.iter().all(u8::is_ascii_hexdigit) {
+            return None;
+        }
+        let byte = u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            decoded.push(byte);
+        } else {
+            decoded.push(b'%');
+            decoded.extend(hex.to_ascii_uppercase());
+        }
+        index += 3;
+    }
+    // Only ASCII sequences were replaced, so the bytes are still UTF-8.
+    let decoded = String::from_utf8(decoded).ok()?;
+    let Some(relative) = decoded.strip_prefix('/') else {
+        return Some(decoded);
+    };
+
+    let mut segments = Vec::new();
+    let mut ends_in_dot_segment = false;
+    for segment in relative.split('/') {
+        ends_in_dot_segment = matches!(segment, "." | "..");
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    // `/a/.` and `/a/b/..` both name the directory `/a/`.
+    if ends_in_dot_segment {
+        segments.push("");
+    }
+    Some(format!("/{}", segments.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn rule(
+        host: &str,
+        path: Option<&str>,
+        methods: &[&str],
+        decision: NetworkRuleDecision,
+    ) -> NetworkRule {
+        NetworkRule {
+            host: host.to_string(),
+            path: path.map(str::to_string),
+            methods: methods.iter().map(ToString::to_string).collect(),
+            decision,
+            reason: None,
+            matches: Vec::new(),
+            not_match: Vec::new(),
+        }
+    }
+
+    fn request<'a>(method: &'a str, path: &'a str) -> RuleTarget<'a> {
+        RuleTarget::Request { method, path }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = NetworkRules::compile(&[
+            NetworkRule {
+                reason: Some("no archive downloads".to_string()),
+                ..rule(
+                    "github.com",
+                    Some("/*/archive/*"),
+                    &[],
+                    NetworkRuleDecision::Deny,
+                )
+            },
+            rule("github.com", None, &[], NetworkRuleDecision::Allow),
+            rule(
+                "api.internal",
+                Some("/v1/search"),
+                &["post"],
+                NetworkRuleDecision::Allow,
+            ),
+            rule("api.internal", None, &[], NetworkRuleDecision::Deny),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            rules.evaluate(
+                "github.com",
+                request("GET", "/openai/codex/archive/main.zip")
+            ),
+            Some(NetworkDecision::deny("rule_denied: no archive downloads"))
+        );
+        assert_eq!(
+            rules.evaluate("GitHub.com", request("GET", "/openai/codex?tab=readme")),
+            Some(NetworkDecision::Allow)
+        );
+        assert_eq!(
+            rules.evaluate("api.internal", request("POST", "/v1/search?q=1")),
+            Some(NetworkDecision::Allow)
+        );
+        assert_eq!(
+            rules.evaluate("api.internal", request("POST", "/v1/index")),
+            Some(NetworkDecision::deny("rule_denied: rules[3]"))
+        );
+        assert_eq!(rules.evaluate("example.com", request("GET", "/")), None);
+    }
+
+    #[test]
+    fn paths_are_normalized_before_matching() {
+        let rules = NetworkRules::compile(&[
+            rule(
+                "github.com",
+                Some("/*/archive/*"),
+                &[],
+                NetworkRuleDecision::Deny,
+            ),
+            rule("github.com", None, &[], NetworkRuleDecision::Allow),
+        ])
+        .unwrap();
+        let archive_denied = Some(NetworkDecision::deny("rule_denied: rules[0]"));
+
+        for path in [
+            "/o/r/%61rchive/x",
+            "/o/r/%61%72%63%68%69%76%65/x",
+            "/o/r/x/../archive/x",
+            "/o/r/./archive/x",
+            "/o/r/x/%2e%2E/archive/x",
+        ] {
+            assert_eq!(
+                rules.evaluate("github.com", request("GET", path)),
+                archive_denied,
+                "{path}"
+            );
+        }
+        assert_eq!(
+            rules.evaluate("github.com", request("GET", "/o/r/%2Farchive")),
+            Some(NetworkDecision::Allow)
+        );
+        assert_eq!(
+            rules.evaluate("github.com", request("GET", "/o/r/%zzarchive/x")),
+            Some(NetworkDecision::deny("invalid_path"))
+        );
+    }
+
+    #[test]
+    fn normalize_path_decodes_unreserved_escapes_and_resolves_dot_segments() {
+        for (path, expected) in [
+            ("/", Some("/")),
+            ("/a/b?x=/../c", Some("/a/b")),
+            ("/%7Euser/%41%2f%3A", Some("/~user/A%2F%3A")),
+            ("/a/./b/../c", Some("/a/c")),
+            ("/a/b/..", Some("/a/")),
+            ("/a/.", Some("/a/")),
+            ("/../../a", Some("/a")),
+            ("/a%2E%2E/b", Some("/a../b")),
+            ("/caf\u{e9}/%2e%2e", Some("/")),
+            ("/a%", None),
+            ("/a%4", None),
+            ("/a%+1", None),
+        ] {
+            assert_eq!(normalize_path(path).as_deref(), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn tunnels_only_see_host_rules() {
+        let rules = NetworkRules::compile(&[
+            rule(
+                "github.com",
+                Some("/*/archive/*"),
+                &[],
+                NetworkRuleDecision::Deny,
+            ),
+            rule(
+                "registry.npmjs.org",
+                None,
+                &["GET"],
+                NetworkRuleDecision::Allow,
+            ),
+            rule("**.npmjs.org", None, &[], NetworkRuleDecision::Deny),
+        ])
+        .unwrap();
+
+        let opaque = RuleTarget::Tunnel { inspected: false };
+        let inspected = RuleTarget::Tunnel { inspected: true };
+        assert_eq!(
+            rules.evaluate("github.com", opaque),
+            Some(NetworkDecision::deny("rule_denied: rules[0]"))
+        );
+        assert_eq!(rules.evaluate("github.com", inspected), None);
+        assert_eq!(
+            rules.evaluate("registry.npmjs.org", opaque),
+            Some(NetworkDecision::deny("rule_denied: rules[2]"))
+        );
+        assert_eq!(
+            rules.evaluate("registry.npmjs.org", inspected),
+            Some(NetworkDecision::deny("rule_denied: rules[2]"))
+        );
+    }
+
+    #[test]
+    fn examples_are_checked_at_compile_time() {
+        let valid = NetworkRule {
+            matches: vec![
+                "POST api.internal/v1/search".to_string(),
+                "post https://api.internal/v1/search?q=codex".to_string(),
+            ],
+            not_match: vec![
+                "api.internal/v1/search".to_string(),
+                "POST api.internal/v1/index".to_string(),
+                "POST other.internal/v1/search".to_string(),
+            ],
+            ..rule(
+                "api.internal",
+                Some("/v1/search"),
+                &["POST"],
+                NetworkRuleDecision::Allow,
+            )
+        };
+        NetworkRules::compile(std::slice::from_ref(&valid)).unwrap();
+
+        let bad_match = NetworkRule {
+            matches: vec!["GET api.internal/v1/search".to_string()],
+            ..valid.clone()
+        };
+        let err = NetworkRules::compile(&[bad_match]).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "invalid network_proxy.policy.rules[0]: `match` example `GET api.internal/v1/search` does not match the rule"
+        );
+
+        let bad_not_match = NetworkRule {
+            not_match: vec!["POST api.internal/v1/search".to_string()],
+            ..valid
+        };
+        let err = NetworkRules::compile(&[bad_not_match]).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "invalid network_proxy.policy.rules[0]: `not_match` example `POST api.internal/v1/search` matches the rule"
+        );
+    }
+
+    #[test]
+    fn compile_rejects_invalid_rules() {
+        for invalid in [
+            rule(" ", None, &[], NetworkRuleDecision::Deny),
+            rule("example.com", Some("v1/*"), &[], NetworkRuleDecision::Deny),
+            rule("example.com", None, &["GET /"], NetworkRuleDecision::Deny),
+            NetworkRule {
+                reason: Some("line\nbreak".to_string()),
+                ..rule("example.com", None, &[], NetworkRuleDecision::Deny)
+            },
+        ] {
+            assert!(
+                NetworkRules::compile(std::slice::from_ref(&invalid)).is_err(),
+                "{invalid:?}"
+            );
+        }
+    }
+}
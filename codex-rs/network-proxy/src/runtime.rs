@@ -1,5 +1,8 @@
+use crate::audit::NetworkAuditEvent;
+use crate::audit::NetworkAuditSink;
 use crate::config::NetworkMode;
 use crate::config::NetworkProxyConfig;
+use crate::network_policy::NetworkDecision;
 use crate::policy::Host;
 use crate::policy::is_loopback_host;
 use crate::policy::is_non_public_ip;
@@ -7,6 +10,9 @@ use crate::policy::normalize_host;
 use crate::reasons::REASON_DENIED;
 use crate::reasons::REASON_NOT_ALLOWED;
 use crate::reasons::REASON_NOT_ALLOWED_LOCAL;
+use crate::rules::NetworkRules;
+use crate::rules::RuleTarget;
+use crate::state::ConfigLoader;
 use crate::state::NetworkProxyConstraints;
 use crate::state::build_config_state;
 use crate::state::validate_policy_against_constraints;
@@ -67,6 +73,8 @@ pub struct BlockedRequest {
     pub host: String,
     pub reason: String,
     pub client: Option<String>,
+    /// See [`crate::NetworkPolicyRequest::client_token`].
+    pub client_token: Option<String>,
     pub method: Option<String>,
     pub mode: Option<NetworkMode>,
     pub protocol: String,
@@ -77,6 +85,7 @@ pub struct BlockedRequestArgs {
     pub host: String,
     pub reason: String,
     pub client: Option<String>,
+    pub client_token: Option<String>,
     pub method: Option<String>,
     pub mode: Option<NetworkMode>,
     pub protocol: String,
@@ -88,6 +97,7 @@ impl BlockedRequest {
             host,
             reason,
             client,
+            client_token,
             method,
             mode,
             protocol,
@@ -96,6 +106,7 @@ impl BlockedRequest {
             host,
             reason,
             client,
+            client_token,
             method,
             mode,
             protocol,
@@ -104,11 +115,22 @@ impl BlockedRequest {
     }
 }
 
+/// A request the proxy let through, see [`NetworkProxyState::record_allowed`].
+pub(crate) struct AllowedRequestArgs {
+    pub(crate) host: String,
+    pub(crate) client_token: Option<String>,
+    pub(crate) method: Option<String>,
+    pub(crate) protocol: String,
+    pub(crate) bytes_sent: Option<u64>,
+    pub(crate) bytes_received: Option<u64>,
+}
+
 #[derive(Clone)]
 pub(crate) struct ConfigState {
     pub(crate) config: NetworkProxyConfig,
     pub(crate) allow_set: GlobSet,
     pub(crate) deny_set: GlobSet,
+    pub(crate) rules: NetworkRules,
     pub(crate) constraints: NetworkProxyConstraints,
     pub(crate) layer_mtimes: Vec<LayerMtime>,
     pub(crate) cfg_path: PathBuf,
@@ -131,6 +153,8 @@ impl LayerMtime {
 #[derive(Clone)]
 pub struct NetworkProxyState {
     state: Arc<RwLock<ConfigState>>,
+    loader: Arc<dyn ConfigLoader>,
+    audit: Option<Arc<dyn NetworkAuditSink>>,
 }
 
 impl std::fmt::Debug for NetworkProxyState {
@@ -142,13 +166,46 @@ impl std::fmt::Debug for NetworkProxyState {
 }
 
 impl NetworkProxyState {
-    pub async fn new() -> Result<Self> {
-        let cfg_state = build_config_state().await?;
+    pub async fn new(loader: Arc<dyn ConfigLoader>) -> Result<Self> {
+        let cfg_state = build_config_state(loader.as_ref()).await?;
         Ok(Self {
             state: Arc::new(RwLock::new(cfg_state)),
+            loader,
+            audit: None,
         })
     }
 
+    /// Report every allowed and blocked request to `sink`.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn NetworkAuditSink>) -> Self {
+        self.audit = Some(sink);
+        self
+    }
+
+    /// Report an allowed request to the audit sink, if any.
+    pub(crate) fn record_allowed(&self, args: AllowedRequestArgs) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        let AllowedRequestArgs {
+            host,
+            client_token,
+            method,
+            protocol,
+            bytes_sent,
+            bytes_received,
+        } = args;
+        audit.record(NetworkAuditEvent {
+            timestamp: unix_timestamp(),
+            protocol,
+            host,
+            method,
+            client_token,
+            decision: NetworkDecision::Allow,
+            bytes_sent,
+            bytes_received,
+        });
+    }
+
     pub async fn current_cfg(&self) -> Result<NetworkProxyConfig> {
         // Callers treat `NetworkProxyState` as a live view of policy. We reload-on-demand so edits to
         // `config.toml` (including Codex-managed writes) take effect without a restart.
@@ -178,7 +235,7 @@ impl NetworkProxyState {
             (guard.config.clone(), guard.cfg_path.clone())
         };
 
-        match build_config_state().await {
+        match build_config_state(self.loader.as_ref()).await {
             Ok(mut new_state) => {
                 // Policy changes are operationally sensitive; logging diffs makes changes traceable
                 // without needing to dump full config blobs (which can include unrelated settings).
@@ -266,6 +323,18 @@ impl NetworkProxyState {
     }
 
     pub async fn record_blocked(&self, entry: BlockedRequest) -> Result<()> {
+        if let Some(audit) = &self.audit {
+            audit.record(NetworkAuditEvent {
+                timestamp: entry.timestamp,
+                protocol: entry.protocol.clone(),
+                host: entry.host.clone(),
+                method: entry.method.clone(),
+                client_token: entry.client_token.clone(),
+                decision: NetworkDecision::deny(entry.reason.clone()),
+                bytes_sent: None,
+                bytes_received: None,
+            });
+        }
         self.reload_if_needed().await?;
         let mut guard = self.state.write().await;
         guard.blocked.push_back(entry);
@@ -330,6 +399,34 @@ impl NetworkProxyState {
         Ok(guard.config.network_proxy.mode.allows_method(method))
     }
 
+    /// The decision of the first `network_proxy.policy.rules` entry that applies, if any.
+    pub(crate) async fn rule_decision(
+        &self,
+        host: &str,
+        target: RuleTarget<'_>,
+    ) -> Result<Option<NetworkDecision>> {
+        self.reload_if_needed().await?;
+        let guard = self.state.read().await;
+        Ok(guard.rules.evaluate(host, target))
+    }
+
+    /// Like [`Self::method_allowed`], but a rule allowing this exact request also lifts the
+    /// method restriction of `limited` mode.
+    pub(crate) async fn request_method_allowed(
+        &self,
+        host: &str,
+        method: &str,
+        path: &str,
+    ) -> Result<bool> {
+        if self.method_allowed(method).await? {
+            return Ok(true);
+        }
+        let decision = self
+            .rule_decision(host, RuleTarget::Request { method, path })
+            .await?;
+        Ok(decision == Some(NetworkDecision::Allow))
+    }
+
     pub async fn allow_upstream_proxy(&self) -> Result<bool> {
         self.reload_if_needed().await?;
         let guard = self.state.read().await;
@@ -425,6 +522,10 @@ fn log_policy_changes(previous: &NetworkProxyConfig, next: &NetworkProxyConfig)
         &previous.network_proxy.policy.denied_domains,
         &next.network_proxy.policy.denied_domains,
     );
+    if previous.network_proxy.policy.rules != next.network_proxy.policy.rules {
+        let count = next.network_proxy.policy.rules.len();
+        info!("network rules updated ({count} rules)");
+    }
 }
 
 fn log_domain_list_changes(list_name: &str, previous: &[String], next: &[String]) {
@@ -499,10 +600,13 @@ pub(crate) fn network_proxy_state_for_policy(
     let deny_set =
         crate::policy::compile_globset(&config.network_proxy.policy.denied_domains).unwrap();
 
+    let rules = NetworkRules::compile(&config.network_proxy.policy.rules).unwrap();
+
     let state = ConfigState {
         config,
         allow_set,
         deny_set,
+        rules,
         constraints: NetworkProxyConstraints::default(),
         layer_mtimes: Vec::new(),
         cfg_path: PathBuf::from("/nonexistent/config.toml"),
@@ -511,6 +615,21 @@ pub(crate) fn network_proxy_state_for_policy(
 
     NetworkProxyState {
         state: Arc::new(RwLock::new(state)),
+        loader: Arc::new(UnreachableConfigLoader),
+        audit: None,
+    }
+}
+
+/// The state built by [`network_proxy_state_for_policy`] tracks no layer files, so it never
+/// reloads.
+#[cfg(test)]
+struct UnreachableConfigLoader;
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl ConfigLoader for UnreachableConfigLoader {
+    async fn load(&self) -> Result<crate::state::ConfigLayers> {
+        anyhow::bail!("test policy state has no config layers")
     }
 }
 
@@ -526,6 +645,56 @@ mod tests {
     use crate::state::validate_policy_against_constraints;
     use pretty_assertions::assert_eq;
 
+    #[tokio::test]
+    async fn audit_sink_sees_allowed_and_blocked_requests() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = {
+            let events = Arc::clone(&events);
+            move |event: NetworkAuditEvent| events.lock().unwrap().push(event)
+        };
+        let state = network_proxy_state_for_policy(NetworkPolicy::default())
+            .with_audit_sink(Arc::new(sink));
+
+        state.record_allowed(AllowedRequestArgs {
+            host: "example.com".to_string(),
+            client_token: Some("call_1".to_string()),
+            method: Some("GET".to_string()),
+            protocol: "http".to_string(),
+            bytes_sent: Some(0),
+            bytes_received: Some(512),
+        });
+        state
+            .record_blocked(BlockedRequest::new(BlockedRequestArgs {
+                host: "evil.example".to_string(),
+                reason: REASON_NOT_ALLOWED.to_string(),
+                client: None,
+                client_token: Some("call_1".to_string()),
+                method: Some("GET".to_string()),
+                mode: None,
+                protocol: "http".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        // Blocked requests still reach `/blocked`.
+        assert_eq!(state.drain_blocked().await.unwrap().len(), 1);
+
+        let events = events.lock().unwrap();
+        let decisions = events
+            .iter()
+            .map(|event| (event.host.as_str(), event.decision.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decisions,
+            vec![
+                ("example.com", NetworkDecision::Allow),
+                ("evil.example", NetworkDecision::deny(REASON_NOT_ALLOWED)),
+            ]
+        );
+        assert_eq!(events[0].bytes_received, Some(512));
+        assert_eq!(events[1].client_token.as_deref(), Some("call_1"));
+    }
+
     #[tokio::test]
     async fn host_blocked_denied_wins_over_allowed() {
         let state = network_proxy_state_for_policy(NetworkPolicy {
@@ -979,6 +1148,166 @@ mod tests {
         assert!(state.is_unix_socket_allowed(&link_s).await.unwrap());
     }
 
+    struct LayersLoader(Vec<crate::state::ConfigLayer>);
+
+    #[async_trait::async_trait]
+    impl ConfigLoader for LayersLoader {
+        async fn load(&self) -> Result<crate::state::ConfigLayers> {
+            let mut effective_config = toml::Value::Table(toml::map::Map::new());
+            for layer in &self.0 {
+                merge_toml(&mut effective_config, &layer.config);
+            }
+            Ok(crate::state::ConfigLayers {
+                effective_config,
+                layers: self.0.clone(),
+                cfg_path: PathBuf::from("/nonexistent/config.toml"),
+            })
+        }
+    }
+
+    fn merge_toml(base: &mut toml::Value, overlay: &toml::Value) {
+        match (base, overlay) {
+            (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+                for (key, value) in overlay {
+                    match base.get_mut(key) {
+                        Some(existing) => merge_toml(existing, value),
+                        None => {
+                            base.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+            (base, overlay) => *base = overlay.clone(),
+        }
+    }
+
+    fn layer(config: &str, user_controlled: bool) -> crate::state::ConfigLayer {
+        crate::state::ConfigLayer {
+            config: toml::from_str(config).unwrap(),
+            path: None,
+            user_controlled,
+        }
+    }
+
+    #[tokio::test]
+    async fn trusted_layers_constrain_user_layers() {
+        let managed = layer(
+            r#"
+[network_proxy.policy]
+allowed_domains = ["*.example.com"]
+"#,
+            false,
+        );
+
+        let narrowing = LayersLoader(vec![
+            managed.clone(),
+            layer(
+                r#"
+[network_proxy]
+enabled = true
+
+[network_proxy.policy]
+allowed_domains = ["api.example.com"]
+"#,
+                true,
+            ),
+        ]);
+        let state = NetworkProxyState::new(Arc::new(narrowing)).await.unwrap();
+        assert_eq!(
+            state.current_patterns().await.unwrap(),
+            (vec!["api.example.com".to_string()], Vec::new())
+        );
+
+        let widening = LayersLoader(vec![
+            managed,
+            layer(
+                r#"
+[network_proxy.policy]
+allowed_domains = ["openai.com"]
+"#,
+                true,
+            ),
+        ]);
+        assert!(NetworkProxyState::new(Arc::new(widening)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rules_are_validated_when_loaded() {
+        let user_rules = |rules: &str| {
+            LayersLoader(vec![
+                layer(
+                    r#"
+[network_proxy]
+mode = "limited"
+
+[network_proxy.policy]
+allowed_domains = ["**.example.com"]
+"#,
+                    false,
+                ),
+                layer(rules, true),
+            ])
+        };
+
+        let valid = user_rules(
+            r#"
+[[network_proxy.policy.rules]]
+host = "api.example.com"
+path = "/v1/*"
+methods = ["GET"]
+decision = "allow"
+match = ["GET api.example.com/v1/search"]
+not_match = ["GET api.example.com/v2/search"]
+"#,
+        );
+        let state = NetworkProxyState::new(Arc::new(valid)).await.unwrap();
+        assert_eq!(
+            state
+                .rule_decision(
+                    "api.example.com",
+                    RuleTarget::Request {
+                        method: "GET",
+                        path: "/v1/search",
+                    },
+                )
+                .await
+                .unwrap(),
+            Some(NetworkDecision::Allow)
+        );
+
+        for invalid in [
+            // The `match` example is outside the rule's path.
+            r#"
+[[network_proxy.policy.rules]]
+host = "api.example.com"
+path = "/v1/*"
+decision = "deny"
+match = ["api.example.com/v2/search"]
+"#,
+            // Allows a host outside the managed allowlist.
+            r#"
+[[network_proxy.policy.rules]]
+host = "openai.com"
+methods = ["GET"]
+decision = "allow"
+"#,
+            // Allows a method the managed `limited` mode does not.
+            r#"
+[[network_proxy.policy.rules]]
+host = "api.example.com"
+methods = ["POST"]
+decision = "allow"
+"#,
+        ] {
+            assert!(
+                NetworkProxyState::new(Arc::new(user_rules(invalid)))
+                    .await
+                    .is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
     #[cfg(not(target_os = "macos"))]
     #[tokio::test]
     async fn unix_socket_allowlist_is_rejected_on_non_macos() {
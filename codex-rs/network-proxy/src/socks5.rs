@@ -1,4 +1,6 @@
 use crate::config::NetworkMode;
+use crate::http_proxy::ClientToken;
+use crate::http_proxy::client_token;
 use crate::network_policy::NetworkDecision;
 use crate::network_policy::NetworkPolicyDecider;
 use crate::network_policy::NetworkPolicyRequest;
@@ -8,19 +10,23 @@ use crate::network_policy::evaluate_host_policy;
 use crate::policy::normalize_host;
 use crate::reasons::REASON_METHOD_NOT_ALLOWED;
 use crate::reasons::REASON_PROXY_DISABLED;
+use crate::rules::RuleTarget;
+use crate::runtime::AllowedRequestArgs;
 use crate::state::BlockedRequest;
 use crate::state::BlockedRequestArgs;
 use crate::state::NetworkProxyState;
-use anyhow::Context as _;
 use anyhow::Result;
 use rama_core::Layer;
 use rama_core::Service;
 use rama_core::error::BoxError;
+use rama_core::extensions::Extensions;
 use rama_core::extensions::ExtensionsRef;
 use rama_core::layer::AddInputExtensionLayer;
 use rama_core::service::service_fn;
 use rama_net::client::EstablishedClientConnection;
 use rama_net::stream::SocketInfo;
+use rama_net::user::Basic;
+use rama_net::user::authority::AuthorizeResult;
 use rama_socks5::Socks5Acceptor;
 use rama_socks5::server::DefaultConnector;
 use rama_socks5::server::DefaultUdpRelay;
@@ -30,6 +36,7 @@ use rama_tcp::TcpStream;
 use rama_tcp::client::Request as TcpRequest;
 use rama_tcp::client::service::TcpConnector;
 use rama_tcp::server::TcpListener;
+use std::convert::Infallible;
 use std::io;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -37,20 +44,16 @@ use tracing::error;
 use tracing::info;
 use tracing::warn;
 
+// SOCKS5 traffic is never decrypted, so only host rules apply to it.
+const SOCKS_TARGET: RuleTarget<'static> = RuleTarget::Tunnel { inspected: false };
+
 pub async fn run_socks5(
     state: Arc<NetworkProxyState>,
+    listener: TcpListener,
     addr: SocketAddr,
     policy_decider: Option<Arc<dyn NetworkPolicyDecider>>,
     enable_socks5_udp: bool,
 ) -> Result<()> {
-    let listener = TcpListener::build()
-        .bind(addr)
-        .await
-        // See `http_proxy.rs` for details on why we wrap `BoxError` before converting to anyhow.
-        .map_err(rama_core::error::OpaqueError::from)
-        .map_err(anyhow::Error::from)
-        .with_context(|| format!("bind SOCKS5 proxy: {addr}"))?;
-
     info!("SOCKS5 proxy listening on {addr}");
 
     match state.network_mode().await {
@@ -74,7 +77,12 @@ pub async fn run_socks5(
     });
 
     let socks_connector = DefaultConnector::default().with_connector(policy_tcp_connector);
-    let base = Socks5Acceptor::new().with_connector(socks_connector);
+    // Credentials are optional and never rejected: the user name is only a
+    // client token used to attribute requests, not a secret.
+    let base = Socks5Acceptor::new()
+        .with_auth_optional(true)
+        .with_authorizer(accept_client_token)
+        .with_connector(socks_connector);
 
     if enable_socks5_udp {
         let udp_state = state.clone();
@@ -98,6 +106,16 @@ pub async fn run_socks5(
     Ok(())
 }
 
+/// Accept any credentials and keep the user name as the connection's client token.
+async fn accept_client_token(credentials: Basic) -> AuthorizeResult<Basic, Infallible> {
+    let mut extensions = Extensions::new();
+    extensions.insert(ClientToken(credentials.username().to_string()));
+    AuthorizeResult {
+        credentials,
+        result: Ok(Some(extensions)),
+    }
+}
+
 async fn handle_socks5_tcp(
     req: TcpRequest,
     tcp_connector: TcpConnector,
@@ -119,6 +137,7 @@ async fn handle_socks5_tcp(
         .extensions()
         .get::<SocketInfo>()
         .map(|info| info.peer_addr().to_string());
+    let client_token = client_token(&req);
 
     match app_state.enabled().await {
         Ok(true) => {}
@@ -128,6 +147,7 @@ async fn handle_socks5_tcp(
                     host: host.clone(),
                     reason: REASON_PROXY_DISABLED.to_string(),
                     client: client.clone(),
+                    client_token: client_token.clone(),
                     method: None,
                     mode: None,
                     protocol: "socks5".to_string(),
@@ -150,6 +170,7 @@ async fn handle_socks5_tcp(
                     host: host.clone(),
                     reason: REASON_METHOD_NOT_ALLOWED.to_string(),
                     client: client.clone(),
+                    client_token: client_token.clone(),
                     method: None,
                     mode: Some(NetworkMode::Limited),
                     protocol: "socks5".to_string(),
@@ -173,18 +194,20 @@ async fn handle_socks5_tcp(
         host: host.clone(),
         port,
         client_addr: client.clone(),
+        client_token: client_token.clone(),
         method: None,
         command: None,
         exec_policy_hint: None,
     });
 
-    match evaluate_host_policy(&app_state, policy_decider.as_ref(), &request).await {
+    match evaluate_host_policy(&app_state, policy_decider.as_ref(), &request, SOCKS_TARGET).await {
         Ok(NetworkDecision::Deny { reason }) => {
             let _ = app_state
                 .record_blocked(BlockedRequest::new(BlockedRequestArgs {
                     host: host.clone(),
                     reason: reason.clone(),
                     client: client.clone(),
+                    client_token: client_token.clone(),
                     method: None,
                     mode: None,
                     protocol: "socks5".to_string(),
@@ -197,6 +220,14 @@ async fn handle_socks5_tcp(
         Ok(NetworkDecision::Allow) => {
             let client = client.as_deref().unwrap_or_default();
             info!("SOCKS allowed (client={client}, host={host}, port={port})");
+            app_state.record_allowed(AllowedRequestArgs {
+                host,
+                client_token,
+                method: None,
+                protocol: "socks5".to_string(),
+                bytes_sent: None,
+                bytes_received: None,
+            });
         }
         Err(err) => {
             error!("failed to evaluate host: {err}");
@@ -228,6 +259,7 @@ async fn inspect_socks5_udp(
     let client = extensions
         .get::<SocketInfo>()
         .map(|info| info.peer_addr().to_string());
+    let client_token = extensions.get::<ClientToken>().map(|token| token.0.clone());
 
     match state.enabled().await {
         Ok(true) => {}
@@ -237,6 +269,7 @@ async fn inspect_socks5_udp(
                     host: host.clone(),
                     reason: REASON_PROXY_DISABLED.to_string(),
                     client: client.clone(),
+                    client_token: client_token.clone(),
                     method: None,
                     mode: None,
                     protocol: "socks5-udp".to_string(),
@@ -262,6 +295,7 @@ async fn inspect_socks5_udp(
                     host: host.clone(),
                     reason: REASON_METHOD_NOT_ALLOWED.to_string(),
                     client: client.clone(),
+                    client_token: client_token.clone(),
                     method: None,
                     mode: Some(NetworkMode::Limited),
                     protocol: "socks5-udp".to_string(),
@@ -284,18 +318,20 @@ async fn inspect_socks5_udp(
         host: host.clone(),
         port,
         client_addr: client.clone(),
+        client_token: client_token.clone(),
         method: None,
         command: None,
         exec_policy_hint: None,
     });
 
-    match evaluate_host_policy(&state, policy_decider.as_ref(), &request).await {
+    match evaluate_host_policy(&state, policy_decider.as_ref(), &request, SOCKS_TARGET).await {
         Ok(NetworkDecision::Deny { reason }) => {
             let _ = state
                 .record_blocked(BlockedRequest::new(BlockedRequestArgs {
                     host: host.clone(),
                     reason: reason.clone(),
                     client: client.clone(),
+                    client_token: client_token.clone(),
                     method: None,
                     mode: None,
                     protocol: "socks5-udp".to_string(),
@@ -308,13 +344,42 @@ async fn inspect_socks5_udp(
                 extensions,
             })
         }
-        Ok(NetworkDecision::Allow) => Ok(RelayResponse {
-            maybe_payload: Some(payload),
-            extensions,
-        }),
+        Ok(NetworkDecision::Allow) => {
+            state.record_allowed(AllowedRequestArgs {
+                host,
+                client_token,
+                method: None,
+                protocol: "socks5-udp".to_string(),
+                bytes_sent: u64::try_from(payload.len()).ok(),
+                bytes_received: None,
+            });
+            Ok(RelayResponse {
+                maybe_payload: Some(payload),
+                extensions,
+            })
+        }
         Err(err) => {
             error!("failed to evaluate UDP host: {err}");
             Err(io::Error::other("proxy error"))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn accept_client_token_keeps_the_user_name() {
+        let credentials = Basic::try_from("token_1:ignored").expect("credentials");
+        let AuthorizeResult { result, .. } = accept_client_token(credentials).await;
+        let extensions = result.expect("accepted").expect("extensions");
+        assert_eq!(
+            extensions
+                .get::<ClientToken>()
+                .map(|token| token.0.as_str()),
+            Some("token_1")
+        );
+    }
+}
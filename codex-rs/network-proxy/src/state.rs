@@ -1,24 +1,17 @@
 use crate::config::NetworkMode;
 use crate::config::NetworkProxyConfig;
+use crate::config::NetworkRuleDecision;
 use crate::policy::DomainPattern;
 use crate::policy::compile_globset;
+use crate::rules::NetworkRules;
 use crate::runtime::ConfigState;
 use crate::runtime::LayerMtime;
 use anyhow::Context;
 use anyhow::Result;
-use codex_app_server_protocol::ConfigLayerSource;
-use codex_core::config::CONFIG_TOML_FILE;
-use codex_core::config::Constrained;
-use codex_core::config::ConstraintError;
-use codex_core::config::find_codex_home;
-use codex_core::config_loader::CloudRequirementsLoader;
-use codex_core::config_loader::ConfigLayerStack;
-use codex_core::config_loader::ConfigLayerStackOrdering;
-use codex_core::config_loader::LoaderOverrides;
-use codex_core::config_loader::RequirementSource;
-use codex_core::config_loader::load_config_layers_state;
+use async_trait::async_trait;
 use serde::Deserialize;
 use std::collections::HashSet;
+use std::path::PathBuf;
 
 pub use crate::runtime::BlockedRequest;
 pub use crate::runtime::BlockedRequestArgs;
@@ -26,42 +19,77 @@ pub use crate::runtime::NetworkProxyState;
 #[cfg(test)]
 pub(crate) use crate::runtime::network_proxy_state_for_policy;
 
-pub(crate) async fn build_config_state() -> Result<ConfigState> {
-    // Load config through `codex-core` so we inherit the same layer ordering and semantics as the
-    // rest of Codex (system/managed layers, user layers, session flags, etc.).
-    let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
-    let cli_overrides = Vec::new();
-    let overrides = LoaderOverrides::default();
-    let config_layer_stack = load_config_layers_state(
-        &codex_home,
-        None,
-        &cli_overrides,
-        overrides,
-        CloudRequirementsLoader::default(),
-    )
-    .await
-    .context("failed to load Codex config")?;
+/// Loads the layered configuration the proxy policy is read from.
+///
+/// Codex implements this on top of its own `config.toml` loader so the proxy sees the same
+/// layers, in the same order, as the rest of Codex (system/managed layers, user layers, session
+/// flags, etc.). The loader is called again whenever one of the layer files changes.
+#[async_trait]
+pub trait ConfigLoader: Send + Sync + 'static {
+    async fn load(&self) -> Result<ConfigLayers>;
+}
+
+/// The configuration layers of Codex, lowest precedence first.
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    /// The layers merged in precedence order.
+    pub effective_config: toml::Value,
+    pub layers: Vec<ConfigLayer>,
+    /// The user's `config.toml`, reported in reload logs.
+    pub cfg_path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    pub config: toml::Value,
+    /// File the layer was read from. Changes to it trigger a reload.
+    pub path: Option<PathBuf>,
+    /// Whether the user controls this layer. Only the other (trusted) layers can constrain the
+    /// policy.
+    pub user_controlled: bool,
+}
 
-    let cfg_path = codex_home.join(CONFIG_TOML_FILE);
+/// A user-controlled setting widens the network policy beyond what a trusted layer allows.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "invalid value for `{field_name}`: `{candidate}` is not in the allowed set {allowed} (set by managed config)"
+)]
+pub struct ConstraintError {
+    pub field_name: &'static str,
+    pub candidate: String,
+    pub allowed: String,
+}
+
+pub(crate) async fn build_config_state(loader: &dyn ConfigLoader) -> Result<ConfigState> {
+    let ConfigLayers {
+        effective_config,
+        layers,
+        cfg_path,
+    } = loader.load().await.context("failed to load Codex config")?;
 
     // Deserialize from the merged effective config, rather than parsing config.toml ourselves.
     // This avoids a second parser/merger implementation (and the drift that comes with it).
-    let merged_toml = config_layer_stack.effective_config();
-    let config: NetworkProxyConfig = merged_toml
+    let config: NetworkProxyConfig = effective_config
         .try_into()
         .context("failed to deserialize network proxy config")?;
 
     // Security boundary: user-controlled layers must not be able to widen restrictions set by
     // trusted/managed layers (e.g., MDM). Enforce this before building runtime state.
-    let constraints = enforce_trusted_constraints(&config_layer_stack, &config)?;
+    let constraints = enforce_trusted_constraints(&layers, &config)?;
 
-    let layer_mtimes = collect_layer_mtimes(&config_layer_stack);
+    let layer_mtimes = layers
+        .into_iter()
+        .filter_map(|layer| layer.path)
+        .map(LayerMtime::new)
+        .collect();
     let deny_set = compile_globset(&config.network_proxy.policy.denied_domains)?;
     let allow_set = compile_globset(&config.network_proxy.policy.allowed_domains)?;
+    let rules = NetworkRules::compile(&config.network_proxy.policy.rules)?;
     Ok(ConfigState {
         config,
         allow_set,
         deny_set,
+        rules,
         constraints,
         layer_mtimes,
         cfg_path,
@@ -69,28 +97,6 @@ pub(crate) async fn build_config_state() -> Result<ConfigState> {
     })
 }
 
-fn collect_layer_mtimes(stack: &ConfigLayerStack) -> Vec<LayerMtime> {
-    stack
-        .get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst, false)
-        .iter()
-        .filter_map(|layer| {
-            let path = match &layer.name {
-                ConfigLayerSource::System { file } => Some(file.as_path().to_path_buf()),
-                ConfigLayerSource::User { file } => Some(file.as_path().to_path_buf()),
-                ConfigLayerSource::Project { dot_codex_folder } => dot_codex_folder
-                    .join(CONFIG_TOML_FILE)
-                    .ok()
-                    .map(|p| p.as_path().to_path_buf()),
-                ConfigLayerSource::LegacyManagedConfigTomlFromFile { file } => {
-                    Some(file.as_path().to_path_buf())
-                }
-                _ => None,
-            };
-            path.map(LayerMtime::new)
-        })
-        .collect()
-}
-
 #[derive(Debug, Default, Deserialize)]
 struct PartialConfig {
     #[serde(default)]
@@ -134,7 +140,7 @@ pub(crate) struct NetworkProxyConstraints {
 }
 
 fn enforce_trusted_constraints(
-    layers: &codex_core::config_loader::ConfigLayerStack,
+    layers: &[ConfigLayer],
     config: &NetworkProxyConfig,
 ) -> Result<NetworkProxyConstraints> {
     let constraints = network_proxy_constraints_from_trusted_layers(layers)?;
@@ -144,16 +150,13 @@ fn enforce_trusted_constraints(
 }
 
 fn network_proxy_constraints_from_trusted_layers(
-    layers: &codex_core::config_loader::ConfigLayerStack,
+    layers: &[ConfigLayer],
 ) -> Result<NetworkProxyConstraints> {
     let mut constraints = NetworkProxyConstraints::default();
-    for layer in layers.get_layers(
-        codex_core::config_loader::ConfigLayerStackOrdering::LowestPrecedenceFirst,
-        false,
-    ) {
+    for layer in layers {
         // Only trusted layers contribute constraints. User-controlled layers can narrow policy but
         // must never widen beyond what managed config allows.
-        if is_user_controlled_layer(&layer.name) {
+        if layer.user_controlled {
             continue;
         }
 
@@ -201,15 +204,6 @@ fn network_proxy_constraints_from_trusted_layers(
     Ok(constraints)
 }
 
-fn is_user_controlled_layer(layer: &ConfigLayerSource) -> bool {
-    matches!(
-        layer,
-        ConfigLayerSource::User { .. }
-            | ConfigLayerSource::Project { .. }
-            | ConfigLayerSource::SessionFlags
-    )
-}
-
 pub(crate) fn validate_policy_against_constraints(
     config: &NetworkProxyConfig,
     constraints: &NetworkProxyConstraints,
@@ -219,174 +213,150 @@ pub(crate) fn validate_policy_against_constraints(
         candidate: impl Into<String>,
         allowed: impl Into<String>,
     ) -> ConstraintError {
-        ConstraintError::InvalidValue {
+        ConstraintError {
             field_name,
             candidate: candidate.into(),
             allowed: allowed.into(),
-            requirement_source: RequirementSource::Unknown,
         }
     }
 
-    let enabled = config.network_proxy.enabled;
-    if let Some(max_enabled) = constraints.enabled {
-        let _ = Constrained::new(enabled, move |candidate| {
-            if *candidate && !max_enabled {
-                Err(invalid_value(
-                    "network_proxy.enabled",
-                    "true",
-                    "false (disabled by managed config)",
-                ))
-            } else {
-                Ok(())
-            }
-        })?;
+    // A managed `false` for a boolean setting means the user may not turn it on.
+    fn check_disabled(
+        field_name: &'static str,
+        candidate: bool,
+        managed: Option<bool>,
+    ) -> std::result::Result<(), ConstraintError> {
+        if candidate && managed == Some(false) {
+            Err(invalid_value(
+                field_name,
+                "true",
+                "false (disabled by managed config)",
+            ))
+        } else {
+            Ok(())
+        }
     }
 
-    if let Some(max_mode) = constraints.mode {
-        let _ = Constrained::new(config.network_proxy.mode, move |candidate| {
-            if network_mode_rank(*candidate) > network_mode_rank(max_mode) {
-                Err(invalid_value(
-                    "network_proxy.mode",
-                    format!("{candidate:?}"),
-                    format!("{max_mode:?} or more restrictive"),
-                ))
-            } else {
-                Ok(())
-            }
-        })?;
+    let proxy = &config.network_proxy;
+    check_disabled("network_proxy.enabled", proxy.enabled, constraints.enabled)?;
+
+    if let Some(max_mode) = constraints.mode
+        && network_mode_rank(proxy.mode) > network_mode_rank(max_mode)
+    {
+        return Err(invalid_value(
+            "network_proxy.mode",
+            format!("{:?}", proxy.mode),
+            format!("{max_mode:?} or more restrictive"),
+        ));
     }
 
-    let allow_upstream_proxy = constraints.allow_upstream_proxy;
-    let _ = Constrained::new(
-        config.network_proxy.allow_upstream_proxy,
-        move |candidate| match allow_upstream_proxy {
-            Some(true) | None => Ok(()),
-            Some(false) => {
-                if *candidate {
-                    Err(invalid_value(
-                        "network_proxy.allow_upstream_proxy",
-                        "true",
-                        "false (disabled by managed config)",
-                    ))
-                } else {
-                    Ok(())
-                }
-            }
-        },
+    check_disabled(
+        "network_proxy.allow_upstream_proxy",
+        proxy.allow_upstream_proxy,
+        constraints.allow_upstream_proxy,
     )?;
-
-    let allow_non_loopback_admin = constraints.dangerously_allow_non_loopback_admin;
-    let _ = Constrained::new(
-        config.network_proxy.dangerously_allow_non_loopback_admin,
-        move |candidate| match allow_non_loopback_admin {
-            Some(true) | None => Ok(()),
-            Some(false) => {
-                if *candidate {
-                    Err(invalid_value(
-                        "network_proxy.dangerously_allow_non_loopback_admin",
-                        "true",
-                        "false (disabled by managed config)",
-                    ))
-                } else {
-                    Ok(())
-                }
-            }
-        },
+    check_disabled(
+        "network_proxy.dangerously_allow_non_loopback_admin",
+        proxy.dangerously_allow_non_loopback_admin,
+        constraints.dangerously_allow_non_loopback_admin,
     )?;
-
-    let allow_non_loopback_proxy = constraints.dangerously_allow_non_loopback_proxy;
-    let _ = Constrained::new(
-        config.network_proxy.dangerously_allow_non_loopback_proxy,
-        move |candidate| match allow_non_loopback_proxy {
-            Some(true) | None => Ok(()),
-            Some(false) => {
-                if *candidate {
-                    Err(invalid_value(
-                        "network_proxy.dangerously_allow_non_loopback_proxy",
-                        "true",
-                        "false (disabled by managed config)",
-                    ))
-                } else {
-                    Ok(())
-                }
-            }
-        },
+    check_disabled(
+        "network_proxy.dangerously_allow_non_loopback_proxy",
+        proxy.dangerously_allow_non_loopback_proxy,
+        constraints.dangerously_allow_non_loopback_proxy,
+    )?;
+    check_disabled(
+        "network_proxy.policy.allow_local_binding",
+        proxy.policy.allow_local_binding,
+        constraints.allow_local_binding,
     )?;
-
-    if let Some(allow_local_binding) = constraints.allow_local_binding {
-        let _ = Constrained::new(
-            config.network_proxy.policy.allow_local_binding,
-            move |candidate| {
-                if *candidate && !allow_local_binding {
-                    Err(invalid_value(
-                        "network_proxy.policy.allow_local_binding",
-                        "true",
-                        "false (disabled by managed config)",
-                    ))
-                } else {
-                    Ok(())
-                }
-            },
-        )?;
-    }
 
     if let Some(allowed_domains) = &constraints.allowed_domains {
         let managed_patterns: Vec<DomainPattern> = allowed_domains
             .iter()
             .map(|entry| DomainPattern::parse_for_constraints(entry))
             .collect();
-        let _ = Constrained::new(
-            config.network_proxy.policy.allowed_domains.clone(),
-            move |candidate| {
-                let mut invalid = Vec::new();
-                for entry in candidate {
-                    let candidate_pattern = DomainPattern::parse_for_constraints(entry);
-                    if !managed_patterns
-                        .iter()
-                        .any(|managed| managed.allows(&candidate_pattern))
-                    {
-                        invalid.push(entry.clone());
-                    }
-                }
-                if invalid.is_empty() {
-                    Ok(())
-                } else {
-                    Err(invalid_value(
-                        "network_proxy.policy.allowed_domains",
-                        format!("{invalid:?}"),
-                        "subset of managed allowed_domains",
-                    ))
-                }
-            },
-        )?;
+        let invalid: Vec<String> = proxy
+            .policy
+            .allowed_domains
+            .iter()
+            .filter(|entry| {
+                let candidate_pattern = DomainPattern::parse_for_constraints(entry);
+                !managed_patterns
+                    .iter()
+                    .any(|managed| managed.allows(&candidate_pattern))
+            })
+            .cloned()
+            .collect();
+        if !invalid.is_empty() {
+            return Err(invalid_value(
+                "network_proxy.policy.allowed_domains",
+                format!("{invalid:?}"),
+                "subset of managed allowed_domains",
+            ));
+        }
+
+        // Allow rules let hosts through just like allowlist entries.
+        let invalid: Vec<String> = proxy
+            .policy
+            .rules
+            .iter()
+            .filter(|rule| rule.decision == NetworkRuleDecision::Allow)
+            .filter(|rule| {
+                let candidate_pattern = DomainPattern::parse_for_constraints(&rule.host);
+                !managed_patterns
+                    .iter()
+                    .any(|managed| managed.allows(&candidate_pattern))
+            })
+            .map(|rule| rule.host.clone())
+            .collect();
+        if !invalid.is_empty() {
+            return Err(invalid_value(
+                "network_proxy.policy.rules",
+                format!("allow rules for {invalid:?}"),
+                "subset of managed allowed_domains",
+            ));
+        }
+    }
+
+    // Allow rules also lift the method restriction of `limited` mode, so under a managed
+    // `limited` mode they may only name the methods it allows.
+    if constraints.mode == Some(NetworkMode::Limited) {
+        let widens_methods = proxy.policy.rules.iter().any(|rule| {
+            rule.decision == NetworkRuleDecision::Allow
+                && (rule.methods.is_empty()
+                    || rule.methods.iter().any(|method| {
+                        !NetworkMode::Limited.allows_method(&method.to_ascii_uppercase())
+                    }))
+        });
+        if widens_methods {
+            return Err(invalid_value(
+                "network_proxy.policy.rules",
+                "allow rules for methods other than GET, HEAD and OPTIONS",
+                "methods allowed by managed limited mode",
+            ));
+        }
     }
 
     if let Some(denied_domains) = &constraints.denied_domains {
-        let required_set: HashSet<String> = denied_domains
+        let candidate_set: HashSet<String> = proxy
+            .policy
+            .denied_domains
             .iter()
             .map(|s| s.to_ascii_lowercase())
             .collect();
-        let _ = Constrained::new(
-            config.network_proxy.policy.denied_domains.clone(),
-            move |candidate| {
-                let candidate_set: HashSet<String> =
-                    candidate.iter().map(|s| s.to_ascii_lowercase()).collect();
-                let missing: Vec<String> = required_set
-                    .iter()
-                    .filter(|entry| !candidate_set.contains(*entry))
-                    .cloned()
-                    .collect();
-                if missing.is_empty() {
-                    Ok(())
-                } else {
-                    Err(invalid_value(
-                        "network_proxy.policy.denied_domains",
-                        "missing managed denied_domains entries",
-                        format!("{missing:?}"),
-                    ))
-                }
-            },
-        )?;
+        let missing: Vec<String> = denied_domains
+            .iter()
+            .map(|s| s.to_ascii_lowercase())
+            .filter(|entry| !candidate_set.contains(entry))
+            .collect();
+        if !missing.is_empty() {
+            return Err(invalid_value(
+                "network_proxy.policy.denied_domains",
+                "missing managed denied_domains entries",
+                format!("{missing:?}"),
+            ));
+        }
     }
 
     if let Some(allow_unix_sockets) = &constraints.allow_unix_sockets {
@@ -394,26 +364,20 @@ pub(crate) fn validate_policy_against_constraints(
             .iter()
             .map(|s| s.to_ascii_lowercase())
             .collect();
-        let _ = Constrained::new(
-            config.network_proxy.policy.allow_unix_sockets.clone(),
-            move |candidate| {
-                let mut invalid = Vec::new();
-                for entry in candidate {
-                    if !allowed_set.contains(&entry.to_ascii_lowercase()) {
-                        invalid.push(entry.clone());
-                    }
-                }
-                if invalid.is_empty() {
-                    Ok(())
-                } else {
-                    Err(invalid_value(
-                        "network_proxy.policy.allow_unix_sockets",
-                        format!("{invalid:?}"),
-                        "subset of managed allow_unix_sockets",
-                    ))
-                }
-            },
-        )?;
+        let invalid: Vec<String> = proxy
+            .policy
+            .allow_unix_sockets
+            .iter()
+            .filter(|entry| !allowed_set.contains(&entry.to_ascii_lowercase()))
+            .cloned()
+            .collect();
+        if !invalid.is_empty() {
+            return Err(invalid_value(
+                "network_proxy.policy.allow_unix_sockets",
+                format!("{invalid:?}"),
+                "subset of managed allow_unix_sockets",
+            ));
+        }
     }
 
     Ok(())
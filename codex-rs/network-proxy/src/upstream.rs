@@ -1,3 +1,6 @@
+use crate::cassette::Cassette;
+use crate::cassette::CassetteRequest;
+use crate::cassette::CassetteResponse;
 use rama_core::Layer;
 use rama_core::Service;
 use rama_core::error::BoxError;
@@ -9,6 +12,7 @@ use rama_core::service::BoxService;
 use rama_http::Body;
 use rama_http::Request;
 use rama_http::Response;
+use rama_http::body::util::BodyExt as _;
 use rama_http::layer::version_adapter::RequestVersionAdapter;
 use rama_http_backend::client::HttpClientService;
 use rama_http_backend::client::HttpConnector;
@@ -19,6 +23,7 @@ use rama_net::http::RequestContext;
 use rama_tcp::client::service::TcpConnector;
 use rama_tls_boring::client::TlsConnectorDataBuilder;
 use rama_tls_boring::client::TlsConnectorLayer;
+use std::sync::Arc;
 use tracing::warn;
 
 #[cfg(target_os = "macos")]
@@ -99,6 +104,7 @@ pub(crate) struct UpstreamClient {
         BoxError,
     >,
     proxy_config: ProxyConfig,
+    recorder: Option<Arc<Cassette>>,
 }
 
 impl UpstreamClient {
@@ -116,6 +122,7 @@ impl UpstreamClient {
         Self {
             connector,
             proxy_config: ProxyConfig::default(),
+            recorder: None,
         }
     }
 
@@ -124,15 +131,17 @@ impl UpstreamClient {
         Self {
             connector,
             proxy_config,
+            recorder: None,
         }
     }
-}
 
-impl Service<Request<Body>> for UpstreamClient {
-    type Output = Response;
-    type Error = OpaqueError;
+    /// Save every response into `cassette`. Bodies are buffered in full to do so.
+    pub(crate) fn recording_into(mut self, cassette: Arc<Cassette>) -> Self {
+        self.recorder = Some(cassette);
+        self
+    }
 
-    async fn serve(&self, mut req: Request<Body>) -> Result<Self::Output, Self::Error> {
+    async fn forward(&self, mut req: Request<Body>) -> Result<Response, OpaqueError> {
         if let Some(proxy) = self.proxy_config.proxy_for_request(&req) {
             req.extensions_mut().insert(proxy);
         }
@@ -156,6 +165,104 @@ impl Service<Request<Body>> for UpstreamClient {
             .map_err(OpaqueError::from_boxed)
             .with_context(|| format!("http request failure for uri: {uri}"))
     }
+
+    async fn forward_and_record(
+        &self,
+        cassette: &Cassette,
+        req: Request<Body>,
+    ) -> Result<Response, OpaqueError> {
+        let (parts, body) = req.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(OpaqueError::from_boxed)
+            .context("read request body")?
+            .to_bytes();
+        let method = parts.method.as_str().to_string();
+        let uri = parts.uri.to_string();
+        let resp = self
+            .forward(Request::from_parts(parts, Body::from(body.clone())))
+            .await?;
+
+        let (parts, resp_body) = resp.into_parts();
+        let resp_body = resp_body
+            .collect()
+            .await
+            .map_err(OpaqueError::from_boxed)
+            .with_context(|| format!("read response body for uri: {uri}"))?
+            .to_bytes();
+        let recorded = CassetteResponse {
+            status: parts.status.as_u16(),
+            headers: parts
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            body: resp_body.to_vec(),
+        };
+        let request = CassetteRequest {
+            method: &method,
+            uri: &uri,
+            body: &body,
+        };
+        if let Err(err) = cassette.store(&request, &recorded).await {
+            warn!("failed to record response for {method} {uri}: {err:#}");
+        }
+        Ok(Response::from_parts(parts, Body::from(resp_body)))
+    }
+}
+
+impl Service<Request<Body>> for UpstreamClient {
+    type Output = Response;
+    type Error = OpaqueError;
+
+    async fn serve(&self, req: Request<Body>) -> Result<Self::Output, Self::Error> {
+        match &self.recorder {
+            Some(cassette) => self.forward_and_record(cassette, req).await,
+            None => self.forward(req).await,
+        }
+    }
+}
+
+/// Answer `req` from `cassette` without contacting upstream, or `None` when nothing was
+/// recorded for it.
+pub(crate) async fn replay(
+    cassette: &Cassette,
+    req: Request<Body>,
+) -> Result<Option<Response>, OpaqueError> {
+    let (parts, body) = req.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map_err(OpaqueError::from_boxed)
+        .context("read request body")?
+        .to_bytes();
+    let uri = parts.uri.to_string();
+    let request = CassetteRequest {
+        method: parts.method.as_str(),
+        uri: &uri,
+        body: &body,
+    };
+    let Some(recorded) = cassette
+        .load(&request)
+        .await
+        .map_err(|err| OpaqueError::from_display(format!("{err:#}")))?
+    else {
+        return Ok(None);
+    };
+
+    let mut builder = Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    builder
+        .body(Body::from(recorded.body))
+        .map(Some)
+        .map_err(|err| {
+            OpaqueError::from_display(format!("invalid recorded response for {uri}: {err}"))
+        })
 }
 
 fn build_http_connector() -> BoxService<
@@ -37,6 +37,27 @@ impl From<Vec<String>> for ExecPolicyAmendment {
     }
 }
 
+/// Proposed network proxy policy change to allow requests to this host.
+///
+/// Applying it appends `host` to `[network_proxy.policy].allowed_domains` in
+/// the user's `config.toml`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(transparent)]
+#[ts(type = "string")]
+pub struct NetworkPolicyAmendment {
+    pub host: String,
+}
+
+impl NetworkPolicyAmendment {
+    pub fn new(host: String) -> Self {
+        Self { host }
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExecApprovalRequestEvent {
     /// Identifier for the associated exec call, if available.
@@ -59,6 +80,30 @@ pub struct ExecApprovalRequestEvent {
     pub parsed_cmd: Vec<ParsedCommand>,
 }
 
+/// The network proxy blocked a request of a command because its host is not in
+/// the allowlist. Approving it lets the command reach the host and reruns it.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct NetworkApprovalRequestEvent {
+    /// Identifier for the exec call that made the request.
+    pub call_id: String,
+    /// Turn ID that this command belongs to.
+    #[serde(default)]
+    pub turn_id: String,
+    /// The host the command tried to reach.
+    pub host: String,
+    pub port: u16,
+    /// The HTTP method of the request, `CONNECT` for HTTPS tunnels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub method: Option<String>,
+    /// The command that made the request.
+    pub command: Vec<String>,
+    /// The command's working directory.
+    pub cwd: PathBuf,
+    /// Proposed policy amendment that allows the host in future sessions.
+    pub proposed_network_policy_amendment: NetworkPolicyAmendment,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ElicitationRequestEvent {
     pub server_name: String,
@@ -53,6 +53,8 @@ pub use crate::approvals::ApplyPatchApprovalRequestEvent;
 pub use crate::approvals::ElicitationAction;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::ExecPolicyAmendment;
+pub use crate::approvals::NetworkApprovalRequestEvent;
+pub use crate::approvals::NetworkPolicyAmendment;
 pub use crate::request_user_input::RequestUserInputEvent;
 
 /// Open/close tags for special user-input blocks. Used across crates to avoid
@@ -199,6 +201,15 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Approve network access for a command whose request the network proxy
+    /// blocked.
+    NetworkApproval {
+        /// The id of the submission we are approving
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
     /// Approve a code patch
     PatchApproval {
         /// The id of the submission we are approving
@@ -793,6 +804,8 @@ pub enum EventMsg {
 
     ExecApprovalRequest(ExecApprovalRequestEvent),
 
+    NetworkApprovalRequest(NetworkApprovalRequestEvent),
+
     RequestUserInput(RequestUserInputEvent),
 
     DynamicToolCallRequest(DynamicToolCallRequest),
@@ -1770,6 +1783,10 @@ impl From<CompactedItem> for ResponseItem {
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
 pub struct TurnContextItem {
+    /// Id of the turn this context applies to. Absent in rollouts written
+    /// before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
     pub cwd: PathBuf,
     pub approval_policy: AskForApproval,
     pub sandbox_policy: SandboxPolicy,
@@ -2365,6 +2382,12 @@ pub enum ReviewDecision {
         proposed_execpolicy_amendment: ExecPolicyAmendment,
     },
 
+    /// User has approved the network request and wants to apply the proposed
+    /// network policy amendment so the host stays allowed in future sessions.
+    ApprovedNetworkPolicyAmendment {
+        proposed_network_policy_amendment: NetworkPolicyAmendment,
+    },
+
     /// User has approved this command and wants to automatically approve any
     /// future identical instances (`command` and `cwd` match exactly) for the
     /// remainder of the session.
@@ -2387,6 +2410,9 @@ impl ReviewDecision {
         match self {
             ReviewDecision::Approved => "approved",
             ReviewDecision::ApprovedExecpolicyAmendment { .. } => "approved_with_amendment",
+            ReviewDecision::ApprovedNetworkPolicyAmendment { .. } => {
+                "approved_with_network_amendment"
+            }
             ReviewDecision::ApprovedForSession => "approved_for_session",
             ReviewDecision::Denied => "denied",
             ReviewDecision::Abort => "abort",
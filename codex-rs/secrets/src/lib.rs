@@ -13,8 +13,23 @@ use sha2::Digest;
 use sha2::Sha256;
 
 mod local;
+mod passphrase;
+mod redaction;
 
 pub use local::LocalSecretsBackend;
+pub use passphrase::PASSPHRASE_ENV_VAR;
+pub use passphrase::PASSPHRASE_FD_ENV_VAR;
+pub use passphrase::PassphraseSecretsBackend;
+pub use passphrase::PassphraseSource;
+pub use redaction::RedactingWriter;
+pub use redaction::SecretRedactor;
+pub use redaction::StreamRedactor;
+pub use redaction::redact_secret_bytes;
+pub use redaction::redact_secrets;
+pub use redaction::redact_secrets_in_json;
+pub use redaction::register_secret_for_redaction;
+pub use redaction::secret_placeholder;
+pub use redaction::secret_redactor;
 
 const KEYRING_SERVICE: &str = "codex";
 
@@ -59,6 +74,18 @@ impl SecretScope {
         Ok(Self::Environment(trimmed.to_string()))
     }
 
+    /// The environment scope of the repository containing `cwd`.
+    pub fn for_cwd(cwd: &Path) -> Result<Self> {
+        Self::environment(environment_id_from_cwd(cwd))
+    }
+
+    pub fn environment_id(&self) -> Option<&str> {
+        match self {
+            Self::Global => None,
+            Self::Environment(environment_id) => Some(environment_id),
+        }
+    }
+
     pub fn canonical_key(&self, name: &SecretName) -> String {
         // Stable, env-safe identifier used as the on-disk map key.
         match self {
@@ -70,6 +97,15 @@ impl SecretScope {
     }
 }
 
+impl fmt::Display for SecretScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Global => f.write_str("global"),
+            Self::Environment(environment_id) => write!(f, "env:{environment_id}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SecretListEntry {
     pub scope: SecretScope,
@@ -79,8 +115,22 @@ pub struct SecretListEntry {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum SecretsBackendKind {
+    /// Encrypted file whose key is kept in the OS keyring.
     #[default]
     Local,
+    /// Encrypted file whose key is derived from a passphrase supplied via
+    /// `CODEX_SECRETS_PASSPHRASE` or `CODEX_SECRETS_PASSPHRASE_FD`, for
+    /// machines without a keyring daemon.
+    Passphrase,
+}
+
+impl fmt::Display for SecretsBackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => f.write_str("local"),
+            Self::Passphrase => f.write_str("passphrase"),
+        }
+    }
 }
 
 pub trait SecretsBackend: Send + Sync {
@@ -88,6 +138,30 @@ pub trait SecretsBackend: Send + Sync {
     fn get(&self, scope: &SecretScope, name: &SecretName) -> Result<Option<String>>;
     fn delete(&self, scope: &SecretScope, name: &SecretName) -> Result<bool>;
     fn list(&self, scope_filter: Option<&SecretScope>) -> Result<Vec<SecretListEntry>>;
+
+    /// Every stored secret with its value.
+    fn entries(&self) -> Result<Vec<(SecretListEntry, String)>> {
+        let mut entries = Vec::new();
+        for entry in self.list(None)? {
+            if let Some(value) = self.get(&entry.scope, &entry.name)? {
+                entries.push((entry, value));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Store many secrets at once, overwriting existing values.
+    fn import(&self, entries: &[(SecretListEntry, String)]) -> Result<()> {
+        for (entry, value) in entries {
+            self.set(&entry.scope, &entry.name, value)?;
+        }
+        Ok(())
+    }
+
+    /// Re-encrypt the stored secrets under a new key. Backends that derive
+    /// their key from a passphrase require `new_passphrase`; others generate
+    /// a fresh key and ignore it.
+    fn rekey(&self, new_passphrase: Option<&str>) -> Result<()>;
 }
 
 #[derive(Clone)]
@@ -97,13 +171,7 @@ pub struct SecretsManager {
 
 impl SecretsManager {
     pub fn new(codex_home: PathBuf, backend_kind: SecretsBackendKind) -> Self {
-        let backend: Arc<dyn SecretsBackend> = match backend_kind {
-            SecretsBackendKind::Local => {
-                let keyring_store: Arc<dyn KeyringStore> = Arc::new(DefaultKeyringStore);
-                Arc::new(LocalSecretsBackend::new(codex_home, keyring_store))
-            }
-        };
-        Self { backend }
+        Self::new_with_keyring_store(codex_home, backend_kind, Arc::new(DefaultKeyringStore))
     }
 
     pub fn new_with_keyring_store(
@@ -115,10 +183,18 @@ impl SecretsManager {
             SecretsBackendKind::Local => {
                 Arc::new(LocalSecretsBackend::new(codex_home, keyring_store))
             }
+            SecretsBackendKind::Passphrase => Arc::new(PassphraseSecretsBackend::new(
+                codex_home,
+                PassphraseSource::from_env(),
+            )),
         };
         Self { backend }
     }
 
+    pub fn with_backend(backend: Arc<dyn SecretsBackend>) -> Self {
+        Self { backend }
+    }
+
     pub fn set(&self, scope: &SecretScope, name: &SecretName, value: &str) -> Result<()> {
         self.backend.set(scope, name, value)
     }
@@ -134,6 +210,25 @@ impl SecretsManager {
     pub fn list(&self, scope_filter: Option<&SecretScope>) -> Result<Vec<SecretListEntry>> {
         self.backend.list(scope_filter)
     }
+
+    /// Every stored secret with its value, read from the backend at once.
+    pub fn entries(&self) -> Result<Vec<(SecretListEntry, String)>> {
+        self.backend.entries()
+    }
+
+    /// Re-encrypt every secret under a new key. See [`SecretsBackend::rekey`].
+    pub fn rekey(&self, new_passphrase: Option<&str>) -> Result<()> {
+        self.backend.rekey(new_passphrase)
+    }
+
+    /// Copy every secret into `target`, overwriting secrets of the same name
+    /// and scope there. Returns the number of secrets copied; the source is
+    /// left untouched.
+    pub fn migrate_to(&self, target: &SecretsManager) -> Result<usize> {
+        let entries = self.backend.entries()?;
+        target.backend.import(&entries)?;
+        Ok(entries.len())
+    }
 }
 
 pub fn environment_id_from_cwd(cwd: &Path) -> String {
@@ -240,4 +335,58 @@ mod tests {
         assert_eq!(manager.get(&scope, &name)?, None);
         Ok(())
     }
+
+    #[test]
+    fn migrate_copies_secrets_between_backends() -> Result<()> {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let local = SecretsManager::new_with_keyring_store(
+            codex_home.path().to_path_buf(),
+            SecretsBackendKind::Local,
+            Arc::new(MockKeyringStore::default()),
+        );
+        let passphrase = SecretsManager::with_backend(Arc::new(PassphraseSecretsBackend::new(
+            codex_home.path().to_path_buf(),
+            PassphraseSource::Literal("hunter2".to_string().into()),
+        )));
+        let repo = SecretScope::environment("repo")?;
+        local.set(
+            &SecretScope::Global,
+            &SecretName::new("GLOBAL_TOKEN")?,
+            "one",
+        )?;
+        local.set(&repo, &SecretName::new("REPO_TOKEN")?, "two")?;
+
+        assert_eq!(local.migrate_to(&passphrase)?, 2);
+
+        assert_eq!(
+            passphrase.get(&repo, &SecretName::new("REPO_TOKEN")?)?,
+            Some("two".to_string())
+        );
+        assert_eq!(local.list(None)?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn local_rekey_keeps_secrets_readable() -> Result<()> {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let keyring = Arc::new(MockKeyringStore::default());
+        let manager = SecretsManager::new_with_keyring_store(
+            codex_home.path().to_path_buf(),
+            SecretsBackendKind::Local,
+            keyring.clone(),
+        );
+        let name = SecretName::new("GITHUB_TOKEN")?;
+        manager.set(&SecretScope::Global, &name, "token-1")?;
+        let account = compute_keyring_account(codex_home.path());
+        let before = keyring.saved_value(&account);
+
+        manager.rekey(None)?;
+
+        assert_ne!(keyring.saved_value(&account), before);
+        assert_eq!(
+            manager.get(&SecretScope::Global, &name)?,
+            Some("token-1".to_string())
+        );
+        Ok(())
+    }
 }
@@ -37,13 +37,13 @@ const SECRETS_VERSION: u8 = 1;
 const LOCAL_SECRETS_FILENAME: &str = "local.age";
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
-struct SecretsFile {
-    version: u8,
-    secrets: BTreeMap<String, String>,
+pub(crate) struct SecretsFile {
+    pub(crate) version: u8,
+    pub(crate) secrets: BTreeMap<String, String>,
 }
 
 impl SecretsFile {
-    fn new_empty() -> Self {
+    pub(crate) fn new_empty() -> Self {
         Self {
             version: SECRETS_VERSION,
             secrets: BTreeMap::new(),
@@ -91,20 +91,42 @@ impl LocalSecretsBackend {
 
     pub fn list(&self, scope_filter: Option<&SecretScope>) -> Result<Vec<SecretListEntry>> {
         let file = self.load_file()?;
-        let mut entries = Vec::new();
-        for canonical_key in file.secrets.keys() {
-            let Some(entry) = parse_canonical_key(canonical_key) else {
-                warn!("skipping invalid canonical secret key: {canonical_key}");
-                continue;
-            };
-            if let Some(scope) = scope_filter
-                && entry.scope != *scope
-            {
-                continue;
-            }
-            entries.push(entry);
+        Ok(list_entries(&file, scope_filter))
+    }
+
+    /// Generate a new random key, re-encrypt the secrets file with it and
+    /// store it in the keyring.
+    ///
+    /// The re-encrypted file is written next to the current one before the
+    /// keyring is updated and only renamed over it afterwards, so an
+    /// interruption at any step leaves the old key and file in place. If the
+    /// rename fails, the keyring gets the old key back.
+    pub fn rekey(&self) -> Result<()> {
+        let file = self.load_file()?;
+        let account = compute_keyring_account(&self.codex_home);
+        let previous = self.load_or_create_passphrase()?;
+        let generated = generate_passphrase()?;
+        let path = self.secrets_path();
+        let plaintext = serde_json::to_vec(&file).context("failed to serialize secrets file")?;
+        let ciphertext = encrypt_with_passphrase(&plaintext, &generated)?;
+        ensure_parent_dir(&path)?;
+        let tmp_path = write_temp_file(&path, &ciphertext)?;
+        if let Err(err) =
+            self.keyring_store
+                .save(keyring_service(), &account, generated.expose_secret())
+        {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(anyhow::anyhow!(err.message()))
+                .context("failed to persist rotated secrets key in keyring");
+        }
+        if let Err(err) = replace_with_temp_file(&tmp_path, &path) {
+            // Keep the keyring consistent with the file that is still on disk.
+            let _ = self
+                .keyring_store
+                .save(keyring_service(), &account, previous.expose_secret());
+            return Err(err);
         }
-        Ok(entries)
+        Ok(())
     }
 
     fn secrets_dir(&self) -> PathBuf {
@@ -120,40 +142,13 @@ impl LocalSecretsBackend {
         if !path.exists() {
             return Ok(SecretsFile::new_empty());
         }
-
-        let ciphertext = fs::read(&path)
-            .with_context(|| format!("failed to read secrets file at {}", path.display()))?;
         let passphrase = self.load_or_create_passphrase()?;
-        let plaintext = decrypt_with_passphrase(&ciphertext, &passphrase)?;
-        let mut parsed: SecretsFile = serde_json::from_slice(&plaintext).with_context(|| {
-            format!(
-                "failed to deserialize decrypted secrets file at {}",
-                path.display()
-            )
-        })?;
-        if parsed.version == 0 {
-            parsed.version = SECRETS_VERSION;
-        }
-        anyhow::ensure!(
-            parsed.version <= SECRETS_VERSION,
-            "secrets file version {} is newer than supported version {}",
-            parsed.version,
-            SECRETS_VERSION
-        );
-        Ok(parsed)
+        load_secrets_file(&path, &passphrase)
     }
 
     fn save_file(&self, file: &SecretsFile) -> Result<()> {
-        let dir = self.secrets_dir();
-        fs::create_dir_all(&dir)
-            .with_context(|| format!("failed to create secrets dir {}", dir.display()))?;
-
         let passphrase = self.load_or_create_passphrase()?;
-        let plaintext = serde_json::to_vec(file).context("failed to serialize secrets file")?;
-        let ciphertext = encrypt_with_passphrase(&plaintext, &passphrase)?;
-        let path = self.secrets_path();
-        write_file_atomically(&path, &ciphertext)?;
-        Ok(())
+        save_secrets_file(&self.secrets_path(), file, &passphrase)
     }
 
     fn load_or_create_passphrase(&self) -> Result<SecretString> {
@@ -196,9 +191,126 @@ impl SecretsBackend for LocalSecretsBackend {
     fn list(&self, scope_filter: Option<&SecretScope>) -> Result<Vec<SecretListEntry>> {
         LocalSecretsBackend::list(self, scope_filter)
     }
+
+    fn entries(&self) -> Result<Vec<(SecretListEntry, String)>> {
+        let file = self.load_file()?;
+        Ok(file_entries(&file))
+    }
+
+    fn import(&self, entries: &[(SecretListEntry, String)]) -> Result<()> {
+        let mut file = self.load_file()?;
+        insert_entries(&mut file, entries)?;
+        self.save_file(&file)
+    }
+
+    fn rekey(&self, _new_passphrase: Option<&str>) -> Result<()> {
+        LocalSecretsBackend::rekey(self)
+    }
+}
+
+/// Read and decrypt a secrets file written by [`save_secrets_file`].
+pub(crate) fn load_secrets_file(path: &Path, passphrase: &SecretString) -> Result<SecretsFile> {
+    if !path.exists() {
+        return Ok(SecretsFile::new_empty());
+    }
+
+    let ciphertext = fs::read(path)
+        .with_context(|| format!("failed to read secrets file at {}", path.display()))?;
+    let plaintext = decrypt_with_passphrase(&ciphertext, passphrase)?;
+    let mut parsed: SecretsFile = serde_json::from_slice(&plaintext).with_context(|| {
+        format!(
+            "failed to deserialize decrypted secrets file at {}",
+            path.display()
+        )
+    })?;
+    if parsed.version == 0 {
+        parsed.version = SECRETS_VERSION;
+    }
+    anyhow::ensure!(
+        parsed.version <= SECRETS_VERSION,
+        "secrets file version {} is newer than supported version {}",
+        parsed.version,
+        SECRETS_VERSION
+    );
+    Ok(parsed)
+}
+
+/// Encrypt `file` with `passphrase` and atomically replace `path`.
+pub(crate) fn save_secrets_file(
+    path: &Path,
+    file: &SecretsFile,
+    passphrase: &SecretString,
+) -> Result<()> {
+    ensure_parent_dir(path)?;
+    let plaintext = serde_json::to_vec(file).context("failed to serialize secrets file")?;
+    let ciphertext = encrypt_with_passphrase(&plaintext, passphrase)?;
+    write_file_atomically(path, &ciphertext)
+}
+
+fn ensure_parent_dir(path: &Path) -> Result<()> {
+    let Some(dir) = path.parent() else {
+        return Ok(());
+    };
+    fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create secrets dir {}", dir.display()))
+}
+
+pub(crate) fn list_entries(
+    file: &SecretsFile,
+    scope_filter: Option<&SecretScope>,
+) -> Vec<SecretListEntry> {
+    let mut entries = Vec::new();
+    for canonical_key in file.secrets.keys() {
+        let Some(entry) = parse_canonical_key(canonical_key) else {
+            warn!("skipping invalid canonical secret key: {canonical_key}");
+            continue;
+        };
+        if let Some(scope) = scope_filter
+            && entry.scope != *scope
+        {
+            continue;
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+pub(crate) fn file_entries(file: &SecretsFile) -> Vec<(SecretListEntry, String)> {
+    list_entries(file, None)
+        .into_iter()
+        .filter_map(|entry| {
+            let value = file
+                .secrets
+                .get(&entry.scope.canonical_key(&entry.name))?
+                .clone();
+            Some((entry, value))
+        })
+        .collect()
+}
+
+pub(crate) fn insert_entries(
+    file: &mut SecretsFile,
+    entries: &[(SecretListEntry, String)],
+) -> Result<()> {
+    for (entry, value) in entries {
+        anyhow::ensure!(
+            !value.is_empty(),
+            "secret {} has an empty value",
+            entry.name
+        );
+        file.secrets
+            .insert(entry.scope.canonical_key(&entry.name), value.clone());
+    }
+    Ok(())
 }
 
 fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp_path = write_temp_file(path, contents)?;
+    replace_with_temp_file(&tmp_path, path)
+}
+
+/// Write `contents` to a new temp file next to `path` and return its path.
+fn write_temp_file(path: &Path, contents: &[u8]) -> Result<PathBuf> {
     let dir = path.parent().with_context(|| {
         format!(
             "failed to compute parent directory for secrets file at {}",
@@ -208,10 +320,11 @@ fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<()> {
     let nonce = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map_or(0, |duration| duration.as_nanos());
-    let tmp_path = dir.join(format!(
-        ".{LOCAL_SECRETS_FILENAME}.tmp-{}-{nonce}",
-        std::process::id()
-    ));
+    let file_name = path.file_name().map_or_else(
+        || LOCAL_SECRETS_FILENAME.into(),
+        |name| name.to_string_lossy(),
+    );
+    let tmp_path = dir.join(format!(".{file_name}.tmp-{}-{nonce}", std::process::id()));
 
     {
         let mut tmp_file = fs::OpenOptions::new()
@@ -234,8 +347,13 @@ fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<()> {
             format!("failed to sync temp secrets file at {}", tmp_path.display())
         })?;
     }
+    Ok(tmp_path)
+}
 
-    match fs::rename(&tmp_path, path) {
+/// Move a file written by [`write_temp_file`] over `path`, removing the temp
+/// file if that fails.
+fn replace_with_temp_file(tmp_path: &Path, path: &Path) -> Result<()> {
+    match fs::rename(tmp_path, path) {
         Ok(()) => Ok(()),
         Err(initial_error) => {
             #[cfg(target_os = "windows")]
@@ -247,7 +365,7 @@ fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<()> {
                             path.display()
                         )
                     })?;
-                    fs::rename(&tmp_path, path).with_context(|| {
+                    fs::rename(tmp_path, path).with_context(|| {
                         format!(
                             "failed to replace secrets file at {} with {}",
                             path.display(),
@@ -258,7 +376,7 @@ fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<()> {
                 }
             }
 
-            let _ = fs::remove_file(&tmp_path);
+            let _ = fs::remove_file(tmp_path);
             Err(initial_error).with_context(|| {
                 format!(
                     "failed to atomically replace secrets file at {} with {}",
@@ -270,7 +388,7 @@ fn write_file_atomically(path: &Path, contents: &[u8]) -> Result<()> {
     }
 }
 
-fn generate_passphrase() -> Result<SecretString> {
+pub(crate) fn generate_passphrase() -> Result<SecretString> {
     let mut bytes = [0_u8; 32];
     let mut rng = OsRng;
     rng.try_fill_bytes(&mut bytes)
@@ -408,4 +526,27 @@ mod tests {
         assert_eq!(backend.get(&scope, &name)?, Some("two".to_string()));
         Ok(())
     }
+
+    #[test]
+    fn rekey_replaces_file_and_key_without_leaving_temp_files() -> Result<()> {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let keyring = Arc::new(MockKeyringStore::default());
+        let backend = LocalSecretsBackend::new(codex_home.path().to_path_buf(), keyring.clone());
+        let account = compute_keyring_account(codex_home.path());
+
+        let scope = SecretScope::Global;
+        let name = SecretName::new("TEST_SECRET")?;
+        backend.set(&scope, &name, "value")?;
+        let previous_key = keyring.saved_value(&account);
+
+        backend.rekey()?;
+
+        assert_ne!(keyring.saved_value(&account), previous_key);
+        let filenames: Vec<String> = fs::read_dir(backend.secrets_dir())?
+            .filter_map(|entry| entry.ok()?.file_name().to_str().map(ToString::to_string))
+            .collect();
+        assert_eq!(filenames, vec![LOCAL_SECRETS_FILENAME.to_string()]);
+        assert_eq!(backend.get(&scope, &name)?, Some("value".to_string()));
+        Ok(())
+    }
 }
@@ -0,0 +1,303 @@
+//! Secrets backend for machines without an OS keyring.
+//!
+//! Secrets live in `codex_home/secrets/passphrase.age`, an age file encrypted
+//! with a key derived from a user supplied passphrase (scrypt for the key,
+//! ChaCha20-Poly1305 for the payload), so tampering or a wrong passphrase is
+//! detected on read. The passphrase comes from `CODEX_SECRETS_PASSPHRASE` or,
+//! to keep it out of the environment, from the file descriptor named by
+//! `CODEX_SECRETS_PASSPHRASE_FD`. The descriptor is closed once read, and the
+//! passphrase is registered for redaction so it never shows up in output.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use age::secrecy::ExposeSecret;
+use age::secrecy::SecretString;
+use anyhow::Context;
+use anyhow::Result;
+
+use crate::SecretListEntry;
+use crate::SecretName;
+use crate::SecretScope;
+use crate::SecretsBackend;
+use crate::local::SecretsFile;
+use crate::local::file_entries;
+use crate::local::insert_entries;
+use crate::local::list_entries;
+use crate::local::load_secrets_file;
+use crate::local::save_secrets_file;
+use crate::register_secret_for_redaction;
+
+/// Environment variable holding the passphrase.
+pub const PASSPHRASE_ENV_VAR: &str = "CODEX_SECRETS_PASSPHRASE";
+/// Environment variable naming a file descriptor to read the passphrase from.
+pub const PASSPHRASE_FD_ENV_VAR: &str = "CODEX_SECRETS_PASSPHRASE_FD";
+
+const PASSPHRASE_SECRETS_FILENAME: &str = "passphrase.age";
+
+/// A file descriptor is closed after it is read, so passphrases read from one
+/// are kept for the rest of the process.
+static FD_PASSPHRASES: LazyLock<Mutex<HashMap<i32, SecretString>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Where [`PassphraseSecretsBackend`] gets its passphrase from.
+#[derive(Clone)]
+pub enum PassphraseSource {
+    /// Read the named environment variable.
+    EnvVar(String),
+    /// Read the given file descriptor to EOF. Unix only.
+    Fd(i32),
+    /// A passphrase that is already known.
+    Literal(SecretString),
+}
+
+impl std::fmt::Debug for PassphraseSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EnvVar(name) => f.debug_tuple("EnvVar").field(name).finish(),
+            Self::Fd(fd) => f.debug_tuple("Fd").field(fd).finish(),
+            Self::Literal(_) => f.write_str("Literal(..)"),
+        }
+    }
+}
+
+impl PassphraseSource {
+    /// `CODEX_SECRETS_PASSPHRASE_FD` when it is set, otherwise
+    /// `CODEX_SECRETS_PASSPHRASE`.
+    pub fn from_env() -> Self {
+        match std::env::var(PASSPHRASE_FD_ENV_VAR)
+            .ok()
+            .and_then(|fd| fd.trim().parse().ok())
+        {
+            Some(fd) => Self::Fd(fd),
+            None => Self::EnvVar(PASSPHRASE_ENV_VAR.to_string()),
+        }
+    }
+
+    fn read(&self) -> Result<SecretString> {
+        let passphrase = match self {
+            Self::EnvVar(name) => {
+                let value = std::env::var(name).with_context(|| {
+                    format!(
+                        "the passphrase secrets backend needs a passphrase in {name} or a file descriptor in {PASSPHRASE_FD_ENV_VAR}"
+                    )
+                })?;
+                SecretString::from(value)
+            }
+            Self::Fd(fd) => read_fd_passphrase(*fd)?,
+            Self::Literal(passphrase) => passphrase.clone(),
+        };
+        anyhow::ensure!(
+            !passphrase.expose_secret().is_empty(),
+            "secrets passphrase must not be empty"
+        );
+        if let Ok(name) = SecretName::new(PASSPHRASE_ENV_VAR) {
+            register_secret_for_redaction(&name, passphrase.expose_secret());
+        }
+        Ok(passphrase)
+    }
+}
+
+fn read_fd_passphrase(fd: i32) -> Result<SecretString> {
+    let mut cache = match FD_PASSPHRASES.lock() {
+        Ok(cache) => cache,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(passphrase) = cache.get(&fd) {
+        return Ok(passphrase.clone());
+    }
+    let raw = read_fd_to_string(fd)?;
+    let trimmed = raw
+        .strip_suffix("\r\n")
+        .or_else(|| raw.strip_suffix('\n'))
+        .unwrap_or(&raw);
+    let passphrase = SecretString::from(trimmed.to_string());
+    cache.insert(fd, passphrase.clone());
+    Ok(passphrase)
+}
+
+/// Reads `fd` to EOF and closes it, so child processes never inherit it.
+#[cfg(unix)]
+fn read_fd_to_string(fd: i32) -> Result<String> {
+    use std::io::Read;
+    use std::os::fd::FromRawFd;
+
+    anyhow::ensure!(
+        fd > 2,
+        "{PASSPHRASE_FD_ENV_VAR} must name a file descriptor other than stdin, stdout or stderr"
+    );
+    // SAFETY: the caller handed this descriptor to Codex for the passphrase
+    // alone; taking ownership closes it once it has been read.
+    let mut file = unsafe { std::fs::File::from_raw_fd(fd) };
+    let mut raw = String::new();
+    file.read_to_string(&mut raw)
+        .with_context(|| format!("failed to read secrets passphrase from file descriptor {fd}"))?;
+    Ok(raw)
+}
+
+#[cfg(not(unix))]
+fn read_fd_to_string(fd: i32) -> Result<String> {
+    anyhow::bail!(
+        "reading the secrets passphrase from file descriptor {fd} is only supported on Unix; set {PASSPHRASE_ENV_VAR} instead"
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct PassphraseSecretsBackend {
+    codex_home: PathBuf,
+    source: PassphraseSource,
+}
+
+impl PassphraseSecretsBackend {
+    pub fn new(codex_home: PathBuf, source: PassphraseSource) -> Self {
+        Self { codex_home, source }
+    }
+
+    fn secrets_path(&self) -> PathBuf {
+        self.codex_home
+            .join("secrets")
+            .join(PASSPHRASE_SECRETS_FILENAME)
+    }
+
+    fn load_file(&self) -> Result<SecretsFile> {
+        let path = self.secrets_path();
+        if !path.exists() {
+            return Ok(SecretsFile::new_empty());
+        }
+        let passphrase = self.source.read()?;
+        load_secrets_file(&path, &passphrase)
+            .context("check that the secrets passphrase is correct")
+    }
+
+    fn save_file(&self, file: &SecretsFile) -> Result<()> {
+        save_secrets_file(&self.secrets_path(), file, &self.source.read()?)
+    }
+}
+
+impl SecretsBackend for PassphraseSecretsBackend {
+    fn set(&self, scope: &SecretScope, name: &SecretName, value: &str) -> Result<()> {
+        anyhow::ensure!(!value.is_empty(), "secret value must not be empty");
+        let mut file = self.load_file()?;
+        file.secrets
+            .insert(scope.canonical_key(name), value.to_string());
+        self.save_file(&file)
+    }
+
+    fn get(&self, scope: &SecretScope, name: &SecretName) -> Result<Option<String>> {
+        let file = self.load_file()?;
+        Ok(file.secrets.get(&scope.canonical_key(name)).cloned())
+    }
+
+    fn delete(&self, scope: &SecretScope, name: &SecretName) -> Result<bool> {
+        let mut file = self.load_file()?;
+        let removed = file.secrets.remove(&scope.canonical_key(name)).is_some();
+        if removed {
+            self.save_file(&file)?;
+        }
+        Ok(removed)
+    }
+
+    fn list(&self, scope_filter: Option<&SecretScope>) -> Result<Vec<SecretListEntry>> {
+        let file = self.load_file()?;
+        Ok(list_entries(&file, scope_filter))
+    }
+
+    fn entries(&self) -> Result<Vec<(SecretListEntry, String)>> {
+        let file = self.load_file()?;
+        Ok(file_entries(&file))
+    }
+
+    fn import(&self, entries: &[(SecretListEntry, String)]) -> Result<()> {
+        let mut file = self.load_file()?;
+        insert_entries(&mut file, entries)?;
+        self.save_file(&file)
+    }
+
+    fn rekey(&self, new_passphrase: Option<&str>) -> Result<()> {
+        let new_passphrase = new_passphrase
+            .filter(|passphrase| !passphrase.is_empty())
+            .context("rekeying the passphrase secrets backend requires a new passphrase")?;
+        let file = self.load_file()?;
+        save_secrets_file(
+            &self.secrets_path(),
+            &file,
+            &SecretString::from(new_passphrase.to_string()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn backend(codex_home: &tempfile::TempDir, passphrase: &str) -> PassphraseSecretsBackend {
+        PassphraseSecretsBackend::new(
+            codex_home.path().to_path_buf(),
+            PassphraseSource::Literal(SecretString::from(passphrase.to_string())),
+        )
+    }
+
+    #[test]
+    fn round_trips_and_rejects_wrong_passphrase() -> Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let name = SecretName::new("DEPLOY_KEY")?;
+        backend(&codex_home, "correct horse").set(&SecretScope::Global, &name, "s3cr3t")?;
+
+        let ciphertext = std::fs::read(codex_home.path().join("secrets/passphrase.age"))?;
+        assert!(
+            !String::from_utf8_lossy(&ciphertext).contains("s3cr3t"),
+            "secret must not be stored in plaintext"
+        );
+        assert_eq!(
+            backend(&codex_home, "correct horse").get(&SecretScope::Global, &name)?,
+            Some("s3cr3t".to_string())
+        );
+        let error = backend(&codex_home, "wrong horse")
+            .get(&SecretScope::Global, &name)
+            .expect_err("wrong passphrase must fail");
+        assert!(
+            format!("{error:#}").contains("passphrase is correct"),
+            "unexpected error: {error:#}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rekey_switches_passphrase() -> Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let name = SecretName::new("DEPLOY_KEY")?;
+        let old = backend(&codex_home, "old passphrase");
+        old.set(&SecretScope::Global, &name, "value")?;
+
+        old.rekey(Some("new passphrase"))?;
+
+        assert!(old.get(&SecretScope::Global, &name).is_err());
+        assert_eq!(
+            backend(&codex_home, "new passphrase").get(&SecretScope::Global, &name)?,
+            Some("value".to_string())
+        );
+        assert!(old.rekey(None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_env_var_explains_how_to_supply_passphrase() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let backend = PassphraseSecretsBackend::new(
+            codex_home.path().to_path_buf(),
+            PassphraseSource::EnvVar("CODEX_SECRETS_TEST_UNSET_PASSPHRASE".to_string()),
+        );
+        let name = SecretName::new("DEPLOY_KEY").expect("valid name");
+
+        let error = backend
+            .set(&SecretScope::Global, &name, "value")
+            .expect_err("missing passphrase must fail");
+        assert!(
+            error.to_string().contains(PASSPHRASE_FD_ENV_VAR),
+            "unexpected error: {error:#}"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+//! Process-wide redaction of secret values.
+//!
+//! Secrets injected into child processes can come back in command output,
+//! tool results or log lines. Every value handed out by Codex is registered
+//! here so those surfaces can replace it with a `[secret:NAME]` placeholder
+//! before the text leaves the process.
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+use tracing::warn;
+
+use crate::SecretName;
+
+/// Values shorter than this are not redacted: replacing every occurrence of a
+/// one or two character secret would mangle all output.
+pub const MIN_REDACTED_SECRET_LEN: usize = 8;
+
+static REGISTRY: LazyLock<RwLock<Arc<SecretRedactor>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(SecretRedactor::default())));
+
+/// Replaces known secret values with `[secret:NAME]` placeholders.
+#[derive(Debug, Clone, Default)]
+pub struct SecretRedactor {
+    /// Patterns to replace, longest first so a secret that contains another
+    /// secret is replaced as a whole.
+    patterns: Vec<RedactionPattern>,
+}
+
+#[derive(Debug, Clone)]
+struct RedactionPattern {
+    needle: String,
+    placeholder: String,
+}
+
+impl SecretRedactor {
+    /// Add `value` under `name`. Both the raw value and its JSON string
+    /// escaped form are matched so serialized payloads are covered too.
+    /// Values shorter than [`MIN_REDACTED_SECRET_LEN`] bytes are skipped.
+    pub fn add(&mut self, name: &SecretName, value: &str) {
+        if value.len() < MIN_REDACTED_SECRET_LEN {
+            if !value.is_empty() {
+                warn!(
+                    "secret `{name}` is shorter than {MIN_REDACTED_SECRET_LEN} bytes and is not redacted from output"
+                );
+            }
+            return;
+        }
+        let placeholder = secret_placeholder(name);
+        let mut needles = vec![value.to_string()];
+        if let Ok(quoted) = serde_json::to_string(value)
+            && let Some(escaped) = quoted
+                .strip_prefix('"')
+                .and_then(|rest| rest.strip_suffix('"'))
+            && escaped != value
+        {
+            needles.push(escaped.to_string());
+        }
+        for needle in needles {
+            if self.patterns.iter().any(|pattern| pattern.needle == needle) {
+                continue;
+            }
+            self.patterns.push(RedactionPattern {
+                needle,
+                placeholder: placeholder.clone(),
+            });
+        }
+        self.patterns
+            .sort_by_key(|pattern| Reverse(pattern.needle.len()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Replace every known secret in `text`.
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut redacted = Cow::Borrowed(text);
+        for pattern in &self.patterns {
+            if redacted.contains(pattern.needle.as_str()) {
+                redacted = Cow::Owned(redacted.replace(&pattern.needle, &pattern.placeholder));
+            }
+        }
+        redacted
+    }
+
+    /// Replace every known secret in raw process output, which may not be
+    /// valid UTF-8.
+    pub fn redact_bytes<'a>(&self, bytes: &'a [u8]) -> Cow<'a, [u8]> {
+        let mut redacted = Cow::Borrowed(bytes);
+        for pattern in &self.patterns {
+            if let Some(replaced) = replace_bytes(
+                &redacted,
+                pattern.needle.as_bytes(),
+                pattern.placeholder.as_bytes(),
+            ) {
+                redacted = Cow::Owned(replaced);
+            }
+        }
+        redacted
+    }
+
+    /// Where to cut `bytes`, the buffered part of a stream, so that nothing
+    /// before the cut can belong to a secret that continues after it.
+    fn stream_split(&self, bytes: &[u8]) -> usize {
+        // Patterns are sorted longest first.
+        let longest = self
+            .patterns
+            .first()
+            .map_or(0, |pattern| pattern.needle.len());
+        let mut split = bytes.len().saturating_sub(longest.saturating_sub(1));
+        // Keep a multi-byte character on one side of the cut.
+        let floor = split.saturating_sub(3);
+        while split > floor && split < bytes.len() && (bytes[split] & 0xC0) == 0x80 {
+            split -= 1;
+        }
+        // A complete secret that starts before the cut is released whole.
+        let mut idx = 0;
+        while idx < split {
+            let matched = self
+                .patterns
+                .iter()
+                .find(|pattern| bytes[idx..].starts_with(pattern.needle.as_bytes()));
+            idx += matched.map_or(1, |pattern| pattern.needle.len());
+        }
+        idx.max(split)
+    }
+}
+
+/// Redacts output that arrives in chunks, such as a child process's stdout.
+///
+/// A secret can be split across two reads, so the last (longest secret - 1)
+/// bytes are held back and redacted together with the next chunk.
+#[derive(Debug)]
+pub struct StreamRedactor {
+    redactor: Arc<SecretRedactor>,
+    pending: Vec<u8>,
+}
+
+impl StreamRedactor {
+    /// Redact the secrets registered in this process when the stream starts.
+    pub fn new() -> Self {
+        Self::with_redactor(secret_redactor())
+    }
+
+    /// Redact the secrets known to `redactor`.
+    pub fn with_redactor(redactor: Arc<SecretRedactor>) -> Self {
+        Self {
+            redactor,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Add `chunk` and return the redacted output that is ready to be shown.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        self.pending.extend_from_slice(chunk);
+        let split = self.redactor.stream_split(&self.pending);
+        let rest = self.pending.split_off(split);
+        let ready = std::mem::replace(&mut self.pending, rest);
+        self.redactor.redact_bytes(&ready).into_owned()
+    }
+
+    /// Return the redacted remainder once the stream has ended.
+    pub fn finish(&mut self) -> Vec<u8> {
+        let rest = std::mem::take(&mut self.pending);
+        self.redactor.redact_bytes(&rest).into_owned()
+    }
+}
+
+impl Default for StreamRedactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The placeholder that replaces the value of `name`.
+pub fn secret_placeholder(name: &SecretName) -> String {
+    format!("[secret:{name}]")
+}
+
+/// Register a secret value so [`redact_secrets`] replaces it from now on.
+pub fn register_secret_for_redaction(name: &SecretName, value: &str) {
+    let mut registry = match REGISTRY.write() {
+        Ok(registry) => registry,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let mut redactor = registry.as_ref().clone();
+    redactor.add(name, value);
+    *registry = Arc::new(redactor);
+}
+
+/// A snapshot of every secret registered in this process.
+pub fn secret_redactor() -> Arc<SecretRedactor> {
+    match REGISTRY.read() {
+        Ok(registry) => Arc::clone(&registry),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+    }
+}
+
+/// Replace every registered secret in `text`.
+pub fn redact_secrets(text: &str) -> Cow<'_, str> {
+    let redactor = secret_redactor();
+    if redactor.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    redactor.redact(text)
+}
+
+/// Replace every registered secret in raw bytes.
+pub fn redact_secret_bytes(bytes: &[u8]) -> Cow<'_, [u8]> {
+    let redactor = secret_redactor();
+    if redactor.is_empty() {
+        return Cow::Borrowed(bytes);
+    }
+    redactor.redact_bytes(bytes)
+}
+
+/// Replace every registered secret in the strings of a JSON value.
+pub fn redact_secrets_in_json(value: &mut serde_json::Value) {
+    let redactor = secret_redactor();
+    if redactor.is_empty() {
+        return;
+    }
+    redact_json_with(&redactor, value);
+}
+
+/// A writer that redacts registered secrets before forwarding each write.
+///
+/// Log formatters emit a whole line per write, so secrets are not split across
+/// calls in practice.
+#[derive(Debug, Clone)]
+pub struct RedactingWriter<W> {
+    inner: W,
+}
+
+impl<W> RedactingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write_all(&redact_secret_bytes(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn redact_json_with(redactor: &SecretRedactor, value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(text) => {
+            if let Cow::Owned(redacted) = redactor.redact(text) {
+                *text = redacted;
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_json_with(redactor, item);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                redact_json_with(redactor, item);
+            }
+        }
+        serde_json::Value::Null | serde_json::Value::Bool(_) | serde_json::Value::Number(_) => {}
+    }
+}
+
+fn replace_bytes(haystack: &[u8], needle: &[u8], replacement: &[u8]) -> Option<Vec<u8>> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+    let mut out: Option<Vec<u8>> = None;
+    let mut start = 0;
+    let mut idx = 0;
+    while idx + needle.len() <= haystack.len() {
+        if &haystack[idx..idx + needle.len()] == needle {
+            let buf = out.get_or_insert_with(|| Vec::with_capacity(haystack.len()));
+            buf.extend_from_slice(&haystack[start..idx]);
+            buf.extend_from_slice(replacement);
+            idx += needle.len();
+            start = idx;
+        } else {
+            idx += 1;
+        }
+    }
+    let mut out = out?;
+    out.extend_from_slice(&haystack[start..]);
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn name(raw: &str) -> SecretName {
+        SecretName::new(raw).expect("valid secret name")
+    }
+
+    #[test]
+    fn redacts_longest_secret_first() {
+        let mut redactor = SecretRedactor::default();
+        redactor.add(&name("SHORT"), "abc12345");
+        redactor.add(&name("LONG"), "xyz-abc12345-xyz");
+
+        assert_eq!(
+            redactor.redact("token=xyz-abc12345-xyz and abc12345"),
+            "token=[secret:LONG] and [secret:SHORT]"
+        );
+        assert!(matches!(redactor.redact("nothing here"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn skips_values_too_short_to_redact() {
+        let mut redactor = SecretRedactor::default();
+        redactor.add(&name("PIN"), "1");
+        redactor.add(&name("CODE"), "ab");
+        redactor.add(&name("ALMOST"), "1234567");
+
+        assert!(redactor.is_empty());
+        assert_eq!(
+            redactor.redact("pin 1, code ab, 1234567"),
+            "pin 1, code ab, 1234567"
+        );
+    }
+
+    #[test]
+    fn redacts_json_escaped_values_and_raw_bytes() {
+        let mut redactor = SecretRedactor::default();
+        redactor.add(&name("QUOTED"), "pa\"ssword");
+
+        let line = serde_json::to_string(&serde_json::json!({ "output": "x pa\"ssword y" }))
+            .expect("serialize");
+        assert_eq!(
+            redactor.redact(&line),
+            r#"{"output":"x [secret:QUOTED] y"}"#
+        );
+        assert_eq!(
+            redactor.redact_bytes(b"\xffpa\"ssword\xff").as_ref(),
+            b"\xff[secret:QUOTED]\xff"
+        );
+    }
+
+    #[test]
+    fn stream_redacts_secrets_split_across_chunks() {
+        let mut redactor = SecretRedactor::default();
+        redactor.add(&name("TOKEN"), "tok-123456");
+        let mut stream = StreamRedactor::with_redactor(Arc::new(redactor));
+
+        let mut output = stream.push(b"auth: tok-12");
+        assert_eq!(output, b"aut");
+        output.extend(stream.push(b"3456 ok\n"));
+        assert_eq!(output, b"auth: [secret:TOKEN]");
+        output.extend(stream.push(b"done tok-123456"));
+        assert_eq!(output, b"auth: [secret:TOKEN] ok\ndone [secret:TOKEN]");
+        assert_eq!(stream.pending, b"");
+    }
+
+    #[test]
+    fn stream_keeps_multi_byte_characters_together() {
+        let mut redactor = SecretRedactor::default();
+        redactor.add(&name("TOKEN"), "abcdefgh");
+        let mut stream = StreamRedactor::with_redactor(Arc::new(redactor));
+
+        // The cut falls seven bytes from the end, inside the `\u{e9}`.
+        let output = stream.push("abcdefgx\u{e9}zzzzzz".as_bytes());
+        assert_eq!(output, b"abcdefgx");
+        assert_eq!(stream.pending, "\u{e9}zzzzzz".as_bytes());
+    }
+
+    #[test]
+    fn redacts_strings_inside_json_values() {
+        let mut redactor = SecretRedactor::default();
+        redactor.add(&name("API_KEY"), "sk-test-key");
+        let mut value = serde_json::json!({
+            "content": [{ "type": "text", "text": "key is sk-test-key" }],
+            "count": 1,
+        });
+
+        redact_json_with(&redactor, &mut value);
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "content": [{ "type": "text", "text": "key is [secret:API_KEY]" }],
+                "count": 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,27 @@
+CREATE TABLE thread_search_entries (
+    id INTEGER PRIMARY KEY,
+    thread_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    content TEXT NOT NULL,
+    UNIQUE(thread_id, source, content),
+    FOREIGN KEY(thread_id) REFERENCES threads(id) ON DELETE CASCADE
+);
+
+CREATE VIRTUAL TABLE thread_search USING fts5(
+    content,
+    content = 'thread_search_entries',
+    content_rowid = 'id',
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER thread_search_entries_ai AFTER INSERT ON thread_search_entries BEGIN
+    INSERT INTO thread_search(rowid, content) VALUES (new.id, new.content);
+END;
+
+CREATE TRIGGER thread_search_entries_ad AFTER DELETE ON thread_search_entries BEGIN
+    INSERT INTO thread_search(thread_search, rowid, content) VALUES ('delete', old.id, old.content);
+END;
+
+-- Threads mirrored before this migration have no search entries yet; rerun the
+-- backfill so every rollout is indexed.
+UPDATE backfill_state SET status = 'pending', last_watermark = NULL;
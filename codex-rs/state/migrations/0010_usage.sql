@@ -0,0 +1,30 @@
+CREATE TABLE usage (
+    id INTEGER PRIMARY KEY,
+    thread_id TEXT NOT NULL,
+    turn_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    model_provider TEXT NOT NULL,
+    cwd TEXT NOT NULL,
+    input_tokens INTEGER NOT NULL,
+    cached_input_tokens INTEGER NOT NULL,
+    output_tokens INTEGER NOT NULL,
+    reasoning_output_tokens INTEGER NOT NULL,
+    recorded_at INTEGER NOT NULL
+);
+
+CREATE INDEX idx_usage_recorded_at ON usage(recorded_at);
+CREATE INDEX idx_usage_thread_id ON usage(thread_id);
+
+-- Turns are identified by a UUID minted when they start, recorded both in the
+-- usage row and in the rollout's turn contexts; turns of rollouts without one
+-- are numbered by their position (`rollout-turn-N`). Either way
+-- (thread_id, turn_id) names one turn, so rerunning the backfill skips turns
+-- already recorded. Older rollouts carry per-process counters that repeat
+-- across resumes, so those stay outside the index.
+CREATE UNIQUE INDEX idx_usage_turn ON usage(thread_id, turn_id)
+    WHERE turn_id GLOB 'rollout-turn-*'
+        OR turn_id GLOB '????????-????-????-????-????????????';
+
+-- Usage is also reconstructed from token counts in existing rollouts; rerun
+-- the backfill so sessions recorded before this migration are included.
+UPDATE backfill_state SET status = 'pending', last_watermark = NULL;
@@ -0,0 +1,6 @@
+ALTER TABLE threads ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE threads ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE threads ADD COLUMN folder TEXT;
+
+CREATE INDEX idx_threads_pinned ON threads(pinned);
+CREATE INDEX idx_threads_folder ON threads(folder);
@@ -0,0 +1,3 @@
+ALTER TABLE logs ADD COLUMN turn_id TEXT;
+
+CREATE INDEX idx_logs_turn_id ON logs(turn_id);
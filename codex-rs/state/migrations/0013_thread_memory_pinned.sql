@@ -0,0 +1 @@
+ALTER TABLE thread_memory ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
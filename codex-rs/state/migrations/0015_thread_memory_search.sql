@@ -0,0 +1,24 @@
+CREATE VIRTUAL TABLE thread_memory_search USING fts5(
+    thread_id UNINDEXED,
+    memory_summary,
+    trace_summary,
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER thread_memory_ai AFTER INSERT ON thread_memory BEGIN
+    INSERT INTO thread_memory_search(thread_id, memory_summary, trace_summary)
+    VALUES (new.thread_id, new.memory_summary, new.trace_summary);
+END;
+
+CREATE TRIGGER thread_memory_ad AFTER DELETE ON thread_memory BEGIN
+    DELETE FROM thread_memory_search WHERE thread_id = old.thread_id;
+END;
+
+CREATE TRIGGER thread_memory_au AFTER UPDATE OF memory_summary, trace_summary ON thread_memory BEGIN
+    DELETE FROM thread_memory_search WHERE thread_id = old.thread_id;
+    INSERT INTO thread_memory_search(thread_id, memory_summary, trace_summary)
+    VALUES (new.thread_id, new.memory_summary, new.trace_summary);
+END;
+
+INSERT INTO thread_memory_search(thread_id, memory_summary, trace_summary)
+SELECT thread_id, memory_summary, trace_summary FROM thread_memory;
@@ -0,0 +1,9 @@
+CREATE TABLE message_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    thread_id TEXT,
+    cwd TEXT,
+    text TEXT NOT NULL,
+    ts INTEGER NOT NULL
+);
+
+CREATE INDEX idx_message_history_cwd ON message_history(cwd);
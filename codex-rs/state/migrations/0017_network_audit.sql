@@ -0,0 +1,17 @@
+CREATE TABLE network_audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ts INTEGER NOT NULL,
+    thread_id TEXT,
+    turn_id TEXT,
+    call_id TEXT,
+    protocol TEXT NOT NULL,
+    host TEXT NOT NULL,
+    method TEXT,
+    decision TEXT NOT NULL,
+    reason TEXT,
+    bytes_sent INTEGER,
+    bytes_received INTEGER
+);
+
+CREATE INDEX idx_network_audit_ts ON network_audit(ts);
+CREATE INDEX idx_network_audit_thread_id ON network_audit(thread_id);
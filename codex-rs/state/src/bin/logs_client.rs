@@ -234,6 +234,7 @@ fn to_log_query(
         file_like: filter.file_like.clone(),
         thread_ids: filter.thread_ids.clone(),
         include_threadless: filter.include_threadless,
+        filter: None,
         after_id,
         limit,
         descending,
@@ -1,8 +1,16 @@
 use crate::model::ThreadMetadata;
+use crate::model::ThreadSearchEntry;
+use crate::model::ThreadSearchSource;
+use crate::model::TurnUsage;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::models::LocalShellAction;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
 use codex_protocol::protocol::SessionMetaLine;
+use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TurnContextItem;
 use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 use codex_protocol::protocol::UserMessageEvent;
@@ -10,6 +18,16 @@ use serde::Serialize;
 use serde_json::Value;
 
 const IMAGE_ONLY_USER_MESSAGE_PLACEHOLDER: &str = "[Image]";
+/// Upper bound on the text indexed per search entry; long agent messages are
+/// cut rather than bloating the index.
+const MAX_SEARCH_CONTENT_BYTES: usize = 16 * 1024;
+/// Model recorded for usage when a rollout never names one.
+const UNKNOWN_USAGE_MODEL: &str = "unknown";
+
+/// Prefix of the turn ids given to usage reconstructed from a rollout whose
+/// turn contexts predate recording turn ids. Those turns are numbered by
+/// position instead.
+pub(crate) const BACKFILLED_TURN_ID_PREFIX: &str = "rollout-turn-";
 
 /// Apply a rollout item to the metadata structure.
 pub fn apply_rollout_item(
@@ -85,6 +103,186 @@ fn apply_response_item(_metadata: &mut ThreadMetadata, _item: &ResponseItem) {
     // Title and first_user_message are derived from EventMsg::UserMessage only.
 }
 
+/// Collect the full-text search entries for rollout items: user and agent
+/// messages plus the commands the agent ran.
+pub fn thread_search_entries(items: &[RolloutItem]) -> Vec<ThreadSearchEntry> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let (source, content) = match item {
+                RolloutItem::EventMsg(EventMsg::UserMessage(user)) => (
+                    ThreadSearchSource::UserMessage,
+                    strip_user_message_prefix(user.message.as_str()).to_string(),
+                ),
+                RolloutItem::EventMsg(EventMsg::AgentMessage(agent)) => (
+                    ThreadSearchSource::AgentMessage,
+                    agent.message.trim().to_string(),
+                ),
+                RolloutItem::ResponseItem(item) => {
+                    (ThreadSearchSource::Command, command_text(item)?)
+                }
+                RolloutItem::SessionMeta(_)
+                | RolloutItem::TurnContext(_)
+                | RolloutItem::Compacted(_)
+                | RolloutItem::EventMsg(_) => return None,
+            };
+            if content.is_empty() {
+                return None;
+            }
+            Some(ThreadSearchEntry {
+                source,
+                content: truncate_search_content(content),
+            })
+        })
+        .collect()
+}
+
+fn command_text(item: &ResponseItem) -> Option<String> {
+    match item {
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => Some(exec.command.join(" ")),
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => {
+            let arguments: Value = serde_json::from_str(arguments).ok()?;
+            let command = match name.as_str() {
+                "exec_command" => arguments.get("cmd")?,
+                "shell" | "shell_command" => arguments.get("command")?,
+                _ => return None,
+            };
+            match command {
+                Value::String(command) => Some(command.trim().to_string()),
+                Value::Array(parts) => Some(
+                    parts
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn truncate_search_content(mut content: String) -> String {
+    if content.len() > MAX_SEARCH_CONTENT_BYTES {
+        let mut end = MAX_SEARCH_CONTENT_BYTES;
+        while !content.is_char_boundary(end) {
+            end -= 1;
+        }
+        content.truncate(end);
+    }
+    content
+}
+
+/// Reconstruct per-turn token usage from the lines of a rollout.
+///
+/// Token counts carry running totals for the session, so a turn's usage is
+/// how much those totals grew between the user message that started it and
+/// the next one. Turns without any growth produce no entry.
+pub fn turn_usage_from_rollout(metadata: &ThreadMetadata, lines: &[RolloutLine]) -> Vec<TurnUsage> {
+    let mut turns = Vec::new();
+    let mut current: Option<TurnUsage> = None;
+    let mut turn_index = 0usize;
+    let mut turn_id: Option<String> = None;
+    let mut model = UNKNOWN_USAGE_MODEL.to_string();
+    let mut cwd = metadata.cwd.clone();
+    let mut previous_totals = TokenUsage::default();
+    for line in lines {
+        match &line.item {
+            RolloutItem::TurnContext(turn_context) => {
+                model = turn_context.model.clone();
+                cwd = turn_context.cwd.clone();
+                if turn_context.turn_id.is_some() && turn_context.turn_id != turn_id {
+                    turns.extend(current.take());
+                    turn_id = turn_context.turn_id.clone();
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) => {
+                turns.extend(current.take());
+                turn_index += 1;
+                turn_id = None;
+            }
+            RolloutItem::EventMsg(EventMsg::TokenCount(token_count)) => {
+                let Some(info) = token_count.info.as_ref() else {
+                    continue;
+                };
+                let totals = &info.total_token_usage;
+                let delta = token_usage_delta(&previous_totals, totals);
+                previous_totals = totals.clone();
+                let Some(delta) = delta else {
+                    continue;
+                };
+                let recorded_at = DateTime::parse_from_rfc3339(line.timestamp.as_str())
+                    .map(|timestamp| timestamp.with_timezone(&Utc))
+                    .unwrap_or(metadata.updated_at);
+                let turn = current.get_or_insert_with(|| TurnUsage {
+                    thread_id: metadata.id,
+                    // Live recording keys turns by their id, so a turn
+                    // recorded both ways is recognized.
+                    turn_id: turn_id
+                        .clone()
+                        .unwrap_or_else(|| format!("{BACKFILLED_TURN_ID_PREFIX}{turn_index}")),
+                    model: model.clone(),
+                    model_provider: metadata.model_provider.clone(),
+                    cwd: cwd.clone(),
+                    input_tokens: 0,
+                    cached_input_tokens: 0,
+                    output_tokens: 0,
+                    reasoning_output_tokens: 0,
+                    recorded_at,
+                });
+                turn.model = model.clone();
+                turn.cwd = cwd.clone();
+                turn.input_tokens += delta.input_tokens;
+                turn.cached_input_tokens += delta.cached_input_tokens;
+                turn.output_tokens += delta.output_tokens;
+                turn.reasoning_output_tokens += delta.reasoning_output_tokens;
+                turn.recorded_at = recorded_at;
+            }
+            RolloutItem::SessionMeta(_)
+            | RolloutItem::ResponseItem(_)
+            | RolloutItem::Compacted(_)
+            | RolloutItem::EventMsg(_) => {}
+        }
+    }
+    turns.extend(current);
+    turns
+}
+
+/// Token usage added between two running totals, or `None` when nothing was
+/// added.
+///
+/// Totals only grow while a session runs. When any counter shrinks the totals
+/// were reset (for example after the context window was filled), and all of
+/// `current` counts as new usage.
+pub fn token_usage_delta(previous: &TokenUsage, current: &TokenUsage) -> Option<TokenUsage> {
+    let grew = current.input_tokens >= previous.input_tokens
+        && current.cached_input_tokens >= previous.cached_input_tokens
+        && current.output_tokens >= previous.output_tokens
+        && current.reasoning_output_tokens >= previous.reasoning_output_tokens;
+    let delta = if grew {
+        TokenUsage {
+            input_tokens: current.input_tokens - previous.input_tokens,
+            cached_input_tokens: current.cached_input_tokens - previous.cached_input_tokens,
+            output_tokens: current.output_tokens - previous.output_tokens,
+            reasoning_output_tokens: current.reasoning_output_tokens
+                - previous.reasoning_output_tokens,
+            total_tokens: current
+                .total_tokens
+                .saturating_sub(previous.total_tokens)
+                .max(0),
+        }
+    } else {
+        current.clone()
+    };
+    (delta.input_tokens > 0 || delta.output_tokens > 0).then_some(delta)
+}
+
 fn strip_user_message_prefix(text: &str) -> &str {
     match text.find(USER_MESSAGE_BEGIN) {
         Some(idx) => text[idx + USER_MESSAGE_BEGIN.len()..].trim(),
@@ -119,14 +317,28 @@ pub(crate) fn enum_to_string<T: Serialize>(value: &T) -> String {
 #[cfg(test)]
 mod tests {
     use super::apply_rollout_item;
+    use super::thread_search_entries;
+    use super::turn_usage_from_rollout;
     use crate::model::ThreadMetadata;
+    use crate::model::ThreadSearchEntry;
+    use crate::model::ThreadSearchSource;
+    use crate::model::TurnUsage;
     use chrono::DateTime;
     use chrono::Utc;
     use codex_protocol::ThreadId;
+    use codex_protocol::config_types::ReasoningSummary;
     use codex_protocol::models::ContentItem;
     use codex_protocol::models::ResponseItem;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::AskForApproval;
     use codex_protocol::protocol::EventMsg;
     use codex_protocol::protocol::RolloutItem;
+    use codex_protocol::protocol::RolloutLine;
+    use codex_protocol::protocol::SandboxPolicy;
+    use codex_protocol::protocol::TokenCountEvent;
+    use codex_protocol::protocol::TokenUsage;
+    use codex_protocol::protocol::TokenUsageInfo;
+    use codex_protocol::protocol::TurnContextItem;
     use codex_protocol::protocol::USER_MESSAGE_BEGIN;
     use codex_protocol::protocol::UserMessageEvent;
 
@@ -207,6 +419,148 @@ mod tests {
         assert_eq!(metadata.title, "");
     }
 
+    #[test]
+    fn thread_search_entries_cover_messages_and_commands() {
+        let items = vec![
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: format!("{USER_MESSAGE_BEGIN} fix the flaky test"),
+                images: None,
+                local_images: vec![],
+                text_elements: vec![],
+            })),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["cargo","test","-p","codex-state"]}"#.to_string(),
+                call_id: "call-1".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "exec_command".to_string(),
+                arguments: r#"{"cmd":"rg flaky"}"#.to_string(),
+                call_id: "call-2".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "view_image".to_string(),
+                arguments: r#"{"path":"a.png"}"#.to_string(),
+                call_id: "call-3".to_string(),
+            }),
+            RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+                message: "The test is fixed.".to_string(),
+            })),
+        ];
+
+        assert_eq!(
+            thread_search_entries(&items),
+            vec![
+                ThreadSearchEntry {
+                    source: ThreadSearchSource::UserMessage,
+                    content: "fix the flaky test".to_string(),
+                },
+                ThreadSearchEntry {
+                    source: ThreadSearchSource::Command,
+                    content: "cargo test -p codex-state".to_string(),
+                },
+                ThreadSearchEntry {
+                    source: ThreadSearchSource::Command,
+                    content: "rg flaky".to_string(),
+                },
+                ThreadSearchEntry {
+                    source: ThreadSearchSource::AgentMessage,
+                    content: "The test is fixed.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn turn_usage_from_rollout_splits_token_growth_by_turn() {
+        let metadata = metadata_for_test();
+        let user_message = |text: &str| {
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: text.to_string(),
+                images: None,
+                local_images: vec![],
+                text_elements: vec![],
+            }))
+        };
+        let token_count = |input: i64, cached: i64, output: i64| {
+            RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                info: Some(TokenUsageInfo {
+                    total_token_usage: TokenUsage {
+                        input_tokens: input,
+                        cached_input_tokens: cached,
+                        output_tokens: output,
+                        reasoning_output_tokens: 0,
+                        total_tokens: input + output,
+                    },
+                    last_token_usage: TokenUsage::default(),
+                    model_context_window: None,
+                }),
+                rate_limits: None,
+            }))
+        };
+        let turn_context = |turn_id: Option<&str>| {
+            RolloutItem::TurnContext(TurnContextItem {
+                turn_id: turn_id.map(str::to_string),
+                cwd: PathBuf::from("/repo"),
+                approval_policy: AskForApproval::OnRequest,
+                sandbox_policy: SandboxPolicy::ReadOnly,
+                model: "gpt-5".to_string(),
+                personality: None,
+                collaboration_mode: None,
+                effort: None,
+                summary: ReasoningSummary::Auto,
+                user_instructions: None,
+                developer_instructions: None,
+                final_output_json_schema: None,
+                truncation_policy: None,
+            })
+        };
+        let lines = [
+            // Written before turn contexts carried turn ids.
+            ("2025-01-01T10:00:00.000Z", user_message("first")),
+            ("2025-01-01T10:00:01.000Z", turn_context(None)),
+            ("2025-01-01T10:00:02.000Z", token_count(100, 20, 10)),
+            // Rate limit updates repeat the same totals.
+            ("2025-01-01T10:00:03.000Z", token_count(100, 20, 10)),
+            ("2025-01-01T10:00:04.000Z", token_count(250, 80, 30)),
+            ("2025-01-02T09:00:00.000Z", user_message("second")),
+            ("2025-01-02T09:00:01.000Z", turn_context(Some("7"))),
+            ("2025-01-02T09:00:02.000Z", token_count(400, 200, 45)),
+            ("2025-01-02T09:30:00.000Z", user_message("no model call")),
+        ]
+        .into_iter()
+        .map(|(timestamp, item)| RolloutLine {
+            timestamp: timestamp.to_string(),
+            item,
+        })
+        .collect::<Vec<_>>();
+
+        let turn = |turn_id: &str, input, cached, output, recorded_at: &str| TurnUsage {
+            thread_id: metadata.id,
+            turn_id: turn_id.to_string(),
+            model: "gpt-5".to_string(),
+            model_provider: "openai".to_string(),
+            cwd: PathBuf::from("/repo"),
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            recorded_at: DateTime::parse_from_rfc3339(recorded_at)
+                .expect("timestamp")
+                .with_timezone(&Utc),
+        };
+        assert_eq!(
+            turn_usage_from_rollout(&metadata, &lines),
+            vec![
+                turn("rollout-turn-1", 250, 80, 30, "2025-01-01T10:00:04Z"),
+                turn("7", 150, 120, 15, "2025-01-02T09:00:02Z"),
+            ]
+        );
+    }
+
     fn metadata_for_test() -> ThreadMetadata {
         let id = ThreadId::from_string(&Uuid::from_u128(42).to_string()).expect("thread id");
         let created_at = DateTime::<Utc>::from_timestamp(1_735_689_600, 0).expect("timestamp");
@@ -12,6 +12,9 @@ mod paths;
 mod runtime;
 
 pub use model::LogEntry;
+pub use model::LogField;
+pub use model::LogFilter;
+pub use model::LogFilterOp;
 pub use model::LogQuery;
 pub use model::LogRow;
 /// Preferred entrypoint: owns configuration and metrics.
@@ -21,16 +24,40 @@ pub use runtime::StateRuntime;
 ///
 /// Most consumers should prefer [`StateRuntime`].
 pub use extract::apply_rollout_item;
+pub use extract::thread_search_entries;
+pub use extract::token_usage_delta;
+pub use extract::turn_usage_from_rollout;
 pub use model::Anchor;
 pub use model::BackfillState;
 pub use model::BackfillStats;
 pub use model::BackfillStatus;
 pub use model::ExtractionOutcome;
+pub use model::KnowledgeStatus;
+pub use model::MessageHistoryEntry;
+pub use model::NetworkAuditDecision;
+pub use model::NetworkAuditEntry;
+pub use model::NetworkAuditQuery;
+pub use model::ProjectKnowledge;
 pub use model::SortKey;
 pub use model::ThreadMemory;
+pub use model::ThreadMemorySearchHit;
 pub use model::ThreadMetadata;
 pub use model::ThreadMetadataBuilder;
+pub use model::ThreadOrganization;
+pub use model::ThreadOrganizationFilter;
+pub use model::ThreadSearchEntry;
+pub use model::ThreadSearchHit;
+pub use model::ThreadSearchPage;
+pub use model::ThreadSearchSource;
 pub use model::ThreadsPage;
+pub use model::TurnUsage;
+pub use model::UsageGroupBy;
+pub use model::UsageQuery;
+pub use model::UsageReportRow;
+pub use model::log_level_rank;
+pub use model::normalize_knowledge_content;
+pub use model::normalize_thread_folder;
+pub use model::normalize_thread_tags;
 pub use runtime::STATE_DB_FILENAME;
 pub use runtime::STATE_DB_VERSION;
 pub use runtime::state_db_filename;
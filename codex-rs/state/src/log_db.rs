@@ -2,7 +2,8 @@
 //!
 //! This module provides a `tracing_subscriber::Layer` that captures events and
 //! inserts them into the `logs` table in `state.sqlite`. The writer runs in a
-//! background task and batches inserts to keep logging overhead low.
+//! background task and batches inserts to keep logging overhead low, while a
+//! second task periodically enforces the [`LogRetention`] limits.
 //!
 //! ## Usage
 //!
@@ -20,6 +21,7 @@
 
 use chrono::Duration as ChronoDuration;
 use chrono::Utc;
+use codex_secrets::redact_secrets;
 use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
@@ -40,16 +42,43 @@ use crate::StateRuntime;
 const LOG_QUEUE_CAPACITY: usize = 512;
 const LOG_BATCH_SIZE: usize = 64;
 const LOG_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
-const LOG_RETENTION_DAYS: i64 = 90;
+const LOG_RETENTION_DAYS: u32 = 90;
+const LOG_RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Limits enforced on the `logs` table. Sweeps run at startup and then hourly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogRetention {
+    /// Delete logs older than this many days.
+    pub max_age_days: Option<u32>,
+    /// Delete the oldest logs once the estimated size of the table exceeds
+    /// this many bytes.
+    pub max_bytes: Option<u64>,
+}
+
+impl Default for LogRetention {
+    fn default() -> Self {
+        Self {
+            max_age_days: Some(LOG_RETENTION_DAYS),
+            max_bytes: None,
+        }
+    }
+}
 
 pub struct LogDbLayer {
     sender: mpsc::Sender<LogEntry>,
 }
 
 pub fn start(state_db: std::sync::Arc<StateRuntime>) -> LogDbLayer {
+    start_with_retention(state_db, LogRetention::default())
+}
+
+pub fn start_with_retention(
+    state_db: std::sync::Arc<StateRuntime>,
+    retention: LogRetention,
+) -> LogDbLayer {
     let (sender, receiver) = mpsc::channel(LOG_QUEUE_CAPACITY);
     tokio::spawn(run_inserter(std::sync::Arc::clone(&state_db), receiver));
-    tokio::spawn(run_retention_cleanup(state_db));
+    tokio::spawn(run_retention_cleanup(state_db, retention));
 
     LogDbLayer { sender }
 }
@@ -70,6 +99,7 @@ where
         if let Some(span) = ctx.span(id) {
             span.extensions_mut().insert(SpanLogContext {
                 thread_id: visitor.thread_id,
+                turn_id: visitor.turn_id,
             });
         }
     }
@@ -83,17 +113,23 @@ where
         let mut visitor = SpanFieldVisitor::default();
         values.record(&mut visitor);
 
-        if visitor.thread_id.is_none() {
+        if visitor.thread_id.is_none() && visitor.turn_id.is_none() {
             return;
         }
 
         if let Some(span) = ctx.span(id) {
             let mut extensions = span.extensions_mut();
             if let Some(log_context) = extensions.get_mut::<SpanLogContext>() {
-                log_context.thread_id = visitor.thread_id;
+                if visitor.thread_id.is_some() {
+                    log_context.thread_id = visitor.thread_id;
+                }
+                if visitor.turn_id.is_some() {
+                    log_context.turn_id = visitor.turn_id;
+                }
             } else {
                 extensions.insert(SpanLogContext {
                     thread_id: visitor.thread_id,
+                    turn_id: visitor.turn_id,
                 });
             }
         }
@@ -103,10 +139,9 @@ where
         let metadata = event.metadata();
         let mut visitor = MessageVisitor::default();
         event.record(&mut visitor);
-        let thread_id = visitor
-            .thread_id
-            .clone()
-            .or_else(|| event_thread_id(event, &ctx));
+        let span_context = event_span_context(event, &ctx);
+        let thread_id = visitor.thread_id.clone().or(span_context.thread_id);
+        let turn_id = visitor.turn_id.clone().or(span_context.turn_id);
 
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -116,8 +151,11 @@ where
             ts_nanos: now.subsec_nanos() as i64,
             level: metadata.level().as_str().to_string(),
             target: metadata.target().to_string(),
-            message: visitor.message,
+            message: visitor
+                .message
+                .map(|message| redact_secrets(&message).into_owned()),
             thread_id,
+            turn_id,
             module_path: metadata.module_path().map(ToString::to_string),
             file: metadata.file().map(ToString::to_string),
             line: metadata.line().map(|line| line as i64),
@@ -130,17 +168,21 @@ where
 #[derive(Clone, Debug, Default)]
 struct SpanLogContext {
     thread_id: Option<String>,
+    turn_id: Option<String>,
 }
 
 #[derive(Default)]
 struct SpanFieldVisitor {
     thread_id: Option<String>,
+    turn_id: Option<String>,
 }
 
 impl SpanFieldVisitor {
     fn record_field(&mut self, field: &Field, value: String) {
         if field.name() == "thread_id" && self.thread_id.is_none() {
             self.thread_id = Some(value);
+        } else if field.name() == "turn_id" && self.turn_id.is_none() {
+            self.turn_id = Some(value);
         }
     }
 }
@@ -175,25 +217,29 @@ impl Visit for SpanFieldVisitor {
     }
 }
 
-fn event_thread_id<S>(
+/// Thread and turn ids of the innermost enclosing spans that set them.
+fn event_span_context<S>(
     event: &Event<'_>,
     ctx: &tracing_subscriber::layer::Context<'_, S>,
-) -> Option<String>
+) -> SpanLogContext
 where
     S: tracing::Subscriber + for<'a> LookupSpan<'a>,
 {
-    let mut thread_id = None;
+    let mut context = SpanLogContext::default();
     if let Some(scope) = ctx.event_scope(event) {
         for span in scope.from_root() {
             let extensions = span.extensions();
-            if let Some(log_context) = extensions.get::<SpanLogContext>()
-                && log_context.thread_id.is_some()
-            {
-                thread_id = log_context.thread_id.clone();
+            if let Some(log_context) = extensions.get::<SpanLogContext>() {
+                if log_context.thread_id.is_some() {
+                    context.thread_id = log_context.thread_id.clone();
+                }
+                if log_context.turn_id.is_some() {
+                    context.turn_id = log_context.turn_id.clone();
+                }
             }
         }
     }
-    thread_id
+    context
 }
 
 async fn run_inserter(
@@ -233,18 +279,42 @@ async fn flush(state_db: &std::sync::Arc<StateRuntime>, buffer: &mut Vec<LogEntr
     let _ = state_db.insert_logs(entries.as_slice()).await;
 }
 
-async fn run_retention_cleanup(state_db: std::sync::Arc<StateRuntime>) {
-    let Some(cutoff) = Utc::now().checked_sub_signed(ChronoDuration::days(LOG_RETENTION_DAYS))
-    else {
+async fn run_retention_cleanup(state_db: std::sync::Arc<StateRuntime>, retention: LogRetention) {
+    if retention.max_age_days.is_none() && retention.max_bytes.is_none() {
         return;
-    };
-    let _ = state_db.delete_logs_before(cutoff.timestamp()).await;
+    }
+    let mut ticker = tokio::time::interval(LOG_RETENTION_SWEEP_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let _ = apply_retention(&state_db, retention).await;
+    }
+}
+
+/// Delete logs outside the retention limits, returning the number of rows
+/// removed.
+pub async fn apply_retention(
+    state_db: &StateRuntime,
+    retention: LogRetention,
+) -> anyhow::Result<u64> {
+    let mut deleted = 0;
+    if let Some(max_age_days) = retention.max_age_days
+        && let Some(cutoff) =
+            Utc::now().checked_sub_signed(ChronoDuration::days(i64::from(max_age_days)))
+    {
+        deleted += state_db.delete_logs_before(cutoff.timestamp()).await?;
+    }
+    if let Some(max_bytes) = retention.max_bytes {
+        deleted += state_db.delete_logs_exceeding_bytes(max_bytes).await?;
+    }
+    Ok(deleted)
 }
 
 #[derive(Default)]
 struct MessageVisitor {
     message: Option<String>,
     thread_id: Option<String>,
+    turn_id: Option<String>,
 }
 
 impl MessageVisitor {
@@ -253,7 +323,10 @@ impl MessageVisitor {
             self.message = Some(value.clone());
         }
         if field.name() == "thread_id" && self.thread_id.is_none() {
-            self.thread_id = Some(value);
+            self.thread_id = Some(value.clone());
+        }
+        if field.name() == "turn_id" && self.turn_id.is_none() {
+            self.turn_id = Some(value);
         }
     }
 }
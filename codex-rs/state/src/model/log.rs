@@ -1,6 +1,8 @@
 use serde::Serialize;
 use sqlx::FromRow;
 
+use super::log_filter::LogFilter;
+
 #[derive(Clone, Debug, Serialize)]
 pub struct LogEntry {
     pub ts: i64,
@@ -9,12 +11,13 @@ pub struct LogEntry {
     pub target: String,
     pub message: Option<String>,
     pub thread_id: Option<String>,
+    pub turn_id: Option<String>,
     pub module_path: Option<String>,
     pub file: Option<String>,
     pub line: Option<i64>,
 }
 
-#[derive(Clone, Debug, FromRow)]
+#[derive(Clone, Debug, FromRow, Serialize)]
 pub struct LogRow {
     pub id: i64,
     pub ts: i64,
@@ -23,6 +26,8 @@ pub struct LogRow {
     pub target: String,
     pub message: Option<String>,
     pub thread_id: Option<String>,
+    pub turn_id: Option<String>,
+    pub module_path: Option<String>,
     pub file: Option<String>,
     pub line: Option<i64>,
 }
@@ -36,6 +41,8 @@ pub struct LogQuery {
     pub file_like: Vec<String>,
     pub thread_ids: Vec<String>,
     pub include_threadless: bool,
+    /// Boolean filter expression applied on top of the other constraints.
+    pub filter: Option<LogFilter>,
     pub after_id: Option<i64>,
     pub limit: Option<usize>,
     pub descending: bool,
@@ -0,0 +1,412 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A boolean filter over log rows, parsed from expressions such as
+/// `level>=warn and (module~core::exec or not thread=none)`.
+///
+/// Terms compare a field with a value: `=` and `!=` match exactly, `~`
+/// matches a substring, and `level` additionally supports `>=`, `>`, `<=` and
+/// `<` in `trace < debug < info < warn < error` order. `thread = none` and
+/// `turn = none` match rows without an id. Terms combine with `and`, `or`,
+/// `not` (or `&&`, `||`, `!`) and parentheses; `and` binds tighter than `or`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogFilter {
+    And(Box<LogFilter>, Box<LogFilter>),
+    Or(Box<LogFilter>, Box<LogFilter>),
+    Not(Box<LogFilter>),
+    Compare {
+        field: LogField,
+        op: LogFilterOp,
+        value: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogField {
+    Level,
+    Thread,
+    Turn,
+    Module,
+    Target,
+    File,
+    Message,
+}
+
+impl LogField {
+    fn parse(raw: &str) -> Option<Self> {
+        Some(match raw.to_ascii_lowercase().as_str() {
+            "level" => Self::Level,
+            "thread" | "thread_id" => Self::Thread,
+            "turn" | "turn_id" => Self::Turn,
+            "module" | "module_path" => Self::Module,
+            "target" => Self::Target,
+            "file" => Self::File,
+            "message" | "msg" => Self::Message,
+            _ => return None,
+        })
+    }
+
+    /// Column holding the field in the `logs` table.
+    pub(crate) fn column(self) -> &'static str {
+        match self {
+            Self::Level => "level",
+            Self::Thread => "thread_id",
+            Self::Turn => "turn_id",
+            Self::Module => "module_path",
+            Self::Target => "target",
+            Self::File => "file",
+            Self::Message => "message",
+        }
+    }
+
+    /// Whether `field = none` means "no value".
+    pub(crate) fn is_optional_id(self) -> bool {
+        matches!(self, Self::Thread | Self::Turn)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFilterOp {
+    Eq,
+    Ne,
+    Contains,
+    Ge,
+    Gt,
+    Le,
+    Lt,
+}
+
+impl LogFilterOp {
+    pub(crate) fn is_ordering(self) -> bool {
+        matches!(self, Self::Ge | Self::Gt | Self::Le | Self::Lt)
+    }
+
+    pub(crate) fn sql(self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Contains => "LIKE",
+            Self::Ge => ">=",
+            Self::Gt => ">",
+            Self::Le => "<=",
+            Self::Lt => "<",
+        }
+    }
+}
+
+/// Log levels from least to most severe, as stored in the `level` column.
+pub(crate) const LOG_LEVELS: [&str; 5] = ["TRACE", "DEBUG", "INFO", "WARN", "ERROR"];
+
+/// Severity rank of a level name, used for `level >= warn` style filters.
+pub fn log_level_rank(level: &str) -> Option<usize> {
+    LOG_LEVELS
+        .iter()
+        .position(|candidate| candidate.eq_ignore_ascii_case(level))
+}
+
+impl LogFilter {
+    pub fn and(self, other: LogFilter) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: LogFilter) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Combine `filters` with `or`; `None` when empty.
+    pub fn any(filters: impl IntoIterator<Item = LogFilter>) -> Option<Self> {
+        filters.into_iter().reduce(Self::or)
+    }
+
+    /// Combine `filters` with `and`; `None` when empty.
+    pub fn all(filters: impl IntoIterator<Item = LogFilter>) -> Option<Self> {
+        filters.into_iter().reduce(Self::and)
+    }
+
+    pub fn compare(field: LogField, op: LogFilterOp, value: impl Into<String>) -> Self {
+        Self::Compare {
+            field,
+            op,
+            value: value.into(),
+        }
+    }
+}
+
+impl FromStr for LogFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> anyhow::Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let filter = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            anyhow::bail!("unexpected `{token}` in log filter");
+        }
+        Ok(filter)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(LogFilterOp),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "{word}"),
+            Self::Quoted(value) => write!(f, "\"{value}\""),
+            Self::Op(op) => match op {
+                LogFilterOp::Contains => write!(f, "~"),
+                op => write!(f, "{}", op.sql()),
+            },
+            Self::And => write!(f, "and"),
+            Self::Or => write!(f, "or"),
+            Self::Not => write!(f, "not"),
+            Self::Open => write!(f, "("),
+            Self::Close => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        let token = match ch {
+            _ if ch.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                Token::Open
+            }
+            ')' => {
+                chars.next();
+                Token::Close
+            }
+            '~' => {
+                chars.next();
+                Token::Op(LogFilterOp::Contains)
+            }
+            '=' => {
+                chars.next();
+                chars.next_if_eq(&'=');
+                Token::Op(LogFilterOp::Eq)
+            }
+            '!' => {
+                chars.next();
+                if chars.next_if_eq(&'=').is_some() {
+                    Token::Op(LogFilterOp::Ne)
+                } else {
+                    Token::Not
+                }
+            }
+            '>' | '<' => {
+                chars.next();
+                let or_equal = chars.next_if_eq(&'=').is_some();
+                Token::Op(match (ch, or_equal) {
+                    ('>', true) => LogFilterOp::Ge,
+                    ('>', false) => LogFilterOp::Gt,
+                    (_, true) => LogFilterOp::Le,
+                    (_, false) => LogFilterOp::Lt,
+                })
+            }
+            '&' | '|' => {
+                chars.next();
+                anyhow::ensure!(
+                    chars.next_if_eq(&ch).is_some(),
+                    "expected `{ch}{ch}` in log filter"
+                );
+                if ch == '&' { Token::And } else { Token::Or }
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(next) if next == ch => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => value.push(escaped),
+                            None => anyhow::bail!("unterminated string in log filter"),
+                        },
+                        Some(next) => value.push(next),
+                        None => anyhow::bail!("unterminated string in log filter"),
+                    }
+                }
+                Token::Quoted(value)
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(next) =
+                    chars.next_if(|next| !next.is_whitespace() && !"()~=!<>&|\"'".contains(*next))
+                {
+                    word.push(next);
+                }
+                match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Word(word),
+                }
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> anyhow::Result<LogFilter> {
+        let mut filter = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            filter = filter.or(self.parse_and()?);
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> anyhow::Result<LogFilter> {
+        let mut filter = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            filter = filter.and(self.parse_unary()?);
+        }
+        Ok(filter)
+    }
+
+    fn parse_unary(&mut self) -> anyhow::Result<LogFilter> {
+        match self.next() {
+            Some(Token::Not) => Ok(LogFilter::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Open) => {
+                let filter = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(filter),
+                    _ => anyhow::bail!("missing `)` in log filter"),
+                }
+            }
+            Some(Token::Word(field)) => self.parse_compare(&field),
+            Some(token) => anyhow::bail!("expected a field name, got `{token}`"),
+            None => anyhow::bail!("log filter ended unexpectedly"),
+        }
+    }
+
+    fn parse_compare(&mut self, raw_field: &str) -> anyhow::Result<LogFilter> {
+        let field = LogField::parse(raw_field).ok_or_else(|| {
+            anyhow::anyhow!(
+                "unknown log field `{raw_field}`; expected level, thread, turn, module, target, file or message"
+            )
+        })?;
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => anyhow::bail!("expected an operator after `{raw_field}`"),
+        };
+        let value = match self.next() {
+            Some(Token::Word(value) | Token::Quoted(value)) => value,
+            _ => anyhow::bail!("expected a value after `{raw_field}`"),
+        };
+        if field == LogField::Level {
+            anyhow::ensure!(
+                log_level_rank(&value).is_some(),
+                "unknown log level `{value}`; expected trace, debug, info, warn or error"
+            );
+            anyhow::ensure!(
+                op != LogFilterOp::Contains,
+                "`~` is not supported for level"
+            );
+            return Ok(LogFilter::compare(field, op, value.to_ascii_uppercase()));
+        }
+        anyhow::ensure!(
+            !op.is_ordering(),
+            "`{}` is only supported for level",
+            op.sql()
+        );
+        Ok(LogFilter::compare(field, op, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn and_binds_tighter_than_or() -> anyhow::Result<()> {
+        let filter: LogFilter = "level>=warn and module~exec or not thread = none".parse()?;
+        assert_eq!(
+            filter,
+            LogFilter::compare(LogField::Level, LogFilterOp::Ge, "WARN")
+                .and(LogFilter::compare(
+                    LogField::Module,
+                    LogFilterOp::Contains,
+                    "exec"
+                ))
+                .or(LogFilter::Not(Box::new(LogFilter::compare(
+                    LogField::Thread,
+                    LogFilterOp::Eq,
+                    "none"
+                ))))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parentheses_and_symbolic_operators() -> anyhow::Result<()> {
+        let filter: LogFilter =
+            r#"(turn == "t 1" || turn=t2) && !(message ~ 'timed out')"#.parse()?;
+        assert_eq!(
+            filter,
+            LogFilter::compare(LogField::Turn, LogFilterOp::Eq, "t 1")
+                .or(LogFilter::compare(LogField::Turn, LogFilterOp::Eq, "t2"))
+                .and(LogFilter::Not(Box::new(LogFilter::compare(
+                    LogField::Message,
+                    LogFilterOp::Contains,
+                    "timed out"
+                ))))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_filters() {
+        for (input, expected) in [
+            ("level >= loud", "unknown log level"),
+            ("module >= core", "only supported for level"),
+            ("color = red", "unknown log field"),
+            ("(level = info", "missing `)`"),
+            ("level = info thread", "unexpected `thread`"),
+            ("level = info and", "ended unexpectedly"),
+        ] {
+            let err = input
+                .parse::<LogFilter>()
+                .expect_err("filter should be rejected");
+            assert!(
+                err.to_string().contains(expected),
+                "{input}: unexpected error {err}"
+            );
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::ThreadId;
+use sqlx::Row;
+use sqlx::sqlite::SqliteRow;
+
+/// A message the user submitted, kept in the cross-session message history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageHistoryEntry {
+    /// Thread the message was submitted in. Unknown for some entries
+    /// imported from `history.jsonl`.
+    pub thread_id: Option<ThreadId>,
+    /// Working directory of the session. Entries imported from
+    /// `history.jsonl` have none.
+    pub cwd: Option<PathBuf>,
+    pub text: String,
+    pub ts: DateTime<Utc>,
+}
+
+impl MessageHistoryEntry {
+    pub(crate) fn try_from_row(row: &SqliteRow) -> Result<Self> {
+        let thread_id: Option<String> = row.try_get("thread_id")?;
+        let cwd: Option<String> = row.try_get("cwd")?;
+        let ts: i64 = row.try_get("ts")?;
+        Ok(Self {
+            thread_id: thread_id.map(ThreadId::try_from).transpose()?,
+            cwd: cwd.map(PathBuf::from),
+            text: row.try_get("text")?,
+            ts: DateTime::<Utc>::from_timestamp(ts, 0)
+                .ok_or_else(|| anyhow::anyhow!("invalid unix timestamp: {ts}"))?,
+        })
+    }
+}
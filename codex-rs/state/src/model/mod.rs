@@ -1,14 +1,33 @@
 mod backfill_state;
 mod log;
+mod log_filter;
+mod message_history;
+mod network_audit;
+mod project_knowledge;
 mod thread_memory;
 mod thread_metadata;
+mod thread_organization;
+mod thread_search;
+mod usage;
 
 pub use backfill_state::BackfillState;
 pub use backfill_state::BackfillStatus;
 pub use log::LogEntry;
 pub use log::LogQuery;
 pub use log::LogRow;
+pub use log_filter::LogField;
+pub use log_filter::LogFilter;
+pub use log_filter::LogFilterOp;
+pub use log_filter::log_level_rank;
+pub use message_history::MessageHistoryEntry;
+pub use network_audit::NetworkAuditDecision;
+pub use network_audit::NetworkAuditEntry;
+pub use network_audit::NetworkAuditQuery;
+pub use project_knowledge::KnowledgeStatus;
+pub use project_knowledge::ProjectKnowledge;
+pub use project_knowledge::normalize_knowledge_content;
 pub use thread_memory::ThreadMemory;
+pub use thread_memory::ThreadMemorySearchHit;
 pub use thread_metadata::Anchor;
 pub use thread_metadata::BackfillStats;
 pub use thread_metadata::ExtractionOutcome;
@@ -16,7 +35,20 @@ pub use thread_metadata::SortKey;
 pub use thread_metadata::ThreadMetadata;
 pub use thread_metadata::ThreadMetadataBuilder;
 pub use thread_metadata::ThreadsPage;
+pub use thread_organization::ThreadOrganization;
+pub use thread_organization::ThreadOrganizationFilter;
+pub use thread_organization::normalize_thread_folder;
+pub use thread_organization::normalize_thread_tags;
+pub use thread_search::ThreadSearchEntry;
+pub use thread_search::ThreadSearchHit;
+pub use thread_search::ThreadSearchPage;
+pub use thread_search::ThreadSearchSource;
+pub use usage::TurnUsage;
+pub use usage::UsageGroupBy;
+pub use usage::UsageQuery;
+pub use usage::UsageReportRow;
 
+pub(crate) use log_filter::LOG_LEVELS;
 pub(crate) use thread_memory::ThreadMemoryRow;
 pub(crate) use thread_metadata::ThreadRow;
 pub(crate) use thread_metadata::anchor_from_item;
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::ThreadId;
+use sqlx::Row;
+use sqlx::sqlite::SqliteRow;
+
+/// Whether the network proxy let a request through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkAuditDecision {
+    Allow,
+    Deny,
+}
+
+impl NetworkAuditDecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "allow" => Ok(Self::Allow),
+            "deny" => Ok(Self::Deny),
+            _ => Err(anyhow::anyhow!("unknown network audit decision: {value}")),
+        }
+    }
+}
+
+/// A request a session's network proxy decided on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkAuditEntry {
+    pub ts: DateTime<Utc>,
+    /// Thread whose command made the request. Unknown for requests of
+    /// processes that were not started by a command, such as MCP servers.
+    pub thread_id: Option<ThreadId>,
+    pub turn_id: Option<String>,
+    /// Tool call that started the process making the request.
+    pub call_id: Option<String>,
+    /// `http`, `https` (intercepted), `http-connect`, `socks5`, `socks5-udp`
+    /// or `unix-socket`.
+    pub protocol: String,
+    pub host: String,
+    pub method: Option<String>,
+    pub decision: NetworkAuditDecision,
+    /// Why the request was blocked.
+    pub reason: Option<String>,
+    /// Bytes sent upstream, when the proxy could tell.
+    pub bytes_sent: Option<i64>,
+    /// Bytes received from upstream, when the proxy could tell.
+    pub bytes_received: Option<i64>,
+}
+
+impl NetworkAuditEntry {
+    pub(crate) fn try_from_row(row: &SqliteRow) -> Result<Self> {
+        let ts: i64 = row.try_get("ts")?;
+        let thread_id: Option<String> = row.try_get("thread_id")?;
+        let decision: String = row.try_get("decision")?;
+        Ok(Self {
+            ts: DateTime::<Utc>::from_timestamp(ts, 0)
+                .ok_or_else(|| anyhow::anyhow!("invalid unix timestamp: {ts}"))?,
+            thread_id: thread_id.map(ThreadId::try_from).transpose()?,
+            turn_id: row.try_get("turn_id")?,
+            call_id: row.try_get("call_id")?,
+            protocol: row.try_get("protocol")?,
+            host: row.try_get("host")?,
+            method: row.try_get("method")?,
+            decision: NetworkAuditDecision::parse(&decision)?,
+            reason: row.try_get("reason")?,
+            bytes_sent: row.try_get("bytes_sent")?,
+            bytes_received: row.try_get("bytes_received")?,
+        })
+    }
+}
+
+/// Filters for listing the network audit log.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkAuditQuery {
+    pub thread_id: Option<ThreadId>,
+    /// Only include requests to this host.
+    pub host: Option<String>,
+    pub decision: Option<NetworkAuditDecision>,
+    /// Only include requests made at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Maximum number of entries, newest first.
+    pub limit: usize,
+}
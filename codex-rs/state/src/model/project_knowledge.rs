@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::ThreadId;
+use sqlx::Row;
+use sqlx::sqlite::SqliteRow;
+
+/// Review state of a project knowledge entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KnowledgeStatus {
+    /// Recorded by the model and waiting for the user's review.
+    Pending,
+    /// Approved by the user; injected into sessions in the repository.
+    Approved,
+    /// Rejected by the user; kept so the same fact is not proposed again.
+    Rejected,
+}
+
+impl KnowledgeStatus {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            KnowledgeStatus::Pending => "pending",
+            KnowledgeStatus::Approved => "approved",
+            KnowledgeStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "approved" => Some(Self::Approved),
+            "rejected" => Some(Self::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// A durable fact about a repository, recorded with the `remember` tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectKnowledge {
+    pub id: i64,
+    /// Root of the repository the fact applies to.
+    pub repo_root: PathBuf,
+    pub content: String,
+    pub status: KnowledgeStatus,
+    /// Thread in which the fact was recorded, if known.
+    pub source_thread_id: Option<ThreadId>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProjectKnowledge {
+    pub(crate) fn try_from_row(row: &SqliteRow) -> Result<Self> {
+        let status: String = row.try_get("status")?;
+        let source_thread_id: Option<String> = row.try_get("source_thread_id")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            repo_root: PathBuf::from(row.try_get::<String, _>("repo_root")?),
+            content: row.try_get("content")?,
+            status: KnowledgeStatus::parse(&status)
+                .ok_or_else(|| anyhow::anyhow!("invalid knowledge status: {status}"))?,
+            source_thread_id: source_thread_id.map(ThreadId::try_from).transpose()?,
+            created_at: epoch_seconds_to_datetime(row.try_get("created_at")?)?,
+            updated_at: epoch_seconds_to_datetime(row.try_get("updated_at")?)?,
+        })
+    }
+}
+
+/// Collapse whitespace so the same fact worded with different spacing is
+/// recorded once. Returns `None` for blank facts.
+pub fn normalize_knowledge_content(content: &str) -> Option<String> {
+    let normalized = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+fn epoch_seconds_to_datetime(secs: i64) -> Result<DateTime<Utc>> {
+    DateTime::<Utc>::from_timestamp(secs, 0)
+        .ok_or_else(|| anyhow::anyhow!("invalid unix timestamp: {secs}"))
+}
@@ -4,6 +4,7 @@ use chrono::Utc;
 use codex_protocol::ThreadId;
 use sqlx::Row;
 use sqlx::sqlite::SqliteRow;
+use std::path::PathBuf;
 
 /// Stored memory summaries for a single thread.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -11,14 +12,27 @@ pub struct ThreadMemory {
     pub thread_id: ThreadId,
     pub trace_summary: String,
     pub memory_summary: String,
+    /// Pinned memories are always included when recalling memories for a cwd.
+    pub pinned: bool,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A memory returned by [`crate::StateRuntime::search_thread_memories`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadMemorySearchHit {
+    pub memory: ThreadMemory,
+    /// Working directory of the thread the memory was recorded for.
+    pub cwd: PathBuf,
+    /// BM25 relevance weighted by recency; higher is better.
+    pub score: f64,
+}
+
 #[derive(Debug)]
 pub(crate) struct ThreadMemoryRow {
     thread_id: String,
     trace_summary: String,
     memory_summary: String,
+    pinned: bool,
     updated_at: i64,
 }
 
@@ -28,6 +42,7 @@ impl ThreadMemoryRow {
             thread_id: row.try_get("thread_id")?,
             trace_summary: row.try_get("trace_summary")?,
             memory_summary: row.try_get("memory_summary")?,
+            pinned: row.try_get("pinned")?,
             updated_at: row.try_get("updated_at")?,
         })
     }
@@ -41,6 +56,7 @@ impl TryFrom<ThreadMemoryRow> for ThreadMemory {
             thread_id: ThreadId::try_from(row.thread_id)?,
             trace_summary: row.trace_summary,
             memory_summary: row.memory_summary,
+            pinned: row.pinned,
             updated_at: epoch_seconds_to_datetime(row.updated_at)?,
         })
     }
@@ -47,6 +47,8 @@ pub struct ExtractionOutcome {
     pub metadata: ThreadMetadata,
     /// The number of rollout lines that failed to parse.
     pub parse_errors: usize,
+    /// Full-text search entries for the rollout's messages and commands.
+    pub search_entries: Vec<crate::ThreadSearchEntry>,
 }
 
 /// Canonical thread metadata derived from rollout files.
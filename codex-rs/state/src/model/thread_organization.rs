@@ -0,0 +1,83 @@
+/// User-managed organization of a thread.
+///
+/// Unlike [`crate::ThreadMetadata`], none of this is derived from rollout
+/// files; it only lives in the state database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadOrganization {
+    /// Tags attached to the thread, sorted and deduplicated.
+    pub tags: Vec<String>,
+    /// Whether the thread is pinned.
+    pub pinned: bool,
+    /// The folder the thread is filed under, as a `/`-separated path.
+    pub folder: Option<String>,
+}
+
+/// Organization filters applied when listing threads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadOrganizationFilter {
+    /// Only include threads carrying every one of these tags.
+    pub tags: Vec<String>,
+    /// Only include pinned (`true`) or unpinned (`false`) threads.
+    pub pinned: Option<bool>,
+    /// Only include threads in this folder or one of its subfolders.
+    pub folder: Option<String>,
+}
+
+impl ThreadOrganizationFilter {
+    /// Whether the filter matches every thread.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.pinned.is_none() && self.folder.is_none()
+    }
+}
+
+/// Trim tags, drop empty ones, and return the rest sorted and deduplicated.
+pub fn normalize_thread_tags<S: AsRef<str>>(tags: &[S]) -> Vec<String> {
+    let mut tags = tags
+        .iter()
+        .map(|tag| tag.as_ref().trim())
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Normalize a folder path by trimming each `/`-separated segment and
+/// dropping empty ones. Returns `None` when nothing is left.
+pub fn normalize_thread_folder(folder: &str) -> Option<String> {
+    let segments = folder
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    if segments.is_empty() {
+        None
+    } else {
+        Some(segments.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_thread_folder;
+    use super::normalize_thread_tags;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn normalize_thread_tags_trims_sorts_and_dedupes() {
+        assert_eq!(
+            normalize_thread_tags(&[" infra", "bug", "", "infra ", "  "]),
+            vec!["bug".to_string(), "infra".to_string()]
+        );
+    }
+
+    #[test]
+    fn normalize_thread_folder_collapses_segments() {
+        assert_eq!(
+            normalize_thread_folder(" /clients// acme /"),
+            Some("clients/acme".to_string())
+        );
+        assert_eq!(normalize_thread_folder(" / "), None);
+    }
+}
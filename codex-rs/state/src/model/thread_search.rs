@@ -0,0 +1,65 @@
+use crate::model::ThreadMetadata;
+use std::ops::Range;
+
+/// Which part of a thread a search entry was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThreadSearchSource {
+    /// A message typed by the user.
+    UserMessage,
+    /// A message produced by the agent.
+    AgentMessage,
+    /// A command the agent ran.
+    Command,
+}
+
+impl ThreadSearchSource {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ThreadSearchSource::UserMessage => "user_message",
+            ThreadSearchSource::AgentMessage => "agent_message",
+            ThreadSearchSource::Command => "command",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user_message" => Some(Self::UserMessage),
+            "agent_message" => Some(Self::AgentMessage),
+            "command" => Some(Self::Command),
+            _ => None,
+        }
+    }
+}
+
+/// A piece of thread text to add to the full-text search index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadSearchEntry {
+    /// Where the text came from.
+    pub source: ThreadSearchSource,
+    /// The indexed text.
+    pub content: String,
+}
+
+/// A thread matching a full-text search, with its best matching entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadSearchHit {
+    /// The matching thread.
+    pub thread: ThreadMetadata,
+    /// Where the best match came from.
+    pub source: ThreadSearchSource,
+    /// A short excerpt of the best matching entry.
+    pub snippet: String,
+    /// Byte ranges of the matched terms within `snippet`.
+    pub highlights: Vec<Range<usize>>,
+    /// BM25 score of the best match; lower is more relevant.
+    pub rank: f64,
+}
+
+/// A single page of full-text search results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadSearchPage {
+    /// The matching threads, most relevant first.
+    pub items: Vec<ThreadSearchHit>,
+    /// The offset to pass to fetch the next page, if any.
+    pub next_offset: Option<usize>,
+}
@@ -0,0 +1,69 @@
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::ThreadId;
+use std::path::PathBuf;
+
+/// Tokens consumed by a single turn of a thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnUsage {
+    /// The thread the turn belongs to.
+    pub thread_id: ThreadId,
+    /// The turn identifier: the UUID minted when the turn started, or the
+    /// turn ordinal for turns reconstructed from a rollout that predates it.
+    pub turn_id: String,
+    /// The model that served the turn.
+    pub model: String,
+    /// The model provider that served the turn.
+    pub model_provider: String,
+    /// The working directory of the turn.
+    pub cwd: PathBuf,
+    /// Input tokens, including cached input tokens.
+    pub input_tokens: i64,
+    /// Input tokens served from the prompt cache.
+    pub cached_input_tokens: i64,
+    /// Output tokens, including reasoning tokens.
+    pub output_tokens: i64,
+    /// Output tokens spent on reasoning.
+    pub reasoning_output_tokens: i64,
+    /// When the turn finished.
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// How usage report rows are grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageGroupBy {
+    /// Calendar day (UTC) the turn finished.
+    Day,
+    /// Model that served the turn.
+    Model,
+    /// Git origin of the thread, falling back to its working directory.
+    Repo,
+    /// Thread id.
+    Thread,
+}
+
+/// Filters for a usage report.
+#[derive(Debug, Clone)]
+pub struct UsageQuery {
+    pub group_by: UsageGroupBy,
+    /// Only include turns recorded at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only include turns recorded before this time.
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Usage totals for one group and model.
+///
+/// Rows are split by model even when grouping by something else so callers
+/// can price each row with the right per-model rates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageReportRow {
+    /// The group key (day, model, repo or thread id depending on the query).
+    pub group: String,
+    pub model: String,
+    pub turns: i64,
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    pub reasoning_output_tokens: i64,
+}
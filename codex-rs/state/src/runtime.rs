@@ -1,19 +1,44 @@
 use crate::DB_ERROR_METRIC;
+use crate::KnowledgeStatus;
 use crate::LogEntry;
+use crate::LogField;
+use crate::LogFilter;
+use crate::LogFilterOp;
 use crate::LogQuery;
 use crate::LogRow;
+use crate::MessageHistoryEntry;
+use crate::NetworkAuditEntry;
+use crate::NetworkAuditQuery;
+use crate::ProjectKnowledge;
 use crate::SortKey;
 use crate::ThreadMemory;
+use crate::ThreadMemorySearchHit;
 use crate::ThreadMetadata;
 use crate::ThreadMetadataBuilder;
+use crate::ThreadOrganization;
+use crate::ThreadOrganizationFilter;
+use crate::ThreadSearchEntry;
+use crate::ThreadSearchHit;
+use crate::ThreadSearchPage;
+use crate::ThreadSearchSource;
 use crate::ThreadsPage;
+use crate::TurnUsage;
+use crate::UsageGroupBy;
+use crate::UsageQuery;
+use crate::UsageReportRow;
 use crate::apply_rollout_item;
+use crate::log_level_rank;
 use crate::migrations::MIGRATOR;
+use crate::model::LOG_LEVELS;
 use crate::model::ThreadMemoryRow;
 use crate::model::ThreadRow;
 use crate::model::anchor_from_item;
 use crate::model::datetime_to_epoch_seconds;
+use crate::normalize_knowledge_content;
+use crate::normalize_thread_folder;
+use crate::normalize_thread_tags;
 use crate::paths::file_modified_time_utc;
+use crate::thread_search_entries;
 use chrono::DateTime;
 use chrono::Utc;
 use codex_otel::OtelManager;
@@ -30,7 +55,11 @@ use sqlx::SqlitePool;
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::sqlite::SqliteJournalMode;
 use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::sqlite::SqliteRow;
 use sqlx::sqlite::SqliteSynchronous;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -41,6 +70,16 @@ pub const STATE_DB_FILENAME: &str = "state";
 pub const STATE_DB_VERSION: u32 = 3;
 
 const METRIC_DB_INIT: &str = "codex.db.init";
+/// Estimated per-row storage overhead (ids, timestamps, index entries) used
+/// when enforcing the log size budget.
+const LOG_ROW_OVERHEAD_BYTES: i64 = 64;
+/// Age in days after which a memory's search relevance is halved.
+const MEMORY_RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+/// Best BM25 matches re-ranked by recency in
+/// [`StateRuntime::search_thread_memories`].
+const MEMORY_SEARCH_CANDIDATES: i64 = 200;
+/// Entries kept in the network audit log; older ones are dropped.
+const MAX_NETWORK_AUDIT_ENTRIES: i64 = 100_000;
 
 #[derive(Clone)]
 pub struct StateRuntime {
@@ -237,7 +276,7 @@ ORDER BY position ASC
     ) -> anyhow::Result<Option<ThreadMemory>> {
         let row = sqlx::query(
             r#"
-SELECT thread_id, trace_summary, memory_summary, updated_at
+SELECT thread_id, trace_summary, memory_summary, pinned, updated_at
 FROM thread_memory
 WHERE thread_id = ?
             "#,
@@ -275,6 +314,7 @@ WHERE thread_id = ?
     }
 
     /// List threads using the underlying database.
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_threads(
         &self,
         page_size: usize,
@@ -283,6 +323,7 @@ WHERE thread_id = ?
         allowed_sources: &[String],
         model_providers: Option<&[String]>,
         archived_only: bool,
+        organization: &ThreadOrganizationFilter,
     ) -> anyhow::Result<crate::ThreadsPage> {
         let limit = page_size.saturating_add(1);
 
@@ -317,6 +358,7 @@ FROM threads
             anchor,
             sort_key,
         );
+        push_thread_organization_filters(&mut builder, organization);
         push_thread_order_and_limit(&mut builder, sort_key, limit);
 
         let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
@@ -352,7 +394,7 @@ FROM threads
         }
 
         let mut builder = QueryBuilder::<Sqlite>::new(
-            "INSERT INTO logs (ts, ts_nanos, level, target, message, thread_id, module_path, file, line) ",
+            "INSERT INTO logs (ts, ts_nanos, level, target, message, thread_id, turn_id, module_path, file, line) ",
         );
         builder.push_values(entries, |mut row, entry| {
             row.push_bind(entry.ts)
@@ -361,6 +403,7 @@ FROM threads
                 .push_bind(&entry.target)
                 .push_bind(&entry.message)
                 .push_bind(&entry.thread_id)
+                .push_bind(&entry.turn_id)
                 .push_bind(&entry.module_path)
                 .push_bind(&entry.file)
                 .push_bind(entry.line);
@@ -377,10 +420,43 @@ FROM threads
         Ok(result.rows_affected())
     }
 
+    /// Delete the oldest logs until the estimated size of the remaining rows
+    /// fits in `max_bytes`.
+    pub(crate) async fn delete_logs_exceeding_bytes(&self, max_bytes: u64) -> anyhow::Result<u64> {
+        let max_bytes = i64::try_from(max_bytes).unwrap_or(i64::MAX);
+        let result = sqlx::query(
+            r#"
+DELETE FROM logs
+WHERE id <= (
+    SELECT id FROM (
+        SELECT id, SUM(
+            LENGTH(level) + LENGTH(target)
+            + COALESCE(LENGTH(message), 0)
+            + COALESCE(LENGTH(thread_id), 0)
+            + COALESCE(LENGTH(turn_id), 0)
+            + COALESCE(LENGTH(module_path), 0)
+            + COALESCE(LENGTH(file), 0)
+            + ?
+        ) OVER (ORDER BY id DESC) AS retained_bytes
+        FROM logs
+    )
+    WHERE retained_bytes > ?
+    ORDER BY id DESC
+    LIMIT 1
+)
+            "#,
+        )
+        .bind(LOG_ROW_OVERHEAD_BYTES)
+        .bind(max_bytes)
+        .execute(self.pool.as_ref())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     /// Query logs with optional filters.
     pub async fn query_logs(&self, query: &LogQuery) -> anyhow::Result<Vec<LogRow>> {
         let mut builder = QueryBuilder::<Sqlite>::new(
-            "SELECT id, ts, ts_nanos, level, target, message, thread_id, file, line FROM logs WHERE 1 = 1",
+            "SELECT id, ts, ts_nanos, level, target, message, thread_id, turn_id, module_path, file, line FROM logs WHERE 1 = 1",
         );
         push_log_filters(&mut builder, query);
         if query.descending {
@@ -509,6 +585,7 @@ ON CONFLICT(id) DO UPDATE SET
     /// Insert or update memory summaries for a thread.
     ///
     /// This method always advances `updated_at`, even if summaries are unchanged.
+    /// The `pinned` flag of an existing memory is kept.
     pub async fn upsert_thread_memory(
         &self,
         thread_id: ThreadId,
@@ -549,7 +626,9 @@ ON CONFLICT(thread_id) DO UPDATE SET
             .ok_or_else(|| anyhow::anyhow!("failed to load upserted thread memory: {thread_id}"))
     }
 
-    /// Get the last `n` memories for threads with an exact cwd match.
+    /// Get the memories to recall for threads with an exact cwd match: every
+    /// pinned memory, then the most recent unpinned ones until `n` memories
+    /// are returned. Pinned memories are returned even when they exceed `n`.
     pub async fn get_last_n_thread_memories_for_cwd(
         &self,
         cwd: &Path,
@@ -559,28 +638,397 @@ ON CONFLICT(thread_id) DO UPDATE SET
             return Ok(Vec::new());
         }
 
-        let rows = sqlx::query(
+        let mut memories = self
+            .query_thread_memories_for_cwd(cwd, Some(true), None)
+            .await?;
+        let remaining = n.saturating_sub(memories.len());
+        if remaining > 0 {
+            memories.extend(
+                self.query_thread_memories_for_cwd(cwd, Some(false), Some(remaining))
+                    .await?,
+            );
+        }
+        Ok(memories)
+    }
+
+    /// List every memory for threads with an exact cwd match, pinned memories
+    /// first and the most recently updated first within each group.
+    pub async fn list_thread_memories_for_cwd(
+        &self,
+        cwd: &Path,
+    ) -> anyhow::Result<Vec<ThreadMemory>> {
+        self.query_thread_memories_for_cwd(cwd, None, None).await
+    }
+
+    async fn query_thread_memories_for_cwd(
+        &self,
+        cwd: &Path,
+        pinned: Option<bool>,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<ThreadMemory>> {
+        let mut builder = QueryBuilder::<Sqlite>::new(
             r#"
 SELECT
     m.thread_id,
     m.trace_summary,
     m.memory_summary,
+    m.pinned,
     m.updated_at
 FROM thread_memory AS m
 INNER JOIN threads AS t ON t.id = m.thread_id
-WHERE t.cwd = ?
-ORDER BY m.updated_at DESC, m.thread_id DESC
+WHERE t.cwd = "#,
+        );
+        builder.push_bind(cwd.display().to_string());
+        if let Some(pinned) = pinned {
+            builder.push(" AND m.pinned = ");
+            builder.push_bind(pinned);
+        }
+        builder.push(" ORDER BY m.pinned DESC, m.updated_at DESC, m.thread_id DESC");
+        if let Some(limit) = limit {
+            builder.push(" LIMIT ");
+            builder.push_bind(limit as i64);
+        }
+        let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
+
+        rows.into_iter()
+            .map(|row| ThreadMemoryRow::try_from_row(&row).and_then(ThreadMemory::try_from))
+            .collect()
+    }
+
+    /// Search the memories of threads started in `repo_root` or any directory
+    /// below it, returning at most `limit` memories ordered by relevance.
+    ///
+    /// Memories match when they contain any term of `query`; BM25 relevance
+    /// is weighted down as a memory ages (see
+    /// [`MEMORY_RECENCY_HALF_LIFE_DAYS`]). Memories with the same summary are
+    /// returned once, keeping the best scored.
+    pub async fn search_thread_memories(
+        &self,
+        repo_root: &Path,
+        query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ThreadMemorySearchHit>> {
+        let Some(match_query) = fts_any_term_query(query) else {
+            return Ok(Vec::new());
+        };
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let root = repo_root.display().to_string();
+        let prefix = format!("{}/", root.trim_end_matches('/'));
+
+        let rows = sqlx::query(
+            r#"
+SELECT
+    m.thread_id,
+    m.trace_summary,
+    m.memory_summary,
+    m.pinned,
+    m.updated_at,
+    t.cwd,
+    bm25(thread_memory_search, 0.0, 2.0, 1.0) AS rank
+FROM thread_memory_search
+INNER JOIN thread_memory AS m ON m.thread_id = thread_memory_search.thread_id
+INNER JOIN threads AS t ON t.id = m.thread_id
+WHERE thread_memory_search MATCH ?
+    AND (t.cwd = ? OR substr(t.cwd, 1, length(?)) = ?)
+ORDER BY rank
 LIMIT ?
             "#,
         )
-        .bind(cwd.display().to_string())
-        .bind(n as i64)
+        .bind(match_query)
+        .bind(root)
+        .bind(prefix.as_str())
+        .bind(prefix.as_str())
+        .bind(MEMORY_SEARCH_CANDIDATES)
         .fetch_all(self.pool.as_ref())
         .await?;
 
-        rows.into_iter()
-            .map(|row| ThreadMemoryRow::try_from_row(&row).and_then(ThreadMemory::try_from))
-            .collect()
+        let now = Utc::now();
+        let mut hits = rows
+            .into_iter()
+            .map(|row| {
+                let memory =
+                    ThreadMemoryRow::try_from_row(&row).and_then(ThreadMemory::try_from)?;
+                let cwd: String = row.try_get("cwd")?;
+                let rank: f64 = row.try_get("rank")?;
+                // bm25() is negative, lower being more relevant.
+                let score = -rank * recency_weight(memory.updated_at, now);
+                Ok(ThreadMemorySearchHit {
+                    memory,
+                    cwd: PathBuf::from(cwd),
+                    score,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b.memory.updated_at.cmp(&a.memory.updated_at))
+        });
+        let mut seen = HashSet::new();
+        hits.retain(|hit| seen.insert(dedup_key(&hit.memory.memory_summary)));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Replace the memory summary of a thread, keeping its trace summary.
+    ///
+    /// Advances `updated_at` like [`Self::upsert_thread_memory`]. Returns
+    /// `None` when the thread has no memory.
+    pub async fn update_thread_memory_summary(
+        &self,
+        thread_id: ThreadId,
+        memory_summary: &str,
+    ) -> anyhow::Result<Option<ThreadMemory>> {
+        sqlx::query(
+            r#"
+UPDATE thread_memory SET
+    memory_summary = ?,
+    updated_at = MAX(?, updated_at + 1)
+WHERE thread_id = ?
+            "#,
+        )
+        .bind(memory_summary)
+        .bind(Utc::now().timestamp())
+        .bind(thread_id.to_string())
+        .execute(self.pool.as_ref())
+        .await?;
+        self.get_thread_memory(thread_id).await
+    }
+
+    /// Pin or unpin the memory of a thread. Returns `None` when the thread
+    /// has no memory.
+    pub async fn set_thread_memory_pinned(
+        &self,
+        thread_id: ThreadId,
+        pinned: bool,
+    ) -> anyhow::Result<Option<ThreadMemory>> {
+        sqlx::query("UPDATE thread_memory SET pinned = ? WHERE thread_id = ?")
+            .bind(pinned)
+            .bind(thread_id.to_string())
+            .execute(self.pool.as_ref())
+            .await?;
+        self.get_thread_memory(thread_id).await
+    }
+
+    /// Delete the memory of a thread. Returns whether a memory was deleted.
+    pub async fn delete_thread_memory(&self, thread_id: ThreadId) -> anyhow::Result<bool> {
+        let result = sqlx::query("DELETE FROM thread_memory WHERE thread_id = ?")
+            .bind(thread_id.to_string())
+            .execute(self.pool.as_ref())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a fact about the repository at `repo_root` as pending review.
+    ///
+    /// The content is normalized with [`normalize_knowledge_content`]. When the
+    /// repository already has the same fact, the existing entry is returned
+    /// unchanged, so a rejected fact is not proposed again.
+    pub async fn record_project_knowledge(
+        &self,
+        repo_root: &Path,
+        content: &str,
+        source_thread_id: Option<ThreadId>,
+    ) -> anyhow::Result<ProjectKnowledge> {
+        let content = normalize_knowledge_content(content)
+            .ok_or_else(|| anyhow::anyhow!("knowledge content must not be empty"))?;
+        let repo_root = repo_root.display().to_string();
+        let existing = sqlx::query(
+            "SELECT * FROM project_knowledge WHERE repo_root = ? AND content = ? ORDER BY id LIMIT 1",
+        )
+        .bind(repo_root.as_str())
+        .bind(content.as_str())
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+        if let Some(row) = existing {
+            return ProjectKnowledge::try_from_row(&row);
+        }
+
+        let now = Utc::now().timestamp();
+        let row = sqlx::query(
+            r#"
+INSERT INTO project_knowledge (
+    repo_root,
+    content,
+    status,
+    source_thread_id,
+    created_at,
+    updated_at
+) VALUES (?, ?, ?, ?, ?, ?)
+RETURNING *
+            "#,
+        )
+        .bind(repo_root)
+        .bind(content)
+        .bind(KnowledgeStatus::Pending.as_str())
+        .bind(source_thread_id.map(|thread_id| thread_id.to_string()))
+        .bind(now)
+        .bind(now)
+        .fetch_one(self.pool.as_ref())
+        .await?;
+        ProjectKnowledge::try_from_row(&row)
+    }
+
+    /// List the knowledge recorded for the repository at `repo_root`, oldest
+    /// first, optionally restricted to one status.
+    pub async fn list_project_knowledge(
+        &self,
+        repo_root: &Path,
+        status: Option<KnowledgeStatus>,
+    ) -> anyhow::Result<Vec<ProjectKnowledge>> {
+        let mut builder =
+            QueryBuilder::<Sqlite>::new("SELECT * FROM project_knowledge WHERE repo_root = ");
+        builder.push_bind(repo_root.display().to_string());
+        if let Some(status) = status {
+            builder.push(" AND status = ");
+            builder.push_bind(status.as_str());
+        }
+        builder.push(" ORDER BY id");
+        let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
+        rows.iter().map(ProjectKnowledge::try_from_row).collect()
+    }
+
+    /// Approve, reject or reset a knowledge entry. Returns `None` when the
+    /// entry is unknown.
+    pub async fn set_project_knowledge_status(
+        &self,
+        id: i64,
+        status: KnowledgeStatus,
+    ) -> anyhow::Result<Option<ProjectKnowledge>> {
+        let row = sqlx::query(
+            "UPDATE project_knowledge SET status = ?, updated_at = ? WHERE id = ? RETURNING *",
+        )
+        .bind(status.as_str())
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+        row.map(|row| ProjectKnowledge::try_from_row(&row))
+            .transpose()
+    }
+
+    /// Delete a knowledge entry. Returns whether an entry was deleted.
+    pub async fn delete_project_knowledge(&self, id: i64) -> anyhow::Result<bool> {
+        let result = sqlx::query("DELETE FROM project_knowledge WHERE id = ?")
+            .bind(id)
+            .execute(self.pool.as_ref())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Append a submitted message to the message history.
+    ///
+    /// A message identical to the newest entry replaces it instead of being
+    /// added again. When `max_bytes` is set, the oldest entries are dropped
+    /// until the text of the remaining ones fits, always keeping the newest.
+    pub async fn append_message_history(
+        &self,
+        entry: &MessageHistoryEntry,
+        max_bytes: Option<usize>,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        insert_message_history_entry(&mut tx, entry).await?;
+        if let Some(max_bytes) = max_bytes.filter(|max_bytes| *max_bytes > 0) {
+            sqlx::query(
+                r#"
+DELETE FROM message_history
+WHERE id < (SELECT MAX(id) FROM message_history)
+    AND id <= (
+        SELECT id FROM (
+            SELECT id, SUM(length(CAST(text AS BLOB))) OVER (ORDER BY id DESC) AS total
+            FROM message_history
+        )
+        WHERE total > ?
+        ORDER BY id DESC
+        LIMIT 1
+    )
+                "#,
+            )
+            .bind(i64::try_from(max_bytes).unwrap_or(i64::MAX))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Append entries imported from a `history.jsonl` file, oldest first.
+    /// Consecutive duplicates are collapsed like in
+    /// [`Self::append_message_history`]. Returns the number of entries read.
+    pub async fn import_message_history(
+        &self,
+        entries: &[MessageHistoryEntry],
+    ) -> anyhow::Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        for entry in entries {
+            insert_message_history_entry(&mut tx, entry).await?;
+        }
+        tx.commit().await?;
+        Ok(entries.len())
+    }
+
+    /// Return the id of the newest message history entry (0 when empty) and
+    /// the number of entries.
+    ///
+    /// Sessions pass the id to [`Self::message_history_entry`] so entries
+    /// appended later by other sessions do not shift the offsets they use.
+    pub async fn message_history_metadata(&self) -> anyhow::Result<(i64, usize)> {
+        let row = sqlx::query(
+            "SELECT COALESCE(MAX(id), 0) AS newest_id, COUNT(*) AS entries FROM message_history",
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+        let entries: i64 = row.try_get("entries")?;
+        Ok((row.try_get("newest_id")?, usize::try_from(entries)?))
+    }
+
+    /// Get the message history entry at `offset`, counting from the oldest,
+    /// among the entries up to `newest_id`.
+    pub async fn message_history_entry(
+        &self,
+        newest_id: i64,
+        offset: usize,
+    ) -> anyhow::Result<Option<MessageHistoryEntry>> {
+        let row =
+            sqlx::query("SELECT * FROM message_history WHERE id <= ? ORDER BY id LIMIT 1 OFFSET ?")
+                .bind(newest_id)
+                .bind(i64::try_from(offset)?)
+                .fetch_optional(self.pool.as_ref())
+                .await?;
+        row.map(|row| MessageHistoryEntry::try_from_row(&row))
+            .transpose()
+    }
+
+    /// List up to `limit` distinct messages from the message history, most
+    /// recently submitted first.
+    ///
+    /// With `repo_root`, only messages submitted in that directory or below
+    /// it are returned.
+    pub async fn search_message_history(
+        &self,
+        repo_root: Option<&Path>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<MessageHistoryEntry>> {
+        // SQLite takes the bare columns from the row holding MAX(id).
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT MAX(id) AS id, thread_id, cwd, text, ts FROM message_history",
+        );
+        if let Some(repo_root) = repo_root {
+            let root = repo_root.display().to_string();
+            let prefix = format!("{}/", root.trim_end_matches('/'));
+            builder.push(" WHERE cwd = ");
+            builder.push_bind(root);
+            builder.push(" OR substr(cwd, 1, length(");
+            builder.push_bind(prefix.clone());
+            builder.push(")) = ");
+            builder.push_bind(prefix);
+        }
+        builder.push(" GROUP BY text ORDER BY id DESC LIMIT ");
+        builder.push_bind(i64::try_from(limit)?);
+        let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
+        rows.iter().map(MessageHistoryEntry::try_from_row).collect()
     }
 
     /// Persist dynamic tools for a thread if none have been stored yet.
@@ -667,9 +1115,168 @@ ON CONFLICT(thread_id, position) DO NOTHING
             }
             return Err(err);
         }
+        if let Err(err) = self
+            .insert_thread_search_entries(builder.id, &thread_search_entries(items))
+            .await
+        {
+            if let Some(otel) = otel {
+                otel.counter(
+                    DB_ERROR_METRIC,
+                    1,
+                    &[("stage", "insert_thread_search_entries")],
+                );
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Add entries to a thread's full-text search index.
+    ///
+    /// Entries already indexed for the thread are skipped, so replaying a
+    /// rollout (for example during backfill) does not duplicate matches.
+    pub async fn insert_thread_search_entries(
+        &self,
+        thread_id: ThreadId,
+        entries: &[ThreadSearchEntry],
+    ) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let thread_id = thread_id.to_string();
+        let mut tx = self.pool.begin().await?;
+        for entry in entries {
+            sqlx::query(
+                r#"
+INSERT INTO thread_search_entries (thread_id, source, content)
+VALUES (?, ?, ?)
+ON CONFLICT(thread_id, source, content) DO NOTHING
+                "#,
+            )
+            .bind(thread_id.as_str())
+            .bind(entry.source.as_str())
+            .bind(entry.content.as_str())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 
+    /// Search thread contents, returning at most one hit per thread ordered by
+    /// relevance.
+    ///
+    /// Every whitespace-separated term in `query` must match, and the last
+    /// term also matches as a prefix so results can follow typing.
+    pub async fn search_threads(
+        &self,
+        query: &str,
+        page_size: usize,
+        offset: usize,
+        allowed_sources: &[String],
+        model_providers: Option<&[String]>,
+        archived_only: bool,
+    ) -> anyhow::Result<ThreadSearchPage> {
+        let Some(match_query) = fts_match_query(query) else {
+            return Ok(ThreadSearchPage {
+                items: Vec::new(),
+                next_offset: None,
+            });
+        };
+        let limit = page_size.saturating_add(1);
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            r#"
+WITH matches AS (
+    SELECT
+        thread_search_entries.thread_id AS thread_id,
+        thread_search_entries.source AS hit_source,
+        snippet(thread_search, 0, char(2), char(3), '…', 16) AS snippet,
+        bm25(thread_search) AS rank
+    FROM thread_search
+    JOIN thread_search_entries ON thread_search_entries.id = thread_search.rowid
+    WHERE thread_search MATCH "#,
+        );
+        builder.push_bind(match_query);
+        builder.push(
+            r#"
+),
+best_matches AS (
+    SELECT
+        thread_id,
+        hit_source,
+        snippet,
+        rank,
+        ROW_NUMBER() OVER (PARTITION BY thread_id ORDER BY rank) AS hit_number
+    FROM matches
+)
+SELECT
+    threads.id,
+    threads.rollout_path,
+    threads.created_at,
+    threads.updated_at,
+    threads.source,
+    threads.model_provider,
+    threads.cwd,
+    threads.cli_version,
+    threads.title,
+    threads.sandbox_policy,
+    threads.approval_mode,
+    threads.tokens_used,
+    threads.first_user_message,
+    threads.archived_at,
+    threads.git_sha,
+    threads.git_branch,
+    threads.git_origin_url,
+    best_matches.hit_source,
+    best_matches.snippet,
+    best_matches.rank
+FROM best_matches
+JOIN threads ON threads.id = best_matches.thread_id
+            "#,
+        );
+        push_thread_filters(
+            &mut builder,
+            archived_only,
+            allowed_sources,
+            model_providers,
+            None,
+            SortKey::UpdatedAt,
+        );
+        builder.push(" AND best_matches.hit_number = 1");
+        builder.push(" ORDER BY best_matches.rank, threads.updated_at DESC, threads.id DESC");
+        builder.push(" LIMIT ");
+        builder.push_bind(limit as i64);
+        builder.push(" OFFSET ");
+        builder.push_bind(offset as i64);
+
+        let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
+        let mut items = rows
+            .into_iter()
+            .map(|row| {
+                let thread = ThreadRow::try_from_row(&row).and_then(ThreadMetadata::try_from)?;
+                let source: String = row.try_get("hit_source")?;
+                let source = ThreadSearchSource::parse(source.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("unknown thread search source: {source}"))?;
+                let (snippet, highlights) = parse_snippet(row.try_get("snippet")?);
+                Ok(ThreadSearchHit {
+                    thread,
+                    source,
+                    snippet,
+                    highlights,
+                    rank: row.try_get("rank")?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let next_offset = if items.len() > page_size {
+            items.pop();
+            Some(offset.saturating_add(page_size))
+        } else {
+            None
+        };
+        Ok(ThreadSearchPage { items, next_offset })
+    }
+
     /// Mark a thread as archived using the underlying database.
     pub async fn mark_archived(
         &self,
@@ -726,31 +1333,361 @@ ON CONFLICT(thread_id, position) DO NOTHING
         Ok(())
     }
 
-    async fn ensure_backfill_state_row(&self) -> anyhow::Result<()> {
-        sqlx::query(
-            r#"
-INSERT INTO backfill_state (id, status, last_watermark, last_success_at, updated_at)
-VALUES (?, ?, NULL, NULL, ?)
-ON CONFLICT(id) DO NOTHING
-            "#,
-        )
-        .bind(1_i64)
-        .bind(crate::BackfillStatus::Pending.as_str())
-        .bind(Utc::now().timestamp())
-        .execute(self.pool.as_ref())
-        .await?;
-        Ok(())
+    /// Load the tags, pin and folder of a thread.
+    pub async fn get_thread_organization(
+        &self,
+        thread_id: ThreadId,
+    ) -> anyhow::Result<Option<ThreadOrganization>> {
+        let row = sqlx::query("SELECT tags, pinned, folder FROM threads WHERE id = ?")
+            .bind(thread_id.to_string())
+            .fetch_optional(self.pool.as_ref())
+            .await?;
+        row.map(|row| thread_organization_from_row(&row))
+            .transpose()
     }
-}
 
-fn push_log_filters<'a>(builder: &mut QueryBuilder<'a, Sqlite>, query: &'a LogQuery) {
-    if let Some(level_upper) = query.level_upper.as_ref() {
-        builder
-            .push(" AND UPPER(level) = ")
-            .push_bind(level_upper.as_str());
-    }
-    if let Some(from_ts) = query.from_ts {
-        builder.push(" AND ts >= ").push_bind(from_ts);
+    /// Load the organization of several threads at once, keyed by thread id.
+    ///
+    /// Threads missing from the database are left out of the result.
+    pub async fn get_thread_organizations(
+        &self,
+        thread_ids: &[ThreadId],
+    ) -> anyhow::Result<HashMap<ThreadId, ThreadOrganization>> {
+        if thread_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT id, tags, pinned, folder FROM threads WHERE id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for thread_id in thread_ids {
+            separated.push_bind(thread_id.to_string());
+        }
+        separated.push_unseparated(")");
+        let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
+        rows.into_iter()
+            .map(|row| {
+                let id: String = row.try_get("id")?;
+                Ok((ThreadId::try_from(id)?, thread_organization_from_row(&row)?))
+            })
+            .collect()
+    }
+
+    /// Replace the tags of a thread.
+    ///
+    /// Tags are normalized with [`normalize_thread_tags`]. Returns the updated
+    /// organization, or `None` when the thread is unknown.
+    pub async fn set_thread_tags<S: AsRef<str>>(
+        &self,
+        thread_id: ThreadId,
+        tags: &[S],
+    ) -> anyhow::Result<Option<ThreadOrganization>> {
+        let tags = serde_json::to_string(&normalize_thread_tags(tags))?;
+        sqlx::query("UPDATE threads SET tags = ? WHERE id = ?")
+            .bind(tags)
+            .bind(thread_id.to_string())
+            .execute(self.pool.as_ref())
+            .await?;
+        self.get_thread_organization(thread_id).await
+    }
+
+    /// Pin or unpin a thread. Returns the updated organization, or `None`
+    /// when the thread is unknown.
+    pub async fn set_thread_pinned(
+        &self,
+        thread_id: ThreadId,
+        pinned: bool,
+    ) -> anyhow::Result<Option<ThreadOrganization>> {
+        sqlx::query("UPDATE threads SET pinned = ? WHERE id = ?")
+            .bind(pinned)
+            .bind(thread_id.to_string())
+            .execute(self.pool.as_ref())
+            .await?;
+        self.get_thread_organization(thread_id).await
+    }
+
+    /// Move a thread into a folder, or out of any folder when `folder` is
+    /// `None` or blank.
+    ///
+    /// The folder is normalized with [`normalize_thread_folder`]. Returns the
+    /// updated organization, or `None` when the thread is unknown.
+    pub async fn set_thread_folder(
+        &self,
+        thread_id: ThreadId,
+        folder: Option<&str>,
+    ) -> anyhow::Result<Option<ThreadOrganization>> {
+        sqlx::query("UPDATE threads SET folder = ? WHERE id = ?")
+            .bind(folder.and_then(normalize_thread_folder))
+            .bind(thread_id.to_string())
+            .execute(self.pool.as_ref())
+            .await?;
+        self.get_thread_organization(thread_id).await
+    }
+
+    /// Record the token usage of a finished turn.
+    pub async fn record_turn_usage(&self, usage: &TurnUsage) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let _ = insert_turn_usage(&mut tx, usage).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Record usage reconstructed from a thread's rollout.
+    ///
+    /// Turns are keyed by `(thread_id, turn_id)`: a turn whose id already has
+    /// a row for the thread is skipped, whether it was recorded live or by an
+    /// earlier backfill, so no turn is counted twice. Returns whether any
+    /// entry was written.
+    pub async fn backfill_thread_usage(
+        &self,
+        thread_id: ThreadId,
+        usage: &[TurnUsage],
+    ) -> anyhow::Result<bool> {
+        if usage.is_empty() {
+            return Ok(false);
+        }
+        let mut tx = self.pool.begin().await?;
+        // Read the recorded ids up front: turns of rollouts written before
+        // turns got UUIDs carry per-process counters, which the unique index
+        // leaves out because one rollout may repeat them for different turns.
+        let recorded: HashSet<String> =
+            sqlx::query_scalar("SELECT turn_id FROM usage WHERE thread_id = ?")
+                .bind(thread_id.to_string())
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .collect();
+        let mut written = false;
+        for entry in usage {
+            if recorded.contains(&entry.turn_id) {
+                continue;
+            }
+            written |= insert_turn_usage(&mut tx, entry).await?;
+        }
+        tx.commit().await?;
+        Ok(written)
+    }
+
+    /// Sum recorded usage per group and model.
+    pub async fn usage_report(&self, query: &UsageQuery) -> anyhow::Result<Vec<UsageReportRow>> {
+        let group_expr = match query.group_by {
+            UsageGroupBy::Day => "strftime('%Y-%m-%d', usage.recorded_at, 'unixepoch')",
+            UsageGroupBy::Model => "usage.model",
+            UsageGroupBy::Repo => "COALESCE(threads.git_origin_url, usage.cwd)",
+            UsageGroupBy::Thread => "usage.thread_id",
+        };
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT ");
+        builder.push(group_expr).push(
+            r#" AS usage_group,
+    usage.model AS model,
+    COUNT(*) AS turns,
+    SUM(usage.input_tokens) AS input_tokens,
+    SUM(usage.cached_input_tokens) AS cached_input_tokens,
+    SUM(usage.output_tokens) AS output_tokens,
+    SUM(usage.reasoning_output_tokens) AS reasoning_output_tokens
+FROM usage
+LEFT JOIN threads ON threads.id = usage.thread_id
+WHERE 1 = 1"#,
+        );
+        if let Some(since) = query.since {
+            builder
+                .push(" AND usage.recorded_at >= ")
+                .push_bind(datetime_to_epoch_seconds(since));
+        }
+        if let Some(until) = query.until {
+            builder
+                .push(" AND usage.recorded_at < ")
+                .push_bind(datetime_to_epoch_seconds(until));
+        }
+        builder.push(" GROUP BY usage_group, usage.model ORDER BY usage_group ASC, model ASC");
+
+        let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(UsageReportRow {
+                    group: row.try_get("usage_group")?,
+                    model: row.try_get("model")?,
+                    turns: row.try_get("turns")?,
+                    input_tokens: row.try_get("input_tokens")?,
+                    cached_input_tokens: row.try_get("cached_input_tokens")?,
+                    output_tokens: row.try_get("output_tokens")?,
+                    reasoning_output_tokens: row.try_get("reasoning_output_tokens")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Append a request decided by a session's network proxy to the network
+    /// audit log, dropping the oldest entries beyond
+    /// [`MAX_NETWORK_AUDIT_ENTRIES`].
+    pub async fn append_network_audit(&self, entry: &NetworkAuditEntry) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let id = sqlx::query(
+            r#"
+INSERT INTO network_audit (
+    ts,
+    thread_id,
+    turn_id,
+    call_id,
+    protocol,
+    host,
+    method,
+    decision,
+    reason,
+    bytes_sent,
+    bytes_received
+) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(datetime_to_epoch_seconds(entry.ts))
+        .bind(entry.thread_id.map(|thread_id| thread_id.to_string()))
+        .bind(entry.turn_id.as_deref())
+        .bind(entry.call_id.as_deref())
+        .bind(entry.protocol.as_str())
+        .bind(entry.host.as_str())
+        .bind(entry.method.as_deref())
+        .bind(entry.decision.as_str())
+        .bind(entry.reason.as_deref())
+        .bind(entry.bytes_sent)
+        .bind(entry.bytes_received)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        sqlx::query("DELETE FROM network_audit WHERE id <= ?")
+            .bind(id - MAX_NETWORK_AUDIT_ENTRIES)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// List network audit entries matching `query`, newest first.
+    pub async fn list_network_audit(
+        &self,
+        query: &NetworkAuditQuery,
+    ) -> anyhow::Result<Vec<NetworkAuditEntry>> {
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM network_audit WHERE 1 = 1");
+        if let Some(thread_id) = query.thread_id {
+            builder
+                .push(" AND thread_id = ")
+                .push_bind(thread_id.to_string());
+        }
+        if let Some(host) = &query.host {
+            builder
+                .push(" AND host = ")
+                .push_bind(host.to_ascii_lowercase());
+        }
+        if let Some(decision) = query.decision {
+            builder
+                .push(" AND decision = ")
+                .push_bind(decision.as_str());
+        }
+        if let Some(since) = query.since {
+            builder
+                .push(" AND ts >= ")
+                .push_bind(datetime_to_epoch_seconds(since));
+        }
+        builder
+            .push(" ORDER BY id DESC LIMIT ")
+            .push_bind(i64::try_from(query.limit)?);
+        let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
+        rows.iter().map(NetworkAuditEntry::try_from_row).collect()
+    }
+
+    async fn ensure_backfill_state_row(&self) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO backfill_state (id, status, last_watermark, last_success_at, updated_at)
+VALUES (?, ?, NULL, NULL, ?)
+ON CONFLICT(id) DO NOTHING
+            "#,
+        )
+        .bind(1_i64)
+        .bind(crate::BackfillStatus::Pending.as_str())
+        .bind(Utc::now().timestamp())
+        .execute(self.pool.as_ref())
+        .await?;
+        Ok(())
+    }
+}
+
+/// Returns whether a row was written; a backfilled turn that is already
+/// recorded is skipped.
+async fn insert_turn_usage(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    usage: &TurnUsage,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query(
+        r#"
+INSERT OR IGNORE INTO usage (
+    thread_id,
+    turn_id,
+    model,
+    model_provider,
+    cwd,
+    input_tokens,
+    cached_input_tokens,
+    output_tokens,
+    reasoning_output_tokens,
+    recorded_at
+) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(usage.thread_id.to_string())
+    .bind(usage.turn_id.as_str())
+    .bind(usage.model.as_str())
+    .bind(usage.model_provider.as_str())
+    .bind(usage.cwd.display().to_string())
+    .bind(usage.input_tokens)
+    .bind(usage.cached_input_tokens)
+    .bind(usage.output_tokens)
+    .bind(usage.reasoning_output_tokens)
+    .bind(datetime_to_epoch_seconds(usage.recorded_at))
+    .execute(&mut **tx)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+async fn insert_message_history_entry(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    entry: &MessageHistoryEntry,
+) -> anyhow::Result<()> {
+    let thread_id = entry.thread_id.map(|thread_id| thread_id.to_string());
+    let cwd = entry.cwd.as_ref().map(|cwd| cwd.display().to_string());
+    let ts = datetime_to_epoch_seconds(entry.ts);
+    let newest = sqlx::query("SELECT id, text FROM message_history ORDER BY id DESC LIMIT 1")
+        .fetch_optional(&mut **tx)
+        .await?;
+    if let Some(newest) = newest
+        && newest.try_get::<String, _>("text")? == entry.text
+    {
+        sqlx::query("UPDATE message_history SET thread_id = ?, cwd = ?, ts = ? WHERE id = ?")
+            .bind(thread_id)
+            .bind(cwd)
+            .bind(ts)
+            .bind(newest.try_get::<i64, _>("id")?)
+            .execute(&mut **tx)
+            .await?;
+        return Ok(());
+    }
+    sqlx::query("INSERT INTO message_history (thread_id, cwd, text, ts) VALUES (?, ?, ?, ?)")
+        .bind(thread_id)
+        .bind(cwd)
+        .bind(entry.text.as_str())
+        .bind(ts)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+fn push_log_filters<'a>(builder: &mut QueryBuilder<'a, Sqlite>, query: &'a LogQuery) {
+    if let Some(level_upper) = query.level_upper.as_ref() {
+        builder
+            .push(" AND UPPER(level) = ")
+            .push_bind(level_upper.as_str());
+    }
+    if let Some(from_ts) = query.from_ts {
+        builder.push(" AND ts >= ").push_bind(from_ts);
     }
     if let Some(to_ts) = query.to_ts {
         builder.push(" AND ts <= ").push_bind(to_ts);
@@ -776,11 +1713,77 @@ fn push_log_filters<'a>(builder: &mut QueryBuilder<'a, Sqlite>, query: &'a LogQu
         }
         builder.push(")");
     }
+    if let Some(filter) = query.filter.as_ref() {
+        builder.push(" AND ");
+        push_log_filter_expr(builder, filter);
+    }
     if let Some(after_id) = query.after_id {
         builder.push(" AND id > ").push_bind(after_id);
     }
 }
 
+fn push_log_filter_expr<'a>(builder: &mut QueryBuilder<'a, Sqlite>, filter: &'a LogFilter) {
+    match filter {
+        LogFilter::And(left, right) | LogFilter::Or(left, right) => {
+            let joiner = if matches!(filter, LogFilter::And(..)) {
+                " AND "
+            } else {
+                " OR "
+            };
+            builder.push("(");
+            push_log_filter_expr(builder, left);
+            builder.push(joiner);
+            push_log_filter_expr(builder, right);
+            builder.push(")");
+        }
+        LogFilter::Not(inner) => {
+            builder.push("NOT (");
+            push_log_filter_expr(builder, inner);
+            builder.push(")");
+        }
+        LogFilter::Compare { field, op, value } => {
+            let column = field.column();
+            match op {
+                LogFilterOp::Contains => {
+                    builder
+                        .push(format!("COALESCE({column}, '') LIKE '%' || "))
+                        .push_bind(value.as_str())
+                        .push(" || '%'");
+                }
+                LogFilterOp::Eq | LogFilterOp::Ne
+                    if field.is_optional_id() && value.eq_ignore_ascii_case("none") =>
+                {
+                    let negation = if *op == LogFilterOp::Ne { " NOT" } else { "" };
+                    builder.push(format!("{column} IS{negation} NULL"));
+                }
+                LogFilterOp::Eq | LogFilterOp::Ne if *field == LogField::Level => {
+                    builder
+                        .push(format!("UPPER({column}) {} ", op.sql()))
+                        .push_bind(value.as_str());
+                }
+                LogFilterOp::Eq => {
+                    builder
+                        .push(format!("{column} IS "))
+                        .push_bind(value.as_str());
+                }
+                LogFilterOp::Ne => {
+                    builder
+                        .push(format!("{column} IS NOT "))
+                        .push_bind(value.as_str());
+                }
+                LogFilterOp::Ge | LogFilterOp::Gt | LogFilterOp::Le | LogFilterOp::Lt => {
+                    let rank = log_level_rank(value).unwrap_or_default() as i64;
+                    builder.push("(CASE UPPER(level)");
+                    for (idx, level) in LOG_LEVELS.iter().enumerate() {
+                        builder.push(format!(" WHEN '{level}' THEN {idx}"));
+                    }
+                    builder.push(format!(" END) {} ", op.sql())).push_bind(rank);
+                }
+            }
+        }
+    }
+}
+
 fn push_like_filters<'a>(
     builder: &mut QueryBuilder<'a, Sqlite>,
     column: &str,
@@ -953,6 +1956,104 @@ fn push_thread_filters<'a>(
     }
 }
 
+fn push_thread_organization_filters<'a>(
+    builder: &mut QueryBuilder<'a, Sqlite>,
+    organization: &'a ThreadOrganizationFilter,
+) {
+    for tag in &organization.tags {
+        builder.push(" AND EXISTS (SELECT 1 FROM json_each(threads.tags) WHERE json_each.value = ");
+        builder.push_bind(tag);
+        builder.push(")");
+    }
+    if let Some(pinned) = organization.pinned {
+        builder.push(" AND pinned = ");
+        builder.push_bind(pinned);
+    }
+    if let Some(folder) = organization.folder.as_deref() {
+        // Match the folder itself and anything nested below it.
+        let prefix = format!("{folder}/");
+        builder.push(" AND (folder = ");
+        builder.push_bind(folder);
+        builder.push(" OR substr(folder, 1, ");
+        builder.push_bind(prefix.chars().count() as i64);
+        builder.push(") = ");
+        builder.push_bind(prefix);
+        builder.push(")");
+    }
+}
+
+fn thread_organization_from_row(row: &SqliteRow) -> anyhow::Result<ThreadOrganization> {
+    let tags: String = row.try_get("tags")?;
+    Ok(ThreadOrganization {
+        tags: serde_json::from_str(tags.as_str())?,
+        pinned: row.try_get("pinned")?,
+        folder: row.try_get("folder")?,
+    })
+}
+
+/// Build an FTS5 query from free text: each term is quoted so FTS syntax in
+/// user input is matched literally, and the last term matches as a prefix.
+fn fts_match_query(query: &str) -> Option<String> {
+    let terms = fts_terms(query);
+    if terms.is_empty() {
+        return None;
+    }
+    let mut match_query = terms.join(" ");
+    match_query.push('*');
+    Some(match_query)
+}
+
+/// Build an FTS5 query matching rows that contain any term of `query`, so
+/// BM25 ranks rows by how many of the terms they share.
+fn fts_any_term_query(query: &str) -> Option<String> {
+    let terms = fts_terms(query);
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+fn fts_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .filter(|term| term.chars().any(char::is_alphanumeric))
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect()
+}
+
+/// Weight applied to the relevance of a memory last updated at `updated_at`:
+/// 1 for a fresh memory, halving every [`MEMORY_RECENCY_HALF_LIFE_DAYS`].
+fn recency_weight(updated_at: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let age_days = (now - updated_at).num_seconds().max(0) as f64 / 86_400.0;
+    0.5_f64.powf(age_days / MEMORY_RECENCY_HALF_LIFE_DAYS)
+}
+
+/// Memories whose summaries only differ in case or whitespace are duplicates.
+fn dedup_key(summary: &str) -> String {
+    summary
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Strip the highlight markers emitted by `snippet()` and return the byte
+/// ranges they enclosed.
+fn parse_snippet(raw: String) -> (String, Vec<Range<usize>>) {
+    let mut snippet = String::with_capacity(raw.len());
+    let mut highlights = Vec::new();
+    let mut start = None;
+    for ch in raw.chars() {
+        match ch {
+            '\u{2}' => start = Some(snippet.len()),
+            '\u{3}' => {
+                if let Some(start) = start.take() {
+                    highlights.push(start..snippet.len());
+                }
+            }
+            ch => snippet.push(ch),
+        }
+    }
+    (snippet, highlights)
+}
+
 fn push_thread_order_and_limit(
     builder: &mut QueryBuilder<'_, Sqlite>,
     sort_key: SortKey,
@@ -971,11 +2072,27 @@ fn push_thread_order_and_limit(
 
 #[cfg(test)]
 mod tests {
+    use super::KnowledgeStatus;
+    use super::LogEntry;
+    use super::LogQuery;
+    use super::MessageHistoryEntry;
+    use super::NetworkAuditEntry;
+    use super::NetworkAuditQuery;
     use super::STATE_DB_FILENAME;
     use super::STATE_DB_VERSION;
+    use super::SortKey;
     use super::StateRuntime;
     use super::ThreadMetadata;
+    use super::ThreadOrganization;
+    use super::ThreadOrganizationFilter;
+    use super::ThreadSearchEntry;
+    use super::ThreadSearchSource;
+    use super::TurnUsage;
+    use super::UsageGroupBy;
+    use super::UsageQuery;
+    use super::UsageReportRow;
     use super::state_db_filename;
+    use crate::NetworkAuditDecision;
     use chrono::DateTime;
     use chrono::Utc;
     use codex_protocol::ThreadId;
@@ -1242,6 +2359,99 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(codex_home).await;
     }
 
+    #[tokio::test]
+    async fn search_thread_memories_ranks_memories_across_the_repository() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let repo = codex_home.join("repo");
+        let crate_dir = repo.join("crates").join("state");
+        let sibling = codex_home.join("repo-other");
+        let mut threads = Vec::new();
+        for (cwd, memory_summary) in [
+            (repo.clone(), "sqlite migration test was flaky locally"),
+            (crate_dir.clone(), "sqlite migration test was flaky on CI"),
+            (repo.clone(), "SQLite  migration test was flaky locally"),
+            (sibling.clone(), "sqlite migration test was flaky there too"),
+            (repo.clone(), "renamed the tui theme"),
+        ] {
+            let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+            runtime
+                .upsert_thread(&test_thread_metadata(&codex_home, thread_id, cwd))
+                .await
+                .expect("upsert thread");
+            runtime
+                .upsert_thread_memory(thread_id, "trace", memory_summary)
+                .await
+                .expect("upsert memory");
+            threads.push(thread_id);
+        }
+        let [recent, old, duplicate, sibling_thread, unrelated] = threads[..] else {
+            panic!("expected five threads");
+        };
+        let ninety_days_ago = Utc::now().timestamp() - 90 * 86_400;
+        sqlx::query("UPDATE thread_memory SET updated_at = ? WHERE thread_id = ?")
+            .bind(ninety_days_ago)
+            .bind(old.to_string())
+            .execute(runtime.pool.as_ref())
+            .await
+            .expect("age memory");
+
+        let hits = runtime
+            .search_thread_memories(&repo, "flaky sqlite test", 10)
+            .await
+            .expect("search memories");
+        let hit_ids = hits
+            .iter()
+            .map(|hit| hit.memory.thread_id)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hit_ids.len(),
+            2,
+            "duplicates and other repositories are skipped"
+        );
+        assert!(hit_ids[0] == recent || hit_ids[0] == duplicate);
+        assert_eq!(hit_ids[1], old);
+        assert_eq!(hits[1].cwd, crate_dir);
+        assert!(hits[0].score > hits[1].score);
+        assert!(!hit_ids.contains(&sibling_thread));
+
+        let limited = runtime
+            .search_thread_memories(&repo, "flaky sqlite test", 1)
+            .await
+            .expect("search memories");
+        assert_eq!(limited.len(), 1);
+
+        // Edits and deletions are reflected in the index.
+        runtime
+            .update_thread_memory_summary(old, "tui theme colors")
+            .await
+            .expect("edit memory");
+        let hits = runtime
+            .search_thread_memories(&repo, "colors", 10)
+            .await
+            .expect("search memories");
+        assert_eq!(
+            hits.iter()
+                .map(|hit| hit.memory.thread_id)
+                .collect::<Vec<_>>(),
+            vec![old]
+        );
+        runtime
+            .delete_thread_memory(unrelated)
+            .await
+            .expect("delete memory");
+        let hits = runtime
+            .search_thread_memories(&repo, "renamed", 10)
+            .await
+            .expect("search memories");
+        assert_eq!(hits, Vec::new());
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
     #[tokio::test]
     async fn upsert_thread_memory_errors_for_unknown_thread() {
         let codex_home = unique_temp_dir();
@@ -1338,58 +2548,1058 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn deleting_thread_cascades_thread_memory() {
+    async fn pinned_thread_memories_are_always_recalled() {
         let codex_home = unique_temp_dir();
         let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
             .await
             .expect("initialize runtime");
 
-        let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
         let cwd = codex_home.join("workspace");
-        runtime
-            .upsert_thread(&test_thread_metadata(&codex_home, thread_id, cwd))
-            .await
-            .expect("upsert thread");
-        runtime
-            .upsert_thread_memory(thread_id, "trace", "memory")
+        let mut thread_ids = Vec::new();
+        for index in 0..3 {
+            let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+            runtime
+                .upsert_thread(&test_thread_metadata(&codex_home, thread_id, cwd.clone()))
+                .await
+                .expect("upsert thread");
+            runtime
+                .upsert_thread_memory(thread_id, "trace", &format!("memory-{index}"))
+                .await
+                .expect("upsert memory");
+            thread_ids.push(thread_id);
+        }
+        let (t1, t2) = (thread_ids[0], thread_ids[1]);
+
+        let pinned = runtime
+            .set_thread_memory_pinned(t1, true)
             .await
-            .expect("upsert memory");
+            .expect("pin memory")
+            .expect("memory exists");
+        assert!(pinned.pinned);
 
-        let count_before =
-            sqlx::query("SELECT COUNT(*) AS count FROM thread_memory WHERE thread_id = ?")
-                .bind(thread_id.to_string())
-                .fetch_one(runtime.pool.as_ref())
-                .await
-                .expect("count before delete")
-                .try_get::<i64, _>("count")
-                .expect("count value");
-        assert_eq!(count_before, 1);
+        let recalled = runtime
+            .get_last_n_thread_memories_for_cwd(cwd.as_path(), 1)
+            .await
+            .expect("recall memories");
+        assert_eq!(
+            recalled
+                .iter()
+                .map(|memory| memory.thread_id)
+                .collect::<Vec<_>>(),
+            vec![t1]
+        );
+        let recalled = runtime
+            .get_last_n_thread_memories_for_cwd(cwd.as_path(), 2)
+            .await
+            .expect("recall memories");
+        assert_eq!(recalled.len(), 2);
+        assert_eq!(recalled[0].thread_id, t1);
+        assert!(!recalled[1].pinned);
 
         runtime
-            .delete_thread(thread_id)
+            .set_thread_memory_pinned(t2, true)
             .await
-            .expect("delete thread");
+            .expect("pin memory");
+        let recalled = runtime
+            .get_last_n_thread_memories_for_cwd(cwd.as_path(), 1)
+            .await
+            .expect("recall memories");
+        assert_eq!(recalled.len(), 2, "pinned memories exceed n");
+        assert!(recalled.iter().all(|memory| memory.pinned));
 
-        let count_after =
-            sqlx::query("SELECT COUNT(*) AS count FROM thread_memory WHERE thread_id = ?")
-                .bind(thread_id.to_string())
-                .fetch_one(runtime.pool.as_ref())
-                .await
-                .expect("count after delete")
-                .try_get::<i64, _>("count")
-                .expect("count value");
-        assert_eq!(count_after, 0);
+        let upserted = runtime
+            .upsert_thread_memory(t1, "trace-1b", "memory-1b")
+            .await
+            .expect("upsert memory");
+        assert!(upserted.pinned, "upsert keeps the pin");
+
+        let listed = runtime
+            .list_thread_memories_for_cwd(cwd.as_path())
+            .await
+            .expect("list memories");
+        assert_eq!(listed.len(), 3);
         assert_eq!(
-            runtime
-                .get_thread_memory(thread_id)
-                .await
-                .expect("get memory after delete"),
-            None
+            listed
+                .iter()
+                .map(|memory| memory.pinned)
+                .collect::<Vec<_>>(),
+            vec![true, true, false]
         );
 
         let _ = tokio::fs::remove_dir_all(codex_home).await;
     }
 
+    #[tokio::test]
+    async fn edit_and_delete_thread_memory() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        runtime
+            .upsert_thread(&test_thread_metadata(
+                &codex_home,
+                thread_id,
+                codex_home.join("a"),
+            ))
+            .await
+            .expect("upsert thread");
+
+        assert_eq!(
+            runtime
+                .update_thread_memory_summary(thread_id, "edited")
+                .await
+                .expect("edit missing memory"),
+            None
+        );
+        assert_eq!(
+            runtime
+                .set_thread_memory_pinned(thread_id, true)
+                .await
+                .expect("pin missing memory"),
+            None
+        );
+
+        let inserted = runtime
+            .upsert_thread_memory(thread_id, "trace", "memory")
+            .await
+            .expect("upsert memory");
+        let edited = runtime
+            .update_thread_memory_summary(thread_id, "edited")
+            .await
+            .expect("edit memory")
+            .expect("memory exists");
+        assert_eq!(edited.trace_summary, "trace");
+        assert_eq!(edited.memory_summary, "edited");
+        assert!(edited.updated_at > inserted.updated_at);
+
+        assert!(
+            runtime
+                .delete_thread_memory(thread_id)
+                .await
+                .expect("delete memory")
+        );
+        assert!(
+            !runtime
+                .delete_thread_memory(thread_id)
+                .await
+                .expect("delete memory again")
+        );
+        assert_eq!(
+            runtime
+                .get_thread_memory(thread_id)
+                .await
+                .expect("get memory"),
+            None
+        );
+        assert!(
+            runtime
+                .get_thread(thread_id)
+                .await
+                .expect("get thread")
+                .is_some(),
+            "deleting a memory keeps the thread"
+        );
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn message_history_collapses_duplicates_and_scopes_searches() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let repo = codex_home.join("repo");
+        let thread_id = ThreadId::new();
+        let entry = |text: &str, cwd: Option<&Path>| MessageHistoryEntry {
+            thread_id: Some(thread_id),
+            cwd: cwd.map(Path::to_path_buf),
+            text: text.to_string(),
+            ts: DateTime::<Utc>::from_timestamp(1_700_000_000, 0).expect("timestamp"),
+        };
+        runtime
+            .import_message_history(&[entry("legacy", None), entry("legacy", None)])
+            .await
+            .expect("import history");
+        for (text, cwd) in [
+            ("run the tests", repo.join("crates").join("core")),
+            ("run the tests", repo.clone()),
+            ("fix clippy", codex_home.join("repo-other")),
+            ("run the tests", repo.clone()),
+        ] {
+            runtime
+                .append_message_history(&entry(text, Some(&cwd)), None)
+                .await
+                .expect("append history");
+        }
+
+        let (newest_id, count) = runtime
+            .message_history_metadata()
+            .await
+            .expect("history metadata");
+        assert_eq!(count, 4, "consecutive duplicates are collapsed");
+        let texts = |entries: Vec<MessageHistoryEntry>| {
+            entries
+                .into_iter()
+                .map(|entry| entry.text)
+                .collect::<Vec<_>>()
+        };
+        let mut in_order = Vec::new();
+        for offset in 0..count {
+            in_order.push(
+                runtime
+                    .message_history_entry(newest_id, offset)
+                    .await
+                    .expect("history entry")
+                    .expect("entry exists"),
+            );
+        }
+        assert_eq!(
+            texts(in_order.clone()),
+            vec!["legacy", "run the tests", "fix clippy", "run the tests"]
+        );
+        assert_eq!(in_order[1].cwd, Some(repo.clone()));
+
+        // Entries appended later do not shift the offsets of a session.
+        runtime
+            .append_message_history(&entry("newer", Some(&repo)), None)
+            .await
+            .expect("append history");
+        assert_eq!(
+            runtime
+                .message_history_entry(newest_id, count)
+                .await
+                .expect("history entry"),
+            None
+        );
+
+        let all = runtime
+            .search_message_history(None, 10)
+            .await
+            .expect("search history");
+        assert_eq!(
+            texts(all),
+            vec!["newer", "run the tests", "fix clippy", "legacy"]
+        );
+        let scoped = runtime
+            .search_message_history(Some(&repo), 10)
+            .await
+            .expect("search history");
+        assert_eq!(texts(scoped), vec!["newer", "run the tests"]);
+
+        // Trimming keeps the newest entries that fit.
+        runtime
+            .append_message_history(&entry("latest", Some(&repo)), Some(12))
+            .await
+            .expect("append history");
+        let remaining = runtime
+            .search_message_history(None, 10)
+            .await
+            .expect("search history");
+        assert_eq!(texts(remaining), vec!["latest", "newer"]);
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn project_knowledge_is_reviewed_per_repository() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+        let repo = codex_home.join("repo");
+        let other_repo = codex_home.join("other");
+
+        let tests = runtime
+            .record_project_knowledge(&repo, "tests need  `--features sqlite`\n", None)
+            .await
+            .expect("record fact");
+        assert_eq!(tests.content, "tests need `--features sqlite`");
+        assert_eq!(tests.status, KnowledgeStatus::Pending);
+        let vendored = runtime
+            .record_project_knowledge(&repo, "never touch vendored/", None)
+            .await
+            .expect("record fact");
+        runtime
+            .record_project_knowledge(&other_repo, "use pnpm", None)
+            .await
+            .expect("record fact");
+        assert!(
+            runtime
+                .record_project_knowledge(&repo, "  ", None)
+                .await
+                .is_err(),
+            "blank facts are rejected"
+        );
+
+        let approved = runtime
+            .set_project_knowledge_status(tests.id, KnowledgeStatus::Approved)
+            .await
+            .expect("approve fact")
+            .expect("fact exists");
+        assert_eq!(approved.status, KnowledgeStatus::Approved);
+        runtime
+            .set_project_knowledge_status(vendored.id, KnowledgeStatus::Rejected)
+            .await
+            .expect("reject fact");
+
+        // Recording a known fact again keeps its review state.
+        let again = runtime
+            .record_project_knowledge(&repo, "never touch vendored/", None)
+            .await
+            .expect("record fact again");
+        assert_eq!(again.id, vendored.id);
+        assert_eq!(again.status, KnowledgeStatus::Rejected);
+
+        let active = runtime
+            .list_project_knowledge(&repo, Some(KnowledgeStatus::Approved))
+            .await
+            .expect("list approved");
+        assert_eq!(
+            active.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+            vec![tests.id]
+        );
+        let all = runtime
+            .list_project_knowledge(&repo, None)
+            .await
+            .expect("list all");
+        assert_eq!(all.len(), 2);
+
+        assert!(
+            runtime
+                .delete_project_knowledge(vendored.id)
+                .await
+                .expect("delete fact")
+        );
+        assert_eq!(
+            runtime
+                .set_project_knowledge_status(vendored.id, KnowledgeStatus::Approved)
+                .await
+                .expect("approve deleted fact"),
+            None
+        );
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn deleting_thread_cascades_thread_memory() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        let cwd = codex_home.join("workspace");
+        runtime
+            .upsert_thread(&test_thread_metadata(&codex_home, thread_id, cwd))
+            .await
+            .expect("upsert thread");
+        runtime
+            .upsert_thread_memory(thread_id, "trace", "memory")
+            .await
+            .expect("upsert memory");
+
+        let count_before =
+            sqlx::query("SELECT COUNT(*) AS count FROM thread_memory WHERE thread_id = ?")
+                .bind(thread_id.to_string())
+                .fetch_one(runtime.pool.as_ref())
+                .await
+                .expect("count before delete")
+                .try_get::<i64, _>("count")
+                .expect("count value");
+        assert_eq!(count_before, 1);
+
+        runtime
+            .delete_thread(thread_id)
+            .await
+            .expect("delete thread");
+
+        let count_after =
+            sqlx::query("SELECT COUNT(*) AS count FROM thread_memory WHERE thread_id = ?")
+                .bind(thread_id.to_string())
+                .fetch_one(runtime.pool.as_ref())
+                .await
+                .expect("count after delete")
+                .try_get::<i64, _>("count")
+                .expect("count value");
+        assert_eq!(count_after, 0);
+        assert_eq!(
+            runtime
+                .get_thread_memory(thread_id)
+                .await
+                .expect("get memory after delete"),
+            None
+        );
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn search_threads_returns_best_hit_per_thread_with_highlights() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let migration_thread =
+            ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        let other_thread = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        for thread_id in [migration_thread, other_thread] {
+            runtime
+                .upsert_thread(&test_thread_metadata(
+                    &codex_home,
+                    thread_id,
+                    codex_home.join("workspace"),
+                ))
+                .await
+                .expect("upsert thread");
+        }
+        let migration_entries = vec![
+            ThreadSearchEntry {
+                source: ThreadSearchSource::UserMessage,
+                content: "write a sqlite migration for thread search".to_string(),
+            },
+            ThreadSearchEntry {
+                source: ThreadSearchSource::Command,
+                content: "cargo test -p codex-state migrations".to_string(),
+            },
+        ];
+        runtime
+            .insert_thread_search_entries(migration_thread, &migration_entries)
+            .await
+            .expect("insert search entries");
+        // Replaying the same entries must not duplicate them.
+        runtime
+            .insert_thread_search_entries(migration_thread, &migration_entries)
+            .await
+            .expect("reinsert search entries");
+        runtime
+            .insert_thread_search_entries(
+                other_thread,
+                &[ThreadSearchEntry {
+                    source: ThreadSearchSource::AgentMessage,
+                    content: "Updated the README".to_string(),
+                }],
+            )
+            .await
+            .expect("insert other search entries");
+
+        let page = runtime
+            .search_threads("sqlite migr", 10, 0, &[], None, false)
+            .await
+            .expect("search threads");
+        assert_eq!(page.next_offset, None);
+        assert_eq!(
+            page.items
+                .iter()
+                .map(|hit| (hit.thread.id, hit.source, hit.snippet.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(
+                migration_thread,
+                ThreadSearchSource::UserMessage,
+                "write a sqlite migration for thread search",
+            )]
+        );
+        let hit = &page.items[0];
+        assert_eq!(
+            hit.highlights
+                .iter()
+                .map(|range| &hit.snippet[range.clone()])
+                .collect::<Vec<_>>(),
+            vec!["sqlite", "migration"]
+        );
+
+        let page = runtime
+            .search_threads("\"readme", 10, 0, &[], None, false)
+            .await
+            .expect("search with fts syntax");
+        assert_eq!(
+            page.items
+                .iter()
+                .map(|hit| hit.thread.id)
+                .collect::<Vec<_>>(),
+            vec![other_thread]
+        );
+        assert_eq!(
+            runtime
+                .search_threads("  ", 10, 0, &[], None, false)
+                .await
+                .expect("empty search")
+                .items,
+            Vec::new()
+        );
+
+        runtime
+            .delete_thread(migration_thread)
+            .await
+            .expect("delete thread");
+        let page = runtime
+            .search_threads("migration", 10, 0, &[], None, false)
+            .await
+            .expect("search after delete");
+        assert_eq!(page.items, Vec::new());
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn search_threads_paginates_by_offset() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        for _ in 0..3 {
+            let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+            runtime
+                .upsert_thread(&test_thread_metadata(
+                    &codex_home,
+                    thread_id,
+                    codex_home.join("workspace"),
+                ))
+                .await
+                .expect("upsert thread");
+            runtime
+                .insert_thread_search_entries(
+                    thread_id,
+                    &[ThreadSearchEntry {
+                        source: ThreadSearchSource::UserMessage,
+                        content: "refactor the parser".to_string(),
+                    }],
+                )
+                .await
+                .expect("insert search entries");
+        }
+
+        let first = runtime
+            .search_threads("parser", 2, 0, &[], None, false)
+            .await
+            .expect("first page");
+        assert_eq!(first.items.len(), 2);
+        assert_eq!(first.next_offset, Some(2));
+        let second = runtime
+            .search_threads("parser", 2, 2, &[], None, false)
+            .await
+            .expect("second page");
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.next_offset, None);
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn usage_report_groups_turns_per_model() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        let mut metadata = test_thread_metadata(&codex_home, thread_id, codex_home.join("repo"));
+        metadata.git_origin_url = Some("https://github.com/openai/codex.git".to_string());
+        runtime
+            .upsert_thread(&metadata)
+            .await
+            .expect("upsert thread");
+        let other_thread_id =
+            ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        for (thread_id, turn_id, model, input, timestamp) in [
+            (thread_id, "0", "gpt-5", 100, 1_700_000_000),
+            (thread_id, "1", "gpt-5", 50, 1_700_000_100),
+            (thread_id, "2", "gpt-5-mini", 10, 1_700_100_000),
+            (other_thread_id, "0", "gpt-5", 7, 1_700_100_000),
+        ] {
+            runtime
+                .record_turn_usage(&test_turn_usage(
+                    thread_id,
+                    turn_id,
+                    model,
+                    input,
+                    codex_home.join("other"),
+                    timestamp,
+                ))
+                .await
+                .expect("record usage");
+        }
+
+        let by_day = runtime
+            .usage_report(&UsageQuery {
+                group_by: UsageGroupBy::Day,
+                since: None,
+                until: None,
+            })
+            .await
+            .expect("usage by day");
+        assert_eq!(
+            by_day,
+            vec![
+                test_report_row("2023-11-14", "gpt-5", 2, 150),
+                test_report_row("2023-11-16", "gpt-5", 1, 7),
+                test_report_row("2023-11-16", "gpt-5-mini", 1, 10),
+            ]
+        );
+
+        let by_repo = runtime
+            .usage_report(&UsageQuery {
+                group_by: UsageGroupBy::Repo,
+                since: DateTime::<Utc>::from_timestamp(1_700_000_050, 0),
+                until: DateTime::<Utc>::from_timestamp(1_700_200_000, 0),
+            })
+            .await
+            .expect("usage by repo");
+        let other_cwd = codex_home.join("other").display().to_string();
+        let mut expected = vec![
+            test_report_row("https://github.com/openai/codex.git", "gpt-5", 1, 50),
+            test_report_row("https://github.com/openai/codex.git", "gpt-5-mini", 1, 10),
+            test_report_row(other_cwd.as_str(), "gpt-5", 1, 7),
+        ];
+        expected.sort_by(|a, b| a.group.cmp(&b.group).then(a.model.cmp(&b.model)));
+        assert_eq!(by_repo, expected);
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn network_audit_lists_newest_first_with_filters() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        let entry =
+            |host: &str, decision: NetworkAuditDecision, timestamp: i64| NetworkAuditEntry {
+                ts: DateTime::<Utc>::from_timestamp(timestamp, 0).expect("timestamp"),
+                thread_id: Some(thread_id),
+                turn_id: Some("turn-1".to_string()),
+                call_id: Some("call-1".to_string()),
+                protocol: "http".to_string(),
+                host: host.to_string(),
+                method: Some("GET".to_string()),
+                decision,
+                reason: (decision == NetworkAuditDecision::Deny).then(|| "denied".to_string()),
+                bytes_sent: Some(12),
+                bytes_received: Some(34),
+            };
+        let first = entry("example.com", NetworkAuditDecision::Allow, 1_700_000_000);
+        let second = entry("evil.example", NetworkAuditDecision::Deny, 1_700_000_100);
+        let mut third = entry("example.com", NetworkAuditDecision::Allow, 1_700_000_200);
+        third.thread_id = None;
+        for entry in [&first, &second, &third] {
+            runtime
+                .append_network_audit(entry)
+                .await
+                .expect("append network audit");
+        }
+
+        let all = runtime
+            .list_network_audit(&NetworkAuditQuery {
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .expect("list all");
+        assert_eq!(all, vec![third.clone(), second.clone(), first.clone()]);
+
+        let for_thread = runtime
+            .list_network_audit(&NetworkAuditQuery {
+                thread_id: Some(thread_id),
+                host: Some("Example.com".to_string()),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .expect("list for thread");
+        assert_eq!(for_thread, vec![first]);
+
+        let denied = runtime
+            .list_network_audit(&NetworkAuditQuery {
+                decision: Some(NetworkAuditDecision::Deny),
+                since: DateTime::<Utc>::from_timestamp(1_700_000_050, 0),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .expect("list denied");
+        assert_eq!(denied, vec![second]);
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn backfill_thread_usage_adds_each_turn_once() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        // The third turn was recorded live when it finished, a minute after
+        // the last token count its rollout line carries.
+        runtime
+            .record_turn_usage(&test_turn_usage(
+                thread_id,
+                "3",
+                "gpt-5",
+                1_000,
+                codex_home.clone(),
+                1_700_000_260,
+            ))
+            .await
+            .expect("record live turn");
+
+        let backfilled = |turn_id: &str, input_tokens, timestamp| {
+            test_turn_usage(
+                thread_id,
+                turn_id,
+                "gpt-5",
+                input_tokens,
+                codex_home.clone(),
+                timestamp,
+            )
+        };
+        let usage = vec![
+            // Written before rollouts recorded turn ids.
+            backfilled("rollout-turn-1", 100, 1_700_000_000),
+            backfilled("2", 10, 1_700_000_100),
+            backfilled("3", 1_000, 1_700_000_200),
+        ];
+        assert!(
+            runtime
+                .backfill_thread_usage(thread_id, &usage[..1])
+                .await
+                .expect("first backfill")
+        );
+        assert!(
+            runtime
+                .backfill_thread_usage(thread_id, &usage)
+                .await
+                .expect("second backfill")
+        );
+        assert!(
+            !runtime
+                .backfill_thread_usage(thread_id, &usage)
+                .await
+                .expect("third backfill")
+        );
+
+        let by_thread = runtime
+            .usage_report(&UsageQuery {
+                group_by: UsageGroupBy::Thread,
+                since: None,
+                until: None,
+            })
+            .await
+            .expect("usage by thread");
+        assert_eq!(
+            by_thread,
+            vec![test_report_row(
+                thread_id.to_string().as_str(),
+                "gpt-5",
+                3,
+                1_110
+            )]
+        );
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    fn test_turn_usage(
+        thread_id: ThreadId,
+        turn_id: &str,
+        model: &str,
+        input_tokens: i64,
+        cwd: PathBuf,
+        timestamp: i64,
+    ) -> TurnUsage {
+        TurnUsage {
+            thread_id,
+            turn_id: turn_id.to_string(),
+            model: model.to_string(),
+            model_provider: "test-provider".to_string(),
+            cwd,
+            input_tokens,
+            cached_input_tokens: input_tokens / 2,
+            output_tokens: 1,
+            reasoning_output_tokens: 0,
+            recorded_at: DateTime::<Utc>::from_timestamp(timestamp, 0).expect("timestamp"),
+        }
+    }
+
+    #[tokio::test]
+    async fn thread_organization_survives_metadata_upserts() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+        let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        let metadata = test_thread_metadata(&codex_home, thread_id, codex_home.join("workspace"));
+        runtime
+            .upsert_thread(&metadata)
+            .await
+            .expect("upsert thread");
+
+        runtime
+            .set_thread_tags(thread_id, &["infra", " bug ", "infra"])
+            .await
+            .expect("set tags");
+        runtime
+            .set_thread_pinned(thread_id, true)
+            .await
+            .expect("set pinned");
+        let organization = runtime
+            .set_thread_folder(thread_id, Some("/clients/ acme/"))
+            .await
+            .expect("set folder");
+        let expected = ThreadOrganization {
+            tags: vec!["bug".to_string(), "infra".to_string()],
+            pinned: true,
+            folder: Some("clients/acme".to_string()),
+        };
+        assert_eq!(organization, Some(expected.clone()));
+
+        runtime
+            .upsert_thread(&metadata)
+            .await
+            .expect("upsert thread again");
+        let organizations = runtime
+            .get_thread_organizations(&[thread_id])
+            .await
+            .expect("get organizations");
+        assert_eq!(organizations.get(&thread_id), Some(&expected));
+
+        let unknown = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        let missing = runtime
+            .set_thread_pinned(unknown, true)
+            .await
+            .expect("set pinned on unknown thread");
+        assert_eq!(missing, None);
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    #[tokio::test]
+    async fn list_threads_filters_by_tags_pin_and_folder() {
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        let mut thread_ids = Vec::new();
+        for (tags, pinned, folder) in [
+            (vec!["infra", "bug"], true, Some("clients/acme")),
+            (vec!["infra"], false, Some("clients/acme-labs")),
+            (vec!["bug"], false, Some("clients")),
+        ] {
+            let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+            runtime
+                .upsert_thread(&test_thread_metadata(
+                    &codex_home,
+                    thread_id,
+                    codex_home.join("workspace"),
+                ))
+                .await
+                .expect("upsert thread");
+            runtime
+                .set_thread_tags(thread_id, &tags)
+                .await
+                .expect("set tags");
+            runtime
+                .set_thread_pinned(thread_id, pinned)
+                .await
+                .expect("set pinned");
+            runtime
+                .set_thread_folder(thread_id, folder)
+                .await
+                .expect("set folder");
+            thread_ids.push(thread_id);
+        }
+
+        let list = |organization: ThreadOrganizationFilter| {
+            let runtime = runtime.clone();
+            async move {
+                let mut ids = runtime
+                    .list_threads(
+                        10,
+                        None,
+                        SortKey::CreatedAt,
+                        &[],
+                        None,
+                        false,
+                        &organization,
+                    )
+                    .await
+                    .expect("list threads")
+                    .items
+                    .into_iter()
+                    .map(|item| item.id)
+                    .collect::<Vec<_>>();
+                ids.sort_by_key(ToString::to_string);
+                ids
+            }
+        };
+        let sorted = |mut ids: Vec<ThreadId>| {
+            ids.sort_by_key(ToString::to_string);
+            ids
+        };
+
+        assert_eq!(
+            list(ThreadOrganizationFilter {
+                tags: vec!["infra".to_string(), "bug".to_string()],
+                ..Default::default()
+            })
+            .await,
+            vec![thread_ids[0]]
+        );
+        assert_eq!(
+            list(ThreadOrganizationFilter {
+                pinned: Some(false),
+                ..Default::default()
+            })
+            .await,
+            sorted(vec![thread_ids[1], thread_ids[2]])
+        );
+        assert_eq!(
+            list(ThreadOrganizationFilter {
+                folder: Some("clients/acme".to_string()),
+                ..Default::default()
+            })
+            .await,
+            vec![thread_ids[0]]
+        );
+        assert_eq!(
+            list(ThreadOrganizationFilter {
+                folder: Some("clients".to_string()),
+                ..Default::default()
+            })
+            .await,
+            sorted(thread_ids.clone())
+        );
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
+    fn test_log_entry(
+        level: &str,
+        message: &str,
+        thread_id: Option<&str>,
+        turn_id: Option<&str>,
+    ) -> LogEntry {
+        LogEntry {
+            ts: 1_700_000_000,
+            ts_nanos: 0,
+            level: level.to_string(),
+            target: "codex_core::exec".to_string(),
+            message: Some(message.to_string()),
+            thread_id: thread_id.map(str::to_string),
+            turn_id: turn_id.map(str::to_string),
+            module_path: Some("codex_core::exec".to_string()),
+            file: Some("core/src/exec.rs".to_string()),
+            line: Some(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn query_logs_applies_filter_expressions() -> anyhow::Result<()> {
+        let codex_home = unique_temp_dir();
+        let runtime =
+            StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None).await?;
+        runtime
+            .insert_logs(&[
+                test_log_entry("DEBUG", "spawned", Some("thread-a"), Some("turn-1")),
+                test_log_entry("WARN", "slow command", Some("thread-a"), Some("turn-2")),
+                test_log_entry("ERROR", "timed out", Some("thread-b"), None),
+                test_log_entry("INFO", "startup", None, None),
+            ])
+            .await?;
+
+        let messages = |filter: &str| {
+            let query = LogQuery {
+                filter: Some(filter.parse().expect("valid filter")),
+                ..Default::default()
+            };
+            let runtime = runtime.clone();
+            async move {
+                runtime
+                    .query_logs(&query)
+                    .await
+                    .expect("query logs")
+                    .into_iter()
+                    .filter_map(|row| row.message)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            messages("level >= warn").await,
+            vec!["slow command", "timed out"]
+        );
+        assert_eq!(
+            messages("thread = thread-a and not turn = turn-1").await,
+            vec!["slow command"]
+        );
+        assert_eq!(
+            messages("turn = none and (level < warn or message ~ out)").await,
+            vec!["timed out", "startup"]
+        );
+        assert_eq!(
+            messages("thread != thread-a").await,
+            vec!["timed out", "startup"]
+        );
+
+        let rows = runtime.query_logs(&LogQuery::default()).await?;
+        assert_eq!(rows[0].turn_id.as_deref(), Some("turn-1"));
+        assert_eq!(rows[0].module_path.as_deref(), Some("codex_core::exec"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_logs_exceeding_bytes_keeps_newest_rows() -> anyhow::Result<()> {
+        let codex_home = unique_temp_dir();
+        let runtime =
+            StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None).await?;
+        let entries = (0..10)
+            .map(|idx| test_log_entry("INFO", &format!("{idx:0>100}"), None, None))
+            .collect::<Vec<_>>();
+        runtime.insert_logs(&entries).await?;
+        let row_bytes = "INFO".len()
+            + "codex_core::exec".len() * 2
+            + "core/src/exec.rs".len()
+            + 100
+            + super::LOG_ROW_OVERHEAD_BYTES as usize;
+
+        let deleted = runtime
+            .delete_logs_exceeding_bytes((row_bytes * 3 + row_bytes / 2) as u64)
+            .await?;
+
+        assert_eq!(deleted, 7);
+        let remaining = runtime
+            .query_logs(&LogQuery::default())
+            .await?
+            .into_iter()
+            .map(|row| row.id)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, vec![8, 9, 10]);
+        assert_eq!(runtime.delete_logs_exceeding_bytes(u64::MAX).await?, 0);
+        Ok(())
+    }
+
+    fn test_report_row(group: &str, model: &str, turns: i64, input_tokens: i64) -> UsageReportRow {
+        UsageReportRow {
+            group: group.to_string(),
+            model: model.to_string(),
+            turns,
+            input_tokens,
+            cached_input_tokens: input_tokens / 2,
+            output_tokens: turns,
+            reasoning_output_tokens: 0,
+        }
+    }
+
     fn test_thread_metadata(
         codex_home: &Path,
         thread_id: ThreadId,
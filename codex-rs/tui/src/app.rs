@@ -1356,6 +1356,7 @@ impl App {
             app.auth_manager.clone(),
             app.server.clone(),
             app.cli_kv_overrides.clone(),
+            app.app_event_tx.clone(),
         )
         .await;
 
@@ -1868,6 +1869,9 @@ impl App {
                 return Ok(AppRunControl::Exit(ExitReason::Fatal(message)));
             }
             AppEvent::CodexOp(op) => {
+                if let Some(bridge) = self.menubar_bridge.as_mut() {
+                    bridge.observe_op(&op);
+                }
                 self.chat_widget.submit_op(op);
             }
             AppEvent::OpenRenameThreadPrompt => {
@@ -1941,6 +1945,40 @@ impl App {
             AppEvent::ConnectorsLoaded(result) => {
                 self.chat_widget.on_connectors_loaded(result);
             }
+            AppEvent::MemoriesLoaded(result) => {
+                self.chat_widget.on_memories_loaded(result);
+            }
+            AppEvent::OpenMemoryActions(memory) => {
+                self.chat_widget.open_memory_actions(memory);
+            }
+            AppEvent::OpenMemoryEditor(memory) => {
+                self.chat_widget.open_memory_editor(memory);
+            }
+            AppEvent::UpdateMemory { thread_id, update } => {
+                self.chat_widget.update_memory(thread_id, update);
+            }
+            AppEvent::MemoryUpdated(result) => {
+                self.chat_widget.on_memory_updated(result);
+            }
+            AppEvent::ProjectKnowledgeLoaded(result) => {
+                self.chat_widget.on_project_knowledge_loaded(result);
+            }
+            AppEvent::OpenProjectKnowledgeActions(entry) => {
+                self.chat_widget.open_project_knowledge_actions(entry);
+            }
+            AppEvent::UpdateProjectKnowledge { id, update } => {
+                self.chat_widget.update_project_knowledge(id, update);
+            }
+            AppEvent::ProjectKnowledgeUpdated(result) => {
+                self.chat_widget.on_project_knowledge_updated(result);
+            }
+            AppEvent::MessageHistoryLoaded(result) => {
+                self.chat_widget.on_message_history_loaded(result);
+            }
+            AppEvent::RecallHistoryEntry(text) => {
+                self.chat_widget
+                    .set_composer_text(text, Vec::new(), Vec::new());
+            }
             AppEvent::UpdateReasoningEffort(effort) => {
                 self.on_update_reasoning_effort(effort);
                 self.refresh_status_line();
@@ -2611,6 +2649,9 @@ impl App {
             AppEvent::ManageSkillsClosed => {
                 self.chat_widget.handle_manage_skills_closed();
             }
+            AppEvent::DismissApprovalRequest { id } => {
+                self.chat_widget.dismiss_approval_request(&id);
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch {
                     cwd,
@@ -2634,6 +2675,15 @@ impl App {
                         "E X E C".to_string(),
                     ));
                 }
+                ApprovalRequest::Network { command, .. } => {
+                    let _ = tui.enter_alt_screen();
+                    let full_cmd = strip_bash_lc_and_escape(&command);
+                    let full_cmd_lines = highlight_bash_to_lines(&full_cmd);
+                    self.overlay = Some(Overlay::new_static_with_lines(
+                        full_cmd_lines,
+                        "N E T W O R K".to_string(),
+                    ));
+                }
                 ApprovalRequest::McpElicitation {
                     server_name,
                     message,
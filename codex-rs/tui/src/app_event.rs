@@ -14,11 +14,15 @@ use codex_chatgpt::connectors::AppInfo;
 use codex_common::approval_presets::ApprovalPreset;
 use codex_core::protocol::Event;
 use codex_core::protocol::RateLimitSnapshot;
+use codex_core::state_db::KnowledgeStatus;
+use codex_core::state_db::ProjectKnowledge;
+use codex_core::state_db::ThreadMemory;
 use codex_file_search::FileMatch;
 use codex_protocol::ThreadId;
 use codex_protocol::openai_models::ModelPreset;
 
 use crate::bottom_pane::ApprovalRequest;
+use crate::bottom_pane::HistorySearchResults;
 use crate::bottom_pane::StatusLineItem;
 use crate::get_git_diff::GitDiffResult;
 use crate::history_cell::HistoryCell;
@@ -152,6 +156,47 @@ pub(crate) enum AppEvent {
     /// Result of computing a `/diff` command.
     DiffResult(GitDiffResult),
 
+    /// Result of loading the memories for the `/memory` view.
+    MemoriesLoaded(Result<Vec<ThreadMemory>, String>),
+
+    /// Show the actions available for one memory.
+    OpenMemoryActions(ThreadMemory),
+
+    /// Open the editor for a memory's summary.
+    OpenMemoryEditor(ThreadMemory),
+
+    /// Apply a change to a stored memory, then reopen the `/memory` view.
+    UpdateMemory {
+        thread_id: ThreadId,
+        update: MemoryUpdate,
+    },
+
+    /// Result of [`AppEvent::UpdateMemory`]: a confirmation or an error message.
+    MemoryUpdated(Result<String, String>),
+
+    /// Result of loading the project knowledge for the `/knowledge` view.
+    ProjectKnowledgeLoaded(Result<Vec<ProjectKnowledge>, String>),
+
+    /// Show the actions available for one knowledge entry.
+    OpenProjectKnowledgeActions(ProjectKnowledge),
+
+    /// Approve, reject or delete a knowledge entry, then reopen the
+    /// `/knowledge` view.
+    UpdateProjectKnowledge {
+        id: i64,
+        update: KnowledgeUpdate,
+    },
+
+    /// Result of [`AppEvent::UpdateProjectKnowledge`]: a confirmation or an
+    /// error message.
+    ProjectKnowledgeUpdated(Result<String, String>),
+
+    /// Result of loading the message history for the Ctrl+R search.
+    MessageHistoryLoaded(Result<HistorySearchResults, String>),
+
+    /// Put a message picked in the history search into the composer.
+    RecallHistoryEntry(String),
+
     /// Export the current chat in the selected format.
     ExportChat {
         format: Option<ChatExportFormat>,
@@ -441,6 +486,11 @@ pub(crate) enum AppEvent {
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
+    /// Close the approval prompt for `id`, which was answered from the menu bar.
+    DismissApprovalRequest {
+        id: String,
+    },
+
     /// Open the feedback note entry overlay after the user selects a category.
     OpenFeedbackNote {
         category: FeedbackCategory,
@@ -475,6 +525,21 @@ pub(crate) enum AppEvent {
     },
 }
 
+/// A change made to a memory from the `/memory` view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MemoryUpdate {
+    SetSummary(String),
+    SetPinned(bool),
+    Delete,
+}
+
+/// A change made to a knowledge entry from the `/knowledge` view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KnowledgeUpdate {
+    SetStatus(KnowledgeStatus),
+    Delete,
+}
+
 /// The exit strategy requested by the UI layer.
 ///
 /// Most user-initiated exits should use `ShutdownFirst` so core cleanup runs and the UI exits only
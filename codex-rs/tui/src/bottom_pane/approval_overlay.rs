@@ -22,6 +22,7 @@ use codex_core::features::Features;
 use codex_core::protocol::ElicitationAction;
 use codex_core::protocol::ExecPolicyAmendment;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::NetworkPolicyAmendment;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_protocol::mcp::RequestId;
@@ -46,6 +47,14 @@ pub(crate) enum ApprovalRequest {
         reason: Option<String>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     },
+    Network {
+        id: String,
+        host: String,
+        port: u16,
+        method: Option<String>,
+        command: Vec<String>,
+        proposed_network_policy_amendment: NetworkPolicyAmendment,
+    },
     ApplyPatch {
         id: String,
         reason: Option<String>,
@@ -60,6 +69,18 @@ pub(crate) enum ApprovalRequest {
     },
 }
 
+impl ApprovalRequest {
+    /// The id the decision is submitted under; elicitations have none.
+    fn approval_id(&self) -> Option<&str> {
+        match self {
+            ApprovalRequest::Exec { id, .. }
+            | ApprovalRequest::Network { id, .. }
+            | ApprovalRequest::ApplyPatch { id, .. } => Some(id),
+            ApprovalRequest::McpElicitation { .. } => None,
+        }
+    }
+}
+
 /// Modal overlay asking the user to approve or deny one or more requests.
 pub(crate) struct ApprovalOverlay {
     current_request: Option<ApprovalRequest>,
@@ -117,6 +138,14 @@ impl ApprovalOverlay {
                 exec_options(proposed_execpolicy_amendment.clone(), features),
                 "Would you like to run the following command?".to_string(),
             ),
+            ApprovalVariant::Network {
+                host,
+                proposed_network_policy_amendment,
+                ..
+            } => (
+                network_options(host, proposed_network_policy_amendment.clone()),
+                format!("Would you like to allow network access to {host}?"),
+            ),
             ApprovalVariant::ApplyPatch { .. } => (
                 patch_options(),
                 "Would you like to make the following edits?".to_string(),
@@ -173,6 +202,9 @@ impl ApprovalOverlay {
                 (ApprovalVariant::Exec { id, command, .. }, ApprovalDecision::Review(decision)) => {
                     self.handle_exec_decision(id, command, decision.clone());
                 }
+                (ApprovalVariant::Network { id, host, .. }, ApprovalDecision::Review(decision)) => {
+                    self.handle_network_decision(id, host, decision.clone());
+                }
                 (ApprovalVariant::ApplyPatch { id, .. }, ApprovalDecision::Review(decision)) => {
                     self.handle_patch_decision(id, decision.clone());
                 }
@@ -202,6 +234,17 @@ impl ApprovalOverlay {
         }));
     }
 
+    fn handle_network_decision(&self, id: &str, host: &str, decision: ReviewDecision) {
+        let cell =
+            history_cell::new_network_approval_decision_cell(host.to_string(), decision.clone());
+        self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::NetworkApproval {
+                id: id.to_string(),
+                decision,
+            }));
+    }
+
     fn handle_patch_decision(&self, id: &str, decision: ReviewDecision) {
         self.app_event_tx.send(AppEvent::CodexOp(Op::PatchApproval {
             id: id.to_string(),
@@ -285,6 +328,9 @@ impl BottomPaneView for ApprovalOverlay {
                 ApprovalVariant::Exec { id, command, .. } => {
                     self.handle_exec_decision(id, command, ReviewDecision::Abort);
                 }
+                ApprovalVariant::Network { id, host, .. } => {
+                    self.handle_network_decision(id, host, ReviewDecision::Abort);
+                }
                 ApprovalVariant::ApplyPatch { id, .. } => {
                     self.handle_patch_decision(id, ReviewDecision::Abort);
                 }
@@ -316,6 +362,24 @@ impl BottomPaneView for ApprovalOverlay {
         self.enqueue_request(request);
         None
     }
+
+    fn dismiss_approval_request(&mut self, id: &str) -> bool {
+        let queued = self.queue.len();
+        self.queue
+            .retain(|request| request.approval_id() != Some(id));
+        let mut dismissed = self.queue.len() != queued;
+        if !self.current_complete
+            && self
+                .current_request
+                .as_ref()
+                .is_some_and(|request| request.approval_id() == Some(id))
+        {
+            self.current_complete = true;
+            self.advance_queue();
+            dismissed = true;
+        }
+        dismissed
+    }
 }
 
 impl Renderable for ApprovalOverlay {
@@ -366,6 +430,40 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
                 }
             }
+            ApprovalRequest::Network {
+                id,
+                host,
+                port,
+                method,
+                command,
+                proposed_network_policy_amendment,
+            } => {
+                let target = match method {
+                    Some(method) => format!("{method} {host}:{port}"),
+                    None => format!("{host}:{port}"),
+                };
+                let mut header: Vec<Line<'static>> = vec![
+                    Line::from(vec![
+                        "Blocked by the network allowlist: ".into(),
+                        target.bold(),
+                    ]),
+                    Line::from(""),
+                ];
+                let full_cmd = strip_bash_lc_and_escape(&command);
+                let mut full_cmd_lines = highlight_bash_to_lines(&full_cmd);
+                if let Some(first) = full_cmd_lines.first_mut() {
+                    first.spans.insert(0, Span::from("$ "));
+                }
+                header.extend(full_cmd_lines);
+                Self {
+                    variant: ApprovalVariant::Network {
+                        id,
+                        host,
+                        proposed_network_policy_amendment,
+                    },
+                    header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
+                }
+            }
             ApprovalRequest::ApplyPatch {
                 id,
                 reason,
@@ -419,6 +517,11 @@ enum ApprovalVariant {
         command: Vec<String>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     },
+    Network {
+        id: String,
+        host: String,
+        proposed_network_policy_amendment: NetworkPolicyAmendment,
+    },
     ApplyPatch {
         id: String,
     },
@@ -493,6 +596,46 @@ fn exec_options(
     .collect()
 }
 
+fn network_options(
+    host: &str,
+    proposed_network_policy_amendment: NetworkPolicyAmendment,
+) -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, allow this time".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
+        ApprovalOption {
+            label: format!("Yes, allow `{host}` for this session"),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: format!("Yes, and always allow `{host}`"),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedNetworkPolicyAmendment {
+                proposed_network_policy_amendment,
+            }),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+        },
+        ApprovalOption {
+            label: "No, keep it blocked".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Denied),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('d'))],
+        },
+        ApprovalOption {
+            label: "No, and tell Codex what to do differently".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Abort),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+        },
+    ]
+}
+
 fn patch_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
@@ -566,6 +709,35 @@ mod tests {
         assert!(view.is_complete());
     }
 
+    #[test]
+    fn dismissing_a_remotely_answered_request_sends_no_decision() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let queued = ApprovalRequest::ApplyPatch {
+            id: "patch".to_string(),
+            reason: None,
+            cwd: PathBuf::from("/repo"),
+            changes: HashMap::new(),
+            diff_view: DiffView::default(),
+        };
+        view.enqueue_request(queued);
+
+        assert!(!view.dismiss_approval_request("unknown"));
+        assert!(view.dismiss_approval_request("test"));
+        assert_eq!(
+            view.current_request
+                .as_ref()
+                .and_then(ApprovalRequest::approval_id),
+            Some("patch")
+        );
+        assert!(!view.is_complete());
+
+        assert!(view.dismiss_approval_request("patch"));
+        assert!(view.is_complete());
+        assert!(rx.try_recv().is_err(), "dismissal must not emit a decision");
+    }
+
     #[test]
     fn shortcut_triggers_selection() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -648,6 +820,45 @@ mod tests {
         assert!(rx.try_recv().is_err());
     }
 
+    #[test]
+    fn network_always_allow_option_emits_network_policy_amendment() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(
+            ApprovalRequest::Network {
+                id: "test".to_string(),
+                host: "pypi.org".to_string(),
+                port: 443,
+                method: Some("CONNECT".to_string()),
+                command: vec!["pip".to_string(), "install".to_string()],
+                proposed_network_policy_amendment: NetworkPolicyAmendment::new(
+                    "pypi.org".to_string(),
+                ),
+            },
+            tx,
+            Features::with_defaults(),
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        let mut saw_op = false;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::NetworkApproval { id, decision }) = ev {
+                assert_eq!(id, "test");
+                assert_eq!(
+                    decision,
+                    ReviewDecision::ApprovedNetworkPolicyAmendment {
+                        proposed_network_policy_amendment: NetworkPolicyAmendment::new(
+                            "pypi.org".to_string()
+                        ),
+                    }
+                );
+                saw_op = true;
+                break;
+            }
+        }
+        assert!(saw_op, "expected network approval to emit an op");
+        assert!(view.is_complete());
+    }
+
     #[test]
     fn header_includes_command_snippet() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
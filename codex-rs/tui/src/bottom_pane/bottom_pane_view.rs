@@ -58,6 +58,12 @@ pub(crate) trait BottomPaneView: Renderable {
         Some(request)
     }
 
+    /// Drop the approval request `id` after it was answered elsewhere; return
+    /// true if the view was showing or queueing it.
+    fn dismiss_approval_request(&mut self, _id: &str) -> bool {
+        false
+    }
+
     /// Try to handle request_user_input; return the original value if not
     /// consumed.
     fn try_consume_user_input_request(
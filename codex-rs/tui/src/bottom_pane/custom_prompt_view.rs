@@ -54,6 +54,13 @@ impl CustomPromptView {
             complete: false,
         }
     }
+
+    /// Prefill the input, e.g. with the current value of the text being edited.
+    pub(crate) fn with_initial_text(mut self, text: &str) -> Self {
+        self.textarea.set_text_clearing_elements(text);
+        self.textarea.set_cursor(text.len());
+        self
+    }
 }
 
 impl BottomPaneView for CustomPromptView {
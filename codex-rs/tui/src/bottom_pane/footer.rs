@@ -749,7 +749,7 @@ fn shortcut_overlay_lines(state: ShortcutsState) -> Vec<Line<'static>> {
         .copy_last_output
         .first()
         .copied()
-        .unwrap_or_else(|| key_hint::ctrl(KeyCode::Char('r')));
+        .unwrap_or_else(|| key_hint::plain(KeyCode::F(8)));
     let copy_last_output = Line::from(vec![
         copy_last_output_key.into(),
         " to copy last output".into(),
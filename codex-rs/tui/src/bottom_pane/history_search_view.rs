@@ -0,0 +1,447 @@
+//! Ctrl+R search over the message history of every session.
+//!
+//! Entries are matched fuzzily against the query and shown best match first,
+//! most recent first among equal matches. Tab switches between messages
+//! submitted in the current repository and all messages.
+
+use chrono::DateTime;
+use chrono::Utc;
+use codex_common::fuzzy_match::fuzzy_match;
+use codex_core::state_db::MessageHistoryEntry;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Block;
+use ratatui::widgets::Widget;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::key_hint;
+use crate::render::Insets;
+use crate::render::RectExt as _;
+use crate::render::renderable::Renderable;
+use crate::style::user_message_style;
+
+use super::CancellationEvent;
+use super::bottom_pane_view::BottomPaneView;
+use super::popup_consts::MAX_POPUP_ROWS;
+use super::scroll_state::ScrollState;
+use super::selection_popup_common::GenericDisplayRow;
+use super::selection_popup_common::render_rows_single_line;
+
+const SEARCH_PLACEHOLDER: &str = "Type to search history";
+const SEARCH_PROMPT_PREFIX: &str = "> ";
+
+/// Messages loaded for the history search, most recent first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct HistorySearchResults {
+    /// Messages submitted in the current repository.
+    pub(crate) repo: Vec<MessageHistoryEntry>,
+    /// Messages submitted anywhere.
+    pub(crate) all: Vec<MessageHistoryEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryScope {
+    Repo,
+    All,
+}
+
+struct HistoryMatch {
+    /// Index into the entries of the current scope.
+    idx: usize,
+    /// The message on a single line, as shown and matched.
+    text: String,
+    match_indices: Option<Vec<usize>>,
+}
+
+pub(crate) struct HistorySearchView {
+    results: HistorySearchResults,
+    scope: HistoryScope,
+    query: String,
+    matches: Vec<HistoryMatch>,
+    state: ScrollState,
+    complete: bool,
+    app_event_tx: AppEventSender,
+}
+
+impl HistorySearchView {
+    pub(crate) fn new(results: HistorySearchResults, app_event_tx: AppEventSender) -> Self {
+        let scope = if results.repo.is_empty() {
+            HistoryScope::All
+        } else {
+            HistoryScope::Repo
+        };
+        let mut view = Self {
+            results,
+            scope,
+            query: String::new(),
+            matches: Vec::new(),
+            state: ScrollState::new(),
+            complete: false,
+            app_event_tx,
+        };
+        view.apply_filter();
+        view
+    }
+
+    fn entries(&self) -> &[MessageHistoryEntry] {
+        match self.scope {
+            HistoryScope::Repo => &self.results.repo,
+            HistoryScope::All => &self.results.all,
+        }
+    }
+
+    fn apply_filter(&mut self) {
+        let filter = self.query.trim();
+        let mut matches = Vec::new();
+        for (idx, entry) in self.entries().iter().enumerate() {
+            let text = single_line(&entry.text);
+            if filter.is_empty() {
+                matches.push((
+                    HistoryMatch {
+                        idx,
+                        text,
+                        match_indices: None,
+                    },
+                    0,
+                ));
+            } else if let Some((indices, score)) = fuzzy_match(&text, filter) {
+                matches.push((
+                    HistoryMatch {
+                        idx,
+                        text,
+                        match_indices: Some(indices),
+                    },
+                    score,
+                ));
+            }
+        }
+        // The sort is stable, so equal matches keep their recency order.
+        matches.sort_by_key(|(_, score)| *score);
+        self.matches = matches.into_iter().map(|(m, _score)| m).collect();
+
+        let len = self.matches.len();
+        self.state.selected_idx = (len > 0).then_some(0);
+        self.state.scroll_top = 0;
+        self.state.ensure_visible(len, MAX_POPUP_ROWS.min(len));
+    }
+
+    fn toggle_scope(&mut self) {
+        self.scope = match self.scope {
+            HistoryScope::Repo => HistoryScope::All,
+            HistoryScope::All => HistoryScope::Repo,
+        };
+        self.apply_filter();
+    }
+
+    fn move_up(&mut self) {
+        let len = self.matches.len();
+        self.state.move_up_wrap(len);
+        self.state.ensure_visible(len, MAX_POPUP_ROWS.min(len));
+    }
+
+    fn move_down(&mut self) {
+        let len = self.matches.len();
+        self.state.move_down_wrap(len);
+        self.state.ensure_visible(len, MAX_POPUP_ROWS.min(len));
+    }
+
+    fn accept(&mut self) {
+        let selected = self
+            .state
+            .selected_idx
+            .and_then(|idx| self.matches.get(idx))
+            .and_then(|m| self.entries().get(m.idx));
+        if let Some(entry) = selected {
+            self.app_event_tx
+                .send(AppEvent::RecallHistoryEntry(entry.text.clone()));
+        }
+        self.complete = true;
+    }
+
+    fn build_rows(&self) -> Vec<GenericDisplayRow> {
+        let entries = self.entries();
+        self.matches
+            .iter()
+            .map(|m| GenericDisplayRow {
+                // The age goes first so that long messages cannot push it out
+                // of view.
+                name_prefix: entries
+                    .get(m.idx)
+                    .map(|entry| format!("{:<10}", human_time_ago(entry.ts)).dim()),
+                name: m.text.clone(),
+                match_indices: m.match_indices.clone(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn scope_line(&self) -> Line<'static> {
+        let (current, other) = match self.scope {
+            HistoryScope::Repo => ("This repository", "all messages"),
+            HistoryScope::All => ("All messages", "this repository"),
+        };
+        Line::from(vec![
+            current.into(),
+            " · ".dim(),
+            key_hint::plain(KeyCode::Tab).into(),
+            format!(" for {other}").dim(),
+        ])
+    }
+
+    fn rows_height(&self) -> u16 {
+        self.matches
+            .len()
+            .clamp(1, MAX_POPUP_ROWS)
+            .try_into()
+            .unwrap_or(1)
+    }
+}
+
+impl BottomPaneView for HistorySearchView {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        match key_event {
+            KeyEvent {
+                code: KeyCode::Up, ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.move_up(),
+            KeyEvent {
+                code: KeyCode::Down,
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            }
+            // Like in shells, pressing Ctrl+R again moves to the next match.
+            | KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.move_down(),
+            KeyEvent {
+                code: KeyCode::Tab, ..
+            } => self.toggle_scope(),
+            KeyEvent {
+                code: KeyCode::Backspace,
+                ..
+            } => {
+                self.query.pop();
+                self.apply_filter();
+            }
+            KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            } => self.accept(),
+            KeyEvent {
+                code: KeyCode::Esc, ..
+            } => {
+                self.on_ctrl_c();
+            }
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                ..
+            } if !modifiers.contains(KeyModifiers::CONTROL)
+                && !modifiers.contains(KeyModifiers::ALT) =>
+            {
+                self.query.push(c);
+                self.apply_filter();
+            }
+            _ => {}
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn on_ctrl_c(&mut self) -> CancellationEvent {
+        self.complete = true;
+        CancellationEvent::Handled
+    }
+
+    fn handle_paste(&mut self, pasted: String) -> bool {
+        self.query.push_str(&single_line(&pasted));
+        self.apply_filter();
+        true
+    }
+}
+
+impl Renderable for HistorySearchView {
+    fn desired_height(&self, _width: u16) -> u16 {
+        // Title, scope, spacer, search prompt (2), rows, padding (2) and footer.
+        self.rows_height().saturating_add(8)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        // Reserve the footer line for the key-hint row.
+        let [content_area, footer_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+
+        Block::default()
+            .style(user_message_style())
+            .render(content_area, buf);
+
+        let [title_area, scope_area, _, search_area, list_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Max(1),
+            Constraint::Length(2),
+            Constraint::Length(self.rows_height()),
+        ])
+        .areas(content_area.inset(Insets::vh(1, 2)));
+
+        Line::from("Search history".bold()).render(title_area, buf);
+        self.scope_line().render(scope_area, buf);
+
+        // Render the search prompt as two lines to mimic the composer.
+        if search_area.height >= 2 {
+            let [placeholder_area, input_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(search_area);
+            Line::from(SEARCH_PLACEHOLDER.dim()).render(placeholder_area, buf);
+            Line::from(vec![SEARCH_PROMPT_PREFIX.dim(), self.query.clone().into()])
+                .render(input_area, buf);
+        }
+
+        if list_area.height > 0 {
+            let render_area = Rect {
+                x: list_area.x.saturating_sub(2),
+                y: list_area.y,
+                width: content_area.width.saturating_sub(2).max(1),
+                height: list_area.height,
+            };
+            render_rows_single_line(
+                render_area,
+                buf,
+                &self.build_rows(),
+                &self.state,
+                render_area.height as usize,
+                "no matching messages",
+            );
+        }
+
+        let hint_area = Rect {
+            x: footer_area.x + 2,
+            y: footer_area.y,
+            width: footer_area.width.saturating_sub(2),
+            height: footer_area.height,
+        };
+        history_search_hint_line().dim().render(hint_area, buf);
+    }
+}
+
+fn history_search_hint_line() -> Line<'static> {
+    Line::from(vec![
+        "Press ".into(),
+        key_hint::plain(KeyCode::Enter).into(),
+        " to recall; ".into(),
+        key_hint::plain(KeyCode::Esc).into(),
+        " to close".into(),
+    ])
+}
+
+/// Show a multi-line message on one line.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn human_time_ago(ts: DateTime<Utc>) -> String {
+    let secs = (Utc::now() - ts).num_seconds().max(0);
+    if secs < 60 {
+        "just now".to_string()
+    } else if secs < 60 * 60 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 60 * 60 * 24 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / (60 * 60 * 24))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn entry(text: &str) -> MessageHistoryEntry {
+        MessageHistoryEntry {
+            thread_id: None,
+            cwd: None,
+            text: text.to_string(),
+            ts: Utc::now(),
+        }
+    }
+
+    fn shown(view: &HistorySearchView) -> Vec<String> {
+        view.matches.iter().map(|m| m.text.clone()).collect()
+    }
+
+    fn type_query(view: &mut HistorySearchView, query: &str) {
+        for c in query.chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn filters_fuzzily_and_switches_scope() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let results = HistorySearchResults {
+            repo: vec![entry("run the tests"), entry("fix\nthe build")],
+            all: vec![
+                entry("deploy to staging"),
+                entry("run the tests"),
+                entry("fix\nthe build"),
+            ],
+        };
+        let mut view = HistorySearchView::new(results, AppEventSender::new(tx_raw));
+        assert_eq!(shown(&view), vec!["run the tests", "fix the build"]);
+
+        type_query(&mut view, "tb");
+        assert_eq!(shown(&view), vec!["fix the build"]);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(shown(&view), vec!["fix the build"]);
+        view.handle_key_event(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(
+            shown(&view),
+            vec!["deploy to staging", "run the tests", "fix the build"]
+        );
+    }
+
+    #[test]
+    fn enter_recalls_the_selected_message() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let results = HistorySearchResults {
+            repo: Vec::new(),
+            all: vec![entry("first"), entry("multi\nline")],
+        };
+        let mut view = HistorySearchView::new(results, AppEventSender::new(tx_raw));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(view.is_complete());
+        match rx.try_recv() {
+            Ok(AppEvent::RecallHistoryEntry(text)) => assert_eq!(text, "multi\nline"),
+            other => panic!("expected RecallHistoryEntry, got {other:?}"),
+        }
+    }
+}
@@ -68,12 +68,15 @@ pub mod custom_prompt_view;
 mod experimental_features_view;
 mod file_search_popup;
 mod footer;
+mod history_search_view;
 mod list_selection_view;
 mod prompt_args;
 mod skill_popup;
 mod skills_toggle_view;
 mod slash_commands;
 pub(crate) use footer::CollaborationModeIndicator;
+pub(crate) use history_search_view::HistorySearchResults;
+pub(crate) use history_search_view::HistorySearchView;
 pub(crate) use list_selection_view::ColumnWidthMode;
 pub(crate) use list_selection_view::SelectionViewParams;
 mod feedback_view;
@@ -848,6 +851,22 @@ impl BottomPane {
         self.push_view(Box::new(modal));
     }
 
+    /// Called when an approval was answered outside this UI, for example from
+    /// the menu bar, so its prompt is no longer needed.
+    pub fn dismiss_approval_request(&mut self, id: &str) {
+        let Some(view) = self.view_stack.last_mut() else {
+            return;
+        };
+        if !view.dismiss_approval_request(id) {
+            return;
+        }
+        if view.is_complete() {
+            self.view_stack.pop();
+            self.on_active_view_complete();
+        }
+        self.request_redraw();
+    }
+
     /// Called when the agent requests user input.
     pub fn push_user_input_request(&mut self, request: RequestUserInputEvent) {
         let request = if let Some(view) = self.view_stack.last_mut() {
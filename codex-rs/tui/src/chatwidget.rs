@@ -82,6 +82,7 @@ use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::NetworkApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::ProgressTraceCategory;
@@ -219,6 +220,9 @@ mod agent;
 use self::agent::spawn_agent;
 use self::agent::spawn_agent_from_existing;
 pub(crate) use self::agent::spawn_op_forwarder;
+mod history_search;
+mod memories;
+mod project_knowledge;
 mod session_header;
 use self::session_header::SessionHeader;
 mod skills;
@@ -1690,6 +1694,15 @@ impl ChatWidget {
         );
     }
 
+    fn on_network_approval_request(&mut self, id: String, ev: NetworkApprovalRequestEvent) {
+        let id2 = id.clone();
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_network_approval(id, ev),
+            |s| s.handle_network_approval_now(id2, ev2),
+        );
+    }
+
     fn on_apply_patch_approval_request(&mut self, id: String, ev: ApplyPatchApprovalRequestEvent) {
         let id2 = id.clone();
         let ev2 = ev.clone();
@@ -2260,6 +2273,12 @@ impl ChatWidget {
         self.had_work_activity = true;
     }
 
+    /// Close the approval prompt for `id` once it was answered elsewhere.
+    pub(crate) fn dismiss_approval_request(&mut self, id: &str) {
+        self.bottom_pane.dismiss_approval_request(id);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_exec_approval_now(&mut self, id: String, ev: ExecApprovalRequestEvent) {
         self.flush_answer_stream_with_separator();
         let command = shlex::try_join(ev.command.iter().map(String::as_str))
@@ -2277,6 +2296,29 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    pub(crate) fn handle_network_approval_now(
+        &mut self,
+        id: String,
+        ev: NetworkApprovalRequestEvent,
+    ) {
+        self.flush_answer_stream_with_separator();
+        self.notify(Notification::NetworkApprovalRequested {
+            host: ev.host.clone(),
+        });
+
+        let request = ApprovalRequest::Network {
+            id,
+            host: ev.host,
+            port: ev.port,
+            method: ev.method,
+            command: ev.command,
+            proposed_network_policy_amendment: ev.proposed_network_policy_amendment,
+        };
+        self.bottom_pane
+            .push_approval_request(request, &self.config.features);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_apply_patch_approval_now(
         &mut self,
         id: String,
@@ -3064,6 +3106,18 @@ impl ChatWidget {
                 self.open_copy_code_block_picker();
                 return;
             }
+            key_event
+                if key_event.kind == KeyEventKind::Press
+                    && self.bottom_pane.no_modal_or_popup_active()
+                    && self
+                        .keybindings
+                        .history_search
+                        .iter()
+                        .any(|binding| binding.matches(&key_event)) =>
+            {
+                self.open_history_search();
+                return;
+            }
             other if other.kind == KeyEventKind::Press => {
                 self.bottom_pane.clear_quit_shortcut_hint();
                 self.quit_shortcut_expires_at = None;
@@ -3479,6 +3533,12 @@ impl ChatWidget {
             SlashCommand::Skills => {
                 self.open_skills_menu();
             }
+            SlashCommand::Memory => {
+                self.open_memories();
+            }
+            SlashCommand::Knowledge => {
+                self.open_project_knowledge();
+            }
             SlashCommand::Status => {
                 self.add_status_output();
             }
@@ -4361,6 +4421,9 @@ impl ChatWidget {
                 // For replayed events, synthesize an empty id (these should not occur).
                 self.on_exec_approval_request(id.unwrap_or_default(), ev)
             }
+            EventMsg::NetworkApprovalRequest(ev) => {
+                self.on_network_approval_request(id.unwrap_or_default(), ev)
+            }
             EventMsg::ApplyPatchApprovalRequest(ev) => {
                 self.on_apply_patch_approval_request(id.unwrap_or_default(), ev)
             }
@@ -8933,6 +8996,7 @@ impl Renderable for ChatWidget {
 enum Notification {
     AgentTurnComplete { response: String },
     ExecApprovalRequested { command: String },
+    NetworkApprovalRequested { host: String },
     EditApprovalRequested { cwd: PathBuf, changes: Vec<PathBuf> },
     ElicitationRequested { server_name: String },
 }
@@ -8947,6 +9011,9 @@ impl Notification {
             Notification::ExecApprovalRequested { command } => {
                 format!("Approval requested: {}", truncate_text(command, 30))
             }
+            Notification::NetworkApprovalRequested { host } => {
+                format!("Codex wants to connect to {host}")
+            }
             Notification::EditApprovalRequested { cwd, changes } => {
                 format!(
                     "Codex wants to edit {}",
@@ -8968,6 +9035,7 @@ impl Notification {
         match self {
             Notification::AgentTurnComplete { .. } => "agent-turn-complete",
             Notification::ExecApprovalRequested { .. }
+            | Notification::NetworkApprovalRequested { .. }
             | Notification::EditApprovalRequested { .. }
             | Notification::ElicitationRequested { .. } => "approval-requested",
         }
@@ -0,0 +1,61 @@
+use codex_core::project_knowledge::knowledge_repo_root;
+use codex_core::state_db;
+
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::HistorySearchResults;
+use crate::bottom_pane::HistorySearchView;
+
+/// Distinct messages loaded for each scope of the history search.
+const HISTORY_SEARCH_LIMIT: usize = 1000;
+
+impl ChatWidget {
+    /// Load the message history of all sessions and show the Ctrl+R search
+    /// once loaded.
+    pub(crate) fn open_history_search(&mut self) {
+        let codex_home = self.config.codex_home.clone();
+        let model_provider_id = self.config.model_provider_id.clone();
+        let cwd = self.config.cwd.clone();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let Some(ctx) = state_db::open_if_present(&codex_home, &model_provider_id).await else {
+                tx.send(AppEvent::MessageHistoryLoaded(Err(
+                    "History search requires the state database (enable the `sqlite` feature)."
+                        .to_string(),
+                )));
+                return;
+            };
+            let repo_root = knowledge_repo_root(&cwd);
+            let repo = ctx
+                .search_message_history(Some(&repo_root), HISTORY_SEARCH_LIMIT)
+                .await;
+            let all = ctx.search_message_history(None, HISTORY_SEARCH_LIMIT).await;
+            let result = match (repo, all) {
+                (Ok(repo), Ok(all)) => Ok(HistorySearchResults { repo, all }),
+                (Err(err), _) | (_, Err(err)) => {
+                    Err(format!("Failed to load message history: {err}"))
+                }
+            };
+            tx.send(AppEvent::MessageHistoryLoaded(result));
+        });
+    }
+
+    pub(crate) fn on_message_history_loaded(
+        &mut self,
+        result: Result<HistorySearchResults, String>,
+    ) {
+        let results = match result {
+            Ok(results) => results,
+            Err(message) => {
+                self.add_error_message(message);
+                return;
+            }
+        };
+        if results.all.is_empty() {
+            self.add_info_message("No messages in the history yet.".to_string(), None);
+            return;
+        }
+        let view = HistorySearchView::new(results, self.app_event_tx.clone());
+        self.bottom_pane.show_view(Box::new(view));
+    }
+}
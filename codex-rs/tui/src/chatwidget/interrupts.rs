@@ -6,6 +6,7 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::NetworkApprovalRequestEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_protocol::approvals::ElicitationRequestEvent;
 use codex_protocol::request_user_input::RequestUserInputEvent;
@@ -15,6 +16,7 @@ use super::ChatWidget;
 #[derive(Debug)]
 pub(crate) enum QueuedInterrupt {
     ExecApproval(String, ExecApprovalRequestEvent),
+    NetworkApproval(String, NetworkApprovalRequestEvent),
     ApplyPatchApproval(String, ApplyPatchApprovalRequestEvent),
     Elicitation(ElicitationRequestEvent),
     RequestUserInput(RequestUserInputEvent),
@@ -46,6 +48,11 @@ impl InterruptManager {
         self.queue.push_back(QueuedInterrupt::ExecApproval(id, ev));
     }
 
+    pub(crate) fn push_network_approval(&mut self, id: String, ev: NetworkApprovalRequestEvent) {
+        self.queue
+            .push_back(QueuedInterrupt::NetworkApproval(id, ev));
+    }
+
     pub(crate) fn push_apply_patch_approval(
         &mut self,
         id: String,
@@ -87,6 +94,9 @@ impl InterruptManager {
         while let Some(q) = self.queue.pop_front() {
             match q {
                 QueuedInterrupt::ExecApproval(id, ev) => chat.handle_exec_approval_now(id, ev),
+                QueuedInterrupt::NetworkApproval(id, ev) => {
+                    chat.handle_network_approval_now(id, ev)
+                }
                 QueuedInterrupt::ApplyPatchApproval(id, ev) => {
                     chat.handle_apply_patch_approval_now(id, ev)
                 }
@@ -0,0 +1,263 @@
+use chrono::Local;
+use codex_core::state_db;
+use codex_core::state_db::ThreadMemory;
+use codex_protocol::ThreadId;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::app_event::MemoryUpdate;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::custom_prompt_view::CustomPromptView;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::history_cell;
+use crate::text_formatting::truncate_text;
+
+/// Graphemes of the memory summary shown in the `/memory` list.
+const MEMORY_PREVIEW_GRAPHEMES: usize = 80;
+
+const STATE_DB_REQUIRED: &str =
+    "Memories require the state database (enable the `sqlite` feature).";
+
+impl ChatWidget {
+    /// Load the memories recorded for threads started in the current cwd and
+    /// show them once loaded.
+    pub(crate) fn open_memories(&mut self) {
+        let codex_home = self.config.codex_home.clone();
+        let model_provider_id = self.config.model_provider_id.clone();
+        let cwd = self.config.cwd.clone();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let result = match state_db::open_if_present(&codex_home, &model_provider_id).await {
+                Some(ctx) => ctx
+                    .list_thread_memories_for_cwd(&cwd)
+                    .await
+                    .map_err(|err| format!("Failed to load memories: {err}")),
+                None => Err(STATE_DB_REQUIRED.to_string()),
+            };
+            tx.send(AppEvent::MemoriesLoaded(result));
+        });
+    }
+
+    pub(crate) fn on_memories_loaded(&mut self, result: Result<Vec<ThreadMemory>, String>) {
+        let memories = match result {
+            Ok(memories) => memories,
+            Err(message) => {
+                self.add_error_message(message);
+                return;
+            }
+        };
+        if memories.is_empty() {
+            self.add_info_message(
+                "No memories for this directory yet.".to_string(),
+                Some("Codex records a memory when a chat started here finishes.".to_string()),
+            );
+            return;
+        }
+
+        let items = memories
+            .into_iter()
+            .map(|memory| SelectionItem {
+                name: memory_preview(&memory),
+                name_prefix: memory.pinned.then(|| "📌 ".into()),
+                description: Some(memory_description(&memory)),
+                search_value: Some(memory.memory_summary.clone()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenMemoryActions(memory.clone()));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Memories".to_string()),
+            subtitle: Some("Pinned memories are always recalled in this directory.".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search memories".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_memory_actions(&mut self, memory: ThreadMemory) {
+        let thread_id = memory.thread_id;
+        let pinned = memory.pinned;
+        let subtitle = memory_preview(&memory);
+        let view_memory = memory.clone();
+        let items = vec![
+            SelectionItem {
+                name: "View".to_string(),
+                description: Some("Show the full memory and trace summary.".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::InsertHistoryCell(Box::new(
+                        history_cell::PlainHistoryCell::new(memory_lines(&view_memory)),
+                    )));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Edit summary".to_string(),
+                description: Some("Rewrite what Codex recalls from this chat.".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenMemoryEditor(memory.clone()));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: if pinned { "Unpin" } else { "Pin" }.to_string(),
+                description: Some(if pinned {
+                    "Recall this memory only while it is among the most recent.".to_string()
+                } else {
+                    "Always recall this memory in this directory.".to_string()
+                }),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::UpdateMemory {
+                        thread_id,
+                        update: MemoryUpdate::SetPinned(!pinned),
+                    });
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Delete".to_string(),
+                description: Some("Forget this memory; the chat itself is kept.".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::UpdateMemory {
+                        thread_id,
+                        update: MemoryUpdate::Delete,
+                    });
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Memory".to_string()),
+            subtitle: Some(subtitle),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_memory_editor(&mut self, memory: ThreadMemory) {
+        let tx = self.app_event_tx.clone();
+        let thread_id = memory.thread_id;
+        let view = CustomPromptView::new(
+            "Edit memory".to_string(),
+            "Type the summary Codex should recall and press Enter".to_string(),
+            None,
+            Box::new(move |summary: String| {
+                tx.send(AppEvent::UpdateMemory {
+                    thread_id,
+                    update: MemoryUpdate::SetSummary(summary),
+                });
+            }),
+        )
+        .with_initial_text(&memory.memory_summary);
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
+    /// Apply `update` to the memory of `thread_id` in the background.
+    pub(crate) fn update_memory(&mut self, thread_id: ThreadId, update: MemoryUpdate) {
+        let codex_home = self.config.codex_home.clone();
+        let model_provider_id = self.config.model_provider_id.clone();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let Some(ctx) = state_db::open_if_present(&codex_home, &model_provider_id).await else {
+                tx.send(AppEvent::MemoryUpdated(Err(STATE_DB_REQUIRED.to_string())));
+                return;
+            };
+            let result = match update {
+                MemoryUpdate::SetSummary(summary) => ctx
+                    .update_thread_memory_summary(thread_id, &summary)
+                    .await
+                    .map(|memory| memory.map(|_| "Memory updated.")),
+                MemoryUpdate::SetPinned(pinned) => ctx
+                    .set_thread_memory_pinned(thread_id, pinned)
+                    .await
+                    .map(|memory| {
+                        memory.map(|_| {
+                            if pinned {
+                                "Memory pinned."
+                            } else {
+                                "Memory unpinned."
+                            }
+                        })
+                    }),
+                MemoryUpdate::Delete => ctx
+                    .delete_thread_memory(thread_id)
+                    .await
+                    .map(|deleted| deleted.then_some("Memory deleted.")),
+            };
+            let result = match result {
+                Ok(Some(message)) => Ok(message.to_string()),
+                Ok(None) => Err("The memory no longer exists.".to_string()),
+                Err(err) => Err(format!("Failed to update memory: {err}")),
+            };
+            tx.send(AppEvent::MemoryUpdated(result));
+        });
+    }
+
+    pub(crate) fn on_memory_updated(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(message) => {
+                self.add_info_message(message, None);
+                self.open_memories();
+            }
+            Err(message) => self.add_error_message(message),
+        }
+    }
+}
+
+fn memory_preview(memory: &ThreadMemory) -> String {
+    let first_line = memory.memory_summary.lines().next().unwrap_or_default();
+    truncate_text(first_line.trim(), MEMORY_PREVIEW_GRAPHEMES)
+}
+
+fn memory_description(memory: &ThreadMemory) -> String {
+    let updated_at = memory
+        .updated_at
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M");
+    if memory.pinned {
+        format!("pinned · updated {updated_at}")
+    } else {
+        format!("updated {updated_at}")
+    }
+}
+
+fn memory_lines(memory: &ThreadMemory) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(vec![
+            "• ".dim(),
+            "Memory".bold(),
+            format!(" from thread {}", memory.thread_id).dim(),
+        ]),
+        Line::from(format!("  {}", memory_description(memory)).dim()),
+        Line::from(""),
+    ];
+    lines.extend(
+        memory
+            .memory_summary
+            .lines()
+            .map(|line| Line::from(format!("  {line}"))),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from("  Trace".bold()));
+    lines.extend(
+        memory
+            .trace_summary
+            .lines()
+            .map(|line| Line::from(format!("  {line}").dim())),
+    );
+    lines
+}
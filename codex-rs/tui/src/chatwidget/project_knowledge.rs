@@ -0,0 +1,201 @@
+use codex_core::project_knowledge::knowledge_repo_root;
+use codex_core::state_db;
+use codex_core::state_db::KnowledgeStatus;
+use codex_core::state_db::ProjectKnowledge;
+
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::app_event::KnowledgeUpdate;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+
+const STATE_DB_REQUIRED: &str =
+    "Project knowledge requires the state database (enable the `sqlite` feature).";
+
+impl ChatWidget {
+    /// Load the knowledge recorded for the repository of the current cwd and
+    /// show it once loaded.
+    pub(crate) fn open_project_knowledge(&mut self) {
+        let codex_home = self.config.codex_home.clone();
+        let model_provider_id = self.config.model_provider_id.clone();
+        let cwd = self.config.cwd.clone();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let result = match state_db::open_if_present(&codex_home, &model_provider_id).await {
+                Some(ctx) => ctx
+                    .list_project_knowledge(&knowledge_repo_root(&cwd), None)
+                    .await
+                    .map_err(|err| format!("Failed to load project knowledge: {err}")),
+                None => Err(STATE_DB_REQUIRED.to_string()),
+            };
+            tx.send(AppEvent::ProjectKnowledgeLoaded(result));
+        });
+    }
+
+    pub(crate) fn on_project_knowledge_loaded(
+        &mut self,
+        result: Result<Vec<ProjectKnowledge>, String>,
+    ) {
+        let mut knowledge = match result {
+            Ok(knowledge) => knowledge,
+            Err(message) => {
+                self.add_error_message(message);
+                return;
+            }
+        };
+        if knowledge.is_empty() {
+            self.add_info_message(
+                "Codex has not remembered anything about this repository yet.".to_string(),
+                Some("Enable the `memory_tool` feature to let Codex record facts.".to_string()),
+            );
+            return;
+        }
+        // Entries awaiting review come first.
+        knowledge.sort_by_key(|entry| (status_rank(entry.status), entry.id));
+
+        let items = knowledge
+            .into_iter()
+            .map(|entry| SelectionItem {
+                name: entry.content.clone(),
+                description: Some(status_label(entry.status).to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenProjectKnowledgeActions(entry.clone()));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Project knowledge".to_string()),
+            subtitle: Some(
+                "Approved facts are added to the instructions of new sessions in this repository."
+                    .to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search facts".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_project_knowledge_actions(&mut self, entry: ProjectKnowledge) {
+        let id = entry.id;
+        let mut items = Vec::new();
+        for (status, name, description) in [
+            (
+                KnowledgeStatus::Approved,
+                "Approve",
+                "Include this fact in new sessions in this repository.",
+            ),
+            (
+                KnowledgeStatus::Rejected,
+                "Reject",
+                "Never use this fact, and keep Codex from proposing it again.",
+            ),
+        ] {
+            if entry.status == status {
+                continue;
+            }
+            items.push(SelectionItem {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::UpdateProjectKnowledge {
+                        id,
+                        update: KnowledgeUpdate::SetStatus(status),
+                    });
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+        items.push(SelectionItem {
+            name: "Delete".to_string(),
+            description: Some("Forget this fact; Codex may propose it again.".to_string()),
+            actions: vec![Box::new(move |tx| {
+                tx.send(AppEvent::UpdateProjectKnowledge {
+                    id,
+                    update: KnowledgeUpdate::Delete,
+                });
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(entry.content),
+            subtitle: Some(status_label(entry.status).to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Apply `update` to the knowledge entry `id` in the background.
+    pub(crate) fn update_project_knowledge(&mut self, id: i64, update: KnowledgeUpdate) {
+        let codex_home = self.config.codex_home.clone();
+        let model_provider_id = self.config.model_provider_id.clone();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let Some(ctx) = state_db::open_if_present(&codex_home, &model_provider_id).await else {
+                tx.send(AppEvent::ProjectKnowledgeUpdated(Err(
+                    STATE_DB_REQUIRED.to_string()
+                )));
+                return;
+            };
+            let result = match update {
+                KnowledgeUpdate::SetStatus(status) => ctx
+                    .set_project_knowledge_status(id, status)
+                    .await
+                    .map(|entry| {
+                        entry.map(|_| match status {
+                            KnowledgeStatus::Approved => {
+                                "Fact approved. It applies to new sessions in this repository."
+                            }
+                            KnowledgeStatus::Rejected => "Fact rejected.",
+                            KnowledgeStatus::Pending => "Fact marked for review.",
+                        })
+                    }),
+                KnowledgeUpdate::Delete => ctx
+                    .delete_project_knowledge(id)
+                    .await
+                    .map(|deleted| deleted.then_some("Fact deleted.")),
+            };
+            let result = match result {
+                Ok(Some(message)) => Ok(message.to_string()),
+                Ok(None) => Err("The fact no longer exists.".to_string()),
+                Err(err) => Err(format!("Failed to update project knowledge: {err}")),
+            };
+            tx.send(AppEvent::ProjectKnowledgeUpdated(result));
+        });
+    }
+
+    pub(crate) fn on_project_knowledge_updated(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(message) => {
+                self.add_info_message(message, None);
+                self.open_project_knowledge();
+            }
+            Err(message) => self.add_error_message(message),
+        }
+    }
+}
+
+fn status_rank(status: KnowledgeStatus) -> u8 {
+    match status {
+        KnowledgeStatus::Pending => 0,
+        KnowledgeStatus::Approved => 1,
+        KnowledgeStatus::Rejected => 2,
+    }
+}
+
+fn status_label(status: KnowledgeStatus) -> &'static str {
+    match status {
+        KnowledgeStatus::Pending => "awaiting review",
+        KnowledgeStatus::Approved => "approved",
+        KnowledgeStatus::Rejected => "rejected",
+    }
+}
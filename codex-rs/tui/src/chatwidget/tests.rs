@@ -1921,7 +1921,6 @@ async fn ctrl_d_with_modal_open_does_not_quit() {
 async fn copy_last_output_shortcuts_show_notice_when_no_output_exists() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
 
-    chat.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
     chat.handle_key_event(KeyEvent::new(KeyCode::F(8), KeyModifiers::NONE));
 
     let cells = drain_insert_history(&mut rx);
@@ -759,6 +759,17 @@ pub fn new_approval_decision_cell(
                 ],
             )
         }
+        ApprovedNetworkPolicyAmendment {
+            proposed_network_policy_amendment,
+        } => (
+            "✔ ".green(),
+            vec![
+                "You ".into(),
+                "approved".bold(),
+                " codex to always connect to ".into(),
+                Span::from(proposed_network_policy_amendment.host).dim(),
+            ],
+        ),
         Denied => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
@@ -792,6 +803,72 @@ pub fn new_approval_decision_cell(
     ))
 }
 
+/// Records the user's answer to a request to reach a host blocked by the
+/// network allowlist.
+pub fn new_network_approval_decision_cell(
+    host: String,
+    decision: codex_core::protocol::ReviewDecision,
+) -> Box<dyn HistoryCell> {
+    use codex_core::protocol::ReviewDecision::*;
+
+    let host = Span::from(host).dim();
+    let (symbol, summary): (Span<'static>, Vec<Span<'static>>) = match decision {
+        Approved | ApprovedExecpolicyAmendment { .. } => (
+            "✔ ".green(),
+            vec![
+                "You ".into(),
+                "allowed".bold(),
+                " codex to connect to ".into(),
+                host,
+                " this time".bold(),
+            ],
+        ),
+        ApprovedForSession => (
+            "✔ ".green(),
+            vec![
+                "You ".into(),
+                "allowed".bold(),
+                " codex to connect to ".into(),
+                host,
+                " for this session".bold(),
+            ],
+        ),
+        ApprovedNetworkPolicyAmendment { .. } => (
+            "✔ ".green(),
+            vec![
+                "You ".into(),
+                "approved".bold(),
+                " codex to always connect to ".into(),
+                host,
+            ],
+        ),
+        Denied => (
+            "✗ ".red(),
+            vec![
+                "You ".into(),
+                "did not allow".bold(),
+                " codex to connect to ".into(),
+                host,
+            ],
+        ),
+        Abort => (
+            "✗ ".red(),
+            vec![
+                "You ".into(),
+                "canceled".bold(),
+                " the request to connect to ".into(),
+                host,
+            ],
+        ),
+    };
+
+    Box::new(PrefixedWrappedHistoryCell::new(
+        Line::from(summary),
+        symbol,
+        "  ",
+    ))
+}
+
 /// Cyan history cell line showing the current review status.
 pub(crate) fn new_review_status_line(message: String) -> PlainHistoryCell {
     PlainHistoryCell {
@@ -2552,6 +2629,7 @@ mod tests {
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
+            secret_refs: Default::default(),
         };
         let mut servers = config.mcp_servers.get().clone();
         servers.insert("docs".to_string(), stdio_config);
@@ -2574,6 +2652,7 @@ mod tests {
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
+            secret_refs: Default::default(),
         };
         servers.insert("http".to_string(), http_config);
         config
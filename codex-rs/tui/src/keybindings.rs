@@ -11,6 +11,7 @@ pub(crate) struct Keybindings {
     pub(crate) copy_prompt: Vec<KeyBinding>,
     pub(crate) copy_last_output: Vec<KeyBinding>,
     pub(crate) copy_code_block: Vec<KeyBinding>,
+    pub(crate) history_search: Vec<KeyBinding>,
     pub(crate) editor: EditorKeybindings,
 }
 
@@ -53,6 +54,11 @@ impl Keybindings {
                 "copy_code_block",
                 defaults.copy_code_block,
             ),
+            history_search: bindings_or_default(
+                keybindings,
+                "history_search",
+                defaults.history_search,
+            ),
             editor: EditorKeybindings {
                 move_left: bindings_or_default(keybindings, "editor_move_left", Vec::new()),
                 move_right: bindings_or_default(keybindings, "editor_move_right", Vec::new()),
@@ -123,14 +129,12 @@ impl Keybindings {
         };
 
         let copy_prompt = vec![KeyBinding::new(KeyCode::Char('l'), KeyModifiers::CONTROL)];
-        let copy_last_output = vec![
-            KeyBinding::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
-            KeyBinding::new(KeyCode::F(8), KeyModifiers::NONE),
-        ];
+        let copy_last_output = vec![KeyBinding::new(KeyCode::F(8), KeyModifiers::NONE)];
         let copy_code_block = vec![
             KeyBinding::new(KeyCode::Char('b'), KeyModifiers::CONTROL),
             KeyBinding::new(KeyCode::F(6), KeyModifiers::NONE),
         ];
+        let history_search = vec![KeyBinding::new(KeyCode::Char('r'), KeyModifiers::CONTROL)];
 
         Self {
             submit,
@@ -139,6 +143,7 @@ impl Keybindings {
             copy_prompt,
             copy_last_output,
             copy_code_block,
+            history_search,
             editor: EditorKeybindings {
                 move_left: Vec::new(),
                 move_right: Vec::new(),
@@ -275,10 +280,16 @@ mod tests {
         let bindings = Keybindings::from_config(&HashMap::new(), true, false);
         assert_eq!(
             bindings.copy_last_output,
-            vec![
-                KeyBinding::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
-                KeyBinding::new(KeyCode::F(8), KeyModifiers::NONE),
-            ],
+            vec![KeyBinding::new(KeyCode::F(8), KeyModifiers::NONE)],
+        );
+    }
+
+    #[test]
+    fn defaults_include_history_search_shortcut() {
+        let bindings = Keybindings::from_config(&HashMap::new(), true, false);
+        assert_eq!(
+            bindings.history_search,
+            vec![KeyBinding::new(KeyCode::Char('r'), KeyModifiers::CONTROL)],
         );
     }
 
@@ -333,7 +333,7 @@ pub async fn run_main(
     };
 
     let file_layer = tracing_subscriber::fmt::layer()
-        .with_writer(non_blocking)
+        .with_writer(move || codex_secrets::RedactingWriter::new(non_blocking.clone()))
         // `with_target(true)` is the default, but we previously disabled it for file output.
         // Keep it enabled so we can selectively enable targets via `RUST_LOG=...` and then
         // grep for a specific module/target while troubleshooting.
@@ -390,7 +390,7 @@ pub async fn run_main(
 
     let log_db_layer = codex_core::state_db::get_state_db(&config, None)
         .await
-        .map(|db| log_db::start(db).with_filter(env_filter()));
+        .map(|db| log_db::start_with_retention(db, config.log_retention).with_filter(env_filter()));
 
     let _ = tracing_subscriber::registry()
         .with(file_layer)
@@ -1030,6 +1030,7 @@ mod tests {
             .clone()
             .unwrap_or_else(|| "gpt-5.1".to_string());
         TurnContextItem {
+            turn_id: None,
             cwd,
             approval_policy: config.approval_policy.value(),
             sandbox_policy: config.sandbox_policy.get().clone(),
@@ -1,30 +1,31 @@
+use crate::app_event_sender::AppEventSender;
 use codex_core::AuthManager;
 use codex_core::ThreadManager;
 use codex_core::config::Config;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
 use std::sync::Arc;
 use toml::Value as TomlValue;
 
-#[cfg(target_os = "macos")]
+#[cfg(unix)]
 mod imp {
     use super::*;
+    use crate::app_event::AppEvent;
+    use codex_app_server_protocol::CommandExecutionApprovalDecision;
+    use codex_app_server_protocol::FileChangeApprovalDecision;
+    use codex_codexd::event_bridge::EventBridge;
     use codex_codexd::producer::CodexdProducerClient;
+    use codex_codexd::producer::RuntimeCommandRequest;
     use codex_codexd::producer::RuntimeMetadata;
-    use codex_codexd::protocol::HubNotification;
-    use serde_json::json;
-    use std::collections::HashMap;
-    use std::collections::HashSet;
+    use codex_codexd::protocol::RuntimeCommand;
+    use codex_core::protocol::ReviewDecision;
+    use codex_protocol::ThreadId;
+    use codex_protocol::user_input::UserInput;
     use std::path::PathBuf;
+    use tokio::sync::mpsc;
 
     pub struct MenuBarBridge {
-        producer: CodexdProducerClient,
-        active_turns: HashMap<String, String>,
-        turn_key_by_turn_id: HashMap<String, String>,
-        turn_start_order: Vec<String>,
-        known_turn_keys: HashSet<String>,
-        current_model: Option<String>,
-        current_model_provider: Option<String>,
-        current_thinking_level: Option<String>,
+        events: EventBridge,
     }
 
     impl MenuBarBridge {
@@ -32,10 +33,11 @@ mod imp {
             _codex_linux_sandbox_exe: Option<PathBuf>,
             config: Arc<Config>,
             _auth_manager: Arc<AuthManager>,
-            _thread_manager: Arc<ThreadManager>,
+            thread_manager: Arc<ThreadManager>,
             _cli_overrides: Vec<(String, TomlValue)>,
+            app_event_tx: AppEventSender,
         ) -> Option<Self> {
-            let producer = CodexdProducerClient::spawn(
+            let (producer, commands) = CodexdProducerClient::spawn_with_commands(
                 config.codex_home.as_path(),
                 RuntimeMetadata {
                     runtime_id: format!("pid:{}", std::process::id()),
@@ -45,16 +47,10 @@ mod imp {
                     display_name: Some("codex-tui".to_string()),
                 },
             );
+            tokio::spawn(run_command_loop(commands, thread_manager, app_event_tx));
 
             Some(Self {
-                producer,
-                active_turns: HashMap::new(),
-                turn_key_by_turn_id: HashMap::new(),
-                turn_start_order: Vec::new(),
-                known_turn_keys: HashSet::new(),
-                current_model: None,
-                current_model_provider: None,
-                current_thinking_level: None,
+                events: EventBridge::new(producer),
             })
         }
 
@@ -64,258 +60,129 @@ mod imp {
             event_turn_id: &str,
             active_thread_id: Option<String>,
         ) {
-            let mut notifications = Vec::new();
-            match event {
-                EventMsg::TurnStarted(_) => {
-                    if let Some(turn_id) = normalize_turn_id(event_turn_id)
-                        && let Some(thread_id) = active_thread_id
-                    {
-                        notifications.extend(self.ensure_turn_started(thread_id, turn_id));
-                    }
-                }
-                EventMsg::SessionConfigured(event) => {
-                    self.current_model = Some(event.model.clone());
-                    self.current_model_provider = Some(event.model_provider_id.clone());
-                    self.current_thinking_level =
-                        event.reasoning_effort.as_ref().map(ToString::to_string);
-                }
-                EventMsg::ItemStarted(item) => {
-                    notifications.extend(
-                        self.ensure_turn_started(item.thread_id.to_string(), item.turn_id.clone()),
-                    );
-                    notifications.push(HubNotification {
-                        method: "item/started".to_string(),
-                        params: Some(json!({
-                            "threadId": item.thread_id,
-                            "turnId": item.turn_id,
-                            "item": item.item,
-                        })),
-                    });
-                }
-                EventMsg::ItemCompleted(item) => {
-                    notifications.extend(
-                        self.ensure_turn_started(item.thread_id.to_string(), item.turn_id.clone()),
-                    );
-                    notifications.push(HubNotification {
-                        method: "item/completed".to_string(),
-                        params: Some(json!({
-                            "threadId": item.thread_id,
-                            "turnId": item.turn_id,
-                            "item": item.item,
-                        })),
-                    });
-                }
-                EventMsg::ProgressTrace(trace) => {
-                    notifications.extend(
-                        self.ensure_turn_started(
-                            trace.thread_id.to_string(),
-                            trace.turn_id.clone(),
-                        ),
-                    );
-                    notifications.push(HubNotification {
-                        method: "turn/progressTrace".to_string(),
-                        params: Some(json!({
-                            "threadId": trace.thread_id,
-                            "turnId": trace.turn_id,
-                            "category": trace.category,
-                            "state": trace.state,
-                            "label": trace.label,
-                        })),
-                    });
-                }
-                EventMsg::AgentMessageContentDelta(event) => {
-                    notifications.extend(
-                        self.ensure_turn_started(event.thread_id.clone(), event.turn_id.clone()),
-                    );
-                }
-                EventMsg::PlanDelta(event) => {
-                    notifications.extend(
-                        self.ensure_turn_started(event.thread_id.clone(), event.turn_id.clone()),
-                    );
-                }
-                EventMsg::ReasoningContentDelta(event) => {
-                    notifications.extend(
-                        self.ensure_turn_started(event.thread_id.clone(), event.turn_id.clone()),
-                    );
-                }
-                EventMsg::ReasoningRawContentDelta(event) => {
-                    notifications.extend(
-                        self.ensure_turn_started(event.thread_id.clone(), event.turn_id.clone()),
-                    );
-                }
-                EventMsg::TokenCount(event) => {
-                    if let Some(info) = &event.info {
-                        let turn_id = normalize_turn_id(event_turn_id);
-                        let thread_id = turn_id
-                            .as_deref()
-                            .and_then(|id| self.resolve_thread_id_for_turn(id))
-                            .or(active_thread_id.clone());
-                        notifications
-                            .push(Self::token_usage_notification(info, thread_id, turn_id));
-                    }
-                }
-                EventMsg::TurnComplete(_) | EventMsg::TurnAborted(_) => {
-                    notifications.extend(self.complete_turn(normalize_turn_id(event_turn_id)));
-                }
-                EventMsg::Error(error) => {
-                    notifications.push(HubNotification {
-                        method: "error".to_string(),
-                        params: Some(json!({
-                            "error": {
-                                "message": error.message,
-                            },
-                            "willRetry": false,
-                        })),
-                    });
-                }
-                _ => {}
-            }
+            self.events
+                .publish_event(event, event_turn_id, active_thread_id);
+        }
 
-            for notification in notifications {
-                let producer = self.producer.clone();
-                tokio::spawn(async move {
-                    producer.publish_hub_notification(notification).await;
-                });
-            }
+        /// Marks the approval answered by `op` as resolved in codexd.
+        pub fn observe_op(&mut self, op: &Op) {
+            self.events.observe_op(op);
         }
 
-        fn ensure_turn_started(
-            &mut self,
-            thread_id: String,
-            turn_id: String,
-        ) -> Vec<HubNotification> {
-            let key = format!("{thread_id}:{turn_id}");
-            if self.known_turn_keys.contains(&key) {
-                return Vec::new();
-            }
+        pub async fn shutdown(self) {
+            self.events.shutdown().await;
+        }
+    }
 
-            if let Some(existing_key) = self.turn_key_by_turn_id.get(&turn_id)
-                && existing_key != &key
+    async fn run_command_loop(
+        mut commands: mpsc::Receiver<RuntimeCommandRequest>,
+        thread_manager: Arc<ThreadManager>,
+        app_event_tx: AppEventSender,
+    ) {
+        while let Some(request) = commands.recv().await {
+            let result = apply_command(&request.command, &thread_manager).await;
+            // The local prompt for an approval answered from the menu bar
+            // would otherwise stay open and submit a second decision.
+            if result.is_ok()
+                && let Some(id) = approval_id(&request.command)
             {
-                return Vec::new();
+                app_event_tx.send(AppEvent::DismissApprovalRequest { id: id.to_string() });
             }
-
-            self.known_turn_keys.insert(key.clone());
-            self.active_turns.insert(key.clone(), thread_id.clone());
-            self.turn_key_by_turn_id
-                .insert(turn_id.clone(), key.clone());
-            self.turn_start_order.push(key);
-
-            vec![HubNotification {
-                method: "turn/started".to_string(),
-                params: Some(json!({
-                    "threadId": thread_id,
-                    "turn": {
-                        "id": turn_id,
-                        "status": "inProgress",
-                        "model": self.current_model.clone(),
-                        "modelProvider": self.current_model_provider.clone(),
-                        "thinkingLevel": self.current_thinking_level.clone(),
-                    }
-                })),
-            }]
-        }
-
-        fn resolve_thread_id_for_turn(&self, turn_id: &str) -> Option<String> {
-            let key = self.turn_key_by_turn_id.get(turn_id)?;
-            self.active_turns.get(key).cloned()
+            request.respond(result);
         }
+    }
 
-        fn token_usage_notification(
-            info: &codex_core::protocol::TokenUsageInfo,
-            thread_id: Option<String>,
-            turn_id: Option<String>,
-        ) -> HubNotification {
-            let mut params = serde_json::Map::new();
-            if let Some(thread_id) = thread_id {
-                params.insert("threadId".to_string(), json!(thread_id));
-            }
-            if let Some(turn_id) = turn_id {
-                params.insert("turnId".to_string(), json!(turn_id));
-            }
-            params.insert(
-                "tokenUsage".to_string(),
-                json!({
-                    "total": {
-                        "totalTokens": info.total_token_usage.total_tokens,
-                        "inputTokens": info.total_token_usage.input_tokens,
-                        "cachedInputTokens": info.total_token_usage.cached_input_tokens,
-                        "outputTokens": info.total_token_usage.output_tokens,
-                        "reasoningOutputTokens": info.total_token_usage.reasoning_output_tokens,
-                    },
-                    "last": {
-                        "totalTokens": info.last_token_usage.total_tokens,
-                        "inputTokens": info.last_token_usage.input_tokens,
-                        "cachedInputTokens": info.last_token_usage.cached_input_tokens,
-                        "outputTokens": info.last_token_usage.output_tokens,
-                        "reasoningOutputTokens": info.last_token_usage.reasoning_output_tokens,
-                    },
-                    "modelContextWindow": info.model_context_window,
-                }),
-            );
-            HubNotification {
-                method: "thread/tokenUsage/updated".to_string(),
-                params: Some(serde_json::Value::Object(params)),
-            }
+    /// The id the local approval prompt answered by `command` was opened with.
+    fn approval_id(command: &RuntimeCommand) -> Option<&str> {
+        match command {
+            RuntimeCommand::CommandExecutionApproval { turn_id, .. }
+            | RuntimeCommand::FileChangeApproval { turn_id, .. } => Some(turn_id),
+            RuntimeCommand::TurnInterrupt { .. } | RuntimeCommand::SendMessage { .. } => None,
         }
+    }
 
-        fn complete_turn(&mut self, turn_id: Option<String>) -> Vec<HubNotification> {
-            if let Some(turn_id) = turn_id
-                && let Some(key) = self.turn_key_by_turn_id.remove(&turn_id)
-                && let Some(thread_id) = self.active_turns.remove(&key)
-            {
-                self.turn_start_order.retain(|existing| existing != &key);
-                return vec![HubNotification {
-                    method: "turn/completed".to_string(),
-                    params: Some(json!({
-                        "threadId": thread_id,
-                        "turn": {
-                            "id": turn_id,
-                            "status": "completed",
-                        }
-                    })),
-                }];
-            }
+    async fn apply_command(
+        command: &RuntimeCommand,
+        thread_manager: &ThreadManager,
+    ) -> Result<(), String> {
+        let (thread_id, op) = match command {
+            RuntimeCommand::TurnInterrupt { thread_id, .. } => (thread_id, Op::Interrupt),
+            RuntimeCommand::CommandExecutionApproval {
+                thread_id,
+                turn_id,
+                decision,
+                ..
+            } => (
+                thread_id,
+                Op::ExecApproval {
+                    id: turn_id.clone(),
+                    decision: exec_review_decision(decision.clone()),
+                },
+            ),
+            RuntimeCommand::FileChangeApproval {
+                thread_id,
+                turn_id,
+                decision,
+                ..
+            } => (
+                thread_id,
+                Op::PatchApproval {
+                    id: turn_id.clone(),
+                    decision: patch_review_decision(decision),
+                },
+            ),
+            RuntimeCommand::SendMessage { thread_id, text } => (
+                thread_id,
+                Op::UserInput {
+                    items: vec![UserInput::Text {
+                        text: text.clone(),
+                        text_elements: Vec::new(),
+                    }],
+                    final_output_json_schema: None,
+                },
+            ),
+        };
+
+        let thread_id =
+            ThreadId::from_string(thread_id).map_err(|err| format!("invalid thread id: {err}"))?;
+        let thread = thread_manager
+            .get_thread(thread_id)
+            .await
+            .map_err(|_| format!("thread not found: {thread_id}"))?;
+        thread
+            .submit(op)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
 
-            while let Some(key) = self.turn_start_order.pop() {
-                let Some(thread_id) = self.active_turns.remove(&key) else {
-                    continue;
-                };
-                let Some((_, turn_id)) = key.split_once(':') else {
-                    continue;
-                };
-                self.turn_key_by_turn_id.remove(turn_id);
-                return vec![HubNotification {
-                    method: "turn/completed".to_string(),
-                    params: Some(json!({
-                        "threadId": thread_id,
-                        "turn": {
-                            "id": turn_id,
-                            "status": "completed",
-                        }
-                    })),
-                }];
+    fn exec_review_decision(decision: CommandExecutionApprovalDecision) -> ReviewDecision {
+        match decision {
+            CommandExecutionApprovalDecision::Accept => ReviewDecision::Approved,
+            CommandExecutionApprovalDecision::AcceptForSession => {
+                ReviewDecision::ApprovedForSession
             }
-
-            Vec::new()
-        }
-
-        pub async fn shutdown(self) {
-            self.producer.shutdown().await;
+            CommandExecutionApprovalDecision::AcceptWithExecpolicyAmendment {
+                execpolicy_amendment,
+            } => ReviewDecision::ApprovedExecpolicyAmendment {
+                proposed_execpolicy_amendment: execpolicy_amendment.into_core(),
+            },
+            CommandExecutionApprovalDecision::Decline => ReviewDecision::Denied,
+            CommandExecutionApprovalDecision::Cancel => ReviewDecision::Abort,
         }
     }
-}
 
-#[cfg(target_os = "macos")]
-fn normalize_turn_id(turn_id: &str) -> Option<String> {
-    let trimmed = turn_id.trim();
-    if trimmed.is_empty() {
-        return None;
+    fn patch_review_decision(decision: &FileChangeApprovalDecision) -> ReviewDecision {
+        match decision {
+            FileChangeApprovalDecision::Accept => ReviewDecision::Approved,
+            FileChangeApprovalDecision::AcceptForSession => ReviewDecision::ApprovedForSession,
+            FileChangeApprovalDecision::Decline => ReviewDecision::Denied,
+            FileChangeApprovalDecision::Cancel => ReviewDecision::Abort,
+        }
     }
-    Some(trimmed.to_string())
 }
 
-#[cfg(not(target_os = "macos"))]
+#[cfg(not(unix))]
 mod imp {
     use super::*;
     use std::path::PathBuf;
@@ -329,6 +196,7 @@ mod imp {
             _auth_manager: Arc<AuthManager>,
             _thread_manager: Arc<ThreadManager>,
             _cli_overrides: Vec<(String, TomlValue)>,
+            _app_event_tx: AppEventSender,
         ) -> Option<Self> {
             None
         }
@@ -341,6 +209,8 @@ mod imp {
         ) {
         }
 
+        pub fn observe_op(&mut self, _op: &Op) {}
+
         pub async fn shutdown(self) {}
     }
 }
@@ -17,6 +17,10 @@ use codex_core::find_thread_labels_by_ids;
 use codex_core::find_thread_names_by_ids;
 use codex_core::path_utils;
 use codex_core::read_session_meta_line;
+use codex_core::state_db::StateDbHandle;
+use codex_core::state_db::ThreadSearchHit;
+use codex_core::state_db::open_if_present;
+use codex_core::state_db::search_threads_db;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -27,9 +31,11 @@ use ratatui::layout::Rect;
 use ratatui::style::Stylize as _;
 use ratatui::text::Line;
 use ratatui::text::Span;
+use tokio::sync::OnceCell;
 use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::warn;
 use unicode_width::UnicodeWidthStr;
 
 use crate::diff_render::display_path_for;
@@ -91,12 +97,19 @@ struct PageLoadRequest {
 
 type PageLoader = Arc<dyn Fn(PageLoadRequest) + Send + Sync>;
 
+/// Runs a full-text search over thread contents for the given query.
+type ContentSearchLoader = Arc<dyn Fn(String) + Send + Sync>;
+
 enum BackgroundEvent {
     PageLoaded {
         request_token: usize,
         search_token: Option<usize>,
         page: std::io::Result<ThreadsPage>,
     },
+    ContentSearchLoaded {
+        query: String,
+        rows: Vec<Row>,
+    },
 }
 
 /// Interactive session picker that lists recorded rollout files with simple
@@ -115,6 +128,10 @@ enum BackgroundEvent {
 /// 1. Provider and source filtering at the backend (only interactive CLI sessions
 ///    for the current model provider).
 /// 2. Working-directory filtering at the picker (unless `--all` is passed).
+///
+/// Search matches titles and previews of the loaded sessions and, when the
+/// state database is available, the full contents of every session (messages
+/// and commands), showing the matching excerpt in place of the preview.
 pub async fn run_resume_picker(
     tui: &mut Tui,
     codex_home: &Path,
@@ -187,6 +204,43 @@ async fn run_session_picker(
         });
     });
 
+    let state_db: Arc<OnceCell<Option<StateDbHandle>>> = Arc::new(OnceCell::new());
+    let content_tx = bg_tx.clone();
+    let content_codex_home = codex_home.to_path_buf();
+    let content_provider = default_provider.clone();
+    let content_search_loader: ContentSearchLoader = Arc::new(move |query: String| {
+        let tx = content_tx.clone();
+        let state_db = state_db.clone();
+        let codex_home = content_codex_home.clone();
+        let provider = content_provider.clone();
+        tokio::spawn(async move {
+            let state_db = state_db
+                .get_or_init(|| open_if_present(&codex_home, provider.as_str()))
+                .await;
+            let rows = match state_db {
+                Some(state_db) => match search_threads_db(
+                    state_db.as_ref(),
+                    query.as_str(),
+                    PAGE_SIZE,
+                    0,
+                    INTERACTIVE_SESSION_SOURCES,
+                    Some(std::slice::from_ref(&provider)),
+                    false,
+                )
+                .await
+                {
+                    Ok(page) => page.items.into_iter().map(row_from_search_hit).collect(),
+                    Err(err) => {
+                        warn!("session content search failed: {err}");
+                        Vec::new()
+                    }
+                },
+                None => Vec::new(),
+            };
+            let _ = tx.send(BackgroundEvent::ContentSearchLoaded { query, rows });
+        });
+    });
+
     let mut state = PickerState::new(
         codex_home.to_path_buf(),
         alt.tui.frame_requester(),
@@ -196,6 +250,7 @@ async fn run_session_picker(
         filter_cwd,
         action,
     );
+    state.content_search_loader = Some(content_search_loader);
     state.start_initial_load();
     state.request_frame();
 
@@ -276,6 +331,10 @@ struct PickerState {
     next_request_token: usize,
     next_search_token: usize,
     page_loader: PageLoader,
+    content_search_loader: Option<ContentSearchLoader>,
+    /// Full-text matches for the current query, merged after local matches.
+    content_rows: Vec<Row>,
+    content_search_pending: bool,
     view_rows: Option<usize>,
     default_provider: String,
     show_all: bool,
@@ -346,11 +405,16 @@ struct Row {
     updated_at: Option<DateTime<Utc>>,
     cwd: Option<PathBuf>,
     git_branch: Option<String>,
+    /// Excerpt of the thread contents that matched a full-text search.
+    snippet: Option<String>,
 }
 
 impl Row {
     fn display_preview(&self) -> &str {
-        self.thread_name.as_deref().unwrap_or(&self.preview)
+        self.snippet
+            .as_deref()
+            .or(self.thread_name.as_deref())
+            .unwrap_or(&self.preview)
     }
 
     fn matches_query(&self, query: &str) -> bool {
@@ -395,6 +459,9 @@ impl PickerState {
             next_request_token: 0,
             next_search_token: 0,
             page_loader,
+            content_search_loader: None,
+            content_rows: Vec::new(),
+            content_search_pending: false,
             view_rows: None,
             default_provider,
             show_all,
@@ -539,6 +606,14 @@ impl PickerState {
                 let completed_token = pending.search_token.or(search_token);
                 self.continue_search_if_token_matches(completed_token);
             }
+            BackgroundEvent::ContentSearchLoaded { query, rows } => {
+                if query != self.query {
+                    return Ok(());
+                }
+                self.content_search_pending = false;
+                self.content_rows = rows;
+                self.apply_filter();
+            }
         }
         Ok(())
     }
@@ -722,6 +797,18 @@ impl PickerState {
         } else {
             let q = self.query.to_lowercase();
             self.filtered_rows = base_iter.filter(|r| r.matches_query(&q)).cloned().collect();
+            let listed: HashSet<PathBuf> = self
+                .filtered_rows
+                .iter()
+                .map(|row| row.path.clone())
+                .collect();
+            let content_rows = self
+                .content_rows
+                .iter()
+                .filter(|row| self.row_matches_filter(row) && !listed.contains(&row.path))
+                .cloned()
+                .collect::<Vec<_>>();
+            self.filtered_rows.extend(content_rows);
         }
         if self.selected >= self.filtered_rows.len() {
             self.selected = self.filtered_rows.len().saturating_sub(1);
@@ -752,6 +839,14 @@ impl PickerState {
         }
         self.query = new_query;
         self.selected = 0;
+        self.content_rows.clear();
+        self.content_search_pending = false;
+        if !self.query.trim().is_empty()
+            && let Some(loader) = self.content_search_loader.as_ref()
+        {
+            self.content_search_pending = true;
+            loader(self.query.clone());
+        }
         self.apply_filter();
         if self.query.is_empty() {
             self.search_state = SearchState::Idle;
@@ -940,6 +1035,26 @@ fn head_to_row(item: &ThreadItem) -> Row {
         updated_at,
         cwd: item.cwd.clone(),
         git_branch: item.git_branch.clone(),
+        snippet: None,
+    }
+}
+
+fn row_from_search_hit(hit: ThreadSearchHit) -> Row {
+    let thread = hit.thread;
+    let preview = thread
+        .first_user_message
+        .filter(|message| !message.trim().is_empty())
+        .unwrap_or_else(|| String::from("(no message yet)"));
+    Row {
+        path: thread.rollout_path,
+        preview,
+        thread_id: Some(thread.id),
+        thread_name: None,
+        created_at: Some(thread.created_at),
+        updated_at: Some(thread.updated_at),
+        cwd: Some(thread.cwd),
+        git_branch: thread.git_branch,
+        snippet: Some(hit.snippet.split_whitespace().collect::<Vec<_>>().join(" ")),
     }
 }
 
@@ -1180,6 +1295,7 @@ fn render_list(
 fn render_empty_state_line(state: &PickerState) -> Line<'static> {
     if !state.query.is_empty() {
         if state.search_state.is_active()
+            || state.content_search_pending
             || (state.pagination.loading.is_pending() && state.pagination.next_cursor.is_some())
         {
             return vec!["Searching…".italic().dim()].into();
@@ -1701,6 +1817,7 @@ mod tests {
             updated_at: None,
             cwd: None,
             git_branch: None,
+            snippet: None,
         };
 
         assert_eq!(row.display_preview(), "My session");
@@ -1732,6 +1849,7 @@ mod tests {
             updated_at: None,
             cwd: None,
             git_branch: None,
+            snippet: None,
         }];
         state.filtered_rows = state.all_rows.clone();
 
@@ -1773,6 +1891,7 @@ mod tests {
             updated_at: None,
             cwd: None,
             git_branch: None,
+            snippet: None,
         }];
         state.filtered_rows = state.all_rows.clone();
         state
@@ -1831,6 +1950,7 @@ mod tests {
                 updated_at: None,
                 cwd: None,
                 git_branch: None,
+                snippet: None,
             },
             Row {
                 path: path_a.clone(),
@@ -1841,6 +1961,7 @@ mod tests {
                 updated_at: None,
                 cwd: None,
                 git_branch: None,
+                snippet: None,
             },
             Row {
                 path: path_b.clone(),
@@ -1851,6 +1972,7 @@ mod tests {
                 updated_at: None,
                 cwd: None,
                 git_branch: None,
+                snippet: None,
             },
         ];
         state.filtered_rows = state.all_rows.clone();
@@ -1908,6 +2030,7 @@ mod tests {
                 updated_at: Some(now - Duration::seconds(42)),
                 cwd: None,
                 git_branch: None,
+                snippet: None,
             },
             Row {
                 path: PathBuf::from("/tmp/b.jsonl"),
@@ -1918,6 +2041,7 @@ mod tests {
                 updated_at: Some(now - Duration::minutes(35)),
                 cwd: None,
                 git_branch: None,
+                snippet: None,
             },
             Row {
                 path: PathBuf::from("/tmp/c.jsonl"),
@@ -1928,6 +2052,7 @@ mod tests {
                 updated_at: Some(now - Duration::hours(2)),
                 cwd: None,
                 git_branch: None,
+                snippet: None,
             },
         ];
         state.all_rows = rows.clone();
@@ -2185,6 +2310,7 @@ mod tests {
                 updated_at: Some(now - Duration::days(2)),
                 cwd: None,
                 git_branch: None,
+                snippet: None,
             },
             Row {
                 path: PathBuf::from("/tmp/b.jsonl"),
@@ -2195,6 +2321,7 @@ mod tests {
                 updated_at: Some(now - Duration::days(3)),
                 cwd: None,
                 git_branch: None,
+                snippet: None,
             },
         ];
         state.all_rows = rows.clone();
@@ -2610,4 +2737,91 @@ mod tests {
         assert!(!state.search_state.is_active());
         assert!(state.pagination.reached_scan_cap);
     }
+
+    #[tokio::test]
+    async fn set_query_merges_content_search_matches() {
+        let loader: PageLoader = Arc::new(|_| {});
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            loader,
+            String::from("openai"),
+            true,
+            None,
+            SessionPickerAction::Resume,
+        );
+        let content_queries: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let query_sink = content_queries.clone();
+        state.content_search_loader = Some(Arc::new(move |query: String| {
+            query_sink.lock().unwrap().push(query);
+        }));
+        state.reset_pagination();
+        state.ingest_page(page(
+            vec![
+                make_item("/tmp/a.jsonl", "2025-01-01T00:00:00Z", "websocket retries"),
+                make_item("/tmp/b.jsonl", "2025-01-02T00:00:00Z", "unrelated"),
+            ],
+            None,
+            2,
+            false,
+        ));
+
+        state.set_query("websocket".to_string());
+        assert_eq!(
+            content_queries.lock().unwrap().clone(),
+            vec!["websocket".to_string()]
+        );
+        assert!(state.content_search_pending);
+
+        let content_row = |path: &str, snippet: &str| Row {
+            path: PathBuf::from(path),
+            preview: String::from("preview"),
+            thread_id: None,
+            thread_name: None,
+            created_at: None,
+            updated_at: None,
+            cwd: None,
+            git_branch: None,
+            snippet: Some(snippet.to_string()),
+        };
+        // Results for an outdated query are dropped.
+        state
+            .handle_background_event(BackgroundEvent::ContentSearchLoaded {
+                query: "websock".to_string(),
+                rows: vec![content_row("/tmp/stale.jsonl", "stale")],
+            })
+            .await
+            .unwrap();
+        assert!(state.content_search_pending);
+
+        state
+            .handle_background_event(BackgroundEvent::ContentSearchLoaded {
+                query: "websocket".to_string(),
+                rows: vec![
+                    content_row("/tmp/a.jsonl", "websocket retries"),
+                    content_row("/tmp/c.jsonl", "…reconnect the websocket…"),
+                ],
+            })
+            .await
+            .unwrap();
+
+        assert!(!state.content_search_pending);
+        assert_eq!(
+            state
+                .filtered_rows
+                .iter()
+                .map(|row| (row.path.clone(), row.display_preview().to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    PathBuf::from("/tmp/a.jsonl"),
+                    "websocket retries".to_string()
+                ),
+                (
+                    PathBuf::from("/tmp/c.jsonl"),
+                    "…reconnect the websocket…".to_string()
+                ),
+            ]
+        );
+    }
 }
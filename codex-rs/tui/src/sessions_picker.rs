@@ -17,6 +17,9 @@ use codex_core::find_thread_labels_by_ids;
 use codex_core::find_thread_names_by_ids;
 use codex_core::path_utils;
 use codex_core::read_session_meta_line;
+use codex_core::state_db::StateDbHandle;
+use codex_core::state_db::ThreadOrganization;
+use codex_core::state_db::open_if_present;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -27,6 +30,7 @@ use ratatui::layout::Rect;
 use ratatui::style::Stylize as _;
 use ratatui::text::Line;
 use ratatui::text::Span;
+use tokio::sync::OnceCell;
 use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -42,6 +46,8 @@ use codex_protocol::ThreadId;
 
 const PAGE_SIZE: usize = 25;
 const LOAD_NEAR_THRESHOLD: usize = 5;
+const ORGANIZATION_REQUIRES_STATE_DB: &str =
+    "Tags, pins and folders require the state database (enable the `sqlite` feature).";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SessionSelection {
@@ -113,6 +119,10 @@ enum BackgroundEvent {
 /// 1. Provider and source filtering at the backend (only interactive CLI sessions
 ///    for the current model provider).
 /// 2. Working-directory filtering at the picker (unless `--all` is passed).
+///
+/// Sessions can be pinned (`p`), tagged (`t`) and filed into folders (`f`).
+/// That organization lives in the state database; pinned sessions are listed
+/// first and search also matches tags and folders.
 pub async fn run_sessions_picker(
     tui: &mut Tui,
     codex_home: &Path,
@@ -294,7 +304,11 @@ struct PickerState {
     thread_name_cache: HashMap<ThreadId, Option<String>>,
     thread_label_cache: HashMap<ThreadId, Option<String>>,
     fork_parent_id_cache: HashMap<PathBuf, Option<ThreadId>>,
+    organization_cache: HashMap<ThreadId, ThreadOrganization>,
+    state_db: OnceCell<Option<StateDbHandle>>,
     pending_management_confirmation: Option<PendingManagementConfirmation>,
+    pending_organization_edit: Option<PendingOrganizationEdit>,
+    organization_status: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -302,6 +316,26 @@ struct PendingManagementConfirmation {
     action: SessionManagementAction,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OrganizationField {
+    Tags,
+    Folder,
+}
+
+/// Text being edited for the selected session's tags or folder.
+#[derive(Clone, Debug)]
+struct PendingOrganizationEdit {
+    field: OrganizationField,
+    thread_id: ThreadId,
+    input: String,
+}
+
+enum OrganizationUpdate {
+    Pinned(bool),
+    Tags(Vec<String>),
+    Folder(String),
+}
+
 struct PaginationState {
     next_cursor: Option<Cursor>,
     num_scanned_files: usize,
@@ -361,6 +395,9 @@ struct Row {
     updated_at: Option<DateTime<Utc>>,
     cwd: Option<PathBuf>,
     git_branch: Option<String>,
+    tags: Vec<String>,
+    pinned: bool,
+    folder: Option<String>,
 }
 
 impl Row {
@@ -377,7 +414,15 @@ impl Row {
         {
             return true;
         }
-        false
+        if let Some(folder) = self.folder.as_ref()
+            && folder.to_lowercase().contains(query)
+        {
+            return true;
+        }
+        let tag_query = query.strip_prefix('#').unwrap_or(query);
+        self.tags
+            .iter()
+            .any(|tag| tag.to_lowercase().contains(tag_query))
     }
 }
 
@@ -423,7 +468,11 @@ impl PickerState {
             thread_name_cache: HashMap::new(),
             thread_label_cache: HashMap::new(),
             fork_parent_id_cache: HashMap::new(),
+            organization_cache: HashMap::new(),
+            state_db: OnceCell::new(),
             pending_management_confirmation: None,
+            pending_organization_edit: None,
+            organization_status: None,
         }
     }
 
@@ -453,6 +502,12 @@ impl PickerState {
             }
             return Ok(None);
         }
+        if self.pending_organization_edit.is_some() {
+            return Ok(self.handle_organization_edit_key(key).await);
+        }
+        if self.organization_status.take().is_some() {
+            self.request_frame();
+        }
 
         match key.code {
             KeyCode::Esc => {
@@ -548,6 +603,33 @@ impl PickerState {
             {
                 self.begin_management_confirmation_for_selected();
             }
+            KeyCode::Char('p')
+                if self.query.is_empty()
+                    && !key
+                        .modifiers
+                        .contains(crossterm::event::KeyModifiers::CONTROL)
+                    && !key.modifiers.contains(crossterm::event::KeyModifiers::ALT) =>
+            {
+                self.toggle_pin_for_selected().await;
+            }
+            KeyCode::Char('t')
+                if self.query.is_empty()
+                    && !key
+                        .modifiers
+                        .contains(crossterm::event::KeyModifiers::CONTROL)
+                    && !key.modifiers.contains(crossterm::event::KeyModifiers::ALT) =>
+            {
+                self.begin_organization_edit_for_selected(OrganizationField::Tags);
+            }
+            KeyCode::Char('f')
+                if self.query.is_empty()
+                    && !key
+                        .modifiers
+                        .contains(crossterm::event::KeyModifiers::CONTROL)
+                    && !key.modifiers.contains(crossterm::event::KeyModifiers::ALT) =>
+            {
+                self.begin_organization_edit_for_selected(OrganizationField::Folder);
+            }
             KeyCode::Backspace => {
                 let mut new_query = self.query.clone();
                 new_query.pop();
@@ -622,6 +704,7 @@ impl PickerState {
                 let page = page.map_err(color_eyre::Report::from)?;
                 self.ingest_page(page);
                 self.update_thread_names().await;
+                self.update_thread_organization().await;
                 let completed_token = pending.search_token.or(search_token);
                 self.continue_search_if_token_matches(completed_token);
             }
@@ -798,6 +881,202 @@ impl PickerState {
         }
     }
 
+    async fn state_db(&self) -> Option<StateDbHandle> {
+        self.state_db
+            .get_or_init(|| open_if_present(&self.codex_home, self.default_provider.as_str()))
+            .await
+            .clone()
+    }
+
+    async fn update_thread_organization(&mut self) {
+        let mut missing_ids = HashSet::new();
+        for row in &self.all_rows {
+            let Some(thread_id) = row_thread_id(row) else {
+                continue;
+            };
+            if !self.organization_cache.contains_key(&thread_id) {
+                missing_ids.insert(thread_id);
+            }
+        }
+        if missing_ids.is_empty() {
+            return;
+        }
+        let missing_ids: Vec<ThreadId> = missing_ids.into_iter().collect();
+        let Some(state_db) = self.state_db().await else {
+            return;
+        };
+        let mut organizations = state_db
+            .get_thread_organizations(&missing_ids)
+            .await
+            .unwrap_or_default();
+        for thread_id in missing_ids {
+            let organization = organizations.remove(&thread_id).unwrap_or_default();
+            self.organization_cache.insert(thread_id, organization);
+        }
+        self.apply_cached_organization();
+    }
+
+    fn apply_cached_organization(&mut self) {
+        let mut updated = false;
+        for row in &mut self.all_rows {
+            let Some(organization) =
+                row_thread_id(row).and_then(|thread_id| self.organization_cache.get(&thread_id))
+            else {
+                continue;
+            };
+            if row.tags == organization.tags
+                && row.pinned == organization.pinned
+                && row.folder == organization.folder
+            {
+                continue;
+            }
+            row.tags = organization.tags.clone();
+            row.pinned = organization.pinned;
+            row.folder = organization.folder.clone();
+            updated = true;
+        }
+        if updated {
+            let selected_path = self
+                .filtered_rows
+                .get(self.selected)
+                .map(|row| row.path.clone());
+            self.apply_filter();
+            if let Some(index) = selected_path
+                .and_then(|path| self.filtered_rows.iter().position(|row| row.path == path))
+            {
+                self.selected = index;
+                self.ensure_selected_visible();
+            }
+        }
+    }
+
+    async fn toggle_pin_for_selected(&mut self) {
+        let Some(row) = self.filtered_rows.get(self.selected) else {
+            return;
+        };
+        let Some(thread_id) = row_thread_id(row) else {
+            return;
+        };
+        let pinned = !row.pinned;
+        self.apply_organization_update(thread_id, OrganizationUpdate::Pinned(pinned))
+            .await;
+    }
+
+    fn begin_organization_edit_for_selected(&mut self, field: OrganizationField) {
+        let Some(row) = self.filtered_rows.get(self.selected) else {
+            return;
+        };
+        let Some(thread_id) = row_thread_id(row) else {
+            return;
+        };
+        let input = match field {
+            OrganizationField::Tags => row.tags.join(", "),
+            OrganizationField::Folder => row.folder.clone().unwrap_or_default(),
+        };
+        self.pending_organization_edit = Some(PendingOrganizationEdit {
+            field,
+            thread_id,
+            input,
+        });
+        self.request_frame();
+    }
+
+    async fn handle_organization_edit_key(&mut self, key: KeyEvent) -> Option<SessionSelection> {
+        let edit = self.pending_organization_edit.as_mut()?;
+        match key.code {
+            KeyCode::Esc => {
+                self.pending_organization_edit = None;
+            }
+            KeyCode::Enter => {
+                let edit = self.pending_organization_edit.take()?;
+                let update = match edit.field {
+                    OrganizationField::Tags => OrganizationUpdate::Tags(
+                        edit.input.split(',').map(str::to_string).collect(),
+                    ),
+                    OrganizationField::Folder => OrganizationUpdate::Folder(edit.input),
+                };
+                self.apply_organization_update(edit.thread_id, update).await;
+            }
+            KeyCode::Backspace => {
+                edit.input.pop();
+            }
+            KeyCode::Char('c')
+                if key
+                    .modifiers
+                    .contains(crossterm::event::KeyModifiers::CONTROL) =>
+            {
+                return Some(SessionSelection::Exit);
+            }
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .contains(crossterm::event::KeyModifiers::CONTROL)
+                    && !key.modifiers.contains(crossterm::event::KeyModifiers::ALT) =>
+            {
+                edit.input.push(c);
+            }
+            _ => {}
+        }
+        self.request_frame();
+        None
+    }
+
+    async fn apply_organization_update(&mut self, thread_id: ThreadId, update: OrganizationUpdate) {
+        let Some(state_db) = self.state_db().await else {
+            self.organization_status = Some(ORGANIZATION_REQUIRES_STATE_DB.to_string());
+            self.request_frame();
+            return;
+        };
+        let result = match update {
+            OrganizationUpdate::Pinned(pinned) => {
+                state_db.set_thread_pinned(thread_id, pinned).await
+            }
+            OrganizationUpdate::Tags(tags) => state_db.set_thread_tags(thread_id, &tags).await,
+            OrganizationUpdate::Folder(folder) => {
+                state_db
+                    .set_thread_folder(thread_id, Some(folder.as_str()))
+                    .await
+            }
+        };
+        match result {
+            Ok(Some(organization)) => {
+                self.organization_cache.insert(thread_id, organization);
+                self.apply_cached_organization();
+            }
+            Ok(None) => {
+                self.organization_status =
+                    Some("This session is not in the state database yet.".to_string());
+            }
+            Err(err) => {
+                self.organization_status = Some(format!("Failed to update session: {err}"));
+            }
+        }
+        self.request_frame();
+    }
+
+    fn organization_hint_line(&self) -> Option<Line<'static>> {
+        if let Some(edit) = self.pending_organization_edit.as_ref() {
+            let label = match edit.field {
+                OrganizationField::Tags => "Tags (comma-separated): ",
+                OrganizationField::Folder => "Folder (a/b, empty to clear): ",
+            };
+            return Some(
+                vec![
+                    label.bold(),
+                    Span::from(edit.input.clone()).cyan(),
+                    "  ".into(),
+                    key_hint::plain(KeyCode::Enter).into(),
+                    " save ".dim(),
+                    key_hint::plain(KeyCode::Esc).into(),
+                    " cancel".dim(),
+                ]
+                .into(),
+            );
+        }
+        let status = self.organization_status.as_ref()?;
+        Some(vec![Span::from(status.clone()).red()].into())
+    }
+
     fn apply_filter(&mut self) {
         let base_iter = self
             .all_rows
@@ -809,6 +1088,9 @@ impl PickerState {
             let q = self.query.to_lowercase();
             self.filtered_rows = base_iter.filter(|r| r.matches_query(&q)).cloned().collect();
         }
+        // Pinned sessions float to the top; the sort is stable so each group
+        // keeps the backend order.
+        self.filtered_rows.sort_by_key(|row| !row.pinned);
         if self.selected >= self.filtered_rows.len() {
             self.selected = self.filtered_rows.len().saturating_sub(1);
         }
@@ -1102,6 +1384,9 @@ fn head_to_row(item: &ThreadItem) -> Row {
         updated_at,
         cwd: item.cwd.clone(),
         git_branch: item.git_branch.clone(),
+        tags: Vec::new(),
+        pinned: false,
+        folder: None,
     }
 }
 
@@ -1192,6 +1477,8 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
         let toggle_scope = if state.show_all { "scoped" } else { "all" };
         let hint_line: Line = if let Some(confirm_line) = state.confirmation_hint_line() {
             confirm_line
+        } else if let Some(organization_line) = state.organization_hint_line() {
+            organization_line
         } else {
             vec![
                 key_hint::plain(KeyCode::Enter).into(),
@@ -1215,6 +1502,13 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
                 key_hint::plain(KeyCode::Char('d')).into(),
                 " manage ".dim(),
                 "    ".dim(),
+                key_hint::plain(KeyCode::Char('p')).into(),
+                " pin ".dim(),
+                key_hint::plain(KeyCode::Char('t')).into(),
+                " tags ".dim(),
+                key_hint::plain(KeyCode::Char('f')).into(),
+                " folder ".dim(),
+                "    ".dim(),
                 key_hint::plain(KeyCode::Up).into(),
                 "/".dim(),
                 key_hint::plain(KeyCode::Down).into(),
@@ -1323,6 +1617,22 @@ fn render_list(
         if add_leading_gap {
             preview_width = preview_width.saturating_sub(2);
         }
+        let pin_label = if row.pinned { "pinned " } else { "" };
+        let folder_label = row
+            .folder
+            .as_ref()
+            .map(|folder| format!("{folder}/ "))
+            .unwrap_or_default();
+        let tags_label = row
+            .tags
+            .iter()
+            .map(|tag| format!(" #{tag}"))
+            .collect::<String>();
+        preview_width = preview_width.saturating_sub(
+            UnicodeWidthStr::width(pin_label)
+                + UnicodeWidthStr::width(folder_label.as_str())
+                + UnicodeWidthStr::width(tags_label.as_str()),
+        );
         let preview = truncate_text(row.display_preview(), preview_width);
         let mut spans: Vec<Span> = vec![marker];
         if let Some(created) = created_span {
@@ -1344,7 +1654,16 @@ fn render_list(
         if add_leading_gap {
             spans.push("  ".into());
         }
+        if !pin_label.is_empty() {
+            spans.push(pin_label.cyan());
+        }
+        if !folder_label.is_empty() {
+            spans.push(Span::from(folder_label).dim());
+        }
         spans.push(preview.into());
+        if !tags_label.is_empty() {
+            spans.push(Span::from(tags_label).cyan());
+        }
 
         let line: Line = spans.into();
         let rect = Rect::new(area.x, y, area.width, 1);
@@ -1683,6 +2002,9 @@ mod tests {
             updated_at: None,
             cwd: None,
             git_branch: None,
+            tags: Vec::new(),
+            pinned: false,
+            folder: None,
         };
         state.all_rows = vec![row.clone()];
         state.filtered_rows = vec![row];
@@ -1808,6 +2130,96 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn pinned_rows_sort_first_and_search_matches_tags_and_folders() {
+        let mut state = make_state(
+            SessionView::Active,
+            PathBuf::from("/tmp/codex-home/sessions/rollout-a.jsonl"),
+            None,
+            None,
+        );
+        let row = |name: &str, tags: &[&str], pinned: bool, folder: Option<&str>| Row {
+            path: PathBuf::from(format!("/tmp/codex-home/sessions/rollout-{name}.jsonl")),
+            preview: name.to_string(),
+            thread_id: None,
+            thread_name: None,
+            created_at: None,
+            updated_at: None,
+            cwd: None,
+            git_branch: None,
+            tags: tags.iter().map(ToString::to_string).collect(),
+            pinned,
+            folder: folder.map(str::to_string),
+        };
+        state.all_rows = vec![
+            row("newest", &["infra"], false, None),
+            row("middle", &[], true, Some("clients/acme")),
+            row("oldest", &["docs"], true, None),
+        ];
+        state.apply_filter();
+        let previews = |state: &PickerState| {
+            state
+                .filtered_rows
+                .iter()
+                .map(|row| row.preview.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(previews(&state), vec!["middle", "oldest", "newest"]);
+
+        state.set_query("#infra".to_string());
+        assert_eq!(previews(&state), vec!["newest"]);
+        state.set_query("acme".to_string());
+        assert_eq!(previews(&state), vec!["middle"]);
+    }
+
+    #[tokio::test]
+    async fn t_opens_prefilled_tag_editor_and_p_requires_state_db() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let thread_id = ThreadId::new();
+        let mut state = make_state(
+            SessionView::Active,
+            PathBuf::from("/tmp/codex-home/sessions/rollout-a.jsonl"),
+            Some(thread_id),
+            None,
+        );
+        state.codex_home = codex_home.path().to_path_buf();
+        state.filtered_rows[0].tags = vec!["ci".to_string(), "infra".to_string()];
+
+        state
+            .handle_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE))
+            .await
+            .expect("key handling should succeed");
+        state
+            .handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE))
+            .await
+            .expect("key handling should succeed");
+        let edit = state
+            .pending_organization_edit
+            .as_ref()
+            .expect("tag editor should be open");
+        assert_eq!(edit.field, OrganizationField::Tags);
+        assert_eq!(edit.thread_id, thread_id);
+        assert_eq!(edit.input, "ci, infrax");
+        assert_eq!(state.query, "");
+
+        state
+            .handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+            .await
+            .expect("key handling should succeed");
+        assert!(state.pending_organization_edit.is_none());
+
+        let selection = state
+            .handle_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE))
+            .await
+            .expect("key handling should succeed");
+        assert_eq!(selection, None);
+        assert_eq!(
+            state.organization_status.as_deref(),
+            Some(ORGANIZATION_REQUIRES_STATE_DB)
+        );
+        assert!(!state.filtered_rows[0].pinned);
+    }
+
     #[tokio::test]
     async fn update_thread_names_numbers_multiple_untitled_forks() {
         let (draw_tx, _draw_rx) = broadcast::channel(1);
@@ -1848,6 +2260,9 @@ mod tests {
             updated_at: None,
             cwd: None,
             git_branch: None,
+            tags: Vec::new(),
+            pinned: false,
+            folder: None,
         };
         state.all_rows = vec![
             row(
@@ -19,6 +19,8 @@ pub enum SlashCommand {
     ElevateSandbox,
     Experimental,
     Skills,
+    Memory,
+    Knowledge,
     Review,
     Rename,
     Export,
@@ -85,6 +87,8 @@ impl SlashCommand {
             }
             SlashCommand::CopyMessage => "copy a previous message from this chat",
             SlashCommand::Skills => "use skills to improve how Codex performs specific tasks",
+            SlashCommand::Memory => "view, edit and pin memories for this directory",
+            SlashCommand::Knowledge => "review facts Codex remembered about this repository",
             SlashCommand::Status => "show current session configuration and token usage",
             SlashCommand::DebugConfig => "show config layers and requirement sources for debugging",
             SlashCommand::Statusline => "configure which items appear in the status line",
@@ -155,6 +159,8 @@ impl SlashCommand {
             | SlashCommand::CopyCodeBlock
             | SlashCommand::CopyMessage
             | SlashCommand::Skills
+            | SlashCommand::Memory
+            | SlashCommand::Knowledge
             | SlashCommand::Status
             | SlashCommand::DebugConfig
             | SlashCommand::Legend